@@ -0,0 +1,190 @@
+/**
+ * Interrupted AI requests
+ *
+ * While the assistant works through its tool loop, the frontend saves the
+ * request's transcript after every completed tool call: the model messages
+ * of finished steps (`api_messages`, in the AI SDK's message format), the
+ * tool calls and results of the step in progress, and the chat messages
+ * shown so far. A request that ends normally deletes its file, so a file
+ * left behind means the app quit or crashed mid-request. Resuming sends the
+ * transcript up to the last completed tool call back to the model.
+ */
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+pub const TRANSCRIPT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentTranscript {
+    #[serde(default)]
+    pub version: u32,
+    pub conversation_id: String,
+    pub title: String,
+    pub provider: String,
+    pub model_id: String,
+    /// Chat messages of the conversation, in the frontend's shape
+    pub messages: Vec<Value>,
+    /// Attachments of the images in `messages`, with their base64 data, so
+    /// follow-up requests can still send them
+    #[serde(default)]
+    pub attachments: BTreeMap<String, Value>,
+    /// Model messages up to the last finished step
+    pub api_messages: Vec<Value>,
+    /// `tool-call` parts of the step in progress
+    #[serde(default)]
+    pub pending_tool_calls: Vec<Value>,
+    /// `tool-result` parts of the step in progress
+    #[serde(default)]
+    pub pending_tool_results: Vec<Value>,
+    pub updated_at: i64,
+}
+
+/// Listing entry for an interrupted request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedQuery {
+    pub conversation_id: String,
+    pub title: String,
+    pub provider: String,
+    pub model_id: String,
+    pub completed_tool_calls: usize,
+    pub updated_at: i64,
+}
+
+/// File name for a conversation's transcript; conversation ids come from
+/// the frontend, so anything but a plain id is rejected
+pub fn transcript_file_name(conversation_id: &str) -> Result<String, String> {
+    let valid = !conversation_id.is_empty()
+        && conversation_id.len() <= 64
+        && conversation_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("Invalid conversation id: {conversation_id:?}"));
+    }
+    Ok(format!("{conversation_id}.json"))
+}
+
+fn tool_call_id(part: &Value) -> Option<&str> {
+    part.get("toolCallId").and_then(Value::as_str)
+}
+
+/// Pending tool calls that already have a result, with those results
+fn completed_pending_calls(transcript: &AgentTranscript) -> (Vec<Value>, Vec<Value>) {
+    let results: Vec<Value> = transcript
+        .pending_tool_results
+        .iter()
+        .filter(|result| {
+            transcript.pending_tool_calls.iter().any(|call| {
+                tool_call_id(call).is_some() && tool_call_id(call) == tool_call_id(result)
+            })
+        })
+        .cloned()
+        .collect();
+    let calls = transcript
+        .pending_tool_calls
+        .iter()
+        .filter(|call| {
+            results
+                .iter()
+                .any(|result| tool_call_id(result) == tool_call_id(call))
+        })
+        .cloned()
+        .collect();
+    (calls, results)
+}
+
+/// Model messages to continue the request with: the finished steps, then
+/// the tool calls of the interrupted step that completed. Calls that were
+/// still running are left out, so the model issues them again.
+pub fn resume_messages(transcript: &AgentTranscript) -> Vec<Value> {
+    let mut messages = transcript.api_messages.clone();
+    let (calls, results) = completed_pending_calls(transcript);
+    if !calls.is_empty() {
+        messages.push(json!({ "role": "assistant", "content": calls }));
+        messages.push(json!({ "role": "tool", "content": results }));
+    }
+    messages
+}
+
+/// Tool calls of the request that finished before it was interrupted
+pub fn completed_tool_calls(transcript: &AgentTranscript) -> usize {
+    let finished = transcript
+        .api_messages
+        .iter()
+        .filter(|message| message.get("role").and_then(Value::as_str) == Some("tool"))
+        .filter_map(|message| message.get("content").and_then(Value::as_array))
+        .map(Vec::len)
+        .sum::<usize>();
+    finished + completed_pending_calls(transcript).1.len()
+}
+
+impl AgentTranscript {
+    pub fn summary(&self) -> InterruptedQuery {
+        InterruptedQuery {
+            conversation_id: self.conversation_id.clone(),
+            title: self.title.clone(),
+            provider: self.provider.clone(),
+            model_id: self.model_id.clone(),
+            completed_tool_calls: completed_tool_calls(self),
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(id: &str) -> Value {
+        json!({ "type": "tool-call", "toolCallId": id, "toolName": "apply_edit", "input": {} })
+    }
+
+    fn result(id: &str) -> Value {
+        json!({
+            "type": "tool-result",
+            "toolCallId": id,
+            "toolName": "apply_edit",
+            "output": { "type": "text", "value": "ok" }
+        })
+    }
+
+    #[test]
+    fn resumes_from_the_last_completed_tool_call() {
+        let transcript = AgentTranscript {
+            version: TRANSCRIPT_VERSION,
+            conversation_id: "c1".into(),
+            title: "Make a box".into(),
+            provider: "anthropic".into(),
+            model_id: "model".into(),
+            messages: Vec::new(),
+            attachments: BTreeMap::new(),
+            api_messages: vec![
+                json!({ "role": "user", "content": "Make a box" }),
+                json!({ "role": "assistant", "content": [call("a")] }),
+                json!({ "role": "tool", "content": [result("a")] }),
+            ],
+            pending_tool_calls: vec![call("b"), call("c")],
+            pending_tool_results: vec![result("b")],
+            updated_at: 0,
+        };
+
+        let messages = resume_messages(&transcript);
+        assert_eq!(messages.len(), 5);
+        assert_eq!(messages[3]["content"], json!([call("b")]));
+        assert_eq!(messages[4]["content"], json!([result("b")]));
+        assert_eq!(completed_tool_calls(&transcript), 2);
+    }
+
+    #[test]
+    fn rejects_path_like_conversation_ids() {
+        assert_eq!(
+            transcript_file_name("0b6c-41f2_x").unwrap(),
+            "0b6c-41f2_x.json"
+        );
+        assert!(transcript_file_name("../settings").is_err());
+        assert!(transcript_file_name("").is_err());
+    }
+}
@@ -0,0 +1,126 @@
+/**
+ * Custom AI instructions
+ *
+ * Users can tailor the assistant with two Markdown files: global
+ * instructions kept next to the app settings, and project instructions in
+ * `.openscad-studio/instructions.md` under the project root, which can be
+ * committed with the project. Both are appended to the built-in system
+ * prompt, project instructions last so they win where the two disagree.
+ */
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const GLOBAL_INSTRUCTIONS_FILE: &str = "instructions.md";
+pub const PROJECT_INSTRUCTIONS_DIR: &str = ".openscad-studio";
+pub const PROJECT_INSTRUCTIONS_FILE: &str = "instructions.md";
+/// Longer instructions would crowd out the conversation in the context window
+pub const MAX_INSTRUCTIONS_CHARS: usize = 20_000;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AiInstructions {
+    pub global: String,
+    /// `None` when no project is open
+    pub project: Option<String>,
+    /// Where the project instructions live, for display
+    pub project_path: Option<String>,
+}
+
+pub fn project_instructions_path(project_root: &Path) -> PathBuf {
+    project_root
+        .join(PROJECT_INSTRUCTIONS_DIR)
+        .join(PROJECT_INSTRUCTIONS_FILE)
+}
+
+pub fn validate_instructions(content: &str) -> Result<(), String> {
+    let chars = content.chars().count();
+    if chars > MAX_INSTRUCTIONS_CHARS {
+        return Err(format!(
+            "Instructions are {chars} characters long; the limit is {MAX_INSTRUCTIONS_CHARS}"
+        ));
+    }
+    Ok(())
+}
+
+/// Contents of an instructions file, empty when it does not exist
+pub fn read_instructions(path: &Path) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read {}: {e}", path.display())),
+    }
+}
+
+/// Write an instructions file; blank instructions remove it
+pub fn write_instructions(path: &Path, content: &str) -> Result<(), String> {
+    validate_instructions(content)?;
+    if content.trim().is_empty() {
+        return match fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to remove {}: {e}", path.display())),
+        };
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// The built-in prompt followed by the user's instructions
+pub fn effective_system_prompt(base: &str, instructions: &AiInstructions) -> String {
+    let mut prompt = base.trim_end().to_string();
+    let sections = [
+        ("User instructions", instructions.global.trim()),
+        (
+            "Project instructions",
+            instructions.project.as_deref().unwrap_or_default().trim(),
+        ),
+    ];
+    for (heading, content) in sections {
+        if !content.is_empty() {
+            prompt.push_str(&format!("\n\n## {heading}\n\n{content}"));
+        }
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_non_empty_instructions_after_the_base_prompt() {
+        let instructions = AiInstructions {
+            global: "Use metric units.\n".into(),
+            project: Some("  ".into()),
+            project_path: None,
+        };
+        assert_eq!(
+            effective_system_prompt("Base\n", &instructions),
+            "Base\n\n## User instructions\n\nUse metric units."
+        );
+
+        let instructions = AiInstructions {
+            project: Some("Wall thickness is 2mm.".into()),
+            ..instructions
+        };
+        assert!(effective_system_prompt("Base", &instructions)
+            .ends_with("Use metric units.\n\n## Project instructions\n\nWall thickness is 2mm."));
+    }
+
+    #[test]
+    fn round_trips_and_removes_instruction_files() {
+        let root = std::env::temp_dir().join(format!("ai-instructions-{}", std::process::id()));
+        let path = project_instructions_path(&root);
+
+        write_instructions(&path, "Prefer modules.").unwrap();
+        assert_eq!(read_instructions(&path).unwrap(), "Prefer modules.");
+        write_instructions(&path, "").unwrap();
+        assert_eq!(read_instructions(&path).unwrap(), "");
+        assert!(write_instructions(&path, &"x".repeat(MAX_INSTRUCTIONS_CHARS + 1)).is_err());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
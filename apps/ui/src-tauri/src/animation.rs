@@ -0,0 +1,282 @@
+/**
+ * `$t` animation frames
+ *
+ * OpenSCAD animates a model through the special variable `$t`, which steps
+ * from 0 towards 1 across the frames. Each frame is a separate PNG render
+ * with `-D $t=...`; the frames are then assembled into a looping GIF,
+ * written out as numbered PNGs, or encoded as a video by an `ffmpeg` found
+ * on PATH (the app doesn't bundle a video encoder). Rendered frames are
+ * cached by their render key (source, camera, size and `$t`), so re-running
+ * an animation after changing only the frame rate or output doesn't render
+ * anything again.
+ *
+ * A turntable goes through the same pipeline with a static model and the
+ * camera orbiting it instead of `$t` changing.
+ */
+use crate::sweep::RgbaImage;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const DEFAULT_FRAMES: usize = 30;
+pub const MAX_FRAMES: usize = 360;
+pub const DEFAULT_FPS: u32 = 10;
+const MAX_CACHED_FRAMES: usize = 720;
+
+pub const DEFAULT_TURNTABLE_STEPS: usize = 24;
+pub const MIN_TURNTABLE_STEPS: usize = 12;
+pub const MAX_TURNTABLE_STEPS: usize = 36;
+/// Degrees above the horizon, matching OpenSCAD's default 55° tilt
+pub const DEFAULT_ELEVATION: f64 = 35.0;
+
+/// `$t` of each frame: `i / frames`, so a looping animation doesn't show
+/// the first pose twice
+pub fn frame_times(frames: usize) -> Vec<f64> {
+    (0..frames).map(|i| i as f64 / frames as f64).collect()
+}
+
+/// Gimbal rotation of each turntable step, one full turn around Z viewed
+/// from `elevation` degrees above the horizon, with its fraction of the turn
+pub fn turntable_rotations(steps: usize, elevation: f64) -> Vec<(f64, [f64; 3])> {
+    let tilt = 90.0 - elevation.clamp(-90.0, 90.0);
+    frame_times(steps)
+        .into_iter()
+        .map(|turn| (turn, [tilt, 0.0, 360.0 * turn]))
+        .collect()
+}
+
+/// OpenSCAD arguments setting `$t`
+pub fn time_args(t: f64) -> Vec<String> {
+    vec!["-D".to_string(), format!("$t={t}")]
+}
+
+fn frame_number_width(frames: usize) -> usize {
+    frames.saturating_sub(1).to_string().len().max(3)
+}
+
+/// Zero-padded PNG name for frame `index`, sorting in frame order
+pub fn frame_file_name(index: usize, frames: usize) -> String {
+    let width = frame_number_width(frames);
+    format!("frame_{index:0width$}.png")
+}
+
+/// Video formats, encoded by `ffmpeg`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoFormat {
+    Mp4,
+    Webm,
+}
+
+impl VideoFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            VideoFormat::Mp4 => "MP4",
+            VideoFormat::Webm => "WebM",
+        }
+    }
+
+    fn codec_args(self) -> &'static [&'static str] {
+        match self {
+            // yuv420p keeps the video playable in browsers and QuickTime; it
+            // needs even dimensions, hence the padding
+            VideoFormat::Mp4 => &[
+                "-c:v",
+                "libx264",
+                "-pix_fmt",
+                "yuv420p",
+                "-vf",
+                "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+                "-movflags",
+                "+faststart",
+            ],
+            // Constant quality VP9 (a zero bitrate lets `-crf` decide)
+            VideoFormat::Webm => &[
+                "-c:v",
+                "libvpx-vp9",
+                "-pix_fmt",
+                "yuv420p",
+                "-b:v",
+                "0",
+                "-crf",
+                "32",
+            ],
+        }
+    }
+}
+
+/// `ffmpeg` on PATH
+pub fn find_ffmpeg() -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        ["ffmpeg", "ffmpeg.exe"]
+            .into_iter()
+            .map(|file| dir.join(file))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// ffmpeg arguments encoding the `frames` PNGs in `frame_dir`, named by
+/// [`frame_file_name`], into `output`
+pub fn ffmpeg_args(
+    frame_dir: &Path,
+    frames: usize,
+    fps: u32,
+    format: VideoFormat,
+    output: &Path,
+) -> Vec<String> {
+    let pattern = frame_dir.join(format!("frame_%0{}d.png", frame_number_width(frames)));
+    let mut args: Vec<String> = ["-y", "-loglevel", "error", "-framerate"]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    args.push(fps.clamp(1, 100).to_string());
+    args.push("-i".to_string());
+    args.push(pattern.to_string_lossy().into_owned());
+    args.extend(format.codec_args().iter().map(|arg| arg.to_string()));
+    args.push(output.to_string_lossy().into_owned());
+    args
+}
+
+/// Per-frame GIF delay in hundredths of a second
+fn frame_delay(fps: u32) -> u16 {
+    (100 / fps.clamp(1, 100)).max(1) as u16
+}
+
+/// Assemble equally sized frames into an infinitely looping GIF
+pub fn encode_gif(frames: &[RgbaImage], fps: u32) -> Result<Vec<u8>, String> {
+    let first = frames.first().ok_or("No frames to encode")?;
+    let (width, height) = (first.width, first.height);
+    if frames
+        .iter()
+        .any(|frame| frame.width != width || frame.height != height)
+    {
+        return Err("All frames must have the same size".into());
+    }
+    let too_large = |_| format!("Frames of {width}×{height} are too large for a GIF");
+    let gif_width = u16::try_from(width).map_err(too_large)?;
+    let gif_height = u16::try_from(height).map_err(too_large)?;
+
+    let mut encoder = gif::Encoder::new(Vec::new(), gif_width, gif_height, &[])
+        .map_err(|e| format!("Failed to start GIF: {e}"))?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(|e| format!("Failed to write GIF: {e}"))?;
+    let delay = frame_delay(fps);
+    for frame in frames {
+        let mut pixels = frame.pixels.clone();
+        let mut gif_frame = gif::Frame::from_rgba_speed(gif_width, gif_height, &mut pixels, 10);
+        gif_frame.delay = delay;
+        encoder
+            .write_frame(&gif_frame)
+            .map_err(|e| format!("Failed to write GIF frame: {e}"))?;
+    }
+    encoder
+        .into_inner()
+        .map_err(|e| format!("Failed to finish GIF: {e}"))
+}
+
+/// Rendered frame PNGs by render key, least recently used first
+#[derive(Default)]
+pub struct AnimationFrameCache {
+    frames: Mutex<VecDeque<(String, Vec<u8>)>>,
+}
+
+impl AnimationFrameCache {
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let mut frames = self.frames.lock().unwrap();
+        let index = frames.iter().position(|(cached, _)| cached == key)?;
+        let entry = frames.remove(index)?;
+        let png = entry.1.clone();
+        frames.push_back(entry);
+        Some(png)
+    }
+
+    pub fn insert(&self, key: String, png: Vec<u8>) {
+        let mut frames = self.frames.lock().unwrap();
+        frames.retain(|(cached, _)| *cached != key);
+        frames.push_back((key, png));
+        while frames.len() > MAX_CACHED_FRAMES {
+            frames.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_time_and_names_frames() {
+        assert_eq!(frame_times(4), vec![0.0, 0.25, 0.5, 0.75]);
+        assert_eq!(time_args(0.25), vec!["-D", "$t=0.25"]);
+        assert_eq!(frame_file_name(7, 30), "frame_007.png");
+        assert_eq!(frame_file_name(42, 1200), "frame_0042.png");
+        assert_eq!(frame_delay(10), 10);
+        assert_eq!(frame_delay(0), 100);
+    }
+
+    #[test]
+    fn ffmpeg_reads_the_numbered_frames() {
+        let args = ffmpeg_args(
+            Path::new("/tmp/frames"),
+            30,
+            12,
+            VideoFormat::Mp4,
+            Path::new("/out/spin.mp4"),
+        );
+        assert_eq!(&args[..5], ["-y", "-loglevel", "error", "-framerate", "12"]);
+        let pattern = PathBuf::from(&args[6]);
+        assert_eq!(pattern.file_name().unwrap(), "frame_%03d.png");
+        assert!(args.windows(2).any(|pair| pair == ["-c:v", "libx264"]));
+        assert_eq!(args.last().unwrap(), "/out/spin.mp4");
+
+        let webm = ffmpeg_args(
+            Path::new("/tmp/frames"),
+            24,
+            10,
+            VideoFormat::Webm,
+            Path::new("/out/spin.webm"),
+        );
+        assert!(webm.windows(2).any(|pair| pair == ["-c:v", "libvpx-vp9"]));
+    }
+
+    #[test]
+    fn turntable_orbits_once() {
+        let rotations = turntable_rotations(12, DEFAULT_ELEVATION);
+        assert_eq!(rotations.len(), 12);
+        assert_eq!(rotations[0], (0.0, [55.0, 0.0, 0.0]));
+        assert_eq!(rotations[3].1, [55.0, 0.0, 90.0]);
+        assert_eq!(turntable_rotations(4, 120.0)[1].1, [0.0, 0.0, 90.0]);
+    }
+
+    #[test]
+    fn encodes_a_looping_gif() {
+        let frame = |color: u8| RgbaImage {
+            width: 4,
+            height: 2,
+            pixels: [color, 0, 0, 255].repeat(8),
+        };
+        let gif = encode_gif(&[frame(0), frame(255)], 10).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+        assert_eq!(&gif[6..10], &[4, 0, 2, 0]);
+        assert!(gif.windows(11).any(|bytes| bytes == b"NETSCAPE2.0"));
+
+        assert!(encode_gif(&[], 10).is_err());
+        let odd = RgbaImage {
+            width: 2,
+            height: 2,
+            pixels: vec![0; 16],
+        };
+        assert!(encode_gif(&[frame(0), odd], 10).is_err());
+    }
+
+    #[test]
+    fn cache_keeps_recent_frames() {
+        let cache = AnimationFrameCache::default();
+        for i in 0..=MAX_CACHED_FRAMES {
+            cache.insert(format!("frame-{i}"), vec![i as u8]);
+        }
+        assert!(cache.get("frame-0").is_none());
+        assert_eq!(cache.get("frame-1"), Some(vec![1]));
+    }
+}
@@ -0,0 +1,108 @@
+/**
+ * PNG snapshots with embedded source
+ *
+ * The source code and any parameter overrides are stored in iTXt chunks of
+ * a rendered preview, so a shared screenshot can be dropped back into the
+ * editor to recover the model. Pixels are re-encoded as RGBA8; other
+ * ancillary chunks of the rendered image are not kept.
+ */
+use crate::sweep::{decode_png, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+pub const SOURCE_KEYWORD: &str = "openscad-studio:source";
+pub const PARAMETERS_KEYWORD: &str = "openscad-studio:parameters";
+const SOFTWARE: &str = "OpenSCAD Studio";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddedSource {
+    pub code: String,
+    /// Top-level variable overrides the image was rendered with
+    pub parameters: BTreeMap<String, String>,
+}
+
+/// Re-encode `image` with the source (and parameters, when any) attached
+pub fn encode_annotated_png(image: &RgbaImage, source: &EmbeddedSource) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, image.width, image.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .add_text_chunk("Software".into(), SOFTWARE.into())
+        .and_then(|_| encoder.add_itxt_chunk(SOURCE_KEYWORD.into(), source.code.clone()))
+        .map_err(|e| format!("Failed to attach source to PNG: {e}"))?;
+    if !source.parameters.is_empty() {
+        let parameters = serde_json::to_string(&source.parameters)
+            .map_err(|e| format!("Failed to serialize parameters: {e}"))?;
+        encoder
+            .add_itxt_chunk(PARAMETERS_KEYWORD.into(), parameters)
+            .map_err(|e| format!("Failed to attach parameters to PNG: {e}"))?;
+    }
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&image.pixels))
+        .map_err(|e| format!("Failed to encode PNG: {e}"))?;
+    Ok(out)
+}
+
+/// Attach the source to an already-encoded PNG
+pub fn annotate_png(png: &[u8], source: &EmbeddedSource) -> Result<Vec<u8>, String> {
+    encode_annotated_png(&decode_png(png)?, source)
+}
+
+/// Source embedded by [`annotate_png`], or `None` for ordinary images
+pub fn extract_source(png: &[u8]) -> Result<Option<EmbeddedSource>, String> {
+    let decoder = png::Decoder::new(Cursor::new(png));
+    let reader = decoder
+        .read_info()
+        .map_err(|e| format!("Failed to read PNG: {e}"))?;
+    let info = reader.info();
+    let text = |keyword: &str| -> Result<Option<String>, String> {
+        info.utf8_text
+            .iter()
+            .find(|chunk| chunk.keyword == keyword)
+            .map(|chunk| {
+                chunk
+                    .get_text()
+                    .map_err(|e| format!("Failed to read {keyword} from PNG: {e}"))
+            })
+            .transpose()
+    };
+
+    let Some(code) = text(SOURCE_KEYWORD)? else {
+        return Ok(None);
+    };
+    let parameters = match text(PARAMETERS_KEYWORD)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid parameters embedded in PNG: {e}"))?,
+        None => BTreeMap::new(),
+    };
+    Ok(Some(EmbeddedSource { code, parameters }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweep::encode_png;
+
+    #[test]
+    fn source_round_trips_through_png() {
+        let image = RgbaImage {
+            width: 2,
+            height: 1,
+            pixels: vec![255, 0, 0, 255, 0, 0, 255, 255],
+        };
+        let plain = encode_png(&image).unwrap();
+        assert_eq!(extract_source(&plain).unwrap(), None);
+
+        let source = EmbeddedSource {
+            code: "size = 10; // ½ scale\ncube(size);".into(),
+            parameters: BTreeMap::from([("size".to_string(), "25".to_string())]),
+        };
+        let annotated = annotate_png(&plain, &source).unwrap();
+        assert_eq!(extract_source(&annotated).unwrap(), Some(source));
+        assert_eq!(decode_png(&annotated).unwrap().pixels, image.pixels);
+    }
+}
@@ -0,0 +1,186 @@
+/**
+ * Batch export planning
+ *
+ * A batch export renders the same source once per parameter set (`-D`
+ * overrides) and writes each result next to the others in one folder. Output
+ * names come from a filename template such as `bracket_{width}x{height}`:
+ * `{name}` expands to that parameter's value and `{index}` to the 1-based
+ * position of the set.
+ */
+use crate::openscad_capabilities::OpenScadCapabilities;
+use std::collections::{HashMap, HashSet};
+
+pub const MAX_BATCH_JOBS: usize = 256;
+pub const EXPORT_FORMATS: &[&str] = &["stl", "3mf", "obj", "off", "amf", "wrl", "pov", "csg"];
+
+/// Check that the binary can write `format` (an output extension)
+pub fn check_export_support(
+    format: &str,
+    capabilities: &OpenScadCapabilities,
+) -> Result<(), String> {
+    if !capabilities.supports_export(format) {
+        return Err(format!(
+            "This OpenSCAD version can't export .{format} files; install a newer one in Settings"
+        ));
+    }
+    Ok(())
+}
+
+/// OpenSCAD arguments exporting to `format`, which OpenSCAD picks from the
+/// output extension except where a variant has to be named. No 3MF color
+/// options are passed: the flags differ between OpenSCAD versions, so 3MF
+/// files carry whatever colors the binary writes by default (recent builds
+/// keep `color()` with the Manifold backend, older ones write none).
+pub fn export_args(format: &str) -> Vec<String> {
+    let mut args = vec![
+        "/input.scad".to_string(),
+        "-o".to_string(),
+        format!("/output.{format}"),
+    ];
+    if format == "stl" {
+        args.push("--export-format=binstl".to_string());
+    }
+    args
+}
+
+/// Make a parameter value usable inside a file name
+fn sanitize_value(value: &str) -> String {
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Expand `template` for one parameter set, appending `.{format}` unless the
+/// template already ends with it
+pub fn output_file_name(
+    template: &str,
+    index: usize,
+    parameters: &HashMap<String, String>,
+    format: &str,
+) -> Result<String, String> {
+    let mut name = String::new();
+    let mut rest = template.trim();
+    while let Some(open) = rest.find('{') {
+        name.push_str(&rest[..open]);
+        let close = rest[open..]
+            .find('}')
+            .ok_or_else(|| format!("Unclosed '{{' in filename template: {template}"))?;
+        let placeholder = rest[open + 1..open + close].trim();
+        if placeholder == "index" {
+            name.push_str(&(index + 1).to_string());
+        } else {
+            let value = parameters.get(placeholder).ok_or_else(|| {
+                format!("Filename template uses {{{placeholder}}}, which parameter set {} does not define", index + 1)
+            })?;
+            name.push_str(&sanitize_value(value));
+        }
+        rest = &rest[open + close + 1..];
+    }
+    name.push_str(rest);
+
+    if name.contains(['/', '\\']) || name.trim_matches('.').is_empty() {
+        return Err(format!("Invalid output file name: {name:?}"));
+    }
+    let extension = format!(".{format}");
+    if !name.to_ascii_lowercase().ends_with(&extension) {
+        name.push_str(&extension);
+    }
+    Ok(name)
+}
+
+/// Output file names for every parameter set, rejecting templates that would
+/// make two sets overwrite each other
+pub fn plan_outputs(
+    template: &str,
+    parameter_sets: &[HashMap<String, String>],
+    format: &str,
+    capabilities: &OpenScadCapabilities,
+) -> Result<Vec<String>, String> {
+    if !EXPORT_FORMATS.contains(&format) {
+        return Err(format!(
+            "Unsupported batch export format: {format} (expected one of {})",
+            EXPORT_FORMATS.join(", ")
+        ));
+    }
+    check_export_support(format, capabilities)?;
+    if parameter_sets.is_empty() {
+        return Err("Provide at least one parameter set.".into());
+    }
+    if parameter_sets.len() > MAX_BATCH_JOBS {
+        return Err(format!(
+            "Too many parameter sets ({}); a batch is limited to {MAX_BATCH_JOBS}.",
+            parameter_sets.len()
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    let mut names = Vec::with_capacity(parameter_sets.len());
+    for (index, parameters) in parameter_sets.iter().enumerate() {
+        let name = output_file_name(template, index, parameters, format)?;
+        if !seen.insert(name.to_ascii_lowercase()) {
+            return Err(format!(
+                "Parameter sets produce the same file name {name}; add {{index}} or more parameters to the template."
+            ));
+        }
+        names.push(name);
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn expands_placeholders_and_appends_extension() {
+        let parameters = set(&[("width", "20"), ("label", "\"Big Box\"")]);
+        assert_eq!(
+            output_file_name("box_{width}_{label}", 0, &parameters, "stl").unwrap(),
+            "box_20_Big_Box.stl"
+        );
+        assert_eq!(
+            output_file_name("part-{index}.STL", 2, &parameters, "stl").unwrap(),
+            "part-3.STL"
+        );
+        assert!(output_file_name("box_{depth}", 0, &parameters, "stl").is_err());
+        assert!(output_file_name("box_{width", 0, &parameters, "stl").is_err());
+        assert!(output_file_name("../{width}", 0, &parameters, "stl").is_err());
+    }
+
+    #[test]
+    fn rejects_colliding_names_and_bad_formats() {
+        let sets = vec![set(&[("width", "10")]), set(&[("width", "20")])];
+        let mut capabilities = OpenScadCapabilities::assumed();
+        assert_eq!(
+            plan_outputs("w{width}", &sets, "3mf", &capabilities).unwrap(),
+            vec!["w10.3mf", "w20.3mf"]
+        );
+        assert!(plan_outputs("same", &sets, "stl", &capabilities).is_err());
+        assert!(plan_outputs("w{width}", &sets, "png", &capabilities).is_err());
+        assert!(plan_outputs("w{width}", &[], "stl", &capabilities).is_err());
+
+        // Older builds can't write POV-Ray
+        assert!(plan_outputs("w{width}", &sets, "pov", &capabilities).is_ok());
+        capabilities.export_formats.insert("pov".to_string(), false);
+        assert!(plan_outputs("w{width}", &sets, "pov", &capabilities).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use crate::cmd::render::RenderNativeResult;
+use crate::locate::path_key;
+use crate::project_files::{hash_included_sources, hash_referenced_assets};
+/**
+ * Render result cache
+ *
+ * Small per-document LRU of native render results keyed by a hash of every
+ * render input, so switching back to a tab (or re-rendering unchanged code)
+ * doesn't spawn OpenSCAD again. Entries also remember a context key (every
+ * input except the code), so a render of slightly newer code can be served
+ * the latest result for the same context while it re-renders, and the files
+ * on disk the render read, so an external change to one of them drops only
+ * the entries that depend on it.
+ */
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+
+const MAX_CACHE_ENTRIES: usize = 16;
+
+/// The project folder as spelled doesn't matter, only which folder it is
+fn working_dir_key(inputs: &RenderCacheInputs) -> Option<String> {
+    inputs
+        .working_dir
+        .as_deref()
+        .map(|dir| path_key(dir, cfg!(windows)))
+}
+
+/// `path` with `.` and `..` resolved, as a comparable key
+fn dependency_key(path: &Path) -> String {
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                clean.pop();
+            }
+            other => clean.push(other),
+        }
+    }
+    path_key(&clean.to_string_lossy(), cfg!(windows))
+}
+
+/// Everything that influences the output of a native render
+pub struct RenderCacheInputs<'a> {
+    pub code: &'a str,
+    pub args: &'a [String],
+    pub auxiliary_files: &'a Option<HashMap<String, String>>,
+    pub input_path: &'a Option<String>,
+    pub working_dir: &'a Option<String>,
+    pub library_paths: &'a Option<Vec<String>>,
+}
+
+/// Keys for storing a render's result, and the files it depends on
+#[derive(Clone)]
+pub struct CacheKeys {
+    pub key: String,
+    pub context: String,
+    pub dependencies: Vec<PathBuf>,
+}
+
+struct CacheEntry {
+    key: String,
+    context: String,
+    dependencies: Vec<String>,
+    result: RenderNativeResult,
+}
+
+#[derive(Default)]
+pub struct RenderCache {
+    entries: VecDeque<CacheEntry>,
+}
+
+impl RenderCache {
+    /// Hash all render inputs into a stable cache key
+    pub fn generate_key(inputs: &RenderCacheInputs) -> String {
+        Self::keys(inputs).key
+    }
+
+    /// Cache and context keys for `inputs`, along with every file on disk
+    /// the key hashed or the render reads
+    pub fn keys(inputs: &RenderCacheInputs) -> CacheKeys {
+        let mut dependencies = Vec::new();
+        let mut hasher = DefaultHasher::new();
+        inputs.code.hash(&mut hasher);
+        inputs.args.hash(&mut hasher);
+        inputs.input_path.hash(&mut hasher);
+        working_dir_key(inputs).hash(&mut hasher);
+        inputs.library_paths.hash(&mut hasher);
+
+        if let Some(aux_files) = inputs.auxiliary_files {
+            let mut sorted: Vec<_> = aux_files.iter().collect();
+            sorted.sort();
+            sorted.hash(&mut hasher);
+        }
+
+        // Data assets and included sources are read from disk, so their
+        // contents are part of the key.
+        let base_dir = inputs.working_dir.as_deref().map(|working_dir| {
+            inputs
+                .input_path
+                .as_deref()
+                .and_then(|input| Path::new(input).parent())
+                .map(|parent| Path::new(working_dir).join(parent))
+                .unwrap_or_else(|| Path::new(working_dir).to_path_buf())
+        });
+        if let Some(base_dir) = &base_dir {
+            hash_referenced_assets(
+                &mut hasher,
+                inputs.code,
+                inputs.auxiliary_files,
+                base_dir,
+                &mut dependencies,
+            );
+        }
+        if let Some(working_dir) = inputs.working_dir.as_deref() {
+            let working_dir = Path::new(working_dir);
+            dependencies.extend(inputs.input_path.iter().map(|path| working_dir.join(path)));
+            dependencies.extend(
+                inputs
+                    .auxiliary_files
+                    .iter()
+                    .flat_map(|files| files.keys().map(|path| working_dir.join(path))),
+            );
+        }
+
+        let mut sources = vec![(base_dir, inputs.code)];
+        if let Some(aux_files) = inputs.auxiliary_files {
+            let mut sorted: Vec<_> = aux_files.iter().collect();
+            sorted.sort();
+            sources.extend(sorted.into_iter().map(|(path, code)| {
+                let dir = inputs.working_dir.as_deref().map(|working_dir| {
+                    let path = Path::new(working_dir).join(path);
+                    path.parent().map(Path::to_path_buf).unwrap_or(path)
+                });
+                (dir, code.as_str())
+            }));
+        }
+        let library_paths: Vec<PathBuf> = inputs
+            .library_paths
+            .iter()
+            .flatten()
+            .map(PathBuf::from)
+            .collect();
+        hash_included_sources(&mut hasher, &sources, &library_paths, &mut dependencies);
+
+        CacheKeys {
+            key: format!("{:016x}", hasher.finish()),
+            context: Self::generate_context_key(inputs),
+            dependencies,
+        }
+    }
+
+    /// Hash the inputs that must match for a result of older code to stand
+    /// in while the current code renders
+    pub fn generate_context_key(inputs: &RenderCacheInputs) -> String {
+        let mut hasher = DefaultHasher::new();
+        inputs.args.hash(&mut hasher);
+        inputs.input_path.hash(&mut hasher);
+        working_dir_key(inputs).hash(&mut hasher);
+        inputs.library_paths.hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
+    /// Look up a cached result, marking it as most recently used
+    pub fn get(&mut self, key: &str) -> Option<RenderNativeResult> {
+        let index = self.entries.iter().position(|entry| entry.key == key)?;
+        let entry = self.entries.remove(index)?;
+        let result = entry.result.clone();
+        self.entries.push_back(entry);
+        Some(result)
+    }
+
+    /// Most recently used result rendered with the same context
+    pub fn latest_for_context(&self, context: &str) -> Option<RenderNativeResult> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.context == context)
+            .map(|entry| entry.result.clone())
+    }
+
+    /// Drop the entries whose render depends on `path`; returns how many
+    pub fn invalidate(&mut self, path: &Path) -> usize {
+        let changed = dependency_key(path);
+        let before = self.entries.len();
+        self.entries
+            .retain(|entry| !entry.dependencies.contains(&changed));
+        before - self.entries.len()
+    }
+
+    pub fn insert(&mut self, keys: CacheKeys, result: RenderNativeResult) {
+        self.entries.retain(|entry| entry.key != keys.key);
+        self.entries.push_back(CacheEntry {
+            key: keys.key,
+            context: keys.context,
+            dependencies: keys
+                .dependencies
+                .iter()
+                .map(|path| dependency_key(path))
+                .collect(),
+            result,
+        });
+        while self.entries.len() > MAX_CACHE_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(exit_code: i32) -> RenderNativeResult {
+        RenderNativeResult {
+            output: Vec::new(),
+            stderr: String::new(),
+            exit_code,
+            duration_ms: 0,
+            safe_mode: false,
+            stale: false,
+            limit_exceeded: None,
+            job_id: None,
+        }
+    }
+
+    fn keys(key: &str, context: &str, dependencies: &[&str]) -> CacheKeys {
+        CacheKeys {
+            key: key.to_string(),
+            context: context.to_string(),
+            dependencies: dependencies.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    #[test]
+    fn key_ignores_auxiliary_file_order() {
+        let args = vec!["--export-format=binstl".to_string()];
+        let mut first = HashMap::new();
+        first.insert("a.scad".to_string(), "cube(1);".to_string());
+        first.insert("b.scad".to_string(), "sphere(1);".to_string());
+        let mut second = HashMap::new();
+        second.insert("b.scad".to_string(), "sphere(1);".to_string());
+        second.insert("a.scad".to_string(), "cube(1);".to_string());
+
+        let key = |aux: &Option<HashMap<String, String>>| {
+            RenderCache::generate_key(&RenderCacheInputs {
+                code: "include <a.scad>",
+                args: &args,
+                auxiliary_files: aux,
+                input_path: &None,
+                working_dir: &None,
+                library_paths: &None,
+            })
+        };
+
+        assert_eq!(key(&Some(first)), key(&Some(second)));
+        assert_ne!(key(&None), key(&Some(HashMap::new())));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_entry() {
+        let mut cache = RenderCache::default();
+        for i in 0..MAX_CACHE_ENTRIES {
+            cache.insert(keys(&format!("key-{i}"), "ctx", &[]), result(i as i32));
+        }
+
+        // Touch the oldest entry so the next insert evicts key-1 instead.
+        assert!(cache.get("key-0").is_some());
+        cache.insert(keys("new", "ctx", &[]), result(-1));
+
+        assert!(cache.get("key-0").is_some());
+        assert!(cache.get("key-1").is_none());
+        assert_eq!(cache.get("new").map(|r| r.exit_code), Some(-1));
+    }
+
+    #[test]
+    fn serves_latest_result_for_matching_context() {
+        let stl = vec!["--export-format=binstl".to_string()];
+        let png = vec!["--export-format=png".to_string()];
+        let inputs = |code, args| RenderCacheInputs {
+            code,
+            args,
+            auxiliary_files: &None,
+            input_path: &None,
+            working_dir: &None,
+            library_paths: &None,
+        };
+        let context = RenderCache::generate_context_key(&inputs("cube(1);", &stl));
+        assert_eq!(
+            context,
+            RenderCache::generate_context_key(&inputs("cube(2);", &stl))
+        );
+        assert_ne!(
+            context,
+            RenderCache::generate_context_key(&inputs("cube(1);", &png))
+        );
+
+        let mut cache = RenderCache::default();
+        cache.insert(keys("a", &context, &[]), result(1));
+        cache.insert(keys("b", "other", &[]), result(2));
+        cache.insert(keys("c", &context, &[]), result(3));
+        assert_eq!(
+            cache.latest_for_context(&context).map(|r| r.exit_code),
+            Some(3)
+        );
+        // A lookup marks "a" as most recently used.
+        cache.get("a");
+        assert_eq!(
+            cache.latest_for_context(&context).map(|r| r.exit_code),
+            Some(1)
+        );
+        assert!(cache.latest_for_context("missing").is_none());
+    }
+
+    #[test]
+    fn invalidates_only_entries_that_read_the_changed_file() {
+        let mut cache = RenderCache::default();
+        cache.insert(
+            keys("box", "ctx", &["/work/box/parts/../lib/bolts.scad"]),
+            result(1),
+        );
+        cache.insert(keys("lid", "ctx", &["/work/box/lid.dat"]), result(2));
+
+        assert_eq!(cache.invalidate(Path::new("/work/box/lib/bolts.scad")), 1);
+        assert!(cache.get("box").is_none());
+        assert!(cache.get("lid").is_some());
+    }
+
+    #[test]
+    fn lists_the_files_a_render_reads() {
+        let dir = std::env::temp_dir()
+            .join("openscad-studio-cache-tests")
+            .join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("bolts.scad"), "module bolt() {}").unwrap();
+        let working_dir = Some(dir.to_string_lossy().to_string());
+
+        let keys = RenderCache::keys(&RenderCacheInputs {
+            code: "use <bolts.scad>\nsurface(file = \"height.dat\");",
+            args: &[],
+            auxiliary_files: &None,
+            input_path: &Some("main.scad".to_string()),
+            working_dir: &working_dir,
+            library_paths: &None,
+        });
+
+        for name in ["bolts.scad", "height.dat", "main.scad"] {
+            assert!(keys.dependencies.contains(&dir.join(name)), "{name}");
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
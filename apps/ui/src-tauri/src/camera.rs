@@ -0,0 +1,138 @@
+/**
+ * Camera placement for PNG renders
+ *
+ * OpenSCAD's `--camera` flag takes either a gimbal camera (translation,
+ * rotation and distance, as shown in the GUI status bar) or a vector camera
+ * (eye and center points). Passing one reproduces a specific viewer
+ * orientation; without one, PNG renders frame the whole model with
+ * `--viewall --autocenter`.
+ */
+use serde::Deserialize;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CameraSpec {
+    /// Rotate (degrees) about the translated center, viewed from `distance`
+    Gimbal {
+        translate: [f64; 3],
+        rotate: [f64; 3],
+        distance: f64,
+    },
+    /// Look from `eye` towards `center`
+    Vector { eye: [f64; 3], center: [f64; 3] },
+}
+
+impl CameraSpec {
+    /// `--camera=...` argument, rejecting non-finite or degenerate cameras
+    pub fn to_arg(&self) -> Result<String, String> {
+        let values: Vec<f64> = match self {
+            CameraSpec::Gimbal {
+                translate,
+                rotate,
+                distance,
+            } => {
+                if *distance <= 0.0 {
+                    return Err("Camera distance must be positive".into());
+                }
+                translate
+                    .iter()
+                    .chain(rotate)
+                    .copied()
+                    .chain([*distance])
+                    .collect()
+            }
+            CameraSpec::Vector { eye, center } => {
+                if eye == center {
+                    return Err("Camera eye and center must differ".into());
+                }
+                eye.iter().chain(center).copied().collect()
+            }
+        };
+        if values.iter().any(|value| !value.is_finite()) {
+            return Err("Camera values must be finite numbers".into());
+        }
+        let values: Vec<String> = values.iter().map(f64::to_string).collect();
+        Ok(format!("--camera={}", values.join(",")))
+    }
+}
+
+/// Arguments for a square PNG render: the explicit camera when given,
+/// otherwise a view framing the whole model
+pub fn png_args(size: u32, camera: Option<&CameraSpec>) -> Result<Vec<String>, String> {
+    let mut args: Vec<String> = ["/input.scad", "-o", "/output.png"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    match camera {
+        Some(camera) => args.push(camera.to_arg()?),
+        None => args.extend(["--viewall".to_string(), "--autocenter".to_string()]),
+    }
+    args.push(format!("--imgsize={size},{size}"));
+    Ok(args)
+}
+
+/// Arguments for a square PNG render at the given gimbal rotation, centred
+/// on the model from a distance that frames all of it
+pub fn orbit_png_args(size: u32, rotate: [f64; 3]) -> Result<Vec<String>, String> {
+    // --viewall and --autocenter replace the translation and distance.
+    let camera = CameraSpec::Gimbal {
+        translate: [0.0; 3],
+        rotate,
+        distance: 1.0,
+    };
+    let mut args = png_args(size, Some(&camera))?;
+    args.extend(["--viewall".to_string(), "--autocenter".to_string()]);
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_camera_flags() {
+        let gimbal: CameraSpec = serde_json::from_str(
+            r#"{"kind": "gimbal", "translate": [0, 0, 5], "rotate": [55, 0, 25.5], "distance": 140}"#,
+        )
+        .unwrap();
+        assert_eq!(gimbal.to_arg().unwrap(), "--camera=0,0,5,55,0,25.5,140");
+
+        let vector = CameraSpec::Vector {
+            eye: [50.0, -50.0, 40.0],
+            center: [0.0, 0.0, 0.0],
+        };
+        assert_eq!(vector.to_arg().unwrap(), "--camera=50,-50,40,0,0,0");
+
+        assert!(CameraSpec::Vector {
+            eye: [1.0; 3],
+            center: [1.0; 3]
+        }
+        .to_arg()
+        .is_err());
+        assert!(CameraSpec::Gimbal {
+            translate: [0.0; 3],
+            rotate: [f64::NAN, 0.0, 0.0],
+            distance: 10.0
+        }
+        .to_arg()
+        .is_err());
+    }
+
+    #[test]
+    fn explicit_camera_replaces_view_all() {
+        let framed = png_args(400, None).unwrap();
+        assert!(framed.contains(&"--viewall".to_string()));
+
+        let camera = CameraSpec::Vector {
+            eye: [0.0, 0.0, 100.0],
+            center: [0.0; 3],
+        };
+        let placed = png_args(400, Some(&camera)).unwrap();
+        assert!(!placed.contains(&"--viewall".to_string()));
+        assert_eq!(placed[3..], ["--camera=0,0,100,0,0,0", "--imgsize=400,400"]);
+
+        let orbit = orbit_png_args(400, [55.0, 0.0, 90.0]).unwrap();
+        assert!(orbit.contains(&"--camera=0,0,0,55,0,90,1".to_string()));
+        assert!(orbit.contains(&"--viewall".to_string()));
+    }
+}
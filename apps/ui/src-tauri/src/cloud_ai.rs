@@ -0,0 +1,582 @@
+use crate::settings::AiProviderSettings;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+/**
+ * Claude through Amazon Bedrock and Google Vertex AI
+ *
+ * Both platforms take an Anthropic Messages API body with the model moved into
+ * the URL and an `anthropic_version` field added. Bedrock requests are signed
+ * with AWS SigV4 and stream back AWS event-stream frames, each wrapping one
+ * Anthropic event; these are re-encoded as the server-sent events the Anthropic
+ * API itself returns, so the frontend can parse both platforms the same way.
+ * Vertex requests carry a Google OAuth token and already stream server-sent
+ * events.
+ */
+use std::collections::BTreeMap;
+
+const BEDROCK_SERVICE: &str = "bedrock";
+const DEFAULT_VERTEX_REGION: &str = "us-east5";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloudProvider {
+    Bedrock,
+    Vertex,
+}
+
+impl CloudProvider {
+    pub const ALL: [CloudProvider; 2] = [CloudProvider::Bedrock, CloudProvider::Vertex];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CloudProvider::Bedrock => "Amazon Bedrock",
+            CloudProvider::Vertex => "Vertex AI",
+        }
+    }
+
+    fn anthropic_version(self) -> &'static str {
+        match self {
+            CloudProvider::Bedrock => "bedrock-2023-05-31",
+            CloudProvider::Vertex => "vertex-2023-10-16",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BedrockConfig {
+    pub region: String,
+    pub credentials: AwsCredentials,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexTarget {
+    pub project: String,
+    pub region: String,
+}
+
+/// A Messages API request reshaped for a cloud platform
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlatformRequest {
+    pub model: String,
+    pub stream: bool,
+    pub body: Value,
+}
+
+fn first_set(values: impl IntoIterator<Item = Option<String>>) -> Option<String> {
+    values
+        .into_iter()
+        .flatten()
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+pub fn aws_credentials_from_env(env: impl Fn(&str) -> Option<String>) -> Option<AwsCredentials> {
+    Some(AwsCredentials {
+        access_key_id: first_set([env("AWS_ACCESS_KEY_ID")])?,
+        secret_access_key: first_set([env("AWS_SECRET_ACCESS_KEY")])?,
+        session_token: first_set([env("AWS_SESSION_TOKEN")]),
+    })
+}
+
+/// Static keys for `profile` from an `~/.aws/credentials` file
+pub fn parse_aws_credentials(contents: &str, profile: &str) -> Option<AwsCredentials> {
+    let mut in_profile = false;
+    let mut values = BTreeMap::new();
+    for line in contents.lines().map(str::trim) {
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_profile = section.trim() == profile;
+        } else if let Some((key, value)) = line.split_once('=').filter(|_| in_profile) {
+            values.insert(key.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    Some(AwsCredentials {
+        access_key_id: first_set([values.remove("aws_access_key_id")])?,
+        secret_access_key: first_set([values.remove("aws_secret_access_key")])?,
+        session_token: first_set([values.remove("aws_session_token")]),
+    })
+}
+
+/// Region and keys for Bedrock: settings first, then the standard AWS
+/// environment variables, then the shared credentials file. Only static keys
+/// are read; SSO, assume-role and `credential_process` profiles aren't
+/// resolved.
+pub fn resolve_bedrock(
+    settings: &AiProviderSettings,
+    env: impl Fn(&str) -> Option<String>,
+    credentials_file: Option<&str>,
+) -> Result<BedrockConfig, String> {
+    let region = first_set([
+        settings.bedrock_region.clone(),
+        env("AWS_REGION"),
+        env("AWS_DEFAULT_REGION"),
+    ])
+    .ok_or("Set an AWS region for Bedrock in Settings or AWS_REGION")?;
+    let profile = first_set([settings.aws_profile.clone(), env("AWS_PROFILE")])
+        .unwrap_or_else(|| "default".to_string());
+    let credentials = aws_credentials_from_env(&env)
+        .or_else(|| credentials_file.and_then(|file| parse_aws_credentials(file, &profile)))
+        .ok_or_else(|| {
+            format!(
+                "No AWS access keys in the environment or ~/.aws/credentials [{profile}]. \
+                 SSO and assume-role profiles aren't supported; export temporary keys with \
+                 `aws configure export-credentials --format env`."
+            )
+        })?;
+    Ok(BedrockConfig {
+        region,
+        credentials,
+    })
+}
+
+pub fn resolve_vertex_target(
+    settings: &AiProviderSettings,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<VertexTarget, String> {
+    let project = first_set([
+        settings.vertex_project.clone(),
+        env("GOOGLE_CLOUD_PROJECT"),
+        env("GCLOUD_PROJECT"),
+    ])
+    .ok_or("Set a Google Cloud project for Vertex AI in Settings or GOOGLE_CLOUD_PROJECT")?;
+    let region = first_set([settings.vertex_region.clone(), env("GOOGLE_CLOUD_LOCATION")])
+        .unwrap_or_else(|| DEFAULT_VERTEX_REGION.to_string());
+    Ok(VertexTarget { project, region })
+}
+
+/// Move the model into the URL and add the platform's API version. Bedrock
+/// takes beta flags in the body; Vertex takes them as a header.
+pub fn platform_request(
+    provider: CloudProvider,
+    mut body: Value,
+    betas: &[String],
+) -> Result<PlatformRequest, String> {
+    let object = body
+        .as_object_mut()
+        .ok_or("Expected a Messages API request body")?;
+    let model = object
+        .remove("model")
+        .and_then(|model| model.as_str().map(str::to_string))
+        .filter(|model| !model.trim().is_empty())
+        .ok_or("The request does not name a model")?;
+    let stream = object
+        .remove("stream")
+        .and_then(|stream| stream.as_bool())
+        .unwrap_or(false);
+    object.insert(
+        "anthropic_version".into(),
+        provider.anthropic_version().into(),
+    );
+    if provider == CloudProvider::Bedrock && !betas.is_empty() {
+        object.insert("anthropic_beta".into(), betas.into());
+    }
+    Ok(PlatformRequest {
+        model,
+        stream,
+        body,
+    })
+}
+
+/// AWS URI encoding: everything but unreserved characters is percent-encoded
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Host and path for a Bedrock model invocation
+pub fn bedrock_endpoint(region: &str, model: &str, stream: bool) -> (String, String) {
+    let action = if stream {
+        "invoke-with-response-stream"
+    } else {
+        "invoke"
+    };
+    (
+        format!("bedrock-runtime.{region}.amazonaws.com"),
+        format!("/model/{}/{action}", uri_encode(model)),
+    )
+}
+
+pub fn vertex_url(target: &VertexTarget, model: &str, stream: bool) -> String {
+    let host = if target.region == "global" {
+        "aiplatform.googleapis.com".to_string()
+    } else {
+        format!("{}-aiplatform.googleapis.com", target.region)
+    };
+    let action = if stream {
+        "streamRawPredict"
+    } else {
+        "rawPredict"
+    };
+    format!(
+        "https://{host}/v1/projects/{}/locations/{}/publishers/anthropic/models/{model}:{action}",
+        target.project, target.region
+    )
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+/// An HTTP request as far as SigV4 needs it. `path` is the path as sent,
+/// already URI-encoded; `headers` are extra headers to sign besides `host`.
+pub struct SignableRequest<'a> {
+    pub method: &'a str,
+    pub host: &'a str,
+    pub path: &'a str,
+    pub headers: &'a [(&'a str, &'a str)],
+    pub body: &'a [u8],
+}
+
+/// Headers that authenticate `request` with AWS Signature Version 4
+pub fn sign_sigv4(
+    request: &SignableRequest,
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    now: DateTime<Utc>,
+) -> Vec<(String, String)> {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let mut added = vec![("x-amz-date".to_string(), amz_date.clone())];
+    if let Some(token) = &credentials.session_token {
+        added.push(("x-amz-security-token".to_string(), token.clone()));
+    }
+    let signed: BTreeMap<String, String> = request
+        .headers
+        .iter()
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.trim().to_string()))
+        .chain([("host".to_string(), request.host.to_string())])
+        .chain(added.iter().cloned())
+        .collect();
+    let canonical_headers: String = signed
+        .iter()
+        .map(|(name, value)| format!("{name}:{value}\n"))
+        .collect();
+    let signed_headers = signed.keys().cloned().collect::<Vec<_>>().join(";");
+
+    // Services other than S3 sign each path segment encoded a second time
+    let canonical_uri = request
+        .path
+        .split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/");
+    let canonical_request = format!(
+        "{}\n{canonical_uri}\n\n{canonical_headers}\n{signed_headers}\n{}",
+        request.method,
+        hex::encode(Sha256::digest(request.body))
+    );
+
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = [region, service, "aws4_request"].iter().fold(
+        hmac_sha256(
+            format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        ),
+        |key, part| hmac_sha256(&key, part.as_bytes()),
+    );
+    let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    added.push((
+        "authorization".to_string(),
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            credentials.access_key_id
+        ),
+    ));
+    added
+}
+
+pub fn sign_bedrock(
+    request: &SignableRequest,
+    config: &BedrockConfig,
+    now: DateTime<Utc>,
+) -> Vec<(String, String)> {
+    sign_sigv4(
+        request,
+        &config.credentials,
+        &config.region,
+        BEDROCK_SERVICE,
+        now,
+    )
+}
+
+/// One frame of an AWS event stream (`application/vnd.amazon.eventstream`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventStreamMessage {
+    /// String-valued headers such as `:message-type` and `:event-type`
+    pub headers: BTreeMap<String, String>,
+    pub payload: Vec<u8>,
+}
+
+/// Splits an AWS event stream into messages as bytes arrive
+#[derive(Debug, Default)]
+pub struct EventStreamDecoder {
+    buffer: Vec<u8>,
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_be_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+fn parse_headers(mut bytes: &[u8]) -> Result<BTreeMap<String, String>, String> {
+    let truncated = || "Truncated event stream header".to_string();
+    let mut headers = BTreeMap::new();
+    while let Some((&name_len, rest)) = bytes.split_first() {
+        let name_len = name_len as usize;
+        let name = rest.get(..name_len).ok_or_else(truncated)?;
+        let (&value_type, rest) = rest[name_len..].split_first().ok_or_else(truncated)?;
+        let value_len = match value_type {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            9 => 16,
+            6 | 7 => {
+                let len = rest.get(..2).ok_or_else(truncated)?;
+                2 + u16::from_be_bytes([len[0], len[1]]) as usize
+            }
+            other => return Err(format!("Unknown event stream header type {other}")),
+        };
+        let value = rest.get(..value_len).ok_or_else(truncated)?;
+        if value_type == 7 {
+            headers.insert(
+                String::from_utf8_lossy(name).into_owned(),
+                String::from_utf8_lossy(&value[2..]).into_owned(),
+            );
+        }
+        bytes = &rest[value_len..];
+    }
+    Ok(headers)
+}
+
+impl EventStreamDecoder {
+    /// Add `bytes` and return every message they complete
+    pub fn push(&mut self, bytes: &[u8]) -> Result<Vec<EventStreamMessage>, String> {
+        self.buffer.extend_from_slice(bytes);
+        let mut messages = Vec::new();
+        while self.buffer.len() >= 12 {
+            let total_len = read_u32(&self.buffer, 0) as usize;
+            let headers_len = read_u32(&self.buffer, 4) as usize;
+            if total_len < 16 + headers_len {
+                return Err("Malformed event stream frame".into());
+            }
+            if crc32fast::hash(&self.buffer[..8]) != read_u32(&self.buffer, 8) {
+                return Err("Event stream frame failed its checksum".into());
+            }
+            if self.buffer.len() < total_len {
+                break;
+            }
+            let frame: Vec<u8> = self.buffer.drain(..total_len).collect();
+            if crc32fast::hash(&frame[..total_len - 4]) != read_u32(&frame, total_len - 4) {
+                return Err("Event stream message failed its checksum".into());
+            }
+            messages.push(EventStreamMessage {
+                headers: parse_headers(&frame[12..12 + headers_len])?,
+                payload: frame[12 + headers_len..total_len - 4].to_vec(),
+            });
+        }
+        Ok(messages)
+    }
+}
+
+fn sse_event(data: &Value) -> String {
+    let kind = data["type"].as_str().unwrap_or("message");
+    format!("event: {kind}\ndata: {data}\n\n")
+}
+
+/// Re-encode a Bedrock stream message as an Anthropic server-sent event.
+/// Bedrock exceptions become Anthropic `error` events.
+pub fn bedrock_message_to_sse(message: &EventStreamMessage) -> Result<Option<String>, String> {
+    let payload: Value = serde_json::from_slice(&message.payload)
+        .map_err(|e| format!("Unreadable Bedrock stream payload: {e}"))?;
+    match message.headers.get(":message-type").map(String::as_str) {
+        Some("event")
+            if message.headers.get(":event-type").map(String::as_str) == Some("chunk") =>
+        {
+            let bytes = payload["bytes"]
+                .as_str()
+                .ok_or("Bedrock chunk without bytes")?;
+            let decoded = base64::engine::general_purpose::STANDARD
+                .decode(bytes)
+                .map_err(|e| format!("Bedrock chunk is not base64: {e}"))?;
+            let event: Value = serde_json::from_slice(&decoded)
+                .map_err(|e| format!("Bedrock chunk is not JSON: {e}"))?;
+            Ok(Some(sse_event(&event)))
+        }
+        Some("exception") | Some("error") => {
+            let kind = message
+                .headers
+                .get(":exception-type")
+                .or_else(|| message.headers.get(":error-code"))
+                .map_or("api_error", String::as_str);
+            Ok(Some(sse_event(&anthropic_error(kind, &payload))))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn anthropic_error(kind: &str, payload: &Value) -> Value {
+    let message = payload["message"]
+        .as_str()
+        .or_else(|| payload["Message"].as_str())
+        .or_else(|| payload["error"]["message"].as_str())
+        .map_or_else(|| payload.to_string(), str::to_string);
+    serde_json::json!({ "type": "error", "error": { "type": kind, "message": message } })
+}
+
+/// An error response from either platform in the Anthropic API's error shape
+pub fn anthropic_error_body(raw: &str) -> String {
+    let payload = serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+    if payload["type"] == "error" {
+        return raw.to_string();
+    }
+    match payload {
+        Value::String(text) => {
+            anthropic_error("api_error", &serde_json::json!({ "message": text }))
+        }
+        payload => anthropic_error("api_error", &payload),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn signs_the_aws_get_vanilla_example() {
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".into(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".into(),
+            session_token: None,
+        };
+        let request = SignableRequest {
+            method: "GET",
+            host: "example.amazonaws.com",
+            path: "/",
+            headers: &[],
+            body: b"",
+        };
+        let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+        let headers = sign_sigv4(&request, &credentials, "us-east-1", "service", now);
+
+        assert_eq!(headers[0], ("x-amz-date".into(), "20150830T123600Z".into()));
+        assert_eq!(
+            headers[1].1,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+
+        let file = "[default]\naws_access_key_id = AKID1\naws_secret_access_key = s1\n\n\
+                    [work]\naws_access_key_id=AKID2\naws_secret_access_key=s2\naws_session_token=t2\n";
+        let env = |name: &str| (name == "AWS_PROFILE").then(|| "work".to_string());
+        let settings = AiProviderSettings {
+            bedrock_region: Some("us-west-2".into()),
+            ..Default::default()
+        };
+        let config = resolve_bedrock(&settings, env, Some(file)).unwrap();
+        assert_eq!(config.region, "us-west-2");
+        assert_eq!(config.credentials.session_token.as_deref(), Some("t2"));
+        assert!(resolve_bedrock(&AiProviderSettings::default(), |_| None, Some(file)).is_err());
+    }
+
+    fn frame(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+        let mut encoded_headers = Vec::new();
+        for (name, value) in headers {
+            encoded_headers.push(name.len() as u8);
+            encoded_headers.extend_from_slice(name.as_bytes());
+            encoded_headers.push(7);
+            encoded_headers.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            encoded_headers.extend_from_slice(value.as_bytes());
+        }
+        let total_len = (16 + encoded_headers.len() + payload.len()) as u32;
+        let mut frame = total_len.to_be_bytes().to_vec();
+        frame.extend_from_slice(&(encoded_headers.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&crc32fast::hash(&frame).to_be_bytes());
+        frame.extend_from_slice(&encoded_headers);
+        frame.extend_from_slice(payload);
+        frame.extend_from_slice(&crc32fast::hash(&frame).to_be_bytes());
+        frame
+    }
+
+    #[test]
+    fn bedrock_stream_becomes_anthropic_events() {
+        let request = platform_request(
+            CloudProvider::Bedrock,
+            serde_json::json!({ "model": "anthropic.claude-v1:0", "stream": true, "max_tokens": 8 }),
+            &["tools-2024-04-04".to_string()],
+        )
+        .unwrap();
+        assert!(request.stream);
+        assert_eq!(request.body["anthropic_version"], "bedrock-2023-05-31");
+        assert_eq!(request.body["anthropic_beta"][0], "tools-2024-04-04");
+        assert!(request.body.get("model").is_none());
+        let (_, path) = bedrock_endpoint("us-east-1", &request.model, request.stream);
+        assert_eq!(
+            path,
+            "/model/anthropic.claude-v1%3A0/invoke-with-response-stream"
+        );
+
+        let event = r#"{"type":"message_stop"}"#;
+        let chunk = format!(
+            r#"{{"bytes":"{}"}}"#,
+            base64::engine::general_purpose::STANDARD.encode(event)
+        );
+        let mut bytes = frame(
+            &[(":message-type", "event"), (":event-type", "chunk")],
+            chunk.as_bytes(),
+        );
+        bytes.extend(frame(
+            &[
+                (":message-type", "exception"),
+                (":exception-type", "throttlingException"),
+            ],
+            br#"{"message":"Too many requests"}"#,
+        ));
+
+        let mut decoder = EventStreamDecoder::default();
+        let (first, rest) = bytes.split_at(20);
+        assert!(decoder.push(first).unwrap().is_empty());
+        let messages = decoder.push(rest).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            bedrock_message_to_sse(&messages[0]).unwrap().unwrap(),
+            format!("event: message_stop\ndata: {event}\n\n")
+        );
+        let error = bedrock_message_to_sse(&messages[1]).unwrap().unwrap();
+        assert!(error.starts_with("event: error\n"));
+        assert!(error.contains("throttlingException") && error.contains("Too many requests"));
+
+        let mut corrupt = frame(&[], b"{}");
+        corrupt[13] ^= 1;
+        assert!(EventStreamDecoder::default().push(&corrupt).is_err());
+    }
+}
@@ -0,0 +1,479 @@
+use crate::cmd::render::OpenScadBinaryState;
+use crate::history::HistoryState;
+use crate::mcp::WindowLaunchIntent;
+use crate::menu::{apply_menu_state, is_enabled_in_context, MenuState};
+use crate::settings::{AppSettings, SettingsState};
+use crate::{create_new_window_with_launch_intent, emit_to_focused_window};
+/**
+ * Action registry
+ *
+ * Single source of truth for every invokable action (menu items, command
+ * palette entries, future scripting). Menu events are routed through
+ * `dispatch_action` so the palette and the menu can never drift apart.
+ */
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionCategory {
+    File,
+    Render,
+    Export,
+    History,
+    View,
+    Ai,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionDescriptor {
+    pub id: String,
+    pub title: String,
+    pub category: ActionCategory,
+    pub enabled: bool,
+    pub accelerator: Option<String>,
+}
+
+/// What happens when an action is invoked.
+#[derive(Debug, Clone, Copy)]
+enum ActionEffect {
+    /// Forward to the focused window as a frontend event.
+    Emit {
+        event: &'static str,
+        payload: Option<&'static str>,
+    },
+    /// Export in the given format and remember it for `export_last`.
+    Export(&'static str),
+    ExportLast,
+    /// Switch the workspace layout preset (shown as a checked View item).
+    SetLayout(&'static str),
+    NewWindow,
+    Undo,
+    Redo,
+}
+
+struct ActionSpec {
+    id: &'static str,
+    title: &'static str,
+    category: ActionCategory,
+    effect: ActionEffect,
+    accelerator: Option<&'static str>,
+}
+
+const fn emit(event: &'static str) -> ActionEffect {
+    ActionEffect::Emit {
+        event,
+        payload: None,
+    }
+}
+
+const fn export(format: &'static str) -> ActionEffect {
+    ActionEffect::Export(format)
+}
+
+/// Action IDs intentionally match the menu item IDs built in `lib.rs`.
+const ACTIONS: &[ActionSpec] = &[
+    ActionSpec {
+        id: "new",
+        title: "New",
+        category: ActionCategory::File,
+        effect: emit("menu:file:new"),
+        accelerator: Some("CmdOrCtrl+N"),
+    },
+    ActionSpec {
+        id: "new_window",
+        title: "New Window",
+        category: ActionCategory::File,
+        effect: ActionEffect::NewWindow,
+        accelerator: Some("CmdOrCtrl+Shift+N"),
+    },
+    ActionSpec {
+        id: "open",
+        title: "Open...",
+        category: ActionCategory::File,
+        effect: emit("menu:file:open"),
+        accelerator: Some("CmdOrCtrl+O"),
+    },
+    ActionSpec {
+        id: "open_folder",
+        title: "Open Folder...",
+        category: ActionCategory::File,
+        effect: emit("menu:file:open_folder"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "save",
+        title: "Save",
+        category: ActionCategory::File,
+        effect: emit("menu:file:save"),
+        accelerator: Some("CmdOrCtrl+S"),
+    },
+    ActionSpec {
+        id: "save_as",
+        title: "Save As...",
+        category: ActionCategory::File,
+        effect: emit("menu:file:save_as"),
+        accelerator: Some("CmdOrCtrl+Shift+S"),
+    },
+    ActionSpec {
+        id: "save_all",
+        title: "Save All",
+        category: ActionCategory::File,
+        effect: emit("menu:file:save_all"),
+        accelerator: Some("CmdOrCtrl+Alt+S"),
+    },
+    ActionSpec {
+        id: "render",
+        title: "Render",
+        category: ActionCategory::Render,
+        effect: emit("menu:render"),
+        accelerator: Some("F5"),
+    },
+    ActionSpec {
+        id: "toggle_auto_render",
+        title: "Pause Auto-Render",
+        category: ActionCategory::Render,
+        effect: emit("menu:render:toggle_auto_render"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "generate_lithophane",
+        title: "Lithophane from Image...",
+        category: ActionCategory::Render,
+        effect: emit("menu:design:lithophane"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_last",
+        title: "Export Again",
+        category: ActionCategory::Export,
+        effect: ActionEffect::ExportLast,
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_stl",
+        title: "Export as STL...",
+        category: ActionCategory::Export,
+        effect: export("stl"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_obj",
+        title: "Export as OBJ...",
+        category: ActionCategory::Export,
+        effect: export("obj"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_amf",
+        title: "Export as AMF...",
+        category: ActionCategory::Export,
+        effect: export("amf"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_3mf",
+        title: "Export as 3MF...",
+        category: ActionCategory::Export,
+        effect: export("3mf"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_off",
+        title: "Export as OFF...",
+        category: ActionCategory::Export,
+        effect: export("off"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_wrl",
+        title: "Export as WRL...",
+        category: ActionCategory::Export,
+        effect: export("wrl"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_pov",
+        title: "Export as POV...",
+        category: ActionCategory::Export,
+        effect: export("pov"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_csg",
+        title: "Export as CSG...",
+        category: ActionCategory::Export,
+        effect: export("csg"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_png",
+        title: "Export as PNG...",
+        category: ActionCategory::Export,
+        effect: export("png"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_svg",
+        title: "Export as SVG...",
+        category: ActionCategory::Export,
+        effect: export("svg"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "export_dxf",
+        title: "Export as DXF...",
+        category: ActionCategory::Export,
+        effect: export("dxf"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "history_undo",
+        title: "Undo Checkpoint",
+        category: ActionCategory::History,
+        effect: ActionEffect::Undo,
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "history_redo",
+        title: "Redo Checkpoint",
+        category: ActionCategory::History,
+        effect: ActionEffect::Redo,
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "layout_default",
+        title: "Default Layout",
+        category: ActionCategory::View,
+        effect: ActionEffect::SetLayout("default"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "layout_ai_first",
+        title: "AI-First Layout",
+        category: ActionCategory::View,
+        effect: ActionEffect::SetLayout("ai-first"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "layout_customizer_first",
+        title: "Customizer-First Layout",
+        category: ActionCategory::View,
+        effect: ActionEffect::SetLayout("customizer-first"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "layout_wide_editor",
+        title: "Wide Editor Layout",
+        category: ActionCategory::View,
+        effect: ActionEffect::SetLayout("wide-editor"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "layout_wide_preview",
+        title: "Wide Preview Layout",
+        category: ActionCategory::View,
+        effect: ActionEffect::SetLayout("wide-preview"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "layout_minimal",
+        title: "Minimal Layout",
+        category: ActionCategory::View,
+        effect: ActionEffect::SetLayout("minimal"),
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "ai_toggle_panel",
+        title: "Toggle AI Panel",
+        category: ActionCategory::Ai,
+        effect: emit("menu:ai:toggle_panel"),
+        // CmdOrCtrl+Shift+L would shadow the editor's "select all occurrences".
+        accelerator: None,
+    },
+    ActionSpec {
+        id: "ai_new_conversation",
+        title: "New AI Conversation",
+        category: ActionCategory::Ai,
+        effect: emit("menu:ai:new_conversation"),
+        accelerator: None,
+    },
+];
+
+/// Runtime state shared by actions (managed by Tauri)
+#[derive(Default)]
+pub struct ActionState {
+    pub last_export_format: Mutex<Option<String>>,
+}
+
+// ============================================================================
+// Registry helpers
+// ============================================================================
+
+fn find_action(id: &str) -> Option<&'static ActionSpec> {
+    ACTIONS.iter().find(|spec| spec.id == id)
+}
+
+pub(crate) fn action_title(id: &str) -> Option<&'static str> {
+    find_action(id).map(|spec| spec.title)
+}
+
+/// Layout preset applied by a `layout_*` action, if any
+pub(crate) fn layout_preset(id: &str) -> Option<&'static str> {
+    match find_action(id)?.effect {
+        ActionEffect::SetLayout(preset) => Some(preset),
+        _ => None,
+    }
+}
+
+pub(crate) fn default_accelerator(id: &str) -> Option<&'static str> {
+    find_action(id).and_then(|spec| spec.accelerator)
+}
+
+/// User override if one is configured, otherwise the built-in default
+pub(crate) fn effective_accelerator(settings: &AppSettings, id: &str) -> Option<String> {
+    match settings.shortcuts.get(id) {
+        Some(custom) => custom.clone(),
+        None => default_accelerator(id).map(str::to_string),
+    }
+}
+
+fn binary_ready(app: &AppHandle) -> bool {
+    app.state::<OpenScadBinaryState>()
+        .path
+        .lock()
+        .unwrap()
+        .is_some()
+}
+
+fn is_action_enabled(app: &AppHandle, spec: &ActionSpec) -> bool {
+    let allowed_by_menu_state = app
+        .state::<MenuState>()
+        .context
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|context| is_enabled_in_context(spec.id, context))
+        .unwrap_or(true);
+    if !allowed_by_menu_state {
+        return false;
+    }
+
+    match spec.effect {
+        ActionEffect::Undo => app
+            .state::<HistoryState>()
+            .history
+            .lock()
+            .unwrap()
+            .can_undo(),
+        ActionEffect::Redo => app
+            .state::<HistoryState>()
+            .history
+            .lock()
+            .unwrap()
+            .can_redo(),
+        ActionEffect::ExportLast => {
+            binary_ready(app)
+                && app
+                    .state::<ActionState>()
+                    .last_export_format
+                    .lock()
+                    .unwrap()
+                    .is_some()
+        }
+        _ => match spec.category {
+            // Rendering and exporting need an initialized OpenSCAD binary.
+            ActionCategory::Render | ActionCategory::Export => binary_ready(app),
+            _ => true,
+        },
+    }
+}
+
+/// Run an action by ID. Used by both `invoke_action` and the native menu.
+pub(crate) fn dispatch_action(app: &AppHandle, id: &str) -> Result<(), String> {
+    let spec = find_action(id).ok_or_else(|| format!("Unknown action: {id}"))?;
+
+    match spec.effect {
+        ActionEffect::Emit { event, payload } => {
+            emit_to_focused_window(app, event, payload);
+            Ok(())
+        }
+        ActionEffect::Export(format) => {
+            emit_to_focused_window(app, "menu:file:export", format);
+            *app.state::<ActionState>()
+                .last_export_format
+                .lock()
+                .unwrap() = Some(format.to_string());
+            Ok(())
+        }
+        ActionEffect::ExportLast => {
+            let format = app
+                .state::<ActionState>()
+                .last_export_format
+                .lock()
+                .unwrap()
+                .clone()
+                .ok_or("Nothing has been exported yet")?;
+            emit_to_focused_window(app, "menu:file:export", format);
+            Ok(())
+        }
+        ActionEffect::SetLayout(preset) => {
+            emit_to_focused_window(app, "menu:view:layout", preset);
+            // Optimistically move the check mark; the frontend confirms it
+            // through `update_menu_state` once the layout is applied.
+            if let Some(context) = app.state::<MenuState>().context.lock().unwrap().as_mut() {
+                context.layout = Some(preset.to_string());
+            }
+            apply_menu_state(app);
+            Ok(())
+        }
+        ActionEffect::NewWindow => {
+            create_new_window_with_launch_intent(app, WindowLaunchIntent::Welcome)
+                .map(|_| ())
+                .map_err(|e| format!("Failed to create window: {e}"))
+        }
+        ActionEffect::Undo => crate::cmd::history::undo(app.clone(), None).map(|_| ()),
+        ActionEffect::Redo => crate::cmd::history::redo(app.clone(), None).map(|_| ()),
+    }
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+/// List every registered action with its current enablement
+#[tauri::command]
+pub fn list_actions(app: AppHandle) -> Result<Vec<ActionDescriptor>, String> {
+    let settings = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .clone();
+    Ok(ACTIONS
+        .iter()
+        .map(|spec| ActionDescriptor {
+            id: spec.id.to_string(),
+            title: spec.title.to_string(),
+            category: spec.category,
+            enabled: is_action_enabled(&app, spec),
+            accelerator: effective_accelerator(&settings, spec.id),
+        })
+        .collect())
+}
+
+/// Invoke an action by ID, refusing actions that are currently disabled
+#[tauri::command]
+pub fn invoke_action(app: AppHandle, id: String) -> Result<(), String> {
+    let spec = find_action(&id).ok_or_else(|| format!("Unknown action: {id}"))?;
+    if !is_action_enabled(&app, spec) {
+        return Err(format!("Action `{id}` is not currently available"));
+    }
+    dispatch_action(&app, &id)
+}
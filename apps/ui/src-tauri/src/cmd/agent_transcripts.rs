@@ -0,0 +1,121 @@
+use crate::agent_transcript::{
+    resume_messages, transcript_file_name, AgentTranscript, InterruptedQuery, TRANSCRIPT_VERSION,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const TRANSCRIPT_DIR_NAME: &str = "ai-transcripts";
+
+/// What the frontend needs to continue an interrupted request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumedQuery {
+    pub conversation_id: String,
+    pub title: String,
+    pub provider: String,
+    pub model_id: String,
+    /// Chat messages to show for the conversation
+    pub messages: Vec<Value>,
+    /// Image attachments referenced by `messages`
+    pub attachments: BTreeMap<String, Value>,
+    /// Model messages to send, ending with the last completed tool call
+    pub api_messages: Vec<Value>,
+}
+
+fn transcript_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(TRANSCRIPT_DIR_NAME))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+fn transcript_path(app: &AppHandle, conversation_id: &str) -> Result<PathBuf, String> {
+    Ok(transcript_dir(app)?.join(transcript_file_name(conversation_id)?))
+}
+
+fn read_transcript(path: &PathBuf) -> Result<AgentTranscript, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("Failed to read {path:?}: {e}"))?;
+    let transcript: AgentTranscript =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid transcript {path:?}: {e}"))?;
+    if transcript.version > TRANSCRIPT_VERSION {
+        return Err(format!(
+            "Transcript {path:?} was written by a newer version of OpenSCAD Studio"
+        ));
+    }
+    Ok(transcript)
+}
+
+/// Save the transcript of a running request, replacing the previous save
+#[tauri::command]
+pub fn save_ai_transcript(app: AppHandle, mut transcript: AgentTranscript) -> Result<(), String> {
+    transcript.version = TRANSCRIPT_VERSION;
+    let path = transcript_path(&app, &transcript.conversation_id)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+    }
+    let json = serde_json::to_string(&transcript)
+        .map_err(|e| format!("Failed to serialize transcript: {e}"))?;
+    // Write then rename, so a crash mid-write keeps the previous save
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json).map_err(|e| format!("Failed to write {tmp:?}: {e}"))?;
+    fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {path:?}: {e}"))
+}
+
+/// Forget the transcript of a request that ended
+#[tauri::command]
+pub fn discard_ai_transcript(app: AppHandle, conversation_id: String) -> Result<(), String> {
+    let path = transcript_path(&app, &conversation_id)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove {path:?}: {e}")),
+    }
+}
+
+/// Requests that were still running when the app last quit, newest first
+#[tauri::command]
+pub fn list_interrupted_ai_queries(app: AppHandle) -> Vec<InterruptedQuery> {
+    let Ok(entries) =
+        transcript_dir(&app).and_then(|dir| fs::read_dir(dir).map_err(|e| e.to_string()))
+    else {
+        return Vec::new();
+    };
+    let mut queries: Vec<InterruptedQuery> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            read_transcript(&path)
+                .inspect_err(|e| eprintln!("[ai] Ignoring transcript: {e}"))
+                .ok()
+        })
+        .map(|transcript| transcript.summary())
+        .collect();
+    queries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    queries
+}
+
+/// Load an interrupted request so the frontend can continue its tool loop
+/// from the last completed tool call
+#[tauri::command]
+pub fn resume_ai_query(app: AppHandle, conversation_id: String) -> Result<ResumedQuery, String> {
+    let transcript = read_transcript(&transcript_path(&app, &conversation_id)?)?;
+    eprintln!(
+        "[ai] Resuming {} after {} completed tool calls",
+        transcript.conversation_id,
+        transcript.summary().completed_tool_calls
+    );
+    Ok(ResumedQuery {
+        api_messages: resume_messages(&transcript),
+        conversation_id: transcript.conversation_id,
+        title: transcript.title,
+        provider: transcript.provider,
+        model_id: transcript.model_id,
+        messages: transcript.messages,
+        attachments: transcript.attachments,
+    })
+}
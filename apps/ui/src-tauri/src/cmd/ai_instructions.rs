@@ -0,0 +1,74 @@
+use crate::ai_instructions::{
+    effective_system_prompt, project_instructions_path, read_instructions, write_instructions,
+    AiInstructions, GLOBAL_INSTRUCTIONS_FILE,
+};
+use crate::cmd::EditorState;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+fn global_instructions_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(GLOBAL_INSTRUCTIONS_FILE))
+        .map_err(|e| format!("Failed to resolve app config dir: {e}"))
+}
+
+fn project_root(editor_state: &EditorState) -> Option<PathBuf> {
+    editor_state
+        .working_dir
+        .lock()
+        .unwrap()
+        .as_deref()
+        .map(PathBuf::from)
+}
+
+fn load_instructions(
+    app: &AppHandle,
+    project_root: Option<&Path>,
+) -> Result<AiInstructions, String> {
+    let global = read_instructions(&global_instructions_path(app)?)?;
+    let project_path = project_root.map(project_instructions_path);
+    let project = project_path.as_deref().map(read_instructions).transpose()?;
+    Ok(AiInstructions {
+        global,
+        project,
+        project_path: project_path.map(|path| path.to_string_lossy().into_owned()),
+    })
+}
+
+/// Get the global instructions and those of the open project
+#[tauri::command]
+pub fn get_ai_instructions(
+    app: AppHandle,
+    editor_state: State<'_, EditorState>,
+) -> Result<AiInstructions, String> {
+    load_instructions(&app, project_root(&editor_state).as_deref())
+}
+
+/// Replace the global instructions; blank instructions remove the file
+#[tauri::command]
+pub fn set_global_ai_instructions(app: AppHandle, content: String) -> Result<(), String> {
+    write_instructions(&global_instructions_path(&app)?, &content)
+}
+
+/// Replace the open project's `.openscad-studio/instructions.md`
+#[tauri::command]
+pub fn set_project_ai_instructions(
+    editor_state: State<'_, EditorState>,
+    content: String,
+) -> Result<(), String> {
+    let root = project_root(&editor_state).ok_or("No project is open")?;
+    write_instructions(&project_instructions_path(&root), &content)
+}
+
+/// The system prompt the assistant runs with: `base_prompt` (the built-in
+/// prompt, which lives in the frontend) merged with the instruction files
+#[tauri::command]
+pub fn get_effective_system_prompt(
+    app: AppHandle,
+    editor_state: State<'_, EditorState>,
+    base_prompt: String,
+) -> Result<String, String> {
+    let instructions = load_instructions(&app, project_root(&editor_state).as_deref())?;
+    Ok(effective_system_prompt(&base_prompt, &instructions))
+}
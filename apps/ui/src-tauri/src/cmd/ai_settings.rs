@@ -0,0 +1,144 @@
+use crate::settings::{update_settings, AiProviderSettings, SettingsState};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, State};
+
+/// Headers the frontend manages itself; overriding them would break auth.
+const RESERVED_HEADERS: &[&str] = &["authorization", "x-api-key", "content-type", "host"];
+
+fn normalize(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn validate_header(name: &str, value: &str) -> Result<(), String> {
+    let valid_name = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b));
+    if !valid_name {
+        return Err(format!("Invalid header name: {name:?}"));
+    }
+    if RESERVED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+        return Err(format!("Header {name} cannot be overridden"));
+    }
+    if value.bytes().any(|b| b == b'\r' || b == b'\n') {
+        return Err(format!("Header {name} contains a line break"));
+    }
+    Ok(())
+}
+
+fn sanitize(settings: AiProviderSettings) -> Result<AiProviderSettings, String> {
+    let mut anthropic_headers = BTreeMap::new();
+    for (name, value) in settings.anthropic_headers {
+        let (name, value) = (name.trim().to_string(), value.trim().to_string());
+        validate_header(&name, &value)?;
+        anthropic_headers.insert(name, value);
+    }
+
+    let openai_organization = normalize(settings.openai_organization);
+    let openai_project = normalize(settings.openai_project);
+    for (name, value) in [
+        ("OpenAI-Organization", &openai_organization),
+        ("OpenAI-Project", &openai_project),
+    ] {
+        if let Some(value) = value {
+            validate_header(name, value)?;
+        }
+    }
+
+    Ok(AiProviderSettings {
+        openai_organization,
+        openai_project,
+        anthropic_headers,
+        bedrock_region: normalize(settings.bedrock_region),
+        aws_profile: normalize(settings.aws_profile),
+        vertex_project: normalize(settings.vertex_project),
+        vertex_region: normalize(settings.vertex_region),
+    })
+}
+
+/// Headers to add to outbound requests for `provider`
+pub(crate) fn request_headers(
+    settings: &AiProviderSettings,
+    provider: &str,
+) -> BTreeMap<String, String> {
+    let mut headers = BTreeMap::new();
+    match provider {
+        "openai" => {
+            if let Some(organization) = &settings.openai_organization {
+                headers.insert("OpenAI-Organization".to_string(), organization.clone());
+            }
+            if let Some(project) = &settings.openai_project {
+                headers.insert("OpenAI-Project".to_string(), project.clone());
+            }
+        }
+        "anthropic" => headers.extend(settings.anthropic_headers.clone()),
+        _ => {}
+    }
+    headers
+}
+
+/// Get the AI provider routing settings (organization/project, cloud platforms)
+#[tauri::command]
+pub fn get_ai_provider_settings(
+    state: State<'_, SettingsState>,
+) -> Result<AiProviderSettings, String> {
+    Ok(state.settings.lock().unwrap().ai_providers.clone())
+}
+
+/// Update the AI provider routing settings
+#[tauri::command]
+pub fn set_ai_provider_settings(
+    app: AppHandle,
+    settings: AiProviderSettings,
+) -> Result<AiProviderSettings, String> {
+    let settings = sanitize(settings)?;
+    update_settings(&app, |current| {
+        current.ai_providers = settings;
+        Ok(current.ai_providers.clone())
+    })
+}
+
+/// Get the extra headers to send with requests to `provider` ("openai" or "anthropic")
+#[tauri::command]
+pub fn get_ai_request_headers(
+    provider: String,
+    state: State<'_, SettingsState>,
+) -> Result<BTreeMap<String, String>, String> {
+    let settings = state.settings.lock().unwrap();
+    Ok(request_headers(&settings.ai_providers, &provider))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openai_headers_skip_blank_values() {
+        let settings = sanitize(AiProviderSettings {
+            openai_organization: Some(" org-123 ".into()),
+            openai_project: Some("  ".into()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let headers = request_headers(&settings, "openai");
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers["OpenAI-Organization"], "org-123");
+        assert!(request_headers(&settings, "anthropic").is_empty());
+    }
+
+    #[test]
+    fn rejects_reserved_or_malformed_headers() {
+        for (name, value) in [("x-api-key", "k"), ("bad header", "v"), ("X-Route", "a\nb")] {
+            let mut anthropic_headers = BTreeMap::new();
+            anthropic_headers.insert(name.to_string(), value.to_string());
+            let settings = AiProviderSettings {
+                anthropic_headers,
+                ..Default::default()
+            };
+            assert!(sanitize(settings).is_err(), "{name} should be rejected");
+        }
+    }
+}
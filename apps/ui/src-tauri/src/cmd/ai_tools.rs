@@ -1,12 +1,37 @@
-use crate::types::Diagnostic;
-use std::sync::Mutex;
-use tauri::State;
+use crate::cmd::documents::{is_active_document, with_document};
+use crate::cmd::files::read_text_file;
+use crate::cmd::history::switch_history_project;
+use crate::cmd::render::{
+    apply_project_target, execute_render, render_policy, OpenScadBinaryState,
+};
+use crate::documents::{DocumentMeta, DocumentsState};
+use crate::parser::parse_openscad_stderr;
+use crate::pending_edits::{
+    apply_replacements, numbered_lines, replace_lines, replace_unique, PendingEdit, PendingEdits,
+    Replacement,
+};
+use crate::project::Project;
+use crate::render::queue::{RenderPriority, RenderQueue};
+use crate::safe_mode::is_escaping_path;
+use crate::types::{ChangeType, Diagnostic, DiagnosticSeverity};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Buffers above this size trigger an `editor:large-file` warning
+const LARGE_FILE_WARNING_BYTES: usize = 1024 * 1024;
 
 // Global state for editor content (used by history system)
 pub struct EditorState {
     pub current_code: Mutex<String>,
     pub diagnostics: Mutex<Vec<Diagnostic>>,
     pub working_dir: Mutex<Option<String>>,
+    /// Open multi-file project; its active file's text is `current_code`
+    pub project: Mutex<Option<Project>>,
 }
 
 impl Default for EditorState {
@@ -17,23 +42,721 @@ impl Default for EditorState {
             ),
             diagnostics: Mutex::new(Vec::new()),
             working_dir: Mutex::new(None),
+            project: Mutex::new(None),
+        }
+    }
+}
+
+/// A single text replacement. Offsets and lengths are in UTF-16 code units,
+/// matching Monaco's `rangeOffset`/`rangeLength`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    pub range_offset: usize,
+    pub range_length: usize,
+    pub text: String,
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+/// Convert a UTF-16 offset into a byte offset, rejecting offsets that are out
+/// of range or split a surrogate pair
+fn byte_offset(text: &str, utf16_offset: usize) -> Option<usize> {
+    let mut units = 0;
+    for (byte, ch) in text.char_indices() {
+        if units == utf16_offset {
+            return Some(byte);
+        }
+        units += ch.len_utf16();
+        if units > utf16_offset {
+            return None;
+        }
+    }
+    (units == utf16_offset).then_some(text.len())
+}
+
+/// Apply `edits` in order, each relative to the result of the previous one
+fn apply_text_edits(code: &str, edits: &[TextEdit]) -> Result<String, String> {
+    let mut result = code.to_string();
+    for edit in edits {
+        let start = byte_offset(&result, edit.range_offset);
+        let end = byte_offset(&result, edit.range_offset + edit.range_length);
+        let (Some(start), Some(end)) = (start, end) else {
+            return Err(format!(
+                "Edit at offset {} (length {}) is outside the document",
+                edit.range_offset, edit.range_length
+            ));
+        };
+        result.replace_range(start..end, &edit.text);
+    }
+    Ok(result)
+}
+
+/// File path of the active document, if it has been saved
+fn active_document_path(app: &AppHandle) -> Option<String> {
+    let documents = app.state::<DocumentsState>();
+    let inner = documents.inner.lock().unwrap();
+    inner.meta(&inner.active_id)?.path.clone()
+}
+
+/// Mirror the editor text into the project buffer of the active file, when
+/// the active document at `document_path` is that file
+fn sync_project_buffer(state: &EditorState, document_path: Option<&str>, code: &str) {
+    if let Some(project) = state.project.lock().unwrap().as_mut() {
+        if let Some(active) = project.editor_file(document_path).map(str::to_string) {
+            project.update(&active, code.to_string());
         }
     }
 }
 
-/// Update editor state with current code (called when user types)
+/// Whether a buffer of `len` bytes newly crosses the large-file limit,
+/// recording in `warned` (the documents already over it) whether
+/// `document_id` is over the limit
+fn crosses_large_file_limit(warned: &mut HashSet<String>, document_id: &str, len: usize) -> bool {
+    if len > LARGE_FILE_WARNING_BYTES {
+        warned.insert(document_id.to_string())
+    } else {
+        warned.remove(document_id);
+        false
+    }
+}
+
+/// A document whose buffer grew past `LARGE_FILE_WARNING_BYTES`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LargeFile {
+    document_id: String,
+    bytes: usize,
+}
+
+/// Emit `editor:large-file` the first time a document's buffer grows past the
+/// limit. `document_id` defaults to the active document.
+fn warn_if_large(app: &AppHandle, document_id: Option<&str>, len: usize) {
+    let documents = app.state::<DocumentsState>();
+    let mut inner = documents.inner.lock().unwrap();
+    let document_id = document_id.unwrap_or(&inner.active_id).to_string();
+    if crosses_large_file_limit(&mut inner.large_files, &document_id, len) {
+        let _ = app.emit(
+            "editor:large-file",
+            LargeFile {
+                document_id,
+                bytes: len,
+            },
+        );
+    }
+}
+
+/// Update editor state with current code (called when user types).
+/// `document_id` targets a tab other than the active one.
 #[tauri::command]
-pub fn update_editor_state(code: String, state: State<'_, EditorState>) -> Result<(), String> {
-    *state.current_code.lock().unwrap() = code;
+pub fn update_editor_state(
+    app: AppHandle,
+    code: String,
+    document_id: Option<String>,
+    state: State<'_, EditorState>,
+) -> Result<(), String> {
+    let len = code.len();
+    if !is_active_document(&app, document_id.as_deref()) {
+        with_document(&app, document_id.as_deref(), |document| {
+            *document.code = code;
+            Ok(())
+        })?;
+    } else {
+        sync_project_buffer(&state, active_document_path(&app).as_deref(), &code);
+        *state.current_code.lock().unwrap() = code;
+    }
+    warn_if_large(&app, document_id.as_deref(), len);
+    Ok(())
+}
+
+/// Apply incremental edits to the editor state instead of resending the whole
+/// buffer. `expected_length` is the UTF-16 length of the buffer after the
+/// edits; on mismatch the state is left untouched and an error is returned so
+/// the caller can fall back to `update_editor_state`.
+#[tauri::command]
+pub fn apply_editor_edits(
+    app: AppHandle,
+    edits: Vec<TextEdit>,
+    expected_length: usize,
+    document_id: Option<String>,
+    state: State<'_, EditorState>,
+) -> Result<(), String> {
+    let edited = |current: &str| {
+        let next = apply_text_edits(current, &edits)?;
+        let length = utf16_len(&next);
+        if length != expected_length {
+            return Err(format!(
+                "Editor state out of sync (expected length {expected_length}, got {length})"
+            ));
+        }
+        Ok(next)
+    };
+    let len = if !is_active_document(&app, document_id.as_deref()) {
+        with_document(&app, document_id.as_deref(), |document| {
+            *document.code = edited(document.code)?;
+            Ok(document.code.len())
+        })?
+    } else {
+        let document_path = active_document_path(&app);
+        let mut current = state.current_code.lock().unwrap();
+        let next = edited(&current)?;
+        sync_project_buffer(&state, document_path.as_deref(), &next);
+        *current = next;
+        current.len()
+    };
+    warn_if_large(&app, document_id.as_deref(), len);
     Ok(())
 }
 
 /// Update working directory in editor state (called when file is opened/saved)
+/// and move the checkpoint history to that folder
 #[tauri::command]
 pub fn update_working_dir(
+    app: AppHandle,
     working_dir: Option<String>,
     state: State<'_, EditorState>,
 ) -> Result<(), String> {
+    switch_history_project(&app, working_dir.as_deref());
     *state.working_dir.lock().unwrap() = working_dir;
     Ok(())
 }
+
+/// An AI edit written to a buffer
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedEdit {
+    /// Document (tab) edited, or `None` for the active one
+    pub document_id: Option<String>,
+    /// Project-relative file, or `None` for the editor buffer
+    pub file_path: Option<String>,
+    /// The file's text after the edit
+    pub code: String,
+    /// Checkpoint taken before editing the editor buffer
+    pub checkpoint_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ApplyEditResult {
+    Applied(AppliedEdit),
+    /// Held for review; see `accept_pending_edit`/`reject_pending_edit`
+    Pending(PendingEdit),
+}
+
+/// Run `edit` on the text of an edit target: the editor buffer for `None`
+/// or the file the editor shows, otherwise a project buffer (loaded from
+/// disk if the file isn't open yet). An inactive `document_id` targets that tab's code.
+fn with_target<T>(
+    app: &AppHandle,
+    state: &EditorState,
+    document_id: Option<&str>,
+    file_path: Option<&str>,
+    edit: impl FnOnce(&mut String) -> Result<T, String>,
+) -> Result<T, String> {
+    if !is_active_document(app, document_id) {
+        if let Some(path) = file_path {
+            return Err(format!(
+                "{path} can only be edited through the active document"
+            ));
+        }
+        return with_document(app, document_id, |document| edit(document.code));
+    }
+    let document_path = active_document_path(app);
+    let mut current_code = state.current_code.lock().unwrap();
+    let mut guard = state.project.lock().unwrap();
+    let (path, project) = match (file_path, guard.as_mut()) {
+        (Some(path), Some(project))
+            if Some(path) != project.editor_file(document_path.as_deref()) =>
+        {
+            (path, project)
+        }
+        (Some(path), None) => return Err(format!("No project is open to edit {path}")),
+        _ => {
+            let result = edit(&mut current_code)?;
+            drop(guard);
+            sync_project_buffer(state, document_path.as_deref(), &current_code);
+            return Ok(result);
+        }
+    };
+
+    if is_escaping_path(path) {
+        return Err(format!("{path} is outside the project"));
+    }
+    let mut content = match project.buffer(path) {
+        Some(buffer) => buffer.content.clone(),
+        None => {
+            let full_path = Path::new(&project.root).join(path);
+            let content = read_text_file(full_path.to_string_lossy().to_string())?.content;
+            project.insert_loaded(path, content.clone());
+            content
+        }
+    };
+    let result = edit(&mut content)?;
+    project.update(path, content);
+    Ok(result)
+}
+
+/// Write an edit to its buffer under one checkpoint and tell the frontend
+fn write_edit(
+    app: &AppHandle,
+    state: &EditorState,
+    document_id: Option<String>,
+    file_path: Option<String>,
+    edit: impl FnOnce(&str) -> Result<String, String>,
+) -> Result<AppliedEdit, String> {
+    let document_path = active_document_path(app);
+    let editor_file = state.project.lock().unwrap().as_ref().and_then(|project| {
+        project
+            .editor_file(document_path.as_deref())
+            .map(str::to_string)
+    });
+    let editor_buffer = file_path.is_none() || file_path == editor_file;
+    let (before, code) = with_target(
+        app,
+        state,
+        document_id.as_deref(),
+        file_path.as_deref(),
+        |code| {
+            let before = code.clone();
+            *code = edit(code)?;
+            Ok((before, code.clone()))
+        },
+    )?;
+
+    let checkpoint_id = if editor_buffer {
+        Some(with_document(app, document_id.as_deref(), |document| {
+            let diagnostics = document.diagnostics.clone();
+            Ok(document.history.create_checkpoint(
+                before,
+                diagnostics,
+                "Before AI edit".to_string(),
+                ChangeType::Ai,
+            ))
+        })?)
+    } else {
+        None
+    };
+    let applied = AppliedEdit {
+        document_id,
+        file_path,
+        code,
+        checkpoint_id,
+    };
+    let _ = app.emit("editor:ai-edit-applied", &applied);
+    Ok(applied)
+}
+
+fn emit_pending_edits(app: &AppHandle, pending: &PendingEdits) {
+    let _ = app.emit("editor:pending-edits", pending.list());
+}
+
+/// Apply an AI edit replacing the unique occurrence of `old_string` in a
+/// file (the editor buffer when `file_path` is omitted). With `dry_run` the
+/// edit is only validated and returned as a pending edit with its diff, so
+/// the user can review it before it reaches the buffer. `document_id`
+/// targets a tab other than the active one.
+#[tauri::command]
+pub fn apply_edit(
+    app: AppHandle,
+    file_path: Option<String>,
+    old_string: String,
+    new_string: String,
+    dry_run: Option<bool>,
+    document_id: Option<String>,
+    state: State<'_, EditorState>,
+    pending: State<'_, PendingEdits>,
+) -> Result<ApplyEditResult, String> {
+    if !dry_run.unwrap_or(false) {
+        return write_edit(&app, &state, document_id, file_path, |code| {
+            replace_unique(code, &old_string, &new_string)
+        })
+        .map(ApplyEditResult::Applied);
+    }
+    let edit = with_target(
+        &app,
+        &state,
+        document_id.as_deref(),
+        file_path.as_deref(),
+        |code| {
+            pending.propose(
+                document_id.clone(),
+                file_path.clone(),
+                code,
+                old_string,
+                new_string,
+            )
+        },
+    )?;
+    emit_pending_edits(&app, &pending);
+    Ok(ApplyEditResult::Pending(edit))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedEdits {
+    #[serde(flatten)]
+    pub edit: AppliedEdit,
+    pub replacements: usize,
+    /// Diagnostics from compiling the render target after the edits, or
+    /// `None` when the compile couldn't run
+    pub diagnostics: Option<Vec<Diagnostic>>,
+}
+
+/// Longest a parse-only check may take; it should finish in milliseconds
+const SYNTAX_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a test compile evaluates
+#[derive(Clone)]
+pub(crate) enum CompileSource {
+    /// The render target: the project entry file with unsaved buffers, or
+    /// the editor code. The given code replaces the editor buffer.
+    Editor(Option<String>),
+    /// A background tab's code, compiled beside its own file when it has one
+    Document {
+        code: String,
+        render_paths: Option<(String, String)>,
+    },
+}
+
+/// Evaluate `source` without building geometry. With `parse_only` the file
+/// is only parsed, by exporting its syntax tree (`.ast`), which catches
+/// syntax errors without running the model. Setting `cancelled` takes the
+/// compile out of the render queue, or stops it once running.
+pub(crate) fn test_compile(
+    app: &AppHandle,
+    source: CompileSource,
+    parse_only: bool,
+    cancelled: Option<&AtomicBool>,
+) -> Result<Vec<Diagnostic>, String> {
+    let binary_path = app
+        .state::<OpenScadBinaryState>()
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let mut auxiliary_files = None;
+    let (code, input_path, working_dir) = match source {
+        CompileSource::Editor(code) => {
+            let editor_state = app.state::<EditorState>();
+            let mut code =
+                code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+            let mut working_dir = editor_state.working_dir.lock().unwrap().clone();
+            let mut input_path = None;
+            apply_project_target(
+                app,
+                &mut code,
+                &mut auxiliary_files,
+                &mut input_path,
+                &mut working_dir,
+            )?;
+            (code, input_path, working_dir)
+        }
+        CompileSource::Document { code, render_paths } => {
+            let (working_dir, input_path) = render_paths.unzip();
+            (code, input_path, working_dir)
+        }
+    };
+    let policy = render_policy(app, &code, &auxiliary_files, &working_dir, &None)?;
+    let output = if parse_only {
+        "/output.ast"
+    } else {
+        "/output.echo"
+    };
+    let timeout = if parse_only {
+        policy.timeout.min(SYNTAX_CHECK_TIMEOUT)
+    } else {
+        policy.timeout
+    };
+    let args = ["/input.scad", "-o", output].map(String::from);
+    let queue = app.state::<RenderQueue>();
+    let _permit = queue.acquire(RenderPriority::Background, cancelled)?;
+    let result = execute_render(
+        &binary_path,
+        &code,
+        &args,
+        &auxiliary_files,
+        &input_path,
+        &policy.working_dir,
+        &policy.library_paths,
+        timeout,
+        cancelled,
+    )?;
+    Ok(parse_openscad_stderr(
+        &result.stderr,
+        Some(&code),
+        Some(input_path.as_deref().unwrap_or("input.scad")),
+        policy.working_dir.as_deref().map(Path::new),
+    ))
+}
+
+/// The running `check_syntax`, which the next one cancels
+#[derive(Default)]
+pub struct SyntaxCheckState {
+    latest: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl SyntaxCheckState {
+    /// Start a check, cancelling the one before it; returns its cancel flag
+    fn start(&self) -> Arc<AtomicBool> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if let Some(previous) = self.latest.lock().unwrap().replace(cancelled.clone()) {
+            previous.store(true, Ordering::SeqCst);
+        }
+        cancelled
+    }
+}
+
+/// Parse the render target for syntax errors without evaluating it, for
+/// linting as the user types. `code` is the unsaved editor text; the
+/// current buffer is used when it's omitted. A newer check supersedes this
+/// one, which then leaves the render queue (or stops) and fails.
+#[tauri::command]
+pub async fn check_syntax(app: AppHandle, code: Option<String>) -> Result<Vec<Diagnostic>, String> {
+    let cancelled = app.state::<SyntaxCheckState>().start();
+    tauri::async_runtime::spawn_blocking(move || {
+        test_compile(&app, CompileSource::Editor(code), true, Some(&cancelled)).map_err(|e| {
+            if cancelled.load(Ordering::SeqCst) {
+                "Syntax check superseded by a newer one".to_string()
+            } else {
+                e
+            }
+        })
+    })
+    .await
+    .map_err(|e| format!("Syntax check task failed: {e}"))?
+}
+
+/// Write an edit under one checkpoint, then test-compile the render target.
+/// The edit is parsed first, and only compiled when it parses cleanly.
+async fn write_checked_edit(
+    app: AppHandle,
+    document_id: Option<String>,
+    file_path: Option<String>,
+    replacements: usize,
+    edit: impl FnOnce(&str) -> Result<String, String>,
+) -> Result<AppliedEdits, String> {
+    // An inactive tab is compiled from its own code, beside its own file,
+    // rather than as the active document's render target.
+    let background = if is_active_document(&app, document_id.as_deref()) {
+        None
+    } else {
+        let documents = app.state::<DocumentsState>();
+        let inner = documents.inner.lock().unwrap();
+        Some(
+            document_id
+                .as_deref()
+                .and_then(|id| inner.meta(id))
+                .and_then(DocumentMeta::render_paths),
+        )
+    };
+    let edit = write_edit(
+        &app,
+        &app.state::<EditorState>(),
+        document_id,
+        file_path,
+        edit,
+    )?;
+
+    let compile_app = app.clone();
+    let source = match background {
+        Some(render_paths) => CompileSource::Document {
+            code: edit.code.clone(),
+            render_paths,
+        },
+        None => CompileSource::Editor(None),
+    };
+    let diagnostics = match tauri::async_runtime::spawn_blocking(move || {
+        let syntax = test_compile(&compile_app, source.clone(), true, None)?;
+        if syntax
+            .iter()
+            .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        {
+            return Ok(syntax);
+        }
+        test_compile(&compile_app, source, false, None)
+    })
+    .await
+    {
+        Ok(Ok(diagnostics)) => Some(diagnostics),
+        Ok(Err(e)) => {
+            eprintln!("[ai_tools] Test compile after AI edit failed: {e}");
+            None
+        }
+        Err(e) => {
+            eprintln!("[ai_tools] Test compile task failed: {e}");
+            None
+        }
+    };
+
+    Ok(AppliedEdits {
+        edit,
+        replacements,
+        diagnostics,
+    })
+}
+
+/// Apply several exact-string replacements to one file as a single edit:
+/// each is applied in order to the result of the previous one, and if any
+/// fails to match exactly once nothing is written. The edit gets one
+/// checkpoint and one test compile of the render target.
+#[tauri::command]
+pub async fn apply_edits(
+    app: AppHandle,
+    file_path: Option<String>,
+    edits: Vec<Replacement>,
+    document_id: Option<String>,
+) -> Result<AppliedEdits, String> {
+    write_checked_edit(app, document_id, file_path, edits.len(), |code| {
+        apply_replacements(code, &edits)
+    })
+    .await
+}
+
+/// Replace lines `start_line..=end_line` (1-based, as numbered by
+/// `get_current_code`) of a file with `new_text`, with the same checkpoint
+/// and test compile as `apply_edits`. Use `end_line = start_line - 1` to
+/// insert before `start_line`.
+#[tauri::command]
+pub async fn edit_lines(
+    app: AppHandle,
+    file_path: Option<String>,
+    start_line: usize,
+    end_line: usize,
+    new_text: String,
+    document_id: Option<String>,
+) -> Result<AppliedEdits, String> {
+    write_checked_edit(app, document_id, file_path, 1, |code| {
+        replace_lines(code, start_line, end_line, &new_text)
+    })
+    .await
+}
+
+/// Text of a file (the editor buffer when `file_path` is omitted), with
+/// line numbers for `edit_lines` when `numbered` is set
+#[tauri::command]
+pub fn get_current_code(
+    app: AppHandle,
+    file_path: Option<String>,
+    numbered: Option<bool>,
+    document_id: Option<String>,
+    state: State<'_, EditorState>,
+) -> Result<String, String> {
+    let code = with_target(
+        &app,
+        &state,
+        document_id.as_deref(),
+        file_path.as_deref(),
+        |code| Ok(code.clone()),
+    )?;
+    Ok(if numbered.unwrap_or(false) {
+        numbered_lines(&code)
+    } else {
+        code
+    })
+}
+
+/// Edits waiting for review, oldest first
+#[tauri::command]
+pub fn list_pending_edits(pending: State<'_, PendingEdits>) -> Vec<PendingEdit> {
+    pending.list()
+}
+
+/// Apply a pending edit to the buffer of the document it was proposed for,
+/// as it is now. Fails (and drops the edit) when the text it replaces has
+/// since changed or the document was closed.
+#[tauri::command]
+pub fn accept_pending_edit(
+    app: AppHandle,
+    id: String,
+    state: State<'_, EditorState>,
+    pending: State<'_, PendingEdits>,
+) -> Result<AppliedEdit, String> {
+    let edit = pending
+        .take(&id)
+        .ok_or_else(|| format!("No pending edit {id}"))?;
+    emit_pending_edits(&app, &pending);
+    write_edit(&app, &state, edit.document_id, edit.file_path, |code| {
+        replace_unique(code, &edit.old_string, &edit.new_string)
+    })
+    .map_err(|e| format!("The edit no longer applies: {e}"))
+}
+
+/// Discard a pending edit without touching the buffer
+#[tauri::command]
+pub fn reject_pending_edit(
+    app: AppHandle,
+    id: String,
+    pending: State<'_, PendingEdits>,
+) -> Result<(), String> {
+    pending
+        .take(&id)
+        .ok_or_else(|| format!("No pending edit {id}"))?;
+    emit_pending_edits(&app, &pending);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(range_offset: usize, range_length: usize, text: &str) -> TextEdit {
+        TextEdit {
+            range_offset,
+            range_length,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn applies_edits_with_utf16_offsets() {
+        // "é" is one UTF-16 unit but two bytes; "😀" is two units.
+        let code = "é😀cube(1);";
+        let edits = [edit(8, 1, "2"), edit(0, 1, "e")];
+
+        let result = apply_text_edits(code, &edits).unwrap();
+        assert_eq!(result, "e😀cube(2);");
+        assert_eq!(utf16_len(&result), 11);
+    }
+
+    #[test]
+    fn rejects_out_of_range_and_split_surrogates() {
+        assert!(apply_text_edits("cube(1);", &[edit(9, 0, "x")]).is_err());
+        assert!(apply_text_edits("😀", &[edit(1, 0, "x")]).is_err());
+    }
+
+    #[test]
+    fn a_new_syntax_check_cancels_the_previous_one() {
+        let checks = SyntaxCheckState::default();
+        let first = checks.start();
+        let second = checks.start();
+        assert!(first.load(Ordering::SeqCst));
+        assert!(!second.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn warns_once_per_large_file_crossing() {
+        let mut warned = HashSet::new();
+        let large = LARGE_FILE_WARNING_BYTES + 1;
+
+        assert!(!crosses_large_file_limit(&mut warned, "a", 10));
+        assert!(crosses_large_file_limit(&mut warned, "a", large));
+        assert!(!crosses_large_file_limit(&mut warned, "a", large + 1));
+        assert!(!crosses_large_file_limit(&mut warned, "a", 10));
+        assert!(crosses_large_file_limit(&mut warned, "a", large));
+    }
+
+    #[test]
+    fn large_file_warnings_are_per_document() {
+        let mut warned = HashSet::new();
+        let large = LARGE_FILE_WARNING_BYTES + 1;
+
+        assert!(crosses_large_file_limit(&mut warned, "a", large));
+        // A small background tab doesn't reset the warning for "a"
+        assert!(!crosses_large_file_limit(&mut warned, "b", 10));
+        assert!(!crosses_large_file_limit(&mut warned, "a", large));
+        assert!(crosses_large_file_limit(&mut warned, "b", large));
+    }
+}
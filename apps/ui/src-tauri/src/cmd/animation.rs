@@ -0,0 +1,455 @@
+use crate::animation::{
+    encode_gif, ffmpeg_args, find_ffmpeg, frame_file_name, frame_times, time_args,
+    turntable_rotations, AnimationFrameCache, VideoFormat, DEFAULT_ELEVATION, DEFAULT_FPS,
+    DEFAULT_FRAMES, DEFAULT_TURNTABLE_STEPS, MAX_FRAMES, MAX_TURNTABLE_STEPS, MIN_TURNTABLE_STEPS,
+};
+use crate::cache::{RenderCache, RenderCacheInputs};
+use crate::camera::{orbit_png_args, png_args, CameraSpec};
+use crate::cmd::render::{execute_render, render_policy, tokio_timeout_wait, RenderPolicy};
+use crate::cmd::sweep::first_error_line;
+use crate::cmd::{EditorState, OpenScadBinaryState};
+use crate::locate::temp_root;
+use crate::render::jobs::RenderJobManager;
+use crate::render::queue::{RenderPriority, RenderQueue};
+use crate::sweep::decode_png;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Frames rendered at the same time
+const ANIMATION_CONCURRENCY: usize = 4;
+const DEFAULT_FRAME_SIZE: u32 = 400;
+const MAX_FRAME_SIZE: u32 = 1024;
+/// Longest ffmpeg may take to encode the frames
+const VIDEO_ENCODE_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimationFormat {
+    /// One looping GIF at `output_path`
+    Gif,
+    /// Numbered PNGs in the `output_path` folder
+    Frames,
+    /// An H.264 MP4 at `output_path`; needs `ffmpeg` on PATH
+    Mp4,
+    /// A VP9 WebM at `output_path`; needs `ffmpeg` on PATH
+    Webm,
+}
+
+impl AnimationFormat {
+    fn video(self) -> Option<VideoFormat> {
+        match self {
+            AnimationFormat::Mp4 => Some(VideoFormat::Mp4),
+            AnimationFormat::Webm => Some(VideoFormat::Webm),
+            AnimationFormat::Gif | AnimationFormat::Frames => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationFrame {
+    pub index: usize,
+    pub t: f64,
+    pub success: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// Served from the frame cache without rendering
+    pub cached: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationResult {
+    /// Render job id; pass it to `cancel_render` to stop the animation
+    pub animation_id: String,
+    /// Written GIF or frame folder; `None` if cancelled or a frame failed
+    pub output_path: Option<String>,
+    /// Finished frames in order (fewer than requested if cancelled)
+    pub frames: Vec<AnimationFrame>,
+    pub cancelled: bool,
+}
+
+/// Emitted as `animation:started` before the first frame renders
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationStarted {
+    pub animation_id: String,
+    pub total: usize,
+}
+
+/// Emitted as `animation:progress` after each frame finishes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationProgress {
+    pub animation_id: String,
+    pub completed: usize,
+    pub total: usize,
+    pub frame: AnimationFrame,
+}
+
+/// Render (or fetch from the cache) one frame's PNG
+fn render_frame(
+    binary_path: &Path,
+    code: &str,
+    args: &[String],
+    policy: &RenderPolicy,
+    cache: &AnimationFrameCache,
+    cancelled: &AtomicBool,
+) -> (Result<Vec<u8>, String>, u64, bool) {
+    let key = RenderCache::generate_key(&RenderCacheInputs {
+        code,
+        args,
+        auxiliary_files: &None,
+        input_path: &None,
+        working_dir: &policy.working_dir,
+        library_paths: &policy.library_paths,
+    });
+    if let Some(png) = cache.get(&key) {
+        return (Ok(png), 0, true);
+    }
+    match execute_render(
+        binary_path,
+        code,
+        args,
+        &None,
+        &None,
+        &policy.working_dir,
+        &policy.library_paths,
+        policy.timeout,
+        Some(cancelled),
+    ) {
+        Ok(render) if render.exit_code == 0 && !render.output.is_empty() => {
+            cache.insert(key, render.output.clone());
+            (Ok(render.output), render.duration_ms, false)
+        }
+        Ok(render) => (
+            Err(first_error_line(&render.stderr)
+                .unwrap_or_else(|| format!("OpenSCAD exited with {}", render.exit_code))),
+            render.duration_ms,
+            false,
+        ),
+        Err(e) => (Err(e), 0, false),
+    }
+}
+
+/// Write `pngs` into the `dir` folder as numbered frames
+fn write_frames(pngs: &[Vec<u8>], dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create output folder {}: {e}", dir.display()))?;
+    for (index, png) in pngs.iter().enumerate() {
+        let path = dir.join(frame_file_name(index, pngs.len()));
+        fs::write(&path, png).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Encode `pngs` into a video at `output_path` with `ffmpeg`, going through
+/// a temporary frame folder
+fn encode_video(
+    ffmpeg: &Path,
+    pngs: &[Vec<u8>],
+    fps: u32,
+    format: VideoFormat,
+    output_path: &str,
+) -> Result<(), String> {
+    let work_dir = temp_root()
+        .join("animation")
+        .join(uuid::Uuid::new_v4().to_string());
+    let outcome = write_frames(pngs, &work_dir).and_then(|_| {
+        let child = Command::new(ffmpeg)
+            .args(ffmpeg_args(
+                &work_dir,
+                pngs.len(),
+                fps,
+                format,
+                Path::new(output_path),
+            ))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start ffmpeg: {e}"))?;
+        let output = tokio_timeout_wait(child, VIDEO_ENCODE_TIMEOUT, "ffmpeg", None)?
+            .into_output("ffmpeg", VIDEO_ENCODE_TIMEOUT)?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let detail = stderr.trim().lines().last().unwrap_or("no error output");
+            return Err(format!(
+                "ffmpeg failed to encode the {}: {detail}",
+                format.label()
+            ));
+        }
+        Ok(())
+    });
+    let _ = fs::remove_dir_all(&work_dir);
+    outcome
+}
+
+/// Write the rendered frames to `output_path` in `format`. Video formats
+/// are encoded with `ffmpeg`.
+fn write_animation(
+    pngs: &[Vec<u8>],
+    format: AnimationFormat,
+    fps: u32,
+    output_path: &str,
+    ffmpeg: Option<&Path>,
+) -> Result<(), String> {
+    let video = |video: VideoFormat| {
+        let ffmpeg = ffmpeg.ok_or_else(|| missing_ffmpeg(video))?;
+        encode_video(ffmpeg, pngs, fps, video, output_path)
+    };
+    match format {
+        AnimationFormat::Gif => {
+            let images = pngs
+                .iter()
+                .map(|png| decode_png(png))
+                .collect::<Result<Vec<_>, _>>()?;
+            let gif = encode_gif(&images, fps)?;
+            fs::write(output_path, gif).map_err(|e| format!("Failed to write {output_path}: {e}"))
+        }
+        AnimationFormat::Frames => write_frames(pngs, Path::new(output_path)),
+        AnimationFormat::Mp4 => video(VideoFormat::Mp4),
+        AnimationFormat::Webm => video(VideoFormat::Webm),
+    }
+}
+
+fn missing_ffmpeg(video: VideoFormat) -> String {
+    format!(
+        "{} output needs ffmpeg, which wasn't found on the PATH. Install ffmpeg, or export a GIF or PNG frames instead.",
+        video.label()
+    )
+}
+
+/// Render `jobs` (`$t` or turn fraction, and OpenSCAD arguments) as one
+/// cancellable job with progress events, then write them out if all
+/// frames succeeded
+#[allow(clippy::too_many_arguments)]
+async fn run_animation(
+    app: AppHandle,
+    binary_path: PathBuf,
+    code: String,
+    policy: RenderPolicy,
+    jobs: Vec<(f64, Vec<String>)>,
+    format: AnimationFormat,
+    fps: u32,
+    output_path: String,
+) -> Result<AnimationResult, String> {
+    // Fail before rendering anything when the video can't be encoded
+    let ffmpeg = match format.video() {
+        Some(video) => Some(find_ffmpeg().ok_or_else(|| missing_ffmpeg(video))?),
+        None => None,
+    };
+    let (animation_id, cancelled) = app.state::<RenderJobManager>().start();
+    let total = jobs.len();
+    let _ = app.emit(
+        "animation:started",
+        AnimationStarted {
+            animation_id: animation_id.clone(),
+            total,
+        },
+    );
+
+    let task_app = app.clone();
+    let task_animation_id = animation_id.clone();
+    let task_output_path = output_path.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let cache = task_app.state::<AnimationFrameCache>();
+        let queue = task_app.state::<RenderQueue>();
+        let mut frames = Vec::with_capacity(total);
+        let mut pngs = Vec::with_capacity(total);
+        for (chunk_index, chunk) in jobs.chunks(ANIMATION_CONCURRENCY).enumerate() {
+            if cancelled.load(Ordering::SeqCst) {
+                break;
+            }
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = chunk
+                    .iter()
+                    .map(|(_, args)| {
+                        let (binary_path, code, policy) = (&binary_path, &code, &policy);
+                        let (cache, cancelled) = (cache.inner(), &cancelled);
+                        let queue = queue.inner();
+                        scope.spawn(move || {
+                            let _permit = queue.acquire(RenderPriority::Export, Some(cancelled));
+                            render_frame(binary_path, code, args, policy, cache, cancelled)
+                        })
+                    })
+                    .collect();
+                for (offset, (handle, (t, _))) in handles.into_iter().zip(chunk).enumerate() {
+                    let (png, duration_ms, cached) = handle
+                        .join()
+                        .unwrap_or_else(|_| (Err("Frame render panicked".into()), 0, false));
+                    let frame = AnimationFrame {
+                        index: chunk_index * ANIMATION_CONCURRENCY + offset,
+                        t: *t,
+                        success: png.is_ok(),
+                        error: png.as_ref().err().cloned(),
+                        duration_ms,
+                        cached,
+                    };
+                    frames.push(frame.clone());
+                    pngs.push(png.ok());
+                    let _ = task_app.emit(
+                        "animation:progress",
+                        AnimationProgress {
+                            animation_id: task_animation_id.clone(),
+                            completed: frames.len(),
+                            total,
+                            frame,
+                        },
+                    );
+                }
+            });
+        }
+        let cancelled = cancelled.load(Ordering::SeqCst);
+        if cancelled {
+            return Ok((frames, false, true));
+        }
+        if let Some(failed) = frames.iter().find(|frame| !frame.success) {
+            eprintln!(
+                "[animation] Frame {} failed: {}",
+                failed.index,
+                failed.error.as_deref().unwrap_or_default()
+            );
+            return Ok((frames, false, false));
+        }
+        let pngs: Vec<Vec<u8>> = pngs.into_iter().flatten().collect();
+        write_animation(&pngs, format, fps, &task_output_path, ffmpeg.as_deref())?;
+        Ok::<_, String>((frames, true, false))
+    })
+    .await;
+    app.state::<RenderJobManager>().finish(&animation_id);
+    let (frames, written, cancelled) =
+        result.map_err(|e| format!("Animation task failed: {e}"))??;
+
+    Ok(AnimationResult {
+        animation_id,
+        output_path: written.then_some(output_path),
+        frames,
+        cancelled,
+    })
+}
+
+/// Render the current code once per frame with `$t` stepping from 0 towards
+/// 1, optionally from a fixed camera, and write a looping GIF (default), a
+/// folder of numbered PNGs or an MP4 (with `ffmpeg` on PATH) to
+/// `output_path`. `animation:progress` is emitted per frame; unchanged
+/// frames are served from the frame cache.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn render_animation(
+    app: AppHandle,
+    output_path: String,
+    frames: Option<usize>,
+    fps: Option<u32>,
+    size: Option<u32>,
+    camera: Option<CameraSpec>,
+    format: Option<AnimationFormat>,
+    code: Option<String>,
+    working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    binary_state: State<'_, OpenScadBinaryState>,
+    editor_state: State<'_, EditorState>,
+) -> Result<AnimationResult, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    let working_dir = working_dir.or_else(|| editor_state.working_dir.lock().unwrap().clone());
+    let total = frames.unwrap_or(DEFAULT_FRAMES);
+    if total == 0 || total > MAX_FRAMES {
+        return Err(format!("Frame count must be between 1 and {MAX_FRAMES}"));
+    }
+    let size = size.unwrap_or(DEFAULT_FRAME_SIZE).clamp(32, MAX_FRAME_SIZE);
+
+    let base_args = png_args(size, camera.as_ref())?;
+    let jobs: Vec<(f64, Vec<String>)> = frame_times(total)
+        .into_iter()
+        .map(|t| {
+            let mut args = base_args.clone();
+            args.extend(time_args(t));
+            (t, args)
+        })
+        .collect();
+
+    let policy = render_policy(&app, &code, &None, &working_dir, &library_paths)?;
+    eprintln!(
+        "[animation] Rendering {} frames at {}px to {}",
+        total, size, output_path
+    );
+    run_animation(
+        app,
+        binary_path,
+        code,
+        policy,
+        jobs,
+        format.unwrap_or(AnimationFormat::Gif),
+        fps.unwrap_or(DEFAULT_FPS),
+        output_path,
+    )
+    .await
+}
+
+/// Render the current model from `steps` (12–36) evenly spaced angles around
+/// it, `elevation` degrees above the horizon, and write a spinning GIF
+/// (default), numbered PNGs, or a WebM or MP4 (with `ffmpeg` on PATH) to
+/// `output_path`. Progress is reported with the `animation:*` events;
+/// unchanged angles come from the frame cache.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn render_turntable(
+    app: AppHandle,
+    output_path: String,
+    steps: Option<usize>,
+    elevation: Option<f64>,
+    fps: Option<u32>,
+    size: Option<u32>,
+    format: Option<AnimationFormat>,
+    code: Option<String>,
+    working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    binary_state: State<'_, OpenScadBinaryState>,
+    editor_state: State<'_, EditorState>,
+) -> Result<AnimationResult, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    let working_dir = working_dir.or_else(|| editor_state.working_dir.lock().unwrap().clone());
+    let steps = steps
+        .unwrap_or(DEFAULT_TURNTABLE_STEPS)
+        .clamp(MIN_TURNTABLE_STEPS, MAX_TURNTABLE_STEPS);
+    let size = size.unwrap_or(DEFAULT_FRAME_SIZE).clamp(32, MAX_FRAME_SIZE);
+
+    let jobs = turntable_rotations(steps, elevation.unwrap_or(DEFAULT_ELEVATION))
+        .into_iter()
+        .map(|(turn, rotate)| Ok((turn, orbit_png_args(size, rotate)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let policy = render_policy(&app, &code, &None, &working_dir, &library_paths)?;
+    eprintln!(
+        "[animation] Rendering {} turntable steps at {}px to {}",
+        steps, size, output_path
+    );
+    run_animation(
+        app,
+        binary_path,
+        code,
+        policy,
+        jobs,
+        format.unwrap_or(AnimationFormat::Gif),
+        fps.unwrap_or(DEFAULT_FPS),
+        output_path,
+    )
+    .await
+}
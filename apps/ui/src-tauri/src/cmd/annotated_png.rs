@@ -0,0 +1,122 @@
+use crate::annotated_png::{annotate_png, extract_source, EmbeddedSource};
+use crate::camera::{png_args, CameraSpec};
+use crate::cmd::render::{execute_render, render_policy};
+use crate::cmd::{EditorState, OpenScadBinaryState};
+use crate::variables::override_args;
+use base64::Engine;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use tauri::{AppHandle, State};
+
+const DEFAULT_IMAGE_SIZE: u32 = 800;
+const MAX_IMAGE_SIZE: u32 = 4096;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotatedPng {
+    /// Base64-encoded PNG, for copying to the clipboard
+    pub image: String,
+    pub mime_type: String,
+    /// Where the image was written, when an output path was given
+    pub output_path: Option<String>,
+}
+
+/// Render a PNG preview with the source code and parameter overrides
+/// embedded in its metadata (defaults to the current editor code and
+/// working directory). `camera` reproduces a viewer orientation; otherwise
+/// the whole model is framed.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_annotated_png(
+    app: AppHandle,
+    code: Option<String>,
+    overrides: Option<HashMap<String, String>>,
+    output_path: Option<String>,
+    working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    image_size: Option<u32>,
+    camera: Option<CameraSpec>,
+    binary_state: State<'_, OpenScadBinaryState>,
+    editor_state: State<'_, EditorState>,
+) -> Result<AnnotatedPng, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    let working_dir = working_dir.or_else(|| editor_state.working_dir.lock().unwrap().clone());
+    let policy = render_policy(&app, &code, &None, &working_dir, &library_paths)?;
+    let overrides = overrides.unwrap_or_default();
+    let size = image_size
+        .unwrap_or(DEFAULT_IMAGE_SIZE)
+        .clamp(32, MAX_IMAGE_SIZE);
+
+    let mut args = png_args(size, camera.as_ref())?;
+    args.extend(override_args(&code, &overrides)?);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let render = execute_render(
+            &binary_path,
+            &code,
+            &args,
+            &None,
+            &None,
+            &policy.working_dir,
+            &policy.library_paths,
+            policy.timeout,
+            None,
+        )?;
+        if render.exit_code != 0 || render.output.is_empty() {
+            let reason = render
+                .stderr
+                .lines()
+                .find(|line| line.trim_start().starts_with("ERROR:"))
+                .map(|line| line.trim().to_string())
+                .unwrap_or_else(|| format!("OpenSCAD exited with {}", render.exit_code));
+            return Err(format!("PNG render failed: {reason}"));
+        }
+
+        let source = EmbeddedSource {
+            code,
+            parameters: overrides.into_iter().collect(),
+        };
+        let png = annotate_png(&render.output, &source)?;
+        if let Some(path) = &output_path {
+            fs::write(path, &png).map_err(|e| format!("Failed to write {path}: {e}"))?;
+            eprintln!("[annotated-png] Wrote {path} ({} bytes)", png.len());
+        }
+        Ok(AnnotatedPng {
+            image: base64::engine::general_purpose::STANDARD.encode(png),
+            mime_type: "image/png".to_string(),
+            output_path,
+        })
+    })
+    .await
+    .map_err(|e| format!("PNG export task failed: {e}"))?
+}
+
+/// Recover the source embedded by `export_annotated_png` from an image file
+/// (`path`) or base64-encoded PNG data (`data`, e.g. from the clipboard).
+/// Returns `None` for images without embedded source.
+#[tauri::command]
+pub fn import_annotated_png(
+    path: Option<String>,
+    data: Option<String>,
+) -> Result<Option<EmbeddedSource>, String> {
+    let bytes = match (path, data) {
+        (Some(path), _) => fs::read(&path).map_err(|e| format!("Failed to read {path}: {e}"))?,
+        (None, Some(data)) => {
+            let data = data
+                .split_once("base64,")
+                .map_or(data.as_str(), |(_, encoded)| encoded);
+            base64::engine::general_purpose::STANDARD
+                .decode(data.trim())
+                .map_err(|e| format!("Invalid base64 image data: {e}"))?
+        }
+        (None, None) => return Err("Provide a PNG path or image data".into()),
+    };
+    extract_source(&bytes)
+}
@@ -0,0 +1,225 @@
+use crate::batch::{export_args, plan_outputs};
+use crate::cmd::export_presets::resolve_export_preset;
+use crate::cmd::render::{execute_render, render_policy, RenderPolicy};
+use crate::cmd::sweep::first_error_line;
+use crate::cmd::{EditorState, OpenScadBinaryState};
+use crate::openscad_capabilities::capabilities;
+use crate::render::jobs::RenderJobManager;
+use crate::render::queue::{RenderPriority, RenderQueue};
+use crate::variables::override_args;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Exports rendered at the same time when running in parallel
+const BATCH_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchJobResult {
+    pub index: usize,
+    pub file_path: String,
+    pub success: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchExportResult {
+    /// Render job id; pass it to `cancel_render` to stop the batch
+    pub batch_id: String,
+    /// Finished jobs in parameter-set order (fewer than requested if cancelled)
+    pub jobs: Vec<BatchJobResult>,
+    pub cancelled: bool,
+}
+
+/// Emitted as `batch:started` before the first export runs
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchStarted {
+    pub batch_id: String,
+    pub total: usize,
+}
+
+/// Emitted as `batch:progress` after each export finishes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    pub batch_id: String,
+    pub completed: usize,
+    pub total: usize,
+    pub job: BatchJobResult,
+}
+
+/// Render one export and write it to `path`
+fn run_job(
+    binary_path: &Path,
+    code: &str,
+    args: &[String],
+    policy: &RenderPolicy,
+    index: usize,
+    path: &Path,
+    cancelled: &AtomicBool,
+) -> BatchJobResult {
+    let result = execute_render(
+        binary_path,
+        code,
+        args,
+        &None,
+        &None,
+        &policy.working_dir,
+        &policy.library_paths,
+        policy.timeout,
+        Some(cancelled),
+    );
+    let (error, duration_ms) = match result {
+        Ok(render) if render.exit_code == 0 && !render.output.is_empty() => (
+            fs::write(path, &render.output)
+                .err()
+                .map(|e| format!("Failed to write {}: {e}", path.display())),
+            render.duration_ms,
+        ),
+        Ok(render) => (
+            Some(
+                first_error_line(&render.stderr)
+                    .unwrap_or_else(|| format!("OpenSCAD exited with {}", render.exit_code)),
+            ),
+            render.duration_ms,
+        ),
+        Err(e) => (Some(e), 0),
+    };
+    BatchJobResult {
+        index,
+        file_path: path.to_string_lossy().to_string(),
+        success: error.is_none(),
+        error,
+        duration_ms,
+    }
+}
+
+/// Export the current code once per parameter set into `output_dir`, naming
+/// files from `filename_template` (see `crate::batch`). Jobs run one at a
+/// time unless `parallel` is set; `batch:progress` is emitted per job.
+/// Every job uses the export preset `preset`, or the active one.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn batch_export(
+    app: AppHandle,
+    parameter_sets: Vec<HashMap<String, String>>,
+    filename_template: String,
+    output_dir: String,
+    format: Option<String>,
+    parallel: Option<bool>,
+    preset: Option<String>,
+    code: Option<String>,
+    working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    binary_state: State<'_, OpenScadBinaryState>,
+    editor_state: State<'_, EditorState>,
+) -> Result<BatchExportResult, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    let working_dir = working_dir.or_else(|| editor_state.working_dir.lock().unwrap().clone());
+    let format = format
+        .unwrap_or_else(|| "stl".to_string())
+        .to_ascii_lowercase();
+
+    let preset = resolve_export_preset(&app, preset.as_deref())?;
+
+    let names = plan_outputs(
+        &filename_template,
+        &parameter_sets,
+        &format,
+        &capabilities(&binary_path),
+    )?;
+    let mut jobs: Vec<(Vec<String>, PathBuf)> = Vec::with_capacity(names.len());
+    for (parameters, name) in parameter_sets.iter().zip(names) {
+        let mut args = export_args(&format);
+        args.extend(override_args(&code, parameters)?);
+        preset.apply(&mut args)?;
+        jobs.push((args, Path::new(&output_dir).join(name)));
+    }
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder {output_dir}: {e}"))?;
+
+    let policy = render_policy(&app, &code, &None, &working_dir, &library_paths)?;
+    let (batch_id, cancelled) = app.state::<RenderJobManager>().start();
+    let total = jobs.len();
+    let chunk_size = if parallel.unwrap_or(false) {
+        BATCH_CONCURRENCY
+    } else {
+        1
+    };
+    eprintln!(
+        "[batch] Exporting {} {} files to {} ({} at a time)",
+        total, format, output_dir, chunk_size
+    );
+    let _ = app.emit(
+        "batch:started",
+        BatchStarted {
+            batch_id: batch_id.clone(),
+            total,
+        },
+    );
+
+    let task_app = app.clone();
+    let task_batch_id = batch_id.clone();
+    let results = tauri::async_runtime::spawn_blocking(move || {
+        let queue = task_app.state::<RenderQueue>();
+        let mut results = Vec::with_capacity(total);
+        for (chunk_index, chunk) in jobs.chunks(chunk_size).enumerate() {
+            if cancelled.load(Ordering::SeqCst) {
+                break;
+            }
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, (args, path))| {
+                        let index = chunk_index * chunk_size + offset;
+                        let (binary_path, code, policy) = (&binary_path, &code, &policy);
+                        let (queue, cancelled) = (queue.inner(), &cancelled);
+                        scope.spawn(move || {
+                            let _permit = queue.acquire(RenderPriority::Export, Some(cancelled));
+                            run_job(binary_path, code, args, policy, index, path, cancelled)
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    let Ok(job) = handle.join() else {
+                        continue;
+                    };
+                    results.push(job.clone());
+                    let _ = task_app.emit(
+                        "batch:progress",
+                        BatchProgress {
+                            batch_id: task_batch_id.clone(),
+                            completed: results.len(),
+                            total,
+                            job,
+                        },
+                    );
+                }
+            });
+        }
+        (results, cancelled.load(Ordering::SeqCst))
+    })
+    .await;
+    app.state::<RenderJobManager>().finish(&batch_id);
+    let (jobs, cancelled) = results.map_err(|e| format!("Batch export task failed: {e}"))?;
+
+    Ok(BatchExportResult {
+        batch_id,
+        jobs,
+        cancelled,
+    })
+}
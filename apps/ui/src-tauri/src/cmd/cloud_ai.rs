@@ -0,0 +1,285 @@
+use crate::cloud_ai::{
+    anthropic_error_body, bedrock_endpoint, bedrock_message_to_sse, platform_request,
+    resolve_bedrock, resolve_vertex_target, sign_bedrock, vertex_url, BedrockConfig, CloudProvider,
+    EventStreamDecoder, SignableRequest,
+};
+use crate::settings::{AiProviderSettings, SettingsState};
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, State};
+
+/// gcloud access tokens last an hour; refresh well before that
+const VERTEX_TOKEN_TTL: Duration = Duration::from_secs(45 * 60);
+
+#[derive(Default)]
+pub struct CloudAiState {
+    /// Requests the frontend abandoned; their streams stop at the next read
+    cancelled: Mutex<HashSet<String>>,
+    vertex_token: Mutex<Option<(String, Instant)>>,
+}
+
+/// Events for one `cloud_ai_request`, in order: `response`, any `chunk`s, `end`
+#[derive(Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum CloudAiEvent {
+    Response { status: u16 },
+    Chunk(String),
+    End,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudProviderStatus {
+    pub provider: CloudProvider,
+    /// Why requests can't be sent yet; `None` when credentials were found
+    pub error: Option<String>,
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+fn provider_settings(app: &AppHandle) -> AiProviderSettings {
+    app.state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .ai_providers
+        .clone()
+}
+
+fn bedrock_config(app: &AppHandle) -> Result<BedrockConfig, String> {
+    let credentials_file = env_var("AWS_SHARED_CREDENTIALS_FILE")
+        .map(PathBuf::from)
+        .or_else(|| {
+            let home = app.path().home_dir().ok()?;
+            Some(home.join(".aws").join("credentials"))
+        })
+        .and_then(|path| std::fs::read_to_string(path).ok());
+    resolve_bedrock(
+        &provider_settings(app),
+        env_var,
+        credentials_file.as_deref(),
+    )
+}
+
+/// An OAuth token from `GOOGLE_OAUTH_ACCESS_TOKEN` or the gcloud CLI
+fn vertex_token(state: &CloudAiState) -> Result<String, String> {
+    if let Some(token) = env_var("GOOGLE_OAUTH_ACCESS_TOKEN").filter(|t| !t.trim().is_empty()) {
+        return Ok(token.trim().to_string());
+    }
+    let mut cached = state.vertex_token.lock().unwrap();
+    if let Some((token, fetched_at)) = cached.as_ref() {
+        if fetched_at.elapsed() < VERTEX_TOKEN_TTL {
+            return Ok(token.clone());
+        }
+    }
+    let gcloud = if cfg!(windows) {
+        "gcloud.cmd"
+    } else {
+        "gcloud"
+    };
+    let output = Command::new(gcloud)
+        .args(["auth", "print-access-token"])
+        .output()
+        .map_err(|e| {
+            format!("No Google credentials: set GOOGLE_OAUTH_ACCESS_TOKEN or install gcloud ({e})")
+        })?;
+    if !output.status.success() {
+        return Err(format!(
+            "gcloud could not provide an access token: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    *cached = Some((token.clone(), Instant::now()));
+    Ok(token)
+}
+
+fn check_provider(app: &AppHandle, provider: CloudProvider) -> Result<(), String> {
+    match provider {
+        CloudProvider::Bedrock => bedrock_config(app).map(|_| ()),
+        CloudProvider::Vertex => {
+            resolve_vertex_target(&provider_settings(app), env_var)?;
+            vertex_token(&app.state::<CloudAiState>()).map(|_| ())
+        }
+    }
+}
+
+/// Longest complete UTF-8 prefix of `bytes`, leaving a split character behind
+fn take_utf8(bytes: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(bytes) {
+        Ok(_) => bytes.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => bytes.len(),
+    };
+    let rest = bytes.split_off(valid);
+    String::from_utf8_lossy(&std::mem::replace(bytes, rest)).into_owned()
+}
+
+fn send(on_event: &Channel<CloudAiEvent>, event: CloudAiEvent) -> Result<(), String> {
+    on_event
+        .send(event)
+        .map_err(|e| format!("Failed to deliver the response: {e}"))
+}
+
+fn stream_body(
+    app: &AppHandle,
+    request_id: &str,
+    event_stream: bool,
+    response: &mut reqwest::blocking::Response,
+    on_event: &Channel<CloudAiEvent>,
+) -> Result<(), String> {
+    let state = app.state::<CloudAiState>();
+    let mut decoder = EventStreamDecoder::default();
+    let mut pending = Vec::new();
+    let mut buffer = [0u8; 16 * 1024];
+    loop {
+        if state.cancelled.lock().unwrap().contains(request_id) {
+            return Ok(());
+        }
+        let read = response
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read the response: {e}"))?;
+        if read == 0 {
+            return Ok(());
+        }
+        let text = if event_stream {
+            decoder
+                .push(&buffer[..read])?
+                .iter()
+                .filter_map(|message| bedrock_message_to_sse(message).transpose())
+                .collect::<Result<String, _>>()?
+        } else {
+            pending.extend_from_slice(&buffer[..read]);
+            take_utf8(&mut pending)
+        };
+        if !text.is_empty() {
+            send(on_event, CloudAiEvent::Chunk(text))?;
+        }
+    }
+}
+
+fn send_request(
+    app: &AppHandle,
+    request_id: &str,
+    provider: CloudProvider,
+    body: serde_json::Value,
+    betas: &[String],
+    on_event: &Channel<CloudAiEvent>,
+) -> Result<(), String> {
+    let request = platform_request(provider, body, betas)?;
+    let payload = serde_json::to_vec(&request.body)
+        .map_err(|e| format!("Failed to serialize the request: {e}"))?;
+    let client = reqwest::blocking::Client::builder()
+        .connect_timeout(Duration::from_secs(30))
+        .timeout(None::<Duration>)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+
+    let builder = match provider {
+        CloudProvider::Bedrock => {
+            let config = bedrock_config(app)?;
+            let (host, path) = bedrock_endpoint(&config.region, &request.model, request.stream);
+            let signable = SignableRequest {
+                method: "POST",
+                host: &host,
+                path: &path,
+                headers: &[("content-type", "application/json")],
+                body: &payload,
+            };
+            sign_bedrock(&signable, &config, chrono::Utc::now())
+                .into_iter()
+                .fold(
+                    client.post(format!("https://{host}{path}")),
+                    |builder, (name, value)| builder.header(name, value),
+                )
+        }
+        CloudProvider::Vertex => {
+            let target = resolve_vertex_target(&provider_settings(app), env_var)?;
+            let token = vertex_token(&app.state::<CloudAiState>())?;
+            let builder = client
+                .post(vertex_url(&target, &request.model, request.stream))
+                .bearer_auth(token);
+            if betas.is_empty() {
+                builder
+            } else {
+                builder.header("anthropic-beta", betas.join(","))
+            }
+        }
+    };
+
+    let mut response = builder
+        .header(CONTENT_TYPE, "application/json")
+        .body(payload)
+        .send()
+        .map_err(|e| format!("{} request failed: {e}", provider.label()))?;
+    let status = response.status();
+    send(
+        on_event,
+        CloudAiEvent::Response {
+            status: status.as_u16(),
+        },
+    )?;
+    if status.is_success() {
+        let event_stream = provider == CloudProvider::Bedrock && request.stream;
+        stream_body(app, request_id, event_stream, &mut response, on_event)?;
+    } else {
+        let raw = response.text().unwrap_or_default();
+        send(on_event, CloudAiEvent::Chunk(anthropic_error_body(&raw)))?;
+    }
+    send(on_event, CloudAiEvent::End)
+}
+
+/// Whether each cloud platform has the credentials and configuration it needs
+#[tauri::command]
+pub async fn get_cloud_ai_providers(app: AppHandle) -> Result<Vec<CloudProviderStatus>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        CloudProvider::ALL
+            .into_iter()
+            .map(|provider| CloudProviderStatus {
+                provider,
+                error: check_provider(&app, provider).err(),
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Credential check failed: {e}"))
+}
+
+/// Send an Anthropic Messages API request to Claude on `provider`, streaming
+/// the response back through `on_event` in the Anthropic API's own format
+#[tauri::command]
+pub async fn cloud_ai_request(
+    app: AppHandle,
+    request_id: String,
+    provider: CloudProvider,
+    body: serde_json::Value,
+    betas: Option<Vec<String>>,
+    on_event: Channel<CloudAiEvent>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let betas = betas.unwrap_or_default();
+        let result = send_request(&app, &request_id, provider, body, &betas, &on_event);
+        app.state::<CloudAiState>()
+            .cancelled
+            .lock()
+            .unwrap()
+            .remove(&request_id);
+        result
+    })
+    .await
+    .map_err(|e| format!("{} request failed: {e}", provider.label()))?
+}
+
+#[tauri::command]
+pub fn cancel_cloud_ai_request(request_id: String, state: State<'_, CloudAiState>) {
+    state.cancelled.lock().unwrap().insert(request_id);
+}
@@ -0,0 +1,170 @@
+use crate::agent_transcript::transcript_file_name;
+use crate::conversation::{
+    from_json, to_json, to_markdown, Conversation, ExportFormat, ImportedConversation,
+};
+use crate::conversation_index::{
+    ConversationIndex, ConversationSearchHit, StoredConversationIndex, INDEX_FILE_NAME,
+    INDEX_VERSION,
+};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+const SAVED_DIR_NAME: &str = "conversations";
+
+/// The loaded conversation search index, read from disk on first use
+#[derive(Default)]
+pub struct ConversationIndexState {
+    index: Mutex<Option<Arc<ConversationIndex>>>,
+}
+
+fn saved_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(SAVED_DIR_NAME))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+fn saved_path(app: &AppHandle, conversation_id: &str) -> Result<PathBuf, String> {
+    Ok(saved_dir(app)?.join(transcript_file_name(conversation_id)?))
+}
+
+/// Write then rename, so a crash mid-write keeps the previous file
+fn write_atomically(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, contents).map_err(|e| format!("Failed to write {tmp:?}: {e}"))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to write {path:?}: {e}"))
+}
+
+fn read_index(path: &Path) -> StoredConversationIndex {
+    let Ok(json) = fs::read_to_string(path) else {
+        return StoredConversationIndex::default();
+    };
+    serde_json::from_str::<StoredConversationIndex>(&json)
+        .inspect_err(|e| eprintln!("[conversations] Ignoring unreadable {path:?}: {e}"))
+        .ok()
+        .filter(|stored| stored.version <= INDEX_VERSION)
+        .unwrap_or_default()
+}
+
+fn loaded_index(
+    app: &AppHandle,
+    loaded: &mut Option<Arc<ConversationIndex>>,
+) -> Result<Arc<ConversationIndex>, String> {
+    if let Some(index) = loaded.as_ref() {
+        return Ok(index.clone());
+    }
+    let stored = read_index(&saved_dir(app)?.join(INDEX_FILE_NAME));
+    let index = Arc::new(ConversationIndex::new(stored));
+    *loaded = Some(index.clone());
+    Ok(index)
+}
+
+/// Write a conversation to `path` as a Markdown transcript or a JSON export
+/// that `import_conversation` can read back. Conversations are held by the
+/// frontend, so the caller passes the conversation itself, and for JSON the
+/// attachments of its images.
+#[tauri::command]
+pub fn export_conversation(
+    conversation: Conversation,
+    attachments: Option<BTreeMap<String, Value>>,
+    format: ExportFormat,
+    path: String,
+) -> Result<(), String> {
+    let contents = match format {
+        ExportFormat::Markdown => to_markdown(&conversation),
+        ExportFormat::Json => to_json(
+            &conversation,
+            &attachments.unwrap_or_default(),
+            chrono::Utc::now().timestamp_millis(),
+        )?,
+    };
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {path}: {e}"))?;
+    eprintln!(
+        "[conversations] Exported {} ({} messages) to {}",
+        conversation.id,
+        conversation.messages.len(),
+        path
+    );
+    Ok(())
+}
+
+/// Read a conversation and its image attachments from a JSON export
+#[tauri::command]
+pub fn import_conversation(path: String) -> Result<ImportedConversation, String> {
+    let json = fs::read_to_string(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    from_json(&json)
+}
+
+/// Save a conversation so it can be searched and reopened, replacing its
+/// previous save and its entry in the search index
+#[tauri::command]
+pub fn save_conversation(
+    app: AppHandle,
+    conversation: Conversation,
+    attachments: Option<BTreeMap<String, Value>>,
+) -> Result<(), String> {
+    let json = to_json(
+        &conversation,
+        &attachments.unwrap_or_default(),
+        chrono::Utc::now().timestamp_millis(),
+    )?;
+    write_atomically(&saved_path(&app, &conversation.id)?, &json)?;
+
+    let state = app.state::<ConversationIndexState>();
+    let mut loaded = state.index.lock().unwrap();
+    let mut stored = loaded_index(&app, &mut loaded)?.stored.clone();
+    stored.upsert(&conversation);
+    let index_json = serde_json::to_string(&stored)
+        .map_err(|e| format!("Failed to serialize conversation index: {e}"))?;
+    write_atomically(&saved_dir(&app)?.join(INDEX_FILE_NAME), &index_json)?;
+    *loaded = Some(Arc::new(ConversationIndex::new(stored)));
+    Ok(())
+}
+
+/// Search the text of saved conversations, tool calls included, best
+/// matches first
+#[tauri::command]
+pub async fn search_conversations(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<ConversationSearchHit>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<ConversationIndexState>();
+        let index = loaded_index(&app, &mut state.index.lock().unwrap())?;
+        Ok(index.search(&query, limit))
+    })
+    .await
+    .map_err(|e| format!("Conversation search task failed: {e}"))?
+}
+
+/// Read a saved conversation and its image attachments. Saves from older
+/// versions are rewritten in the current format once they have been read.
+#[tauri::command]
+pub fn open_saved_conversation(
+    app: AppHandle,
+    conversation_id: String,
+) -> Result<ImportedConversation, String> {
+    let path = saved_path(&app, &conversation_id)?;
+    let json = fs::read_to_string(&path).map_err(|e| format!("Failed to read {path:?}: {e}"))?;
+    let saved = from_json(&json)?;
+    if saved.migrated {
+        let json = to_json(
+            &saved.conversation,
+            &saved.attachments,
+            chrono::Utc::now().timestamp_millis(),
+        )?;
+        match write_atomically(&path, &json) {
+            Ok(()) => eprintln!("[conversations] Migrated {path:?} to the current format"),
+            Err(e) => eprintln!("[conversations] Failed to migrate {path:?}: {e}"),
+        }
+    }
+    Ok(saved)
+}
@@ -0,0 +1,16 @@
+use crate::cmd::EditorState;
+use crate::customizer::{customizer_schema, CustomizerSchema};
+use tauri::State;
+
+/// Customizer parameters (grouped, annotated top-level variables) of the
+/// given code, defaulting to the current editor code. Values are applied to
+/// renders through the `overrides` argument of `render_preview` and
+/// `render_native`.
+#[tauri::command]
+pub fn get_customizer_parameters(
+    code: Option<String>,
+    editor_state: State<'_, EditorState>,
+) -> CustomizerSchema {
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    customizer_schema(&code)
+}
@@ -0,0 +1,53 @@
+use crate::cmd::documents::with_document;
+use crate::cmd::EditorState;
+use crate::parser::parse_openscad_stderr;
+use crate::types::Diagnostic;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Parse OpenSCAD stderr into diagnostics with best-effort token ranges
+/// (defaults to locating tokens in the current editor code). Diagnostics
+/// name the project file they refer to, relative to the working directory;
+/// `input_path` is the rendered file, whose diagnostics get token ranges.
+/// With a `document_id`, tokens are located in that tab's code and the
+/// result is stored as its diagnostics.
+#[tauri::command]
+pub fn parse_diagnostics(
+    app: AppHandle,
+    stderr: String,
+    code: Option<String>,
+    input_path: Option<String>,
+    document_id: Option<String>,
+    editor_state: State<'_, EditorState>,
+) -> Result<Vec<Diagnostic>, String> {
+    let code = match (code, document_id.as_deref()) {
+        (Some(code), _) => code,
+        (None, Some(id)) => with_document(&app, Some(id), |document| Ok(document.code.clone()))?,
+        (None, None) => editor_state.current_code.lock().unwrap().clone(),
+    };
+    let working_dir = editor_state.working_dir.lock().unwrap().clone();
+    let diagnostics = parse_openscad_stderr(
+        &stderr,
+        Some(&code),
+        input_path.as_deref(),
+        working_dir.as_deref().map(Path::new),
+    );
+    if document_id.is_some() {
+        with_document(&app, document_id.as_deref(), |document| {
+            *document.diagnostics = diagnostics.clone();
+            Ok(())
+        })?;
+    }
+    Ok(diagnostics)
+}
+
+/// The diagnostics last stored for a document (the active one by default)
+#[tauri::command]
+pub fn get_diagnostics(
+    app: AppHandle,
+    document_id: Option<String>,
+) -> Result<Vec<Diagnostic>, String> {
+    with_document(&app, document_id.as_deref(), |document| {
+        Ok(document.diagnostics.clone())
+    })
+}
@@ -0,0 +1,16 @@
+use crate::cmd::docs_index::search_index;
+use crate::docs_index::DocsSearchResult;
+use tauri::AppHandle;
+
+/// Search the OpenSCAD reference, library cheat sheets and installed
+/// libraries, best matches first. `source` limits results to "OpenSCAD" or
+/// one library.
+#[tauri::command]
+pub async fn search_docs(
+    app: AppHandle,
+    query: String,
+    source: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<DocsSearchResult>, String> {
+    search_index(app, query, source, limit).await
+}
@@ -0,0 +1,176 @@
+use crate::cmd::libraries::library_overview;
+use crate::docs_index::{
+    bundled_entries, index_library, library_fingerprint, DocsIndex, DocsSearchResult,
+    IndexedLibrary, StoredDocsIndex, INDEX_FILE_NAME, INDEX_VERSION,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// The loaded documentation index, built or read from disk on first use
+#[derive(Default)]
+pub struct DocsIndexState {
+    index: Mutex<Option<Arc<DocsIndex>>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocsIndexStatus {
+    pub path: String,
+    /// When the stored index was built, if it exists
+    pub built_at: Option<i64>,
+    pub libraries: Vec<IndexedLibrary>,
+    /// Searchable entries, bundled documentation included
+    pub entries: usize,
+    /// Whether installed libraries changed since the index was built
+    pub stale: bool,
+}
+
+fn index_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(INDEX_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+/// Installed libraries as (name, folder)
+fn installed_libraries(app: &AppHandle) -> Vec<(String, PathBuf)> {
+    library_overview(app)
+        .map(|overview| {
+            overview
+                .installed
+                .into_iter()
+                .map(|library| (library.name, PathBuf::from(library.path)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn fingerprints(libraries: &[(String, PathBuf)]) -> Vec<(String, String)> {
+    libraries
+        .iter()
+        .map(|(name, dir)| (name.clone(), library_fingerprint(dir)))
+        .collect()
+}
+
+fn read_index(path: &Path) -> Option<DocsIndex> {
+    let json = fs::read_to_string(path).ok()?;
+    let stored: StoredDocsIndex = serde_json::from_str(&json)
+        .inspect_err(|e| eprintln!("[docs_index] Ignoring unreadable {}: {e}", path.display()))
+        .ok()?;
+    Some(DocsIndex::new(stored))
+}
+
+fn scan_libraries(libraries: &[(String, PathBuf)]) -> StoredDocsIndex {
+    let mut stored = StoredDocsIndex {
+        version: INDEX_VERSION,
+        built_at: chrono::Utc::now().timestamp_millis(),
+        ..Default::default()
+    };
+    for (name, dir) in libraries {
+        let (library, entries) = index_library(name, dir);
+        eprintln!(
+            "[docs_index] Indexed {name}: {} entries from {} files",
+            library.entries, library.files
+        );
+        stored.libraries.push(library);
+        stored.entries.extend(entries);
+    }
+    stored
+}
+
+fn save_index(app: &AppHandle, stored: &StoredDocsIndex) -> Result<(), String> {
+    let path = index_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let json = serde_json::to_string(stored)
+        .map_err(|e| format!("Failed to serialize documentation index: {e}"))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// The documentation index, reading the stored one or rebuilding it when
+/// installed libraries changed. Blocking.
+pub(crate) fn docs_index(app: &AppHandle) -> Arc<DocsIndex> {
+    let state = app.state::<DocsIndexState>();
+    let mut loaded = state.index.lock().unwrap();
+    let libraries = installed_libraries(app);
+    let current = fingerprints(&libraries);
+    if let Some(index) = loaded.as_ref().filter(|index| index.is_current(&current)) {
+        return index.clone();
+    }
+
+    let stored = index_path(app)
+        .ok()
+        .and_then(|path| read_index(&path))
+        .filter(|index| index.is_current(&current));
+    let index = stored.unwrap_or_else(|| {
+        let stored = scan_libraries(&libraries);
+        if let Err(e) = save_index(app, &stored) {
+            eprintln!("[docs_index] {e}; keeping the index in memory only");
+        }
+        DocsIndex::new(stored)
+    });
+    let index = Arc::new(index);
+    *loaded = Some(index.clone());
+    index
+}
+
+fn status(app: &AppHandle, index: Option<&DocsIndex>) -> Result<DocsIndexStatus, String> {
+    let current = fingerprints(&installed_libraries(app));
+    Ok(DocsIndexStatus {
+        path: index_path(app)?.to_string_lossy().to_string(),
+        built_at: index
+            .map(|index| index.stored.built_at)
+            .filter(|built_at| *built_at > 0),
+        libraries: index
+            .map(|index| index.stored.libraries.clone())
+            .unwrap_or_default(),
+        entries: index.map_or_else(|| bundled_entries().len(), DocsIndex::entry_count),
+        stale: !index.is_some_and(|index| index.is_current(&current)),
+    })
+}
+
+/// Rebuild the documentation index from the installed libraries
+#[tauri::command]
+pub async fn build_docs_index(app: AppHandle) -> Result<DocsIndexStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let stored = scan_libraries(&installed_libraries(&app));
+        save_index(&app, &stored)?;
+        let index = Arc::new(DocsIndex::new(stored));
+        *app.state::<DocsIndexState>().index.lock().unwrap() = Some(index.clone());
+        status(&app, Some(&index))
+    })
+    .await
+    .map_err(|e| format!("Documentation indexing task failed: {e}"))?
+}
+
+/// What the stored documentation index covers, without building it
+#[tauri::command]
+pub fn get_docs_index_status(app: AppHandle) -> Result<DocsIndexStatus, String> {
+    let loaded = app.state::<DocsIndexState>().index.lock().unwrap().clone();
+    match loaded {
+        Some(index) => status(&app, Some(&index)),
+        None => {
+            let stored = read_index(&index_path(&app)?);
+            status(&app, stored.as_ref())
+        }
+    }
+}
+
+/// Search the indexed documentation (built on first use), best matches first
+pub(crate) async fn search_index(
+    app: AppHandle,
+    query: String,
+    source: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<DocsSearchResult>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        docs_index(&app).search(&query, source.as_deref(), limit)
+    })
+    .await
+    .map_err(|e| format!("Documentation search task failed: {e}"))
+}
@@ -0,0 +1,502 @@
+use crate::cmd::files::read_text_file;
+use crate::cmd::EditorState;
+use crate::documents::{
+    title_for_path, untitled_document, DocumentInfo, DocumentMeta, DocumentsInner, DocumentsState,
+    ParkedDocument,
+};
+use crate::history::{EditorHistory, HistoryState};
+use crate::types::{ChangeType, Diagnostic};
+/**
+ * Document (tab) Tauri commands
+ */
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentContents {
+    pub document: DocumentInfo,
+    pub code: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Code, diagnostics and checkpoints of one document, wherever it lives
+pub(crate) struct DocumentBuffers<'a> {
+    pub code: &'a mut String,
+    pub diagnostics: &'a mut Vec<Diagnostic>,
+    pub history: &'a mut EditorHistory,
+    /// Whether this is the active document, shown in the editor
+    pub is_active: bool,
+}
+
+/// Run `f` on the buffers of `document_id`, or of the active document when
+/// it is `None`. Inactive documents are edited in place without switching.
+pub(crate) fn with_document<T>(
+    app: &AppHandle,
+    document_id: Option<&str>,
+    f: impl FnOnce(DocumentBuffers<'_>) -> Result<T, String>,
+) -> Result<T, String> {
+    with_document_in(
+        &app.state::<DocumentsState>(),
+        &app.state::<EditorState>(),
+        &app.state::<HistoryState>(),
+        document_id,
+        f,
+    )
+}
+
+fn with_document_in<T>(
+    documents: &DocumentsState,
+    editor_state: &EditorState,
+    history_state: &HistoryState,
+    document_id: Option<&str>,
+    f: impl FnOnce(DocumentBuffers<'_>) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut inner = documents.inner.lock().unwrap();
+    let parked_id = match document_id {
+        Some(id) if id != inner.active_id => {
+            if inner.meta(id).is_none() {
+                return Err(format!("Document not found: {id}"));
+            }
+            Some(id)
+        }
+        _ => None,
+    };
+
+    let Some(id) = parked_id else {
+        let mut history = history_state.history.lock().unwrap();
+        let mut code = editor_state.current_code.lock().unwrap();
+        let mut diagnostics = editor_state.diagnostics.lock().unwrap();
+        return f(DocumentBuffers {
+            code: &mut code,
+            diagnostics: &mut diagnostics,
+            history: &mut history,
+            is_active: true,
+        });
+    };
+    let parked = inner
+        .parked
+        .get_mut(id)
+        .ok_or_else(|| format!("Document is not parked: {id}"))?;
+    f(DocumentBuffers {
+        code: &mut parked.code,
+        diagnostics: &mut parked.diagnostics,
+        history: &mut parked.history,
+        is_active: false,
+    })
+}
+
+/// Whether `document_id` is absent or names the active document
+pub(crate) fn is_active_document(app: &AppHandle, document_id: Option<&str>) -> bool {
+    document_id.is_none_or(|id| {
+        id == app
+            .state::<DocumentsState>()
+            .inner
+            .lock()
+            .unwrap()
+            .active_id
+    })
+}
+
+/// Move the active document out of the editor/history state into the parked map
+fn park_active(
+    inner: &mut DocumentsInner,
+    editor_state: &EditorState,
+    history_state: &HistoryState,
+) {
+    let history = std::mem::replace(
+        &mut *history_state.history.lock().unwrap(),
+        EditorHistory::new(),
+    );
+    let code = std::mem::take(&mut *editor_state.current_code.lock().unwrap());
+    let diagnostics = std::mem::take(&mut *editor_state.diagnostics.lock().unwrap());
+
+    inner.parked.insert(
+        inner.active_id.clone(),
+        ParkedDocument {
+            code,
+            diagnostics,
+            history,
+        },
+    );
+}
+
+/// Make a saved project file the project's active file when its document is
+/// activated, so the project buffer follows the editor. The buffer is loaded
+/// from disk first if the file wasn't open in the project yet.
+fn follow_in_project(meta: &DocumentMeta, code: &str, editor_state: &EditorState) {
+    let mut guard = editor_state.project.lock().unwrap();
+    let (Some(project), Some(path)) = (guard.as_mut(), meta.path.as_deref()) else {
+        return;
+    };
+    let Some(relative) = project.relative_path(Path::new(path)) else {
+        return;
+    };
+    if project.buffer(&relative).is_none() {
+        let saved = read_text_file(path.to_string())
+            .map(|file| file.content)
+            .unwrap_or_else(|_| code.to_string());
+        project.insert_loaded(&relative, saved);
+    }
+    project.update(&relative, code.to_string());
+    project.active_file = relative;
+}
+
+/// Load a parked document into the editor/history state and mark it active
+fn activate(
+    inner: &mut DocumentsInner,
+    id: &str,
+    editor_state: &EditorState,
+    history_state: &HistoryState,
+) -> Result<DocumentContents, String> {
+    let meta = inner
+        .meta(id)
+        .cloned()
+        .ok_or_else(|| format!("Document not found: {id}"))?;
+    let parked = inner
+        .parked
+        .remove(id)
+        .ok_or_else(|| format!("Document is not parked: {id}"))?;
+
+    *history_state.history.lock().unwrap() = parked.history;
+    *editor_state.current_code.lock().unwrap() = parked.code.clone();
+    *editor_state.diagnostics.lock().unwrap() = parked.diagnostics.clone();
+    inner.active_id = meta.id.clone();
+    follow_in_project(&meta, &parked.code, editor_state);
+
+    Ok(DocumentContents {
+        document: inner.info(&meta),
+        code: parked.code,
+        diagnostics: parked.diagnostics,
+    })
+}
+
+fn active_contents(inner: &DocumentsInner, editor_state: &EditorState) -> DocumentContents {
+    let meta = inner
+        .meta(&inner.active_id)
+        .cloned()
+        .unwrap_or_else(untitled_document);
+    DocumentContents {
+        document: inner.info(&meta),
+        code: editor_state.current_code.lock().unwrap().clone(),
+        diagnostics: editor_state.diagnostics.lock().unwrap().clone(),
+    }
+}
+
+fn emit_documents_changed(app: &AppHandle, inner: &DocumentsInner) {
+    let _ = app.emit("documents:changed", inner.list());
+}
+
+/// List open documents in tab order
+#[tauri::command]
+pub fn list_documents(documents: State<'_, DocumentsState>) -> Result<Vec<DocumentInfo>, String> {
+    Ok(documents.inner.lock().unwrap().list())
+}
+
+/// Open a document as a new tab (or focus it if the path is already open)
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn open_document(
+    app: AppHandle,
+    code: String,
+    path: Option<String>,
+    title: Option<String>,
+    activate_document: Option<bool>,
+    documents: State<'_, DocumentsState>,
+    editor_state: State<'_, EditorState>,
+    history_state: State<'_, HistoryState>,
+) -> Result<DocumentContents, String> {
+    let mut inner = documents.inner.lock().unwrap();
+
+    if let Some(existing) = path
+        .as_ref()
+        .and_then(|path| {
+            inner
+                .documents
+                .iter()
+                .find(|doc| doc.path.as_ref() == Some(path))
+        })
+        .map(|doc| doc.id.clone())
+    {
+        if existing == inner.active_id {
+            return Ok(active_contents(&inner, &editor_state));
+        }
+        park_active(&mut inner, &editor_state, &history_state);
+        let contents = activate(&mut inner, &existing, &editor_state, &history_state)?;
+        emit_documents_changed(&app, &inner);
+        return Ok(contents);
+    }
+
+    let meta = DocumentMeta {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: title
+            .or_else(|| path.as_deref().map(title_for_path))
+            .unwrap_or_else(|| "Untitled".to_string()),
+        path,
+    };
+
+    let mut history = EditorHistory::new();
+    history.create_checkpoint(
+        code.clone(),
+        Vec::new(),
+        format!("Opened {}", meta.title),
+        ChangeType::FileLoad,
+    );
+    inner.parked.insert(
+        meta.id.clone(),
+        ParkedDocument {
+            code: code.clone(),
+            diagnostics: Vec::new(),
+            history,
+        },
+    );
+    inner.documents.push(meta.clone());
+
+    let contents = if activate_document.unwrap_or(true) {
+        park_active(&mut inner, &editor_state, &history_state);
+        activate(&mut inner, &meta.id, &editor_state, &history_state)?
+    } else {
+        DocumentContents {
+            document: inner.info(&meta),
+            code,
+            diagnostics: Vec::new(),
+        }
+    };
+
+    emit_documents_changed(&app, &inner);
+    Ok(contents)
+}
+
+/// Make another open document the active one
+#[tauri::command]
+pub fn switch_document(
+    app: AppHandle,
+    document_id: String,
+    documents: State<'_, DocumentsState>,
+    editor_state: State<'_, EditorState>,
+    history_state: State<'_, HistoryState>,
+) -> Result<DocumentContents, String> {
+    let mut inner = documents.inner.lock().unwrap();
+    if document_id == inner.active_id {
+        return Ok(active_contents(&inner, &editor_state));
+    }
+    if inner.meta(&document_id).is_none() {
+        return Err(format!("Document not found: {document_id}"));
+    }
+
+    park_active(&mut inner, &editor_state, &history_state);
+    let contents = activate(&mut inner, &document_id, &editor_state, &history_state)?;
+    emit_documents_changed(&app, &inner);
+    Ok(contents)
+}
+
+/// Close a document, returning the document that is active afterwards
+#[tauri::command]
+pub fn close_document(
+    app: AppHandle,
+    document_id: String,
+    documents: State<'_, DocumentsState>,
+    editor_state: State<'_, EditorState>,
+    history_state: State<'_, HistoryState>,
+) -> Result<DocumentContents, String> {
+    let mut inner = documents.inner.lock().unwrap();
+    let index = inner
+        .documents
+        .iter()
+        .position(|doc| doc.id == document_id)
+        .ok_or_else(|| format!("Document not found: {document_id}"))?;
+
+    inner.documents.remove(index);
+    inner.parked.remove(&document_id);
+    inner.render_caches.remove(&document_id);
+    inner.large_files.remove(&document_id);
+
+    if document_id != inner.active_id {
+        emit_documents_changed(&app, &inner);
+        return Ok(active_contents(&inner, &editor_state));
+    }
+
+    // Closing the active tab: fall back to its neighbour, or a fresh buffer.
+    let contents = match inner
+        .documents
+        .get(index)
+        .or_else(|| inner.documents.last())
+        .map(|doc| doc.id.clone())
+    {
+        Some(next_id) => activate(&mut inner, &next_id, &editor_state, &history_state)?,
+        None => {
+            let fresh = untitled_document();
+            let defaults = EditorState::default();
+            inner.parked.insert(
+                fresh.id.clone(),
+                ParkedDocument {
+                    code: defaults.current_code.into_inner().unwrap(),
+                    diagnostics: Vec::new(),
+                    history: EditorHistory::new(),
+                },
+            );
+            inner.documents.push(fresh.clone());
+            activate(&mut inner, &fresh.id, &editor_state, &history_state)?
+        }
+    };
+
+    emit_documents_changed(&app, &inner);
+    Ok(contents)
+}
+
+/// Associate a document with a file path (e.g. after Save As)
+#[tauri::command]
+pub fn set_document_path(
+    app: AppHandle,
+    document_id: String,
+    path: Option<String>,
+    documents: State<'_, DocumentsState>,
+    editor_state: State<'_, EditorState>,
+) -> Result<DocumentInfo, String> {
+    let mut inner = documents.inner.lock().unwrap();
+    let meta = inner
+        .documents
+        .iter_mut()
+        .find(|doc| doc.id == document_id)
+        .ok_or_else(|| format!("Document not found: {document_id}"))?;
+    meta.title = path
+        .as_deref()
+        .map(title_for_path)
+        .unwrap_or_else(|| "Untitled".to_string());
+    meta.path = path;
+    let meta = meta.clone();
+    if meta.id == inner.active_id {
+        let code = editor_state.current_code.lock().unwrap().clone();
+        follow_in_project(&meta, &code, &editor_state);
+    }
+
+    let info = inner.info(&meta);
+    emit_documents_changed(&app, &inner);
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Project;
+
+    /// Default editor and history states, plus a parked background tab
+    fn with_background_tab() -> (DocumentsState, EditorState, HistoryState, String) {
+        let documents = DocumentsState::default();
+        let background = untitled_document();
+        let id = background.id.clone();
+        {
+            let mut inner = documents.inner.lock().unwrap();
+            inner.documents.push(background);
+            inner.parked.insert(
+                id.clone(),
+                ParkedDocument {
+                    code: "sphere(5);".to_string(),
+                    diagnostics: Vec::new(),
+                    history: EditorHistory::new(),
+                },
+            );
+        }
+        (documents, EditorState::default(), HistoryState::new(), id)
+    }
+
+    #[test]
+    fn background_tabs_are_edited_in_place() {
+        let (documents, editor, history, id) = with_background_tab();
+        let active_code = editor.current_code.lock().unwrap().clone();
+
+        with_document_in(&documents, &editor, &history, Some(&id), |document| {
+            assert!(!document.is_active);
+            *document.code = "sphere(10);".to_string();
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            documents.inner.lock().unwrap().parked[&id].code,
+            "sphere(10);"
+        );
+        assert_eq!(*editor.current_code.lock().unwrap(), active_code);
+    }
+
+    #[test]
+    fn background_tabs_undo_their_own_history() {
+        let (documents, editor, history, id) = with_background_tab();
+        let active_code = editor.current_code.lock().unwrap().clone();
+        history.history.lock().unwrap().create_checkpoint(
+            active_code.clone(),
+            Vec::new(),
+            "Active".to_string(),
+            ChangeType::User,
+        );
+        for code in ["sphere(5);", "sphere(10);"] {
+            with_document_in(&documents, &editor, &history, Some(&id), |document| {
+                *document.code = code.to_string();
+                document.history.create_checkpoint(
+                    code.to_string(),
+                    Vec::new(),
+                    "Edit".to_string(),
+                    ChangeType::Ai,
+                );
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        let restored = with_document_in(&documents, &editor, &history, Some(&id), |document| {
+            let checkpoint = document.history.undo().cloned().ok_or("Nothing to undo")?;
+            *document.code = checkpoint.code.clone();
+            Ok(checkpoint.code)
+        })
+        .unwrap();
+
+        assert_eq!(restored, "sphere(5);");
+        assert_eq!(
+            documents.inner.lock().unwrap().parked[&id].code,
+            "sphere(5);"
+        );
+        assert_eq!(*editor.current_code.lock().unwrap(), active_code);
+        let history = history.history.lock().unwrap();
+        assert_eq!(history.checkpoints().len(), 1);
+        assert_eq!(history.get_current().unwrap().code, active_code);
+    }
+
+    #[test]
+    fn activating_a_project_file_makes_it_the_active_file() {
+        let editor = EditorState::default();
+        let mut project = Project::new(
+            "/tmp/project".into(),
+            vec!["main.scad".into(), "parts/gear.scad".into()],
+            "main.scad".into(),
+        );
+        project.insert_loaded("main.scad", "gear();".into());
+        *editor.project.lock().unwrap() = Some(project);
+
+        let mut meta = untitled_document();
+        follow_in_project(&meta, "cube(1);", &editor);
+        meta.path = Some("/tmp/elsewhere/gear.scad".into());
+        follow_in_project(&meta, "cube(1);", &editor);
+        assert_eq!(
+            editor.project.lock().unwrap().as_ref().unwrap().active_file,
+            "main.scad"
+        );
+
+        meta.path = Some("/tmp/project/parts/gear.scad".into());
+        follow_in_project(&meta, "module gear() cube(2);", &editor);
+        let guard = editor.project.lock().unwrap();
+        let project = guard.as_ref().unwrap();
+        assert_eq!(project.active_file, "parts/gear.scad");
+        assert_eq!(
+            project.buffer("parts/gear.scad").unwrap().content,
+            "module gear() cube(2);"
+        );
+    }
+
+    #[test]
+    fn unknown_documents_are_an_error() {
+        let (documents, editor, history, _) = with_background_tab();
+        let result = with_document_in(&documents, &editor, &history, Some("missing"), |_| Ok(()));
+        assert_eq!(result, Err("Document not found: missing".to_string()));
+    }
+}
@@ -0,0 +1,50 @@
+use crate::export_presets::{validate_presets, ExportPreset};
+use crate::settings::{update_settings, ExportPresetSettings, SettingsState};
+use tauri::{AppHandle, Manager, State};
+
+/// The preset called `name`, or the active one when `name` is `None`
+pub(crate) fn resolve_export_preset(
+    app: &AppHandle,
+    name: Option<&str>,
+) -> Result<ExportPreset, String> {
+    let state = app.state::<SettingsState>();
+    let settings = state.settings.lock().unwrap();
+    let presets = &settings.export_presets;
+    let name = name.unwrap_or(&presets.active);
+    presets
+        .presets
+        .iter()
+        .find(|preset| preset.name == name)
+        .cloned()
+        .ok_or_else(|| format!("No export preset named '{name}'"))
+}
+
+#[tauri::command]
+pub fn get_export_presets(state: State<'_, SettingsState>) -> ExportPresetSettings {
+    state.settings.lock().unwrap().export_presets.clone()
+}
+
+/// Replace the preset list and the preset applied by default
+#[tauri::command]
+pub fn set_export_presets(
+    app: AppHandle,
+    presets: Vec<ExportPreset>,
+    active: String,
+) -> Result<(), String> {
+    validate_presets(&presets, &active)?;
+    update_settings(&app, |settings| {
+        settings.export_presets = ExportPresetSettings { presets, active };
+        Ok(())
+    })
+}
+
+/// Choose the preset applied to exports that don't name one
+#[tauri::command]
+pub fn set_active_export_preset(app: AppHandle, name: String) -> Result<(), String> {
+    update_settings(&app, |settings| {
+        let presets = &mut settings.export_presets;
+        validate_presets(&presets.presets, &name)?;
+        presets.active = name;
+        Ok(())
+    })
+}
@@ -0,0 +1,100 @@
+use crate::project_files::{
+    is_asset_file, is_parameter_file, is_project_file, parameter_set_names,
+};
+use crate::text_file::{decode, encode, TextFormat};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextFileContents {
+    /// LF-normalized UTF-8 text
+    pub content: String,
+    pub format: TextFormat,
+}
+
+/// Read a text file, detecting its encoding, BOM and line endings
+#[tauri::command]
+pub fn read_text_file(path: String) -> Result<TextFileContents, String> {
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    let (content, format) = decode(&bytes).map_err(|e| format!("{path}: {e}"))?;
+    Ok(TextFileContents { content, format })
+}
+
+/// Write a text file in the given format (defaults to UTF-8 with LF, or the
+/// existing file's format when it already exists)
+#[tauri::command]
+pub fn write_text_file(
+    path: String,
+    content: String,
+    format: Option<TextFormat>,
+) -> Result<TextFormat, String> {
+    let format = format.unwrap_or_else(|| {
+        fs::read(&path)
+            .ok()
+            .and_then(|bytes| decode(&bytes).ok())
+            .map(|(_, format)| format)
+            .unwrap_or_default()
+    });
+
+    let format = format.for_text(&content);
+
+    let target = Path::new(&path);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {e}"))?;
+    }
+    let temp_path = target.with_extension("openscad-studio.tmp");
+    fs::write(&temp_path, encode(&content, &format))
+        .map_err(|e| format!("Failed to write {path}: {e}"))?;
+    fs::rename(&temp_path, target).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Failed to replace {path}: {e}")
+    })?;
+    Ok(format)
+}
+
+fn collect_project_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read {dir:?}: {e}"))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Skips dotfiles, VCS folders and our own `.openscad-studio-*` render temps.
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            collect_project_files(root, &path, files)?;
+        } else if is_project_file(&name) || is_asset_file(&name) {
+            if let Ok(relative) = path.strip_prefix(root) {
+                files.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// List project files (.scad, .h, .json parameter files and .csv/.dat/.png
+/// data assets) under `root`, as sorted root-relative paths
+#[tauri::command]
+pub fn list_project_files(root: String) -> Result<Vec<String>, String> {
+    let root = PathBuf::from(root);
+    let mut files = Vec::new();
+    collect_project_files(&root, &root, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+/// Parameter set names in a customizer .json file. Pass `content` to read an
+/// unsaved buffer instead of the file on disk.
+#[tauri::command]
+pub fn list_parameter_sets(path: String, content: Option<String>) -> Result<Vec<String>, String> {
+    if !is_parameter_file(&path) {
+        return Err(format!("{path} is not a .json parameter file"));
+    }
+    let json = match content {
+        Some(content) => content,
+        None => read_text_file(path.clone())?.content,
+    };
+    parameter_set_names(&json).map_err(|e| format!("{path}: {e}"))
+}
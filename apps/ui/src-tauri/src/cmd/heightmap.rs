@@ -0,0 +1,89 @@
+use crate::cmd::EditorState;
+use crate::heightmap::{
+    load_grid, surface_snippet, to_dat, to_png, HeightmapFormat, HeightmapOptions,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeightmapImport {
+    /// Absolute path of the written .dat/.png
+    pub output_path: String,
+    /// Path used in the snippet (relative to the working directory when possible)
+    pub file_reference: String,
+    pub snippet: String,
+    pub columns: u32,
+    pub rows: u32,
+}
+
+fn output_path(image_path: &Path, target_dir: &Path, format: HeightmapFormat) -> PathBuf {
+    let stem = image_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("heightmap");
+    let extension = match format {
+        HeightmapFormat::Dat => "dat",
+        HeightmapFormat::Png => "png",
+    };
+    target_dir.join(format!("{stem}-heightmap.{extension}"))
+}
+
+/// Convert an image into a surface() heightmap next to the project, then
+/// ask the editor to insert the matching snippet (which triggers a preview)
+#[tauri::command]
+pub fn import_heightmap(
+    app: AppHandle,
+    image_path: String,
+    options: Option<HeightmapOptions>,
+    editor_state: State<'_, EditorState>,
+) -> Result<HeightmapImport, String> {
+    let options = options.unwrap_or_default();
+    let source = Path::new(&image_path);
+    let bytes = fs::read(source).map_err(|e| format!("Failed to read {image_path}: {e}"))?;
+    let grid = load_grid(&bytes, options.max_resolution)?;
+
+    let working_dir = editor_state.working_dir.lock().unwrap().clone();
+    let target_dir = match &working_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => source
+            .parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| format!("{image_path} has no parent directory"))?,
+    };
+    let output = output_path(source, &target_dir, options.format);
+    let contents = match options.format {
+        HeightmapFormat::Dat => to_dat(&grid, &options).into_bytes(),
+        HeightmapFormat::Png => to_png(&grid)?,
+    };
+    fs::write(&output, contents)
+        .map_err(|e| format!("Failed to write heightmap {}: {e}", output.display()))?;
+
+    let file_reference = match &working_dir {
+        Some(dir) => output
+            .strip_prefix(dir)
+            .unwrap_or(&output)
+            .to_string_lossy()
+            .replace('\\', "/"),
+        None => output.to_string_lossy().replace('\\', "/"),
+    };
+    let snippet = surface_snippet(&file_reference, &grid, &options);
+
+    eprintln!(
+        "[heightmap] Wrote {}x{} heightmap to {}",
+        grid.width,
+        grid.height,
+        output.display()
+    );
+    let _ = app.emit("editor:insert-snippet", &snippet);
+
+    Ok(HeightmapImport {
+        output_path: output.to_string_lossy().to_string(),
+        file_reference,
+        snippet,
+        columns: grid.width,
+        rows: grid.height,
+    })
+}
@@ -1,81 +1,353 @@
-use crate::cmd::EditorState;
-use crate::history::HistoryState;
-use crate::types::{ChangeType, CheckpointDiff, EditorCheckpoint};
+use crate::cmd::documents::with_document;
+use crate::cmd::mesh::render_geometry_stats;
+use crate::cmd::render::render_policy;
+use crate::cmd::{EditorState, OpenScadBinaryState};
+use crate::documents::DocumentsState;
+use crate::history::{
+    history_file_name, prune_checkpoints, restore_checkpoint, write_restored_file, EditorHistory,
+    HistoryState, PersistedHistory, HISTORY_FILE_VERSION,
+};
+use crate::safe_mode::{canonical_project_path, is_escaping_path};
+use crate::settings::{update_settings, HistorySettings, SettingsState};
+use crate::types::{ChangeType, CheckpointDiff, ConversationLink, EditorCheckpoint, GeometryStats};
 /**
  * History-related Tauri commands
  */
-use tauri::{AppHandle, Emitter, State};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, State};
 
-/// Create a checkpoint in the history
+const DEFAULT_TREND_LIMIT: usize = 20;
+const HISTORY_DIR_NAME: &str = "history";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GeometryTrendPoint {
+    pub checkpoint_id: String,
+    pub timestamp: i64,
+    pub description: String,
+    pub change_type: ChangeType,
+    pub geometry: Option<GeometryStats>,
+    /// Why geometry could not be computed for this checkpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Bounding box size change relative to the previous point with geometry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_delta: Option<[f64; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_delta: Option<f64>,
+}
+
+fn history_path(app: &AppHandle, project: &str) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(HISTORY_DIR_NAME).join(history_file_name(project)))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+fn history_settings(app: &AppHandle) -> HistorySettings {
+    app.state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .history
+        .clone()
+}
+
+/// Saved checkpoints of `project`, pruned; empty when there are none
+fn load_history(
+    app: &AppHandle,
+    project: &str,
+    settings: &HistorySettings,
+) -> Vec<EditorCheckpoint> {
+    let Ok(path) = history_path(app, project) else {
+        return Vec::new();
+    };
+    let Ok(json) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    match serde_json::from_str::<PersistedHistory>(&json) {
+        Ok(saved) if saved.version <= HISTORY_FILE_VERSION && saved.project == project => {
+            let mut checkpoints = saved.checkpoints;
+            prune_checkpoints(
+                &mut checkpoints,
+                settings,
+                chrono::Utc::now().timestamp_millis(),
+            );
+            checkpoints
+        }
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            eprintln!(
+                "[history] Ignoring unreadable history file {:?}: {}",
+                path, e
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Save the in-memory history under its project folder. Does nothing when
+/// persistence is off or no project folder is known.
+pub(crate) fn save_history(app: &AppHandle) -> Result<(), String> {
+    let settings = history_settings(app);
+    if !settings.persist {
+        return Ok(());
+    }
+    let state = app.state::<HistoryState>();
+    let (project, mut checkpoints) = {
+        let history = state.history.lock().unwrap();
+        let Some(project) = history.project.clone() else {
+            return Ok(());
+        };
+        (project, history.checkpoints())
+    };
+    prune_checkpoints(
+        &mut checkpoints,
+        &settings,
+        chrono::Utc::now().timestamp_millis(),
+    );
+
+    let path = history_path(app, &project)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create history directory: {e}"))?;
+    }
+    let json = serde_json::to_string(&PersistedHistory {
+        version: HISTORY_FILE_VERSION,
+        project,
+        checkpoints,
+    })
+    .map_err(|e| format!("Failed to serialize history: {e}"))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).map_err(|e| format!("Failed to write history: {e}"))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to replace history file: {e}"))
+}
+
+/// Move the history to the project folder `working_dir`: the previous
+/// project's checkpoints are saved and cleared, and the new project's saved
+/// checkpoints are loaded in front of any made before a folder was known
+pub(crate) fn switch_history_project(app: &AppHandle, working_dir: Option<&str>) {
+    let next = working_dir.map(canonical_project_path);
+    let state = app.state::<HistoryState>();
+    let previous = state.history.lock().unwrap().project.clone();
+    if previous == next {
+        return;
+    }
+    if previous.is_some() {
+        if let Err(e) = save_history(app) {
+            eprintln!("[history] {e}");
+        }
+    }
+
+    let settings = history_settings(app);
+    let saved = match &next {
+        Some(project) if settings.persist => load_history(app, project, &settings),
+        _ => Vec::new(),
+    };
+    let mut history = state.history.lock().unwrap();
+    if previous.is_some() {
+        history.clear();
+    }
+    history.prepend(saved);
+    history.project = next;
+}
+
+/// Path of a file in the open project, for a checkpoint of that file
+fn project_file_path(app: &AppHandle, file_path: &str) -> Result<PathBuf, String> {
+    if is_escaping_path(file_path) {
+        return Err(format!("{file_path} is outside the project"));
+    }
+    let editor_state = app.state::<EditorState>();
+    let project = editor_state.project.lock().unwrap();
+    let project = project
+        .as_ref()
+        .ok_or_else(|| format!("No project is open to checkpoint {file_path}"))?;
+    Ok(Path::new(&project.root).join(file_path))
+}
+
+/// Create a checkpoint in the history of `document_id` (the active document
+/// when omitted), linked to the chat message it is made for, if any. With
+/// `file_path` (project-relative) it holds that file's content instead of
+/// the document's, and restoring it writes the file back.
 #[tauri::command]
 pub fn create_checkpoint(
+    app: AppHandle,
     code: String,
     description: String,
     change_type: ChangeType,
-    editor_state: State<'_, EditorState>,
-    history_state: State<'_, HistoryState>,
+    document_id: Option<String>,
+    conversation: Option<ConversationLink>,
+    file_path: Option<String>,
 ) -> Result<String, String> {
-    let diagnostics = editor_state.diagnostics.lock().unwrap().clone();
-
-    let mut history = history_state.history.lock().unwrap();
-    let id = history.create_checkpoint(code, diagnostics, description, change_type);
+    let file_path = file_path
+        .map(|path| project_file_path(&app, &path))
+        .transpose()?;
+    let (id, is_active) = with_document(&app, document_id.as_deref(), |document| {
+        let id = match &file_path {
+            Some(path) => document
+                .history
+                .create_file_checkpoint(path, Some(code), description),
+            None => {
+                let diagnostics = document.diagnostics.clone();
+                document
+                    .history
+                    .create_checkpoint(code, diagnostics, description, change_type)
+            }
+        };
+        if let Some(link) = conversation {
+            document.history.link_conversation(&id, link);
+        }
+        Ok((id, document.is_active))
+    })?;
+    if is_active {
+        if let Err(e) = save_history(&app) {
+            eprintln!("[history] {e}");
+        }
+    }
 
     Ok(id)
 }
 
-/// Undo to previous checkpoint
 #[tauri::command]
-pub fn undo(
-    app: AppHandle,
-    history_state: State<'_, HistoryState>,
-    editor_state: State<'_, EditorState>,
-) -> Result<EditorCheckpoint, String> {
-    let mut history = history_state.history.lock().unwrap();
-
-    if let Some(checkpoint) = history.undo() {
-        // Update editor state
-        *editor_state.current_code.lock().unwrap() = checkpoint.code.clone();
-        *editor_state.diagnostics.lock().unwrap() = checkpoint.diagnostics.clone();
+pub fn get_history_settings(state: State<'_, SettingsState>) -> HistorySettings {
+    state.settings.lock().unwrap().history.clone()
+}
 
-        // Emit event to frontend to update editor
-        let _ = app.emit("history:restore", checkpoint.clone());
+/// Update history persistence and pruning, re-saving the current history
+/// under the new limits
+#[tauri::command]
+pub fn set_history_settings(app: AppHandle, settings: HistorySettings) -> Result<(), String> {
+    update_settings(&app, |current| {
+        current.history = settings;
+        Ok(())
+    })?;
+    save_history(&app)
+}
 
-        Ok(checkpoint.clone())
-    } else {
-        Err("Cannot undo: no more history".to_string())
-    }
+/// A project file put back by restoring one of its checkpoints
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileRestored {
+    path: String,
+    code: String,
+    /// The file didn't exist at the checkpoint, so it was removed
+    removed: bool,
 }
 
-/// Redo to next checkpoint
-#[tauri::command]
-pub fn redo(
-    app: AppHandle,
-    history_state: State<'_, HistoryState>,
-    editor_state: State<'_, EditorState>,
+/// Move a document's history with `step` and load the checkpoint it lands
+/// on into the document, or into its own file for a checkpoint of another
+/// project file. The editor is only told when the document is active.
+fn step_history(
+    app: &AppHandle,
+    document_id: Option<&str>,
+    step: impl FnOnce(&mut EditorHistory) -> Option<EditorCheckpoint>,
+    unavailable: String,
 ) -> Result<EditorCheckpoint, String> {
-    let mut history = history_state.history.lock().unwrap();
+    let (checkpoint, is_active, restored_file) = with_document(app, document_id, |document| {
+        let checkpoint = step(document.history).ok_or(unavailable)?;
+        let restored_file = restore_checkpoint(&checkpoint, document.code, document.diagnostics)?;
+        Ok((checkpoint, document.is_active, restored_file))
+    })?;
 
-    if let Some(checkpoint) = history.redo() {
-        // Update editor state
-        *editor_state.current_code.lock().unwrap() = checkpoint.code.clone();
-        *editor_state.diagnostics.lock().unwrap() = checkpoint.diagnostics.clone();
+    if let Some(path) = restored_file {
+        let editor_state = app.state::<EditorState>();
+        if let Some(project) = editor_state.project.lock().unwrap().as_mut() {
+            project.mark_written(&path, &checkpoint.code);
+        }
+        let _ = app.emit(
+            "history:file-restored",
+            FileRestored {
+                path: path.to_string_lossy().into_owned(),
+                code: checkpoint.code.clone(),
+                removed: checkpoint.file_absent,
+            },
+        );
+        return Ok(checkpoint);
+    }
 
-        // Emit event to frontend to update editor
+    if history_settings(app).write_to_disk {
+        write_restored_document(app, document_id, &checkpoint.code);
+    }
+
+    // Emit event to frontend to update editor
+    if is_active {
         let _ = app.emit("history:restore", checkpoint.clone());
+    }
+    Ok(checkpoint)
+}
+
+/// A restore that couldn't be written to the document's file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RestoreWriteFailed {
+    path: String,
+    error: String,
+}
 
-        Ok(checkpoint.clone())
-    } else {
-        Err("Cannot redo: already at latest".to_string())
+/// Write restored code to the document's file, if it has one. A failed
+/// write doesn't fail the restore, which already happened; it is reported
+/// as `history:write-failed` so the user knows the file and editor differ.
+fn write_restored_document(app: &AppHandle, document_id: Option<&str>, code: &str) {
+    let path = {
+        let documents = app.state::<DocumentsState>();
+        let inner = documents.inner.lock().unwrap();
+        let id = document_id.unwrap_or(&inner.active_id);
+        inner.meta(id).and_then(|meta| meta.path.clone())
+    };
+    let Some(path) = path else {
+        return;
+    };
+    match write_restored_file(Path::new(&path), code) {
+        Ok(backup) => {
+            if let Some(backup) = backup {
+                eprintln!("[history] Wrote {path} (previous contents in {backup:?})");
+            }
+            // The file now holds the restored code; an open project buffer
+            // must agree, or the watcher reports our own write as a conflict
+            let editor_state = app.state::<EditorState>();
+            if let Some(project) = editor_state.project.lock().unwrap().as_mut() {
+                project.mark_written(Path::new(&path), code);
+            }
+        }
+        Err(error) => {
+            eprintln!("[history] {error}");
+            let _ = app.emit("history:write-failed", RestoreWriteFailed { path, error });
+        }
     }
 }
 
+/// Undo to previous checkpoint
+#[tauri::command]
+pub fn undo(app: AppHandle, document_id: Option<String>) -> Result<EditorCheckpoint, String> {
+    step_history(
+        &app,
+        document_id.as_deref(),
+        |history| history.undo().cloned(),
+        "Cannot undo: no more history".to_string(),
+    )
+}
+
+/// Redo to next checkpoint
+#[tauri::command]
+pub fn redo(app: AppHandle, document_id: Option<String>) -> Result<EditorCheckpoint, String> {
+    step_history(
+        &app,
+        document_id.as_deref(),
+        |history| history.redo().cloned(),
+        "Cannot redo: already at latest".to_string(),
+    )
+}
+
 /// Get all history checkpoints
 #[tauri::command]
 pub fn get_history(
-    history_state: State<'_, HistoryState>,
+    app: AppHandle,
+    document_id: Option<String>,
 ) -> Result<Vec<EditorCheckpoint>, String> {
-    let history = history_state.history.lock().unwrap();
-    Ok(history.get_all())
+    with_document(&app, document_id.as_deref(), |document| {
+        Ok(document.history.get_all())
+    })
 }
 
 /// Restore to a specific checkpoint
@@ -83,64 +355,176 @@ pub fn get_history(
 pub fn restore_to_checkpoint(
     app: AppHandle,
     checkpoint_id: String,
-    history_state: State<'_, HistoryState>,
-    editor_state: State<'_, EditorState>,
+    document_id: Option<String>,
 ) -> Result<EditorCheckpoint, String> {
-    let mut history = history_state.history.lock().unwrap();
-
-    if let Some(checkpoint) = history.restore_to(&checkpoint_id) {
-        // Update editor state
-        *editor_state.current_code.lock().unwrap() = checkpoint.code.clone();
-        *editor_state.diagnostics.lock().unwrap() = checkpoint.diagnostics.clone();
+    step_history(
+        &app,
+        document_id.as_deref(),
+        |history| history.restore_to(&checkpoint_id).cloned(),
+        format!("Checkpoint not found: {checkpoint_id}"),
+    )
+}
 
-        // Emit event to frontend to update editor
-        let _ = app.emit("history:restore", checkpoint.clone());
+/// Checkpoints made for an AI conversation's messages, in chat order
+#[tauri::command]
+pub fn get_checkpoints_for_conversation(
+    app: AppHandle,
+    conversation_id: String,
+    document_id: Option<String>,
+) -> Result<Vec<EditorCheckpoint>, String> {
+    with_document(&app, document_id.as_deref(), |document| {
+        Ok(document.history.for_conversation(&conversation_id))
+    })
+}
 
-        Ok(checkpoint.clone())
-    } else {
-        Err(format!("Checkpoint not found: {checkpoint_id}"))
-    }
+/// Revert the code to how it was when a chat message was sent, before the
+/// edits of its request
+#[tauri::command]
+pub fn restore_conversation_state(
+    app: AppHandle,
+    message_id: String,
+    document_id: Option<String>,
+) -> Result<EditorCheckpoint, String> {
+    step_history(
+        &app,
+        document_id.as_deref(),
+        |history| history.restore_to_message(&message_id).cloned(),
+        format!("No checkpoint for message: {message_id}"),
+    )
 }
 
 /// Get diff between two checkpoints
 #[tauri::command]
 pub fn get_checkpoint_diff(
+    app: AppHandle,
     from_id: String,
     to_id: String,
-    history_state: State<'_, HistoryState>,
+    document_id: Option<String>,
 ) -> Result<CheckpointDiff, String> {
-    let history = history_state.history.lock().unwrap();
-
-    history
-        .get_diff(&from_id, &to_id)
-        .ok_or_else(|| "Failed to generate diff".to_string())
+    with_document(&app, document_id.as_deref(), |document| {
+        document
+            .history
+            .get_diff(&from_id, &to_id)
+            .ok_or_else(|| "Failed to generate diff".to_string())
+    })
 }
 
 /// Check if undo is available
 #[tauri::command]
-pub fn can_undo(history_state: State<'_, HistoryState>) -> Result<bool, String> {
-    let history = history_state.history.lock().unwrap();
-    Ok(history.can_undo())
+pub fn can_undo(app: AppHandle, document_id: Option<String>) -> Result<bool, String> {
+    with_document(&app, document_id.as_deref(), |document| {
+        Ok(document.history.can_undo())
+    })
 }
 
 /// Check if redo is available
 #[tauri::command]
-pub fn can_redo(history_state: State<'_, HistoryState>) -> Result<bool, String> {
-    let history = history_state.history.lock().unwrap();
-    Ok(history.can_redo())
+pub fn can_redo(app: AppHandle, document_id: Option<String>) -> Result<bool, String> {
+    with_document(&app, document_id.as_deref(), |document| {
+        Ok(document.history.can_redo())
+    })
 }
 
 /// Get a specific checkpoint by ID
 #[tauri::command]
 pub fn get_checkpoint_by_id(
+    app: AppHandle,
     checkpoint_id: String,
-    history_state: State<'_, HistoryState>,
+    document_id: Option<String>,
 ) -> Result<EditorCheckpoint, String> {
-    let history = history_state.history.lock().unwrap();
-    let checkpoints = history.get_all();
+    with_document(&app, document_id.as_deref(), |document| {
+        document
+            .history
+            .get_by_id(&checkpoint_id)
+            .cloned()
+            .ok_or_else(|| format!("Checkpoint not found: {checkpoint_id}"))
+    })
+}
+
+/// Geometry statistics across the most recent checkpoints (oldest first).
+/// Missing statistics are computed by rendering the checkpoint and cached on it.
+#[tauri::command]
+pub async fn get_geometry_trend(
+    app: AppHandle,
+    limit: Option<usize>,
+    history_state: State<'_, HistoryState>,
+    editor_state: State<'_, EditorState>,
+    binary_state: State<'_, OpenScadBinaryState>,
+) -> Result<Vec<GeometryTrendPoint>, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let working_dir = editor_state.working_dir.lock().unwrap().clone();
+
+    let checkpoints = {
+        let history = history_state.history.lock().unwrap();
+        let mut all = history.get_all();
+        all.retain(|checkpoint| checkpoint.file_path.is_none());
+        let skip = all
+            .len()
+            .saturating_sub(limit.unwrap_or(DEFAULT_TREND_LIMIT));
+        all.into_iter().skip(skip).collect::<Vec<_>>()
+    };
+
+    // Render outside the history lock; checkpoints may be pruned meanwhile.
+    let mut points = Vec::with_capacity(checkpoints.len());
+    for checkpoint in checkpoints {
+        let (geometry, error) = match checkpoint.geometry {
+            Some(geometry) => (Some(geometry), None),
+            None => {
+                let path = binary_path.clone();
+                let code = checkpoint.code.clone();
+                let result = match render_policy(&app, &code, &None, &working_dir, &None) {
+                    Ok(policy) => tauri::async_runtime::spawn_blocking(move || {
+                        render_geometry_stats(&path, &code, &policy)
+                    })
+                    .await
+                    .map_err(|e| format!("Geometry task failed: {e}"))?,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(stats) => {
+                        history_state
+                            .history
+                            .lock()
+                            .unwrap()
+                            .set_geometry(&checkpoint.id, stats.clone());
+                        (Some(stats), None)
+                    }
+                    Err(e) => (None, Some(e)),
+                }
+            }
+        };
+
+        points.push(GeometryTrendPoint {
+            checkpoint_id: checkpoint.id,
+            timestamp: checkpoint.timestamp,
+            description: checkpoint.description,
+            change_type: checkpoint.change_type,
+            geometry,
+            error,
+            size_delta: None,
+            volume_delta: None,
+        });
+    }
+
+    let mut previous: Option<GeometryStats> = None;
+    for point in &mut points {
+        if let Some(current) = &point.geometry {
+            if let Some(prev) = &previous {
+                point.size_delta = Some([
+                    current.size[0] - prev.size[0],
+                    current.size[1] - prev.size[1],
+                    current.size[2] - prev.size[2],
+                ]);
+                point.volume_delta = Some(current.volume - prev.volume);
+            }
+            previous = Some(current.clone());
+        }
+    }
 
-    checkpoints
-        .into_iter()
-        .find(|c| c.id == checkpoint_id)
-        .ok_or_else(|| format!("Checkpoint not found: {checkpoint_id}"))
+    Ok(points)
 }
@@ -0,0 +1,130 @@
+use crate::cmd::project_archive::free_dir;
+use crate::create_new_window_with_launch_intent;
+use crate::mcp::WindowLaunchIntent;
+use crate::project_archive::{main_file, unpack_matching, MAX_ARCHIVE_ENTRIES};
+use crate::thing_import::{
+    is_thing_file, parse_thing_url, printables_download_link, printables_download_request,
+    thingiverse_zip_url, Thing, ThingSite, MAX_THING_DOWNLOAD_BYTES, PRINTABLES_GRAPHQL_URL,
+};
+use serde::Serialize;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteImport {
+    pub project_dir: String,
+    pub main_file: String,
+    /// Project-relative paths of the extracted files
+    pub files: Vec<String>,
+    pub window_id: String,
+}
+
+fn download_zip(client: &reqwest::blocking::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Failed to download {url}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("{url} returned {}", response.status()));
+    }
+    let mut bytes = Vec::new();
+    response
+        .take(MAX_THING_DOWNLOAD_BYTES as u64 + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {url}: {e}"))?;
+    if bytes.len() > MAX_THING_DOWNLOAD_BYTES {
+        return Err(format!(
+            "{url} is larger than {} MB",
+            MAX_THING_DOWNLOAD_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(bytes)
+}
+
+fn archive_url(client: &reqwest::blocking::Client, thing: &Thing) -> Result<String, String> {
+    match thing.site {
+        ThingSite::Thingiverse => Ok(thingiverse_zip_url(&thing.id)),
+        ThingSite::Printables => {
+            let body = client
+                .post(PRINTABLES_GRAPHQL_URL)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(printables_download_request(&thing.id).to_string())
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.text())
+                .map_err(|e| format!("Failed to ask Printables for a download link: {e}"))?;
+            let response = serde_json::from_str(&body)
+                .map_err(|e| format!("Unexpected response from Printables: {e}"))?;
+            printables_download_link(&response)
+        }
+    }
+}
+
+/// Download a Thingiverse or Printables thing into a new folder under
+/// `parent_dir` and open its main .scad file in a new window, which sets the
+/// working directory and renders it
+#[tauri::command]
+pub async fn import_remote_project(
+    app: AppHandle,
+    url: String,
+    parent_dir: String,
+) -> Result<RemoteImport, String> {
+    tauri::async_runtime::spawn_blocking(move || import_thing(&app, &url, Path::new(&parent_dir)))
+        .await
+        .map_err(|e| format!("Thing import task failed: {e}"))?
+}
+
+fn import_thing(app: &AppHandle, url: &str, parent_dir: &Path) -> Result<RemoteImport, String> {
+    let thing = parse_thing_url(url)?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .user_agent(concat!("OpenSCAD-Studio/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let bytes = download_zip(&client, &archive_url(&client, &thing)?)?;
+
+    let folder_name = thing.folder_name();
+    let project_dir = free_dir(parent_dir, &folder_name)?;
+    let unpacked = unpack_matching(
+        Cursor::new(bytes),
+        &project_dir,
+        MAX_ARCHIVE_ENTRIES,
+        is_thing_file,
+    )
+    .and_then(|files| {
+        let main = main_file(&files, &folder_name)
+            .ok_or_else(|| format!("{url} has no .scad file to open"))?;
+        Ok((files, main))
+    })
+    .inspect_err(|_| {
+        let _ = fs::remove_dir_all(&project_dir);
+    });
+    let (files, main) = unpacked?;
+
+    let window_id = create_new_window_with_launch_intent(
+        app,
+        WindowLaunchIntent::OpenFile {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            file_path: project_dir.join(&main).to_string_lossy().to_string(),
+        },
+    )
+    .map_err(|e| format!("Failed to open a window for the imported project: {e}"))?;
+
+    eprintln!(
+        "[import-remote] Extracted {} file(s) from {url} into {}",
+        files.len(),
+        project_dir.display()
+    );
+    Ok(RemoteImport {
+        project_dir: project_dir.to_string_lossy().to_string(),
+        main_file: main,
+        files,
+        window_id,
+    })
+}
@@ -0,0 +1,510 @@
+use crate::cmd::render::{
+    bundled_binary_path, get_binary_version, init_binary, OpenScadBinaryState,
+};
+use crate::locate::system_binary_path;
+use crate::openscad_installer::{
+    checksum_url, find_binary, install_id, is_valid_install_id, latest_snapshot_asset,
+    list_installs, parse_sha256, read_manifest, stable_asset, write_manifest, InstallManifest,
+    PackageFormat, Platform, ReleaseAsset, ReleaseChannel, INSTALLS_DIR, SNAPSHOTS_URL,
+};
+use crate::settings::{update_settings, SettingsState};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+const MAX_PACKAGE_BYTES: u64 = 1024 * 1024 * 1024;
+const PROGRESS_STEP_BYTES: u64 = 1024 * 1024;
+const BUNDLED_ID: &str = "bundled";
+const SYSTEM_ID: &str = "system";
+
+/// Held for the whole of an install, so two never share a staging folder
+static INSTALL_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BuildSource {
+    Bundled,
+    System,
+    Downloaded,
+}
+
+/// An OpenSCAD binary renders can use
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenScadBuild {
+    /// `bundled`, `system`, or a downloaded build's install ID
+    pub id: String,
+    pub source: BuildSource,
+    pub channel: Option<ReleaseChannel>,
+    pub version: String,
+    pub path: String,
+    /// Renders currently use this build
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenScadBuilds {
+    pub builds: Vec<OpenScadBuild>,
+    /// Builds can be downloaded for this platform
+    pub can_download: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InstallProgress {
+    channel: ReleaseChannel,
+    received_bytes: u64,
+    total_bytes: Option<u64>,
+}
+
+fn installs_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(INSTALLS_DIR))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+/// Binary of the build with `id`, if it's still there
+pub(crate) fn build_binary_path(app: &AppHandle, id: &str) -> Option<PathBuf> {
+    match id {
+        BUNDLED_ID => bundled_binary_path(app),
+        SYSTEM_ID => system_binary_path(),
+        id if is_valid_install_id(id) => {
+            let dir = installs_dir(app).ok()?;
+            let manifest = read_manifest(&dir.join(id))?;
+            Some(manifest.binary_path(&dir)).filter(|path| path.is_file())
+        }
+        _ => None,
+    }
+}
+
+fn active_setting(app: &AppHandle) -> Option<String> {
+    app.state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .openscad
+        .active
+        .clone()
+}
+
+/// Binary of the build picked in settings; `None` when none is picked or
+/// it has gone missing, so discovery falls back to the usual order
+pub(crate) fn active_binary_path(app: &AppHandle) -> Option<PathBuf> {
+    let path = build_binary_path(app, &active_setting(app)?);
+    if path.is_none() {
+        eprintln!("[installer] Active OpenSCAD build is missing, falling back");
+    }
+    path
+}
+
+/// Most recently downloaded build, used when no other binary is found
+pub(crate) fn downloaded_binary_path(app: &AppHandle) -> Option<PathBuf> {
+    let dir = installs_dir(app).ok()?;
+    let latest = list_installs(&dir).into_iter().next()?;
+    Some(latest.binary_path(&dir))
+}
+
+fn display_version(binary: &Path) -> String {
+    get_binary_version(binary)
+        .map(|version| version.trim_start_matches("OpenSCAD version ").to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn downloaded_build(dir: &Path, manifest: InstallManifest) -> OpenScadBuild {
+    OpenScadBuild {
+        path: manifest.binary_path(dir).to_string_lossy().into_owned(),
+        id: manifest.id,
+        source: BuildSource::Downloaded,
+        channel: Some(manifest.channel),
+        version: manifest.version,
+        active: false,
+    }
+}
+
+fn list_builds(app: &AppHandle) -> Result<OpenScadBuilds, String> {
+    let mut builds = Vec::new();
+    for (id, source, path) in [
+        (BUNDLED_ID, BuildSource::Bundled, bundled_binary_path(app)),
+        (SYSTEM_ID, BuildSource::System, system_binary_path()),
+    ] {
+        if let Some(path) = path {
+            builds.push(OpenScadBuild {
+                id: id.to_string(),
+                source,
+                channel: None,
+                version: display_version(&path),
+                path: path.to_string_lossy().into_owned(),
+                active: false,
+            });
+        }
+    }
+    let dir = installs_dir(app)?;
+    builds.extend(
+        list_installs(&dir)
+            .into_iter()
+            .map(|manifest| downloaded_build(&dir, manifest)),
+    );
+
+    // Same order render discovery uses: the picked build, else the first found
+    let active = active_setting(app).filter(|id| builds.iter().any(|build| &build.id == id));
+    let active_index = match active {
+        Some(id) => builds.iter().position(|build| build.id == id),
+        None => Some(0),
+    };
+    if let Some(build) = active_index.and_then(|index| builds.get_mut(index)) {
+        build.active = true;
+    }
+
+    Ok(OpenScadBuilds {
+        builds,
+        can_download: Platform::current().is_some(),
+    })
+}
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .user_agent(concat!("OpenSCAD-Studio/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))
+}
+
+fn release_asset(
+    client: &reqwest::blocking::Client,
+    channel: ReleaseChannel,
+    platform: Platform,
+) -> Result<ReleaseAsset, String> {
+    match channel {
+        ReleaseChannel::Stable => stable_asset(platform)
+            .ok_or_else(|| "No stable OpenSCAD release is published for this platform".into()),
+        ReleaseChannel::Nightly => {
+            let url = format!("{SNAPSHOTS_URL}/");
+            let listing = client
+                .get(&url)
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.text())
+                .map_err(|e| format!("Failed to list OpenSCAD snapshots: {e}"))?;
+            latest_snapshot_asset(&listing, platform)
+                .ok_or_else(|| "No OpenSCAD snapshot found for this platform".into())
+        }
+    }
+}
+
+/// The SHA-256 digest published for `asset`. Packages without one are not
+/// installed, since nothing could show they arrived intact.
+fn published_sha256(
+    client: &reqwest::blocking::Client,
+    asset: &ReleaseAsset,
+) -> Result<String, String> {
+    let url = checksum_url(asset);
+    let text = client
+        .get(&url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .map_err(|e| format!("Failed to fetch the checksum {url}: {e}"))?;
+    parse_sha256(&text).ok_or_else(|| format!("{url} doesn't hold a SHA-256 checksum"))
+}
+
+/// Download `asset` to `dest`, returning the package's SHA-256 digest
+fn download_package(
+    app: &AppHandle,
+    client: &reqwest::blocking::Client,
+    asset: &ReleaseAsset,
+    dest: &Path,
+) -> Result<String, String> {
+    let url = &asset.url;
+    let mut response = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Failed to download {url}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("{url} returned {}", response.status()));
+    }
+    let total_bytes = response.content_length();
+    let mut file = File::create(dest).map_err(|e| format!("Failed to create {dest:?}: {e}"))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    let mut received_bytes = 0;
+    let mut reported_bytes = 0;
+    loop {
+        let read = response
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {url}: {e}"))?;
+        if read == 0 {
+            break;
+        }
+        received_bytes += read as u64;
+        if received_bytes > MAX_PACKAGE_BYTES {
+            return Err(format!("{url} is larger than 1 GB"));
+        }
+        hasher.update(&buffer[..read]);
+        file.write_all(&buffer[..read])
+            .map_err(|e| format!("Failed to write {dest:?}: {e}"))?;
+        if received_bytes - reported_bytes >= PROGRESS_STEP_BYTES {
+            reported_bytes = received_bytes;
+            let _ = app.emit(
+                "openscad:install-progress",
+                InstallProgress {
+                    channel: asset.channel,
+                    received_bytes,
+                    total_bytes,
+                },
+            );
+        }
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn unpack_zip(package: &Path, dest: &Path) -> Result<(), String> {
+    let file = File::open(package).map_err(|e| format!("Failed to open {package:?}: {e}"))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip archive: {e}"))?;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read archive entry: {e}"))?;
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let target = dest.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&target).map_err(|e| format!("Failed to create {target:?}: {e}"))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {parent:?}: {e}"))?;
+        }
+        let mut out =
+            File::create(&target).map_err(|e| format!("Failed to create {target:?}: {e}"))?;
+        std::io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("Failed to extract {target:?}: {e}"))?;
+    }
+    Ok(())
+}
+
+fn run(command: &mut Command, what: &str) -> Result<(), String> {
+    let status = command
+        .status()
+        .map_err(|e| format!("Failed to {what}: {e}"))?;
+    if !status.success() {
+        return Err(format!(
+            "Failed to {what} (exit code {})",
+            status.code().unwrap_or(-1)
+        ));
+    }
+    Ok(())
+}
+
+/// Copy OpenSCAD.app out of the disk image with the tools macOS ships
+fn unpack_dmg(package: &Path, dest: &Path) -> Result<(), String> {
+    let mount_point = dest.join("mount");
+    run(
+        Command::new("hdiutil")
+            .args(["attach", "-nobrowse", "-readonly", "-mountpoint"])
+            .arg(&mount_point)
+            .arg(package),
+        "mount the OpenSCAD disk image",
+    )?;
+    let copied = run(
+        Command::new("ditto")
+            .arg(mount_point.join("OpenSCAD.app"))
+            .arg(dest.join("OpenSCAD.app")),
+        "copy OpenSCAD.app out of the disk image",
+    );
+    let _ = Command::new("hdiutil")
+        .arg("detach")
+        .arg(&mount_point)
+        .status();
+    let _ = fs::remove_dir(&mount_point);
+    copied?;
+    // Refuse an app whose signature is missing or doesn't match its contents
+    run(
+        Command::new("codesign")
+            .args(["--verify", "--deep", "--strict"])
+            .arg(dest.join("OpenSCAD.app")),
+        "verify the code signature of OpenSCAD.app",
+    )
+}
+
+fn unpack_package(package: &Path, dest: &Path, format: PackageFormat) -> Result<(), String> {
+    match format {
+        PackageFormat::AppImage => {
+            let binary = dest.join("OpenSCAD.AppImage");
+            fs::rename(package, &binary).map_err(|e| format!("Failed to move AppImage: {e}"))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&binary, fs::Permissions::from_mode(0o755))
+                    .map_err(|e| format!("Failed to make AppImage executable: {e}"))?;
+            }
+            return Ok(());
+        }
+        PackageFormat::Zip => unpack_zip(package, dest)?,
+        PackageFormat::Dmg => unpack_dmg(package, dest)?,
+    }
+    fs::remove_file(package).map_err(|e| format!("Failed to remove downloaded package: {e}"))
+}
+
+fn stage_build(
+    app: &AppHandle,
+    client: &reqwest::blocking::Client,
+    asset: &ReleaseAsset,
+    staging: &Path,
+) -> Result<InstallManifest, String> {
+    let expected_sha256 = published_sha256(client, asset)?;
+    let package = staging.join("package");
+    let sha256 = download_package(app, client, asset, &package)?;
+    if sha256 != expected_sha256 {
+        return Err(format!(
+            "{} doesn't match its published checksum; the download may be damaged or tampered with",
+            asset.url
+        ));
+    }
+    unpack_package(&package, staging, asset.format)?;
+    let binary = find_binary(staging, asset.format)
+        .ok_or("The downloaded package doesn't contain an OpenSCAD binary")?;
+    let manifest = InstallManifest {
+        id: install_id(asset.channel, &asset.version),
+        channel: asset.channel,
+        version: asset.version.clone(),
+        binary,
+        installed_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+    write_manifest(staging, &manifest)?;
+    Ok(manifest)
+}
+
+/// Download and unpack into a staging folder, then move it into place only
+/// once the build is complete and verified
+fn install(
+    app: &AppHandle,
+    channel: ReleaseChannel,
+    dir: &Path,
+) -> Result<InstallManifest, String> {
+    let _installing = INSTALL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let platform = Platform::current()
+        .ok_or("OpenSCAD doesn't publish builds for this platform; install it manually")?;
+    let client = http_client()?;
+    let asset = release_asset(&client, channel, platform)?;
+    let id = install_id(channel, &asset.version);
+    if !is_valid_install_id(&id) {
+        return Err(format!("Unexpected OpenSCAD version: {}", asset.version));
+    }
+    let install_dir = dir.join(&id);
+    if let Some(existing) = read_manifest(&install_dir) {
+        return Ok(existing);
+    }
+
+    let staging = dir.join(format!(".staging-{id}"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|e| format!("Failed to clear previous download: {e}"))?;
+    }
+    fs::create_dir_all(&staging).map_err(|e| format!("Failed to create {staging:?}: {e}"))?;
+
+    let manifest = match stage_build(app, &client, &asset, &staging) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+    if install_dir.exists() {
+        fs::remove_dir_all(&install_dir)
+            .map_err(|e| format!("Failed to replace {install_dir:?}: {e}"))?;
+    }
+    fs::rename(&staging, &install_dir)
+        .map_err(|e| format!("Failed to move build into place: {e}"))?;
+    Ok(manifest)
+}
+
+/// The bundled, system and downloaded OpenSCAD builds, marking the one
+/// renders use
+#[tauri::command]
+pub async fn list_openscad_versions(app: AppHandle) -> Result<OpenScadBuilds, String> {
+    tauri::async_runtime::spawn_blocking(move || list_builds(&app))
+        .await
+        .map_err(|e| format!("Listing OpenSCAD builds failed: {e}"))?
+}
+
+/// Download the stable release or latest nightly snapshot for this platform
+/// into the app data folder, checked against the SHA-256 checksum published
+/// next to it (and, on macOS, the app's code signature). Reports progress
+/// through `openscad:install-progress` events; an already downloaded version
+/// is returned as is.
+#[tauri::command]
+pub async fn install_openscad(
+    app: AppHandle,
+    channel: ReleaseChannel,
+) -> Result<OpenScadBuild, String> {
+    let dir = installs_dir(&app)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+    let task_dir = dir.clone();
+    let manifest = tauri::async_runtime::spawn_blocking(move || install(&app, channel, &task_dir))
+        .await
+        .map_err(|e| format!("OpenSCAD install task failed: {e}"))??;
+    Ok(downloaded_build(&dir, manifest))
+}
+
+/// Switch renders to the build with `id`, or back to automatic discovery
+/// with `None`. Returns the version now in use.
+#[tauri::command]
+pub async fn set_active_openscad(
+    app: AppHandle,
+    state: State<'_, OpenScadBinaryState>,
+    id: Option<String>,
+) -> Result<String, String> {
+    if let Some(id) = &id {
+        if build_binary_path(&app, id).is_none() {
+            return Err(format!("OpenSCAD build not found: {id}"));
+        }
+    }
+    update_settings(&app, |settings| {
+        settings.openscad.active = id;
+        Ok(())
+    })?;
+    init_binary(&app, &state)
+}
+
+/// Delete a downloaded build, switching back to automatic discovery if
+/// renders were using it
+#[tauri::command]
+pub async fn remove_openscad_version(
+    app: AppHandle,
+    state: State<'_, OpenScadBinaryState>,
+    id: String,
+) -> Result<(), String> {
+    if !is_valid_install_id(&id) || id == BUNDLED_ID || id == SYSTEM_ID {
+        return Err(format!("Not a downloaded OpenSCAD build: {id}"));
+    }
+    let install_dir = installs_dir(&app)?.join(&id);
+    if read_manifest(&install_dir).is_none() {
+        return Err(format!("OpenSCAD build not found: {id}"));
+    }
+    let was_active = active_setting(&app).as_deref() == Some(id.as_str());
+    if was_active {
+        update_settings(&app, |settings| {
+            settings.openscad.active = None;
+            Ok(())
+        })?;
+    }
+    fs::remove_dir_all(&install_dir).map_err(|e| format!("Failed to remove {id}: {e}"))?;
+    if was_active {
+        init_binary(&app, &state)?;
+    }
+    Ok(())
+}
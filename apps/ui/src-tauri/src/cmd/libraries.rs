@@ -0,0 +1,307 @@
+use crate::libraries::{
+    archive_url, check_search_paths, find_known, is_replaceable, latest_release_tag,
+    latest_release_url, list_installed, user_library_dir, write_manifest, InstalledLibrary,
+    KnownLibrary, LibraryManifest, LibraryPathStatus, KNOWN_LIBRARIES,
+};
+use crate::project_archive::unpack_with_limit;
+use crate::safe_mode::canonical_project_path;
+use crate::settings::{update_settings, SettingsState};
+use serde::Serialize;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_LIBRARY_ARCHIVE_BYTES: u64 = 200 * 1024 * 1024;
+/// Library repos carry far more files than a project (BOSL2 tutorials,
+/// dotSCAD samples), so the archive entry limit is raised
+const MAX_LIBRARY_ARCHIVE_ENTRIES: usize = 20_000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableLibrary {
+    pub name: String,
+    pub repo: String,
+    pub description: String,
+    pub usage: String,
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryOverview {
+    /// OpenSCAD's user library folder, where libraries are installed
+    pub library_dir: String,
+    pub installed: Vec<InstalledLibrary>,
+    pub available: Vec<AvailableLibrary>,
+}
+
+fn library_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .home_dir()
+        .map(|home| user_library_dir(&home))
+        .map_err(|e| format!("Failed to resolve home directory: {e}"))
+}
+
+pub(crate) fn library_overview(app: &AppHandle) -> Result<LibraryOverview, String> {
+    let dir = library_dir(app)?;
+    let pinned = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .libraries
+        .pinned
+        .clone();
+    let installed = list_installed(&dir, &pinned);
+    let available = KNOWN_LIBRARIES
+        .iter()
+        .map(|library| AvailableLibrary {
+            name: library.name.to_string(),
+            repo: library.repo.to_string(),
+            description: library.description.to_string(),
+            usage: library.usage.to_string(),
+            installed: installed.iter().any(|i| i.name == library.name),
+        })
+        .collect();
+    Ok(LibraryOverview {
+        library_dir: dir.to_string_lossy().to_string(),
+        installed,
+        available,
+    })
+}
+
+/// Installed libraries plus the ones available for one-click install
+#[tauri::command]
+pub fn list_libraries(app: AppHandle) -> Result<LibraryOverview, String> {
+    library_overview(&app)
+}
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .user_agent(concat!("OpenSCAD-Studio/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))
+}
+
+/// Release tag to install, or `None` to fall back to the default branch
+fn latest_release(client: &reqwest::blocking::Client, repo: &str) -> Option<String> {
+    let response = client.get(latest_release_url(repo)).send().ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    latest_release_tag(&response.text().ok()?)
+}
+
+fn download_archive(client: &reqwest::blocking::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Failed to download {url}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("{url} returned {}", response.status()));
+    }
+    let mut bytes = Vec::new();
+    response
+        .take(MAX_LIBRARY_ARCHIVE_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {url}: {e}"))?;
+    if bytes.len() as u64 > MAX_LIBRARY_ARCHIVE_BYTES {
+        return Err(format!(
+            "{url} is larger than {} MB",
+            MAX_LIBRARY_ARCHIVE_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Extract into `staging`, then swap the library folder in only once
+/// everything was extracted
+fn install_from_archive(
+    library: &KnownLibrary,
+    reference: &str,
+    bytes: Vec<u8>,
+    staging: &Path,
+    dir: &Path,
+) -> Result<(), String> {
+    unpack_with_limit(Cursor::new(bytes), staging, MAX_LIBRARY_ARCHIVE_ENTRIES)?;
+    let source = match library.source_dir {
+        Some(sub) => staging.join(sub),
+        None => staging.to_path_buf(),
+    };
+    if !source.is_dir() {
+        return Err(format!(
+            "{} {reference} has no {} folder",
+            library.name,
+            library.source_dir.unwrap_or(".")
+        ));
+    }
+    write_manifest(
+        &source,
+        &LibraryManifest {
+            name: library.name.to_string(),
+            repo: library.repo.to_string(),
+            version: reference.to_string(),
+            installed_at: chrono::Utc::now().timestamp(),
+        },
+    )?;
+    let target = dir.join(library.name);
+    if target.exists() {
+        fs::remove_dir_all(&target)
+            .map_err(|e| format!("Failed to remove old {}: {e}", library.name))?;
+    }
+    fs::rename(&source, &target).map_err(|e| format!("Failed to install {}: {e}", library.name))
+}
+
+fn install(
+    library: &KnownLibrary,
+    version: Option<String>,
+    replace_unmanaged: bool,
+    dir: PathBuf,
+) -> Result<InstalledLibrary, String> {
+    let target = dir.join(library.name);
+    if !replace_unmanaged && !is_replaceable(&target, library.name) {
+        return Err(format!(
+            "{} was not installed by OpenSCAD Studio. Confirm replacing it to install {} there.",
+            target.display(),
+            library.name
+        ));
+    }
+    let client = http_client()?;
+    let (reference, is_branch) = match version {
+        Some(version) => (version, false),
+        None => match latest_release(&client, library.repo) {
+            Some(tag) => (tag, false),
+            None => (library.default_branch.to_string(), true),
+        },
+    };
+    eprintln!("[libraries] Installing {} {}", library.name, reference);
+    let bytes = download_archive(&client, &archive_url(library.repo, &reference, is_branch))?;
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let staging = dir.join(format!(".staging-{}", uuid::Uuid::new_v4()));
+    let result = install_from_archive(library, &reference, bytes, &staging, &dir);
+    let _ = fs::remove_dir_all(&staging);
+    result?;
+
+    list_installed(&dir, &Default::default())
+        .into_iter()
+        .find(|installed| installed.name == library.name)
+        .ok_or_else(|| format!("{} was not installed", library.name))
+}
+
+/// Install or update a known library into OpenSCAD's user library folder.
+/// Uses `version` (a release tag) when given, else the pinned version, else
+/// the latest release, else the default branch. A folder of that name the
+/// app didn't install is only replaced with `replace_unmanaged`.
+#[tauri::command]
+pub async fn install_library(
+    app: AppHandle,
+    name: String,
+    version: Option<String>,
+    replace_unmanaged: Option<bool>,
+) -> Result<InstalledLibrary, String> {
+    let library = find_known(&name).ok_or_else(|| format!("Unknown library: {name}"))?;
+    let pinned = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .libraries
+        .pinned
+        .get(library.name)
+        .cloned();
+    let version = version.or(pinned.clone());
+    let dir = library_dir(&app)?;
+    let replace_unmanaged = replace_unmanaged.unwrap_or(false);
+
+    let mut installed = tauri::async_runtime::spawn_blocking(move || {
+        install(library, version, replace_unmanaged, dir)
+    })
+    .await
+    .map_err(|e| format!("Library install task failed: {e}"))??;
+    installed.pinned_version = pinned;
+    Ok(installed)
+}
+
+/// Pin a library to a version so installs and updates keep using it, or
+/// unpin it with `None`
+#[tauri::command]
+pub fn pin_library(app: AppHandle, name: String, version: Option<String>) -> Result<(), String> {
+    let library = find_known(&name).ok_or_else(|| format!("Unknown library: {name}"))?;
+    update_settings(&app, |settings| {
+        match version.filter(|v| !v.trim().is_empty()) {
+            Some(version) => {
+                settings
+                    .libraries
+                    .pinned
+                    .insert(library.name.to_string(), version.trim().to_string());
+            }
+            None => {
+                settings.libraries.pinned.remove(library.name);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Check that each library search path exists. Relative paths resolve
+/// against `root` (a project folder) when given.
+#[tauri::command]
+pub fn validate_library_paths(
+    paths: Vec<String>,
+    root: Option<String>,
+) -> Result<Vec<LibraryPathStatus>, String> {
+    Ok(check_search_paths(root.as_deref().map(Path::new), &paths))
+}
+
+/// The project's extra library search paths, passed to OpenSCAD as
+/// `OPENSCADPATH` when rendering it
+#[tauri::command]
+pub fn get_project_library_paths(
+    root: String,
+    state: State<'_, SettingsState>,
+) -> Result<Vec<LibraryPathStatus>, String> {
+    let paths = state
+        .settings
+        .lock()
+        .unwrap()
+        .libraries
+        .project_paths
+        .get(&canonical_project_path(&root))
+        .cloned()
+        .unwrap_or_default();
+    Ok(check_search_paths(Some(Path::new(&root)), &paths))
+}
+
+/// Replace the project's extra library search paths. Missing folders are
+/// kept but reported so the user can fix them.
+#[tauri::command]
+pub fn set_project_library_paths(
+    app: AppHandle,
+    root: String,
+    paths: Vec<String>,
+) -> Result<Vec<LibraryPathStatus>, String> {
+    if !Path::new(&root).is_dir() {
+        return Err(format!("Not a folder: {root}"));
+    }
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .collect();
+    let statuses = check_search_paths(Some(Path::new(&root)), &paths);
+    update_settings(&app, |settings| {
+        let project_paths = &mut settings.libraries.project_paths;
+        if paths.is_empty() {
+            project_paths.remove(&canonical_project_path(&root));
+        } else {
+            project_paths.insert(canonical_project_path(&root), paths);
+        }
+        Ok(())
+    })?;
+    Ok(statuses)
+}
@@ -0,0 +1,33 @@
+use crate::heightmap::load_grid;
+use crate::lithophane::{lithophane_code, Lithophane, LithophaneOptions};
+use std::fs;
+use std::path::Path;
+
+/// Generate lithophane OpenSCAD code from a PNG photo. The frontend opens the
+/// result as a new document, which renders through the normal pipeline.
+#[tauri::command]
+pub async fn generate_lithophane(
+    image_path: String,
+    options: Option<LithophaneOptions>,
+) -> Result<Lithophane, String> {
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        let bytes =
+            fs::read(&image_path).map_err(|e| format!("Failed to read {image_path}: {e}"))?;
+        let grid = load_grid(&bytes, options.max_resolution)?;
+        let source_name = Path::new(&image_path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("image");
+        let lithophane = lithophane_code(&grid, &options, source_name)?;
+        eprintln!(
+            "[lithophane] Generated {}x{} lithophane ({} bytes of code)",
+            lithophane.columns,
+            lithophane.rows,
+            lithophane.code.len()
+        );
+        Ok(lithophane)
+    })
+    .await
+    .map_err(|e| format!("Lithophane task failed: {e}"))?
+}
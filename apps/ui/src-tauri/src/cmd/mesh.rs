@@ -0,0 +1,224 @@
+use crate::cmd::render::{execute_render, render_policy, RenderPolicy};
+use crate::cmd::sweep::first_error_line;
+use crate::cmd::{EditorState, OpenScadBinaryState};
+use crate::decimate::{decimate, DecimationOptions};
+use crate::geometry::{encode_binary_stl, parse_stl, stl_stats, triangle_stats};
+use crate::measure::{probe, Probe, ProbeResult};
+use crate::mesh_checks::{check_mesh, MeshCheckOptions, MeshWarning};
+use crate::types::{Diagnostic, GeometryStats};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecimationReport {
+    pub before: GeometryStats,
+    pub after: GeometryStats,
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintabilityReport {
+    pub warnings: Vec<MeshWarning>,
+    /// The same warnings as editor diagnostics
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeasureReport {
+    pub stats: GeometryStats,
+    pub probes: Vec<ProbeResult>,
+}
+
+/// Decimate an exported STL file, writing binary STL to `output_path`
+/// (defaults to overwriting the input)
+#[tauri::command]
+pub async fn decimate_mesh_file(
+    path: String,
+    options: DecimationOptions,
+    output_path: Option<String>,
+) -> Result<DecimationReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let is_stl = Path::new(&path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("stl"));
+        if !is_stl {
+            return Err("Mesh decimation is only supported for STL exports".to_string());
+        }
+        let bytes = fs::read(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+        let triangles = parse_stl(&bytes)?;
+        let reduced = decimate(&triangles, &options)?;
+
+        let output_path = output_path.unwrap_or_else(|| path.clone());
+        let temp_path = Path::new(&output_path).with_extension("openscad-studio.tmp");
+        fs::write(&temp_path, encode_binary_stl(&reduced))
+            .map_err(|e| format!("Failed to write {output_path}: {e}"))?;
+        fs::rename(&temp_path, &output_path).map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            format!("Failed to replace {output_path}: {e}")
+        })?;
+
+        let report = DecimationReport {
+            before: triangle_stats(&triangles),
+            after: triangle_stats(&reduced),
+            output_path,
+        };
+        eprintln!(
+            "[mesh] Decimated {} -> {} triangles",
+            report.before.triangle_count, report.after.triangle_count
+        );
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Decimation task failed: {e}"))?
+}
+
+/// Render `code` to binary STL
+fn render_stl(binary_path: &Path, code: &str, policy: &RenderPolicy) -> Result<Vec<u8>, String> {
+    let args: Vec<String> = ["/input.scad", "-o", "/output.stl", "--export-format=binstl"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    let result = execute_render(
+        binary_path,
+        code,
+        &args,
+        &None,
+        &None,
+        &policy.working_dir,
+        &policy.library_paths,
+        policy.timeout,
+        None,
+    )?;
+    if result.exit_code != 0 || result.output.is_empty() {
+        return Err(first_error_line(&result.stderr)
+            .unwrap_or_else(|| "The design did not produce 3D geometry".to_string()));
+    }
+    Ok(result.output)
+}
+
+/// Render `code` to a temporary STL and compute its statistics
+pub(crate) fn render_geometry_stats(
+    binary_path: &Path,
+    code: &str,
+    policy: &RenderPolicy,
+) -> Result<GeometryStats, String> {
+    stl_stats(&render_stl(binary_path, code, policy)?)
+}
+
+/// Volume, surface area, bounding box, triangle count and manifoldness of
+/// the rendered geometry (defaults to the current editor code)
+#[tauri::command]
+pub async fn analyze_geometry(
+    app: AppHandle,
+    code: Option<String>,
+    working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    binary_state: State<'_, OpenScadBinaryState>,
+    editor_state: State<'_, EditorState>,
+) -> Result<GeometryStats, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    let working_dir = working_dir.or_else(|| editor_state.working_dir.lock().unwrap().clone());
+    let policy = render_policy(&app, &code, &None, &working_dir, &library_paths)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        render_geometry_stats(&binary_path, &code, &policy)
+    })
+    .await
+    .map_err(|e| format!("Geometry analysis task failed: {e}"))?
+}
+
+/// Render the design and check the mesh for printability problems (defaults
+/// to the current editor code)
+#[tauri::command]
+pub async fn check_printability(
+    app: AppHandle,
+    code: Option<String>,
+    working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    options: Option<MeshCheckOptions>,
+    binary_state: State<'_, OpenScadBinaryState>,
+    editor_state: State<'_, EditorState>,
+) -> Result<PrintabilityReport, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    let working_dir = working_dir.or_else(|| editor_state.working_dir.lock().unwrap().clone());
+    let policy = render_policy(&app, &code, &None, &working_dir, &library_paths)?;
+    let options = options.unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let triangles = parse_stl(&render_stl(&binary_path, &code, &policy)?)?;
+        let warnings = check_mesh(&triangles, &options);
+        eprintln!(
+            "[mesh] Printability check found {} issue(s) in {} triangles",
+            warnings.len(),
+            triangles.len()
+        );
+        Ok(PrintabilityReport {
+            diagnostics: warnings.iter().map(MeshWarning::to_diagnostic).collect(),
+            warnings,
+        })
+    })
+    .await
+    .map_err(|e| format!("Printability check task failed: {e}"))?
+}
+
+/// Render `code` to a mesh and measure it along each probe line
+pub(crate) fn measure_mesh(
+    binary_path: &Path,
+    code: &str,
+    policy: &RenderPolicy,
+    probes: &[Probe],
+) -> Result<MeasureReport, String> {
+    let triangles = parse_stl(&render_stl(binary_path, code, policy)?)?;
+    Ok(MeasureReport {
+        stats: triangle_stats(&triangles),
+        probes: probes.iter().map(|line| probe(&triangles, line)).collect(),
+    })
+}
+
+/// Render the design (defaults to the current editor code) and measure it:
+/// bounding box and size, plus where each axis-aligned probe line passes
+/// through solid material and gaps
+#[tauri::command]
+pub async fn measure_model(
+    app: AppHandle,
+    code: Option<String>,
+    working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    probes: Option<Vec<Probe>>,
+    binary_state: State<'_, OpenScadBinaryState>,
+    editor_state: State<'_, EditorState>,
+) -> Result<MeasureReport, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    let working_dir = working_dir.or_else(|| editor_state.working_dir.lock().unwrap().clone());
+    let policy = render_policy(&app, &code, &None, &working_dir, &library_paths)?;
+    let probes = probes.unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        measure_mesh(&binary_path, &code, &policy, &probes)
+    })
+    .await
+    .map_err(|e| format!("Measurement task failed: {e}"))?
+}
@@ -1,6 +1,51 @@
+pub mod actions;
+pub mod agent_transcripts;
+pub mod ai_instructions;
+pub mod ai_settings;
 pub mod ai_tools;
+pub mod animation;
+pub mod annotated_png;
+pub mod batch;
+pub mod cloud_ai;
+pub mod conversations;
+pub mod customizer;
+pub mod diagnostics;
+pub mod docs;
+pub mod docs_index;
+pub mod documents;
+pub mod export_presets;
+pub mod files;
+pub mod heightmap;
 pub mod history;
+pub mod import_remote;
+pub mod installer;
+pub mod libraries;
+pub mod lithophane;
+pub mod mesh;
+pub mod outline;
+pub mod printing;
+pub mod profile2d;
+pub mod project;
+pub mod project_archive;
+pub mod qr;
+pub mod recovery;
+pub mod reference_geometry;
 pub mod render;
+pub mod render_scheduler;
+pub mod safe_mode;
+pub mod secrets;
+pub mod session;
+pub mod settings_transfer;
+pub mod shortcuts;
+pub mod slicer;
+pub mod snippets;
+pub mod step_export;
+pub mod sweep;
+pub mod symbols;
+pub mod templates;
+pub mod tool_permissions;
+pub mod url_import;
+pub mod variables;
 
 pub use ai_tools::{update_editor_state, update_working_dir, EditorState};
 pub use render::OpenScadBinaryState;
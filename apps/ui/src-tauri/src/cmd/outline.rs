@@ -0,0 +1,28 @@
+use crate::outline::{convert_outline, OutlineConversion, OutlineFormat, OutlineOptions};
+use std::fs;
+use std::path::Path;
+
+/// Convert an SVG or DXF outline into `polygon()` code. Reads `path` unless
+/// `content` is given (SVG path data alone is accepted too).
+#[tauri::command]
+pub fn convert_outline_to_polygon(
+    path: Option<String>,
+    content: Option<String>,
+    format: Option<OutlineFormat>,
+    options: Option<OutlineOptions>,
+) -> Result<OutlineConversion, String> {
+    let content = match (content, &path) {
+        (Some(content), _) => content,
+        (None, Some(path)) => {
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?
+        }
+        (None, None) => return Err("Provide a file path or outline content".into()),
+    };
+    let format = format.unwrap_or_else(|| OutlineFormat::detect(path.as_deref(), &content));
+    let source_name = path
+        .as_deref()
+        .and_then(|path| Path::new(path).file_name())
+        .and_then(|name| name.to_str())
+        .unwrap_or("outline");
+    convert_outline(&content, format, source_name, &options.unwrap_or_default())
+}
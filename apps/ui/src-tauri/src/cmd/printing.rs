@@ -0,0 +1,303 @@
+use crate::printing::{
+    check_upload, error_message, multipart_form, normalize_base_url, parse_status, status_url,
+    upload_url, PrinterConnection, PrinterKind, PrinterSummary, API_KEY_HEADER, STATUS_TIMEOUT,
+    UPLOAD_TIMEOUT,
+};
+use crate::settings::{update_settings, SettingsState};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const PROGRESS_STEP_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrinterInput {
+    /// Existing printer to update; a new one is added when `None`
+    pub id: Option<String>,
+    pub name: String,
+    pub kind: PrinterKind,
+    pub url: String,
+    /// `None` keeps the stored key, an empty string removes it
+    pub api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrinterUploadProgress {
+    pub printer_id: String,
+    pub file_name: String,
+    /// uploading | done | failed
+    pub stage: String,
+    pub sent_bytes: u64,
+    pub total_bytes: u64,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrinterUpload {
+    pub printer: String,
+    pub file_name: String,
+    pub started_print: bool,
+    pub duration_ms: u64,
+}
+
+fn printer(app: &AppHandle, id: &str) -> Result<PrinterConnection, String> {
+    app.state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .printing
+        .printers
+        .iter()
+        .find(|printer| printer.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown printer: {id}"))
+}
+
+fn http_client(timeout: std::time::Duration) -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .user_agent(concat!("OpenSCAD-Studio/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))
+}
+
+fn with_api_key(
+    request: reqwest::blocking::RequestBuilder,
+    printer: &PrinterConnection,
+) -> reqwest::blocking::RequestBuilder {
+    match &printer.api_key {
+        Some(key) => request.header(API_KEY_HEADER, key),
+        None => request,
+    }
+}
+
+/// Configured printers (API keys are never sent back to the UI)
+#[tauri::command]
+pub fn list_printers(state: State<'_, SettingsState>) -> Vec<PrinterSummary> {
+    state
+        .settings
+        .lock()
+        .unwrap()
+        .printing
+        .printers
+        .iter()
+        .map(PrinterSummary::from)
+        .collect()
+}
+
+/// Add or update an OctoPrint/Moonraker connection
+#[tauri::command]
+pub fn save_printer(app: AppHandle, printer: PrinterInput) -> Result<PrinterSummary, String> {
+    let name = printer.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Give the printer a name".into());
+    }
+    let url = normalize_base_url(&printer.url)?;
+    update_settings(&app, |settings| {
+        let printers = &mut settings.printing.printers;
+        let existing = printer
+            .id
+            .as_deref()
+            .and_then(|id| printers.iter().position(|p| p.id == id));
+        let api_key = match (printer.api_key, existing) {
+            (Some(key), _) => Some(key.trim().to_string()).filter(|key| !key.is_empty()),
+            (None, Some(index)) => printers[index].api_key.clone(),
+            (None, None) => None,
+        };
+        let connection = PrinterConnection {
+            id: printer
+                .id
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            name,
+            kind: printer.kind,
+            url,
+            api_key,
+        };
+        let summary = PrinterSummary::from(&connection);
+        match existing {
+            Some(index) => printers[index] = connection,
+            None => printers.push(connection),
+        }
+        Ok(summary)
+    })
+}
+
+#[tauri::command]
+pub fn remove_printer(app: AppHandle, id: String) -> Result<(), String> {
+    update_settings(&app, |settings| {
+        settings
+            .printing
+            .printers
+            .retain(|printer| printer.id != id);
+        Ok(())
+    })
+}
+
+/// Check the printer is reachable and the API key works; returns the
+/// server's name and version
+#[tauri::command]
+pub async fn test_printer_connection(app: AppHandle, id: String) -> Result<String, String> {
+    let printer = printer(&app, &id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let client = http_client(STATUS_TIMEOUT)?;
+        let url = status_url(&printer);
+        let response = with_api_key(client.get(&url), &printer)
+            .send()
+            .map_err(|e| format!("Could not reach {}: {e}", printer.url))?;
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        if !status.is_success() {
+            return Err(error_message(status.as_u16(), &body));
+        }
+        parse_status(printer.kind, &body)
+    })
+    .await
+    .map_err(|e| format!("Printer connection test failed: {e}"))?
+}
+
+/// Reports upload progress as the request body is read
+struct ProgressReader<R> {
+    inner: R,
+    app: AppHandle,
+    progress: PrinterUploadProgress,
+    reported_bytes: u64,
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buffer)?;
+        self.progress.sent_bytes += read as u64;
+        if self.progress.sent_bytes - self.reported_bytes >= PROGRESS_STEP_BYTES {
+            self.reported_bytes = self.progress.sent_bytes;
+            let _ = self
+                .app
+                .emit("printer:upload-progress", self.progress.clone());
+        }
+        Ok(read)
+    }
+}
+
+fn emit_progress(
+    app: &AppHandle,
+    printer_id: &str,
+    file_name: &str,
+    stage: &str,
+    total_bytes: u64,
+    message: Option<String>,
+) {
+    let _ = app.emit(
+        "printer:upload-progress",
+        PrinterUploadProgress {
+            printer_id: printer_id.to_string(),
+            file_name: file_name.to_string(),
+            stage: stage.to_string(),
+            sent_bytes: if stage == "done" { total_bytes } else { 0 },
+            total_bytes,
+            message,
+        },
+    );
+}
+
+/// Upload an exported STL or sliced G-code file to a configured printer,
+/// optionally starting the print. Progress is emitted as
+/// `printer:upload-progress`.
+#[tauri::command]
+pub async fn upload_to_printer(
+    app: AppHandle,
+    printer_id: String,
+    file_path: String,
+    start_print: Option<bool>,
+) -> Result<PrinterUpload, String> {
+    let printer = printer(&app, &printer_id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let file_name = Path::new(&file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| format!("{file_path} has no file name"))?;
+        let result = run_upload(
+            &app,
+            &printer,
+            &file_path,
+            &file_name,
+            start_print.unwrap_or(false),
+        );
+        if let Err(e) = &result {
+            emit_progress(&app, &printer.id, &file_name, "failed", 0, Some(e.clone()));
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("Printer upload task failed: {e}"))?
+}
+
+fn run_upload(
+    app: &AppHandle,
+    printer: &PrinterConnection,
+    file_path: &str,
+    file_name: &str,
+    start_print: bool,
+) -> Result<PrinterUpload, String> {
+    check_upload(printer.kind, file_name, start_print)?;
+    let file = File::open(file_path).map_err(|e| format!("Failed to open {file_path}: {e}"))?;
+    let file_size = file
+        .metadata()
+        .map_err(|e| format!("Failed to read {file_path}: {e}"))?
+        .len();
+
+    let form = multipart_form(printer.kind, file_name, start_print);
+    let total_bytes = form.head.len() as u64 + file_size + form.tail.len() as u64;
+    let body = ProgressReader {
+        inner: Cursor::new(form.head)
+            .chain(file)
+            .chain(Cursor::new(form.tail)),
+        app: app.clone(),
+        progress: PrinterUploadProgress {
+            printer_id: printer.id.clone(),
+            file_name: file_name.to_string(),
+            stage: "uploading".to_string(),
+            sent_bytes: 0,
+            total_bytes,
+            message: None,
+        },
+        reported_bytes: 0,
+    };
+    emit_progress(app, &printer.id, file_name, "uploading", total_bytes, None);
+
+    let start = Instant::now();
+    let client = http_client(UPLOAD_TIMEOUT)?;
+    let response = with_api_key(client.post(upload_url(printer)), printer)
+        .header(reqwest::header::CONTENT_TYPE, form.content_type)
+        .body(reqwest::blocking::Body::sized(body, total_bytes))
+        .send()
+        .map_err(|e| format!("Upload to {} failed: {e}", printer.name))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().unwrap_or_default();
+        return Err(format!(
+            "Upload to {} failed: {}",
+            printer.name,
+            error_message(status.as_u16(), &body)
+        ));
+    }
+
+    let upload = PrinterUpload {
+        printer: printer.name.clone(),
+        file_name: file_name.to_string(),
+        started_print: start_print,
+        duration_ms: start.elapsed().as_millis() as u64,
+    };
+    eprintln!(
+        "[printing] Uploaded {file_name} to {} ({}) in {}ms",
+        printer.name,
+        printer.kind.label(),
+        upload.duration_ms
+    );
+    emit_progress(app, &printer.id, file_name, "done", total_bytes, None);
+    Ok(upload)
+}
@@ -0,0 +1,117 @@
+use crate::cmd::render::{execute_render, render_policy, RenderPolicy};
+use crate::cmd::sweep::first_error_line;
+use crate::cmd::{EditorState, OpenScadBinaryState};
+use crate::outline::{outline_paths, OutlineFormat, Point};
+use crate::profile2d::{measure_profile, profile_svg, ProfileMeasurements};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Curve flattening tolerance in drawing units (mm)
+const PROFILE_TOLERANCE: f64 = 0.01;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileDrawing {
+    /// Standalone SVG of the profile
+    pub svg: String,
+    pub measurements: ProfileMeasurements,
+    /// Input that was skipped while reading the outlines
+    pub warnings: Vec<String>,
+}
+
+fn profile_drawing(
+    paths: Vec<Vec<Point>>,
+    warnings: Vec<String>,
+    dimensioned: bool,
+) -> Result<ProfileDrawing, String> {
+    let measurements = measure_profile(&paths)?;
+    let svg = profile_svg(&paths, dimensioned.then_some(&measurements));
+    Ok(ProfileDrawing {
+        svg,
+        measurements,
+        warnings,
+    })
+}
+
+/// Render `code` to SVG and read back its outlines
+fn render_profile(
+    binary_path: &Path,
+    code: &str,
+    policy: &RenderPolicy,
+) -> Result<(Vec<Vec<Point>>, Vec<String>), String> {
+    let args: Vec<String> = ["/input.scad", "-o", "/output.svg"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    let result = execute_render(
+        binary_path,
+        code,
+        &args,
+        &None,
+        &None,
+        &policy.working_dir,
+        &policy.library_paths,
+        policy.timeout,
+        None,
+    )?;
+    if result.exit_code != 0 || result.output.is_empty() {
+        return Err(first_error_line(&result.stderr)
+            .unwrap_or_else(|| "The design did not produce 2D geometry".to_string()));
+    }
+    outline_paths(
+        &String::from_utf8_lossy(&result.output),
+        OutlineFormat::Svg,
+        PROFILE_TOLERANCE,
+    )
+}
+
+/// Render a 2D design (defaults to the current editor code) and return it
+/// as an SVG drawing annotated with its overall dimensions, plus its
+/// bounding box, area and cut length
+#[tauri::command]
+pub async fn render_2d_with_dimensions(
+    app: AppHandle,
+    code: Option<String>,
+    working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    binary_state: State<'_, OpenScadBinaryState>,
+    editor_state: State<'_, EditorState>,
+) -> Result<ProfileDrawing, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    let working_dir = working_dir.or_else(|| editor_state.working_dir.lock().unwrap().clone());
+    let policy = render_policy(&app, &code, &None, &working_dir, &library_paths)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let (paths, warnings) = render_profile(&binary_path, &code, &policy)?;
+        profile_drawing(paths, warnings, true)
+    })
+    .await
+    .map_err(|e| format!("2D render task failed: {e}"))?
+}
+
+/// Convert a DXF (read from `path` unless `content` is given) into an SVG
+/// preview of what a laser cutter would cut, with its measurements
+#[tauri::command]
+pub fn preview_dxf(
+    path: Option<String>,
+    content: Option<String>,
+    dimensioned: Option<bool>,
+) -> Result<ProfileDrawing, String> {
+    let content = match (content, &path) {
+        (Some(content), _) => content,
+        (None, Some(path)) => {
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?
+        }
+        (None, None) => return Err("Provide a DXF file path or content".into()),
+    };
+    let (paths, warnings) = outline_paths(&content, OutlineFormat::Dxf, PROFILE_TOLERANCE)?;
+    profile_drawing(paths, warnings, dimensioned.unwrap_or(false))
+}
@@ -0,0 +1,215 @@
+use crate::cmd::files::{list_project_files, read_text_file, write_text_file};
+use crate::cmd::history::switch_history_project;
+use crate::cmd::EditorState;
+use crate::file_watcher::{unwatch_project, watch_project};
+use crate::project::{Project, ProjectSnapshot};
+use crate::project_archive::main_file;
+use crate::safe_mode::is_escaping_path;
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFileContents {
+    pub project: ProjectSnapshot,
+    pub path: String,
+    pub content: String,
+}
+
+fn file_path(root: &str, path: &str) -> String {
+    Path::new(root).join(path).to_string_lossy().to_string()
+}
+
+/// Open a project folder and load its entry file (given, or `main.scad`, or
+/// a top-level .scad named after the folder) into the editor, and start
+/// watching the folder for external changes
+#[tauri::command]
+pub fn open_project(
+    app: AppHandle,
+    root: String,
+    entry_file: Option<String>,
+    editor_state: State<'_, EditorState>,
+) -> Result<ProjectFileContents, String> {
+    let files = list_project_files(root.clone())?;
+    let folder_name = Path::new(&root)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let entry = entry_file
+        .or_else(|| main_file(&files, folder_name))
+        .ok_or_else(|| format!("{root} contains no .scad file"))?;
+    let content = read_text_file(file_path(&root, &entry))?.content;
+
+    let mut project = Project::new(root.clone(), files, entry.clone());
+    project.insert_loaded(&entry, content.clone());
+    let snapshot = project.snapshot();
+
+    switch_history_project(&app, Some(&root));
+    *editor_state.current_code.lock().unwrap() = content.clone();
+    *editor_state.working_dir.lock().unwrap() = Some(root.clone());
+    *editor_state.project.lock().unwrap() = Some(project);
+    if let Err(e) = watch_project(&app, &root) {
+        eprintln!("[project] {}", e);
+    }
+    Ok(ProjectFileContents {
+        project: snapshot,
+        path: entry,
+        content,
+    })
+}
+
+/// The open project, if any, with a refreshed file list
+#[tauri::command]
+pub fn get_project(editor_state: State<'_, EditorState>) -> Option<ProjectSnapshot> {
+    let mut guard = editor_state.project.lock().unwrap();
+    let project = guard.as_mut()?;
+    if let Ok(files) = list_project_files(project.root.clone()) {
+        project.set_files(files);
+    }
+    Some(project.snapshot())
+}
+
+/// Make `path` the active file, keeping the previous file's unsaved text
+#[tauri::command]
+pub fn open_project_file(
+    path: String,
+    editor_state: State<'_, EditorState>,
+) -> Result<ProjectFileContents, String> {
+    if is_escaping_path(&path) {
+        return Err(format!("{path} is outside the project"));
+    }
+    let mut current_code = editor_state.current_code.lock().unwrap();
+    let mut guard = editor_state.project.lock().unwrap();
+    let project = guard.as_mut().ok_or("No project is open")?;
+
+    let previous = project.active_file.clone();
+    project.update(&previous, current_code.clone());
+    let content = match project.buffer(&path) {
+        Some(buffer) => buffer.content.clone(),
+        None => {
+            let content = read_text_file(file_path(&project.root, &path))?.content;
+            project.insert_loaded(&path, content.clone());
+            content
+        }
+    };
+    project.active_file = path.clone();
+    *current_code = content.clone();
+
+    Ok(ProjectFileContents {
+        project: project.snapshot(),
+        path,
+        content,
+    })
+}
+
+/// Save one project file (the active file by default), or every dirty file
+/// with `all`
+#[tauri::command]
+pub fn save_project_file(
+    path: Option<String>,
+    all: Option<bool>,
+    editor_state: State<'_, EditorState>,
+) -> Result<ProjectSnapshot, String> {
+    let current_code = editor_state.current_code.lock().unwrap().clone();
+    let mut guard = editor_state.project.lock().unwrap();
+    let project = guard.as_mut().ok_or("No project is open")?;
+
+    let active = project.active_file.clone();
+    project.update(&active, current_code);
+    let paths = if all.unwrap_or(false) {
+        project.dirty_files()
+    } else {
+        vec![path.unwrap_or(active)]
+    };
+    for path in paths {
+        let Some(buffer) = project.buffer(&path) else {
+            return Err(format!("{path} is not open"));
+        };
+        write_text_file(
+            file_path(&project.root, &path),
+            buffer.content.clone(),
+            None,
+        )?;
+        project.mark_saved(&path);
+    }
+    Ok(project.snapshot())
+}
+
+/// Choose the file renders start from
+#[tauri::command]
+pub fn set_project_entry_file(
+    path: String,
+    editor_state: State<'_, EditorState>,
+) -> Result<ProjectSnapshot, String> {
+    let mut guard = editor_state.project.lock().unwrap();
+    let project = guard.as_mut().ok_or("No project is open")?;
+    if !project.files().contains(&path) {
+        return Err(format!("{path} is not part of the project"));
+    }
+    project.entry_file = path;
+    Ok(project.snapshot())
+}
+
+/// Settle a `file:changed` event: with `reload` the disk version replaces
+/// the buffer (returned when it is the active file, to show in the editor);
+/// otherwise the editor text is kept and stays dirty against the disk.
+/// Changes in a project under another `root` are only the frontend's to
+/// settle.
+#[tauri::command]
+pub fn resolve_file_change(
+    root: String,
+    path: String,
+    reload: bool,
+    editor_state: State<'_, EditorState>,
+) -> Result<Option<String>, String> {
+    if is_escaping_path(&path) {
+        return Err(format!("{path} is outside the project"));
+    }
+    let mut current_code = editor_state.current_code.lock().unwrap();
+    let mut guard = editor_state.project.lock().unwrap();
+    let Some(project) = guard.as_mut().filter(|project| project.root == root) else {
+        return Ok(None);
+    };
+    let disk = read_text_file(file_path(&project.root, &path))?.content;
+
+    if !reload {
+        project.keep_over_disk(&path, disk);
+        return Ok(None);
+    }
+    if project.buffer(&path).is_some() {
+        project.insert_loaded(&path, disk.clone());
+    }
+    if project.active_file == path {
+        *current_code = disk.clone();
+        return Ok(Some(disk));
+    }
+    Ok(None)
+}
+
+/// Close the project at `root` and stop watching it unless another window
+/// has it open; returns the paths of files that had unsaved changes
+#[tauri::command]
+pub fn close_project(
+    app: AppHandle,
+    root: String,
+    editor_state: State<'_, EditorState>,
+) -> Vec<String> {
+    unwatch_project(&app, &root);
+    let current_code = editor_state.current_code.lock().unwrap().clone();
+    let project = {
+        let mut guard = editor_state.project.lock().unwrap();
+        match guard.as_ref() {
+            Some(project) if project.root == root => guard.take(),
+            _ => None,
+        }
+    };
+    match project {
+        Some(mut project) => {
+            let active = project.active_file.clone();
+            project.update(&active, current_code);
+            project.dirty_files()
+        }
+        None => Vec::new(),
+    }
+}
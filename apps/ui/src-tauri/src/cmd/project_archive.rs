@@ -0,0 +1,128 @@
+use crate::cmd::files::list_project_files;
+use crate::cmd::EditorState;
+use crate::create_new_window_with_launch_intent;
+use crate::mcp::WindowLaunchIntent;
+use crate::project_archive::{main_file, pack, unpack};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectArchive {
+    pub archive_path: String,
+    pub project_dir: String,
+    /// Root-relative paths of the packed/unpacked files
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenedProjectArchive {
+    #[serde(flatten)]
+    pub archive: ProjectArchive,
+    pub main_file: String,
+    pub window_id: String,
+}
+
+/// Folder named after `archive`, next to it
+fn unpack_dir(archive: &Path) -> Result<PathBuf, String> {
+    let parent = archive
+        .parent()
+        .ok_or_else(|| format!("{} has no parent directory", archive.display()))?;
+    let stem = archive
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("project");
+    free_dir(parent, stem)
+}
+
+/// `<parent>/<stem>`, or `<parent>/<stem>-N` when that already exists
+pub(crate) fn free_dir(parent: &Path, stem: &str) -> Result<PathBuf, String> {
+    (0..1000)
+        .map(|n| match n {
+            0 => parent.join(stem),
+            n => parent.join(format!("{stem}-{n}")),
+        })
+        .find(|dir| !dir.exists())
+        .ok_or_else(|| format!("No free folder name for {stem} in {}", parent.display()))
+}
+
+/// Pack the current project (working directory) into a .zip archive
+#[tauri::command]
+pub fn save_project_archive(
+    path: String,
+    editor_state: State<'_, EditorState>,
+) -> Result<ProjectArchive, String> {
+    let root = editor_state
+        .working_dir
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "Open a project folder before saving it as an archive".to_string())?;
+    let files = list_project_files(root.clone())?;
+    if files.is_empty() {
+        return Err(format!("{root} contains no project files"));
+    }
+
+    let target = Path::new(&path);
+    let temp_path = target.with_extension("zip.tmp");
+    let file = fs::File::create(&temp_path)
+        .map_err(|e| format!("Failed to create {}: {e}", temp_path.display()))?;
+    pack(Path::new(&root), &files, file)
+        .and_then(|_| {
+            fs::rename(&temp_path, target).map_err(|e| format!("Failed to replace {path}: {e}"))
+        })
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })?;
+
+    eprintln!("[archive] Packed {} file(s) into {path}", files.len());
+    Ok(ProjectArchive {
+        archive_path: path,
+        project_dir: root,
+        files,
+    })
+}
+
+/// Unpack a .zip project next to the archive and open it in a new window
+#[tauri::command]
+pub fn open_project_archive(app: AppHandle, path: String) -> Result<OpenedProjectArchive, String> {
+    let archive = Path::new(&path);
+    let file = fs::File::open(archive).map_err(|e| format!("Failed to open {path}: {e}"))?;
+    let project_dir = unpack_dir(archive)?;
+    let files = unpack(file, &project_dir).inspect_err(|_| {
+        let _ = fs::remove_dir_all(&project_dir);
+    })?;
+    let stem = archive
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let main =
+        main_file(&files, stem).ok_or_else(|| format!("{path} contains no .scad file to open"))?;
+
+    let window_id = create_new_window_with_launch_intent(
+        &app,
+        WindowLaunchIntent::OpenFile {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            file_path: project_dir.join(&main).to_string_lossy().to_string(),
+        },
+    )
+    .map_err(|e| format!("Failed to open a window for the unpacked project: {e}"))?;
+
+    eprintln!(
+        "[archive] Unpacked {} file(s) from {path} into {}",
+        files.len(),
+        project_dir.display()
+    );
+    Ok(OpenedProjectArchive {
+        archive: ProjectArchive {
+            archive_path: path,
+            project_dir: project_dir.to_string_lossy().to_string(),
+            files,
+        },
+        main_file: main,
+        window_id,
+    })
+}
@@ -0,0 +1,10 @@
+use crate::qr::{qr_code_snippet, QrCodeOptions, QrCodeSnippet};
+
+/// Generate OpenSCAD code for an embossed or debossed QR code plate
+#[tauri::command]
+pub fn generate_qr_code(
+    data: String,
+    options: Option<QrCodeOptions>,
+) -> Result<QrCodeSnippet, String> {
+    qr_code_snippet(&data, &options.unwrap_or_default())
+}
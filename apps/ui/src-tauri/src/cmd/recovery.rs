@@ -0,0 +1,62 @@
+use crate::recovery::{
+    begin_run, discard_recovered, end_run, list_recovered, take_recovered, write_autosave,
+    AutosaveBuffer, RecoveredContent, RecoveredFile, RECOVERY_DIR_NAME,
+};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, Window};
+
+fn recovery_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(RECOVERY_DIR_NAME))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+/// Mark the app as running at startup, collecting the previous run's
+/// snapshots if it crashed
+pub(crate) fn start_recovery(app: &AppHandle) {
+    match recovery_dir(app).and_then(|dir| begin_run(&dir)) {
+        Ok(0) => {}
+        Ok(count) => eprintln!("[recovery] {count} unsaved files recovered"),
+        Err(e) => eprintln!("[recovery] {e}"),
+    }
+}
+
+/// Drop autosave snapshots on a clean exit
+pub(crate) fn finish_recovery(app: &AppHandle) {
+    if let Ok(dir) = recovery_dir(app) {
+        end_run(&dir);
+    }
+}
+
+/// Replace this window's autosave snapshots with its current unsaved buffers
+#[tauri::command]
+pub fn autosave_buffers(
+    app: AppHandle,
+    window: Window,
+    buffers: Vec<AutosaveBuffer>,
+) -> Result<usize, String> {
+    write_autosave(
+        &recovery_dir(&app)?,
+        window.label(),
+        &buffers,
+        chrono::Utc::now().timestamp_millis(),
+    )
+}
+
+/// Unsaved files left behind by a crash, newest first
+#[tauri::command]
+pub fn list_recovered_files(app: AppHandle) -> Result<Vec<RecoveredFile>, String> {
+    Ok(list_recovered(&recovery_dir(&app)?))
+}
+
+/// Contents of a recovered file, which is then removed from recovery
+#[tauri::command]
+pub fn restore_recovered_file(app: AppHandle, id: String) -> Result<RecoveredContent, String> {
+    take_recovered(&recovery_dir(&app)?, &id)
+}
+
+#[tauri::command]
+pub fn discard_recovered_file(app: AppHandle, id: String) -> Result<(), String> {
+    discard_recovered(&recovery_dir(&app)?, &id)
+}
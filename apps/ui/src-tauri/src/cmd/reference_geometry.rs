@@ -0,0 +1,127 @@
+use crate::cmd::EditorState;
+use crate::reference_geometry::{
+    inspect_reference, list_reference_files, ReferenceFormat, ReferenceGeometry,
+    MAX_REFERENCE_BYTES,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadableReference {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceGeometryList {
+    pub files: Vec<ReferenceGeometry>,
+    /// Reference files OpenSCAD would fail to import
+    pub unreadable: Vec<UnreadableReference>,
+}
+
+fn read_reference(path: &Path) -> Result<Vec<u8>, String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?
+        .len();
+    if size > MAX_REFERENCE_BYTES {
+        return Err(format!(
+            "{} is larger than {} MB",
+            path.display(),
+            MAX_REFERENCE_BYTES / (1024 * 1024)
+        ));
+    }
+    fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+/// `<dir>/<name>`, or `<dir>/<stem>-N.<ext>` when a different file already
+/// has that name; `None` when an identical copy is already there
+fn copy_target(dir: &Path, name: &str, bytes: &[u8]) -> Result<Option<PathBuf>, String> {
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+    let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+    for n in 0..1000 {
+        let candidate = match n {
+            0 => dir.join(name),
+            n => dir.join(format!("{stem}-{n}.{extension}")),
+        };
+        match fs::read(&candidate) {
+            Ok(existing) if existing == bytes => return Ok(None),
+            Ok(_) => continue,
+            Err(_) => return Ok(Some(candidate)),
+        }
+    }
+    Err(format!("No free file name for {name} in {}", dir.display()))
+}
+
+fn import_path(file: &Path, working_dir: Option<&str>) -> String {
+    working_dir
+        .and_then(|dir| file.strip_prefix(dir).ok())
+        .unwrap_or(file)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Copy a mesh or drawing into the project (the working directory, else the
+/// source's folder is used as is) after checking OpenSCAD can import it.
+/// Files already in the project are only checked.
+#[tauri::command]
+pub fn import_reference_geometry(
+    source_path: String,
+    editor_state: State<'_, EditorState>,
+) -> Result<ReferenceGeometry, String> {
+    let source = Path::new(&source_path);
+    let name = source
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("{source_path} has no file name"))?;
+    if ReferenceFormat::from_path(name).is_none() {
+        return Err(format!("{name} is not an STL, OFF, 3MF, SVG or DXF file"));
+    }
+    let bytes = read_reference(source)?;
+
+    let working_dir = editor_state.working_dir.lock().unwrap().clone();
+    let inside_project = working_dir
+        .as_deref()
+        .is_some_and(|dir| source.starts_with(dir));
+    let target = match &working_dir {
+        Some(dir) if !inside_project => {
+            copy_target(Path::new(dir), name, &bytes)?.unwrap_or_else(|| Path::new(dir).join(name))
+        }
+        _ => source.to_path_buf(),
+    };
+    let geometry = inspect_reference(&import_path(&target, working_dir.as_deref()), &bytes)?;
+
+    if !target.exists() {
+        fs::write(&target, &bytes)
+            .map_err(|e| format!("Failed to copy {name} into the project: {e}"))?;
+        eprintln!("[reference] Copied {source_path} to {}", target.display());
+    }
+    Ok(geometry)
+}
+
+/// Check every reference file in the project, so models (and the AI) know
+/// what can be imported and how
+#[tauri::command]
+pub fn list_reference_geometry(
+    editor_state: State<'_, EditorState>,
+) -> Result<ReferenceGeometryList, String> {
+    let Some(root) = editor_state.working_dir.lock().unwrap().clone() else {
+        return Ok(ReferenceGeometryList::default());
+    };
+    Ok(reference_geometry_list(Path::new(&root)))
+}
+
+pub(crate) fn reference_geometry_list(root: &Path) -> ReferenceGeometryList {
+    let mut list = ReferenceGeometryList::default();
+    for path in list_reference_files(root) {
+        match read_reference(&root.join(&path)).and_then(|bytes| inspect_reference(&path, &bytes)) {
+            Ok(geometry) => list.files.push(geometry),
+            Err(reason) => list.unreadable.push(UnreadableReference { path, reason }),
+        }
+    }
+    list
+}
@@ -1,25 +1,66 @@
+use crate::batch::check_export_support;
+use crate::cache::{CacheKeys, RenderCache, RenderCacheInputs};
+use crate::camera::CameraSpec;
+use crate::cmd::export_presets::resolve_export_preset;
+use crate::cmd::installer::{active_binary_path, downloaded_binary_path};
+use crate::cmd::EditorState;
+use crate::documents::DocumentsState;
+use crate::image_export::{
+    color_schemes, key_out_background, set_colorscheme, ColorScheme, ImageExportOptions,
+};
+use crate::libraries::{openscad_path_env, resolve_search_path};
+use crate::locate::{
+    launch_command, openscad_command, system_binary_path, temp_root_for, LaunchContext,
+};
+use crate::openscad_capabilities::{adapt_backend_args, capabilities, OpenScadCapabilities};
+use crate::process_limits::{
+    limit_memory, limit_message, memory_limit_for, ran_out_of_memory, set_memory_limit,
+    ExceededLimit, RenderLimitSettings,
+};
+use crate::render::jobs::RenderJobManager;
+use crate::render::queue::{
+    default_concurrency, QueueStatus, RenderPriority, RenderQueue, MAX_CONCURRENCY,
+};
+use crate::safe_mode::{
+    canonical_project_path, check_untrusted_code, requires_safe_mode, SAFE_MODE_TIMEOUT,
+};
+use crate::settings::{update_settings, SettingsState};
+use crate::sweep::{decode_png, encode_png};
+use crate::variables::override_args;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
 use std::hash::{Hash, Hasher};
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::{Component, Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Mutex;
 use std::time::UNIX_EPOCH;
 use std::time::{Duration, Instant};
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 // ============================================================================
 // Types
 // ============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RenderNativeResult {
     pub output: Vec<u8>,
     pub stderr: String,
     pub exit_code: i32,
     pub duration_ms: u64,
+    /// Rendered in isolation because the project is untrusted
+    pub safe_mode: bool,
+    /// Served from an older version of the code while a fresh render runs
+    pub stale: bool,
+    /// Set when OpenSCAD was stopped for exceeding the timeout or memory
+    /// limit; `stderr` then ends with an `ERROR:` line saying so
+    pub limit_exceeded: Option<ExceededLimit>,
+    /// Render job that produced this result, or for a stale result the job
+    /// re-rendering it; `None` for cache hits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
 }
 
 /// Managed state holding the resolved path to the OpenSCAD binary.
@@ -28,6 +69,81 @@ pub struct OpenScadBinaryState {
     pub version: Mutex<Option<String>>,
 }
 
+/// Tracks the latest preview so stale background final-render checks are dropped.
+#[derive(Default)]
+pub struct PreviewCheckState {
+    pub generation: AtomicU64,
+}
+
+/// Latest native render per document, so background revalidations that a
+/// newer render superseded are cancelled and don't emit outdated results.
+#[derive(Default)]
+pub struct RevalidateState {
+    generations: Mutex<HashMap<String, u64>>,
+    /// Job of the background revalidation running for each document
+    revalidations: Mutex<HashMap<String, String>>,
+}
+
+impl RevalidateState {
+    /// Record a new render for `document_id` and return its generation. A
+    /// revalidation still running for the document is cancelled.
+    pub fn start_render(&self, document_id: &str, jobs: &RenderJobManager) -> u64 {
+        if let Some(job_id) = self.revalidations.lock().unwrap().remove(document_id) {
+            jobs.cancel(&job_id);
+        }
+        let mut generations = self.generations.lock().unwrap();
+        let generation = generations.entry(document_id.to_string()).or_default();
+        *generation += 1;
+        *generation
+    }
+
+    pub fn is_latest(&self, document_id: &str, generation: u64) -> bool {
+        self.generations.lock().unwrap().get(document_id) == Some(&generation)
+    }
+
+    fn start_revalidation(&self, document_id: &str, job_id: &str) {
+        self.revalidations
+            .lock()
+            .unwrap()
+            .insert(document_id.to_string(), job_id.to_string());
+    }
+
+    fn finish_revalidation(&self, document_id: &str, job_id: &str) {
+        let mut revalidations = self.revalidations.lock().unwrap();
+        if revalidations.get(document_id).map(String::as_str) == Some(job_id) {
+            revalidations.remove(document_id);
+        }
+    }
+}
+
+/// Emitted when a `render_preview` job completes, fails or is cancelled
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderJobFinished {
+    pub job_id: String,
+    pub result: Option<RenderNativeResult>,
+    pub error: Option<String>,
+    pub cancelled: bool,
+}
+
+/// Emitted when a stale-while-revalidate render finishes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevalidatedRender {
+    pub document_id: String,
+    pub result: RenderNativeResult,
+}
+
+/// Emitted when the `$preview=false` branch behaves differently from the preview
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewDivergence {
+    pub preview_exit_code: i32,
+    pub final_exit_code: i32,
+    pub final_stderr: String,
+    pub warnings: Vec<String>,
+}
+
 impl Default for OpenScadBinaryState {
     fn default() -> Self {
         Self {
@@ -43,11 +159,21 @@ impl Default for OpenScadBinaryState {
 
 /// Resolve the path to the OpenSCAD binary.
 /// Tries (in order):
-/// 1. Dev-mode OpenSCAD.app in src-tauri/binaries/ (preferred in dev to avoid
+/// 1. The build picked in settings (bundled, system or downloaded)
+/// 2. Dev-mode OpenSCAD.app in src-tauri/binaries/ (preferred in dev to avoid
 ///    macOS provenance attributes that Tauri's resource copy adds)
-/// 2. Bundled OpenSCAD.app resource (Tauri resource bundling — production)
-/// 3. System-installed binary via PATH
+/// 3. Bundled OpenSCAD.app resource (Tauri resource bundling — production)
+/// 4. System-installed binary via PATH
+/// 5. The most recently downloaded build
 fn resolve_binary_path(app: &AppHandle) -> Option<PathBuf> {
+    active_binary_path(app)
+        .or_else(|| bundled_binary_path(app))
+        .or_else(system_binary_path)
+        .or_else(|| downloaded_binary_path(app))
+}
+
+/// OpenSCAD.app shipped with the app (or placed in binaries/ during dev)
+pub(crate) fn bundled_binary_path(app: &AppHandle) -> Option<PathBuf> {
     // Dev mode: look in src-tauri/binaries/OpenSCAD.app first.
     // Tauri copies resources to target/debug/ which adds com.apple.provenance
     // attributes, causing macOS to SIGKILL the binary. The source in binaries/
@@ -76,20 +202,6 @@ fn resolve_binary_path(app: &AppHandle) -> Option<PathBuf> {
         }
     }
 
-    // Fallback: system-installed OpenSCAD via PATH
-    if let Ok(output) = Command::new("which").arg("openscad").output() {
-        if output.status.success() {
-            let path_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if !path_str.is_empty() {
-                let path = PathBuf::from(&path_str);
-                if path.exists() {
-                    eprintln!("[render] Found system OpenSCAD at {:?}", path);
-                    return Some(path);
-                }
-            }
-        }
-    }
-
     None
 }
 
@@ -98,6 +210,7 @@ fn app_bundle_root(binary_path: &Path) -> Option<PathBuf> {
         .parent() // MacOS/
         .and_then(|p| p.parent()) // Contents/
         .and_then(|p| p.parent()) // OpenSCAD.app/
+        .filter(|p| p.extension().is_some_and(|ext| ext == "app"))
         .map(Path::to_path_buf)
 }
 
@@ -217,8 +330,11 @@ fn prepare_binary_for_execution(binary_path: &Path) -> Result<PathBuf, String> {
 }
 
 /// Get the OpenSCAD version string from the binary.
-fn get_binary_version(binary_path: &Path) -> Option<String> {
-    let output = Command::new(binary_path).arg("--version").output().ok()?;
+pub(crate) fn get_binary_version(binary_path: &Path) -> Option<String> {
+    let output = openscad_command(binary_path)
+        .arg("--version")
+        .output()
+        .ok()?;
 
     // OpenSCAD prints version to stderr
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -247,6 +363,8 @@ struct RenderWorkspace {
     output_path: PathBuf,
     /// Temp files written into the project directory (need cleanup)
     project_temp_files: Vec<PathBuf>,
+    /// Directory project-relative paths resolve against (project root or temp input dir)
+    files_root: PathBuf,
 }
 
 fn normalize_relative_project_path(path: &str) -> Result<PathBuf, String> {
@@ -367,20 +485,21 @@ fn create_render_workspace(
     input_path: &Option<String>,
     working_dir: &Option<String>,
     library_paths: &Option<Vec<String>>,
+    temp_root: &Path,
 ) -> Result<RenderWorkspace, String> {
     let render_id = uuid::Uuid::new_v4().to_string();
-    let temp_dir = std::env::temp_dir()
-        .join("openscad-studio")
-        .join(&render_id);
+    let temp_dir = temp_root.join(&render_id);
     fs::create_dir_all(&temp_dir).map_err(|e| format!("Failed to create temp dir: {}", e))?;
 
     let output_file_path = temp_dir.join(output_filename);
     let mut project_temp_files = Vec::new();
 
+    let files_root;
     let input_file_path = if let Some(wd) = working_dir {
         // Write the input file into the project directory so all relative
         // paths (import, include, use) resolve against the real filesystem.
         let project_root = PathBuf::from(wd);
+        files_root = project_root.clone();
         let relative_input = resolve_project_relative_path(
             &project_root,
             input_path.as_deref().unwrap_or("input.scad"),
@@ -462,6 +581,7 @@ fn create_render_workspace(
         // No project root — use temp dir for everything (like WASM)
         let input_dir = temp_dir.join("input_dir");
         fs::create_dir_all(&input_dir).map_err(|e| format!("Failed to create input_dir: {}", e))?;
+        files_root = input_dir.clone();
 
         let relative_input = input_path.as_deref().unwrap_or("input.scad");
         let input_file = input_dir.join(relative_input);
@@ -497,80 +617,137 @@ fn create_render_workspace(
         input_path: input_file_path,
         output_path: output_file_path,
         project_temp_files,
+        files_root,
     })
 }
 
 // ============================================================================
-// Tauri commands
+// Render execution
 // ============================================================================
 
-const RENDER_TIMEOUT_SECS: u64 = 120;
-const MAX_STDERR_BYTES: usize = 100 * 1024; // 100KB
-
-/// Initialize the native render backend: find the binary and cache its path.
-#[tauri::command]
-pub async fn render_init(
-    app: AppHandle,
-    state: State<'_, OpenScadBinaryState>,
-) -> Result<String, String> {
-    let binary_path =
-        resolve_binary_path(&app).ok_or("OpenSCAD binary not found. Install OpenSCAD or place the binary in the app's binaries/ directory.")?;
-    let binary_path = prepare_binary_for_execution(&binary_path)?;
+/// Resolve the `-p` parameter file argument. Unsaved parameter files from
+/// `auxiliary_files` are written into the render temp dir; otherwise the path
+/// resolves against the project (or temp input) directory.
+fn resolve_parameter_file(
+    workspace: &RenderWorkspace,
+    raw_path: &str,
+    auxiliary_files: &Option<HashMap<String, String>>,
+) -> Result<PathBuf, String> {
+    let relative = raw_path
+        .strip_prefix("/input_dir/")
+        .unwrap_or(raw_path)
+        .trim_start_matches('/');
+    let normalized = normalize_relative_project_path(relative)?;
+
+    let unsaved = auxiliary_files.as_ref().and_then(|files| {
+        files.iter().find_map(|(path, content)| {
+            normalize_relative_project_path(path)
+                .ok()
+                .filter(|path| *path == normalized)
+                .map(|_| content)
+        })
+    });
+    if let Some(content) = unsaved {
+        let temp_path = workspace.temp_dir.join("parameters.json");
+        fs::write(&temp_path, content)
+            .map_err(|e| format!("Failed to write parameter file: {}", e))?;
+        return Ok(temp_path);
+    }
 
-    let version = get_binary_version(&binary_path).unwrap_or_else(|| "unknown".to_string());
-    eprintln!(
-        "[render] OpenSCAD initialized: {:?} ({})",
-        binary_path, version
-    );
+    Ok(workspace.files_root.join(normalized))
+}
 
-    *state.path.lock().unwrap() = Some(binary_path);
-    *state.version.lock().unwrap() = Some(version.clone());
+/// Remove the render's temp files from the project and its temp directory
+fn cleanup_render_workspace(workspace: &RenderWorkspace) {
+    // Clean up project temp files first (these are in the user's project dir)
+    for temp_file in &workspace.project_temp_files {
+        if let Err(e) = fs::remove_file(temp_file) {
+            eprintln!(
+                "[render] Failed to clean up project temp file {:?}: {}",
+                temp_file, e
+            );
+        }
+    }
 
-    Ok(version)
+    // Clean up temp output directory
+    if let Err(e) = fs::remove_dir_all(&workspace.temp_dir) {
+        eprintln!(
+            "[render] Failed to clean up temp dir {:?}: {}",
+            workspace.temp_dir, e
+        );
+    }
 }
 
-/// Render OpenSCAD code using the native binary.
-#[tauri::command]
-pub async fn render_native(
-    code: String,
-    args: Vec<String>,
-    auxiliary_files: Option<HashMap<String, String>>,
-    input_path: Option<String>,
-    working_dir: Option<String>,
-    library_paths: Option<Vec<String>>,
-    state: State<'_, OpenScadBinaryState>,
+/// Run OpenSCAD once in a fresh workspace and collect its output. Setting
+/// `cancelled` kills the process; its temp files are still cleaned up.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn execute_render(
+    binary_path: &Path,
+    code: &str,
+    args: &[String],
+    auxiliary_files: &Option<HashMap<String, String>>,
+    input_path: &Option<String>,
+    working_dir: &Option<String>,
+    library_paths: &Option<Vec<String>>,
+    timeout: Duration,
+    cancelled: Option<&AtomicBool>,
 ) -> Result<RenderNativeResult, String> {
-    let binary_path = state
-        .path
-        .lock()
-        .unwrap()
-        .clone()
-        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
-
     // Determine output filename from args (find -o flag)
     let output_filename = args
         .windows(2)
         .find(|w| w[0] == "-o")
         .map(|w| w[1].trim_start_matches('/').to_string())
         .unwrap_or_else(|| "output.off".to_string());
+    let capabilities = capabilities(binary_path);
+    if let Some(format) = Path::new(&output_filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+    {
+        check_export_support(format, &capabilities)?;
+    }
 
     // Create workspace — when working_dir is set, input files are written
     // into the project directory so all relative paths resolve naturally.
     let workspace = create_render_workspace(
-        &code,
+        code,
         &output_filename,
-        &auxiliary_files,
-        &input_path,
-        &working_dir,
-        &library_paths,
+        auxiliary_files,
+        input_path,
+        working_dir,
+        library_paths,
+        &temp_root_for(binary_path),
     )?;
 
-    // Build the command
-    let mut cmd = Command::new(&binary_path);
+    // Build the command; sandboxed builds get the folders OpenSCAD reads
+    // and writes shared into the sandbox
+    let search_paths = library_paths.as_deref().unwrap_or_default();
+    let mut context = LaunchContext::default();
+    context.folders.push(&workspace.temp_dir);
+    if let Some(wd) = working_dir {
+        context.folders.push(Path::new(wd));
+    }
+    context.folders.extend(search_paths.iter().map(Path::new));
+    if let Some(value) =
+        openscad_path_env(search_paths, std::env::var_os("OPENSCADPATH").as_deref())
+    {
+        context.env.push(("OPENSCADPATH", value));
+    }
+    let mut cmd = launch_command(binary_path, &context);
+    // Run from the folder holding the render's files, so nothing resolved
+    // against the current directory reaches outside them. Without a project
+    // (including safe-mode renders) that folder only holds copies of the
+    // input and auxiliary files.
+    cmd.current_dir(&workspace.files_root);
+    let args = adapt_backend_args(args, &capabilities);
 
     // Replace placeholder paths in args with actual workspace paths
+    let mut previous_arg: Option<&str> = None;
     for arg in &args {
-        if arg == "/input.scad" || arg.starts_with("/input_dir/") {
+        if previous_arg == Some("-p") {
+            let parameter_file = resolve_parameter_file(&workspace, arg, auxiliary_files)
+                .inspect_err(|_| cleanup_render_workspace(&workspace))?;
+            cmd.arg(parameter_file);
+        } else if arg == "/input.scad" || arg.starts_with("/input_dir/") {
             cmd.arg(workspace.input_path.to_str().unwrap());
         } else if arg.starts_with("/output.") {
             cmd.arg(workspace.output_path.to_str().unwrap());
@@ -579,6 +756,11 @@ pub async fn render_native(
         } else {
             cmd.arg(arg);
         }
+        previous_arg = Some(arg);
+    }
+    let memory_limit = memory_limit_for(binary_path);
+    if let Some(limit_mb) = memory_limit {
+        limit_memory(&mut cmd, limit_mb);
     }
 
     eprintln!(
@@ -594,6 +776,7 @@ pub async fn render_native(
         .stderr(std::process::Stdio::piped())
         .spawn()
         .map_err(|e| {
+            cleanup_render_workspace(&workspace);
             format!(
                 "Failed to spawn OpenSCAD: {} (binary: {:?})",
                 e, binary_path
@@ -601,10 +784,36 @@ pub async fn render_native(
         })?;
 
     // Wait with timeout
-    let output = tokio_timeout_wait(child, Duration::from_secs(RENDER_TIMEOUT_SECS))
-        .map_err(|e| e.to_string())?;
-
+    let outcome = tokio_timeout_wait(child, timeout, "OpenSCAD render", cancelled);
     let duration_ms = start.elapsed().as_millis() as u64;
+    let output = match outcome {
+        Ok(WaitOutcome::Exited(output)) => output,
+        Ok(WaitOutcome::TimedOut) => {
+            cleanup_render_workspace(&workspace);
+            eprintln!(
+                "[render] OpenSCAD render timed out after {}s",
+                timeout.as_secs()
+            );
+            return Ok(RenderNativeResult {
+                output: Vec::new(),
+                stderr: limit_message(ExceededLimit::Timeout, timeout, 0),
+                exit_code: -1,
+                duration_ms,
+                safe_mode: false,
+                stale: false,
+                limit_exceeded: Some(ExceededLimit::Timeout),
+                job_id: None,
+            });
+        }
+        Ok(WaitOutcome::Cancelled) => {
+            cleanup_render_workspace(&workspace);
+            return Err("OpenSCAD render cancelled".to_string());
+        }
+        Err(e) => {
+            cleanup_render_workspace(&workspace);
+            return Err(e);
+        }
+    };
 
     // Collect stderr (truncate if too large)
     let stderr_raw = String::from_utf8_lossy(&output.stderr);
@@ -618,6 +827,17 @@ pub async fn render_native(
     };
 
     let exit_code = output.status.code().unwrap_or(-1);
+    let mut limit_exceeded = None;
+    let stderr = match memory_limit {
+        Some(limit_mb) if ran_out_of_memory(&stderr, exit_code) => {
+            limit_exceeded = Some(ExceededLimit::Memory);
+            let message = limit_message(ExceededLimit::Memory, timeout, limit_mb);
+            format!("{}\n{message}", stderr.trim_end())
+                .trim_start()
+                .to_string()
+        }
+        _ => stderr,
+    };
 
     eprintln!(
         "[render] Completed in {}ms, exit_code={}, stderr_len={}",
@@ -628,86 +848,938 @@ pub async fn render_native(
 
     // Read output file if it exists
     let output_bytes = if workspace.output_path.exists() {
-        fs::read(&workspace.output_path)
-            .map_err(|e| format!("Failed to read output file: {}", e))?
+        fs::read(&workspace.output_path).map_err(|e| {
+            cleanup_render_workspace(&workspace);
+            format!("Failed to read output file: {}", e)
+        })?
     } else {
         Vec::new()
     };
 
-    // Clean up project temp files first (these are in the user's project dir)
-    for temp_file in &workspace.project_temp_files {
-        if let Err(e) = fs::remove_file(temp_file) {
-            eprintln!(
-                "[render] Failed to clean up project temp file {:?}: {}",
-                temp_file, e
-            );
-        }
-    }
-
-    // Clean up temp output directory
-    if let Err(e) = fs::remove_dir_all(&workspace.temp_dir) {
-        eprintln!(
-            "[render] Failed to clean up temp dir {:?}: {}",
-            workspace.temp_dir, e
-        );
-    }
+    cleanup_render_workspace(&workspace);
 
     Ok(RenderNativeResult {
         output: output_bytes,
         stderr,
         exit_code,
         duration_ms,
+        safe_mode: false,
+        stale: false,
+        limit_exceeded,
+        job_id: None,
     })
 }
 
-/// Cancel a running render by killing the process.
-/// For now this is a no-op — process cancellation will be added when we
-/// track child PIDs in state. The frontend can still call renderService.cancel()
-/// which prevents it from processing the result.
-#[tauri::command]
-pub async fn render_cancel() -> Result<(), String> {
-    // TODO: Track child PID in state and kill here
+// ============================================================================
+// Safe mode
+// ============================================================================
+
+/// Effective render inputs after applying safe mode and the project's
+/// configured library search paths
+pub(crate) struct RenderPolicy {
+    pub working_dir: Option<String>,
+    pub library_paths: Option<Vec<String>>,
+    pub timeout: Duration,
+    pub safe_mode: bool,
+}
+
+/// Restrict renders of untrusted projects when safe mode is enabled: no
+/// project working directory or library paths, a short timeout, and no file
+/// references outside the project. Emits `render:untrusted-project` so the
+/// frontend can offer to trust the folder.
+pub(crate) fn render_policy(
+    app: &AppHandle,
+    code: &str,
+    auxiliary_files: &Option<HashMap<String, String>>,
+    working_dir: &Option<String>,
+    library_paths: &Option<Vec<String>>,
+) -> Result<RenderPolicy, String> {
+    let settings = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .clone();
+    if !requires_safe_mode(&settings, working_dir.as_deref()) {
+        let mut library_paths = library_paths.clone();
+        let project_paths = working_dir.as_deref().and_then(|dir| {
+            let paths = settings
+                .libraries
+                .project_paths
+                .get(&canonical_project_path(dir))?;
+            Some((dir, paths))
+        });
+        if let Some((dir, paths)) = project_paths {
+            library_paths.get_or_insert_with(Vec::new).extend(
+                paths
+                    .iter()
+                    .map(|path| resolve_search_path(Some(Path::new(dir)), path))
+                    .map(|path| path.to_string_lossy().to_string()),
+            );
+        }
+        return Ok(RenderPolicy {
+            working_dir: working_dir.clone(),
+            library_paths,
+            timeout: settings.render_limits.timeout(),
+            safe_mode: false,
+        });
+    }
+
+    let _ = app.emit("render:untrusted-project", working_dir);
+    check_untrusted_code(code, auxiliary_files)?;
+    eprintln!(
+        "[render] Safe mode: rendering untrusted project {:?} in isolation",
+        working_dir
+    );
+    Ok(RenderPolicy {
+        working_dir: None,
+        library_paths: None,
+        timeout: SAFE_MODE_TIMEOUT.min(settings.render_limits.timeout()),
+        safe_mode: true,
+    })
+}
+
+// ============================================================================
+// Projects
+// ============================================================================
+
+/// When a project is open and the caller names no render target, render the
+/// project's entry file against the project folder, passing unsaved buffers
+/// of other project files along as auxiliary files.
+pub(crate) fn apply_project_target(
+    app: &AppHandle,
+    code: &mut String,
+    auxiliary_files: &mut Option<HashMap<String, String>>,
+    input_path: &mut Option<String>,
+    working_dir: &mut Option<String>,
+) -> Result<(), String> {
+    if input_path.is_some() {
+        return Ok(());
+    }
+    let editor_state = app.state::<EditorState>();
+    let project = editor_state.project.lock().unwrap();
+    let Some(project) = project.as_ref() else {
+        return Ok(());
+    };
+    if working_dir.as_ref().is_some_and(|dir| *dir != project.root) {
+        return Ok(());
+    }
+
+    let inputs = project.render_inputs(code)?;
+    *code = inputs.code;
+    *input_path = Some(inputs.input_path);
+    *working_dir = Some(inputs.working_dir);
+    if !inputs.auxiliary_files.is_empty() {
+        auxiliary_files
+            .get_or_insert_with(HashMap::new)
+            .extend(inputs.auxiliary_files);
+    }
     Ok(())
 }
 
 // ============================================================================
-// Timeout helper (without tokio — uses std threads)
+// Tauri commands
 // ============================================================================
 
-fn tokio_timeout_wait(
-    child: std::process::Child,
-    timeout: Duration,
-) -> Result<std::process::Output, String> {
-    // Use a thread to wait, with a timeout via channel
-    let (tx, rx) = std::sync::mpsc::channel();
+const MAX_STDERR_BYTES: usize = 100 * 1024; // 100KB
+
+/// Initialize the native render backend: find the binary and cache its path.
+#[tauri::command]
+pub async fn render_init(
+    app: AppHandle,
+    state: State<'_, OpenScadBinaryState>,
+) -> Result<String, String> {
+    init_binary(&app, &state)
+}
+
+/// Resolve the binary renders use, cache it with its version and return the
+/// version; re-run when the active build changes
+pub(crate) fn init_binary(app: &AppHandle, state: &OpenScadBinaryState) -> Result<String, String> {
+    let binary_path = resolve_binary_path(app)
+        .ok_or("OpenSCAD binary not found. Install OpenSCAD or download a build in Settings.")?;
+    let binary_path = prepare_binary_for_execution(&binary_path)?;
+
+    let version = get_binary_version(&binary_path).unwrap_or_else(|| "unknown".to_string());
+    eprintln!(
+        "[render] OpenSCAD initialized: {:?} ({})",
+        binary_path, version
+    );
+    // Probe now so the first render doesn't wait for it
+    capabilities(&binary_path);
+
+    *state.path.lock().unwrap() = Some(binary_path);
+    *state.version.lock().unwrap() = Some(version.clone());
+
+    Ok(version)
+}
+
+/// How the OpenSCAD binary selects the Manifold backend and which
+/// experimental features it accepts
+#[tauri::command]
+pub async fn get_openscad_capabilities(
+    state: State<'_, OpenScadBinaryState>,
+) -> Result<OpenScadCapabilities, String> {
+    let binary_path = state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    tauri::async_runtime::spawn_blocking(move || capabilities(&binary_path))
+        .await
+        .map_err(|e| format!("Capability probe failed: {e}"))
+}
+
+/// Color schemes the OpenSCAD binary accepts for `--colorscheme`, each
+/// marked dark or light so previews can follow the app theme
+#[tauri::command]
+pub async fn list_colorschemes(
+    state: State<'_, OpenScadBinaryState>,
+) -> Result<Vec<ColorScheme>, String> {
+    let binary_path = state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    // OpenSCAD prints its help to stderr
+    let help = openscad_command(&binary_path)
+        .arg("--help")
+        .output()
+        .map(|output| {
+            let mut help = String::from_utf8_lossy(&output.stderr).to_string();
+            help.push_str(&String::from_utf8_lossy(&output.stdout));
+            help
+        })
+        .unwrap_or_default();
+    Ok(color_schemes(&help))
+}
+
+/// Cache a successful render for a document that is still open
+fn cache_render_result(
+    documents: &DocumentsState,
+    document_id: String,
+    keys: CacheKeys,
+    result: &RenderNativeResult,
+) {
+    if result.exit_code != 0 {
+        return;
+    }
+    let mut inner = documents.inner.lock().unwrap();
+    // Skip documents that were closed while rendering.
+    if inner.meta(&document_id).is_some() {
+        inner.render_caches.entry(document_id).or_default().insert(
+            keys,
+            RenderNativeResult {
+                job_id: None,
+                ..result.clone()
+            },
+        );
+    }
+}
+
+/// Render OpenSCAD code using the native binary.
+/// `overrides` temporarily replaces top-level variable values via `-D`;
+/// `camera` places the camera for PNG output via `--camera`;
+/// `apply_export_preset` adds the active export preset's quality overrides;
+/// `image_options` sets the size, colors and view of PNG output.
+///
+/// With `check_final_branch`, a preview renders with `$preview=true` and,
+/// once it succeeds, the `$preview=false` branch is compiled in the
+/// background; `render:preview-divergence` is emitted if the two disagree.
+///
+/// With `stale_while_revalidate`, a cache miss for a document returns that
+/// document's latest result for the same args/paths immediately (marked
+/// `stale`), renders in the background and emits `render:revalidated` with
+/// the fresh result unless a newer render for the document started first.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn render_native(
+    app: AppHandle,
+    mut code: String,
+    mut args: Vec<String>,
+    mut auxiliary_files: Option<HashMap<String, String>>,
+    mut input_path: Option<String>,
+    mut working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    document_id: Option<String>,
+    overrides: Option<HashMap<String, String>>,
+    stale_while_revalidate: Option<bool>,
+    check_final_branch: Option<bool>,
+    camera: Option<CameraSpec>,
+    apply_export_preset: Option<bool>,
+    image_options: Option<ImageExportOptions>,
+    job_id: Option<String>,
+    state: State<'_, OpenScadBinaryState>,
+    documents: State<'_, DocumentsState>,
+) -> Result<RenderNativeResult, String> {
+    let binary_path = state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    if input_path.is_none() && working_dir.is_none() {
+        // Background tabs render beside their own file rather than the
+        // active document's project.
+        let inner = documents.inner.lock().unwrap();
+        if let Some((dir, file_name)) = document_id
+            .as_deref()
+            .filter(|id| *id != inner.active_id)
+            .and_then(|id| inner.meta(id))
+            .and_then(|meta| meta.render_paths())
+        {
+            input_path = Some(file_name);
+            working_dir = Some(dir);
+        }
+    }
+    apply_project_target(
+        &app,
+        &mut code,
+        &mut auxiliary_files,
+        &mut input_path,
+        &mut working_dir,
+    )?;
+    if let Some(overrides) = &overrides {
+        args.extend(override_args(&code, overrides)?);
+    }
+    if let Some(camera) = &camera {
+        // An explicit camera is reproduced as given rather than refitted.
+        args.retain(|arg| {
+            !arg.starts_with("--camera") && arg != "--viewall" && arg != "--autocenter"
+        });
+        args.push(camera.to_arg()?);
+    }
+    if apply_export_preset.unwrap_or(false) {
+        resolve_export_preset(&app, None)?.apply(&mut args)?;
+    }
+    if let Some(options) = &image_options {
+        options.apply(&mut args)?;
+    }
+    let priority = if apply_export_preset.unwrap_or(false) || image_options.is_some() {
+        RenderPriority::Export
+    } else {
+        RenderPriority::Preview
+    };
+    let check_final = check_final_branch.unwrap_or(false) && priority == RenderPriority::Preview;
+    if check_final {
+        args = with_preview_flag(&args, true);
+    }
+    // Keyed-out images aren't cached: the cache key only covers OpenSCAD's
+    // inputs.
+    let transparent = image_options.is_some_and(|options| options.transparent);
+    let document_id = document_id.filter(|_| !transparent);
+    let policy = render_policy(&app, &code, &auxiliary_files, &working_dir, &library_paths)?;
+
+    // Renders scoped to a document are served from that document's cache.
+    let cache_keys = document_id.as_ref().map(|_| {
+        RenderCache::keys(&RenderCacheInputs {
+            code: &code,
+            args: &args,
+            auxiliary_files: &auxiliary_files,
+            input_path: &input_path,
+            working_dir: &policy.working_dir,
+            library_paths: &policy.library_paths,
+        })
+    });
+    if let (Some(document_id), Some(keys)) = (&document_id, &cache_keys) {
+        let generation = app
+            .state::<RevalidateState>()
+            .start_render(document_id, &app.state::<RenderJobManager>());
+        let mut inner = documents.inner.lock().unwrap();
+        let cache = inner.render_caches.get_mut(document_id);
+        if let Some(cache) = cache {
+            if let Some(cached) = cache.get(&keys.key) {
+                eprintln!("[render] Cache hit for document {}", document_id);
+                return Ok(cached);
+            }
+            let stale = if stale_while_revalidate.unwrap_or(false) {
+                cache.latest_for_context(&keys.context)
+            } else {
+                None
+            };
+            if let Some(mut stale) = stale {
+                drop(inner);
+                eprintln!(
+                    "[render] Serving stale result for document {} while re-rendering",
+                    document_id
+                );
+                let (job_id, cancelled) = app.state::<RenderJobManager>().start_as(job_id)?;
+                app.state::<RevalidateState>()
+                    .start_revalidation(document_id, &job_id);
+                stale.job_id = Some(job_id.clone());
+                let app = app.clone();
+                let document_id = document_id.clone();
+                let keys = keys.clone();
+                std::thread::spawn(move || {
+                    let jobs = app.state::<RenderJobManager>();
+                    let queue = app.state::<RenderQueue>();
+                    let result = queue
+                        .acquire(priority, Some(&cancelled))
+                        .and_then(|_permit| {
+                            execute_render(
+                                &binary_path,
+                                &code,
+                                &args,
+                                &auxiliary_files,
+                                &input_path,
+                                &policy.working_dir,
+                                &policy.library_paths,
+                                policy.timeout,
+                                Some(&cancelled),
+                            )
+                        });
+                    jobs.finish(&job_id);
+                    app.state::<RevalidateState>()
+                        .finish_revalidation(&document_id, &job_id);
+                    let mut result = match result {
+                        Ok(result) => result,
+                        Err(e) => {
+                            eprintln!("[render] Revalidation failed to run: {}", e);
+                            return;
+                        }
+                    };
+                    result.safe_mode = policy.safe_mode;
+                    let documents = app.state::<DocumentsState>();
+                    cache_render_result(&documents, document_id.clone(), keys, &result);
+
+                    if !app
+                        .state::<RevalidateState>()
+                        .is_latest(&document_id, generation)
+                    {
+                        return;
+                    }
+                    let _ = app.emit(
+                        "render:revalidated",
+                        RevalidatedRender {
+                            document_id,
+                            result,
+                        },
+                    );
+                });
+                stale.stale = true;
+                return Ok(stale);
+            }
+        }
+    }
 
-    let handle = std::thread::spawn(move || {
-        let result = child.wait_with_output();
-        let _ = tx.send(result);
+    let jobs = app.state::<RenderJobManager>();
+    let (job_id, cancelled) = jobs.start_as(job_id)?;
+    let safe_mode = policy.safe_mode;
+    let check_generation = check_final.then(|| {
+        app.state::<PreviewCheckState>()
+            .generation
+            .fetch_add(1, Ordering::SeqCst)
+            + 1
     });
+    // Waiting for a queue slot and running OpenSCAD both block.
+    let task_app = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let preview = task_app
+            .state::<RenderQueue>()
+            .acquire(priority, Some(&cancelled))
+            .and_then(|_permit| {
+                execute_render(
+                    &binary_path,
+                    &code,
+                    &args,
+                    &auxiliary_files,
+                    &input_path,
+                    &policy.working_dir,
+                    &policy.library_paths,
+                    policy.timeout,
+                    Some(&cancelled),
+                )
+            });
+        let succeeded = preview
+            .as_ref()
+            .is_ok_and(|preview| preview.exit_code == 0 && !cancelled.load(Ordering::SeqCst));
+        if let (Some(generation), true) = (check_generation, succeeded) {
+            let checked = preview.clone()?;
+            std::thread::spawn(move || {
+                check_final_branch(
+                    &task_app,
+                    &checked,
+                    generation,
+                    &binary_path,
+                    &code,
+                    &args,
+                    &auxiliary_files,
+                    &input_path,
+                    &policy,
+                    None,
+                );
+            });
+        }
+        preview
+    })
+    .await
+    .map_err(|e| format!("Render task failed: {e}"));
+    jobs.finish(&job_id);
+    let mut result = result??;
+    result.safe_mode = safe_mode;
+    result.job_id = Some(job_id);
+    if transparent && result.exit_code == 0 && !result.output.is_empty() {
+        let mut image = decode_png(&result.output)?;
+        key_out_background(&mut image);
+        result.output = encode_png(&image)?;
+    }
 
-    match rx.recv_timeout(timeout) {
-        Ok(result) => {
-            let _ = handle.join();
-            result.map_err(|e| format!("OpenSCAD process error: {}", e))
+    if let (Some(document_id), Some(keys)) = (document_id, cache_keys) {
+        cache_render_result(&documents, document_id, keys, &result);
+    }
+
+    Ok(result)
+}
+
+/// Replace any `$preview` definition in the args with `-D $preview=<value>`.
+fn with_preview_flag(args: &[String], preview: bool) -> Vec<String> {
+    let mut result = Vec::with_capacity(args.len() + 2);
+    let mut iter = args.iter().peekable();
+    while let Some(arg) = iter.next() {
+        if arg == "-D"
+            && iter
+                .peek()
+                .is_some_and(|next| next.starts_with("$preview="))
+        {
+            iter.next();
+            continue;
         }
-        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
-            // Process timed out — we can't easily kill it from here since
-            // ownership moved to the thread, but we return an error
-            Err(format!(
-                "OpenSCAD render timed out after {}s",
-                timeout.as_secs()
-            ))
+        if arg.starts_with("-D$preview=") {
+            continue;
+        }
+        result.push(arg.clone());
+    }
+    result.push("-D".to_string());
+    result.push(format!("$preview={}", preview));
+    result
+}
+
+fn diagnostic_lines(stderr: &str) -> Vec<&str> {
+    stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("ERROR:") || line.starts_with("WARNING:"))
+        .collect()
+}
+
+fn is_empty_geometry(result: &RenderNativeResult) -> bool {
+    result.stderr.contains("Current top level object is empty")
+}
+
+/// Describe how the final render diverges from a successful preview.
+/// Returns no warnings when the preview itself failed — those errors are
+/// already surfaced by the preview.
+fn compare_preview_and_final(
+    preview: &RenderNativeResult,
+    final_render: &RenderNativeResult,
+) -> Vec<String> {
+    const MAX_NEW_DIAGNOSTICS: usize = 5;
+
+    if preview.exit_code != 0 {
+        return Vec::new();
+    }
+
+    let mut warnings = Vec::new();
+    let final_diagnostics = diagnostic_lines(&final_render.stderr);
+
+    if final_render.exit_code != 0 {
+        let reason = final_diagnostics
+            .iter()
+            .find(|line| line.starts_with("ERROR:"))
+            .map(|line| line.to_string())
+            .unwrap_or_else(|| format!("exit code {}", final_render.exit_code));
+        warnings.push(format!(
+            "Final render ($preview=false) fails while the preview succeeds: {}",
+            reason
+        ));
+        return warnings;
+    }
+
+    match (is_empty_geometry(preview), is_empty_geometry(final_render)) {
+        (false, true) => warnings.push(
+            "Final render ($preview=false) produces empty geometry; the preview does not".into(),
+        ),
+        (true, false) => warnings.push(
+            "Preview produces empty geometry; the final render ($preview=false) does not".into(),
+        ),
+        _ => {}
+    }
+
+    let preview_diagnostics = diagnostic_lines(&preview.stderr);
+    warnings.extend(
+        final_diagnostics
+            .into_iter()
+            .filter(|line| !preview_diagnostics.contains(line))
+            .take(MAX_NEW_DIAGNOSTICS)
+            .map(|line| format!("Only in final render: {}", line)),
+    );
+
+    warnings
+}
+
+/// Start a `$preview=true` render job and return its id immediately. The
+/// result arrives as a `render:job-finished` event; after a successful
+/// preview the `$preview=false` branch is compiled in the same job and
+/// `render:preview-divergence` is emitted if the two disagree.
+/// `overrides` temporarily replaces top-level variable values via `-D`;
+/// `colorscheme` (see `list_colorschemes`) colors PNG previews.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn render_preview(
+    app: AppHandle,
+    mut code: String,
+    args: Vec<String>,
+    mut auxiliary_files: Option<HashMap<String, String>>,
+    mut input_path: Option<String>,
+    mut working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    overrides: Option<HashMap<String, String>>,
+    colorscheme: Option<String>,
+) -> Result<String, String> {
+    let binary_path = app
+        .state::<OpenScadBinaryState>()
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+
+    apply_project_target(
+        &app,
+        &mut code,
+        &mut auxiliary_files,
+        &mut input_path,
+        &mut working_dir,
+    )?;
+
+    let mut args = args;
+    if let Some(overrides) = &overrides {
+        args.extend(override_args(&code, overrides)?);
+    }
+    if let Some(scheme) = &colorscheme {
+        set_colorscheme(&mut args, scheme)?;
+    }
+
+    let generation = app
+        .state::<PreviewCheckState>()
+        .generation
+        .fetch_add(1, Ordering::SeqCst)
+        + 1;
+
+    let policy = render_policy(&app, &code, &auxiliary_files, &working_dir, &library_paths)?;
+
+    let (job_id, cancelled) = app.state::<RenderJobManager>().start();
+    let started_job_id = job_id.clone();
+    std::thread::spawn(move || {
+        let queue = app.state::<RenderQueue>();
+        let preview = queue
+            .acquire(RenderPriority::Preview, Some(&cancelled))
+            .and_then(|_permit| {
+                execute_render(
+                    &binary_path,
+                    &code,
+                    &with_preview_flag(&args, true),
+                    &auxiliary_files,
+                    &input_path,
+                    &policy.working_dir,
+                    &policy.library_paths,
+                    policy.timeout,
+                    Some(&cancelled),
+                )
+            })
+            .map(|mut preview| {
+                preview.safe_mode = policy.safe_mode;
+                preview
+            });
+        let _ = app.emit(
+            "render:job-finished",
+            RenderJobFinished {
+                job_id: job_id.clone(),
+                result: preview.as_ref().ok().cloned(),
+                error: preview.as_ref().err().cloned(),
+                cancelled: cancelled.load(Ordering::SeqCst),
+            },
+        );
+
+        if let Ok(preview) = preview
+            .as_ref()
+            .ok()
+            .filter(|preview| preview.exit_code == 0 && !cancelled.load(Ordering::SeqCst))
+        {
+            check_final_branch(
+                &app,
+                preview,
+                generation,
+                &binary_path,
+                &code,
+                &args,
+                &auxiliary_files,
+                &input_path,
+                &policy,
+                Some(&cancelled),
+            );
+        }
+        app.state::<RenderJobManager>().finish(&job_id);
+    });
+
+    Ok(started_job_id)
+}
+
+/// Render the `$preview=false` branch of a successful `preview` and report
+/// whether it diverges. Only a consistency check, so it yields to previews
+/// and exports.
+#[allow(clippy::too_many_arguments)]
+fn check_final_branch(
+    app: &AppHandle,
+    preview: &RenderNativeResult,
+    generation: u64,
+    binary_path: &Path,
+    code: &str,
+    args: &[String],
+    auxiliary_files: &Option<HashMap<String, String>>,
+    input_path: &Option<String>,
+    policy: &RenderPolicy,
+    cancelled: Option<&AtomicBool>,
+) {
+    let final_render = app
+        .state::<RenderQueue>()
+        .acquire(RenderPriority::Background, cancelled)
+        .and_then(|_permit| {
+            execute_render(
+                binary_path,
+                code,
+                &with_preview_flag(args, false),
+                auxiliary_files,
+                input_path,
+                &policy.working_dir,
+                &policy.library_paths,
+                policy.timeout,
+                cancelled,
+            )
+        });
+    report_divergence(app, preview, generation, final_render);
+}
+
+/// Emit `render:preview-divergence` when the final render of the latest
+/// preview behaves differently
+fn report_divergence(
+    app: &AppHandle,
+    preview: &RenderNativeResult,
+    generation: u64,
+    final_render: Result<RenderNativeResult, String>,
+) {
+    let final_render = match final_render {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("[render] Final-branch check failed to run: {}", e);
+            return;
+        }
+    };
+
+    // A newer preview has started; its own check will report.
+    if app
+        .state::<PreviewCheckState>()
+        .generation
+        .load(Ordering::SeqCst)
+        != generation
+    {
+        return;
+    }
+
+    let warnings = compare_preview_and_final(preview, &final_render);
+    if warnings.is_empty() {
+        return;
+    }
+
+    eprintln!(
+        "[render] Preview and final render diverge: {}",
+        warnings.join("; ")
+    );
+    let _ = app.emit(
+        "render:preview-divergence",
+        PreviewDivergence {
+            preview_exit_code: preview.exit_code,
+            final_exit_code: final_render.exit_code,
+            final_stderr: final_render.stderr,
+            warnings,
+        },
+    );
+}
+
+/// Cancel every running render by killing its OpenSCAD process.
+#[tauri::command]
+pub async fn render_cancel(app: AppHandle) -> Result<(), String> {
+    let count = app.state::<RenderJobManager>().cancel_all();
+    if count > 0 {
+        eprintln!("[render] Cancelling {} running render(s)", count);
+    }
+    Ok(())
+}
+
+/// Size the render queue from the `render_concurrency` setting
+pub(crate) fn apply_render_concurrency(app: &AppHandle) {
+    let limit = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .render_concurrency;
+    app.state::<RenderQueue>()
+        .set_limit(limit.unwrap_or_else(default_concurrency));
+}
+
+#[tauri::command]
+pub fn get_render_limits(state: State<'_, SettingsState>) -> RenderLimitSettings {
+    state.settings.lock().unwrap().render_limits.clone()
+}
+
+/// Set the timeout and (Linux) memory limit for OpenSCAD runs; renders that
+/// are already running keep their limits
+#[tauri::command]
+pub fn set_render_limits(app: AppHandle, limits: RenderLimitSettings) -> Result<(), String> {
+    limits.validate()?;
+    let memory_limit_mb = limits.memory_limit_mb;
+    update_settings(&app, |settings| {
+        settings.render_limits = limits;
+        Ok(())
+    })?;
+    set_memory_limit(memory_limit_mb);
+    Ok(())
+}
+
+/// How many renders are running and waiting, and the concurrency limit
+#[tauri::command]
+pub fn get_render_queue_status(queue: State<'_, RenderQueue>) -> QueueStatus {
+    queue.status()
+}
+
+/// Limit how many renders run at once; `None` derives the limit from the
+/// CPU count. Waiting renders start as soon as the new limit allows.
+#[tauri::command]
+pub fn set_render_concurrency(app: AppHandle, limit: Option<usize>) -> Result<(), String> {
+    if limit.is_some_and(|limit| !(1..=MAX_CONCURRENCY).contains(&limit)) {
+        return Err(format!(
+            "Render concurrency must be between 1 and {MAX_CONCURRENCY}"
+        ));
+    }
+    update_settings(&app, |settings| {
+        settings.render_concurrency = limit;
+        Ok(())
+    })?;
+    apply_render_concurrency(&app);
+    Ok(())
+}
+
+/// Cancel one render job (as returned by `render_preview`), killing its
+/// OpenSCAD process and removing its temp files.
+#[tauri::command]
+pub fn cancel_render(app: AppHandle, job_id: String) -> Result<(), String> {
+    if app.state::<RenderJobManager>().cancel(&job_id) {
+        eprintln!("[render] Cancelling render job {}", job_id);
+        Ok(())
+    } else {
+        Err(format!("Render job {} is not running", job_id))
+    }
+}
+
+// ============================================================================
+// Process wait helper (without tokio — uses std threads)
+// ============================================================================
+
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+fn spawn_pipe_reader<R: Read + Send + 'static>(
+    pipe: Option<R>,
+) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+/// How a waited-on process ended
+pub(crate) enum WaitOutcome {
+    Exited(std::process::Output),
+    /// Killed because the timeout elapsed
+    TimedOut,
+    /// Killed because the cancellation flag was set
+    Cancelled,
+}
+
+impl WaitOutcome {
+    /// The process output, treating a timeout or cancellation as an error
+    pub(crate) fn into_output(
+        self,
+        label: &str,
+        timeout: Duration,
+    ) -> Result<std::process::Output, String> {
+        match self {
+            WaitOutcome::Exited(output) => Ok(output),
+            WaitOutcome::TimedOut => {
+                Err(format!("{} timed out after {}s", label, timeout.as_secs()))
+            }
+            WaitOutcome::Cancelled => Err(format!("{} cancelled", label)),
         }
-        Err(e) => Err(format!("Channel error waiting for OpenSCAD: {}", e)),
     }
 }
 
+/// Wait for `child`, collecting its output. The process is killed when
+/// `timeout` elapses or `cancelled` is set.
+pub(crate) fn tokio_timeout_wait(
+    mut child: std::process::Child,
+    timeout: Duration,
+    label: &str,
+    cancelled: Option<&AtomicBool>,
+) -> Result<WaitOutcome, String> {
+    // Drain the pipes on their own threads so a chatty process can't block
+    let stdout = spawn_pipe_reader(child.stdout.take());
+    let stderr = spawn_pipe_reader(child.stderr.take());
+    let start = Instant::now();
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(e) => {
+                let _ = child.kill();
+                return Err(format!("{} process error: {}", label, e));
+            }
+        }
+        let stopped = if cancelled.is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+            Some(WaitOutcome::Cancelled)
+        } else if start.elapsed() >= timeout {
+            Some(WaitOutcome::TimedOut)
+        } else {
+            None
+        };
+        if let Some(outcome) = stopped {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(outcome);
+        }
+        std::thread::sleep(WAIT_POLL_INTERVAL);
+    };
+
+    Ok(WaitOutcome::Exited(std::process::Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        create_render_workspace, normalize_relative_project_path, resolve_project_relative_path,
+        compare_preview_and_final, create_render_workspace, execute_render,
+        normalize_relative_project_path, resolve_parameter_file, resolve_project_relative_path,
+        with_preview_flag, RenderNativeResult,
     };
+    use crate::locate::temp_root;
+    use std::collections::HashMap;
     use std::fs;
     use std::path::PathBuf;
 
@@ -766,6 +1838,7 @@ mod tests {
             &Some("openscad/poly555.scad".into()),
             &Some(project_root.to_string_lossy().to_string()),
             &None,
+            &temp_root(),
         )
         .unwrap();
 
@@ -785,4 +1858,141 @@ mod tests {
         let _ = fs::remove_dir_all(workspace.temp_dir);
         let _ = fs::remove_dir_all(project_root);
     }
+
+    #[test]
+    fn create_render_workspace_without_project_holds_only_copies() {
+        let workspace = create_render_workspace(
+            "include <parts/gear.scad>",
+            "output.off",
+            &Some(HashMap::from([(
+                "parts/gear.scad".to_string(),
+                "cube(1);".to_string(),
+            )])),
+            &None,
+            &None,
+            &None,
+            &temp_root(),
+        )
+        .unwrap();
+
+        assert!(workspace.files_root.starts_with(&workspace.temp_dir));
+        assert_eq!(
+            workspace.input_path.parent(),
+            Some(workspace.files_root.as_path())
+        );
+        let mut files: Vec<_> = fs::read_dir(&workspace.files_root)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["input.scad", "parts"]);
+        assert!(workspace.project_temp_files.is_empty());
+
+        let _ = fs::remove_dir_all(workspace.temp_dir);
+    }
+
+    #[test]
+    fn resolve_parameter_file_prefers_unsaved_content() {
+        let project_root = create_temp_project_dir("parameter-file");
+        let working_dir = Some(project_root.to_string_lossy().to_string());
+        let mut aux = HashMap::new();
+        aux.insert("presets/sizes.json".to_string(), "{}".to_string());
+        let aux = Some(aux);
+
+        let workspace = create_render_workspace(
+            "cube(1);",
+            "output.stl",
+            &aux,
+            &None,
+            &working_dir,
+            &None,
+            &temp_root(),
+        )
+        .unwrap();
+
+        let unsaved = resolve_parameter_file(&workspace, "/presets/sizes.json", &aux).unwrap();
+        assert!(unsaved.starts_with(&workspace.temp_dir));
+        assert_eq!(fs::read_to_string(&unsaved).unwrap(), "{}");
+
+        let on_disk = resolve_parameter_file(&workspace, "other.json", &aux).unwrap();
+        assert_eq!(on_disk, project_root.join("other.json"));
+        assert!(resolve_parameter_file(&workspace, "../x.json", &aux).is_err());
+
+        for temp_file in workspace.project_temp_files {
+            let _ = fs::remove_file(temp_file);
+        }
+        let _ = fs::remove_dir_all(workspace.temp_dir);
+        let _ = fs::remove_dir_all(project_root);
+    }
+
+    #[test]
+    fn rejected_parameter_file_leaves_no_temp_input_in_the_project() {
+        let project_root = create_temp_project_dir("rejected-parameter-file");
+        let args = ["-p", "../x.json", "-o", "/output.stl", "/input.scad"].map(String::from);
+
+        let error = execute_render(
+            &project_root.join("missing-openscad"),
+            "cube(1);",
+            &args,
+            &None,
+            &None,
+            &Some(project_root.to_string_lossy().to_string()),
+            &None,
+            std::time::Duration::from_secs(5),
+            None,
+        )
+        .unwrap_err();
+
+        assert!(error.contains("escapes the workspace root"));
+        assert_eq!(fs::read_dir(&project_root).unwrap().count(), 0);
+        let _ = fs::remove_dir_all(project_root);
+    }
+
+    fn result(exit_code: i32, stderr: &str) -> RenderNativeResult {
+        RenderNativeResult {
+            output: Vec::new(),
+            stderr: stderr.to_string(),
+            exit_code,
+            duration_ms: 0,
+            safe_mode: false,
+            stale: false,
+            limit_exceeded: None,
+            job_id: None,
+        }
+    }
+
+    #[test]
+    fn with_preview_flag_replaces_existing_definition() {
+        let args: Vec<String> = ["/input.scad", "-D", "$preview=true", "-o", "/output.stl"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(
+            with_preview_flag(&args, false),
+            vec!["/input.scad", "-o", "/output.stl", "-D", "$preview=false"]
+        );
+    }
+
+    #[test]
+    fn compare_preview_and_final_reports_final_only_failures() {
+        let preview = result(0, "WARNING: shared warning\n");
+        let failing = result(1, "ERROR: Parser error in file input.scad, line 3\n");
+        let diverging = result(
+            0,
+            "WARNING: shared warning\nWARNING: Ignoring unknown variable 'x'\nCurrent top level object is empty.\n",
+        );
+
+        let warnings = compare_preview_and_final(&preview, &failing);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("Parser error"));
+
+        let warnings = compare_preview_and_final(&preview, &diverging);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("empty geometry"));
+        assert!(warnings[1].contains("unknown variable"));
+
+        assert!(compare_preview_and_final(&preview, &preview).is_empty());
+        assert!(compare_preview_and_final(&failing, &preview).is_empty());
+    }
 }
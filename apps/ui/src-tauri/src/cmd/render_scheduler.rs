@@ -0,0 +1,142 @@
+use crate::cmd::ai_tools::{test_compile, CompileSource};
+use crate::render::jobs::RenderJobManager;
+use crate::render::scheduler::{validate_settings, RenderScheduler, SkipReason};
+use crate::settings::{update_settings, RenderSchedulerSettings, SettingsState};
+use crate::types::DiagnosticSeverity;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// How often a render held for `only_when_idle` checks again
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Emitted when `schedule_render` queues a request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderScheduled {
+    pub document_id: Option<String>,
+    pub generation: u64,
+    pub delay_ms: u64,
+}
+
+/// Emitted when a scheduled request is dropped instead of rendered
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderSkipped {
+    pub document_id: Option<String>,
+    pub generation: u64,
+    pub reason: SkipReason,
+}
+
+/// Outcome of `schedule_render`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledRender {
+    pub generation: u64,
+    /// Why the request was dropped; `None` means render now
+    pub skipped: Option<SkipReason>,
+}
+
+#[tauri::command]
+pub fn get_render_scheduler_settings(state: State<'_, SettingsState>) -> RenderSchedulerSettings {
+    state.settings.lock().unwrap().render_scheduler.clone()
+}
+
+#[tauri::command]
+pub fn set_render_scheduler_settings(
+    app: AppHandle,
+    settings: RenderSchedulerSettings,
+) -> Result<(), String> {
+    validate_settings(&settings)?;
+    update_settings(&app, |current| {
+        current.render_scheduler = settings;
+        Ok(())
+    })
+}
+
+/// Schedule an automatic render of `code` and wait until it should run.
+/// The request waits out the debounce interval, and a newer request for the
+/// same `document_id` supersedes it (reason `superseded`). With
+/// `onlyWhenIdle` it also waits for running renders to finish; with
+/// `onlyWhenClean` code that doesn't parse is skipped. Emits
+/// `render:scheduled` now and `render:skipped` for dropped requests; the
+/// caller renders when the result isn't skipped.
+#[tauri::command]
+pub async fn schedule_render(
+    app: AppHandle,
+    document_id: Option<String>,
+    code: String,
+) -> Result<ScheduledRender, String> {
+    let settings = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .render_scheduler
+        .clone();
+    let key = document_id.clone().unwrap_or_default();
+    let generation = app.state::<RenderScheduler>().schedule(&key);
+    let _ = app.emit(
+        "render:scheduled",
+        RenderScheduled {
+            document_id: document_id.clone(),
+            generation,
+            delay_ms: settings.debounce_ms,
+        },
+    );
+
+    let skipped = tauri::async_runtime::spawn_blocking({
+        let app = app.clone();
+        move || {
+            let is_latest = || app.state::<RenderScheduler>().is_latest(&key, generation);
+
+            std::thread::sleep(Duration::from_millis(settings.debounce_ms));
+            while settings.only_when_idle
+                && is_latest()
+                && !app.state::<RenderJobManager>().is_idle()
+            {
+                std::thread::sleep(IDLE_POLL_INTERVAL);
+            }
+            if !is_latest() {
+                return Some(SkipReason::Superseded);
+            }
+            if settings.only_when_clean {
+                match test_compile(&app, CompileSource::Editor(Some(code)), true, None) {
+                    Ok(diagnostics)
+                        if diagnostics
+                            .iter()
+                            .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error) =>
+                    {
+                        return Some(SkipReason::SyntaxErrors);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        eprintln!("[render] Syntax check before scheduled render failed: {e}")
+                    }
+                }
+                // The check takes a moment; a newer edit may have arrived
+                if !is_latest() {
+                    return Some(SkipReason::Superseded);
+                }
+            }
+            None
+        }
+    })
+    .await
+    .map_err(|e| format!("Render scheduling task failed: {e}"))?;
+
+    if let Some(reason) = skipped {
+        let _ = app.emit(
+            "render:skipped",
+            RenderSkipped {
+                document_id,
+                generation,
+                reason,
+            },
+        );
+    }
+    Ok(ScheduledRender {
+        generation,
+        skipped,
+    })
+}
@@ -0,0 +1,56 @@
+use crate::safe_mode::canonical_project_path;
+use crate::settings::{update_settings, SafeModeSettings, SettingsState};
+use tauri::{AppHandle, State};
+
+/// Get the safe-mode settings, including trusted project folders
+#[tauri::command]
+pub fn get_safe_mode_settings(state: State<'_, SettingsState>) -> Result<SafeModeSettings, String> {
+    Ok(state.settings.lock().unwrap().safe_mode.clone())
+}
+
+/// Turn safe-mode rendering for untrusted projects on or off
+#[tauri::command]
+pub fn set_safe_mode_enabled(app: AppHandle, enabled: bool) -> Result<SafeModeSettings, String> {
+    update_settings(&app, |settings| {
+        settings.safe_mode.enabled = enabled;
+        Ok(settings.safe_mode.clone())
+    })
+}
+
+/// Check whether a project folder is trusted
+#[tauri::command]
+pub fn is_project_trusted(path: String, state: State<'_, SettingsState>) -> Result<bool, String> {
+    let settings = state.settings.lock().unwrap();
+    Ok(settings
+        .safe_mode
+        .trusted_projects
+        .contains(&canonical_project_path(&path)))
+}
+
+/// Trust a project folder so it renders with full access
+#[tauri::command]
+pub fn trust_project(app: AppHandle, path: String) -> Result<SafeModeSettings, String> {
+    if !std::path::Path::new(&path).is_dir() {
+        return Err(format!("Not a folder: {path}"));
+    }
+    update_settings(&app, |settings| {
+        settings
+            .safe_mode
+            .trusted_projects
+            .insert(canonical_project_path(&path));
+        Ok(settings.safe_mode.clone())
+    })
+}
+
+/// Revoke trust for a project folder
+#[tauri::command]
+pub fn untrust_project(app: AppHandle, path: String) -> Result<SafeModeSettings, String> {
+    update_settings(&app, |settings| {
+        let canonical = canonical_project_path(&path);
+        settings
+            .safe_mode
+            .trusted_projects
+            .retain(|trusted| *trusted != canonical && *trusted != path);
+        Ok(settings.safe_mode.clone())
+    })
+}
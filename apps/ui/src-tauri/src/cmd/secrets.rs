@@ -0,0 +1,114 @@
+use crate::secrets::{
+    migrate_secrets, validate_key_name, KeyStorageBackend, KeychainStore, SecretStore,
+    API_KEY_NAMES, STORE_FILE_NAME,
+};
+use crate::settings::{update_settings, SettingsState};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State, Wry};
+use tauri_plugin_store::{Store, StoreExt};
+
+/// The plaintext `secrets.json` store
+struct FileStore(Arc<Store<Wry>>);
+
+impl FileStore {
+    fn open(app: &AppHandle) -> Result<Self, String> {
+        app.store(STORE_FILE_NAME)
+            .map(FileStore)
+            .map_err(|e| format!("Failed to open {STORE_FILE_NAME}: {e}"))
+    }
+
+    fn save(&self) -> Result<(), String> {
+        self.0
+            .save()
+            .map_err(|e| format!("Failed to save {STORE_FILE_NAME}: {e}"))
+    }
+}
+
+impl SecretStore for FileStore {
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        Ok(self
+            .0
+            .get(name)
+            .and_then(|value| value.as_str().map(str::to_string)))
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), String> {
+        self.0.set(name, value);
+        self.save()
+    }
+
+    fn delete(&self, name: &str) -> Result<(), String> {
+        if self.0.delete(name) {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+fn open_store(app: &AppHandle, backend: KeyStorageBackend) -> Result<Box<dyn SecretStore>, String> {
+    Ok(match backend {
+        KeyStorageBackend::File => Box::new(FileStore::open(app)?),
+        KeyStorageBackend::Keychain => Box::new(KeychainStore),
+    })
+}
+
+pub(crate) fn current_store(app: &AppHandle) -> Result<Box<dyn SecretStore>, String> {
+    let backend = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .key_storage;
+    open_store(app, backend)
+}
+
+#[tauri::command]
+pub fn get_key_storage_backend(state: State<'_, SettingsState>) -> KeyStorageBackend {
+    state.settings.lock().unwrap().key_storage
+}
+
+/// Switch where API keys are kept, moving existing keys across; returns how
+/// many keys moved
+#[tauri::command]
+pub fn set_key_storage_backend(
+    app: AppHandle,
+    backend: KeyStorageBackend,
+) -> Result<usize, String> {
+    update_settings(&app, |settings| {
+        if settings.key_storage == backend {
+            return Ok(0);
+        }
+        let from = open_store(&app, settings.key_storage)?;
+        let to = open_store(&app, backend)?;
+        let moved = migrate_secrets(&*from, &*to, API_KEY_NAMES)?;
+        eprintln!("[secrets] Moved {moved} API keys to the {backend}");
+        settings.key_storage = backend;
+        Ok(moved)
+    })
+}
+
+/// Every stored API key, keyed by provider
+#[tauri::command]
+pub fn get_api_keys(app: AppHandle) -> Result<BTreeMap<String, String>, String> {
+    let store = current_store(&app)?;
+    let mut keys = BTreeMap::new();
+    for &name in API_KEY_NAMES {
+        if let Some(value) = store.get(name)? {
+            keys.insert(name.to_string(), value);
+        }
+    }
+    Ok(keys)
+}
+
+#[tauri::command]
+pub fn set_api_key(app: AppHandle, provider: String, key: String) -> Result<(), String> {
+    validate_key_name(&provider)?;
+    current_store(&app)?.set(&provider, &key)
+}
+
+#[tauri::command]
+pub fn delete_api_key(app: AppHandle, provider: String) -> Result<(), String> {
+    validate_key_name(&provider)?;
+    current_store(&app)?.delete(&provider)
+}
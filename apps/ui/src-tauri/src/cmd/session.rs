@@ -0,0 +1,125 @@
+use crate::emit_to_focused_window;
+use crate::menu::rebuild_app_menu;
+use crate::session::{
+    parse_recent_menu_id, record_recent, RecentKind, RecentProject, SessionSnapshot,
+    CLEAR_RECENTS_MENU_ID, RECENT_PROJECTS_KEY, SESSION_KEY, STORE_FILE_NAME,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_store::{Store, StoreExt};
+
+/// Session from the previous run, handed to the first window that asks
+#[derive(Default)]
+pub struct SessionState {
+    launch: Mutex<Option<SessionSnapshot>>,
+}
+
+fn session_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, String> {
+    app.store(STORE_FILE_NAME)
+        .map_err(|e| format!("Failed to open {STORE_FILE_NAME}: {e}"))
+}
+
+fn read_key<T: DeserializeOwned + Default>(store: &Store<Wry>, key: &str) -> T {
+    store
+        .get(key)
+        .and_then(|value| {
+            serde_json::from_value(value)
+                .inspect_err(|e| eprintln!("[session] Ignoring unreadable {key}: {e}"))
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+fn write_key(store: &Store<Wry>, key: &str, value: impl Serialize) -> Result<(), String> {
+    let value =
+        serde_json::to_value(value).map_err(|e| format!("Failed to serialize {key}: {e}"))?;
+    store.set(key, value);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save {STORE_FILE_NAME}: {e}"))
+}
+
+/// Recent projects, most recent first
+pub(crate) fn recent_projects(app: &AppHandle) -> Vec<RecentProject> {
+    session_store(app)
+        .map(|store| read_key(&store, RECENT_PROJECTS_KEY))
+        .unwrap_or_default()
+}
+
+fn set_recent_projects(app: &AppHandle, recents: &[RecentProject]) -> Result<(), String> {
+    write_key(&*session_store(app)?, RECENT_PROJECTS_KEY, recents)?;
+    rebuild_app_menu(app)
+}
+
+/// Read the previous session at startup so `restore_session` can hand it out
+pub(crate) fn load_launch_session(app: &AppHandle) {
+    let Ok(store) = session_store(app) else {
+        return;
+    };
+    let snapshot: SessionSnapshot = read_key(&store, SESSION_KEY);
+    *app.state::<SessionState>().launch.lock().unwrap() =
+        snapshot.restorable(|path| Path::new(path).exists());
+}
+
+/// Handle Open Recent menu items. Returns `true` when the event was consumed.
+pub(crate) fn handle_recent_menu_event(app: &AppHandle, id: &str) -> bool {
+    if id == CLEAR_RECENTS_MENU_ID {
+        if let Err(e) = clear_recents(app.clone()) {
+            eprintln!("[session] {e}");
+        }
+        return true;
+    }
+    let Some(index) = parse_recent_menu_id(id) else {
+        return false;
+    };
+    if let Some(recent) = recent_projects(app).into_iter().nth(index) {
+        emit_to_focused_window(app, "menu:file:open_recent", recent);
+    }
+    true
+}
+
+/// Recently opened files and folders, most recent first
+#[tauri::command]
+pub fn get_recent_projects(app: AppHandle) -> Vec<RecentProject> {
+    recent_projects(&app)
+}
+
+/// Record that a file or folder was opened
+#[tauri::command]
+pub fn add_recent_project(
+    app: AppHandle,
+    path: String,
+    kind: Option<RecentKind>,
+) -> Result<Vec<RecentProject>, String> {
+    let mut recents = recent_projects(&app);
+    record_recent(
+        &mut recents,
+        &path,
+        kind.unwrap_or_default(),
+        chrono::Utc::now().timestamp_millis(),
+    );
+    set_recent_projects(&app, &recents)?;
+    Ok(recents)
+}
+
+/// Forget every recent project
+#[tauri::command]
+pub fn clear_recents(app: AppHandle) -> Result<(), String> {
+    set_recent_projects(&app, &[])
+}
+
+/// Persist the current session for the next launch
+#[tauri::command]
+pub fn save_session(app: AppHandle, snapshot: SessionSnapshot) -> Result<(), String> {
+    write_key(&*session_store(&app)?, SESSION_KEY, snapshot)
+}
+
+/// The previous session, minus files that no longer exist. Returned once per
+/// launch so later windows open empty.
+#[tauri::command]
+pub fn restore_session(app: AppHandle) -> Option<SessionSnapshot> {
+    app.state::<SessionState>().launch.lock().unwrap().take()
+}
@@ -0,0 +1,151 @@
+use crate::cmd::installer::build_binary_path;
+use crate::cmd::render::{apply_render_concurrency, init_binary, OpenScadBinaryState};
+use crate::cmd::secrets::current_store;
+use crate::menu::rebuild_app_menu;
+use crate::process_limits::set_memory_limit;
+use crate::secrets::{validate_key_name, API_KEY_NAMES};
+use crate::settings::{update_settings, AppSettings, SettingsState};
+use crate::settings_transfer::{
+    list_profiles, merge_imported, parse_bundle, profile_name, read_profiles, write_profiles,
+    SettingsBundle, SettingsProfile, PROFILES_FILE_NAME,
+};
+use crate::tray::sync_tray;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+fn profiles_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(PROFILES_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app config dir: {e}"))
+}
+
+fn current_bundle(app: &AppHandle, ui: serde_json::Value) -> SettingsBundle {
+    let settings = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .clone();
+    SettingsBundle::new(settings, ui, chrono::Utc::now().timestamp_millis())
+}
+
+/// Replace the backend settings with `imported` and bring running state
+/// (menu, tray, render limits, OpenSCAD build) in line with them
+fn apply_settings(app: &AppHandle, imported: AppSettings) -> Result<(), String> {
+    let settings = update_settings(app, |current| {
+        let mut next = merge_imported(current, imported);
+        // A downloaded build from another machine may not exist here
+        if next
+            .openscad
+            .active
+            .as_deref()
+            .is_some_and(|id| build_binary_path(app, id).is_none())
+        {
+            next.openscad.active = None;
+        }
+        *current = next;
+        Ok(current.clone())
+    })?;
+
+    rebuild_app_menu(app)?;
+    sync_tray(app, &settings.tray)?;
+    set_memory_limit(settings.render_limits.memory_limit_mb);
+    apply_render_concurrency(app);
+    if let Err(e) = init_binary(app, &app.state::<OpenScadBinaryState>()) {
+        eprintln!("[settings] OpenSCAD not available after import: {e}");
+    }
+    Ok(())
+}
+
+/// Write the current settings to `path`. `ui` carries the frontend's own
+/// preferences; secrets are only included with `include_secrets`.
+#[tauri::command]
+pub fn export_settings(
+    app: AppHandle,
+    path: String,
+    ui: serde_json::Value,
+    include_secrets: Option<bool>,
+) -> Result<(), String> {
+    let mut bundle = current_bundle(&app, ui);
+    if include_secrets.unwrap_or(false) {
+        let store = current_store(&app)?;
+        for &name in API_KEY_NAMES {
+            if let Some(key) = store.get(name)? {
+                bundle.api_keys.insert(name.to_string(), key);
+            }
+        }
+    } else {
+        bundle.strip_secrets();
+    }
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize settings: {e}"))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write {path}: {e}"))
+}
+
+/// Apply settings exported by `export_settings`, storing any API keys they
+/// carry. Returns the frontend preferences for the frontend to apply.
+#[tauri::command]
+pub fn import_settings(app: AppHandle, path: String) -> Result<serde_json::Value, String> {
+    let json = fs::read_to_string(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    let bundle = parse_bundle(&json)?;
+    for name in bundle.api_keys.keys() {
+        validate_key_name(name)?;
+    }
+    apply_settings(&app, bundle.app)?;
+    if !bundle.api_keys.is_empty() {
+        let store = current_store(&app)?;
+        for (name, key) in &bundle.api_keys {
+            store.set(name, key)?;
+        }
+    }
+    Ok(bundle.ui)
+}
+
+/// Saved settings profiles, by name
+#[tauri::command]
+pub fn list_settings_profiles(app: AppHandle) -> Result<Vec<SettingsProfile>, String> {
+    Ok(list_profiles(&read_profiles(&profiles_path(&app)?)))
+}
+
+/// Save the current settings as profile `name`, replacing one with that name
+#[tauri::command]
+pub fn save_settings_profile(
+    app: AppHandle,
+    name: String,
+    ui: serde_json::Value,
+) -> Result<Vec<SettingsProfile>, String> {
+    let name = profile_name(&name)?;
+    let path = profiles_path(&app)?;
+    let mut bundle = current_bundle(&app, ui);
+    bundle.strip_secrets();
+    let mut profiles = read_profiles(&path);
+    profiles.insert(name, bundle);
+    write_profiles(&path, &profiles)?;
+    Ok(list_profiles(&profiles))
+}
+
+/// Switch to profile `name`; returns its frontend preferences
+#[tauri::command]
+pub fn apply_settings_profile(app: AppHandle, name: String) -> Result<serde_json::Value, String> {
+    let bundle = read_profiles(&profiles_path(&app)?)
+        .remove(name.trim())
+        .ok_or_else(|| format!("No settings profile named {name}"))?;
+    apply_settings(&app, bundle.app)?;
+    Ok(bundle.ui)
+}
+
+#[tauri::command]
+pub fn delete_settings_profile(
+    app: AppHandle,
+    name: String,
+) -> Result<Vec<SettingsProfile>, String> {
+    let path = profiles_path(&app)?;
+    let mut profiles = read_profiles(&path);
+    if profiles.remove(name.trim()).is_none() {
+        return Err(format!("No settings profile named {name}"));
+    }
+    write_profiles(&path, &profiles)?;
+    Ok(list_profiles(&profiles))
+}
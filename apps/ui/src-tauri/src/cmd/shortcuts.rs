@@ -0,0 +1,209 @@
+use crate::cmd::actions::{action_title, default_accelerator, effective_accelerator};
+use crate::menu::{menu_action_ids, rebuild_app_menu};
+use crate::settings::{update_settings, SettingsState};
+/**
+ * Keyboard shortcut configuration
+ *
+ * Users can override the accelerator of any menu action. Overrides are
+ * persisted in the app settings and applied by rebuilding the native menu.
+ */
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub action_id: String,
+    pub title: String,
+    pub accelerator: Option<String>,
+    pub default_accelerator: Option<String>,
+    pub is_custom: bool,
+}
+
+const MODIFIERS: &[&str] = &[
+    "cmdorctrl",
+    "commandorcontrol",
+    "cmd",
+    "command",
+    "super",
+    "ctrl",
+    "control",
+    "alt",
+    "option",
+    "shift",
+];
+
+const NAMED_KEYS: &[&str] = &[
+    "enter",
+    "return",
+    "space",
+    "tab",
+    "backspace",
+    "delete",
+    "escape",
+    "esc",
+    "up",
+    "down",
+    "left",
+    "right",
+    "home",
+    "end",
+    "pageup",
+    "pagedown",
+    "insert",
+    "plus",
+    "minus",
+    "comma",
+    "period",
+    "slash",
+    "backslash",
+    "semicolon",
+    "quote",
+    "backquote",
+    "bracketleft",
+    "bracketright",
+];
+
+fn is_valid_key(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    if key.chars().count() == 1 {
+        return key.chars().all(|c| c.is_ascii_graphic());
+    }
+    if let Some(number) = lower.strip_prefix('f') {
+        if let Ok(n) = number.parse::<u8>() {
+            return (1..=24).contains(&n);
+        }
+    }
+    NAMED_KEYS.contains(&lower.as_str())
+}
+
+/// Validate an accelerator string such as `CmdOrCtrl+Shift+R`.
+fn validate_accelerator(accelerator: &str) -> Result<String, String> {
+    let trimmed = accelerator.trim();
+    let parts: Vec<&str> = trimmed.split('+').map(str::trim).collect();
+    let Some((key, modifiers)) = parts.split_last() else {
+        return Err("Shortcut cannot be empty".to_string());
+    };
+
+    for modifier in modifiers {
+        if !MODIFIERS.contains(&modifier.to_ascii_lowercase().as_str()) {
+            return Err(format!("Unknown modifier `{modifier}` in `{trimmed}`"));
+        }
+    }
+
+    if !is_valid_key(key) {
+        return Err(format!("Unsupported key `{key}` in `{trimmed}`"));
+    }
+
+    Ok(parts.join("+"))
+}
+
+fn normalized(accelerator: &str) -> String {
+    accelerator.to_ascii_lowercase().replace(' ', "")
+}
+
+fn collect_bindings(app: &AppHandle) -> Vec<ShortcutBinding> {
+    let settings = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .clone();
+    menu_action_ids()
+        .map(|id| ShortcutBinding {
+            action_id: id.to_string(),
+            title: action_title(id).unwrap_or(id).to_string(),
+            accelerator: effective_accelerator(&settings, id),
+            default_accelerator: default_accelerator(id).map(str::to_string),
+            is_custom: settings.shortcuts.contains_key(id),
+        })
+        .collect()
+}
+
+/// Get the effective shortcut for every menu action
+#[tauri::command]
+pub fn get_shortcuts(app: AppHandle) -> Result<Vec<ShortcutBinding>, String> {
+    Ok(collect_bindings(&app))
+}
+
+/// Set (or clear, with `None`) the shortcut for a menu action
+#[tauri::command]
+pub fn set_shortcut(
+    app: AppHandle,
+    action_id: String,
+    accelerator: Option<String>,
+) -> Result<Vec<ShortcutBinding>, String> {
+    if !menu_action_ids().any(|id| id == action_id) {
+        return Err(format!(
+            "Action `{action_id}` cannot be bound to a shortcut"
+        ));
+    }
+
+    let accelerator = accelerator
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| validate_accelerator(&value))
+        .transpose()?;
+
+    update_settings(&app, |settings| {
+        if let Some(accelerator) = &accelerator {
+            let conflict = menu_action_ids().find(|id| {
+                *id != action_id
+                    && effective_accelerator(settings, id)
+                        .is_some_and(|other| normalized(&other) == normalized(accelerator))
+            });
+            if let Some(conflict) = conflict {
+                return Err(format!(
+                    "`{accelerator}` is already used by \"{}\"",
+                    action_title(conflict).unwrap_or(conflict)
+                ));
+            }
+        }
+
+        if accelerator.as_deref() == default_accelerator(&action_id) {
+            settings.shortcuts.remove(&action_id);
+        } else {
+            settings.shortcuts.insert(action_id.clone(), accelerator);
+        }
+        Ok(())
+    })?;
+
+    rebuild_app_menu(&app)?;
+    Ok(collect_bindings(&app))
+}
+
+/// Restore every shortcut to its default
+#[tauri::command]
+pub fn reset_shortcuts(app: AppHandle) -> Result<Vec<ShortcutBinding>, String> {
+    update_settings(&app, |settings| {
+        settings.shortcuts.clear();
+        Ok(())
+    })?;
+    rebuild_app_menu(&app)?;
+    Ok(collect_bindings(&app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_accelerator;
+
+    #[test]
+    fn validate_accelerator_accepts_modifiers_and_function_keys() {
+        assert_eq!(
+            validate_accelerator(" CmdOrCtrl + Shift + R ").unwrap(),
+            "CmdOrCtrl+Shift+R"
+        );
+        assert!(validate_accelerator("F12").is_ok());
+        assert!(validate_accelerator("Alt+Enter").is_ok());
+    }
+
+    #[test]
+    fn validate_accelerator_rejects_unknown_modifiers_and_keys() {
+        assert!(validate_accelerator("Hyper+R")
+            .unwrap_err()
+            .contains("Unknown modifier"));
+        assert!(validate_accelerator("CmdOrCtrl+F25")
+            .unwrap_err()
+            .contains("Unsupported key"));
+        assert!(validate_accelerator("CmdOrCtrl+").is_err());
+    }
+}
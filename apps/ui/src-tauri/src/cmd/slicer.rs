@@ -0,0 +1,210 @@
+use crate::cmd::render::{execute_render, render_policy, RenderPolicy};
+use crate::cmd::sweep::first_error_line;
+use crate::cmd::{EditorState, OpenScadBinaryState};
+use crate::settings::{update_settings, SettingsState, SlicerSettings};
+use crate::slicer::{
+    detect_slicers, resolve_slicer, slicer_command, Slicer, MODEL_FORMATS, SLICERS,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, State};
+
+/// Exports older than this are removed on the next hand-off; slicers read
+/// the file after launching, so it can't be deleted right away
+const STALE_EXPORT_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlicerStatus {
+    pub settings: SlicerSettings,
+    pub detected: Vec<Slicer>,
+    /// Slicer "Send to slicer" would use
+    pub active: Option<Slicer>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlicerHandoff {
+    pub slicer: Slicer,
+    pub model_path: String,
+}
+
+fn slicer_status(settings: SlicerSettings) -> SlicerStatus {
+    let detected = detect_slicers();
+    SlicerStatus {
+        active: resolve_slicer(&settings, &detected),
+        settings,
+        detected,
+    }
+}
+
+/// Installed slicers and the one models are sent to
+#[tauri::command]
+pub async fn get_slicer_settings(state: State<'_, SettingsState>) -> Result<SlicerStatus, String> {
+    let settings = state.settings.lock().unwrap().slicer.clone();
+    tauri::async_runtime::spawn_blocking(move || slicer_status(settings))
+        .await
+        .map_err(|e| format!("Slicer detection failed: {e}"))
+}
+
+/// Choose the slicer (a known ID), override its executable and the export
+/// format. `None` clears a field back to auto-detection.
+#[tauri::command]
+pub fn set_slicer(
+    app: AppHandle,
+    slicer: Option<String>,
+    executable: Option<String>,
+    format: Option<String>,
+) -> Result<SlicerStatus, String> {
+    if let Some(id) = slicer.as_deref() {
+        if !SLICERS.iter().any(|app| app.id == id) {
+            return Err(format!("Unknown slicer: {id}"));
+        }
+    }
+    let executable = executable.filter(|path| !path.trim().is_empty());
+    if let Some(path) = executable.as_deref() {
+        if !Path::new(path.trim()).exists() {
+            return Err(format!("Slicer not found: {path}"));
+        }
+    }
+    if let Some(format) = format.as_deref() {
+        if !MODEL_FORMATS.contains(&format) {
+            return Err(format!("Unsupported model format: {format}"));
+        }
+    }
+    let settings = update_settings(&app, |settings| {
+        settings.slicer = SlicerSettings {
+            slicer,
+            executable,
+            format,
+        };
+        Ok(settings.slicer.clone())
+    })?;
+    Ok(slicer_status(settings))
+}
+
+/// File name for the exported model, from the active project file
+fn model_name(editor_state: &EditorState) -> String {
+    let name: String = editor_state
+        .project
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|project| {
+            Path::new(&project.active_file)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' ' | '.'))
+        .collect();
+    match name.trim() {
+        "" => "model".to_string(),
+        name => name.to_string(),
+    }
+}
+
+fn remove_stale_exports(root: &Path) {
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > STALE_EXPORT_AGE);
+        if stale {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// Render `code` to `<temp>/openscad-studio-slicer/<id>/<name>.<format>`
+fn export_model(
+    binary_path: &Path,
+    code: &str,
+    policy: &RenderPolicy,
+    name: &str,
+    format: &str,
+) -> Result<PathBuf, String> {
+    let args = vec![
+        "/input.scad".to_string(),
+        "-o".to_string(),
+        format!("/output.{format}"),
+    ];
+    let result = execute_render(
+        binary_path,
+        code,
+        &args,
+        &None,
+        &None,
+        &policy.working_dir,
+        &policy.library_paths,
+        policy.timeout,
+        None,
+    )?;
+    if result.exit_code != 0 || result.output.is_empty() {
+        return Err(first_error_line(&result.stderr)
+            .unwrap_or_else(|| "The design did not produce 3D geometry".to_string()));
+    }
+
+    let root = std::env::temp_dir().join("openscad-studio-slicer");
+    remove_stale_exports(&root);
+    let dir = root.join(uuid::Uuid::new_v4().to_string());
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create temp dir: {e}"))?;
+    let path = dir.join(format!("{name}.{format}"));
+    fs::write(&path, &result.output)
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Export the design (defaults to the current editor code) and open it in
+/// the configured or detected slicer
+#[tauri::command]
+pub async fn send_to_slicer(
+    app: AppHandle,
+    code: Option<String>,
+    working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    binary_state: State<'_, OpenScadBinaryState>,
+    editor_state: State<'_, EditorState>,
+    settings_state: State<'_, SettingsState>,
+) -> Result<SlicerHandoff, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    let working_dir = working_dir.or_else(|| editor_state.working_dir.lock().unwrap().clone());
+    let policy = render_policy(&app, &code, &None, &working_dir, &library_paths)?;
+    let name = model_name(&editor_state);
+    let settings = settings_state.settings.lock().unwrap().slicer.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let slicer = resolve_slicer(&settings, &detect_slicers()).ok_or(
+            "No slicer found. Install PrusaSlicer, UltiMaker Cura or Bambu Studio, or choose a slicer executable in settings.",
+        )?;
+        let model = export_model(&binary_path, &code, &policy, &name, &slicer.format)?;
+        let mut child = slicer_command(Path::new(&slicer.path), &model)
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {e}", slicer.name))?;
+        // Reap the slicer when it exits; it outlives the hand-off
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+        eprintln!("[slicer] Sent {} to {}", model.display(), slicer.name);
+        Ok(SlicerHandoff {
+            slicer,
+            model_path: model.to_string_lossy().to_string(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Slicer hand-off task failed: {e}"))?
+}
@@ -0,0 +1,54 @@
+use crate::cmd::history::save_history;
+use crate::cmd::EditorState;
+use crate::history::HistoryState;
+use crate::snippets::{snippet, Snippet, SnippetSearchResult};
+use crate::types::ChangeType;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertedSnippet {
+    pub snippet: Snippet,
+    /// Checkpoint holding the code from before the insert
+    pub checkpoint_id: String,
+}
+
+/// Search the bundled snippet library, best matches first; an empty query
+/// lists every snippet
+#[tauri::command]
+pub fn search_snippets(
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SnippetSearchResult>, String> {
+    Ok(crate::snippets::search_snippets(&query, limit))
+}
+
+/// Checkpoint the current code, then ask the editor to insert snippet `id`
+/// at the cursor
+#[tauri::command]
+pub fn insert_snippet(
+    app: AppHandle,
+    id: String,
+    editor_state: State<'_, EditorState>,
+    history_state: State<'_, HistoryState>,
+) -> Result<InsertedSnippet, String> {
+    let snippet = snippet(&id).ok_or_else(|| format!("Unknown snippet: {id}"))?;
+    let code = editor_state.current_code.lock().unwrap().clone();
+    let diagnostics = editor_state.diagnostics.lock().unwrap().clone();
+    let checkpoint_id = history_state.history.lock().unwrap().create_checkpoint(
+        code,
+        diagnostics,
+        format!("Before inserting snippet: {}", snippet.title),
+        ChangeType::User,
+    );
+    if let Err(e) = save_history(&app) {
+        eprintln!("[history] {e}");
+    }
+
+    let _ = app.emit("editor:insert-snippet", &snippet.code);
+    Ok(InsertedSnippet {
+        snippet: snippet.clone(),
+        checkpoint_id,
+    })
+}
@@ -0,0 +1,157 @@
+use crate::cmd::render::tokio_timeout_wait;
+use crate::settings::{update_settings, SettingsState, StepExportSettings};
+use crate::step_export::{
+    capability, converter_command, resolve_converter, StepCapability, FREECAD_SCRIPT,
+    STEP_EXPORT_TIMEOUT,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepExportProgress {
+    /// detecting | converting | done | failed
+    pub stage: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepExportResult {
+    pub output_path: String,
+    pub converter: String,
+    pub duration_ms: u64,
+}
+
+fn progress(app: &AppHandle, stage: &str, message: impl Into<String>) {
+    let _ = app.emit(
+        "step-export:progress",
+        StepExportProgress {
+            stage: stage.to_string(),
+            message: message.into(),
+        },
+    );
+}
+
+/// Report whether STEP export is available and which converter it uses
+#[tauri::command]
+pub fn get_step_export_capability(state: State<'_, SettingsState>) -> StepCapability {
+    let settings = state.settings.lock().unwrap().step_export.clone();
+    capability(resolve_converter(&settings).as_ref())
+}
+
+/// Configure (or clear, with `None`) the external STEP converter command
+#[tauri::command]
+pub fn set_step_converter(
+    app: AppHandle,
+    command: Option<String>,
+    args: Option<Vec<String>>,
+) -> Result<StepCapability, String> {
+    let settings = update_settings(&app, |settings| {
+        settings.step_export = StepExportSettings {
+            converter_command: command.filter(|c| !c.trim().is_empty()),
+            converter_args: args.unwrap_or_default(),
+        };
+        Ok(settings.step_export.clone())
+    })?;
+    Ok(capability(resolve_converter(&settings).as_ref()))
+}
+
+/// Convert an exported STL mesh into a STEP file with the external converter
+#[tauri::command]
+pub async fn export_step(
+    app: AppHandle,
+    input_path: String,
+    output_path: String,
+) -> Result<StepExportResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = run_step_export(&app, &input_path, &output_path);
+        if let Err(e) = &result {
+            progress(&app, "failed", e.clone());
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("STEP export task failed: {e}"))?
+}
+
+fn run_step_export(
+    app: &AppHandle,
+    input_path: &str,
+    output_path: &str,
+) -> Result<StepExportResult, String> {
+    progress(app, "detecting", "Looking for a STEP converter");
+    let settings = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .step_export
+        .clone();
+    let converter = resolve_converter(&settings);
+    let report = capability(converter.as_ref());
+    let Some(converter) = converter else {
+        return Err(report.message);
+    };
+    if !Path::new(input_path).is_file() {
+        return Err(format!("Mesh to convert not found: {input_path}"));
+    }
+
+    let work_dir = std::env::temp_dir()
+        .join("openscad-studio-step")
+        .join(uuid::Uuid::new_v4().to_string());
+    fs::create_dir_all(&work_dir).map_err(|e| format!("Failed to create temp dir: {e}"))?;
+    let script_path = work_dir.join("stl_to_step.py");
+    let temp_output = work_dir.join("output.step");
+    let outcome = fs::write(&script_path, FREECAD_SCRIPT)
+        .map_err(|e| format!("Failed to write converter script: {e}"))
+        .and_then(|_| {
+            let converter_name = report.converter.clone().unwrap_or_default();
+            progress(
+                app,
+                "converting",
+                format!("Converting with {converter_name}"),
+            );
+            let start = Instant::now();
+            let child = converter_command(
+                &converter,
+                Path::new(input_path),
+                &temp_output,
+                &script_path,
+            )
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start {converter_name}: {e}"))?;
+            let output = tokio_timeout_wait(child, STEP_EXPORT_TIMEOUT, "STEP converter", None)?
+                .into_output("STEP converter", STEP_EXPORT_TIMEOUT)?;
+            if !output.status.success() || !temp_output.is_file() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let detail = [stderr.trim(), stdout.trim()]
+                    .into_iter()
+                    .find(|text| !text.is_empty())
+                    .unwrap_or("no output file was written");
+                return Err(format!("STEP conversion failed: {detail}"));
+            }
+            fs::copy(&temp_output, output_path)
+                .map_err(|e| format!("Failed to write {output_path}: {e}"))?;
+            Ok(StepExportResult {
+                output_path: output_path.to_string(),
+                converter: converter_name,
+                duration_ms: start.elapsed().as_millis() as u64,
+            })
+        });
+    let _ = fs::remove_dir_all(&work_dir);
+
+    let result = outcome?;
+    eprintln!(
+        "[step] Exported {} in {}ms via {}",
+        result.output_path, result.duration_ms, result.converter
+    );
+    progress(app, "done", format!("Saved {}", result.output_path));
+    Ok(result)
+}
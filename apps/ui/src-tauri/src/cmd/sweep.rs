@@ -0,0 +1,196 @@
+use crate::camera::{png_args, CameraSpec};
+use crate::cmd::render::{execute_render, render_policy, RenderPolicy};
+use crate::cmd::{EditorState, OpenScadBinaryState};
+use crate::sweep::{
+    compose_contact_sheet, decode_png, encode_png, sheet_columns, DEFAULT_TILE_SIZE,
+    MAX_SWEEP_VALUES,
+};
+use crate::variables::override_args;
+use base64::Engine;
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Previews rendered at the same time
+const SWEEP_CONCURRENCY: usize = 4;
+const MAX_TILE_SIZE: u32 = 512;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepTile {
+    pub value: String,
+    pub success: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepResult {
+    /// Base64-encoded PNG contact sheet
+    pub image: String,
+    pub mime_type: String,
+    pub columns: u32,
+    pub tile_size: u32,
+    /// One entry per value, in sheet order
+    pub tiles: Vec<SweepTile>,
+}
+
+pub(crate) fn first_error_line(stderr: &str) -> Option<String> {
+    stderr
+        .lines()
+        .find(|line| line.trim_start().starts_with("ERROR:"))
+        .map(|line| line.trim().to_string())
+}
+
+/// Render one preview per value of `name` and tile them into a contact sheet
+pub(crate) fn run_sweep(
+    binary_path: &Path,
+    code: &str,
+    name: &str,
+    values: &[String],
+    policy: &RenderPolicy,
+    tile_size: Option<u32>,
+    camera: Option<&CameraSpec>,
+) -> Result<SweepResult, String> {
+    if values.is_empty() {
+        return Err("Provide at least one value to sweep.".into());
+    }
+    if values.len() > MAX_SWEEP_VALUES {
+        return Err(format!(
+            "Too many values ({}); a sweep is limited to {MAX_SWEEP_VALUES}.",
+            values.len()
+        ));
+    }
+    let tile_size = tile_size
+        .unwrap_or(DEFAULT_TILE_SIZE)
+        .clamp(32, MAX_TILE_SIZE);
+
+    let mut overrides = std::collections::HashMap::new();
+    let mut jobs = Vec::with_capacity(values.len());
+    for value in values {
+        overrides.clear();
+        overrides.insert(name.to_string(), value.clone());
+        let mut args = png_args(tile_size, camera)?;
+        args.extend(override_args(code, &overrides)?);
+        jobs.push(args);
+    }
+
+    eprintln!(
+        "[sweep] Rendering {} previews of `{}` at {}px",
+        jobs.len(),
+        name,
+        tile_size
+    );
+
+    let mut results = Vec::with_capacity(jobs.len());
+    for chunk in jobs.chunks(SWEEP_CONCURRENCY) {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|args| {
+                    scope.spawn(move || {
+                        execute_render(
+                            binary_path,
+                            code,
+                            args,
+                            &None,
+                            &None,
+                            &policy.working_dir,
+                            &policy.library_paths,
+                            policy.timeout,
+                            None,
+                        )
+                    })
+                })
+                .collect();
+            for handle in handles {
+                results.push(
+                    handle
+                        .join()
+                        .unwrap_or_else(|_| Err("Sweep render panicked".into())),
+                );
+            }
+        });
+    }
+
+    let mut tiles = Vec::with_capacity(values.len());
+    let mut images = Vec::with_capacity(values.len());
+    for (value, result) in values.iter().zip(results) {
+        let (image, error, duration_ms) = match result {
+            Ok(render) if render.exit_code == 0 && !render.output.is_empty() => {
+                match decode_png(&render.output) {
+                    Ok(image) => (Some(image), None, render.duration_ms),
+                    Err(e) => (None, Some(e), render.duration_ms),
+                }
+            }
+            Ok(render) => (
+                None,
+                Some(
+                    first_error_line(&render.stderr)
+                        .unwrap_or_else(|| format!("OpenSCAD exited with {}", render.exit_code)),
+                ),
+                render.duration_ms,
+            ),
+            Err(e) => (None, Some(e), 0),
+        };
+        tiles.push(SweepTile {
+            value: value.clone(),
+            success: image.is_some(),
+            error,
+            duration_ms,
+        });
+        images.push(image);
+    }
+
+    let sheet = compose_contact_sheet(&images, tile_size);
+    Ok(SweepResult {
+        image: base64::engine::general_purpose::STANDARD.encode(encode_png(&sheet)?),
+        mime_type: "image/png".to_string(),
+        columns: sheet_columns(values.len()),
+        tile_size,
+        tiles,
+    })
+}
+
+/// Render a contact sheet of previews, one per value of a top-level variable
+/// (defaults to the current editor code and working directory), optionally
+/// from a fixed camera
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn sweep_parameter(
+    app: AppHandle,
+    name: String,
+    values: Vec<String>,
+    code: Option<String>,
+    working_dir: Option<String>,
+    library_paths: Option<Vec<String>>,
+    tile_size: Option<u32>,
+    camera: Option<CameraSpec>,
+    binary_state: State<'_, OpenScadBinaryState>,
+    editor_state: State<'_, EditorState>,
+) -> Result<SweepResult, String> {
+    let binary_path = binary_state
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("OpenSCAD binary not initialized. Call render_init first.")?;
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    let working_dir = working_dir.or_else(|| editor_state.working_dir.lock().unwrap().clone());
+    let policy = render_policy(&app, &code, &None, &working_dir, &library_paths)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        run_sweep(
+            &binary_path,
+            &code,
+            &name,
+            &values,
+            &policy,
+            tile_size,
+            camera.as_ref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Sweep task failed: {e}"))?
+}
@@ -0,0 +1,126 @@
+use crate::cmd::EditorState;
+use crate::libraries::{resolve_search_path, user_library_dir};
+use crate::safe_mode::{canonical_project_path, requires_safe_mode};
+use crate::settings::SettingsState;
+use crate::symbols::{self, Symbol};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+/// Where the editor's includes resolve: the active file's folder, the
+/// project's library paths and the user library folder. Unsaved project
+/// buffers are read in place of their files.
+struct IncludeContext {
+    dir: Option<PathBuf>,
+    library_paths: Vec<PathBuf>,
+    buffers: HashMap<PathBuf, String>,
+}
+
+impl IncludeContext {
+    fn new(app: &AppHandle) -> Self {
+        let editor_state = app.state::<EditorState>();
+        let working_dir = editor_state.working_dir.lock().unwrap().clone();
+        let mut dir = working_dir.as_deref().map(PathBuf::from);
+        let mut buffers = HashMap::new();
+        if let Some(project) = editor_state.project.lock().unwrap().as_ref() {
+            let root = Path::new(&project.root);
+            dir = root
+                .join(&project.active_file)
+                .parent()
+                .map(Path::to_path_buf);
+            buffers.extend(project.files().iter().filter_map(|file| {
+                let buffer = project.buffer(file)?;
+                Some((root.join(file), buffer.content.clone()))
+            }));
+        }
+
+        let settings = app
+            .state::<SettingsState>()
+            .settings
+            .lock()
+            .unwrap()
+            .clone();
+        let mut library_paths = Vec::new();
+        // Untrusted projects only see their own folder, as in safe mode renders
+        if !requires_safe_mode(&settings, working_dir.as_deref()) {
+            if let Some(working_dir) = &working_dir {
+                let root = Path::new(working_dir);
+                library_paths.extend(
+                    settings
+                        .libraries
+                        .project_paths
+                        .get(&canonical_project_path(working_dir))
+                        .into_iter()
+                        .flatten()
+                        .map(|path| resolve_search_path(Some(root), path)),
+                );
+            }
+            if let Ok(home) = app.path().home_dir() {
+                library_paths.push(user_library_dir(&home));
+            }
+        }
+
+        Self {
+            dir,
+            library_paths,
+            buffers,
+        }
+    }
+
+    fn read(&self, path: &Path) -> Option<String> {
+        self.buffers
+            .get(path)
+            .cloned()
+            .or_else(|| fs::read_to_string(path).ok())
+    }
+}
+
+/// Modules, functions and variables defined at file scope, for the editor
+/// outline (defaults to the current editor code)
+#[tauri::command]
+pub fn document_symbols(code: Option<String>, editor_state: State<'_, EditorState>) -> Vec<Symbol> {
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    symbols::document_symbols(&code)
+}
+
+/// Definition of `name` in the editor code or the files it includes
+#[tauri::command]
+pub fn find_definition(app: AppHandle, name: String, code: Option<String>) -> Option<Symbol> {
+    let code = code.unwrap_or_else(|| {
+        app.state::<EditorState>()
+            .current_code
+            .lock()
+            .unwrap()
+            .clone()
+    });
+    let context = IncludeContext::new(&app);
+    symbols::find_definition(
+        &name,
+        &code,
+        context.dir.as_deref(),
+        &context.library_paths,
+        |path| context.read(path),
+    )
+}
+
+/// User-defined modules, functions and variables starting with `prefix`,
+/// from the editor code and the files it includes
+#[tauri::command]
+pub fn completion_candidates(app: AppHandle, prefix: String, code: Option<String>) -> Vec<Symbol> {
+    let code = code.unwrap_or_else(|| {
+        app.state::<EditorState>()
+            .current_code
+            .lock()
+            .unwrap()
+            .clone()
+    });
+    let context = IncludeContext::new(&app);
+    symbols::completion_candidates(
+        &prefix,
+        &code,
+        context.dir.as_deref(),
+        &context.library_paths,
+        |path| context.read(path),
+    )
+}
@@ -0,0 +1,262 @@
+use crate::cmd::files::list_project_files;
+use crate::cmd::EditorState;
+use crate::create_new_window_with_launch_intent;
+use crate::customizer::customizer_schema;
+use crate::mcp::WindowLaunchIntent;
+use crate::templates::{
+    apply_parameters, builtin_template, is_contained, template_slug, TemplateManifest,
+    TemplateSummary, BUILTIN_TEMPLATES, MANIFEST_FILE_NAME,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateProject {
+    pub project_dir: String,
+    pub main_file: String,
+    pub files: Vec<String>,
+    pub window_id: String,
+}
+
+fn templates_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("templates"))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+fn read_manifest(dir: &Path) -> Result<TemplateManifest, String> {
+    let path = dir.join(MANIFEST_FILE_NAME);
+    let text =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {e}", path.display()))
+}
+
+fn user_template(dir: &Path) -> Result<TemplateSummary, String> {
+    let manifest = read_manifest(dir)?;
+    let files = list_project_files(dir.to_string_lossy().to_string())?;
+    let main = fs::read_to_string(dir.join(&manifest.main_file)).map_err(|e| {
+        format!(
+            "Failed to read {} in template {}: {e}",
+            manifest.main_file, manifest.name
+        )
+    })?;
+    Ok(TemplateSummary {
+        id: dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        name: manifest.name,
+        description: manifest.description,
+        builtin: false,
+        main_file: manifest.main_file,
+        files,
+        parameters: customizer_schema(&main).parameters,
+    })
+}
+
+fn user_templates(app: &AppHandle) -> Result<Vec<TemplateSummary>, String> {
+    let Ok(entries) = fs::read_dir(templates_dir(app)?) else {
+        return Ok(Vec::new());
+    };
+    let mut templates: Vec<TemplateSummary> = entries
+        .flatten()
+        .filter(|entry| entry.path().join(MANIFEST_FILE_NAME).is_file())
+        .filter_map(|entry| {
+            user_template(&entry.path())
+                .inspect_err(|e| eprintln!("[templates] Skipping template: {e}"))
+                .ok()
+        })
+        .collect();
+    templates.sort_by_key(|template| template.name.to_lowercase());
+    Ok(templates)
+}
+
+/// Built-in starters followed by the user's saved templates
+#[tauri::command]
+pub fn list_templates(app: AppHandle) -> Result<Vec<TemplateSummary>, String> {
+    let mut templates: Vec<TemplateSummary> = BUILTIN_TEMPLATES
+        .iter()
+        .map(|template| template.summary())
+        .collect();
+    templates.extend(user_templates(&app)?);
+    Ok(templates)
+}
+
+/// Files of template `id` as (relative path, contents), main file first
+fn template_files(app: &AppHandle, id: &str) -> Result<(String, Vec<(String, Vec<u8>)>), String> {
+    if let Some(template) = builtin_template(id) {
+        return Ok((
+            template.main_file.to_string(),
+            vec![(
+                template.main_file.to_string(),
+                template.source.as_bytes().to_vec(),
+            )],
+        ));
+    }
+    if !is_contained(id) {
+        return Err(format!("Unknown template: {id}"));
+    }
+    let dir = templates_dir(app)?.join(id);
+    if !dir.join(MANIFEST_FILE_NAME).is_file() {
+        return Err(format!("Unknown template: {id}"));
+    }
+    let summary = user_template(&dir)?;
+    let mut files = Vec::with_capacity(summary.files.len());
+    for file in &summary.files {
+        let bytes = fs::read(dir.join(file))
+            .map_err(|e| format!("Failed to read {file} in template {}: {e}", summary.name))?;
+        files.push((file.clone(), bytes));
+    }
+    files.sort_by_key(|(file, _)| *file != summary.main_file);
+    Ok((summary.main_file, files))
+}
+
+/// Create a project in `dest` (a new or empty folder) from template `name`,
+/// with `params` (OpenSCAD literals keyed by parameter) written into its
+/// main file, and open it in a new window
+#[tauri::command]
+pub fn create_project_from_template(
+    app: AppHandle,
+    name: String,
+    params: Option<BTreeMap<String, String>>,
+    dest: String,
+) -> Result<TemplateProject, String> {
+    let (main_file, mut files) = template_files(&app, &name)?;
+    if let Some(params) = params.filter(|params| !params.is_empty()) {
+        let (_, main) = files
+            .iter_mut()
+            .find(|(file, _)| *file == main_file)
+            .ok_or_else(|| format!("Template {name} has no {main_file}"))?;
+        let code = String::from_utf8(std::mem::take(main))
+            .map_err(|_| format!("{main_file} is not UTF-8 text"))?;
+        *main = apply_parameters(&code, &params)?.into_bytes();
+    }
+
+    let project_dir = PathBuf::from(&dest);
+    let occupied = fs::read_dir(&project_dir)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if occupied {
+        return Err(format!("{dest} is not empty; choose a new folder"));
+    }
+    for (file, bytes) in &files {
+        let path = project_dir.join(file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        fs::write(&path, bytes).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+
+    let window_id = create_new_window_with_launch_intent(
+        &app,
+        WindowLaunchIntent::OpenFile {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            file_path: project_dir.join(&main_file).to_string_lossy().to_string(),
+        },
+    )
+    .map_err(|e| format!("Failed to open a window for the new project: {e}"))?;
+
+    eprintln!("[templates] Created {dest} from template {name}");
+    Ok(TemplateProject {
+        project_dir: dest,
+        main_file,
+        files: files.into_iter().map(|(file, _)| file).collect(),
+        window_id,
+    })
+}
+
+/// Save the open project (working directory) as a user template, with its
+/// active file as the main file
+#[tauri::command]
+pub fn save_project_as_template(
+    app: AppHandle,
+    name: String,
+    description: Option<String>,
+    editor_state: State<'_, EditorState>,
+) -> Result<TemplateSummary, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Give the template a name".into());
+    }
+    let root = editor_state
+        .working_dir
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "Open a project folder before saving it as a template".to_string())?;
+    let files = list_project_files(root.clone())?;
+    let main_file = editor_state
+        .project
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|project| project.active_file.clone())
+        .filter(|file| files.contains(file))
+        .or_else(|| files.iter().find(|file| file.ends_with(".scad")).cloned())
+        .ok_or_else(|| format!("{root} contains no .scad files"))?;
+
+    let parent = templates_dir(&app)?;
+    let slug = template_slug(&name);
+    let dir = (0..1000)
+        .map(|n| match n {
+            0 => slug.clone(),
+            n => format!("{slug}-{n}"),
+        })
+        .filter(|id| builtin_template(id).is_none())
+        .map(|id| parent.join(id))
+        .find(|dir| !dir.exists())
+        .ok_or_else(|| format!("No free folder name for template {name}"))?;
+    let copied = files
+        .iter()
+        .try_for_each(|file| {
+            let target = dir.join(file);
+            if let Some(folder) = target.parent() {
+                fs::create_dir_all(folder)
+                    .map_err(|e| format!("Failed to create {}: {e}", folder.display()))?;
+            }
+            fs::copy(Path::new(&root).join(file), &target)
+                .map(|_| ())
+                .map_err(|e| format!("Failed to copy {file}: {e}"))
+        })
+        .and_then(|_| {
+            let manifest = TemplateManifest {
+                name: name.clone(),
+                description: description.unwrap_or_default().trim().to_string(),
+                main_file,
+            };
+            let json = serde_json::to_string_pretty(&manifest)
+                .map_err(|e| format!("Failed to serialize template: {e}"))?;
+            fs::write(dir.join(MANIFEST_FILE_NAME), json)
+                .map_err(|e| format!("Failed to write template manifest: {e}"))
+        })
+        .and_then(|_| user_template(&dir))
+        .inspect_err(|_| {
+            let _ = fs::remove_dir_all(&dir);
+        });
+    let template = copied?;
+    eprintln!(
+        "[templates] Saved {root} as template {} ({} file(s))",
+        template.id,
+        template.files.len()
+    );
+    Ok(template)
+}
+
+/// Delete a user template; built-in templates can't be removed
+#[tauri::command]
+pub fn delete_template(app: AppHandle, id: String) -> Result<(), String> {
+    if builtin_template(&id).is_some() {
+        return Err(format!("{id} is a built-in template"));
+    }
+    let dir = templates_dir(&app)?.join(&id);
+    if !is_contained(&id) || !dir.join(MANIFEST_FILE_NAME).is_file() {
+        return Err(format!("Unknown template: {id}"));
+    }
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete template {id}: {e}"))
+}
@@ -0,0 +1,111 @@
+use crate::settings::{update_settings, SettingsState};
+use crate::tool_permissions::{
+    ToolApprovals, ToolPermission, ToolPermissionSettings, APPROVAL_TIMEOUT,
+};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+
+/// Emitted as `tool-approval-request` when a tool call needs the user's
+/// approval; answer with `approve_tool_call`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolApprovalRequest {
+    pub request_id: String,
+    pub tool_name: String,
+    pub arguments: Value,
+}
+
+/// Check `tool_name` against the permission settings, asking the user in
+/// `window_id` (or every window) when it needs approval. Blocks until the
+/// user answers; `Err` is the reason the call may not run.
+pub(crate) fn authorize_tool(
+    app: &AppHandle,
+    window_id: Option<&str>,
+    tool_name: &str,
+    arguments: &Value,
+) -> Result<(), String> {
+    let permission = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .tool_permissions
+        .permission(tool_name);
+    match permission {
+        ToolPermission::Allow => return Ok(()),
+        ToolPermission::Deny => {
+            return Err(format!(
+                "`{tool_name}` is disabled in OpenSCAD Studio's AI tool permissions."
+            ))
+        }
+        ToolPermission::Ask => {}
+    }
+
+    let approvals = app.state::<ToolApprovals>();
+    let (request_id, rx) = approvals.request();
+    let request = ToolApprovalRequest {
+        request_id: request_id.clone(),
+        tool_name: tool_name.to_string(),
+        arguments: arguments.clone(),
+    };
+    let emitted = match window_id {
+        Some(window_id) => app.emit_to(window_id, "tool-approval-request", request),
+        None => app.emit("tool-approval-request", request),
+    };
+    if let Err(e) = emitted {
+        approvals.abandon(&request_id);
+        return Err(format!("Failed to ask for approval of `{tool_name}`: {e}"));
+    }
+
+    let approved = rx.recv_timeout(APPROVAL_TIMEOUT).unwrap_or(false);
+    approvals.abandon(&request_id);
+    if approved {
+        Ok(())
+    } else {
+        Err(format!("The user declined the `{tool_name}` call."))
+    }
+}
+
+#[tauri::command]
+pub fn get_tool_permissions(state: State<'_, SettingsState>) -> ToolPermissionSettings {
+    state.settings.lock().unwrap().tool_permissions.clone()
+}
+
+#[tauri::command]
+pub fn set_tool_permissions(
+    app: AppHandle,
+    permissions: ToolPermissionSettings,
+) -> Result<(), String> {
+    update_settings(&app, |settings| {
+        settings.tool_permissions = permissions;
+        Ok(())
+    })
+}
+
+/// Check a tool call of the in-app assistant before it runs, prompting in
+/// the calling window when the tool needs approval
+#[tauri::command]
+pub async fn authorize_tool_call(
+    app: AppHandle,
+    window: Window,
+    tool_name: String,
+    arguments: Value,
+) -> Result<(), String> {
+    let window_id = window.label().to_string();
+    tokio::task::spawn_blocking(move || {
+        authorize_tool(&app, Some(&window_id), &tool_name, &arguments)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Answer a `tool-approval-request`
+#[tauri::command]
+pub fn approve_tool_call(
+    approvals: State<'_, ToolApprovals>,
+    request_id: String,
+    approved: bool,
+) -> Result<(), String> {
+    approvals.resolve(&request_id, approved)
+}
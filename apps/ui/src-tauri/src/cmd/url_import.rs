@@ -0,0 +1,109 @@
+use crate::create_new_window_with_launch_intent;
+use crate::mcp::WindowLaunchIntent;
+use crate::url_import::{fetch_project, resolve_source, MAX_REMOTE_FILE_BYTES};
+use serde::Serialize;
+use std::fs;
+use std::io::Read;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingDependency {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlImport {
+    /// Temp directory the project was written to
+    pub project_dir: String,
+    pub main_file: String,
+    /// Project-relative paths of every downloaded file
+    pub files: Vec<String>,
+    pub missing: Vec<MissingDependency>,
+    pub window_id: String,
+}
+
+fn download(client: &reqwest::blocking::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Failed to download {url}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("{url} returned {}", response.status()));
+    }
+    let mut bytes = Vec::new();
+    response
+        .take(MAX_REMOTE_FILE_BYTES as u64 + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {url}: {e}"))?;
+    if bytes.len() > MAX_REMOTE_FILE_BYTES {
+        return Err(format!(
+            "{url} is larger than {} MB",
+            MAX_REMOTE_FILE_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Download a remote .scad file and its include dependencies into a fresh
+/// temp directory, then open it in a new window. The directory is never
+/// trusted, so safe mode applies to it when enabled.
+#[tauri::command]
+pub async fn open_from_url(app: AppHandle, url: String) -> Result<UrlImport, String> {
+    tauri::async_runtime::spawn_blocking(move || import_url(&app, &url))
+        .await
+        .map_err(|e| format!("URL import task failed: {e}"))?
+}
+
+fn import_url(app: &AppHandle, url: &str) -> Result<UrlImport, String> {
+    let source = resolve_source(url)?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .user_agent(concat!("OpenSCAD-Studio/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let project = fetch_project(&source, |url| download(&client, url))?;
+    let project_dir = std::env::temp_dir()
+        .join("openscad-studio-url")
+        .join(uuid::Uuid::new_v4().to_string());
+    for (path, bytes) in &project.files {
+        let target = project_dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {parent:?}: {e}"))?;
+        }
+        fs::write(&target, bytes).map_err(|e| format!("Failed to write {path}: {e}"))?;
+    }
+
+    let main_path = project_dir.join(&project.main_file);
+    let window_id = create_new_window_with_launch_intent(
+        app,
+        WindowLaunchIntent::OpenFile {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            file_path: main_path.to_string_lossy().to_string(),
+        },
+    )
+    .map_err(|e| format!("Failed to open a window for the downloaded project: {e}"))?;
+
+    eprintln!(
+        "[url-import] Downloaded {} file(s) from {url} into {} ({} missing)",
+        project.files.len(),
+        project_dir.display(),
+        project.missing.len()
+    );
+    Ok(UrlImport {
+        project_dir: project_dir.to_string_lossy().to_string(),
+        main_file: project.main_file,
+        files: project.files.keys().cloned().collect(),
+        missing: project
+            .missing
+            .into_iter()
+            .map(|(path, reason)| MissingDependency { path, reason })
+            .collect(),
+        window_id,
+    })
+}
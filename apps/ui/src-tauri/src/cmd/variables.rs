@@ -0,0 +1,14 @@
+use crate::cmd::EditorState;
+use crate::variables::{find_top_level_variables, TopLevelVariable};
+use tauri::State;
+
+/// List top-level variables that can be overridden with `-D`
+/// (defaults to the current editor code)
+#[tauri::command]
+pub fn get_top_level_variables(
+    code: Option<String>,
+    editor_state: State<'_, EditorState>,
+) -> Result<Vec<TopLevelVariable>, String> {
+    let code = code.unwrap_or_else(|| editor_state.current_code.lock().unwrap().clone());
+    Ok(find_top_level_variables(&code))
+}
@@ -0,0 +1,624 @@
+/**
+ * AI conversation storage, export and import
+ *
+ * Saved conversations and JSON exports share one stored shape: each message
+ * has a role and a list of typed content blocks, so text, images, tool calls
+ * and their results, and the checkpoint a request was made from all survive
+ * a save and replay as they were. The frontend converts its chat messages to
+ * and from this shape (`utils/storedConversation.ts`). Markdown exports are a
+ * readable transcript with tool calls inlined and `apply_edit` /
+ * `create_file` calls shown as diffs.
+ *
+ * Version 1 stored the frontend's chat messages as they were, one
+ * `type`-tagged message per user prompt, reply or tool call, and carried no
+ * image attachments; it is migrated when read.
+ */
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+use std::collections::{BTreeMap, HashMap};
+
+pub const EXPORT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conversation {
+    pub id: String,
+    pub title: String,
+    pub timestamp: i64,
+    pub messages: Vec<Message>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    pub id: String,
+    pub role: Role,
+    pub timestamp: i64,
+    pub content: Vec<ContentBlock>,
+    /// Agent turn an assistant reply belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+    /// How an assistant reply ended: `complete`, `cancelled` or `error`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// Editor checkpoint taken before a user request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Image {
+        attachment_id: String,
+        filename: String,
+        mime_type: String,
+        width: u32,
+        height: u32,
+    },
+    #[serde(rename_all = "camelCase")]
+    ToolCall {
+        tool_call_id: String,
+        tool_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        args: Option<Value>,
+        /// `pending`, `completed`, `error` or `denied`
+        state: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ToolResult {
+        tool_call_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        result: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error_text: Option<String>,
+    },
+}
+
+/// Chat message as version 1 stored it
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum LegacyMessage {
+    #[serde(rename_all = "camelCase")]
+    User {
+        id: String,
+        timestamp: i64,
+        parts: Vec<ContentBlock>,
+        #[serde(default)]
+        checkpoint_id: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Assistant {
+        id: String,
+        timestamp: i64,
+        turn_id: String,
+        content: String,
+        state: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ToolCall {
+        id: String,
+        timestamp: i64,
+        tool_call_id: String,
+        tool_name: String,
+        #[serde(default)]
+        args: Option<Value>,
+        state: String,
+        #[serde(default)]
+        result: Option<Value>,
+        #[serde(default)]
+        error_text: Option<String>,
+    },
+}
+
+impl From<LegacyMessage> for Message {
+    fn from(message: LegacyMessage) -> Self {
+        match message {
+            LegacyMessage::User {
+                id,
+                timestamp,
+                parts,
+                checkpoint_id,
+            } => Message {
+                id,
+                role: Role::User,
+                timestamp,
+                content: parts,
+                turn_id: None,
+                state: None,
+                checkpoint_id,
+            },
+            LegacyMessage::Assistant {
+                id,
+                timestamp,
+                turn_id,
+                content,
+                state,
+            } => Message {
+                id,
+                role: Role::Assistant,
+                timestamp,
+                content: vec![ContentBlock::Text { text: content }],
+                turn_id: Some(turn_id),
+                state: Some(state),
+                checkpoint_id: None,
+            },
+            LegacyMessage::ToolCall {
+                id,
+                timestamp,
+                tool_call_id,
+                tool_name,
+                args,
+                state,
+                result,
+                error_text,
+            } => {
+                let mut content = vec![ContentBlock::ToolCall {
+                    tool_call_id: tool_call_id.clone(),
+                    tool_name,
+                    args,
+                    state,
+                }];
+                if result.is_some() || error_text.is_some() {
+                    content.push(ContentBlock::ToolResult {
+                        tool_call_id,
+                        result,
+                        error_text,
+                    });
+                }
+                Message {
+                    id,
+                    role: Role::Assistant,
+                    timestamp,
+                    content,
+                    turn_id: None,
+                    state: None,
+                    checkpoint_id: None,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LegacyConversation {
+    id: String,
+    title: String,
+    timestamp: i64,
+    messages: Vec<LegacyMessage>,
+}
+
+impl From<LegacyConversation> for Conversation {
+    fn from(conversation: LegacyConversation) -> Self {
+        Conversation {
+            id: conversation.id,
+            title: conversation.title,
+            timestamp: conversation.timestamp,
+            messages: conversation
+                .messages
+                .into_iter()
+                .map(Message::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConversationExport<C> {
+    version: u32,
+    exported_at: i64,
+    conversation: C,
+    #[serde(default)]
+    attachments: BTreeMap<String, Value>,
+}
+
+/// A conversation read back from an export or a save, with its image
+/// attachments in the frontend's `AttachmentStore` shape
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedConversation {
+    pub conversation: Conversation,
+    pub attachments: BTreeMap<String, Value>,
+    /// Read from an older version, so a save should be rewritten
+    #[serde(skip)]
+    pub migrated: bool,
+}
+
+impl Conversation {
+    /// Ids of the image attachments the messages refer to
+    fn attachment_ids(&self) -> impl Iterator<Item = &str> {
+        self.messages
+            .iter()
+            .flat_map(|message| &message.content)
+            .filter_map(|block| match block {
+                ContentBlock::Image { attachment_id, .. } => Some(attachment_id.as_str()),
+                _ => None,
+            })
+    }
+}
+
+/// Unified-style line diff between `old` and `new`
+fn line_diff(old: &str, new: &str) -> String {
+    let mut diff = String::new();
+    for change in TextDiff::from_lines(old, new).iter_all_changes() {
+        let sign = match change.tag() {
+            ChangeTag::Delete => '-',
+            ChangeTag::Insert => '+',
+            ChangeTag::Equal => ' ',
+        };
+        diff.push(sign);
+        diff.push_str(change.as_str().unwrap_or_default().trim_end_matches('\n'));
+        diff.push('\n');
+    }
+    diff
+}
+
+/// Fence `text` with enough backticks that its own fences don't end the block
+fn fenced(language: &str, text: &str) -> String {
+    let mut fence = "```".to_string();
+    while text.contains(&fence) {
+        fence.push('`');
+    }
+    format!(
+        "{fence}{language}\n{}\n{fence}\n",
+        text.trim_end_matches('\n')
+    )
+}
+
+fn arg<'a>(args: &'a Option<Value>, name: &str) -> Option<&'a str> {
+    args.as_ref()?.get(name)?.as_str()
+}
+
+fn tool_call_markdown(
+    tool_name: &str,
+    args: &Option<Value>,
+    state: &str,
+    result: &Option<Value>,
+    error_text: &Option<String>,
+) -> String {
+    let mut out = format!("**Tool call: `{tool_name}`** ({state})\n\n");
+    let edited_file = arg(args, "file_path").unwrap_or("render target");
+    match (
+        tool_name,
+        arg(args, "old_string"),
+        arg(args, "new_string"),
+        arg(args, "content"),
+    ) {
+        ("apply_edit", Some(old), Some(new), _) => {
+            out.push_str(&format!("Edit to `{edited_file}`:\n\n"));
+            out.push_str(&fenced("diff", &line_diff(old, new)));
+        }
+        ("create_file" | "write_file", _, _, Some(content)) => {
+            out.push_str(&format!("New content for `{edited_file}`:\n\n"));
+            out.push_str(&fenced("diff", &line_diff("", content)));
+        }
+        _ => {
+            if let Some(args) = args.as_ref().filter(|args| !args.is_null()) {
+                let json = serde_json::to_string_pretty(args).unwrap_or_default();
+                out.push_str(&fenced("json", &json));
+            }
+        }
+    }
+    if let Some(error) = error_text {
+        out.push_str(&format!("\nError: {error}\n"));
+    } else if let Some(result) = result {
+        let text = match result {
+            Value::String(text) => text.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        };
+        // Screenshots come back as data URLs; a transcript can't show them.
+        if text.contains("data:image/") {
+            out.push_str("\nResult: (image)\n");
+        } else if !text.trim().is_empty() {
+            out.push('\n');
+            out.push_str(&fenced("text", &text));
+        }
+    }
+    out
+}
+
+fn format_time(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms)
+        .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default()
+}
+
+/// Readable Markdown transcript of `conversation`
+pub fn to_markdown(conversation: &Conversation) -> String {
+    let mut out = format!(
+        "# {}\n\n_Exported from OpenSCAD Studio · {}_\n",
+        conversation.title,
+        format_time(conversation.timestamp)
+    );
+    // Results are shown with their calls
+    let results: HashMap<&str, (&Option<Value>, &Option<String>)> = conversation
+        .messages
+        .iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|block| match block {
+            ContentBlock::ToolResult {
+                tool_call_id,
+                result,
+                error_text,
+            } => Some((tool_call_id.as_str(), (result, error_text))),
+            _ => None,
+        })
+        .collect();
+    for message in &conversation.messages {
+        let has_text = message.content.iter().any(|block| {
+            matches!(
+                block,
+                ContentBlock::Text { .. } | ContentBlock::Image { .. }
+            )
+        });
+        if has_text {
+            out.push_str(match message.role {
+                Role::User => "\n## User\n\n",
+                Role::Assistant => "\n## Assistant\n\n",
+            });
+        }
+        for block in &message.content {
+            match block {
+                ContentBlock::Text { text } => {
+                    out.push_str(text.trim_end());
+                    out.push('\n');
+                }
+                ContentBlock::Image {
+                    filename,
+                    width,
+                    height,
+                    ..
+                } => out.push_str(&format!("_[Image: {filename}, {width}×{height}]_\n")),
+                ContentBlock::ToolCall {
+                    tool_call_id,
+                    tool_name,
+                    args,
+                    state,
+                } => {
+                    let (result, error_text) = results
+                        .get(tool_call_id.as_str())
+                        .copied()
+                        .unwrap_or((&None, &None));
+                    out.push('\n');
+                    out.push_str(&tool_call_markdown(
+                        tool_name, args, state, result, error_text,
+                    ));
+                }
+                ContentBlock::ToolResult { .. } => {}
+            }
+        }
+        if let Some(state) = message
+            .state
+            .as_deref()
+            .filter(|state| *state != "complete")
+        {
+            out.push_str(&format!("\n_(response {state})_\n"));
+        }
+    }
+    out
+}
+
+/// JSON export that `from_json` reads back. Only the attachments the
+/// conversation references are written.
+pub fn to_json(
+    conversation: &Conversation,
+    attachments: &BTreeMap<String, Value>,
+    exported_at: i64,
+) -> Result<String, String> {
+    let attachments = conversation
+        .attachment_ids()
+        .filter_map(|id| Some((id.to_string(), attachments.get(id)?.clone())))
+        .collect();
+    serde_json::to_string_pretty(&ConversationExport {
+        version: EXPORT_VERSION,
+        exported_at,
+        conversation,
+        attachments,
+    })
+    .map_err(|e| format!("Failed to serialize conversation: {e}"))
+}
+
+fn conversation_from_value(value: Value, version: u32) -> Result<Conversation, String> {
+    if version < 2 {
+        serde_json::from_value::<LegacyConversation>(value).map(Conversation::from)
+    } else {
+        serde_json::from_value(value)
+    }
+    .map_err(|e| format!("Invalid conversation: {e}"))
+}
+
+/// Read a JSON export or save (or a bare conversation object), migrating
+/// older versions to the current shape
+pub fn from_json(json: &str) -> Result<ImportedConversation, String> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| format!("Not a conversation export: {e}"))?;
+    if value.get("conversation").is_some() {
+        let export: ConversationExport<Value> = serde_json::from_value(value)
+            .map_err(|e| format!("Invalid conversation export: {e}"))?;
+        if export.version > EXPORT_VERSION {
+            return Err(format!(
+                "Conversation export version {} is newer than this app supports",
+                export.version
+            ));
+        }
+        return Ok(ImportedConversation {
+            conversation: conversation_from_value(export.conversation, export.version)?,
+            attachments: export.attachments,
+            migrated: export.version < EXPORT_VERSION,
+        });
+    }
+    // Bare objects carry no version; legacy messages are tagged with `type`
+    let legacy = value
+        .pointer("/messages/0")
+        .is_some_and(|message| message.get("type").is_some());
+    let version = if legacy { 1 } else { EXPORT_VERSION };
+    Ok(ImportedConversation {
+        conversation: conversation_from_value(value, version)?,
+        attachments: BTreeMap::new(),
+        migrated: legacy,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Conversation {
+        serde_json::from_value(json!({
+            "id": "conv-1",
+            "title": "Rounded box",
+            "timestamp": 1_700_000_000_000_i64,
+            "messages": [
+                {
+                    "id": "m1", "role": "user", "timestamp": 1, "checkpointId": "cp-1",
+                    "content": [
+                        { "type": "text", "text": "Round the corners" },
+                        { "type": "image", "attachmentId": "a1", "filename": "sketch.png",
+                          "mimeType": "image/png", "width": 640, "height": 480 }
+                    ]
+                },
+                {
+                    "id": "m2", "role": "assistant", "timestamp": 2,
+                    "content": [
+                        { "type": "tool-call", "toolCallId": "t1", "toolName": "apply_edit",
+                          "state": "completed",
+                          "args": { "old_string": "cube(10);\n", "new_string": "minkowski() {\n  cube(8);\n  sphere(1);\n}\n" } },
+                        { "type": "tool-result", "toolCallId": "t1", "result": "✅ Edit applied" }
+                    ]
+                },
+                {
+                    "id": "m3", "role": "assistant", "timestamp": 3, "turnId": "turn-1",
+                    "state": "complete",
+                    "content": [{ "type": "text", "text": "Done — the corners now have a 1 mm radius." }]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    /// `sample` as version 1 stored it
+    fn legacy_sample() -> Value {
+        json!({
+            "id": "conv-1",
+            "title": "Rounded box",
+            "timestamp": 1_700_000_000_000_i64,
+            "messages": [
+                {
+                    "type": "user", "id": "m1", "timestamp": 1,
+                    "parts": [
+                        { "type": "text", "text": "Round the corners" },
+                        { "type": "image", "attachmentId": "a1", "filename": "sketch.png",
+                          "mimeType": "image/png", "width": 640, "height": 480 }
+                    ],
+                    "checkpointId": "cp-1"
+                },
+                {
+                    "type": "tool-call", "id": "m2", "timestamp": 2, "toolCallId": "t1",
+                    "toolName": "apply_edit", "state": "completed",
+                    "args": { "old_string": "cube(10);\n", "new_string": "minkowski() {\n  cube(8);\n  sphere(1);\n}\n" },
+                    "result": "✅ Edit applied"
+                },
+                {
+                    "type": "assistant", "id": "m3", "timestamp": 3, "turnId": "turn-1",
+                    "content": "Done — the corners now have a 1 mm radius.", "state": "complete"
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn markdown_inlines_tool_calls_as_diffs() {
+        let markdown = to_markdown(&sample());
+        assert!(markdown.starts_with("# Rounded box\n"));
+        assert!(markdown.contains("## User\n\nRound the corners\n_[Image: sketch.png, 640×480]_"));
+        assert!(markdown.contains("**Tool call: `apply_edit`** (completed)"));
+        assert!(markdown.contains("```diff\n-cube(10);\n+minkowski() {\n+  cube(8);"));
+        assert!(markdown.contains("✅ Edit applied"));
+        assert!(markdown.contains("## Assistant\n\nDone"));
+        assert!(!markdown.contains("(response"));
+    }
+
+    #[test]
+    fn json_export_round_trips() {
+        let attachments = BTreeMap::from([
+            (
+                "a1".to_string(),
+                json!({ "id": "a1", "normalizedData": "iVBORw0KGgo=" }),
+            ),
+            ("unused".to_string(), json!({ "id": "unused" })),
+        ]);
+        let json = to_json(&sample(), &attachments, 42).unwrap();
+        let imported = from_json(&json).unwrap();
+        assert_eq!(
+            serde_json::to_value(&imported.conversation).unwrap(),
+            serde_json::to_value(sample()).unwrap()
+        );
+        assert_eq!(imported.attachments.keys().collect::<Vec<_>>(), ["a1"]);
+        assert_eq!(imported.attachments["a1"], attachments["a1"]);
+        assert!(!imported.migrated);
+
+        let bare = serde_json::to_string(&sample()).unwrap();
+        assert_eq!(from_json(&bare).unwrap().conversation.id, "conv-1");
+        assert!(from_json(r#"{"version": 99, "exportedAt": 0, "conversation": {"id": "x", "title": "", "timestamp": 0, "messages": []}}"#).is_err());
+        assert!(from_json("# Not JSON").is_err());
+    }
+
+    #[test]
+    fn migrates_chat_messages_to_content_blocks() {
+        let expected = serde_json::to_value(sample()).unwrap();
+        let version_1 = json!({ "version": 1, "exportedAt": 0, "conversation": legacy_sample() });
+        let migrated = from_json(&version_1.to_string()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&migrated.conversation).unwrap(),
+            expected
+        );
+        assert!(migrated.attachments.is_empty());
+        assert!(migrated.migrated);
+
+        let bare = from_json(&legacy_sample().to_string()).unwrap();
+        assert_eq!(serde_json::to_value(&bare.conversation).unwrap(), expected);
+        assert!(bare.migrated);
+    }
+
+    #[test]
+    fn pending_tool_calls_migrate_without_a_result() {
+        let mut legacy = legacy_sample();
+        legacy["messages"][1]["state"] = json!("pending");
+        legacy["messages"][1]
+            .as_object_mut()
+            .unwrap()
+            .remove("result");
+        let conversation = from_json(&legacy.to_string()).unwrap().conversation;
+        assert!(matches!(
+            conversation.messages[1].content.as_slice(),
+            [ContentBlock::ToolCall { state, .. }] if state == "pending"
+        ));
+    }
+
+    #[test]
+    fn fences_survive_embedded_backticks() {
+        assert_eq!(fenced("text", "a ``` b"), "````text\na ``` b\n````\n");
+    }
+}
@@ -0,0 +1,416 @@
+/**
+ * Search index for saved AI conversations
+ *
+ * Saved conversations are stored one file each; their searchable text is
+ * kept together in `conversation-index.json` so a search reads one file.
+ * A conversation's text is its prompts, the assistant's replies, and the
+ * arguments and results of its tool calls, so code the agent wrote or read
+ * is found too. Saving a conversation replaces its entry. Term counts are
+ * rebuilt in memory when the index is loaded and ranked with BM25, like the
+ * documentation index.
+ */
+use crate::conversation::{ContentBlock, Conversation, Message};
+use crate::docs::tokenize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+pub const INDEX_FILE_NAME: &str = "conversation-index.json";
+/// Bump when the stored format or the text taken from messages changes
+pub const INDEX_VERSION: u32 = 1;
+
+const DEFAULT_LIMIT: usize = 20;
+const TITLE_WEIGHT: f64 = 3.0;
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+const SNIPPET_CHARS: usize = 160;
+/// Characters of a snippet kept before the first match
+const SNIPPET_LEAD_CHARS: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedMessage {
+    pub id: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedConversation {
+    pub id: String,
+    pub title: String,
+    pub timestamp: i64,
+    pub messages: Vec<IndexedMessage>,
+}
+
+/// `conversation-index.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredConversationIndex {
+    pub version: u32,
+    /// Most recently saved first
+    pub conversations: Vec<IndexedConversation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationSearchHit {
+    pub conversation_id: String,
+    pub title: String,
+    pub timestamp: i64,
+    /// The message the snippet is from, unless only the title matched
+    pub message_id: Option<String>,
+    pub snippet: String,
+    pub score: f64,
+}
+
+struct IndexedDoc {
+    title_terms: HashMap<String, usize>,
+    body_terms: HashMap<String, usize>,
+    len: usize,
+}
+
+pub struct ConversationIndex {
+    pub stored: StoredConversationIndex,
+    docs: Vec<IndexedDoc>,
+    doc_freq: HashMap<String, usize>,
+    avg_len: f64,
+}
+
+fn term_counts<'a>(texts: impl IntoIterator<Item = &'a str>) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for term in texts.into_iter().flat_map(tokenize) {
+        *counts.entry(term).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// String values in `value`, leaving out data URLs and the app's own
+/// `__`-prefixed bookkeeping fields
+fn collect_strings<'a>(value: &'a Value, strings: &mut Vec<&'a str>) {
+    match value {
+        Value::String(text) if !text.is_empty() && !text.starts_with("data:") => strings.push(text),
+        Value::Array(items) => {
+            for item in items {
+                collect_strings(item, strings);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                if !key.starts_with("__") {
+                    collect_strings(field, strings);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn message_text(message: &Message) -> IndexedMessage {
+    let mut texts = Vec::new();
+    for block in &message.content {
+        match block {
+            ContentBlock::Text { text } => texts.push(text.as_str()),
+            ContentBlock::Image { filename, .. } => texts.push(filename.as_str()),
+            ContentBlock::ToolCall {
+                tool_name, args, ..
+            } => {
+                texts.push(tool_name.as_str());
+                if let Some(value) = args {
+                    collect_strings(value, &mut texts);
+                }
+            }
+            ContentBlock::ToolResult {
+                result, error_text, ..
+            } => {
+                if let Some(value) = result {
+                    collect_strings(value, &mut texts);
+                }
+                texts.extend(error_text.as_deref());
+            }
+        }
+    }
+    IndexedMessage {
+        id: message.id.clone(),
+        text: texts.join("\n"),
+    }
+}
+
+/// The searchable text of a conversation
+pub fn index_conversation(conversation: &Conversation) -> IndexedConversation {
+    IndexedConversation {
+        id: conversation.id.clone(),
+        title: conversation.title.clone(),
+        timestamp: conversation.timestamp,
+        messages: conversation
+            .messages
+            .iter()
+            .map(message_text)
+            .filter(|message| !message.text.trim().is_empty())
+            .collect(),
+    }
+}
+
+/// About `SNIPPET_CHARS` of `text` on one line, around the first of `terms`
+fn snippet(text: &str, terms: &[String]) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let lower = flat.to_lowercase();
+    // Lowercasing can change byte lengths; start at the beginning if it did
+    let match_byte = terms
+        .iter()
+        .filter_map(|term| lower.find(term.as_str()))
+        .min()
+        .filter(|_| lower.len() == flat.len())
+        .unwrap_or(0);
+    let match_char = flat[..match_byte].chars().count();
+    let start = match_char.saturating_sub(SNIPPET_LEAD_CHARS);
+    let total = flat.chars().count();
+    let mut snippet: String = flat.chars().skip(start).take(SNIPPET_CHARS).collect();
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if start + SNIPPET_CHARS < total {
+        snippet.push('…');
+    }
+    snippet
+}
+
+impl StoredConversationIndex {
+    /// Add `conversation`, replacing its previous entry
+    pub fn upsert(&mut self, conversation: &Conversation) {
+        self.version = INDEX_VERSION;
+        self.conversations
+            .retain(|indexed| indexed.id != conversation.id);
+        self.conversations
+            .insert(0, index_conversation(conversation));
+    }
+}
+
+impl ConversationIndex {
+    pub fn new(stored: StoredConversationIndex) -> Self {
+        let docs: Vec<IndexedDoc> = stored
+            .conversations
+            .iter()
+            .map(|conversation| {
+                let title_terms = term_counts([conversation.title.as_str()]);
+                let body_terms = term_counts(
+                    conversation
+                        .messages
+                        .iter()
+                        .map(|message| message.text.as_str()),
+                );
+                IndexedDoc {
+                    len: title_terms.values().sum::<usize>() + body_terms.values().sum::<usize>(),
+                    title_terms,
+                    body_terms,
+                }
+            })
+            .collect();
+        let mut doc_freq = HashMap::new();
+        for doc in &docs {
+            let terms: HashSet<&String> = doc
+                .title_terms
+                .keys()
+                .chain(doc.body_terms.keys())
+                .collect();
+            for term in terms {
+                *doc_freq.entry(term.clone()).or_insert(0) += 1;
+            }
+        }
+        let avg_len =
+            docs.iter().map(|doc| doc.len).sum::<usize>() as f64 / docs.len().max(1) as f64;
+        Self {
+            stored,
+            docs,
+            doc_freq,
+            avg_len,
+        }
+    }
+
+    /// Rank saved conversations for `query` (best first), each with a
+    /// snippet from the message matching the most terms
+    pub fn search(&self, query: &str, limit: Option<usize>) -> Vec<ConversationSearchHit> {
+        let mut terms = tokenize(query);
+        terms.sort_unstable();
+        terms.dedup();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let total = self.docs.len() as f64;
+
+        let mut hits: Vec<ConversationSearchHit> = self
+            .stored
+            .conversations
+            .iter()
+            .zip(&self.docs)
+            .filter_map(|(conversation, doc)| {
+                let mut score = 0.0;
+                let mut matched_terms = 0;
+                for term in &terms {
+                    let in_title = doc.title_terms.get(term).copied().unwrap_or(0);
+                    let in_body = doc.body_terms.get(term).copied().unwrap_or(0);
+                    let frequency = in_title as f64 * TITLE_WEIGHT + in_body as f64;
+                    if frequency == 0.0 {
+                        continue;
+                    }
+                    matched_terms += 1;
+                    let with_term = self.doc_freq.get(term).copied().unwrap_or(0) as f64;
+                    let idf = (1.0 + (total - with_term + 0.5) / (with_term + 0.5)).ln();
+                    let length_norm = 1.0 - BM25_B + BM25_B * doc.len as f64 / self.avg_len;
+                    score +=
+                        idf * frequency * (BM25_K1 + 1.0) / (frequency + BM25_K1 * length_norm);
+                }
+                // Prefer conversations that cover every term of the query.
+                if matched_terms == terms.len() {
+                    score *= 2.0;
+                }
+                (score > 0.0).then(|| self.hit(conversation, &terms, score))
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| b.timestamp.cmp(&a.timestamp))
+        });
+        hits.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+        hits
+    }
+
+    fn hit(
+        &self,
+        conversation: &IndexedConversation,
+        terms: &[String],
+        score: f64,
+    ) -> ConversationSearchHit {
+        let mut best: Option<(&IndexedMessage, usize)> = None;
+        for message in &conversation.messages {
+            let message_terms: HashSet<String> = tokenize(&message.text).into_iter().collect();
+            let matched = terms
+                .iter()
+                .filter(|term| message_terms.contains(*term))
+                .count();
+            if matched > best.map_or(0, |(_, count)| count) {
+                best = Some((message, matched));
+            }
+        }
+        let (message_id, snippet) = match best {
+            Some((message, _)) => (Some(message.id.clone()), snippet(&message.text, terms)),
+            None => (
+                None,
+                conversation
+                    .messages
+                    .first()
+                    .map(|message| snippet(&message.text, terms))
+                    .unwrap_or_default(),
+            ),
+        };
+        ConversationSearchHit {
+            conversation_id: conversation.id.clone(),
+            title: conversation.title.clone(),
+            timestamp: conversation.timestamp,
+            message_id,
+            snippet,
+            score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn conversation(id: &str, title: &str, messages: Value) -> Conversation {
+        serde_json::from_value(json!({
+            "id": id, "title": title, "timestamp": 1, "messages": messages
+        }))
+        .unwrap()
+    }
+
+    fn gear_conversation() -> Conversation {
+        conversation(
+            "gears",
+            "Gear rack",
+            json!([
+                { "id": "u1", "role": "user", "timestamp": 1,
+                  "content": [{ "type": "text", "text": "Add a rack for the spur gear" }] },
+                { "id": "t1", "role": "assistant", "timestamp": 2, "content": [
+                    { "type": "tool-call", "toolCallId": "c1", "toolName": "apply_edit",
+                      "state": "completed",
+                      "args": { "old_string": "", "new_string": "module gear_rack(teeth = 12) {\n  cube([teeth * 3, 5, 5]);\n}" } },
+                    { "type": "tool-result", "toolCallId": "c1",
+                      "result": { "status": "success", "__checkpointId": "ckpt-9",
+                                  "image_data_url": "data:image/png;base64,Z2VhcnM=" } }
+                ] },
+                { "id": "a1", "role": "assistant", "timestamp": 3, "turnId": "turn",
+                  "state": "complete",
+                  "content": [{ "type": "text", "text": "Added `gear_rack` with 12 teeth." }] }
+            ]),
+        )
+    }
+
+    #[test]
+    fn indexes_tool_calls_and_ranks_conversations() {
+        let mut stored = StoredConversationIndex::default();
+        stored.upsert(&gear_conversation());
+        stored.upsert(&conversation(
+            "box",
+            "Storage box",
+            json!([
+                { "id": "u1", "role": "user", "timestamp": 1,
+                  "content": [{ "type": "text", "text": "A box with a lid, like a cube with rounded edges" }] }
+            ]),
+        ));
+        assert_eq!(stored.conversations[0].id, "box");
+        let tool_text = &stored.conversations[1].messages[1].text;
+        assert!(tool_text.contains("module gear_rack"));
+        assert!(!tool_text.contains("ckpt-9") && !tool_text.contains("base64"));
+
+        let index = ConversationIndex::new(stored);
+        let hits = index.search("gear_rack teeth", None);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].conversation_id, "gears");
+        assert_eq!(hits[0].message_id.as_deref(), Some("t1"));
+        assert!(hits[0]
+            .snippet
+            .starts_with("apply_edit module gear_rack(teeth = 12)"));
+
+        let hits = index.search("cube", None);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].conversation_id, "box");
+
+        let hits = index.search("storage", None);
+        assert_eq!(hits[0].message_id, None);
+        assert!(index.search("  ", None).is_empty());
+        assert!(index.search("zzz", None).is_empty());
+    }
+
+    #[test]
+    fn saving_replaces_the_previous_entry() {
+        let mut stored = StoredConversationIndex::default();
+        stored.upsert(&gear_conversation());
+        let mut renamed = gear_conversation();
+        renamed.title = "Helical gears".into();
+        renamed.messages.truncate(1);
+        stored.upsert(&renamed);
+        assert_eq!(stored.version, INDEX_VERSION);
+        assert_eq!(stored.conversations.len(), 1);
+
+        let index = ConversationIndex::new(stored);
+        assert!(index.search("gear_rack", None).is_empty());
+        assert_eq!(index.search("helical", None)[0].title, "Helical gears");
+    }
+
+    #[test]
+    fn snippets_are_windows_around_the_match() {
+        let text = format!("{} needle {}", "word ".repeat(30), "word ".repeat(40));
+        let snippet = snippet(&text, &["needle".to_string()]);
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert_eq!(snippet.chars().count(), SNIPPET_CHARS + 2);
+        assert_eq!(
+            snippet.find("needle"),
+            Some('…'.len_utf8() + SNIPPET_LEAD_CHARS)
+        );
+    }
+}
@@ -0,0 +1,311 @@
+/**
+ * Customizer parameter schema
+ *
+ * Reads OpenSCAD customizer annotations on top-level variables:
+ *
+ * - `/* [Group] */` starts a parameter group; `/* [Hidden] */` hides the
+ *   variables that follow
+ * - a `// comment` on the line above a variable is its description
+ * - a trailing `// [min:max]` or `// [min:step:max]` makes a slider,
+ *   `// [a, b, c]` or `// [10:Small, 20:Large]` a dropdown, and `// [n]` on a
+ *   string a maximum length
+ *
+ * Like OpenSCAD, only literal values declared before the first module or
+ * function definition are parameters.
+ */
+use crate::variables::{find_top_level_variables, VariableKind};
+use serde::Serialize;
+
+pub const DEFAULT_GROUP: &str = "Parameters";
+const HIDDEN_GROUP: &str = "Hidden";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DropdownOption {
+    /// OpenSCAD literal passed to `-D`
+    pub value: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ParameterWidget {
+    Input,
+    Checkbox,
+    Slider {
+        min: f64,
+        max: f64,
+        step: Option<f64>,
+    },
+    Dropdown {
+        options: Vec<DropdownOption>,
+    },
+    Text {
+        max_length: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomizerParameter {
+    pub name: String,
+    pub group: String,
+    pub description: Option<String>,
+    pub kind: VariableKind,
+    /// Source text of the assigned literal
+    pub default_value: String,
+    pub widget: ParameterWidget,
+    /// 1-based line of the assignment
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomizerSchema {
+    /// Group names in source order
+    pub groups: Vec<String>,
+    pub parameters: Vec<CustomizerParameter>,
+}
+
+/// Text of a `// ...` comment on `line`, skipping `//` inside strings
+fn line_comment(line: &str) -> Option<&str> {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '/' if !in_string && line[index + 1..].starts_with('/') => {
+                return Some(line[index + 2..].trim());
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `/* [Name] */` group marker
+fn group_marker(line: &str) -> Option<&str> {
+    let inner = line
+        .trim()
+        .strip_prefix("/*")?
+        .strip_suffix("*/")?
+        .trim()
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .trim();
+    (!inner.is_empty()).then_some(inner)
+}
+
+fn is_definition(line: &str) -> bool {
+    let line = line.trim_start();
+    ["module ", "function "]
+        .iter()
+        .any(|keyword| line.starts_with(keyword))
+}
+
+fn widget(kind: &VariableKind, annotation: Option<&str>) -> ParameterWidget {
+    let default = match kind {
+        VariableKind::Boolean => ParameterWidget::Checkbox,
+        VariableKind::String => ParameterWidget::Text { max_length: None },
+        _ => ParameterWidget::Input,
+    };
+    let Some(spec) = annotation
+        .and_then(|comment| comment.strip_prefix('['))
+        .and_then(|comment| comment.split_once(']'))
+        .map(|(spec, _)| spec.trim())
+    else {
+        return default;
+    };
+
+    if spec.contains(',') {
+        let options = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|option| !option.is_empty())
+            .map(|option| match option.split_once(':') {
+                Some((value, label)) => (value.trim(), label.trim()),
+                None => (option, option),
+            })
+            .map(|(value, label)| DropdownOption {
+                value: match kind {
+                    VariableKind::String if !value.starts_with('"') => format!("\"{value}\""),
+                    _ => value.to_string(),
+                },
+                label: label.to_string(),
+            })
+            .collect();
+        return ParameterWidget::Dropdown { options };
+    }
+
+    let numbers: Option<Vec<f64>> = spec
+        .split(':')
+        .map(|part| part.trim().parse::<f64>().ok())
+        .collect();
+    match (kind, numbers.as_deref()) {
+        (VariableKind::String, Some([max_length])) => ParameterWidget::Text {
+            max_length: Some(*max_length as usize),
+        },
+        (VariableKind::Number, Some([max])) => ParameterWidget::Slider {
+            min: 0.0,
+            max: *max,
+            step: None,
+        },
+        (VariableKind::Number, Some([min, max])) => ParameterWidget::Slider {
+            min: *min,
+            max: *max,
+            step: None,
+        },
+        (VariableKind::Number, Some([min, step, max])) => ParameterWidget::Slider {
+            min: *min,
+            max: *max,
+            step: Some(*step),
+        },
+        _ => default,
+    }
+}
+
+pub fn customizer_schema(code: &str) -> CustomizerSchema {
+    let lines: Vec<&str> = code.lines().collect();
+    let first_definition = lines
+        .iter()
+        .position(|line| is_definition(line))
+        .map_or(usize::MAX, |index| index + 1);
+
+    let mut groups = Vec::new();
+    let mut group_starts = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(name) = group_marker(line) {
+            group_starts.push((index + 1, name.to_string()));
+            if name != HIDDEN_GROUP && !groups.iter().any(|group| group == name) {
+                groups.push(name.to_string());
+            }
+        }
+    }
+
+    let mut parameters = Vec::new();
+    for variable in find_top_level_variables(code) {
+        if variable.line >= first_definition
+            || variable.kind == VariableKind::Expression
+            || variable.name.starts_with('$')
+        {
+            continue;
+        }
+        let group = group_starts
+            .iter()
+            .rev()
+            .find(|(line, _)| *line < variable.line)
+            .map_or(DEFAULT_GROUP, |(_, name)| name.as_str());
+        if group == HIDDEN_GROUP {
+            continue;
+        }
+        let source_line = lines.get(variable.line - 1).copied().unwrap_or_default();
+        let description = variable
+            .line
+            .checked_sub(2)
+            .and_then(|index| lines.get(index))
+            .filter(|line| line.trim_start().starts_with("//"))
+            .and_then(|line| line_comment(line))
+            .filter(|comment| !comment.is_empty())
+            .map(str::to_string);
+
+        parameters.push(CustomizerParameter {
+            widget: widget(&variable.kind, line_comment(source_line)),
+            name: variable.name,
+            group: group.to_string(),
+            description,
+            kind: variable.kind,
+            default_value: variable.value,
+            line: variable.line,
+        });
+    }
+    parameters.sort_by_key(|parameter| parameter.line);
+
+    if parameters.iter().any(|p| p.group == DEFAULT_GROUP) {
+        groups.insert(0, DEFAULT_GROUP.to_string());
+    }
+    groups.retain(|group| parameters.iter().any(|p| p.group == *group));
+    CustomizerSchema { groups, parameters }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_annotations_and_groups() {
+        let code = r#"// Overall width
+width = 40; // [10:100]
+wall = 2; // [0.8:0.4:4]
+/* [Label] */
+label = "Hi"; // [12]
+font = "Liberation Sans"; // [Liberation Sans, DejaVu Sans]
+size = 20; // [10:Small, 20:Medium, 30:Large]
+rounded = true;
+/* [Hidden] */
+eps = 0.01;
+/* [Label] */
+inner = width - 2 * wall;
+module body() cube(width);
+later = 5;
+"#;
+        let schema = customizer_schema(code);
+        let names: Vec<_> = schema.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["width", "wall", "label", "font", "size", "rounded"]
+        );
+        assert_eq!(schema.groups, vec!["Parameters", "Label"]);
+
+        let width = &schema.parameters[0];
+        assert_eq!(width.description.as_deref(), Some("Overall width"));
+        assert_eq!(
+            width.widget,
+            ParameterWidget::Slider {
+                min: 10.0,
+                max: 100.0,
+                step: None
+            }
+        );
+        assert_eq!(
+            schema.parameters[1].widget,
+            ParameterWidget::Slider {
+                min: 0.8,
+                max: 4.0,
+                step: Some(0.4)
+            }
+        );
+        assert_eq!(schema.parameters[2].group, "Label");
+        assert_eq!(
+            schema.parameters[2].widget,
+            ParameterWidget::Text {
+                max_length: Some(12)
+            }
+        );
+        let ParameterWidget::Dropdown { options } = &schema.parameters[3].widget else {
+            panic!("font should be a dropdown");
+        };
+        assert_eq!(options[1].value, "\"DejaVu Sans\"");
+        let ParameterWidget::Dropdown { options } = &schema.parameters[4].widget else {
+            panic!("size should be a dropdown");
+        };
+        assert_eq!(
+            options[2],
+            DropdownOption {
+                value: "30".into(),
+                label: "Large".into()
+            }
+        );
+        assert_eq!(schema.parameters[5].widget, ParameterWidget::Checkbox);
+    }
+
+    #[test]
+    fn comment_detection_skips_strings() {
+        assert_eq!(
+            line_comment("url = \"http://x\"; // [a, b]"),
+            Some("[a, b]")
+        );
+        assert_eq!(line_comment("url = \"http://x\";"), None);
+    }
+}
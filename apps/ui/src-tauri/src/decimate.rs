@@ -0,0 +1,425 @@
+/**
+ * Mesh decimation
+ *
+ * Quadric error metric edge collapse (Garland & Heckbert): each vertex
+ * accumulates the planes of its faces, and the cheapest edge is collapsed
+ * repeatedly until the target triangle count is reached or the next
+ * collapse would move the surface further than the error tolerance.
+ * Collapses that would flip a face or pinch the surface are skipped, so
+ * closed manifold input stays closed.
+ */
+use crate::geometry::Triangle;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DecimationOptions {
+    /// Stop once the mesh has at most this many triangles
+    pub target_triangles: Option<usize>,
+    /// Maximum surface deviation (model units) a collapse may introduce
+    pub max_error: Option<f64>,
+}
+
+type Vec3 = [f64; 3];
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn face_normal(a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    cross(sub(b, a), sub(c, a))
+}
+
+/// Symmetric 4x4 quadric stored as its upper triangle
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(n: Vec3, d: f64) -> Self {
+        let [a, b, c] = n;
+        Quadric([
+            a * a,
+            a * b,
+            a * c,
+            a * d,
+            b * b,
+            b * c,
+            b * d,
+            c * c,
+            c * d,
+            d * d,
+        ])
+    }
+
+    fn add(&self, other: &Quadric) -> Quadric {
+        let mut sum = self.0;
+        for (value, other) in sum.iter_mut().zip(other.0) {
+            *value += other;
+        }
+        Quadric(sum)
+    }
+
+    fn error(&self, p: Vec3) -> f64 {
+        let q = &self.0;
+        let [x, y, z] = p;
+        q[0] * x * x
+            + 2.0 * q[1] * x * y
+            + 2.0 * q[2] * x * z
+            + 2.0 * q[3] * x
+            + q[4] * y * y
+            + 2.0 * q[5] * y * z
+            + 2.0 * q[6] * y
+            + q[7] * z * z
+            + 2.0 * q[8] * z
+            + q[9]
+    }
+
+    /// Position minimizing the error, if the system is well conditioned
+    fn optimum(&self) -> Option<Vec3> {
+        let q = &self.0;
+        let m = [[q[0], q[1], q[2]], [q[1], q[4], q[5]], [q[2], q[5], q[7]]];
+        let rhs = [-q[3], -q[6], -q[8]];
+        let det = dot(m[0], cross(m[1], m[2]));
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        // Cramer's rule
+        let column = |i: usize| {
+            let mut replaced = m;
+            for (row, value) in replaced.iter_mut().zip(rhs) {
+                row[i] = value;
+            }
+            dot(replaced[0], cross(replaced[1], replaced[2])) / det
+        };
+        Some([column(0), column(1), column(2)])
+    }
+}
+
+struct Candidate {
+    cost: f64,
+    keep: usize,
+    remove: usize,
+    position: Vec3,
+    stamps: (u32, u32),
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost.total_cmp(&other.cost) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    // Reversed so the max-heap pops the cheapest collapse first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+struct Mesh {
+    positions: Vec<Vec3>,
+    quadrics: Vec<Quadric>,
+    stamps: Vec<u32>,
+    vertex_alive: Vec<bool>,
+    faces: Vec<[usize; 3]>,
+    face_alive: Vec<bool>,
+    vertex_faces: Vec<Vec<usize>>,
+    live_faces: usize,
+}
+
+impl Mesh {
+    fn from_triangles(triangles: &[Triangle]) -> Self {
+        let mut index: HashMap<[u64; 3], usize> = HashMap::new();
+        let mut positions = Vec::new();
+        let mut faces = Vec::with_capacity(triangles.len());
+        for triangle in triangles {
+            let mut face = [0; 3];
+            for (slot, vertex) in face.iter_mut().zip(triangle) {
+                let key = vertex.map(f64::to_bits);
+                *slot = *index.entry(key).or_insert_with(|| {
+                    positions.push(*vertex);
+                    positions.len() - 1
+                });
+            }
+            if face[0] != face[1] && face[1] != face[2] && face[0] != face[2] {
+                faces.push(face);
+            }
+        }
+
+        let mut quadrics = vec![Quadric::default(); positions.len()];
+        let mut vertex_faces = vec![Vec::new(); positions.len()];
+        for (f, face) in faces.iter().enumerate() {
+            let [a, b, c] = face.map(|v| positions[v]);
+            let n = face_normal(a, b, c);
+            let length = dot(n, n).sqrt();
+            if length > 0.0 {
+                let n = n.map(|x| x / length);
+                let plane = Quadric::from_plane(n, -dot(n, a));
+                for &v in face {
+                    quadrics[v] = quadrics[v].add(&plane);
+                }
+            }
+            for &v in face {
+                vertex_faces[v].push(f);
+            }
+        }
+
+        Mesh {
+            stamps: vec![0; positions.len()],
+            vertex_alive: vec![true; positions.len()],
+            face_alive: vec![true; faces.len()],
+            live_faces: faces.len(),
+            positions,
+            quadrics,
+            faces,
+            vertex_faces,
+        }
+    }
+
+    fn neighbors(&self, v: usize) -> HashSet<usize> {
+        self.vertex_faces[v]
+            .iter()
+            .filter(|&&f| self.face_alive[f])
+            .flat_map(|&f| self.faces[f])
+            .filter(|&w| w != v)
+            .collect()
+    }
+
+    fn candidate(&self, a: usize, b: usize) -> Candidate {
+        let quadric = self.quadrics[a].add(&self.quadrics[b]);
+        let (pa, pb) = (self.positions[a], self.positions[b]);
+        let midpoint = [
+            (pa[0] + pb[0]) / 2.0,
+            (pa[1] + pb[1]) / 2.0,
+            (pa[2] + pb[2]) / 2.0,
+        ];
+        let (position, cost) = quadric
+            .optimum()
+            .into_iter()
+            .chain([pa, pb, midpoint])
+            .map(|p| (p, quadric.error(p).max(0.0)))
+            .min_by(|x, y| x.1.total_cmp(&y.1))
+            .unwrap_or((midpoint, 0.0));
+        Candidate {
+            cost,
+            keep: a,
+            remove: b,
+            position,
+            stamps: (self.stamps[a], self.stamps[b]),
+        }
+    }
+
+    /// Moving `keep`/`remove` to `position` must not flip or degenerate any
+    /// surviving face, and the edge must have exactly two shared neighbours.
+    fn can_collapse(&self, keep: usize, remove: usize, position: Vec3) -> bool {
+        let shared = self
+            .neighbors(keep)
+            .intersection(&self.neighbors(remove))
+            .count();
+        if shared != 2 {
+            return false;
+        }
+        for &v in &[keep, remove] {
+            for &f in &self.vertex_faces[v] {
+                if !self.face_alive[f] {
+                    continue;
+                }
+                let face = self.faces[f];
+                if face.contains(&keep) && face.contains(&remove) {
+                    continue;
+                }
+                let before = face.map(|w| self.positions[w]);
+                let after = face.map(|w| {
+                    if w == keep || w == remove {
+                        position
+                    } else {
+                        self.positions[w]
+                    }
+                });
+                let n_before = face_normal(before[0], before[1], before[2]);
+                let n_after = face_normal(after[0], after[1], after[2]);
+                if dot(n_before, n_after) <= 0.0 {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn collapse(&mut self, keep: usize, remove: usize, position: Vec3) {
+        for f in std::mem::take(&mut self.vertex_faces[remove]) {
+            if !self.face_alive[f] {
+                continue;
+            }
+            if self.faces[f].contains(&keep) {
+                self.face_alive[f] = false;
+                self.live_faces -= 1;
+            } else {
+                for v in &mut self.faces[f] {
+                    if *v == remove {
+                        *v = keep;
+                    }
+                }
+                self.vertex_faces[keep].push(f);
+            }
+        }
+        let face_alive = &self.face_alive;
+        self.vertex_faces[keep].retain(|&f| face_alive[f]);
+        self.positions[keep] = position;
+        self.quadrics[keep] = self.quadrics[keep].add(&self.quadrics[remove]);
+        self.vertex_alive[remove] = false;
+        self.stamps[keep] += 1;
+    }
+}
+
+/// Simplify `triangles` until `options.target_triangles` or `max_error` stops it
+pub fn decimate(
+    triangles: &[Triangle],
+    options: &DecimationOptions,
+) -> Result<Vec<Triangle>, String> {
+    if options.target_triangles.is_none() && options.max_error.is_none() {
+        return Err("Set a target triangle count or an error tolerance".into());
+    }
+    let target = options.target_triangles.unwrap_or(0);
+    let max_cost = options.max_error.map(|error| error * error);
+
+    let mut mesh = Mesh::from_triangles(triangles);
+    let mut heap = BinaryHeap::new();
+    let edges: HashSet<(usize, usize)> = mesh
+        .faces
+        .iter()
+        .flat_map(|f| [(f[0], f[1]), (f[1], f[2]), (f[2], f[0])])
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .collect();
+    for (a, b) in edges {
+        heap.push(mesh.candidate(a, b));
+    }
+
+    while mesh.live_faces > target {
+        let Some(candidate) = heap.pop() else { break };
+        let Candidate {
+            cost,
+            keep,
+            remove,
+            position,
+            stamps,
+        } = candidate;
+        if !mesh.vertex_alive[keep]
+            || !mesh.vertex_alive[remove]
+            || stamps != (mesh.stamps[keep], mesh.stamps[remove])
+        {
+            continue;
+        }
+        if max_cost.is_some_and(|max| cost > max) {
+            break;
+        }
+        if !mesh.can_collapse(keep, remove, position) {
+            continue;
+        }
+        mesh.collapse(keep, remove, position);
+        for neighbor in mesh.neighbors(keep) {
+            heap.push(mesh.candidate(keep, neighbor));
+        }
+    }
+
+    Ok(mesh
+        .faces
+        .iter()
+        .zip(&mesh.face_alive)
+        .filter(|(_, &alive)| alive)
+        .map(|(face, _)| face.map(|v| mesh.positions[v]))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::triangle_stats;
+
+    /// Unit cube with each face split into an n x n grid (outward winding)
+    fn tessellated_cube(n: usize) -> Vec<Triangle> {
+        let mut triangles = Vec::new();
+        let step = 1.0 / n as f64;
+        for axis in 0..3 {
+            for side in [0.0, 1.0] {
+                let (u_axis, v_axis) = ((axis + 1) % 3, (axis + 2) % 3);
+                let point = |i: usize, j: usize| {
+                    let mut p = [0.0; 3];
+                    p[axis] = side;
+                    p[u_axis] = i as f64 * step;
+                    p[v_axis] = j as f64 * step;
+                    p
+                };
+                for i in 0..n {
+                    for j in 0..n {
+                        let (a, b, c, d) = (
+                            point(i, j),
+                            point(i + 1, j),
+                            point(i + 1, j + 1),
+                            point(i, j + 1),
+                        );
+                        if side == 1.0 {
+                            triangles.push([a, b, c]);
+                            triangles.push([a, c, d]);
+                        } else {
+                            triangles.push([a, c, b]);
+                            triangles.push([a, d, c]);
+                        }
+                    }
+                }
+            }
+        }
+        triangles
+    }
+
+    #[test]
+    fn flat_regions_collapse_without_changing_shape() {
+        let cube = tessellated_cube(8);
+        let before = triangle_stats(&cube);
+        assert_eq!(before.triangle_count, 768);
+
+        let options = DecimationOptions {
+            max_error: Some(1e-6),
+            ..Default::default()
+        };
+        let reduced = decimate(&cube, &options).unwrap();
+        let after = triangle_stats(&reduced);
+        assert!(after.triangle_count < 100, "{}", after.triangle_count);
+        assert!((after.volume - 1.0).abs() < 1e-9);
+        assert_eq!(after.size, before.size);
+    }
+
+    #[test]
+    fn stops_at_target_triangle_count() {
+        let cube = tessellated_cube(4);
+        let options = DecimationOptions {
+            target_triangles: Some(100),
+            ..Default::default()
+        };
+        let reduced = decimate(&cube, &options).unwrap();
+        assert!(reduced.len() <= 100 && reduced.len() >= 98);
+        assert!(decimate(&cube, &DecimationOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+/**
+ * Bundled OpenSCAD documentation
+ *
+ * A condensed copy of the OpenSCAD manual/cheat sheet is compiled into the
+ * binary and split into sections on `##` headings. `docs_index` searches it
+ * together with the library cheat sheets and installed libraries.
+ */
+use serde::Serialize;
+use std::sync::OnceLock;
+
+const REFERENCE: &str = include_str!("../docs/openscad-reference.md");
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocSection {
+    pub title: String,
+    pub content: String,
+}
+
+/// Sections of a markdown document, one per `##` heading
+pub fn parse_sections(markdown: &str) -> Vec<DocSection> {
+    let mut sections = Vec::new();
+    let mut current: Option<DocSection> = None;
+
+    for line in markdown.lines() {
+        if let Some(title) = line.strip_prefix("## ") {
+            sections.extend(current.take());
+            current = Some(DocSection {
+                title: title.trim().to_string(),
+                content: String::new(),
+            });
+        } else if let Some(section) = current.as_mut() {
+            section.content.push_str(line);
+            section.content.push('\n');
+        }
+    }
+    sections.extend(current);
+
+    for section in &mut sections {
+        section.content = section.content.trim().to_string();
+    }
+    sections
+}
+
+pub fn sections() -> &'static [DocSection] {
+    static SECTIONS: OnceLock<Vec<DocSection>> = OnceLock::new();
+    SECTIONS.get_or_init(|| parse_sections(REFERENCE))
+}
+
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_reference_has_sections() {
+        let titles: Vec<_> = sections().iter().map(|s| s.title.as_str()).collect();
+        assert!(titles.contains(&"linear_extrude"));
+        assert!(sections().iter().all(|s| !s.content.is_empty()));
+    }
+}
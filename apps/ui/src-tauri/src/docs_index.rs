@@ -0,0 +1,450 @@
+/**
+ * Documentation index for AI context
+ *
+ * A keyword (BM25) index over the bundled OpenSCAD reference, the BOSL2 and
+ * MCAD cheat sheets, and the doc comments above the modules and functions of
+ * installed libraries, so the agent can look library APIs up instead of
+ * guessing them. Library entries are scanned on demand and stored on disk
+ * with a fingerprint per library; the store is rebuilt when a library is
+ * installed, updated or removed. Bundled sections always come from the
+ * binary and are never stored.
+ */
+use crate::docs::{parse_sections, sections, tokenize};
+use crate::libraries::read_manifest;
+use crate::symbols::{document_symbols, SymbolKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+pub const INDEX_FILE_NAME: &str = "docs-index.json";
+/// Bump when the stored format or the way libraries are scanned changes
+pub const INDEX_VERSION: u32 = 1;
+pub const MANUAL_SOURCE: &str = "OpenSCAD";
+
+const DEFAULT_LIMIT: usize = 5;
+const TITLE_WEIGHT: f64 = 5.0;
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+const MAX_LIBRARY_FILES: usize = 2_000;
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+const MAX_ENTRY_CHARS: usize = 1_500;
+/// Folders of library checkouts that hold no library code
+const SKIPPED_DIRS: &[&str] = &[
+    "examples",
+    "images",
+    "scripts",
+    "test",
+    "tests",
+    "tutorials",
+];
+
+const CHEAT_SHEETS: &[(&str, &str)] = &[
+    ("BOSL2", include_str!("../docs/bosl2-cheatsheet.md")),
+    ("MCAD", include_str!("../docs/mcad-cheatsheet.md")),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocEntry {
+    /// "OpenSCAD" or the library name
+    pub source: String,
+    pub title: String,
+    /// Library-relative file a library entry was read from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedLibrary {
+    pub name: String,
+    /// Changes whenever the library is reinstalled or updated
+    pub fingerprint: String,
+    pub files: usize,
+    pub entries: usize,
+}
+
+/// `docs-index.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredDocsIndex {
+    pub version: u32,
+    pub built_at: i64,
+    pub libraries: Vec<IndexedLibrary>,
+    pub entries: Vec<DocEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocsSearchResult {
+    #[serde(flatten)]
+    pub entry: DocEntry,
+    pub score: f64,
+}
+
+struct IndexedDoc {
+    entry: DocEntry,
+    title_terms: HashMap<String, usize>,
+    body_terms: HashMap<String, usize>,
+    len: usize,
+}
+
+pub struct DocsIndex {
+    pub stored: StoredDocsIndex,
+    docs: Vec<IndexedDoc>,
+    doc_freq: HashMap<String, usize>,
+    avg_len: f64,
+}
+
+fn term_counts(text: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for term in tokenize(text) {
+        *counts.entry(term).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Sections of the bundled reference and cheat sheets
+pub fn bundled_entries() -> Vec<DocEntry> {
+    let manual = sections().iter().map(|section| DocEntry {
+        source: MANUAL_SOURCE.to_string(),
+        title: section.title.clone(),
+        file: None,
+        content: section.content.clone(),
+    });
+    let cheat_sheets = CHEAT_SHEETS.iter().flat_map(|(source, markdown)| {
+        parse_sections(markdown)
+            .into_iter()
+            .map(|section| DocEntry {
+                source: source.to_string(),
+                title: section.title,
+                file: None,
+                content: section.content,
+            })
+    });
+    manual.chain(cheat_sheets).collect()
+}
+
+/// The `//` comment block right above 1-based `line`, stopping at the
+/// examples of BOSL2-style doc blocks
+fn doc_comment(lines: &[&str], line: usize) -> String {
+    let mut end = line.saturating_sub(1);
+    if end > 0 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    let mut start = end;
+    while start > 0 && lines[start - 1].trim_start().starts_with("//") {
+        start -= 1;
+    }
+    let mut doc = Vec::new();
+    for line in &lines[start..end] {
+        let text = line.trim_start().trim_start_matches('/');
+        let text = text.strip_prefix(' ').unwrap_or(text).trim_end();
+        if text.starts_with("Example") {
+            break;
+        }
+        if !text.chars().all(|c| c == '-' || c == '=' || c == '*') {
+            doc.push(text);
+        }
+    }
+    doc.join("\n").trim().to_string()
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// One entry per public module or function defined in a library file
+pub fn library_file_entries(source: &str, file: &str, code: &str) -> Vec<DocEntry> {
+    let lines: Vec<&str> = code.lines().collect();
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for symbol in document_symbols(code) {
+        let kind = match symbol.kind {
+            SymbolKind::Module => "module",
+            SymbolKind::Function => "function",
+            SymbolKind::Variable => continue,
+        };
+        if symbol.name.starts_with('_') || !seen.insert(symbol.name.clone()) {
+            continue;
+        }
+        let doc = doc_comment(&lines, symbol.line);
+        let mut content = format!("`{}` ({kind} in {file})", symbol.detail);
+        if !doc.is_empty() {
+            content.push('\n');
+            content.push_str(&doc);
+        }
+        entries.push(DocEntry {
+            source: source.to_string(),
+            title: symbol.name,
+            file: Some(file.to_string()),
+            content: truncate(&content, MAX_ENTRY_CHARS),
+        });
+    }
+    entries
+}
+
+/// Installed version for libraries installed by the app, else the folder's
+/// modification time
+pub fn library_fingerprint(dir: &Path) -> String {
+    if let Some(manifest) = read_manifest(dir) {
+        return format!("{}@{}", manifest.version, manifest.installed_at);
+    }
+    let modified = fs::metadata(dir)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs());
+    format!("modified@{modified}")
+}
+
+/// Library-relative paths of the `.scad` files under `dir`, sorted
+fn library_files(dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(folder) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&folder) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            if name.starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                if !SKIPPED_DIRS.contains(&name.to_ascii_lowercase().as_str()) {
+                    pending.push(path);
+                }
+            } else if name.ends_with(".scad") && files.len() < MAX_LIBRARY_FILES {
+                if let Ok(relative) = path.strip_prefix(dir) {
+                    files.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Scan an installed library's files for documented modules and functions
+pub fn index_library(name: &str, dir: &Path) -> (IndexedLibrary, Vec<DocEntry>) {
+    let files = library_files(dir);
+    let mut entries = Vec::new();
+    for file in &files {
+        let path = dir.join(file);
+        let too_large =
+            fs::metadata(&path).map_or(true, |metadata| metadata.len() > MAX_FILE_BYTES);
+        if too_large {
+            continue;
+        }
+        if let Ok(code) = fs::read_to_string(&path) {
+            entries.extend(library_file_entries(name, file, &code));
+        }
+    }
+    let library = IndexedLibrary {
+        name: name.to_string(),
+        fingerprint: library_fingerprint(dir),
+        files: files.len(),
+        entries: entries.len(),
+    };
+    (library, entries)
+}
+
+impl DocsIndex {
+    pub fn new(stored: StoredDocsIndex) -> Self {
+        let docs: Vec<IndexedDoc> = bundled_entries()
+            .into_iter()
+            .chain(stored.entries.iter().cloned())
+            .map(|entry| {
+                let title_terms = term_counts(&entry.title);
+                let body_terms = term_counts(&entry.content);
+                IndexedDoc {
+                    len: title_terms.values().sum::<usize>() + body_terms.values().sum::<usize>(),
+                    entry,
+                    title_terms,
+                    body_terms,
+                }
+            })
+            .collect();
+        let mut doc_freq = HashMap::new();
+        for doc in &docs {
+            let terms: HashSet<&String> = doc
+                .title_terms
+                .keys()
+                .chain(doc.body_terms.keys())
+                .collect();
+            for term in terms {
+                *doc_freq.entry(term.clone()).or_insert(0) += 1;
+            }
+        }
+        let avg_len =
+            docs.iter().map(|doc| doc.len).sum::<usize>() as f64 / docs.len().max(1) as f64;
+        Self {
+            stored,
+            docs,
+            doc_freq,
+            avg_len,
+        }
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.docs.len()
+    }
+
+    /// Whether the stored libraries are exactly `libraries` (name and
+    /// fingerprint) in the current index format
+    pub fn is_current(&self, libraries: &[(String, String)]) -> bool {
+        let stored: HashSet<(&str, &str)> = self
+            .stored
+            .libraries
+            .iter()
+            .map(|library| (library.name.as_str(), library.fingerprint.as_str()))
+            .collect();
+        let current: HashSet<(&str, &str)> = libraries
+            .iter()
+            .map(|(name, fingerprint)| (name.as_str(), fingerprint.as_str()))
+            .collect();
+        self.stored.version == INDEX_VERSION && stored == current
+    }
+
+    /// Rank entries for `query` (best first), optionally only those from
+    /// `source` ("OpenSCAD" or a library name)
+    pub fn search(
+        &self,
+        query: &str,
+        source: Option<&str>,
+        limit: Option<usize>,
+    ) -> Vec<DocsSearchResult> {
+        let mut terms = tokenize(query);
+        terms.dedup();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let total = self.docs.len() as f64;
+
+        let mut results: Vec<DocsSearchResult> = self
+            .docs
+            .iter()
+            .filter(|doc| source.is_none_or(|source| doc.entry.source.eq_ignore_ascii_case(source)))
+            .filter_map(|doc| {
+                let mut score = 0.0;
+                let mut matched_terms = 0;
+                for term in &terms {
+                    let in_title = doc.title_terms.get(term).copied().unwrap_or(0);
+                    let in_body = doc.body_terms.get(term).copied().unwrap_or(0);
+                    let frequency = in_title as f64 * TITLE_WEIGHT + in_body as f64;
+                    if frequency == 0.0 {
+                        continue;
+                    }
+                    matched_terms += 1;
+                    let with_term = self.doc_freq.get(term).copied().unwrap_or(0) as f64;
+                    let idf = (1.0 + (total - with_term + 0.5) / (with_term + 0.5)).ln();
+                    let length_norm = 1.0 - BM25_B + BM25_B * doc.len as f64 / self.avg_len;
+                    score +=
+                        idf * frequency * (BM25_K1 + 1.0) / (frequency + BM25_K1 * length_norm);
+                }
+                // Prefer entries that cover every term of the query.
+                if matched_terms == terms.len() {
+                    score *= 2.0;
+                }
+                (score > 0.0).then(|| DocsSearchResult {
+                    entry: doc.entry.clone(),
+                    score,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.entry.title.cmp(&b.entry.title))
+        });
+        results.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn searches_bundled_documentation() {
+        let index = DocsIndex::new(StoredDocsIndex::default());
+        assert!(index.entry_count() > sections().len());
+
+        let results = index.search("rotate_extrude vase", None, None);
+        assert_eq!(results[0].entry.title, "rotate_extrude");
+        assert_eq!(results[0].entry.source, MANUAL_SOURCE);
+
+        let results = index.search("$preview", None, Some(1));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entry.title, "$preview");
+
+        let results = index.search("cuboid rounding edges", None, None);
+        assert_eq!(results[0].entry.title, "cuboid");
+        assert_eq!(results[0].entry.source, "BOSL2");
+
+        let results = index.search("nut hole", Some("mcad"), None);
+        assert_eq!(results[0].entry.title, "nuts_and_bolts.scad");
+        assert!(results.iter().all(|result| result.entry.source == "MCAD"));
+
+        assert!(index.search("   ", None, None).is_empty());
+    }
+
+    #[test]
+    fn indexes_library_doc_comments() {
+        let code = "\
+// Module: pill()
+// Synopsis: A capsule.
+// Usage:
+//   pill(l, r);
+// Example:
+//   pill(20, 5);
+module pill(l, r = 1) { hull() { sphere(r); up(l) sphere(r); } }
+
+function pill_volume(l, r) = PI * r * r * (l + 4 * r / 3);
+
+// internal
+module _helper() {}
+";
+        let entries = library_file_entries("Shapes", "capsule.scad", code);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].title, "pill");
+        assert_eq!(
+            entries[0].content,
+            "`pill(l, r)` (module in capsule.scad)\nModule: pill()\nSynopsis: A capsule.\nUsage:\n  pill(l, r);"
+        );
+        assert_eq!(
+            entries[1].content,
+            "`pill_volume(l, r)` (function in capsule.scad)"
+        );
+
+        let stored = StoredDocsIndex {
+            version: INDEX_VERSION,
+            built_at: 0,
+            libraries: vec![IndexedLibrary {
+                name: "Shapes".into(),
+                fingerprint: "v1@0".into(),
+                files: 1,
+                entries: entries.len(),
+            }],
+            entries,
+        };
+        let index = DocsIndex::new(stored);
+        assert!(index.is_current(&[("Shapes".into(), "v1@0".into())]));
+        assert!(!index.is_current(&[("Shapes".into(), "v2@0".into())]));
+        assert!(!index.is_current(&[]));
+
+        let results = index.search("capsule", Some("Shapes"), None);
+        assert_eq!(results[0].entry.title, "pill");
+        assert_eq!(results[0].entry.file.as_deref(), Some("capsule.scad"));
+    }
+}
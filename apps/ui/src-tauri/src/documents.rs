@@ -0,0 +1,140 @@
+use crate::cache::RenderCache;
+use crate::history::EditorHistory;
+use crate::types::Diagnostic;
+/**
+ * Open document management
+ *
+ * A window can hold several open documents (tabs). The active document lives
+ * in `EditorState` / `HistoryState` so every existing command keeps operating
+ * on "the current buffer"; inactive documents are parked here and swapped in
+ * on switch. Render caches are kept per document for all tabs. Commands that
+ * take an optional `document_id` reach a parked document's code,
+ * diagnostics and checkpoints without switching to it.
+ */
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentInfo {
+    pub id: String,
+    pub title: String,
+    pub path: Option<String>,
+    pub is_active: bool,
+}
+
+/// Editor contents of a document that is not currently active
+pub struct ParkedDocument {
+    pub code: String,
+    pub diagnostics: Vec<Diagnostic>,
+    pub history: EditorHistory,
+}
+
+#[derive(Debug, Clone)]
+pub struct DocumentMeta {
+    pub id: String,
+    pub title: String,
+    pub path: Option<String>,
+}
+
+impl DocumentMeta {
+    /// Working directory and input file a saved document renders with, so
+    /// its relative `include`/`import` paths resolve next to the file
+    pub fn render_paths(&self) -> Option<(String, String)> {
+        let path = std::path::Path::new(self.path.as_deref()?);
+        let parent = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())?;
+        let file_name = path.file_name()?;
+        Some((
+            parent.to_string_lossy().to_string(),
+            file_name.to_string_lossy().to_string(),
+        ))
+    }
+}
+
+pub struct DocumentsInner {
+    pub active_id: String,
+    /// Tab order
+    pub documents: Vec<DocumentMeta>,
+    pub parked: HashMap<String, ParkedDocument>,
+    pub render_caches: HashMap<String, RenderCache>,
+    /// Documents over the large-file limit that were already warned about
+    pub large_files: HashSet<String>,
+}
+
+impl DocumentsInner {
+    pub fn meta(&self, id: &str) -> Option<&DocumentMeta> {
+        self.documents.iter().find(|doc| doc.id == id)
+    }
+
+    pub fn info(&self, meta: &DocumentMeta) -> DocumentInfo {
+        DocumentInfo {
+            id: meta.id.clone(),
+            title: meta.title.clone(),
+            path: meta.path.clone(),
+            is_active: meta.id == self.active_id,
+        }
+    }
+
+    pub fn list(&self) -> Vec<DocumentInfo> {
+        self.documents.iter().map(|meta| self.info(meta)).collect()
+    }
+}
+
+/// Global document state (managed by Tauri)
+pub struct DocumentsState {
+    pub inner: Mutex<DocumentsInner>,
+}
+
+pub fn untitled_document() -> DocumentMeta {
+    DocumentMeta {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: "Untitled".to_string(),
+        path: None,
+    }
+}
+
+pub fn title_for_path(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+impl Default for DocumentsState {
+    fn default() -> Self {
+        // The initial editor buffer is the first document.
+        let initial = untitled_document();
+        Self {
+            inner: Mutex::new(DocumentsInner {
+                active_id: initial.id.clone(),
+                documents: vec![initial],
+                parked: HashMap::new(),
+                render_caches: HashMap::new(),
+                large_files: HashSet::new(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_documents_render_beside_their_file() {
+        let mut meta = untitled_document();
+        assert_eq!(meta.render_paths(), None);
+
+        meta.path = Some("/home/user/parts/bracket.scad".into());
+        assert_eq!(
+            meta.render_paths(),
+            Some(("/home/user/parts".into(), "bracket.scad".into()))
+        );
+
+        meta.path = Some("bracket.scad".into());
+        assert_eq!(meta.render_paths(), None);
+    }
+}
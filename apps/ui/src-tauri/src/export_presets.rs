@@ -0,0 +1,180 @@
+/**
+ * Export quality presets
+ *
+ * A preset is a named set of `$fn` / `$fa` / `$fs` overrides plus an
+ * optional geometry backend. Exports pass the selected preset to OpenSCAD
+ * as `-D` definitions, which take precedence over the values assigned at
+ * the top of the file, so the same model can be exported as a quick draft
+ * or a smooth final part without editing it.
+ */
+use serde::{Deserialize, Serialize};
+
+pub const BACKENDS: &[&str] = &["manifold", "cgal"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPreset {
+    pub name: String,
+    /// Fixed number of fragments per circle; overrides `$fa`/`$fs` when set
+    #[serde(default)]
+    pub r#fn: Option<u32>,
+    /// Minimum angle in degrees per fragment
+    #[serde(default)]
+    pub fa: Option<f64>,
+    /// Minimum fragment length in mm
+    #[serde(default)]
+    pub fs: Option<f64>,
+    /// `manifold` or `cgal`; `None` keeps the backend the export asked for
+    #[serde(default)]
+    pub backend: Option<String>,
+}
+
+fn preset(name: &str, fa: f64, fs: f64) -> ExportPreset {
+    ExportPreset {
+        name: name.to_string(),
+        r#fn: None,
+        fa: Some(fa),
+        fs: Some(fs),
+        backend: Some("manifold".to_string()),
+    }
+}
+
+/// Presets available before the user defines their own
+pub fn builtin_presets() -> Vec<ExportPreset> {
+    vec![
+        preset("draft", 12.0, 2.0),
+        preset("normal", 6.0, 0.5),
+        preset("fine", 2.0, 0.2),
+    ]
+}
+
+impl ExportPreset {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Export presets need a name".into());
+        }
+        if self.r#fn == Some(0) {
+            return Err(format!("Preset '{}': $fn must be at least 1", self.name));
+        }
+        for (variable, value) in [("$fa", self.fa), ("$fs", self.fs)] {
+            if value.is_some_and(|value| !value.is_finite() || value <= 0.0) {
+                return Err(format!(
+                    "Preset '{}': {variable} must be a positive number",
+                    self.name
+                ));
+            }
+        }
+        if let Some(backend) = &self.backend {
+            if !BACKENDS.contains(&backend.as_str()) {
+                return Err(format!(
+                    "Preset '{}': unknown backend '{backend}' (expected {})",
+                    self.name,
+                    BACKENDS.join(" or ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Add this preset's overrides to OpenSCAD `args`, replacing any backend
+    /// they already select when the preset has one
+    pub fn apply(&self, args: &mut Vec<String>) -> Result<(), String> {
+        self.validate()?;
+        if let Some(backend) = &self.backend {
+            args.retain(|arg| !arg.starts_with("--backend"));
+            args.push(format!("--backend={backend}"));
+        }
+        if let Some(segments) = self.r#fn {
+            args.extend(["-D".to_string(), format!("$fn={segments}")]);
+        }
+        if let Some(fa) = self.fa {
+            args.extend(["-D".to_string(), format!("$fa={fa}")]);
+        }
+        if let Some(fs) = self.fs {
+            args.extend(["-D".to_string(), format!("$fs={fs}")]);
+        }
+        Ok(())
+    }
+}
+
+/// Check a full preset list and the active preset name before saving
+pub fn validate_presets(presets: &[ExportPreset], active: &str) -> Result<(), String> {
+    for (index, preset) in presets.iter().enumerate() {
+        preset.validate()?;
+        if presets[..index]
+            .iter()
+            .any(|other| other.name == preset.name)
+        {
+            return Err(format!("Duplicate export preset '{}'", preset.name));
+        }
+    }
+    if !presets.iter().any(|preset| preset.name == active) {
+        return Err(format!("No export preset named '{active}'"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_overrides_and_backend() {
+        let mut args = vec![
+            "/input.scad".to_string(),
+            "-o".to_string(),
+            "/output.stl".to_string(),
+            "--backend=cgal".to_string(),
+        ];
+        let preset = ExportPreset {
+            r#fn: Some(64),
+            ..preset("smooth", 1.0, 0.1)
+        };
+        preset.apply(&mut args).unwrap();
+        assert_eq!(
+            args[3..],
+            [
+                "--backend=manifold",
+                "-D",
+                "$fn=64",
+                "-D",
+                "$fa=1",
+                "-D",
+                "$fs=0.1"
+            ]
+        );
+
+        let keep_backend = ExportPreset {
+            name: "coarse".into(),
+            r#fn: Some(12),
+            fa: None,
+            fs: None,
+            backend: None,
+        };
+        let mut args = vec!["--backend=cgal".to_string()];
+        keep_backend.apply(&mut args).unwrap();
+        assert_eq!(args, ["--backend=cgal", "-D", "$fn=12"]);
+    }
+
+    #[test]
+    fn rejects_invalid_presets() {
+        let presets = builtin_presets();
+        assert!(validate_presets(&presets, "normal").is_ok());
+        assert!(validate_presets(&presets, "ultra").is_err());
+
+        let mut duplicated = presets.clone();
+        duplicated.push(preset("draft", 1.0, 1.0));
+        assert!(validate_presets(&duplicated, "draft").is_err());
+
+        assert!(preset("zero", 0.0, 1.0).validate().is_err());
+        let unknown_backend = ExportPreset {
+            backend: Some("opencascade".into()),
+            ..preset("x", 1.0, 1.0)
+        };
+        assert!(unknown_backend.validate().is_err());
+
+        let parsed: ExportPreset = serde_json::from_str(r#"{"name": "rings", "fn": 48}"#).unwrap();
+        assert_eq!(parsed.r#fn, Some(48));
+        assert_eq!(parsed.backend, None);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::cmd::EditorState;
+use crate::documents::DocumentsState;
+use crate::locate::plain_path;
+use crate::project::DiskChange;
+use crate::project_files::{is_asset_file, is_project_file};
+/**
+ * External file change watching
+ *
+ * Every open project folder is watched recursively, one watcher per folder
+ * however many windows have it open; the watcher stops when the last of
+ * them closes the project. A change to a project file or data asset is
+ * compared with what the editor last loaded or saved, so the app's own
+ * saves (and hidden render temp files) are ignored, and reported as a
+ * `file:changed` event. Cached renders that read the file are dropped.
+ * Clean files can simply be reloaded; for files with unsaved edits the
+ * frontend asks the user and calls `resolve_file_change` to reload or keep
+ * its version.
+ */
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Watchers by project root
+#[derive(Default)]
+pub struct FileWatcherState {
+    watchers: Mutex<HashMap<PathBuf, ProjectWatcher>>,
+}
+
+struct ProjectWatcher {
+    /// How many times the project is open
+    opened: usize,
+    _watcher: RecommendedWatcher,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChanged {
+    pub root: String,
+    /// Root-relative, `/`-separated path
+    pub path: String,
+    pub removed: bool,
+    /// The file has unsaved edits; ask before reloading
+    pub conflict: bool,
+}
+
+/// Root-relative path of a watched file worth reporting
+fn relevant_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let mut parts = Vec::new();
+    for component in relative.components() {
+        let Component::Normal(part) = component else {
+            return None;
+        };
+        let part = part.to_str()?;
+        // Hidden folders and the `.openscad-studio-*` render temp files
+        if part.starts_with('.') {
+            return None;
+        }
+        parts.push(part);
+    }
+    let relative = parts.join("/");
+    (is_project_file(&relative) || is_asset_file(&relative)).then_some(relative)
+}
+
+fn content_hash(content: &Option<Vec<u8>>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Remember `disk` as the content of `path`; false when it is what was
+/// last seen, as editors often fire several events for one save
+fn record_content(seen: &Mutex<HashMap<String, u64>>, path: &str, disk: &Option<Vec<u8>>) -> bool {
+    let hash = content_hash(disk);
+    seen.lock().unwrap().insert(path.to_string(), hash) != Some(hash)
+}
+
+fn handle_change(app: &AppHandle, root: &Path, path: &str, seen: &Mutex<HashMap<String, u64>>) {
+    let changed_path = root.join(path);
+    // Assets may be binary, so they are compared as bytes
+    let disk = std::fs::read(&changed_path).ok();
+    if !record_content(seen, path, &disk) {
+        return;
+    }
+
+    // Only the backend's own project knows its buffers; other windows'
+    // projects compare against their editor in the frontend.
+    let change = {
+        let editor = app.state::<EditorState>();
+        let guard = editor.project.lock().unwrap();
+        match guard.as_ref() {
+            Some(project) if Path::new(&project.root) == root => {
+                let text = disk
+                    .as_deref()
+                    .and_then(|bytes| std::str::from_utf8(bytes).ok());
+                project.disk_change(path, text)
+            }
+            _ => DiskChange::Clean,
+        }
+    };
+    if change == DiskChange::Unchanged {
+        return;
+    }
+
+    let documents = app.state::<DocumentsState>();
+    for cache in documents.inner.lock().unwrap().render_caches.values_mut() {
+        cache.invalidate(&changed_path);
+    }
+
+    eprintln!("[watcher] {} changed on disk ({:?})", path, change);
+    let _ = app.emit(
+        "file:changed",
+        FileChanged {
+            root: root.to_string_lossy().to_string(),
+            path: path.to_string(),
+            // An unreadable file is not necessarily gone
+            removed: !changed_path.exists(),
+            conflict: change == DiskChange::Conflict,
+        },
+    );
+}
+
+/// Start watching `root`, or count another opening of a watched project
+pub fn watch_project(app: &AppHandle, root: &str) -> Result<(), String> {
+    let root = PathBuf::from(root);
+    let state = app.state::<FileWatcherState>();
+    if let Some(watcher) = state.watchers.lock().unwrap().get_mut(&root) {
+        watcher.opened += 1;
+        return Ok(());
+    }
+    // Events may report the resolved path (e.g. /private/var on macOS).
+    let resolved_root = std::fs::canonicalize(&root)
+        .map(plain_path)
+        .unwrap_or_else(|_| root.clone());
+    let seen = Arc::new(Mutex::new(HashMap::new()));
+    let handler_app = app.clone();
+    let handler_root = root.clone();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let event = match result {
+            Ok(event) => event,
+            Err(e) => {
+                eprintln!("[watcher] Watch error: {}", e);
+                return;
+            }
+        };
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            return;
+        }
+        for path in &event.paths {
+            let relative =
+                relevant_path(&handler_root, path).or_else(|| relevant_path(&resolved_root, path));
+            if let Some(relative) = relative {
+                handle_change(&handler_app, &handler_root, &relative, &seen);
+            }
+        }
+    })
+    .map_err(|e| format!("Failed to create file watcher: {e}"))?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {e}", root.display()))?;
+
+    eprintln!("[watcher] Watching {}", root.display());
+    state
+        .watchers
+        .lock()
+        .unwrap()
+        .entry(root)
+        .or_insert(ProjectWatcher {
+            opened: 0,
+            _watcher: watcher,
+        })
+        .opened += 1;
+    Ok(())
+}
+
+/// Stop watching `root` once every window that opened it has closed it
+pub fn unwatch_project(app: &AppHandle, root: &str) {
+    let root = PathBuf::from(root);
+    let state = app.state::<FileWatcherState>();
+    let mut watchers = state.watchers.lock().unwrap();
+    let Some(watcher) = watchers.get_mut(&root) else {
+        return;
+    };
+    watcher.opened -= 1;
+    if watcher.opened == 0 {
+        watchers.remove(&root);
+        eprintln!("[watcher] Stopped watching {}", root.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_project_files_but_not_hidden_or_foreign_paths() {
+        let root = Path::new("/projects/box");
+        assert_eq!(
+            relevant_path(root, Path::new("/projects/box/parts/lid.scad")),
+            Some("parts/lid.scad".to_string())
+        );
+        assert_eq!(
+            relevant_path(root, Path::new("/projects/box/height.dat")),
+            Some("height.dat".to_string())
+        );
+        for path in [
+            "/projects/box/.openscad-studio-main-1a2b3c4d.scad",
+            "/projects/box/.git/config.json",
+            "/projects/box/notes.txt",
+            "/projects/other/main.scad",
+        ] {
+            assert_eq!(relevant_path(root, Path::new(path)), None, "{path}");
+        }
+    }
+
+    #[test]
+    fn reports_each_change_to_a_binary_asset() {
+        let dir = std::env::temp_dir().join(format!("watcher-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("logo.png");
+        let seen = Mutex::new(HashMap::new());
+
+        for bytes in [[0x89, 0xff, 0x00], [0x89, 0xfe, 0x00]] {
+            std::fs::write(&path, bytes).unwrap();
+            let disk = std::fs::read(&path).ok();
+            assert!(record_content(&seen, "logo.png", &disk));
+            // A second event for the same write is not reported again
+            assert!(!record_content(&seen, "logo.png", &disk));
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+/**
+ * Geometry statistics from STL output
+ *
+ * Parses binary or ASCII STL and computes triangle count, axis-aligned
+ * bounding box, enclosed volume (via signed tetrahedra), surface area and
+ * whether the mesh is closed (every edge shared by exactly two triangles).
+ */
+use crate::types::GeometryStats;
+use std::collections::HashMap;
+
+pub(crate) type Triangle = [[f64; 3]; 3];
+
+/// Whether `bytes` is binary STL: the size matches the triangle count in the
+/// header, even when the header itself starts with `solid`
+pub(crate) fn is_binary_stl(bytes: &[u8]) -> bool {
+    bytes
+        .get(80..84)
+        .map(|count| u32::from_le_bytes(count.try_into().unwrap()) as usize)
+        .is_some_and(|count| bytes.len() == 84 + count * 50)
+}
+
+fn parse_binary_stl(bytes: &[u8]) -> Option<Vec<Triangle>> {
+    if !is_binary_stl(bytes) {
+        return None;
+    }
+    let count = (bytes.len() - 84) / 50;
+
+    let read =
+        |offset: usize| f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()) as f64;
+    Some(
+        (0..count)
+            .map(|i| {
+                // Skip the 12-byte normal
+                let base = 84 + i * 50 + 12;
+                let vertex = |v: usize| {
+                    let offset = base + v * 12;
+                    [read(offset), read(offset + 4), read(offset + 8)]
+                };
+                [vertex(0), vertex(1), vertex(2)]
+            })
+            .collect(),
+    )
+}
+
+fn parse_ascii_stl(text: &str) -> Result<Vec<Triangle>, String> {
+    let mut vertices = Vec::new();
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some("vertex") {
+            continue;
+        }
+        let mut coords = [0.0; 3];
+        for coord in &mut coords {
+            *coord = parts
+                .next()
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| format!("Malformed STL vertex: {}", line.trim()))?;
+        }
+        vertices.push(coords);
+    }
+
+    if vertices.len() % 3 != 0 {
+        return Err("STL vertex count is not a multiple of 3".into());
+    }
+    Ok(vertices
+        .chunks_exact(3)
+        .map(|v| [v[0], v[1], v[2]])
+        .collect())
+}
+
+/// Triangles of binary or ASCII STL data
+pub(crate) fn parse_stl(bytes: &[u8]) -> Result<Vec<Triangle>, String> {
+    match parse_binary_stl(bytes) {
+        Some(triangles) => Ok(triangles),
+        None => parse_ascii_stl(&String::from_utf8_lossy(bytes)),
+    }
+}
+
+/// Binary STL with facet normals computed from the winding
+pub(crate) fn encode_binary_stl(triangles: &[Triangle]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(84 + triangles.len() * 50);
+    let mut header = [0u8; 80];
+    header[..20].copy_from_slice(b"OpenSCAD Studio mesh");
+    out.extend_from_slice(&header);
+    out.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+    for [a, b, c] in triangles {
+        let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        let n = [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ];
+        let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2])
+            .sqrt()
+            .max(f64::MIN_POSITIVE);
+        for value in n
+            .iter()
+            .map(|x| x / length)
+            .chain([a, b, c].into_iter().flatten().copied())
+        {
+            out.extend_from_slice(&(value as f32).to_le_bytes());
+        }
+        out.extend_from_slice(&[0, 0]);
+    }
+    out
+}
+
+/// Compute statistics for binary or ASCII STL data
+pub fn stl_stats(bytes: &[u8]) -> Result<GeometryStats, String> {
+    Ok(triangle_stats(&parse_stl(bytes)?))
+}
+
+/// Edges used by anything other than exactly two triangles. Vertices are
+/// matched exactly, as STL writers repeat the same coordinates per facet.
+pub(crate) fn non_manifold_edges(triangles: &[Triangle]) -> usize {
+    let key = |vertex: &[f64; 3]| vertex.map(|coord| (coord as f32).to_bits());
+    let mut edges: HashMap<([u32; 3], [u32; 3]), usize> = HashMap::new();
+    for triangle in triangles {
+        for i in 0..3 {
+            let (a, b) = (key(&triangle[i]), key(&triangle[(i + 1) % 3]));
+            if a == b {
+                continue;
+            }
+            *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+        }
+    }
+    edges.values().filter(|&&count| count != 2).count()
+}
+
+pub(crate) fn triangle_stats(triangles: &[Triangle]) -> GeometryStats {
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    let mut volume = 0.0;
+    let mut surface_area = 0.0;
+
+    for [a, b, c] in triangles {
+        for vertex in [a, b, c] {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(vertex[axis]);
+                max[axis] = max[axis].max(vertex[axis]);
+            }
+        }
+        // a · (b × c) / 6
+        volume += (a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+            + a[2] * (b[0] * c[1] - b[1] * c[0]))
+            / 6.0;
+        // |(b - a) × (c - a)| / 2
+        let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        let n = [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ];
+        surface_area += (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt() / 2.0;
+    }
+
+    if triangles.is_empty() {
+        min = [0.0; 3];
+        max = [0.0; 3];
+    }
+    let non_manifold_edges = non_manifold_edges(triangles);
+
+    GeometryStats {
+        triangle_count: triangles.len(),
+        bounding_box_min: min,
+        bounding_box_max: max,
+        size: [max[0] - min[0], max[1] - min[1], max[2] - min[2]],
+        volume: volume.abs(),
+        surface_area,
+        non_manifold_edges,
+        manifold: !triangles.is_empty() && non_manifold_edges == 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit tetrahedron scaled by 6 along x (volume = 1)
+    const TETRA: [Triangle; 4] = [
+        [[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [6.0, 0.0, 0.0]],
+        [[0.0, 0.0, 0.0], [6.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+        [[0.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]],
+        [[6.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    ];
+
+    #[test]
+    fn binary_and_ascii_stl_agree() {
+        let mut binary = vec![0u8; 80];
+        binary.extend_from_slice(&(TETRA.len() as u32).to_le_bytes());
+        let mut ascii = String::from("solid t\n");
+        for triangle in TETRA {
+            binary.extend_from_slice(&[0u8; 12]);
+            ascii.push_str("facet normal 0 0 0\nouter loop\n");
+            for vertex in triangle {
+                for coord in vertex {
+                    binary.extend_from_slice(&(coord as f32).to_le_bytes());
+                }
+                ascii.push_str(&format!(
+                    "vertex {} {} {}\n",
+                    vertex[0], vertex[1], vertex[2]
+                ));
+            }
+            binary.extend_from_slice(&[0u8; 2]);
+            ascii.push_str("endloop\nendfacet\n");
+        }
+        ascii.push_str("endsolid t\n");
+
+        let stats = stl_stats(&binary).unwrap();
+        assert_eq!(stats.triangle_count, 4);
+        assert_eq!(stats.size, [6.0, 1.0, 1.0]);
+        assert!((stats.volume - 1.0).abs() < 1e-9);
+        assert!(stats.manifold);
+        assert_eq!(stl_stats(ascii.as_bytes()).unwrap(), stats);
+        assert_eq!(stl_stats(&encode_binary_stl(&TETRA)).unwrap(), stats);
+    }
+
+    #[test]
+    fn reports_surface_area_and_open_edges() {
+        let stats = triangle_stats(&TETRA);
+        // Three right-triangle faces plus the slanted one
+        let slanted = 73.0f64.sqrt() / 2.0;
+        assert!((stats.surface_area - (3.0 + 3.0 + 0.5 + slanted)).abs() < 1e-9);
+        assert_eq!(stats.non_manifold_edges, 0);
+
+        // Dropping a face leaves its three edges with a single triangle.
+        let open = triangle_stats(&TETRA[..3]);
+        assert_eq!(open.non_manifold_edges, 3);
+        assert!(!open.manifold);
+        assert!(!triangle_stats(&[]).manifold);
+    }
+}
@@ -0,0 +1,413 @@
+use crate::batch::{export_args, EXPORT_FORMATS};
+use crate::camera::png_args;
+use crate::cmd::render::execute_render;
+use crate::headless_ai::{request_edits, HeadlessProvider};
+use crate::locate::{plain_path, system_binary_path};
+use crate::parser::parse_openscad_stderr;
+use crate::pending_edits::apply_replacements;
+use crate::types::{Diagnostic, DiagnosticSeverity};
+use crate::variables::override_args;
+/**
+ * Headless command line mode
+ *
+ * `openscad-studio --headless <command>` renders or checks a .scad file with
+ * the native OpenSCAD binary without opening a window, and prints a JSON
+ * report (output path, diagnostics, timing) to stdout for CI pipelines and
+ * scripts. `edit` first has a model change the file as a prompt file asks,
+ * and keeps the change only if the file still evaluates without errors.
+ * The exit code is non-zero when the render fails or reports errors.
+ */
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const HEADLESS_FLAG: &str = "--headless";
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "openscad-studio --headless",
+    version,
+    about = "Render, check and AI-edit OpenSCAD files without the GUI"
+)]
+struct Cli {
+    /// OpenSCAD binary to run (defaults to `openscad` on PATH)
+    #[arg(long, global = true, value_name = "PATH")]
+    openscad: Option<PathBuf>,
+    #[command(subcommand)]
+    command: HeadlessCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum HeadlessCommand {
+    /// Render a .scad file to STL, 3MF, OBJ, OFF, AMF, WRL, POV, CSG or PNG
+    Render {
+        #[command(flatten)]
+        source: SourceArgs,
+        /// Output file; the format is taken from its extension
+        #[arg(short, long)]
+        output: PathBuf,
+        /// PNG width and height in pixels
+        #[arg(long, default_value_t = 800)]
+        imgsize: u32,
+    },
+    /// Evaluate a .scad file and report diagnostics without writing output
+    Check {
+        #[command(flatten)]
+        source: SourceArgs,
+    },
+    /// Edit a .scad file with a model, as a prompt file asks. The API key is
+    /// read from ANTHROPIC_API_KEY or OPENAI_API_KEY.
+    Edit {
+        #[command(flatten)]
+        source: SourceArgs,
+        /// File with the instructions for the model
+        #[arg(long, value_name = "FILE")]
+        prompt: PathBuf,
+        #[arg(long, value_enum, default_value_t = HeadlessProvider::Anthropic)]
+        provider: HeadlessProvider,
+        /// Model id (defaults to the app's default for the provider)
+        #[arg(long)]
+        model: Option<String>,
+        /// Report the edit without writing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// What `edit` asks of the model
+struct EditRequest {
+    prompt: PathBuf,
+    provider: HeadlessProvider,
+    model: Option<String>,
+    dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+struct SourceArgs {
+    /// The .scad file to process
+    input: PathBuf,
+    /// Override a top-level variable, e.g. `-D width=20`
+    #[arg(short = 'D', value_name = "NAME=VALUE")]
+    define: Vec<String>,
+    /// Extra library search path (passed to OpenSCAD as OPENSCADPATH)
+    #[arg(short = 'L', long = "library-path", value_name = "DIR")]
+    library_paths: Vec<String>,
+    /// Give up after this many seconds
+    #[arg(long, default_value_t = 120)]
+    timeout: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HeadlessReport {
+    success: bool,
+    input: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+    diagnostics: Vec<Diagnostic>,
+    duration_ms: u64,
+    /// With `edit`: the model's description of its change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    /// With `edit`: how many replacements were made
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replacements: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Parse `NAME=VALUE` overrides
+fn parse_defines(defines: &[String]) -> Result<HashMap<String, String>, String> {
+    defines
+        .iter()
+        .map(|define| {
+            define
+                .split_once('=')
+                .map(|(name, value)| (name.trim().to_string(), value.to_string()))
+                .filter(|(name, _)| !name.is_empty())
+                .ok_or_else(|| format!("Expected NAME=VALUE, got {define:?}"))
+        })
+        .collect()
+}
+
+/// OpenSCAD arguments for writing `output`
+fn output_args(output: &Path, imgsize: u32) -> Result<Vec<String>, String> {
+    let format = output
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if format == "png" {
+        return png_args(imgsize.clamp(32, 4096), None);
+    }
+    if !EXPORT_FORMATS.contains(&format.as_str()) {
+        return Err(format!(
+            "Unsupported output format {format:?} (expected png or one of {})",
+            EXPORT_FORMATS.join(", ")
+        ));
+    }
+    Ok(export_args(&format))
+}
+
+/// Echo output evaluates the file without building geometry
+fn check_args() -> Vec<String> {
+    vec![
+        "/input.scad".to_string(),
+        "-o".to_string(),
+        "/output.echo".to_string(),
+    ]
+}
+
+/// Have the model edit `code`; returns the new code, its summary and the
+/// number of replacements
+fn edit_code(
+    request: &EditRequest,
+    file_name: &str,
+    code: &str,
+    timeout: Duration,
+) -> Result<(String, Option<String>, usize), String> {
+    let prompt = fs::read_to_string(&request.prompt)
+        .map_err(|e| format!("Failed to read {}: {e}", request.prompt.display()))?;
+    let key_var = request.provider.key_var();
+    let api_key = std::env::var(key_var)
+        .ok()
+        .filter(|key| !key.trim().is_empty())
+        .ok_or_else(|| format!("Set {key_var} to edit with this provider"))?;
+    let model = request
+        .model
+        .as_deref()
+        .unwrap_or(request.provider.default_model());
+    let edits = request_edits(
+        request.provider,
+        model,
+        api_key.trim(),
+        &prompt,
+        file_name,
+        code,
+        timeout,
+    )?;
+    let edited = apply_replacements(code, &edits.replacements)
+        .map_err(|e| format!("The model's edit doesn't apply: {e}"))?;
+    Ok((edited, edits.summary, edits.replacements.len()))
+}
+
+fn run_command(binary: &Path, command: HeadlessCommand) -> Result<HeadlessReport, String> {
+    let (source, output, mut args, edit) = match command {
+        HeadlessCommand::Render {
+            source,
+            output,
+            imgsize,
+        } => {
+            let args = output_args(&output, imgsize)?;
+            (source, Some(output), args, None)
+        }
+        HeadlessCommand::Check { source } => (source, None, check_args(), None),
+        HeadlessCommand::Edit {
+            source,
+            prompt,
+            provider,
+            model,
+            dry_run,
+        } => {
+            let request = EditRequest {
+                prompt,
+                provider,
+                model,
+                dry_run,
+            };
+            (source, None, check_args(), Some(request))
+        }
+    };
+
+    let mut code = fs::read_to_string(&source.input)
+        .map_err(|e| format!("Failed to read {}: {e}", source.input.display()))?;
+    let input = fs::canonicalize(&source.input)
+        .map(plain_path)
+        .map_err(|e| format!("Failed to resolve {}: {e}", source.input.display()))?;
+    let working_dir = input.parent().map(|dir| dir.to_string_lossy().to_string());
+    let input_name = input
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string());
+    let timeout = Duration::from_secs(source.timeout.max(1));
+
+    let (mut summary, mut replacements) = (None, None);
+    if let Some(request) = &edit {
+        let file_name = input_name.as_deref().unwrap_or("input.scad");
+        let (edited, edit_summary, count) = edit_code(request, file_name, &code, timeout)?;
+        code = edited;
+        summary = edit_summary;
+        replacements = Some(count);
+    }
+    args.extend(override_args(&code, &parse_defines(&source.define)?)?);
+    let library_paths = (!source.library_paths.is_empty()).then_some(source.library_paths);
+
+    let render = execute_render(
+        binary,
+        &code,
+        &args,
+        &None,
+        &input_name,
+        &working_dir,
+        &library_paths,
+        timeout,
+        None,
+    )?;
+    let diagnostics = parse_openscad_stderr(
+        &render.stderr,
+        Some(&code),
+        input_name.as_deref(),
+        working_dir.as_deref().map(Path::new),
+    );
+    let has_errors = diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error);
+
+    let mut error = None;
+    if render.exit_code != 0 || has_errors {
+        error = Some(format!("OpenSCAD exited with {}", render.exit_code));
+    } else if let Some(output) = &output {
+        if render.output.is_empty() {
+            error = Some("OpenSCAD produced no output (is the model empty?)".to_string());
+        } else if let Err(e) = fs::write(output, &render.output) {
+            error = Some(format!("Failed to write {}: {e}", output.display()));
+        }
+    } else if edit.as_ref().is_some_and(|request| !request.dry_run) {
+        // Only an edit that still evaluates cleanly is written back
+        if let Err(e) = fs::write(&input, &code) {
+            error = Some(format!("Failed to write {}: {e}", input.display()));
+        }
+    }
+
+    Ok(HeadlessReport {
+        success: error.is_none(),
+        input: input.to_string_lossy().to_string(),
+        output: output.map(|path| path.to_string_lossy().to_string()),
+        diagnostics,
+        duration_ms: render.duration_ms,
+        summary,
+        replacements,
+        error,
+    })
+}
+
+/// Run a headless command from the process arguments (including the
+/// `--headless` flag) and return the process exit code
+pub fn run_headless(args: Vec<String>) -> i32 {
+    let args = args.into_iter().filter(|arg| arg != HEADLESS_FLAG);
+    let cli = match Cli::try_parse_from(args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return e.exit_code();
+        }
+    };
+
+    let input = match &cli.command {
+        HeadlessCommand::Render { source, .. }
+        | HeadlessCommand::Check { source }
+        | HeadlessCommand::Edit { source, .. } => source.input.to_string_lossy().to_string(),
+    };
+    let report = cli
+        .openscad
+        .or_else(system_binary_path)
+        .ok_or_else(|| {
+            "OpenSCAD binary not found. Install OpenSCAD or pass --openscad.".to_string()
+        })
+        .and_then(|binary| run_command(&binary, cli.command))
+        .unwrap_or_else(|error| HeadlessReport {
+            success: false,
+            input,
+            output: None,
+            diagnostics: Vec::new(),
+            duration_ms: 0,
+            summary: None,
+            replacements: None,
+            error: Some(error),
+        });
+
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => println!("{json}"),
+        Err(e) => eprintln!("Failed to serialize report: {e}"),
+    }
+    if report.success {
+        0
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_render_arguments() {
+        let cli = Cli::try_parse_from([
+            "openscad-studio",
+            "render",
+            "box.scad",
+            "-o",
+            "out/box.stl",
+            "-D",
+            "width=20",
+            "-L",
+            "/opt/libs",
+        ])
+        .unwrap();
+        let HeadlessCommand::Render { source, output, .. } = cli.command else {
+            panic!("expected render");
+        };
+        assert_eq!(output, PathBuf::from("out/box.stl"));
+        assert_eq!(
+            parse_defines(&source.define).unwrap()["width"],
+            "20".to_string()
+        );
+        assert_eq!(source.library_paths, vec!["/opt/libs".to_string()]);
+        assert!(parse_defines(&["width".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parses_edit_arguments() {
+        let cli = Cli::try_parse_from([
+            "openscad-studio",
+            "edit",
+            "box.scad",
+            "--prompt",
+            "wider.txt",
+            "--provider",
+            "openai",
+            "--dry-run",
+        ])
+        .unwrap();
+        let HeadlessCommand::Edit {
+            source,
+            prompt,
+            provider,
+            model,
+            dry_run,
+        } = cli.command
+        else {
+            panic!("expected edit");
+        };
+        assert_eq!(source.input, PathBuf::from("box.scad"));
+        assert_eq!(prompt, PathBuf::from("wider.txt"));
+        assert_eq!(provider, HeadlessProvider::Openai);
+        assert_eq!(model, None);
+        assert!(dry_run);
+        assert!(Cli::try_parse_from(["openscad-studio", "edit", "box.scad"]).is_err());
+    }
+
+    #[test]
+    fn picks_output_arguments_from_extension() {
+        let stl = output_args(Path::new("box.STL"), 800).unwrap();
+        assert_eq!(stl[2], "/output.stl");
+        assert!(stl.contains(&"--export-format=binstl".to_string()));
+        let png = output_args(Path::new("box.png"), 300).unwrap();
+        assert!(png.contains(&"--imgsize=300,300".to_string()));
+        assert_eq!(output_args(Path::new("box.csg"), 800).unwrap().len(), 3);
+        assert!(output_args(Path::new("box.dxf"), 800).is_err());
+    }
+}
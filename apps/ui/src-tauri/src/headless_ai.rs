@@ -0,0 +1,197 @@
+use crate::pending_edits::Replacement;
+use reqwest::header::CONTENT_TYPE;
+use serde::Deserialize;
+use serde_json::{json, Value};
+/**
+ * AI edits for the headless command line
+ *
+ * `--headless edit` sends the prompt and the file's code to a model in a
+ * single request, without tools or streaming, and asks for the change as
+ * exact-string replacements (the shape of the in-app `apply_edits` tool).
+ * There is no keychain prompt without a window, so the API key comes from
+ * the provider's usual environment variable.
+ */
+use std::time::Duration;
+
+const ANTHROPIC_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const OPENAI_URL: &str = "https://api.openai.com/v1/chat/completions";
+const MAX_OUTPUT_TOKENS: u32 = 8192;
+
+const SYSTEM_PROMPT: &str = "You edit OpenSCAD files. You are given a file and a request. \
+Reply with a single JSON object and nothing else: \
+{\"summary\": \"<one sentence describing the change>\", \
+\"replacements\": [{\"old_string\": \"<exact text from the file>\", \"new_string\": \"<its replacement>\"}]}. \
+Replacements are applied in order, each to the result of the previous one, and each old_string \
+must appear exactly once at that point, so include enough surrounding lines to make it unique. \
+Keep the file valid OpenSCAD.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HeadlessProvider {
+    Anthropic,
+    Openai,
+}
+
+impl HeadlessProvider {
+    /// Environment variable holding the API key
+    pub fn key_var(self) -> &'static str {
+        match self {
+            HeadlessProvider::Anthropic => "ANTHROPIC_API_KEY",
+            HeadlessProvider::Openai => "OPENAI_API_KEY",
+        }
+    }
+
+    /// The app's default model for the provider
+    pub fn default_model(self) -> &'static str {
+        match self {
+            HeadlessProvider::Anthropic => "claude-sonnet-4-5",
+            HeadlessProvider::Openai => "gpt-5.4",
+        }
+    }
+}
+
+/// The change a model proposed for a file
+#[derive(Debug, Deserialize)]
+pub struct ProposedEdits {
+    #[serde(default)]
+    pub summary: Option<String>,
+    pub replacements: Vec<Replacement>,
+}
+
+fn user_message(prompt: &str, file_name: &str, code: &str) -> String {
+    format!("File `{file_name}`:\n\n```openscad\n{code}\n```\n\nRequest:\n\n{prompt}")
+}
+
+/// Request body for the provider's API
+fn request_body(
+    provider: HeadlessProvider,
+    model: &str,
+    prompt: &str,
+    file_name: &str,
+    code: &str,
+) -> Value {
+    let message = user_message(prompt, file_name, code);
+    match provider {
+        HeadlessProvider::Anthropic => json!({
+            "model": model,
+            "max_tokens": MAX_OUTPUT_TOKENS,
+            "system": SYSTEM_PROMPT,
+            "messages": [{ "role": "user", "content": message }],
+        }),
+        HeadlessProvider::Openai => json!({
+            "model": model,
+            "max_completion_tokens": MAX_OUTPUT_TOKENS,
+            "response_format": { "type": "json_object" },
+            "messages": [
+                { "role": "system", "content": SYSTEM_PROMPT },
+                { "role": "user", "content": message },
+            ],
+        }),
+    }
+}
+
+/// The model's reply text from a response body
+fn response_text(provider: HeadlessProvider, body: &Value) -> Result<String, String> {
+    let text = match provider {
+        HeadlessProvider::Anthropic => body["content"].as_array().map(|blocks| {
+            blocks
+                .iter()
+                .filter(|block| block["type"] == "text")
+                .filter_map(|block| block["text"].as_str())
+                .collect::<String>()
+        }),
+        HeadlessProvider::Openai => body["choices"][0]["message"]["content"]
+            .as_str()
+            .map(str::to_string),
+    };
+    text.filter(|text| !text.trim().is_empty())
+        .ok_or_else(|| "The model returned no text".to_string())
+}
+
+/// Read the JSON object out of a reply, which may wrap it in a code fence
+pub fn parse_edits(text: &str) -> Result<ProposedEdits, String> {
+    let json = match (text.find('{'), text.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &text[start..=end],
+        _ => return Err("The model's reply has no JSON object".to_string()),
+    };
+    serde_json::from_str(json).map_err(|e| format!("The model's reply isn't a valid edit: {e}"))
+}
+
+/// Ask the model for edits to `code`
+pub fn request_edits(
+    provider: HeadlessProvider,
+    model: &str,
+    api_key: &str,
+    prompt: &str,
+    file_name: &str,
+    code: &str,
+    timeout: Duration,
+) -> Result<ProposedEdits, String> {
+    let client = reqwest::blocking::Client::builder()
+        .connect_timeout(Duration::from_secs(30))
+        .timeout(timeout)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let payload = serde_json::to_vec(&request_body(provider, model, prompt, file_name, code))
+        .map_err(|e| format!("Failed to serialize the request: {e}"))?;
+    let request = match provider {
+        HeadlessProvider::Anthropic => client
+            .post(ANTHROPIC_URL)
+            .header("x-api-key", api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION),
+        HeadlessProvider::Openai => client.post(OPENAI_URL).bearer_auth(api_key),
+    };
+    let response = request
+        .header(CONTENT_TYPE, "application/json")
+        .body(payload)
+        .send()
+        .map_err(|e| format!("Request to the model failed: {e}"))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .map_err(|e| format!("Failed to read the model's response: {e}"))?;
+    if !status.is_success() {
+        let message = serde_json::from_str::<Value>(&text)
+            .ok()
+            .and_then(|body| body["error"]["message"].as_str().map(str::to_string))
+            .unwrap_or(text);
+        return Err(format!("The model request failed ({status}): {message}"));
+    }
+    let body: Value =
+        serde_json::from_str(&text).map_err(|e| format!("The model's response isn't JSON: {e}"))?;
+    parse_edits(&response_text(provider, &body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_replacements_from_a_fenced_reply() {
+        let reply = "Here you go:\n```json\n{\"summary\": \"Wider box\", \"replacements\": \
+            [{\"old_string\": \"width = 10;\", \"new_string\": \"width = 20;\"}]}\n```";
+        let edits = parse_edits(reply).unwrap();
+        assert_eq!(edits.summary.as_deref(), Some("Wider box"));
+        assert_eq!(edits.replacements.len(), 1);
+        assert_eq!(edits.replacements[0].new_string, "width = 20;");
+        assert!(parse_edits("I can't help with that.").is_err());
+    }
+
+    #[test]
+    fn reads_reply_text_for_each_provider() {
+        let anthropic = json!({ "content": [
+            { "type": "thinking", "thinking": "..." },
+            { "type": "text", "text": "{}" },
+        ]});
+        assert_eq!(
+            response_text(HeadlessProvider::Anthropic, &anthropic).unwrap(),
+            "{}"
+        );
+        let openai = json!({ "choices": [{ "message": { "content": "{}" } }] });
+        assert_eq!(
+            response_text(HeadlessProvider::Openai, &openai).unwrap(),
+            "{}"
+        );
+        assert!(response_text(HeadlessProvider::Openai, &json!({})).is_err());
+    }
+}
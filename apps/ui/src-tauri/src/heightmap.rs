@@ -0,0 +1,232 @@
+/**
+ * Heightmap conversion for surface()
+ *
+ * Turns an image into either a `.dat` height matrix (heights already in
+ * millimetres) or a downscaled grayscale PNG, plus the `surface()` snippet
+ * that places it at the requested size. OpenSCAD reads `.dat` rows bottom
+ * to top but PNGs top to bottom, so `.dat` rows are written reversed to keep
+ * the image upright.
+ */
+use crate::sweep::{decode_png, encode_png, RgbaImage};
+use serde::Deserialize;
+
+pub const DEFAULT_MAX_RESOLUTION: u32 = 200;
+const MAX_RESOLUTION_LIMIT: u32 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeightmapFormat {
+    #[default]
+    Dat,
+    Png,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HeightmapOptions {
+    pub format: HeightmapFormat,
+    /// Longest side of the output grid in samples
+    pub max_resolution: u32,
+    /// Footprint width in mm (depth follows the aspect ratio)
+    pub width: f64,
+    /// Height of pure white in mm
+    pub height: f64,
+    /// Height added under every sample in mm
+    pub base: f64,
+    /// Dark areas high instead of light areas
+    pub invert: bool,
+}
+
+impl Default for HeightmapOptions {
+    fn default() -> Self {
+        Self {
+            format: HeightmapFormat::Dat,
+            max_resolution: DEFAULT_MAX_RESOLUTION,
+            width: 100.0,
+            height: 5.0,
+            base: 0.0,
+            invert: false,
+        }
+    }
+}
+
+/// Grayscale samples in 0..=1, row-major from the top-left
+pub struct GrayGrid {
+    pub width: u32,
+    pub height: u32,
+    pub values: Vec<f64>,
+}
+
+/// Rec. 601 luma, with transparent pixels treated as white
+pub fn to_gray(image: &RgbaImage) -> GrayGrid {
+    let values = image
+        .pixels
+        .chunks_exact(4)
+        .map(|px| {
+            let luma = (0.299 * px[0] as f64 + 0.587 * px[1] as f64 + 0.114 * px[2] as f64) / 255.0;
+            let alpha = px[3] as f64 / 255.0;
+            luma * alpha + (1.0 - alpha)
+        })
+        .collect();
+    GrayGrid {
+        width: image.width,
+        height: image.height,
+        values,
+    }
+}
+
+/// Box-filter down so the longest side is at most `max_side` (never upscales)
+pub fn downscale(grid: &GrayGrid, max_side: u32) -> GrayGrid {
+    let max_side = max_side.clamp(2, MAX_RESOLUTION_LIMIT);
+    let longest = grid.width.max(grid.height);
+    if longest <= max_side {
+        return GrayGrid {
+            width: grid.width,
+            height: grid.height,
+            values: grid.values.clone(),
+        };
+    }
+
+    let ratio = longest as f64 / max_side as f64;
+    let width = ((grid.width as f64 / ratio).round() as u32).max(1);
+    let height = ((grid.height as f64 / ratio).round() as u32).max(1);
+    let mut values = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        let y0 = (y as f64 * ratio) as u32;
+        let y1 = (((y + 1) as f64 * ratio) as u32).clamp(y0 + 1, grid.height);
+        for x in 0..width {
+            let x0 = (x as f64 * ratio) as u32;
+            let x1 = (((x + 1) as f64 * ratio) as u32).clamp(x0 + 1, grid.width);
+            let mut sum = 0.0;
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    sum += grid.values[(sy * grid.width + sx) as usize];
+                }
+            }
+            values.push(sum / ((y1 - y0) * (x1 - x0)) as f64);
+        }
+    }
+    GrayGrid {
+        width,
+        height,
+        values,
+    }
+}
+
+fn level(value: f64, invert: bool) -> f64 {
+    if invert {
+        1.0 - value
+    } else {
+        value
+    }
+}
+
+/// `.dat` matrix of heights in mm, bottom row first
+pub fn to_dat(grid: &GrayGrid, options: &HeightmapOptions) -> String {
+    let mut out = String::new();
+    for row in (0..grid.height).rev() {
+        let start = (row * grid.width) as usize;
+        let line: Vec<String> = grid.values[start..start + grid.width as usize]
+            .iter()
+            .map(|&v| {
+                format!(
+                    "{:.3}",
+                    options.base + level(v, options.invert) * options.height
+                )
+            })
+            .collect();
+        out.push_str(&line.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+pub fn to_png(grid: &GrayGrid) -> Result<Vec<u8>, String> {
+    let pixels = grid
+        .values
+        .iter()
+        .flat_map(|&v| {
+            let g = (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+            [g, g, g, 255]
+        })
+        .collect();
+    encode_png(&RgbaImage {
+        width: grid.width,
+        height: grid.height,
+        pixels,
+    })
+}
+
+/// `surface()` call sized to `options.width` for a file of `grid`'s shape
+pub fn surface_snippet(file: &str, grid: &GrayGrid, options: &HeightmapOptions) -> String {
+    let step = options.width / grid.width.saturating_sub(1).max(1) as f64;
+    match options.format {
+        HeightmapFormat::Dat => format!(
+            "// Heightmap {}x{} from {file}\nscale([{step:.4}, {step:.4}, 1])\n    surface(file = \"{file}\", center = true);\n",
+            grid.width, grid.height
+        ),
+        HeightmapFormat::Png => format!(
+            // PNG surfaces span 0..100 in Z.
+            "// Heightmap {}x{} from {file}\ntranslate([0, 0, {base}])\n    scale([{step:.4}, {step:.4}, {z:.4}])\n        surface(file = \"{file}\", center = true, invert = {invert});\n",
+            grid.width,
+            grid.height,
+            base = options.base,
+            z = options.height / 100.0,
+            invert = options.invert,
+        ),
+    }
+}
+
+/// Decode a PNG and downscale it to a heightmap grid
+pub fn load_grid(bytes: &[u8], max_resolution: u32) -> Result<GrayGrid, String> {
+    if !bytes.starts_with(b"\x89PNG") {
+        return Err("Only PNG images are supported for heightmaps".into());
+    }
+    Ok(downscale(&to_gray(&decode_png(bytes)?), max_resolution))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(width: u32, height: u32) -> GrayGrid {
+        GrayGrid {
+            width,
+            height,
+            values: (0..width * height)
+                .map(|i| (i / width) as f64 / (height - 1) as f64)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn downscale_averages_and_keeps_aspect() {
+        let grid = downscale(&gradient(40, 20), 10);
+        assert_eq!((grid.width, grid.height), (10, 5));
+        assert!(grid.values[0] < grid.values[grid.values.len() - 1]);
+
+        let small = downscale(&gradient(4, 4), 10);
+        assert_eq!((small.width, small.height), (4, 4));
+    }
+
+    #[test]
+    fn dat_rows_are_bottom_first_heights() {
+        let grid = gradient(2, 2);
+        let options = HeightmapOptions {
+            height: 2.0,
+            base: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(to_dat(&grid, &options), "3.000 3.000\n1.000 1.000\n");
+
+        let inverted = HeightmapOptions {
+            invert: true,
+            ..options
+        };
+        assert_eq!(to_dat(&grid, &inverted), "1.000 1.000\n3.000 3.000\n");
+
+        assert!(load_grid(b"GIF89a", options.max_resolution).is_err());
+        let png = to_png(&grid).unwrap();
+        assert_eq!(load_grid(&png, options.max_resolution).unwrap().width, 2);
+    }
+}
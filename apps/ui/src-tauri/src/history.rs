@@ -1,18 +1,186 @@
-use crate::types::{ChangeType, CheckpointDiff, Diagnostic, EditorCheckpoint};
+use crate::settings::HistorySettings;
+use crate::text_file::{decode, encode};
+use crate::types::{
+    ChangeType, CheckpointDiff, ConversationLink, Diagnostic, EditorCheckpoint, GeometryStats,
+};
 /**
  * Editor History Management
  *
  * Provides undo/redo functionality with checkpoint system.
  * Tracks up to MAX_CHECKPOINTS snapshots of editor state.
+ *
+ * Checkpoints are also saved per project folder so they survive restarts;
+ * saved histories are pruned by age and size according to the history
+ * settings, which can also have restored code written back to the file.
  */
-use std::collections::VecDeque;
-use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
 const MAX_CHECKPOINTS: usize = 50;
+pub const HISTORY_FILE_VERSION: u32 = 1;
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// On-disk form of a project's history
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedHistory {
+    pub version: u32,
+    /// Canonical project folder the checkpoints belong to
+    pub project: String,
+    pub checkpoints: Vec<EditorCheckpoint>,
+}
+
+/// File name for a project's saved history (FNV-1a of its canonical path,
+/// which stays stable across app versions)
+pub fn history_file_name(project: &str) -> String {
+    let hash = project.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}.json")
+}
+
+/// Drop checkpoints older than `max_age_days`, then the oldest ones until
+/// the rest serialize to at most `max_bytes`
+pub fn prune_checkpoints(
+    checkpoints: &mut Vec<EditorCheckpoint>,
+    settings: &HistorySettings,
+    now_ms: i64,
+) {
+    if settings.max_age_days > 0 {
+        let cutoff = now_ms - i64::from(settings.max_age_days) * DAY_MS;
+        checkpoints.retain(|checkpoint| checkpoint.timestamp >= cutoff);
+    }
+    let mut total = 0;
+    let keep = checkpoints
+        .iter()
+        .rev()
+        .take_while(|checkpoint| {
+            total += serde_json::to_vec(checkpoint).map_or(0, |json| json.len() as u64);
+            total <= settings.max_bytes
+        })
+        .count();
+    checkpoints.drain(..checkpoints.len() - keep);
+}
+
+/// Line diff of `old` against `new` with +/-/space prefixes, plus the
+/// number of added and removed lines
+pub fn line_diff(old: &str, new: &str) -> (String, usize, usize) {
+    use similar::{ChangeTag, TextDiff};
+
+    let diff = TextDiff::from_lines(old, new);
+    let mut unified_diff = String::new();
+    let mut added_lines = 0;
+    let mut removed_lines = 0;
+
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Delete => {
+                unified_diff.push_str(&format!("-{change}"));
+                removed_lines += 1;
+            }
+            ChangeTag::Insert => {
+                unified_diff.push_str(&format!("+{change}"));
+                added_lines += 1;
+            }
+            ChangeTag::Equal => {
+                unified_diff.push_str(&format!(" {change}"));
+            }
+        }
+    }
+
+    (unified_diff, added_lines, removed_lines)
+}
+
+/// `<file>.bak` next to `path`
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of what the last restore wrote to each file, so a run of restores
+/// keeps the backup of the file as it was before the first one
+fn restored_files() -> &'static Mutex<HashMap<PathBuf, u64>> {
+    static RESTORED: OnceLock<Mutex<HashMap<PathBuf, u64>>> = OnceLock::new();
+    RESTORED.get_or_init(Default::default)
+}
+
+/// Write restored code to the file at `path` in the file's own encoding and
+/// line endings, first copying what it held to `backup_path`. While the file
+/// still holds what an earlier restore wrote, the existing backup is kept:
+/// it has the content from before that run of restores. Returns the backup,
+/// or `None` when the file already holds the code or is gone. Files that
+/// can't be decoded (broken UTF-16) are left alone.
+pub fn write_restored_file(path: &Path, code: &str) -> Result<Option<PathBuf>, String> {
+    let Ok(bytes) = fs::read(path) else {
+        return Ok(None);
+    };
+    let (content, format) =
+        decode(&bytes).map_err(|e| format!("Not restoring over {path:?}: {e}"))?;
+    if content == code {
+        return Ok(None);
+    }
+    let backup = backup_path(path);
+    let mut restored = restored_files().lock().unwrap();
+    let mid_run = restored.get(path) == Some(&content_hash(&bytes)) && backup.exists();
+    if !mid_run {
+        fs::write(&backup, &bytes).map_err(|e| format!("Failed to back up {path:?}: {e}"))?;
+    }
+    let encoded = encode(code, &format);
+    let temp_path = path.with_extension("openscad-studio.tmp");
+    fs::write(&temp_path, &encoded).map_err(|e| format!("Failed to write {path:?}: {e}"))?;
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Failed to replace {path:?}: {e}")
+    })?;
+    restored.insert(path.to_path_buf(), content_hash(&encoded));
+    Ok(Some(backup))
+}
+
+/// Put a checkpoint's code back: into the document's `code` and
+/// `diagnostics`, or over its file for a checkpoint of another project file
+/// (removing the file if it didn't exist then). Returns the file written or
+/// removed, if any.
+pub fn restore_checkpoint(
+    checkpoint: &EditorCheckpoint,
+    code: &mut String,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Option<PathBuf>, String> {
+    let Some(file_path) = &checkpoint.file_path else {
+        *code = checkpoint.code.clone();
+        *diagnostics = checkpoint.diagnostics.clone();
+        return Ok(None);
+    };
+    let path = PathBuf::from(file_path);
+    if checkpoint.file_absent {
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to remove {path:?}: {e}")),
+        }
+    } else {
+        fs::write(&path, &checkpoint.code)
+            .map_err(|e| format!("Failed to restore {path:?}: {e}"))?;
+    }
+    Ok(Some(path))
+}
 
 pub struct EditorHistory {
     checkpoints: VecDeque<EditorCheckpoint>,
     current_index: Option<usize>, // None means we're at the latest state (not in history)
+    /// Canonical project folder the history is saved under, if any
+    pub project: Option<String>,
 }
 
 impl EditorHistory {
@@ -20,6 +188,7 @@ impl EditorHistory {
         Self {
             checkpoints: VecDeque::new(),
             current_index: None,
+            project: None,
         }
     }
 
@@ -38,6 +207,12 @@ impl EditorHistory {
             diagnostics,
             description,
             change_type,
+            geometry: None,
+            conversation_id: None,
+            message_id: None,
+            message_index: None,
+            file_path: None,
+            file_absent: false,
         };
 
         let id = checkpoint.id.clone();
@@ -63,8 +238,30 @@ impl EditorHistory {
         id
     }
 
+    /// Create a checkpoint of another project file's content, such as one
+    /// the AI is about to write, without touching the document's code.
+    /// `None` records that the file doesn't exist yet.
+    pub fn create_file_checkpoint(
+        &mut self,
+        file_path: &Path,
+        code: Option<String>,
+        description: String,
+    ) -> String {
+        let file_absent = code.is_none();
+        let id = self.create_checkpoint(
+            code.unwrap_or_default(),
+            Vec::new(),
+            description,
+            ChangeType::Ai,
+        );
+        if let Some(checkpoint) = self.checkpoints.back_mut() {
+            checkpoint.file_path = Some(file_path.to_string_lossy().into_owned());
+            checkpoint.file_absent = file_absent;
+        }
+        id
+    }
+
     /// Get current checkpoint (or latest if at head)
-    #[allow(dead_code)]
     pub fn get_current(&self) -> Option<&EditorCheckpoint> {
         if let Some(index) = self.current_index {
             self.checkpoints.get(index)
@@ -126,6 +323,54 @@ impl EditorHistory {
         self.checkpoints.iter().find(|c| c.id == id)
     }
 
+    /// Attach rendered geometry statistics to a checkpoint
+    pub fn set_geometry(&mut self, id: &str, stats: GeometryStats) -> bool {
+        match self.checkpoints.iter_mut().find(|c| c.id == id) {
+            Some(checkpoint) => {
+                checkpoint.geometry = Some(stats);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Associate a checkpoint with the chat message it was made for
+    pub fn link_conversation(&mut self, id: &str, link: ConversationLink) -> bool {
+        match self.checkpoints.iter_mut().find(|c| c.id == id) {
+            Some(checkpoint) => {
+                checkpoint.conversation_id = Some(link.conversation_id);
+                checkpoint.message_id = Some(link.message_id);
+                checkpoint.message_index = Some(link.message_index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Checkpoints made for messages of a conversation, in chat order
+    pub fn for_conversation(&self, conversation_id: &str) -> Vec<EditorCheckpoint> {
+        let mut checkpoints: Vec<EditorCheckpoint> = self
+            .checkpoints
+            .iter()
+            .filter(|c| c.conversation_id.as_deref() == Some(conversation_id))
+            .cloned()
+            .collect();
+        checkpoints.sort_by_key(|c| c.message_index);
+        checkpoints
+    }
+
+    /// Restore the code from before a chat message's edits: the first
+    /// checkpoint made for that message
+    pub fn restore_to_message(&mut self, message_id: &str) -> Option<&EditorCheckpoint> {
+        let id = self
+            .checkpoints
+            .iter()
+            .find(|c| c.message_id.as_deref() == Some(message_id))?
+            .id
+            .clone();
+        self.restore_to(&id)
+    }
+
     /// Restore to specific checkpoint
     pub fn restore_to(&mut self, id: &str) -> Option<&EditorCheckpoint> {
         if let Some(index) = self.checkpoints.iter().position(|c| c.id == id) {
@@ -141,29 +386,7 @@ impl EditorHistory {
         let from = self.get_by_id(from_id)?;
         let to = self.get_by_id(to_id)?;
 
-        // Use similar crate for diff generation
-        use similar::{ChangeTag, TextDiff};
-
-        let diff = TextDiff::from_lines(&from.code, &to.code);
-        let mut unified_diff = String::new();
-        let mut added_lines = 0;
-        let mut removed_lines = 0;
-
-        for change in diff.iter_all_changes() {
-            match change.tag() {
-                ChangeTag::Delete => {
-                    unified_diff.push_str(&format!("-{change}"));
-                    removed_lines += 1;
-                }
-                ChangeTag::Insert => {
-                    unified_diff.push_str(&format!("+{change}"));
-                    added_lines += 1;
-                }
-                ChangeTag::Equal => {
-                    unified_diff.push_str(&format!(" {change}"));
-                }
-            }
-        }
+        let (unified_diff, added_lines, removed_lines) = line_diff(&from.code, &to.code);
 
         Some(CheckpointDiff {
             from_id: from_id.to_string(),
@@ -197,11 +420,27 @@ impl EditorHistory {
     }
 
     /// Clear all history
-    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.checkpoints.clear();
         self.current_index = None;
     }
+
+    /// All checkpoints, oldest first
+    pub fn checkpoints(&self) -> Vec<EditorCheckpoint> {
+        self.checkpoints.iter().cloned().collect()
+    }
+
+    /// Put earlier checkpoints (e.g. loaded from disk) before the current
+    /// ones, keeping the newest MAX_CHECKPOINTS and moving to the latest state
+    pub fn prepend(&mut self, earlier: Vec<EditorCheckpoint>) {
+        for checkpoint in earlier.into_iter().rev() {
+            self.checkpoints.push_front(checkpoint);
+        }
+        while self.checkpoints.len() > MAX_CHECKPOINTS {
+            self.checkpoints.pop_front();
+        }
+        self.current_index = None;
+    }
 }
 
 /// Global history state (managed by Tauri)
@@ -222,3 +461,197 @@ impl Default for HistoryState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(timestamp: i64, code: &str) -> EditorCheckpoint {
+        EditorCheckpoint {
+            id: timestamp.to_string(),
+            timestamp,
+            code: code.to_string(),
+            diagnostics: Vec::new(),
+            description: String::new(),
+            change_type: ChangeType::User,
+            geometry: None,
+            conversation_id: None,
+            message_id: None,
+            message_index: None,
+            file_path: None,
+            file_absent: false,
+        }
+    }
+
+    #[test]
+    fn prunes_by_age_then_size() {
+        let now = 100 * DAY_MS;
+        let mut checkpoints = vec![
+            checkpoint(now - 40 * DAY_MS, "old"),
+            checkpoint(now - 2 * DAY_MS, &"x".repeat(1000)),
+            checkpoint(now - DAY_MS, "recent"),
+            checkpoint(now, "latest"),
+        ];
+        let one_size = serde_json::to_vec(&checkpoints[3]).unwrap().len() as u64;
+        let settings = HistorySettings {
+            persist: true,
+            max_age_days: 30,
+            max_bytes: one_size * 3,
+            write_to_disk: false,
+        };
+        prune_checkpoints(&mut checkpoints, &settings, now);
+        let codes: Vec<&str> = checkpoints.iter().map(|c| c.code.as_str()).collect();
+        assert_eq!(codes, ["recent", "latest"]);
+    }
+
+    #[test]
+    fn prepends_saved_checkpoints() {
+        let mut history = EditorHistory::new();
+        history.create_checkpoint("new".into(), Vec::new(), String::new(), ChangeType::User);
+        history.prepend(vec![checkpoint(1, "a"), checkpoint(2, "b")]);
+        let codes: Vec<String> = history.checkpoints().into_iter().map(|c| c.code).collect();
+        assert_eq!(codes, ["a", "b", "new"]);
+        assert_eq!(history.undo().map(|c| c.code.as_str()), Some("b"));
+
+        assert_eq!(
+            history_file_name("/work/box"),
+            history_file_name("/work/box")
+        );
+        assert_ne!(
+            history_file_name("/work/box"),
+            history_file_name("/work/lid")
+        );
+    }
+
+    #[test]
+    fn writes_restored_code_over_the_file_with_a_backup() {
+        let dir = std::env::temp_dir().join(format!("history-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("box.scad");
+        fs::write(&path, "cube(20);\r\nsphere(5);\r\n").unwrap();
+
+        let backup = write_restored_file(&path, "cube(10);\nsphere(5);\n").unwrap();
+        assert_eq!(backup, Some(dir.join("box.scad.bak")));
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "cube(10);\r\nsphere(5);\r\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("box.scad.bak")).unwrap(),
+            "cube(20);\r\nsphere(5);\r\n"
+        );
+
+        assert_eq!(
+            write_restored_file(&path, "cube(10);\nsphere(5);\n").unwrap(),
+            None
+        );
+        assert_eq!(
+            write_restored_file(&dir.join("gone.scad"), "cube(1);").unwrap(),
+            None
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_backup_from_before_a_run_of_restores() {
+        let dir = std::env::temp_dir().join(format!("history-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("box.scad");
+        let backup = dir.join("box.scad.bak");
+        fs::write(&path, "cube(30);").unwrap();
+
+        write_restored_file(&path, "cube(20);").unwrap();
+        write_restored_file(&path, "cube(10);").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "cube(10);");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "cube(30);");
+
+        // A save in between starts a new run
+        fs::write(&path, "cube(5);").unwrap();
+        write_restored_file(&path, "cube(20);").unwrap();
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "cube(5);");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn leaves_files_that_are_not_text_alone() {
+        let dir = std::env::temp_dir().join(format!("history-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("box.scad");
+        fs::write(&path, [0x63, 0xff, 0xfe, 0x00]).unwrap();
+
+        assert!(write_restored_file(&path, "cube(10);").is_err());
+        assert_eq!(fs::read(&path).unwrap(), [0x63, 0xff, 0xfe, 0x00]);
+        assert!(!dir.join("box.scad.bak").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn links_checkpoints_to_chat_messages() {
+        let mut history = EditorHistory::new();
+        let link = |message_id: &str, message_index| ConversationLink {
+            conversation_id: "conv".into(),
+            message_id: message_id.into(),
+            message_index,
+        };
+        let first =
+            history.create_checkpoint("a".into(), Vec::new(), String::new(), ChangeType::Ai);
+        let second =
+            history.create_checkpoint("b".into(), Vec::new(), String::new(), ChangeType::Ai);
+        history.create_checkpoint("c".into(), Vec::new(), String::new(), ChangeType::User);
+        let third =
+            history.create_checkpoint("d".into(), Vec::new(), String::new(), ChangeType::Ai);
+        assert!(history.link_conversation(&third, link("m3", 2)));
+        assert!(history.link_conversation(&first, link("m1", 0)));
+        assert!(history.link_conversation(&second, link("m1", 0)));
+        assert!(!history.link_conversation("missing", link("m1", 0)));
+
+        let codes: Vec<String> = history
+            .for_conversation("conv")
+            .into_iter()
+            .map(|c| c.code)
+            .collect();
+        assert_eq!(codes, ["a", "b", "d"]);
+        assert!(history.for_conversation("other").is_empty());
+
+        assert_eq!(
+            history.restore_to_message("m1").map(|c| c.code.as_str()),
+            Some("a")
+        );
+        assert!(history.can_redo());
+        assert!(history.restore_to_message("m2").is_none());
+    }
+
+    #[test]
+    fn restores_file_checkpoints_to_their_file() {
+        let dir = std::env::temp_dir().join(format!("history-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let main = dir.join("main.scad");
+        let created = dir.join("parts.scad");
+        fs::write(&main, "use <parts.scad>\ngear();").unwrap();
+
+        let mut history = EditorHistory::new();
+        history.create_checkpoint(
+            "use <parts.scad>\ngear();".into(),
+            Vec::new(),
+            String::new(),
+            ChangeType::User,
+        );
+        let id =
+            history.create_file_checkpoint(&created, None, "Before AI write to parts.scad".into());
+        fs::write(&created, "module gear() cube(1);").unwrap();
+
+        let mut code = "use <parts.scad>\ngear();".to_string();
+        let mut diagnostics = Vec::new();
+        let checkpoint = history.restore_to(&id).cloned().unwrap();
+        let written = restore_checkpoint(&checkpoint, &mut code, &mut diagnostics).unwrap();
+
+        assert_eq!(written, Some(created.clone()));
+        assert_eq!(code, "use <parts.scad>\ngear();");
+        assert_eq!(
+            fs::read_to_string(&main).unwrap(),
+            "use <parts.scad>\ngear();"
+        );
+        assert!(!created.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
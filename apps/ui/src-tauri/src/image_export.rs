@@ -0,0 +1,290 @@
+/**
+ * PNG export options
+ *
+ * OpenSCAD's image export is controlled by a handful of flags: image size,
+ * color scheme, projection, full render versus preview, and viewport
+ * overlays such as axes. These options replace whatever the caller's
+ * arguments already set for them. OpenSCAD can't write a transparent
+ * background, so that option keys out the background color afterwards.
+ *
+ * The color schemes a binary supports are read from its `--help` output,
+ * falling back to the schemes every recent release ships.
+ */
+use crate::sweep::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+pub const COLOR_SCHEMES: &[&str] = &[
+    "Cornfield",
+    "Metallic",
+    "Sunset",
+    "Starnight",
+    "BeforeDawn",
+    "Nature",
+    "DeepOcean",
+    "Solarized",
+    "Tomorrow",
+    "Tomorrow Night",
+    "ClearSky",
+    "Monotone",
+];
+/// Schemes with a dark background, for matching a dark app theme
+const DARK_SCHEMES: &[&str] = &[
+    "Starnight",
+    "BeforeDawn",
+    "DeepOcean",
+    "Solarized",
+    "Tomorrow Night",
+];
+pub const VIEW_OPTIONS: &[&str] = &["axes", "crosshairs", "edges", "scales", "wireframe"];
+const MAX_IMAGE_SIZE: u32 = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Projection {
+    Orthographic,
+    Perspective,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImageExportOptions {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// One of `COLOR_SCHEMES`
+    pub colorscheme: Option<String>,
+    pub projection: Option<Projection>,
+    /// Build the full geometry (`--render`) instead of the fast preview
+    pub full_render: bool,
+    /// Viewport overlays from `VIEW_OPTIONS`
+    pub view: Vec<String>,
+    /// Make the background transparent
+    pub transparent: bool,
+}
+
+fn is_replaced_flag(arg: &str) -> bool {
+    let flag = arg.split_once('=').map_or(arg, |(flag, _)| flag);
+    matches!(
+        flag,
+        "--imgsize" | "--colorscheme" | "--projection" | "--view" | "--render" | "--preview"
+    )
+}
+
+impl ImageExportOptions {
+    /// Replace the image flags in OpenSCAD `args` with these options
+    pub fn apply(&self, args: &mut Vec<String>) -> Result<(), String> {
+        let mut flags = Vec::new();
+        if let Some(width) = self.width.or(self.height) {
+            let height = self.height.unwrap_or(width);
+            if !(1..=MAX_IMAGE_SIZE).contains(&width) || !(1..=MAX_IMAGE_SIZE).contains(&height) {
+                return Err(format!(
+                    "Image size must be between 1 and {MAX_IMAGE_SIZE} pixels"
+                ));
+            }
+            flags.push(format!("--imgsize={width},{height}"));
+        }
+        if let Some(scheme) = &self.colorscheme {
+            if !COLOR_SCHEMES.contains(&scheme.as_str()) {
+                return Err(format!(
+                    "Unknown color scheme '{scheme}' (expected one of {})",
+                    COLOR_SCHEMES.join(", ")
+                ));
+            }
+            flags.push(format!("--colorscheme={scheme}"));
+        }
+        if let Some(projection) = self.projection {
+            flags.push(match projection {
+                Projection::Orthographic => "--projection=o".to_string(),
+                Projection::Perspective => "--projection=p".to_string(),
+            });
+        }
+        flags.push(if self.full_render {
+            "--render".to_string()
+        } else {
+            "--preview".to_string()
+        });
+        if let Some(unknown) = self
+            .view
+            .iter()
+            .find(|view| !VIEW_OPTIONS.contains(&view.as_str()))
+        {
+            return Err(format!(
+                "Unknown view option '{unknown}' (expected {})",
+                VIEW_OPTIONS.join(", ")
+            ));
+        }
+        if !self.view.is_empty() {
+            flags.push(format!("--view={}", self.view.join(",")));
+        }
+
+        args.retain(|arg| !is_replaced_flag(arg));
+        args.extend(flags);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColorScheme {
+    pub name: String,
+    pub dark: bool,
+}
+
+/// Scheme names listed for `--colorscheme` in `openscad --help`. The list
+/// wraps across lines, sometimes inside a name ("Tomorrow\n Night").
+pub fn parse_colorschemes(help: &str) -> Vec<String> {
+    let mut lines = help
+        .lines()
+        .skip_while(|line| !line.contains("=colorscheme:"));
+    let Some(first) = lines.next() else {
+        return Vec::new();
+    };
+    let mut listing = first
+        .split_once("=colorscheme:")
+        .map_or("", |(_, rest)| rest)
+        .to_string();
+    for line in lines.take_while(|line| !line.trim_start().starts_with('-')) {
+        listing.push(' ');
+        listing.push_str(line.trim());
+    }
+    listing
+        .split('|')
+        .map(|name| name.split_whitespace().collect::<Vec<_>>().join(" "))
+        .map(|name| name.trim_start_matches('*').to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Schemes from `--help` output (or the known list), marked dark or light
+pub fn color_schemes(help: &str) -> Vec<ColorScheme> {
+    let mut names = parse_colorschemes(help);
+    if names.is_empty() {
+        names = COLOR_SCHEMES.iter().map(|name| name.to_string()).collect();
+    }
+    names
+        .into_iter()
+        .map(|name| ColorScheme {
+            dark: DARK_SCHEMES.contains(&name.as_str()),
+            name,
+        })
+        .collect()
+}
+
+/// Replace any `--colorscheme` in `args` with `scheme`
+pub fn set_colorscheme(args: &mut Vec<String>, scheme: &str) -> Result<(), String> {
+    if scheme.trim().is_empty() || scheme.starts_with('-') {
+        return Err(format!("Invalid color scheme '{scheme}'"));
+    }
+    args.retain(|arg| !arg.starts_with("--colorscheme"));
+    args.push(format!("--colorscheme={scheme}"));
+    Ok(())
+}
+
+/// Clear every pixel matching the top-left (background) color
+pub fn key_out_background(image: &mut RgbaImage) {
+    let Some(background) = image.pixels.get(..4).map(|px| [px[0], px[1], px[2]]) else {
+        return;
+    };
+    for pixel in image.pixels.chunks_exact_mut(4) {
+        if pixel[..3] == background {
+            pixel[3] = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_image_flags() {
+        let mut args: Vec<String> = [
+            "/input.scad",
+            "-o",
+            "/output.png",
+            "--imgsize=1920,1440",
+            "--preview",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let options: ImageExportOptions = serde_json::from_str(
+            r#"{"width": 800, "colorscheme": "Tomorrow Night", "projection": "orthographic",
+                "fullRender": true, "view": ["axes", "scales"]}"#,
+        )
+        .unwrap();
+        options.apply(&mut args).unwrap();
+        assert_eq!(
+            args[3..],
+            [
+                "--imgsize=800,800",
+                "--colorscheme=Tomorrow Night",
+                "--projection=o",
+                "--render",
+                "--view=axes,scales"
+            ]
+        );
+
+        let mut args = vec!["--imgsize=10,10".to_string(), "--viewall".to_string()];
+        ImageExportOptions::default().apply(&mut args).unwrap();
+        assert_eq!(args, ["--viewall", "--preview"]);
+    }
+
+    #[test]
+    fn rejects_unknown_values() {
+        let scheme = ImageExportOptions {
+            colorscheme: Some("Neon".into()),
+            ..Default::default()
+        };
+        assert!(scheme.apply(&mut Vec::new()).is_err());
+        let view = ImageExportOptions {
+            view: vec!["grid".into()],
+            ..Default::default()
+        };
+        assert!(view.apply(&mut Vec::new()).is_err());
+        let size = ImageExportOptions {
+            width: Some(0),
+            height: Some(100),
+            ..Default::default()
+        };
+        assert!(size.apply(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn reads_schemes_from_help() {
+        let help = "  --camera arg       camera parameters\n  \
+                    --colorscheme arg  =colorscheme: *Cornfield | Metallic | \n                     \
+                    Sunset | Starnight | BeforeDawn | Nature | DeepOcean | \n                     \
+                    Solarized | Tomorrow | Tomorrow\n                     \
+                    Night | ClearSky | Monotone\n  \
+                    -d [ --d ] arg     deps_file\n";
+        let schemes = parse_colorschemes(help);
+        assert_eq!(schemes.len(), 12);
+        assert_eq!(schemes[0], "Cornfield");
+        assert_eq!(schemes[9], "Tomorrow Night");
+        assert_eq!(schemes[11], "Monotone");
+
+        let fallback = color_schemes("OpenSCAD version 2021.01");
+        assert_eq!(fallback.len(), COLOR_SCHEMES.len());
+        assert!(fallback
+            .iter()
+            .any(|scheme| scheme.name == "Starnight" && scheme.dark));
+
+        let mut args = vec!["--colorscheme=Metallic".to_string()];
+        set_colorscheme(&mut args, "DeepOcean").unwrap();
+        assert_eq!(args, ["--colorscheme=DeepOcean"]);
+        assert!(set_colorscheme(&mut args, " ").is_err());
+    }
+
+    #[test]
+    fn keys_out_the_background() {
+        let mut image = RgbaImage {
+            width: 3,
+            height: 1,
+            pixels: vec![255, 255, 229, 255, 200, 0, 0, 255, 255, 255, 229, 255],
+        };
+        key_out_background(&mut image);
+        assert_eq!(
+            image.pixels,
+            vec![255, 255, 229, 0, 200, 0, 0, 255, 255, 255, 229, 0]
+        );
+    }
+}
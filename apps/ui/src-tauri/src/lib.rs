@@ -1,18 +1,82 @@
+mod agent_transcript;
+mod ai_instructions;
+mod animation;
+mod annotated_png;
+mod batch;
+mod cache;
+mod camera;
+mod cloud_ai;
 mod cmd;
+mod conversation;
+mod conversation_index;
+mod customizer;
+mod decimate;
+mod docs;
+mod docs_index;
+mod documents;
+mod export_presets;
+mod file_watcher;
+mod geometry;
+mod headless;
+mod headless_ai;
+mod heightmap;
 mod history;
+mod image_export;
+mod libraries;
+mod lithophane;
+mod locate;
 mod mcp;
+mod measure;
+mod menu;
+mod mesh_checks;
+mod openscad_capabilities;
+mod openscad_installer;
+mod outline;
+mod parser;
+mod pending_edits;
+mod printing;
+mod process_limits;
+mod profile2d;
+mod project;
+mod project_archive;
+mod project_files;
+mod qr;
+mod recovery;
+mod reference_geometry;
+mod render;
+mod safe_mode;
+mod secrets;
+mod session;
+mod settings;
+mod settings_transfer;
+mod slicer;
+mod snippets;
+mod step_export;
+mod sweep;
+mod symbols;
+mod templates;
+mod text_file;
+mod thing_import;
+mod tool_permissions;
+mod tray;
 mod types;
+mod url_import;
+mod variables;
 
 use cmd::{update_editor_state, update_working_dir, EditorState, OpenScadBinaryState};
+use documents::DocumentsState;
 use history::HistoryState;
 use mcp::{
-    record_window_startup_phase, remove_window, update_window_focus, McpServerState,
-    WindowLaunchIntent,
+    most_recent_window, record_window_startup_phase, remove_window, update_window_focus,
+    McpServerState, WindowLaunchIntent,
 };
-use tauri::menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use menu::MenuState;
+use settings::SettingsState;
 use tauri::{Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
 use uuid::Uuid;
 
+pub use headless::{run_headless, HEADLESS_FLAG};
+
 pub(crate) fn create_new_window_with_launch_intent(
     app: &tauri::AppHandle,
     intent: WindowLaunchIntent,
@@ -54,21 +118,24 @@ fn build_window_with_label(
     Ok(())
 }
 
-fn emit_to_focused_window<T: serde::Serialize + Clone>(
+/// Emit to the focused window. When none is focused, e.g. for a tray action
+/// while background mode has hidden every window, emit to the window focused
+/// most recently, or failing that to any open window.
+pub(crate) fn emit_to_focused_window<T: serde::Serialize + Clone>(
     app: &tauri::AppHandle,
     event: &str,
     payload: T,
 ) {
-    if let Some((_, window)) = app
-        .webview_windows()
-        .into_iter()
-        .find(|(_, window)| window.is_focused().unwrap_or(false))
-    {
-        let _ = window.emit(event, payload.clone());
-        return;
-    }
-
-    if let Some(window) = app.get_webview_window("main") {
+    let windows = app.webview_windows();
+    let target = windows
+        .values()
+        .find(|window| window.is_focused().unwrap_or(false))
+        .or_else(|| {
+            most_recent_window(&app.state::<McpServerState>()).and_then(|label| windows.get(&label))
+        })
+        .or_else(|| windows.get("main"))
+        .or_else(|| windows.values().next());
+    if let Some(window) = target {
         let _ = window.emit(event, payload);
     }
 }
@@ -85,15 +152,84 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_store::Builder::new().build())
         .manage(editor_state)
         .manage(history_state)
         .manage(openscad_state)
+        .manage(cmd::render::PreviewCheckState::default())
+        .manage(cmd::render::RevalidateState::default())
+        .manage(render::jobs::RenderJobManager::default())
+        .manage(render::queue::RenderQueue::default())
+        .manage(render::scheduler::RenderScheduler::default())
+        .manage(pending_edits::PendingEdits::default())
+        .manage(cmd::ai_tools::SyntaxCheckState::default())
+        .manage(tool_permissions::ToolApprovals::default())
+        .manage(animation::AnimationFrameCache::default())
         .manage(mcp_state.clone())
+        .manage(SettingsState::default())
+        .manage(MenuState::default())
+        .manage(cmd::actions::ActionState::default())
+        .manage(DocumentsState::default())
+        .manage(cmd::docs_index::DocsIndexState::default())
+        .manage(cmd::conversations::ConversationIndexState::default())
+        .manage(file_watcher::FileWatcherState::default())
+        .manage(cmd::session::SessionState::default())
+        .manage(cmd::cloud_ai::CloudAiState::default())
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             update_editor_state,
+            cmd::ai_tools::apply_editor_edits,
+            cmd::ai_tools::apply_edit,
+            cmd::ai_tools::apply_edits,
+            cmd::ai_tools::check_syntax,
+            cmd::ai_tools::edit_lines,
+            cmd::ai_tools::get_current_code,
+            cmd::ai_tools::list_pending_edits,
+            cmd::ai_tools::accept_pending_edit,
+            cmd::ai_tools::reject_pending_edit,
             update_working_dir,
+            cmd::actions::list_actions,
+            cmd::actions::invoke_action,
+            cmd::shortcuts::get_shortcuts,
+            cmd::shortcuts::set_shortcut,
+            cmd::shortcuts::reset_shortcuts,
+            cmd::session::get_recent_projects,
+            cmd::session::add_recent_project,
+            cmd::session::clear_recents,
+            cmd::session::save_session,
+            cmd::session::restore_session,
+            cmd::recovery::autosave_buffers,
+            cmd::recovery::list_recovered_files,
+            cmd::recovery::restore_recovered_file,
+            cmd::recovery::discard_recovered_file,
+            cmd::secrets::get_key_storage_backend,
+            cmd::secrets::set_key_storage_backend,
+            cmd::secrets::get_api_keys,
+            cmd::secrets::set_api_key,
+            cmd::secrets::delete_api_key,
+            cmd::settings_transfer::export_settings,
+            cmd::settings_transfer::import_settings,
+            cmd::settings_transfer::list_settings_profiles,
+            cmd::settings_transfer::save_settings_profile,
+            cmd::settings_transfer::apply_settings_profile,
+            cmd::settings_transfer::delete_settings_profile,
+            menu::update_menu_state,
+            tray::get_tray_settings,
+            tray::set_tray_settings,
+            cmd::documents::list_documents,
+            cmd::documents::open_document,
+            cmd::documents::switch_document,
+            cmd::documents::close_document,
+            cmd::documents::set_document_path,
             cmd::history::create_checkpoint,
+            cmd::history::get_checkpoints_for_conversation,
+            cmd::history::restore_conversation_state,
+            cmd::history::get_history_settings,
+            cmd::history::set_history_settings,
+            cmd::export_presets::get_export_presets,
+            cmd::export_presets::set_export_presets,
+            cmd::export_presets::set_active_export_preset,
+            cmd::history::get_geometry_trend,
             cmd::history::undo,
             cmd::history::redo,
             cmd::history::get_history,
@@ -104,137 +240,163 @@ pub fn run() {
             cmd::history::get_checkpoint_by_id,
             cmd::render::render_init,
             cmd::render::render_native,
+            cmd::render::render_preview,
+            cmd::installer::list_openscad_versions,
+            cmd::installer::install_openscad,
+            cmd::installer::set_active_openscad,
+            cmd::installer::remove_openscad_version,
+            cmd::variables::get_top_level_variables,
+            cmd::symbols::document_symbols,
+            cmd::symbols::find_definition,
+            cmd::symbols::completion_candidates,
+            cmd::customizer::get_customizer_parameters,
+            cmd::diagnostics::parse_diagnostics,
+            cmd::diagnostics::get_diagnostics,
+            cmd::docs::search_docs,
+            cmd::docs_index::build_docs_index,
+            cmd::docs_index::get_docs_index_status,
+            cmd::snippets::search_snippets,
+            cmd::snippets::insert_snippet,
+            cmd::sweep::sweep_parameter,
+            cmd::animation::render_animation,
+            cmd::animation::render_turntable,
+            cmd::batch::batch_export,
+            cmd::files::read_text_file,
+            cmd::files::write_text_file,
+            cmd::files::list_project_files,
+            cmd::files::list_parameter_sets,
+            cmd::project::open_project,
+            cmd::project::get_project,
+            cmd::project::open_project_file,
+            cmd::project::save_project_file,
+            cmd::project::set_project_entry_file,
+            cmd::project::resolve_file_change,
+            cmd::project::close_project,
+            cmd::heightmap::import_heightmap,
+            cmd::lithophane::generate_lithophane,
+            cmd::qr::generate_qr_code,
+            cmd::outline::convert_outline_to_polygon,
+            cmd::profile2d::render_2d_with_dimensions,
+            cmd::profile2d::preview_dxf,
+            cmd::mesh::decimate_mesh_file,
+            cmd::mesh::analyze_geometry,
+            cmd::mesh::check_printability,
+            cmd::mesh::measure_model,
+            cmd::step_export::get_step_export_capability,
+            cmd::step_export::set_step_converter,
+            cmd::slicer::get_slicer_settings,
+            cmd::slicer::set_slicer,
+            cmd::slicer::send_to_slicer,
+            cmd::printing::list_printers,
+            cmd::printing::save_printer,
+            cmd::printing::remove_printer,
+            cmd::printing::test_printer_connection,
+            cmd::printing::upload_to_printer,
+            cmd::step_export::export_step,
+            cmd::url_import::open_from_url,
+            cmd::import_remote::import_remote_project,
+            cmd::reference_geometry::import_reference_geometry,
+            cmd::reference_geometry::list_reference_geometry,
+            cmd::libraries::list_libraries,
+            cmd::libraries::install_library,
+            cmd::libraries::pin_library,
+            cmd::libraries::validate_library_paths,
+            cmd::libraries::get_project_library_paths,
+            cmd::libraries::set_project_library_paths,
+            cmd::project_archive::open_project_archive,
+            cmd::templates::list_templates,
+            cmd::templates::create_project_from_template,
+            cmd::templates::save_project_as_template,
+            cmd::templates::delete_template,
+            cmd::project_archive::save_project_archive,
+            cmd::conversations::export_conversation,
+            cmd::conversations::import_conversation,
+            cmd::conversations::save_conversation,
+            cmd::conversations::search_conversations,
+            cmd::conversations::open_saved_conversation,
+            cmd::annotated_png::export_annotated_png,
+            cmd::annotated_png::import_annotated_png,
+            cmd::safe_mode::get_safe_mode_settings,
+            cmd::safe_mode::set_safe_mode_enabled,
+            cmd::safe_mode::is_project_trusted,
+            cmd::safe_mode::trust_project,
+            cmd::safe_mode::untrust_project,
+            cmd::ai_settings::get_ai_provider_settings,
+            cmd::ai_settings::set_ai_provider_settings,
+            cmd::ai_settings::get_ai_request_headers,
+            cmd::cloud_ai::get_cloud_ai_providers,
+            cmd::cloud_ai::cloud_ai_request,
+            cmd::cloud_ai::cancel_cloud_ai_request,
             cmd::render::render_cancel,
+            cmd::render::list_colorschemes,
+            cmd::render::get_openscad_capabilities,
+            cmd::render::cancel_render,
+            cmd::render::get_render_queue_status,
+            cmd::render::set_render_concurrency,
+            cmd::render::get_render_limits,
+            cmd::render::set_render_limits,
+            cmd::render_scheduler::schedule_render,
+            cmd::render_scheduler::get_render_scheduler_settings,
+            cmd::render_scheduler::set_render_scheduler_settings,
+            cmd::tool_permissions::get_tool_permissions,
+            cmd::tool_permissions::set_tool_permissions,
+            cmd::tool_permissions::authorize_tool_call,
+            cmd::tool_permissions::approve_tool_call,
+            cmd::agent_transcripts::save_ai_transcript,
+            cmd::agent_transcripts::discard_ai_transcript,
+            cmd::agent_transcripts::list_interrupted_ai_queries,
+            cmd::agent_transcripts::resume_ai_query,
+            cmd::ai_instructions::get_ai_instructions,
+            cmd::ai_instructions::set_global_ai_instructions,
+            cmd::ai_instructions::set_project_ai_instructions,
+            cmd::ai_instructions::get_effective_system_prompt,
             mcp::configure_mcp_server,
             mcp::get_mcp_server_status,
             mcp::mcp_submit_tool_response,
+            mcp::get_tool_timeouts,
+            mcp::set_tool_timeout,
             mcp::mcp_mark_window_bridge_ready,
             mcp::mcp_report_window_startup_phase,
             mcp::report_window_open_result,
             mcp::mcp_update_window_context,
         ])
         .setup(|app| {
-            // Create app menu (About, Hide, Quit, etc.)
-            let app_menu = SubmenuBuilder::new(app, "OpenSCAD Studio")
-                .about(None)
-                .separator()
-                .hide()
-                .hide_others()
-                .show_all()
-                .separator()
-                .quit()
-                .build()?;
-
-            // Create File menu
-            let file_menu = SubmenuBuilder::new(app, "File")
-                .item(
-                    &MenuItemBuilder::with_id("new", "New")
-                        .accelerator("CmdOrCtrl+N")
-                        .build(app)?,
-                )
-                .item(
-                    &MenuItemBuilder::with_id("new_window", "New Window")
-                        .accelerator("CmdOrCtrl+Shift+N")
-                        .build(app)?,
-                )
-                .item(
-                    &MenuItemBuilder::with_id("open", "Open...")
-                        .accelerator("CmdOrCtrl+O")
-                        .build(app)?,
-                )
-                .item(&MenuItemBuilder::with_id("open_folder", "Open Folder...").build(app)?)
-                .separator()
-                .item(
-                    &MenuItemBuilder::with_id("save", "Save")
-                        .accelerator("CmdOrCtrl+S")
-                        .build(app)?,
-                )
-                .item(
-                    &MenuItemBuilder::with_id("save_as", "Save As...")
-                        .accelerator("CmdOrCtrl+Shift+S")
-                        .build(app)?,
-                )
-                .item(
-                    &MenuItemBuilder::with_id("save_all", "Save All")
-                        .accelerator("CmdOrCtrl+Alt+S")
-                        .build(app)?,
-                )
-                .separator()
-                .item(&MenuItemBuilder::with_id("export_stl", "Export as STL...").build(app)?)
-                .item(&MenuItemBuilder::with_id("export_obj", "Export as OBJ...").build(app)?)
-                .item(&MenuItemBuilder::with_id("export_amf", "Export as AMF...").build(app)?)
-                .item(&MenuItemBuilder::with_id("export_3mf", "Export as 3MF...").build(app)?)
-                .item(&MenuItemBuilder::with_id("export_png", "Export as PNG...").build(app)?)
-                .item(&MenuItemBuilder::with_id("export_svg", "Export as SVG...").build(app)?)
-                .item(&MenuItemBuilder::with_id("export_dxf", "Export as DXF...").build(app)?)
-                .build()?;
-
-            // Create Edit menu
-            let edit_menu = SubmenuBuilder::new(app, "Edit")
-                .undo()
-                .redo()
-                .separator()
-                .cut()
-                .copy()
-                .paste()
-                .separator()
-                .select_all()
-                .build()?;
-
-            let menu = MenuBuilder::new(app)
-                .item(&app_menu)
-                .item(&file_menu)
-                .item(&edit_menu)
-                .build()?;
-
+            *app.state::<SettingsState>().settings.lock().unwrap() =
+                settings::load_settings(app.handle());
+            cmd::render::apply_render_concurrency(app.handle());
+            process_limits::set_memory_limit(
+                app.state::<SettingsState>()
+                    .settings
+                    .lock()
+                    .unwrap()
+                    .render_limits
+                    .memory_limit_mb,
+            );
+            cmd::session::load_launch_session(app.handle());
+            cmd::recovery::start_recovery(app.handle());
+            let menu = menu::build_app_menu(app.handle())?;
             app.set_menu(menu)?;
 
+            let tray_settings = app
+                .state::<SettingsState>()
+                .settings
+                .lock()
+                .unwrap()
+                .tray
+                .clone();
+            if let Err(e) = tray::sync_tray(app.handle(), &tray_settings) {
+                eprintln!("[tray] {e}");
+            }
+
             Ok(())
         })
-        .on_menu_event(move |app, event| match event.id().as_ref() {
-            "new" => {
-                emit_to_focused_window(app, "menu:file:new", ());
-            }
-            "new_window" => {
-                let _ = create_new_window_with_launch_intent(app, WindowLaunchIntent::Welcome);
-            }
-            "open" => {
-                emit_to_focused_window(app, "menu:file:open", ());
-            }
-            "open_folder" => {
-                emit_to_focused_window(app, "menu:file:open_folder", ());
+        .on_menu_event(move |app, event| {
+            let id = event.id().as_ref();
+            if !tray::handle_tray_menu_event(app, id)
+                && !cmd::session::handle_recent_menu_event(app, id)
+            {
+                let _ = cmd::actions::dispatch_action(app, id);
             }
-            "save" => {
-                emit_to_focused_window(app, "menu:file:save", ());
-            }
-            "save_as" => {
-                emit_to_focused_window(app, "menu:file:save_as", ());
-            }
-            "save_all" => {
-                emit_to_focused_window(app, "menu:file:save_all", ());
-            }
-            "export_stl" => {
-                emit_to_focused_window(app, "menu:file:export", "stl");
-            }
-            "export_obj" => {
-                emit_to_focused_window(app, "menu:file:export", "obj");
-            }
-            "export_amf" => {
-                emit_to_focused_window(app, "menu:file:export", "amf");
-            }
-            "export_3mf" => {
-                emit_to_focused_window(app, "menu:file:export", "3mf");
-            }
-            "export_png" => {
-                emit_to_focused_window(app, "menu:file:export", "png");
-            }
-            "export_svg" => {
-                emit_to_focused_window(app, "menu:file:export", "svg");
-            }
-            "export_dxf" => {
-                emit_to_focused_window(app, "menu:file:export", "dxf");
-            }
-            _ => {}
         })
         .on_window_event(move |window, event| match event {
             tauri::WindowEvent::Focused(focused) => {
@@ -242,10 +404,23 @@ pub fn run() {
             }
             tauri::WindowEvent::Destroyed => {
                 remove_window(&window_mcp_state, window.label());
+                if let Err(e) = cmd::history::save_history(window.app_handle()) {
+                    eprintln!("[history] {e}");
+                }
+            }
+            tauri::WindowEvent::CloseRequested { api, .. }
+                if tray::should_hide_instead_of_close(window) =>
+            {
+                api.prevent_close();
+                let _ = window.hide();
             }
-            tauri::WindowEvent::CloseRequested { .. } => {}
             _ => {}
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                cmd::recovery::finish_recovery(app);
+            }
+        });
 }
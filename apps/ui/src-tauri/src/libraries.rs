@@ -0,0 +1,315 @@
+/**
+ * OpenSCAD library management
+ *
+ * Libraries live in OpenSCAD's per-user library folder, which OpenSCAD
+ * searches on its own and the app auto-discovers, so `use <BOSL2/std.scad>`
+ * works once a library is installed there. Libraries installed by the app
+ * carry a small manifest recording the installed version; folders without
+ * one were installed by hand and are listed but never touched.
+ *
+ * Projects can add their own search paths (e.g. a vendored `lib/` folder or
+ * a shared checkout elsewhere); renders pass them to OpenSCAD through the
+ * `OPENSCADPATH` environment variable.
+ */
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = ".openscad-studio-library.json";
+
+/// A library the app knows how to download from GitHub
+pub struct KnownLibrary {
+    /// Folder name under the library directory, as used in `use <...>`
+    pub name: &'static str,
+    /// GitHub `owner/repo`
+    pub repo: &'static str,
+    /// Fetched when the repo has no releases and no version is given
+    pub default_branch: &'static str,
+    /// Subfolder of the repo holding the library files, if not the root
+    pub source_dir: Option<&'static str>,
+    pub description: &'static str,
+    /// Typical statement to pull the library in
+    pub usage: &'static str,
+}
+
+pub const KNOWN_LIBRARIES: &[KnownLibrary] = &[
+    KnownLibrary {
+        name: "BOSL2",
+        repo: "BelfrySCAD/BOSL2",
+        default_branch: "master",
+        source_dir: None,
+        description: "Belfry OpenSCAD Library v2: attachments, rounding, threading, gears and more",
+        usage: "include <BOSL2/std.scad>",
+    },
+    KnownLibrary {
+        name: "MCAD",
+        repo: "openscad/MCAD",
+        default_branch: "master",
+        source_dir: None,
+        description: "Mechanical parts: gears, nuts and bolts, bearings, motors",
+        usage: "use <MCAD/boxes.scad>",
+    },
+    KnownLibrary {
+        name: "dotSCAD",
+        repo: "JustinSDK/dotSCAD",
+        default_branch: "master",
+        source_dir: Some("src"),
+        description: "Functions and modules for paths, shapes, curves and patterns",
+        usage: "use <dotSCAD/rounded_square.scad>",
+    },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryManifest {
+    pub name: String,
+    pub repo: String,
+    /// Release tag or branch the files came from
+    pub version: String,
+    pub installed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledLibrary {
+    pub name: String,
+    pub path: String,
+    /// Known only for libraries installed by the app
+    pub version: Option<String>,
+    pub managed: bool,
+    pub pinned_version: Option<String>,
+    pub usage: Option<String>,
+}
+
+pub fn find_known(name: &str) -> Option<&'static KnownLibrary> {
+    KNOWN_LIBRARIES
+        .iter()
+        .find(|library| library.name.eq_ignore_ascii_case(name))
+}
+
+/// OpenSCAD's per-user library folder
+pub fn user_library_dir(home: &Path) -> PathBuf {
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        home.join("Documents").join("OpenSCAD").join("libraries")
+    } else {
+        home.join(".local")
+            .join("share")
+            .join("OpenSCAD")
+            .join("libraries")
+    }
+}
+
+/// GitHub source archive for a release tag or branch
+pub fn archive_url(repo: &str, reference: &str, is_branch: bool) -> String {
+    let kind = if is_branch { "heads" } else { "tags" };
+    format!("https://github.com/{repo}/archive/refs/{kind}/{reference}.zip")
+}
+
+pub fn latest_release_url(repo: &str) -> String {
+    format!("https://api.github.com/repos/{repo}/releases/latest")
+}
+
+/// Tag name from a GitHub "latest release" API response
+pub fn latest_release_tag(json: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    value
+        .get("tag_name")?
+        .as_str()
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+}
+
+pub fn read_manifest(library_dir: &Path) -> Option<LibraryManifest> {
+    let json = fs::read_to_string(library_dir.join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+pub fn write_manifest(library_dir: &Path, manifest: &LibraryManifest) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize library manifest: {e}"))?;
+    fs::write(library_dir.join(MANIFEST_FILE), json)
+        .map_err(|e| format!("Failed to write library manifest: {e}"))
+}
+
+/// Whether installing library `name` may replace what is at `target`:
+/// nothing, or a folder the app installed that library into. Anything else
+/// was put there by hand and is only replaced once the user confirms.
+pub fn is_replaceable(target: &Path, name: &str) -> bool {
+    if !target.exists() {
+        return true;
+    }
+    read_manifest(target).is_some_and(|manifest| manifest.name.eq_ignore_ascii_case(name))
+}
+
+/// Library folders under `dir`, sorted by name
+pub fn list_installed(dir: &Path, pins: &BTreeMap<String, String>) -> Vec<InstalledLibrary> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut libraries: Vec<InstalledLibrary> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if name.starts_with('.') {
+                return None;
+            }
+            let manifest = read_manifest(&entry.path());
+            Some(InstalledLibrary {
+                path: entry.path().to_string_lossy().to_string(),
+                version: manifest.as_ref().map(|m| m.version.clone()),
+                managed: manifest.is_some(),
+                pinned_version: pins.get(&name).cloned(),
+                usage: find_known(&name).map(|known| known.usage.to_string()),
+                name,
+            })
+        })
+        .collect();
+    libraries.sort_by_key(|library| library.name.to_lowercase());
+    libraries
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryPathStatus {
+    /// As configured
+    pub path: String,
+    /// Absolute path passed to OpenSCAD
+    pub resolved: String,
+    pub exists: bool,
+}
+
+/// Absolute form of a configured search path; relative paths resolve
+/// against the project folder
+pub fn resolve_search_path(root: Option<&Path>, path: &str) -> PathBuf {
+    let path = Path::new(path.trim());
+    match root {
+        Some(root) if path.is_relative() => root.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+pub fn check_search_paths(root: Option<&Path>, paths: &[String]) -> Vec<LibraryPathStatus> {
+    paths
+        .iter()
+        .map(|path| {
+            let resolved = resolve_search_path(root, path);
+            LibraryPathStatus {
+                path: path.clone(),
+                exists: resolved.is_dir(),
+                resolved: resolved.to_string_lossy().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// `OPENSCADPATH` value searching `paths` before any inherited entries, or
+/// `None` when there is nothing to add
+pub fn openscad_path_env(paths: &[String], inherited: Option<&OsStr>) -> Option<OsString> {
+    if paths.is_empty() {
+        return None;
+    }
+    let inherited = inherited
+        .map(|value| std::env::split_paths(value).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let entries = paths
+        .iter()
+        .map(PathBuf::from)
+        .chain(inherited)
+        .filter(|path| !path.as_os_str().is_empty());
+    std::env::join_paths(entries).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_github_urls_and_reads_release_tags() {
+        assert_eq!(
+            archive_url("BelfrySCAD/BOSL2", "v2.0.716", false),
+            "https://github.com/BelfrySCAD/BOSL2/archive/refs/tags/v2.0.716.zip"
+        );
+        assert_eq!(
+            archive_url("openscad/MCAD", "master", true),
+            "https://github.com/openscad/MCAD/archive/refs/heads/master.zip"
+        );
+        assert_eq!(
+            latest_release_tag(r#"{"tag_name": "v3.3", "name": "dotSCAD 3.3"}"#),
+            Some("v3.3".to_string())
+        );
+        assert_eq!(latest_release_tag(r#"{"message": "Not Found"}"#), None);
+        assert!(find_known("bosl2").is_some());
+    }
+
+    #[test]
+    fn builds_openscad_path_from_project_paths() {
+        let root = std::env::temp_dir();
+        let statuses = check_search_paths(
+            Some(&root),
+            &[".".to_string(), "no-such-library-folder".to_string()],
+        );
+        assert!(statuses[0].exists);
+        assert!(!statuses[1].exists);
+        assert_eq!(
+            statuses[1].resolved,
+            root.join("no-such-library-folder").to_string_lossy()
+        );
+
+        assert_eq!(openscad_path_env(&[], Some(OsStr::new("/opt/scad"))), None);
+        let value = openscad_path_env(
+            &["/work/lib".to_string()],
+            Some(std::env::join_paths(["/opt/scad"]).unwrap().as_os_str()),
+        )
+        .unwrap();
+        assert_eq!(
+            std::env::split_paths(&value).collect::<Vec<_>>(),
+            vec![PathBuf::from("/work/lib"), PathBuf::from("/opt/scad")]
+        );
+    }
+
+    #[test]
+    fn lists_managed_and_manual_libraries() {
+        let dir = std::env::temp_dir()
+            .join("openscad-studio-library-tests")
+            .join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(dir.join("BOSL2")).unwrap();
+        fs::create_dir_all(dir.join("my_parts")).unwrap();
+        fs::create_dir_all(dir.join(".staging")).unwrap();
+        write_manifest(
+            &dir.join("BOSL2"),
+            &LibraryManifest {
+                name: "BOSL2".into(),
+                repo: "BelfrySCAD/BOSL2".into(),
+                version: "v2.0.716".into(),
+                installed_at: 0,
+            },
+        )
+        .unwrap();
+        let mut pins = BTreeMap::new();
+        pins.insert("BOSL2".to_string(), "v2.0.716".to_string());
+
+        let libraries = list_installed(&dir, &pins);
+        assert_eq!(libraries.len(), 2);
+        assert_eq!(libraries[0].name, "BOSL2");
+        assert!(libraries[0].managed);
+        assert_eq!(libraries[0].version.as_deref(), Some("v2.0.716"));
+        assert_eq!(libraries[0].pinned_version.as_deref(), Some("v2.0.716"));
+        assert_eq!(
+            libraries[0].usage.as_deref(),
+            Some("include <BOSL2/std.scad>")
+        );
+        assert_eq!(libraries[1].name, "my_parts");
+        assert!(!libraries[1].managed && libraries[1].usage.is_none());
+
+        assert!(is_replaceable(&dir.join("BOSL2"), "BOSL2"));
+        assert!(is_replaceable(&dir.join("MCAD"), "MCAD"));
+        assert!(!is_replaceable(&dir.join("my_parts"), "my_parts"));
+        fs::rename(dir.join("BOSL2"), dir.join("MCAD")).unwrap();
+        assert!(!is_replaceable(&dir.join("MCAD"), "MCAD"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
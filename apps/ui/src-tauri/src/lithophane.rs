@@ -0,0 +1,267 @@
+/**
+ * Lithophane generator
+ *
+ * Converts a photo into a self-contained OpenSCAD file: a single closed
+ * polyhedron whose thickness follows image darkness (dark = thick, so it
+ * blocks more light), optionally surrounded by a flat frame. Generating the
+ * mesh directly avoids depending on a side-car heightmap file.
+ */
+use crate::heightmap::GrayGrid;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_LITHOPHANE_RESOLUTION: u32 = 150;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LithophaneOptions {
+    /// Width of the image area in mm (depth follows the aspect ratio)
+    pub width: f64,
+    /// Thickness of pure white in mm
+    pub min_thickness: f64,
+    /// Thickness of pure black in mm
+    pub max_thickness: f64,
+    /// Longest side of the sample grid
+    pub max_resolution: u32,
+    /// Frame border width in mm; 0 disables the frame
+    pub frame_width: f64,
+}
+
+impl Default for LithophaneOptions {
+    fn default() -> Self {
+        Self {
+            width: 100.0,
+            min_thickness: 0.8,
+            max_thickness: 3.0,
+            max_resolution: DEFAULT_LITHOPHANE_RESOLUTION,
+            frame_width: 3.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Lithophane {
+    pub code: String,
+    pub columns: u32,
+    pub rows: u32,
+    pub width: f64,
+    pub depth: f64,
+}
+
+pub struct Mesh {
+    pub points: Vec<[f64; 3]>,
+    /// Triangles in OpenSCAD order (clockwise seen from outside)
+    pub faces: Vec<[usize; 3]>,
+}
+
+fn validate(options: &LithophaneOptions) -> Result<(), String> {
+    if options.width <= 0.0 {
+        return Err("Lithophane width must be positive".into());
+    }
+    if options.min_thickness <= 0.0 || options.max_thickness <= options.min_thickness {
+        return Err("Lithophane thickness needs 0 < minimum < maximum".into());
+    }
+    if options.frame_width < 0.0 {
+        return Err("Frame width cannot be negative".into());
+    }
+    Ok(())
+}
+
+/// Closed relief mesh over the grid, image top row at the far (+Y) edge
+pub fn lithophane_mesh(grid: &GrayGrid, options: &LithophaneOptions) -> Result<Mesh, String> {
+    validate(options)?;
+    let (columns, rows) = (grid.width as usize, grid.height as usize);
+    if columns < 2 || rows < 2 {
+        return Err("Image is too small for a lithophane".into());
+    }
+
+    let step = options.width / (columns - 1) as f64;
+    let range = options.max_thickness - options.min_thickness;
+    let top = |r: usize, c: usize| r * columns + c;
+    let bottom = |r: usize, c: usize| rows * columns + r * columns + c;
+
+    let mut points = Vec::with_capacity(2 * rows * columns);
+    for is_top in [true, false] {
+        for r in 0..rows {
+            for c in 0..columns {
+                let z = if is_top {
+                    options.min_thickness + (1.0 - grid.values[r * columns + c]) * range
+                } else {
+                    0.0
+                };
+                points.push([c as f64 * step, (rows - 1 - r) as f64 * step, z]);
+            }
+        }
+    }
+
+    // Built counter-clockwise seen from outside, flipped for OpenSCAD below.
+    let mut faces = Vec::new();
+    for r in 0..rows - 1 {
+        for c in 0..columns - 1 {
+            let (a, b, cc, d) = (top(r, c), top(r, c + 1), top(r + 1, c + 1), top(r + 1, c));
+            faces.push([d, cc, b]);
+            faces.push([d, b, a]);
+            let (a, b, cc, d) = (
+                bottom(r, c),
+                bottom(r, c + 1),
+                bottom(r + 1, c + 1),
+                bottom(r + 1, c),
+            );
+            faces.push([b, cc, d]);
+            faces.push([a, b, d]);
+        }
+    }
+
+    // Boundary walk, counter-clockwise seen from above.
+    let mut boundary = Vec::new();
+    boundary.extend((0..columns).map(|c| (rows - 1, c)));
+    boundary.extend((0..rows - 1).rev().map(|r| (r, columns - 1)));
+    boundary.extend((0..columns - 1).rev().map(|c| (0, c)));
+    boundary.extend((1..rows - 1).map(|r| (r, 0)));
+    for (i, &(pr, pc)) in boundary.iter().enumerate() {
+        let (qr, qc) = boundary[(i + 1) % boundary.len()];
+        let (pb, qb, qt, pt) = (bottom(pr, pc), bottom(qr, qc), top(qr, qc), top(pr, pc));
+        faces.push([pb, qb, qt]);
+        faces.push([pb, qt, pt]);
+    }
+
+    for face in &mut faces {
+        face.reverse();
+    }
+    Ok(Mesh { points, faces })
+}
+
+fn format_number(value: f64) -> String {
+    let text = format!("{value:.3}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn write_list<T>(out: &mut String, items: &[T], per_line: usize, fmt: impl Fn(&T) -> String) {
+    for (index, chunk) in items.chunks(per_line).enumerate() {
+        let line: Vec<String> = chunk.iter().map(&fmt).collect();
+        out.push_str("        ");
+        out.push_str(&line.join(","));
+        if (index + 1) * per_line < items.len() {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+}
+
+/// Full OpenSCAD source for a lithophane of `grid`
+pub fn lithophane_code(
+    grid: &GrayGrid,
+    options: &LithophaneOptions,
+    source_name: &str,
+) -> Result<Lithophane, String> {
+    let mesh = lithophane_mesh(grid, options)?;
+    let step = options.width / (grid.width - 1) as f64;
+    let depth = (grid.height - 1) as f64 * step;
+
+    let mut code = format!(
+        "// Lithophane of {source_name} ({}x{} samples)\n\
+         width = {};\n\
+         depth = {};\n\
+         frame_width = {};\n\
+         frame_thickness = {};\n\n\
+         module lithophane() {{\n    polyhedron(points = [\n",
+        grid.width,
+        grid.height,
+        format_number(options.width),
+        format_number(depth),
+        format_number(options.frame_width),
+        format_number(options.max_thickness),
+    );
+    write_list(&mut code, &mesh.points, 8, |p| {
+        format!(
+            "[{},{},{}]",
+            format_number(p[0]),
+            format_number(p[1]),
+            format_number(p[2])
+        )
+    });
+    code.push_str("    ], faces = [\n");
+    write_list(&mut code, &mesh.faces, 12, |f| {
+        format!("[{},{},{}]", f[0], f[1], f[2])
+    });
+    code.push_str(
+        "    ]);\n}\n\n\
+         lithophane();\n\n\
+         if (frame_width > 0)\n    difference() {\n        \
+         translate([-frame_width, -frame_width, 0])\n            \
+         cube([width + 2 * frame_width, depth + 2 * frame_width, frame_thickness]);\n        \
+         translate([0.01, 0.01, -1])\n            \
+         cube([width - 0.02, depth - 0.02, frame_thickness + 2]);\n    }\n",
+    );
+
+    Ok(Lithophane {
+        code,
+        columns: grid.width,
+        rows: grid.height,
+        width: options.width,
+        depth,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn grid() -> GrayGrid {
+        GrayGrid {
+            width: 4,
+            height: 3,
+            values: (0..12).map(|i| i as f64 / 11.0).collect(),
+        }
+    }
+
+    #[test]
+    fn mesh_is_closed_and_clockwise() {
+        let mesh = lithophane_mesh(&grid(), &LithophaneOptions::default()).unwrap();
+
+        let edges: Vec<(usize, usize)> = mesh
+            .faces
+            .iter()
+            .flat_map(|f| [(f[0], f[1]), (f[1], f[2]), (f[2], f[0])])
+            .collect();
+        let unique: HashSet<_> = edges.iter().copied().collect();
+        assert_eq!(unique.len(), edges.len());
+        assert!(edges.iter().all(|&(a, b)| unique.contains(&(b, a))));
+
+        // Clockwise-from-outside winding gives a negative signed volume.
+        let volume: f64 = mesh
+            .faces
+            .iter()
+            .map(|f| {
+                let [a, b, c] = f.map(|i| mesh.points[i]);
+                a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+                    + a[2] * (b[0] * c[1] - b[1] * c[0])
+            })
+            .sum::<f64>()
+            / 6.0;
+        assert!(volume < 0.0);
+    }
+
+    #[test]
+    fn code_embeds_dimensions_and_rejects_bad_options() {
+        let lithophane =
+            lithophane_code(&grid(), &LithophaneOptions::default(), "cat.png").unwrap();
+        assert!(lithophane
+            .code
+            .starts_with("// Lithophane of cat.png (4x3 samples)"));
+        assert!(lithophane.code.contains("depth = 66.667;"));
+        assert!((lithophane.depth - 200.0 / 3.0).abs() < 1e-9);
+
+        let bad = LithophaneOptions {
+            max_thickness: 0.5,
+            ..Default::default()
+        };
+        assert!(lithophane_code(&grid(), &bad, "cat.png").is_err());
+    }
+}
@@ -0,0 +1,393 @@
+/**
+ * Finding and launching OpenSCAD on each platform
+ *
+ * A system-wide OpenSCAD is looked up on PATH first (`which` on Unix,
+ * `where.exe` on Windows), then where installers put it: the registry and
+ * Program Files on Windows, the Applications folders on macOS.
+ *
+ * On Linux it may also be a Flatpak, a Snap or an AppImage. Flatpak and
+ * Snap builds are sandboxed: Flatpak builds run through `flatpak run` with
+ * the folders a render touches shared into the sandbox, and Snap builds
+ * get their temp folder inside the snap's own data folder, since their
+ * `/tmp` is private.
+ *
+ * Windows paths get some extra care before they reach OpenSCAD: the
+ * console build (`openscad.com`) is preferred so diagnostics arrive on
+ * stderr, spawned processes don't flash a console window, and the verbatim
+ * `\\?\` prefix that `canonicalize` adds is removed whenever the path fits
+ * in `MAX_PATH`, since OpenSCAD can't open verbatim paths to short files.
+ */
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Longest path Windows programs that aren't long-path aware can open
+pub const WINDOWS_MAX_PATH: usize = 260;
+/// OpenSCAD's Flathub application ID
+pub const FLATPAK_APP_ID: &str = "org.openscad.OpenSCAD";
+
+/// Folders and environment an OpenSCAD run needs beyond its own install
+#[derive(Default)]
+pub struct LaunchContext<'a> {
+    /// Folders OpenSCAD reads and writes
+    pub folders: Vec<&'a Path>,
+    pub env: Vec<(&'static str, OsString)>,
+}
+
+/// First path printed by `which`/`where.exe`
+pub fn parse_lookup_output(stdout: &str) -> Option<PathBuf> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(PathBuf::from)
+}
+
+/// String value of a single-value `reg query` listing
+pub fn parse_registry_value(stdout: &str) -> Option<String> {
+    stdout.lines().find_map(|line| {
+        ["REG_SZ", "REG_EXPAND_SZ"].iter().find_map(|kind| {
+            let (_, value) = line.split_once(&format!(" {kind} "))?;
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        })
+    })
+}
+
+/// `path` without Windows' `\\?\` verbatim prefix, if the plain form still
+/// fits in `MAX_PATH`; verbatim UNC paths become `\\server\share\...`
+pub fn strip_verbatim_prefix(path: &str) -> Option<String> {
+    let plain = if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{unc}")
+    } else {
+        let rest = path.strip_prefix(r"\\?\")?;
+        // Only drive paths have a plain equivalent (`\\?\Volume{...}` doesn't)
+        let bytes = rest.as_bytes();
+        if bytes.len() < 2 || !bytes[0].is_ascii_alphabetic() || bytes[1] != b':' {
+            return None;
+        }
+        rest.to_string()
+    };
+    (plain.len() < WINDOWS_MAX_PATH).then_some(plain)
+}
+
+/// `path` in the form to hand to OpenSCAD and compare against user input
+pub fn plain_path(path: PathBuf) -> PathBuf {
+    match path.to_str().and_then(strip_verbatim_prefix) {
+        Some(plain) => PathBuf::from(plain),
+        None => path,
+    }
+}
+
+/// Key for comparing paths that may differ only in spelling: Windows paths
+/// are case-insensitive and accept either separator
+pub fn path_key(path: &str, windows: bool) -> String {
+    if !windows {
+        return path.trim_end_matches('/').to_string();
+    }
+    let path = strip_verbatim_prefix(path).unwrap_or_else(|| path.to_string());
+    path.replace('\\', "/").trim_end_matches('/').to_lowercase()
+}
+
+/// Folder for the app's temporary files, in a form OpenSCAD can open
+pub fn temp_root() -> PathBuf {
+    plain_path(std::env::temp_dir()).join("openscad-studio")
+}
+
+/// Temp folder the OpenSCAD at `binary` can reach
+pub fn temp_root_for(binary: &Path) -> PathBuf {
+    match (snap_name(binary), std::env::var_os("HOME")) {
+        (Some(snap), Some(home)) => PathBuf::from(home)
+            .join("snap")
+            .join(snap)
+            .join("common")
+            .join("openscad-studio"),
+        _ => temp_root(),
+    }
+}
+
+/// App ID of a Flatpak install's exported launcher
+/// (`.../flatpak/exports/bin/org.openscad.OpenSCAD`)
+pub fn flatpak_app_id(binary: &Path) -> Option<&str> {
+    let name = binary.file_name()?.to_str()?;
+    let exported = binary.parent()?.ends_with("exports/bin");
+    (exported && name.starts_with(FLATPAK_APP_ID)).then_some(name)
+}
+
+/// Snap a `/snap/bin` command belongs to: `openscad-nightly.openscad`
+/// belongs to `openscad-nightly`
+pub fn snap_name(binary: &Path) -> Option<&str> {
+    if binary.parent()? != Path::new("/snap/bin") {
+        return None;
+    }
+    binary.file_name()?.to_str()?.split('.').next()
+}
+
+/// `flatpak run` arguments for OpenSCAD with `context` shared into the sandbox
+pub fn flatpak_run_args(app_id: &str, context: &LaunchContext) -> Vec<String> {
+    let mut args = vec!["run".to_string(), "--command=openscad".to_string()];
+    args.extend(
+        context
+            .folders
+            .iter()
+            .map(|folder| format!("--filesystem={}", folder.display())),
+    );
+    args.extend(
+        context
+            .env
+            .iter()
+            .map(|(name, value)| format!("--env={name}={}", value.to_string_lossy())),
+    );
+    args.push(app_id.to_string());
+    args
+}
+
+/// The newest OpenSCAD AppImage among `names`
+pub fn newest_appimage<'a>(names: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    names
+        .into_iter()
+        .filter(|name| {
+            let name = name.to_ascii_lowercase();
+            name.starts_with("openscad") && name.ends_with(".appimage")
+        })
+        .max()
+}
+
+/// A command for `binary` that doesn't open a console window on Windows
+pub fn openscad_command(binary: &Path) -> Command {
+    launch_command(binary, &LaunchContext::default())
+}
+
+/// A command for `binary` that can reach everything in `context`
+pub fn launch_command(binary: &Path, context: &LaunchContext) -> Command {
+    #[allow(unused_mut)]
+    let mut command = match flatpak_app_id(binary) {
+        Some(app_id) => {
+            let mut command = Command::new("flatpak");
+            command.args(flatpak_run_args(app_id, context));
+            command
+        }
+        None => {
+            let mut command = Command::new(binary);
+            command.envs(context.env.iter().map(|(name, value)| (name, value)));
+            command
+        }
+    };
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+/// On Windows, the console wrapper `openscad.com` next to `openscad.exe`:
+/// the GUI executable detaches from the console and its output is lost
+pub fn console_binary(binary: PathBuf) -> PathBuf {
+    let is_exe = binary
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"));
+    if !is_exe {
+        return binary;
+    }
+    let console = binary.with_extension("com");
+    if console.is_file() {
+        console
+    } else {
+        binary
+    }
+}
+
+fn path_lookup() -> Option<PathBuf> {
+    let finder = if cfg!(windows) { "where.exe" } else { "which" };
+    let output = openscad_command(Path::new(finder))
+        .arg("openscad")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_lookup_output(&String::from_utf8_lossy(&output.stdout)).filter(|path| path.exists())
+}
+
+#[cfg(windows)]
+fn install_locations() -> Vec<PathBuf> {
+    const KEYS: &[(&str, Option<&str>)] = &[
+        (r"HKLM\SOFTWARE\OpenSCAD", None),
+        (r"HKCU\SOFTWARE\OpenSCAD", None),
+        (
+            r"HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall\OpenSCAD",
+            Some("InstallLocation"),
+        ),
+        (
+            r"HKCU\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall\OpenSCAD",
+            Some("InstallLocation"),
+        ),
+    ];
+    let registry = KEYS.iter().filter_map(|(key, value)| {
+        let mut command = openscad_command(Path::new("reg"));
+        command.args(["query", key]);
+        match value {
+            Some(value) => command.args(["/v", value]),
+            None => command.arg("/ve"),
+        };
+        let output = command.output().ok()?;
+        output.status.success().then_some(())?;
+        parse_registry_value(&String::from_utf8_lossy(&output.stdout))
+    });
+    let program_files = ["ProgramFiles", "ProgramW6432", "ProgramFiles(x86)"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .chain(
+            std::env::var("LOCALAPPDATA")
+                .ok()
+                .map(|dir| format!(r"{dir}\Programs")),
+        )
+        .map(|dir| format!(r"{dir}\OpenSCAD"));
+    registry
+        .chain(program_files)
+        .map(|dir| PathBuf::from(dir).join("openscad.exe"))
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn install_locations() -> Vec<PathBuf> {
+    let app = Path::new("OpenSCAD.app/Contents/MacOS/OpenSCAD");
+    let mut locations = vec![Path::new("/Applications").join(app)];
+    if let Some(home) = std::env::var_os("HOME") {
+        locations.push(PathBuf::from(home).join("Applications").join(app));
+    }
+    locations
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn newest_appimage_in(dir: PathBuf) -> Option<PathBuf> {
+    let names: Vec<String> = std::fs::read_dir(&dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    newest_appimage(names.iter().map(String::as_str)).map(|name| dir.join(name))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn install_locations() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut locations: Vec<PathBuf> = [
+        "/usr/bin/openscad",
+        "/usr/local/bin/openscad",
+        "/snap/bin/openscad",
+        "/snap/bin/openscad-nightly",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .collect();
+    locations.push(Path::new("/var/lib/flatpak/exports/bin").join(FLATPAK_APP_ID));
+    if let Some(home) = home {
+        locations.push(
+            home.join(".local/share/flatpak/exports/bin")
+                .join(FLATPAK_APP_ID),
+        );
+        locations.extend(
+            ["Applications", ".local/bin", "bin", "Downloads"]
+                .iter()
+                .filter_map(|dir| newest_appimage_in(home.join(dir))),
+        );
+    }
+    locations
+}
+
+/// System-installed OpenSCAD: PATH first, then the usual install locations
+pub fn system_binary_path() -> Option<PathBuf> {
+    let found =
+        path_lookup().or_else(|| install_locations().into_iter().find(|path| path.is_file()))?;
+    let found = console_binary(plain_path(found));
+    eprintln!("[locate] Found system OpenSCAD at {:?}", found);
+    Some(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lookup_and_registry_output() {
+        assert_eq!(
+            parse_lookup_output(
+                "\r\nC:\\Program Files\\OpenSCAD\\openscad.exe\r\nC:\\Other\\openscad.exe\r\n"
+            ),
+            Some(PathBuf::from("C:\\Program Files\\OpenSCAD\\openscad.exe"))
+        );
+        assert_eq!(parse_lookup_output("\n"), None);
+
+        let listing = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\OpenSCAD\r\n    (Default)    REG_SZ    C:\\Program Files\\OpenSCAD\r\n\r\n";
+        assert_eq!(
+            parse_registry_value(listing).as_deref(),
+            Some("C:\\Program Files\\OpenSCAD")
+        );
+        assert_eq!(
+            parse_registry_value("    (Default)    REG_SZ    \r\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn recognises_linux_sandboxed_installs() {
+        let flatpak = Path::new("/var/lib/flatpak/exports/bin/org.openscad.OpenSCAD");
+        assert_eq!(flatpak_app_id(flatpak), Some(FLATPAK_APP_ID));
+        assert_eq!(flatpak_app_id(Path::new("/usr/bin/openscad")), None);
+        let context = LaunchContext {
+            folders: vec![Path::new("/home/me/project")],
+            env: vec![("OPENSCADPATH", OsString::from("/home/me/libs"))],
+        };
+        assert_eq!(
+            flatpak_run_args(FLATPAK_APP_ID, &context),
+            [
+                "run",
+                "--command=openscad",
+                "--filesystem=/home/me/project",
+                "--env=OPENSCADPATH=/home/me/libs",
+                FLATPAK_APP_ID
+            ]
+        );
+
+        assert_eq!(
+            snap_name(Path::new("/snap/bin/openscad-nightly.openscad")),
+            Some("openscad-nightly")
+        );
+        assert_eq!(snap_name(Path::new("/usr/bin/openscad")), None);
+
+        assert_eq!(
+            newest_appimage([
+                "notes.txt",
+                "OpenSCAD-2021.01-x86_64.AppImage",
+                "OpenSCAD-2025.03.10-x86_64.AppImage",
+                "Other.AppImage"
+            ]),
+            Some("OpenSCAD-2025.03.10-x86_64.AppImage")
+        );
+    }
+
+    #[test]
+    fn strips_verbatim_prefixes_that_fit() {
+        assert_eq!(
+            strip_verbatim_prefix(r"\\?\C:\Users\me\model.scad").as_deref(),
+            Some(r"C:\Users\me\model.scad")
+        );
+        assert_eq!(
+            strip_verbatim_prefix(r"\\?\UNC\server\share\model.scad").as_deref(),
+            Some(r"\\server\share\model.scad")
+        );
+        assert_eq!(strip_verbatim_prefix(r"\\?\Volume{abc}\x"), None);
+        assert_eq!(strip_verbatim_prefix("/home/me/model.scad"), None);
+        let long = format!(r"\\?\C:\{}", "a".repeat(WINDOWS_MAX_PATH));
+        assert_eq!(strip_verbatim_prefix(&long), None);
+
+        assert_eq!(
+            path_key(r"\\?\C:\Projects\Bracket\", true),
+            path_key("c:/projects/bracket", true)
+        );
+        assert_ne!(path_key("/Projects", false), path_key("/projects", false));
+    }
+}
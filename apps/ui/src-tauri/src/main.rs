@@ -1,6 +1,31 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+/// A GUI-subsystem binary starts without a console, so headless output would
+/// go nowhere. Attach to the console of the shell that launched us, if any.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // Fails harmlessly when there is no parent console or one is attached
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args
+        .iter()
+        .skip(1)
+        .any(|arg| arg == openscad_studio_lib::HEADLESS_FLAG)
+    {
+        #[cfg(windows)]
+        attach_parent_console();
+        std::process::exit(openscad_studio_lib::run_headless(args));
+    }
     openscad_studio_lib::run()
 }
@@ -9,16 +9,53 @@ use rmcp::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::sync::{mpsc, Arc, Mutex};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager, State, Window};
 use uuid::Uuid;
 
+use crate::cmd::libraries::library_overview;
+use crate::cmd::mesh::{measure_mesh, render_geometry_stats};
+use crate::cmd::reference_geometry::reference_geometry_list;
+use crate::cmd::render::render_policy;
+use crate::cmd::sweep::run_sweep;
+use crate::cmd::tool_permissions::authorize_tool;
+use crate::cmd::{EditorState, OpenScadBinaryState};
 use crate::create_new_window_with_launch_intent;
+use crate::history::HistoryState;
+use crate::locate::plain_path;
+use crate::measure::{Axis, Probe};
+use crate::pending_edits::{apply_replacements, numbered_lines, replace_lines, Replacement};
+use crate::project_files::is_project_file;
+use crate::safe_mode::is_escaping_path;
+use crate::settings::{update_settings, SettingsState};
+use crate::types::Diagnostic;
 
 const MCP_DEFAULT_PORT: u16 = 32123;
+const MAX_TOOL_TIMEOUT_SECS: u64 = 600;
+
+/// Default timeouts of the tools the frontend runs, for MCP clients and the
+/// in-app agent alike. The frontend reads the same file. Render-backed tools
+/// get enough headroom for the 120s native render timeout.
+const TOOL_TIMEOUTS_JSON: &str = include_str!("../tool-timeouts.json");
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolTimeoutDefaults {
+    /// Timeout of tools not listed in `tools`
+    default_secs: u64,
+    tools: BTreeMap<String, u64>,
+}
+
+fn tool_timeout_defaults() -> &'static ToolTimeoutDefaults {
+    static DEFAULTS: OnceLock<ToolTimeoutDefaults> = OnceLock::new();
+    DEFAULTS.get_or_init(|| {
+        serde_json::from_str(TOOL_TIMEOUTS_JSON).expect("tool-timeouts.json is malformed")
+    })
+}
 
 // ── Public status types ──────────────────────────────────────────────────────
 
@@ -71,6 +108,35 @@ pub struct McpToolResponse {
     pub content: Vec<McpContentItem>,
     #[serde(default, skip_serializing_if = "is_false")]
     pub is_error: bool,
+    /// Optional machine-readable result supplied by the tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_id: Option<String>,
+}
+
+/// Structured counterpart of a tool result, returned next to the text summary
+/// so agents don't have to parse prose.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct StructuredToolResult {
+    status: &'static str,
+    summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+    diagnostics: Vec<Diagnostic>,
+    checkpoint_id: Option<String>,
+}
+
+/// Emitted after an MCP tool wrote a file into the workspace
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceFileWritten {
+    window_id: String,
+    file_path: String,
+    created: bool,
 }
 
 // ── Workspace / session types ─────────────────────────────────────────────────
@@ -263,6 +329,7 @@ fn text_tool_response(message: impl Into<String>, is_error: bool) -> McpToolResp
             text: message.into(),
         }],
         is_error,
+        ..Default::default()
     }
 }
 
@@ -274,7 +341,7 @@ fn normalize_workspace_root(path: &str) -> Option<String> {
 
     fs::canonicalize(trimmed)
         .ok()
-        .and_then(|resolved| resolved.into_os_string().into_string().ok())
+        .and_then(|resolved| plain_path(resolved).into_os_string().into_string().ok())
 }
 
 fn remove_pending(
@@ -439,6 +506,29 @@ fn require_bound_window_id(
     }
 }
 
+fn default_tool_timeout_secs(tool_name: &str) -> u64 {
+    let defaults = tool_timeout_defaults();
+    defaults
+        .tools
+        .get(tool_name)
+        .copied()
+        .unwrap_or(defaults.default_secs)
+}
+
+fn resolve_tool_timeout(overrides: &BTreeMap<String, u64>, tool_name: &str) -> Duration {
+    let secs = overrides
+        .get(tool_name)
+        .copied()
+        .unwrap_or_else(|| default_tool_timeout_secs(tool_name));
+    Duration::from_secs(secs.clamp(1, MAX_TOOL_TIMEOUT_SECS))
+}
+
+fn tool_timeout(app: &AppHandle, tool_name: &str) -> Duration {
+    let settings = app.state::<SettingsState>();
+    let settings = settings.settings.lock().unwrap();
+    resolve_tool_timeout(&settings.tool_timeouts, tool_name)
+}
+
 fn call_frontend_tool(
     app: &AppHandle,
     inner: &Arc<Mutex<McpStateInner>>,
@@ -473,11 +563,24 @@ fn call_frontend_tool(
         ));
     }
 
-    match rx.recv_timeout(Duration::from_secs(30)) {
+    let timeout = tool_timeout(app, tool_name);
+    match rx.recv_timeout(timeout) {
         Ok(response) => Ok(response),
         Err(mpsc::RecvTimeoutError::Timeout) => {
             remove_pending(inner, &request_id);
-            Err("Timed out waiting for OpenSCAD Studio to complete the MCP tool request.".into())
+            // Let the window abandon the work; a late response is dropped.
+            let _ = window.emit("mcp:tool-cancelled", &request_id);
+            eprintln!(
+                "[mcp] Tool `{tool_name}` timed out after {}s",
+                timeout.as_secs()
+            );
+            Ok(text_tool_response(
+                format!(
+                    "`{tool_name}` timed out after {}s without a response from OpenSCAD Studio. The operation may still be running; check `get_diagnostics` before retrying.",
+                    timeout.as_secs()
+                ),
+                true,
+            ))
         }
         Err(mpsc::RecvTimeoutError::Disconnected) => {
             remove_pending(inner, &request_id);
@@ -813,165 +916,1082 @@ fn get_project_context_response(
     text_tool_response(parts.join("\n"), false)
 }
 
-// ── Convert McpToolResponse → rmcp CallToolResult ────────────────────────────
-
-fn mcp_response_to_call_tool_result(response: McpToolResponse) -> CallToolResult {
-    let content: Vec<Content> = response
-        .content
-        .into_iter()
-        .map(|item| match item {
-            McpContentItem::Text { text } => Content::text(text),
-            McpContentItem::Image { data, mime_type } => Content::image(data, mime_type),
-        })
-        .collect();
-
-    if response.is_error {
-        CallToolResult::error(content)
-    } else {
-        CallToolResult::success(content)
+/// Resolve a workspace-relative project file path, refusing anything that
+/// would land outside `workspace_root` (including through symlinked folders
+/// and links to missing files)
+fn resolve_workspace_file(workspace_root: &str, file_path: &str) -> Result<PathBuf, String> {
+    let file_path = file_path.trim();
+    if file_path.is_empty() || is_escaping_path(file_path) {
+        return Err(format!(
+            "`{file_path}` must be a relative path inside the workspace folder."
+        ));
+    }
+    if !is_project_file(file_path) {
+        return Err(format!(
+            "`{file_path}` is not a project file; only .scad, .h and .json files can be written."
+        ));
     }
-}
-
-// ── Tool parameter structs ────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
-pub struct GetOrCreateWorkspaceParams {
-    /// Absolute path to the folder to open as a workspace.
-    pub folder_path: String,
+    let root = fs::canonicalize(workspace_root)
+        .map_err(|e| format!("Workspace folder {workspace_root} is not accessible: {e}"))?;
+    let path = root.join(file_path);
+    // A link to a missing file would have the write land wherever it points
+    if path.is_symlink() && !path.exists() {
+        return Err(format!(
+            "`{file_path}` is a link to a file that doesn't exist."
+        ));
+    }
+    // The deepest existing ancestor decides where the write really lands.
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .and_then(|ancestor| fs::canonicalize(ancestor).ok());
+    match existing {
+        Some(existing) if existing.starts_with(&root) => Ok(path),
+        _ => Err(format!(
+            "`{file_path}` resolves outside the workspace folder."
+        )),
+    }
 }
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
-pub struct SetRenderTargetParams {
-    /// Workspace-relative path to the .scad file to use as render target.
-    pub file_path: String,
+/// A project file of the bound window's workspace that a tool writes
+struct WorkspaceFile {
+    window_id: String,
+    workspace_root: String,
+    path: PathBuf,
+    /// Workspace-relative, `/`-separated
+    file_path: String,
 }
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
-pub struct GetPreviewScreenshotParams {
-    /// View perspective: "front", "back", "left", "right", "top", "bottom", or "isometric"
-    pub view: String,
-    /// Camera azimuth angle in degrees
-    #[serde(default)]
-    pub azimuth: Option<f64>,
-    /// Camera elevation angle in degrees
-    #[serde(default)]
-    pub elevation: Option<f64>,
-}
+/// Resolve `file_path` in the workspace of the session's bound window
+fn bound_workspace_file(
+    inner: &Arc<Mutex<McpStateInner>>,
+    session_id: &str,
+    file_path: &str,
+) -> Result<WorkspaceFile, McpToolResponse> {
+    let (window_id, workspace_root) = {
+        let mut locked = inner.lock().unwrap();
+        let window_id = require_bound_window_id(&mut locked, session_id)?;
+        let root = locked
+            .workspaces
+            .get(&window_id)
+            .and_then(|workspace| workspace.descriptor.workspace_root.clone());
+        (window_id, root)
+    };
+    let Some(workspace_root) = workspace_root else {
+        return Err(text_tool_response(
+            "❌ The selected Studio window has no workspace folder. Call `get_or_create_workspace(folder_path)` with a folder first.",
+            true,
+        ));
+    };
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
-pub struct ExportFileParams {
-    /// Export format: stl, obj, amf, 3mf, svg, or dxf
-    pub format: String,
-    /// Absolute output path, or workspace-relative path when a workspace root is open
-    pub file_path: String,
+    let path = resolve_workspace_file(&workspace_root, file_path)
+        .map_err(|e| text_tool_response(format!("❌ {e}"), true))?;
+    let file_path = file_path.trim().replace('\\', "/");
+    if path.is_dir() {
+        return Err(text_tool_response(
+            format!("❌ `{file_path}` is a folder."),
+            true,
+        ));
+    }
+    Ok(WorkspaceFile {
+        window_id,
+        workspace_root,
+        path,
+        file_path,
+    })
 }
 
-// ── rmcp handler ──────────────────────────────────────────────────────────────
-
-#[derive(Clone)]
-struct OpenScadMcpHandler {
-    tool_router: ToolRouter<OpenScadMcpHandler>,
-    app: AppHandle,
-    shared_state: Arc<Mutex<McpStateInner>>,
-    session_id: String,
-}
+/// Write `content` over a workspace file whose content was `previous` (`None`
+/// for a new file), after recording `previous` as a checkpoint of that file.
+/// Returns the checkpoint id.
+fn write_workspace_contents(
+    app: &AppHandle,
+    file: &WorkspaceFile,
+    previous: Option<&str>,
+    content: &str,
+) -> Result<String, McpToolResponse> {
+    let WorkspaceFile {
+        window_id,
+        workspace_root,
+        path,
+        file_path,
+    } = file;
+
+    // A checkpoint of the written file itself, so restoring it puts that
+    // file back and leaves the editor's document alone
+    let checkpoint_id = app
+        .state::<HistoryState>()
+        .history
+        .lock()
+        .unwrap()
+        .create_file_checkpoint(
+            path,
+            previous.map(str::to_string),
+            format!("Before AI write to {file_path}"),
+        );
 
-impl Drop for OpenScadMcpHandler {
-    fn drop(&mut self) {
-        let mut inner = self.shared_state.lock().unwrap();
-        inner.sessions.remove(&self.session_id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            text_tool_response(format!("❌ Failed to create {file_path}: {e}"), true)
+        })?;
+    }
+    fs::write(path, content)
+        .map_err(|e| text_tool_response(format!("❌ Failed to write {file_path}: {e}"), true))?;
+
+    // Keep an open project model in step with the file on disk.
+    let editor = app.state::<EditorState>();
+    if let Some(project) = editor.project.lock().unwrap().as_mut() {
+        if fs::canonicalize(&project.root).ok() == fs::canonicalize(workspace_root).ok() {
+            project.insert_loaded(file_path, content.to_string());
+        }
     }
+
+    let _ = app.emit(
+        "mcp:file-written",
+        WorkspaceFileWritten {
+            window_id: window_id.clone(),
+            file_path: file_path.clone(),
+            created: previous.is_none(),
+        },
+    );
+    Ok(checkpoint_id)
 }
 
-impl OpenScadMcpHandler {
-    fn new(app: AppHandle, shared_state: Arc<Mutex<McpStateInner>>) -> Self {
-        let session_id = Uuid::new_v4().to_string();
-        {
-            let mut inner = shared_state.lock().unwrap();
-            inner.sessions.entry(session_id.clone()).or_default();
+/// Write `content` to a workspace file for `create_file`/`write_file`. The
+/// previous content is recorded as a checkpoint first; for a new file the
+/// checkpoint records that it didn't exist, so restoring it removes the file.
+fn write_workspace_file_response(
+    app: &AppHandle,
+    inner: &Arc<Mutex<McpStateInner>>,
+    session_id: &str,
+    params: WriteFileParams,
+    overwrite: bool,
+) -> McpToolResponse {
+    let file = match bound_workspace_file(inner, session_id, &params.file_path) {
+        Ok(file) => file,
+        Err(response) => return response,
+    };
+    let file_path = &file.file_path;
+    let previous = if file.path.exists() {
+        if !overwrite {
+            return text_tool_response(
+                format!(
+                    "❌ `{file_path}` already exists. Use `write_file` to replace its content."
+                ),
+                true,
+            );
         }
-        Self {
-            tool_router: Self::tool_router(),
-            app,
-            shared_state,
-            session_id,
+        // Its content goes in the checkpoint, so a file that can't be read
+        // as text isn't replaced
+        match fs::read_to_string(&file.path) {
+            Ok(previous) => Some(previous),
+            Err(e) => {
+                return text_tool_response(
+                    format!("❌ Failed to read {file_path} before replacing it: {e}"),
+                    true,
+                )
+            }
         }
-    }
+    } else {
+        None
+    };
 
-    async fn call_frontend(
-        &self,
-        tool_name: &str,
-        arguments: Value,
-    ) -> Result<CallToolResult, McpError> {
-        let window_id = {
-            let mut locked = self.shared_state.lock().unwrap();
-            match require_bound_window_id(&mut locked, &self.session_id) {
-                Ok(id) => id,
-                Err(resp) => return Ok(mcp_response_to_call_tool_result(resp)),
-            }
+    let checkpoint_id =
+        match write_workspace_contents(app, &file, previous.as_deref(), &params.content) {
+            Ok(id) => id,
+            Err(response) => return response,
         };
+    McpToolResponse {
+        content: vec![McpContentItem::Text {
+            text: if previous.is_none() {
+                format!("✅ Created {file_path}. It can now be pulled in with `include`/`use`.")
+            } else {
+                format!("✅ Wrote {file_path}.")
+            },
+        }],
+        checkpoint_id: Some(checkpoint_id),
+        ..Default::default()
+    }
+}
 
-        let app = self.app.clone();
-        let state = self.shared_state.clone();
-        let tool = tool_name.to_string();
+/// Text of a workspace file as it is on disk, numbered for `edit_lines`
+/// when asked
+fn workspace_code_response(
+    inner: &Arc<Mutex<McpStateInner>>,
+    session_id: &str,
+    params: GetCurrentCodeParams,
+) -> McpToolResponse {
+    let file = match bound_workspace_file(inner, session_id, &params.file_path) {
+        Ok(file) => file,
+        Err(response) => return response,
+    };
+    match fs::read_to_string(&file.path) {
+        Ok(code) if params.numbered => text_tool_response(numbered_lines(&code), false),
+        Ok(code) => text_tool_response(code, false),
+        Err(e) => text_tool_response(format!("❌ Failed to read {}: {e}", file.file_path), true),
+    }
+}
 
-        let result = tokio::task::spawn_blocking(move || {
-            call_frontend_tool(&app, &state, &window_id, &tool, arguments)
-        })
-        .await
-        .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+/// Replace the content of an existing workspace file with the result of
+/// `edit`, under one checkpoint. Nothing is written when `edit` fails.
+fn edit_workspace_file_response(
+    app: &AppHandle,
+    inner: &Arc<Mutex<McpStateInner>>,
+    session_id: &str,
+    file_path: &str,
+    edit: impl FnOnce(&str) -> Result<String, String>,
+    summary: &str,
+) -> McpToolResponse {
+    let file = match bound_workspace_file(inner, session_id, file_path) {
+        Ok(file) => file,
+        Err(response) => return response,
+    };
+    let file_path = &file.file_path;
+    let Ok(previous) = fs::read_to_string(&file.path) else {
+        return text_tool_response(
+            format!("❌ `{file_path}` does not exist. Use `create_file` to add it."),
+            true,
+        );
+    };
+    let content = match edit(&previous) {
+        Ok(content) => content,
+        Err(e) => {
+            return text_tool_response(format!("❌ Nothing was changed in {file_path}: {e}"), true)
+        }
+    };
 
-        Ok(mcp_response_to_call_tool_result(
-            result.unwrap_or_else(|e| text_tool_response(e, true)),
-        ))
+    let checkpoint_id = match write_workspace_contents(app, &file, Some(&previous), &content) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    McpToolResponse {
+        content: vec![McpContentItem::Text {
+            text: format!("✅ {summary} in {file_path}."),
+        }],
+        checkpoint_id: Some(checkpoint_id),
+        ..Default::default()
     }
 }
 
-#[tool_router]
-impl OpenScadMcpHandler {
-    #[tool(
-        description = "Ensure this MCP session is bound to the exact requested workspace folder by attaching to an already-open match or opening/initializing it in OpenSCAD Studio."
-    )]
-    async fn get_or_create_workspace(
-        &self,
-        Parameters(params): Parameters<GetOrCreateWorkspaceParams>,
-    ) -> Result<CallToolResult, McpError> {
-        let args = serde_json::json!({ "folder_path": params.folder_path });
-        let app = self.app.clone();
-        let state = self.shared_state.clone();
-        let session_id = self.session_id.clone();
+fn sweep_parameter_response(app: &AppHandle, params: SweepParameterParams) -> McpToolResponse {
+    let Some(binary_path) = app
+        .state::<OpenScadBinaryState>()
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+    else {
+        return text_tool_response(
+            "The native OpenSCAD renderer is not available in this Studio session.",
+            true,
+        );
+    };
+    let (code, working_dir) = {
+        let editor = app.state::<EditorState>();
+        let code = editor.current_code.lock().unwrap().clone();
+        let working_dir = editor.working_dir.lock().unwrap().clone();
+        (code, working_dir)
+    };
 
-        let result = tokio::task::spawn_blocking(move || {
-            get_or_create_workspace_response(&app, &state, &session_id, &args)
+    let sweep = match render_policy(app, &code, &None, &working_dir, &None).and_then(|policy| {
+        run_sweep(
+            &binary_path,
+            &code,
+            &params.name,
+            &params.values,
+            &policy,
+            params.tile_size,
+            None,
+        )
+    }) {
+        Ok(sweep) => sweep,
+        Err(e) => return text_tool_response(e, true),
+    };
+
+    let lines = sweep
+        .tiles
+        .iter()
+        .enumerate()
+        .map(|(index, tile)| match &tile.error {
+            None => format!("{}. {} = {}", index + 1, params.name, tile.value),
+            Some(error) => format!(
+                "{}. {} = {} (failed: {error})",
+                index + 1,
+                params.name,
+                tile.value
+            ),
         })
-        .await
-        .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+        .collect::<Vec<_>>()
+        .join("\n");
+    let summary = format!(
+        "Contact sheet with {} columns, tiles in order:\n{lines}",
+        sweep.columns
+    );
 
-        Ok(mcp_response_to_call_tool_result(result))
+    McpToolResponse {
+        content: vec![
+            McpContentItem::Text { text: summary },
+            McpContentItem::Image {
+                data: sweep.image.clone(),
+                mime_type: sweep.mime_type.clone(),
+            },
+        ],
+        is_error: sweep.tiles.iter().all(|tile| !tile.success),
+        data: serde_json::to_value(&sweep.tiles).ok(),
+        ..Default::default()
     }
+}
 
-    #[tool(
-        description = "Get the current OpenSCAD Studio render target and workspace summary for the selected workspace."
-    )]
-    async fn get_project_context(&self) -> Result<CallToolResult, McpError> {
-        let state = self.shared_state.clone();
-        let session_id = self.session_id.clone();
+fn analyze_geometry_response(app: &AppHandle) -> McpToolResponse {
+    let Some(binary_path) = app
+        .state::<OpenScadBinaryState>()
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+    else {
+        return text_tool_response(
+            "The native OpenSCAD renderer is not available in this Studio session.",
+            true,
+        );
+    };
+    let (code, working_dir) = {
+        let editor = app.state::<EditorState>();
+        let code = editor.current_code.lock().unwrap().clone();
+        let working_dir = editor.working_dir.lock().unwrap().clone();
+        (code, working_dir)
+    };
 
-        let result =
-            tokio::task::spawn_blocking(move || get_project_context_response(&state, &session_id))
-                .await
-                .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+    let stats = match render_policy(app, &code, &None, &working_dir, &None)
+        .and_then(|policy| render_geometry_stats(&binary_path, &code, &policy))
+    {
+        Ok(stats) => stats,
+        Err(e) => return text_tool_response(format!("❌ Geometry analysis failed: {e}"), true),
+    };
 
-        Ok(mcp_response_to_call_tool_result(result))
-    }
+    let [x, y, z] = stats.size;
+    let [min_x, min_y, min_z] = stats.bounding_box_min;
+    let [max_x, max_y, max_z] = stats.bounding_box_max;
+    let manifold = if stats.manifold {
+        "yes".to_string()
+    } else {
+        format!("no ({} non-manifold edges)", stats.non_manifold_edges)
+    };
+    let summary = format!(
+        "Size: {x:.3} x {y:.3} x {z:.3} mm\n\
+         Bounding box: [{min_x:.3}, {min_y:.3}, {min_z:.3}] to [{max_x:.3}, {max_y:.3}, {max_z:.3}]\n\
+         Volume: {:.3} mm³\n\
+         Surface area: {:.3} mm²\n\
+         Triangles: {}\n\
+         Manifold: {manifold}",
+        stats.volume, stats.surface_area, stats.triangle_count
+    );
 
-    #[tool(description = "Change which workspace-relative file Studio compiles and previews.")]
-    async fn set_render_target(
-        &self,
-        Parameters(params): Parameters<SetRenderTargetParams>,
-    ) -> Result<CallToolResult, McpError> {
-        let args = serde_json::json!({ "file_path": params.file_path });
-        self.call_frontend("set_render_target", args).await
+    McpToolResponse {
+        content: vec![McpContentItem::Text { text: summary }],
+        data: serde_json::to_value(&stats).ok(),
+        ..Default::default()
+    }
+}
+
+fn format_span([from, to]: [f64; 2]) -> String {
+    format!("{:.3} mm ({from:.3} to {to:.3})", to - from)
+}
+
+fn measure_response(app: &AppHandle, params: MeasureParams) -> McpToolResponse {
+    let Some(binary_path) = app
+        .state::<OpenScadBinaryState>()
+        .path
+        .lock()
+        .unwrap()
+        .clone()
+    else {
+        return text_tool_response(
+            "The native OpenSCAD renderer is not available in this Studio session.",
+            true,
+        );
+    };
+    let probes = match params
+        .probes
+        .unwrap_or_default()
+        .into_iter()
+        .map(|line| {
+            Ok(Probe {
+                axis: Axis::parse(&line.axis)?,
+                at: line.at,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()
+    {
+        Ok(probes) => probes,
+        Err(e) => return text_tool_response(format!("❌ {e}"), true),
+    };
+    let (code, working_dir) = {
+        let editor = app.state::<EditorState>();
+        let code = editor.current_code.lock().unwrap().clone();
+        let working_dir = editor.working_dir.lock().unwrap().clone();
+        (code, working_dir)
+    };
+
+    let report = match render_policy(app, &code, &None, &working_dir, &None)
+        .and_then(|policy| measure_mesh(&binary_path, &code, &policy, &probes))
+    {
+        Ok(report) => report,
+        Err(e) => return text_tool_response(format!("❌ Measurement failed: {e}"), true),
+    };
+
+    let [x, y, z] = report.stats.size;
+    let [min_x, min_y, min_z] = report.stats.bounding_box_min;
+    let [max_x, max_y, max_z] = report.stats.bounding_box_max;
+    let mut summary = format!(
+        "Size: X {x:.3} mm, Y {y:.3} mm, Z {z:.3} mm\n\
+         Bounding box: [{min_x:.3}, {min_y:.3}, {min_z:.3}] to [{max_x:.3}, {max_y:.3}, {max_z:.3}]"
+    );
+    for result in &report.probes {
+        let [first, second] = match result.axis {
+            Axis::X => ["y", "z"],
+            Axis::Y => ["x", "z"],
+            Axis::Z => ["x", "y"],
+        };
+        let axis = format!("{:?}", result.axis);
+        summary.push_str(&format!(
+            "\n\n{axis} line at {first}={}, {second}={}:",
+            result.at[0], result.at[1]
+        ));
+        if result.solid.is_empty() {
+            summary.push_str(" misses the model");
+            continue;
+        }
+        for span in &result.solid {
+            summary.push_str(&format!("\n- solid {}", format_span(*span)));
+        }
+        for gap in &result.gaps {
+            summary.push_str(&format!("\n- gap {}", format_span(*gap)));
+        }
+        if let Some(extent) = result.extent {
+            summary.push_str(&format!("\n- overall {extent:.3} mm"));
+        }
+    }
+
+    McpToolResponse {
+        content: vec![McpContentItem::Text { text: summary }],
+        data: serde_json::to_value(&report).ok(),
+        ..Default::default()
+    }
+}
+
+fn list_libraries_response(app: &AppHandle) -> McpToolResponse {
+    let overview = match library_overview(app) {
+        Ok(overview) => overview,
+        Err(e) => return text_tool_response(format!("❌ Failed to list libraries: {e}"), true),
+    };
+
+    let mut text = if overview.installed.is_empty() {
+        "No OpenSCAD libraries are installed. Only use built-in modules and functions.".to_string()
+    } else {
+        let mut text = "Installed OpenSCAD libraries (safe to include/use):".to_string();
+        for library in &overview.installed {
+            text.push_str(&format!("\n- {}", library.name));
+            if let Some(version) = &library.version {
+                text.push_str(&format!(" {version}"));
+            }
+            if let Some(usage) = &library.usage {
+                text.push_str(&format!(" — {usage}"));
+            }
+        }
+        text
+    };
+    let missing: Vec<&str> = overview
+        .available
+        .iter()
+        .filter(|library| !library.installed)
+        .map(|library| library.name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        text.push_str(&format!(
+            "\nNot installed (the user can install these from Studio): {}",
+            missing.join(", ")
+        ));
+    }
+
+    McpToolResponse {
+        content: vec![McpContentItem::Text { text }],
+        data: serde_json::to_value(&overview).ok(),
+        ..Default::default()
+    }
+}
+
+fn list_reference_geometry_response(app: &AppHandle) -> McpToolResponse {
+    let Some(root) = app
+        .state::<EditorState>()
+        .working_dir
+        .lock()
+        .unwrap()
+        .clone()
+    else {
+        return text_tool_response(
+            "No project folder is open, so there is no reference geometry to import.",
+            false,
+        );
+    };
+    let list = reference_geometry_list(std::path::Path::new(&root));
+    if list.files.is_empty() && list.unreadable.is_empty() {
+        return text_tool_response(
+            "The project has no STL, OFF, 3MF, SVG or DXF files to import.",
+            false,
+        );
+    }
+
+    let mut text =
+        "Reference geometry in the project (use these import() statements as is):".to_string();
+    for file in &list.files {
+        text.push_str(&format!("\n- {}", file.import_statement));
+        let mut details = Vec::new();
+        if let Some(encoding) = file.encoding {
+            details.push(format!("{encoding} STL"));
+        }
+        if let Some(count) = file.face_count {
+            details.push(format!("{count} faces"));
+        }
+        if let Some([x, y, z]) = file.size {
+            details.push(format!("{x:.2} × {y:.2} × {z:.2} mm"));
+        }
+        if let Some(units) = &file.units {
+            details.push(format!("units: {units}"));
+        }
+        if !details.is_empty() {
+            text.push_str(&format!(" — {}", details.join(", ")));
+        }
+        for warning in &file.warnings {
+            text.push_str(&format!("\n  Warning: {warning}"));
+        }
+    }
+    for file in &list.unreadable {
+        text.push_str(&format!(
+            "\n- {} cannot be imported: {}",
+            file.path, file.reason
+        ));
+    }
+
+    McpToolResponse {
+        content: vec![McpContentItem::Text { text }],
+        data: serde_json::to_value(&list).ok(),
+        ..Default::default()
+    }
+}
+
+fn search_docs_response(
+    query: &str,
+    results: Result<Vec<crate::docs_index::DocsSearchResult>, String>,
+) -> McpToolResponse {
+    let results = match results {
+        Ok(results) => results,
+        Err(e) => return text_tool_response(e, true),
+    };
+    if results.is_empty() {
+        return text_tool_response(
+            format!("No documentation sections matched \"{query}\"."),
+            false,
+        );
+    }
+
+    let text = results
+        .iter()
+        .map(|result| {
+            let entry = &result.entry;
+            match &entry.file {
+                Some(file) => format!(
+                    "## {} ({}, {file})\n{}",
+                    entry.title, entry.source, entry.content
+                ),
+                None => format!("## {} ({})\n{}", entry.title, entry.source, entry.content),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    text_tool_response(text, false)
+}
+
+fn search_snippets_response(query: &str, limit: Option<usize>) -> McpToolResponse {
+    let results = crate::snippets::search_snippets(query, limit.or(Some(3)));
+    if results.is_empty() {
+        return text_tool_response(format!("No snippets matched \"{query}\"."), false);
+    }
+
+    let text = results
+        .iter()
+        .map(|result| {
+            format!(
+                "## {} ({})\n{}\n\n```openscad\n{}```",
+                result.snippet.title,
+                result.snippet.id,
+                result.snippet.description,
+                result.snippet.code
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    McpToolResponse {
+        content: vec![McpContentItem::Text { text }],
+        data: serde_json::to_value(&results).ok(),
+        ..Default::default()
+    }
+}
+
+/// Parse a lowercase enum option such as `"high"` or `"deboss"`
+fn parse_enum_option<T: serde::de::DeserializeOwned>(
+    name: &str,
+    value: Option<String>,
+) -> Result<Option<T>, String> {
+    value
+        .map(|value| {
+            serde_json::from_value(Value::String(value.to_lowercase()))
+                .map_err(|_| format!("Invalid {name} \"{value}\""))
+        })
+        .transpose()
+}
+
+fn generate_qr_code_response(params: GenerateQrCodeParams) -> McpToolResponse {
+    let mut options = crate::qr::QrCodeOptions::default();
+    let parsed = parse_enum_option("ecc", params.ecc)
+        .and_then(|ecc| Ok((ecc, parse_enum_option("relief", params.relief)?)));
+    match parsed {
+        Ok((ecc, relief)) => {
+            options.ecc = ecc.unwrap_or(options.ecc);
+            options.relief = relief.unwrap_or(options.relief);
+        }
+        Err(e) => return text_tool_response(e, true),
+    }
+    if let Some(size) = params.size {
+        options.size = size;
+    }
+
+    match crate::qr::qr_code_snippet(&params.data, &options) {
+        Ok(snippet) => text_tool_response(
+            format!(
+                "QR code version {} ({}x{} modules). Insert this OpenSCAD code:\n\n{}",
+                snippet.version, snippet.modules, snippet.modules, snippet.code
+            ),
+            false,
+        ),
+        Err(e) => text_tool_response(format!("Failed to generate QR code: {e}"), true),
+    }
+}
+
+// ── Convert McpToolResponse → rmcp CallToolResult ────────────────────────────
+
+/// Drop the decorative status glyph (✅, ❌, ⚠️, …) that opens a tool result,
+/// which models tend to misread. The rest of the text is left as is.
+fn strip_status_emoji(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) if matches!(c as u32, 0x2600..=0x27BF | 0x1F300..=0x1FAFF) => chars
+            .as_str()
+            .trim_start_matches('\u{FE0F}')
+            .trim_start_matches(' ')
+            .to_string(),
+        _ => text.to_string(),
+    }
+}
+
+fn structured_tool_result(response: &McpToolResponse) -> StructuredToolResult {
+    let summary = response
+        .content
+        .iter()
+        .find_map(|item| match item {
+            McpContentItem::Text { text } => Some(strip_status_emoji(text)),
+            McpContentItem::Image { .. } => None,
+        })
+        .unwrap_or_default();
+
+    StructuredToolResult {
+        status: if response.is_error { "error" } else { "ok" },
+        summary,
+        data: response.data.clone(),
+        diagnostics: response.diagnostics.clone(),
+        checkpoint_id: response.checkpoint_id.clone(),
+    }
+}
+
+fn mcp_response_to_call_tool_result(response: McpToolResponse) -> CallToolResult {
+    let structured = serde_json::to_value(structured_tool_result(&response)).ok();
+    let mut content: Vec<Content> = response
+        .content
+        .into_iter()
+        .map(|item| match item {
+            McpContentItem::Text { text } => Content::text(strip_status_emoji(&text)),
+            McpContentItem::Image { data, mime_type } => Content::image(data, mime_type),
+        })
+        .collect();
+    if let Some(structured) = &structured {
+        content.push(Content::text(structured.to_string()));
+    }
+
+    let mut result = if response.is_error {
+        CallToolResult::error(content)
+    } else {
+        CallToolResult::success(content)
+    };
+    result.structured_content = structured;
+    result
+}
+
+// ── Tool parameter structs ────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetOrCreateWorkspaceParams {
+    /// Absolute path to the folder to open as a workspace.
+    pub folder_path: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetRenderTargetParams {
+    /// Workspace-relative path to the .scad file to use as render target.
+    pub file_path: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetPreviewScreenshotParams {
+    /// View perspective: "front", "back", "left", "right", "top", "bottom", or "isometric"
+    pub view: String,
+    /// Camera azimuth angle in degrees
+    #[serde(default)]
+    pub azimuth: Option<f64>,
+    /// Camera elevation angle in degrees
+    #[serde(default)]
+    pub elevation: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CaptureViewParams {
+    /// Named view: "front", "back", "left", "right", "top", "bottom", or "isometric"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view: Option<String>,
+    /// Camera azimuth angle in degrees (overrides `view`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub azimuth: Option<f64>,
+    /// Camera elevation angle in degrees (overrides `view`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevation: Option<f64>,
+    /// Explicit [x, y, z] camera direction from the model center (overrides everything else)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<[f64; 3]>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct MeasureProbeParams {
+    /// Axis the probe line runs along: "x", "y" or "z"
+    pub axis: String,
+    /// Coordinates of the line on the other two axes in x, y, z order: [y, z] for an x line, [x, z] for y, [x, y] for z
+    pub at: [f64; 2],
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct MeasureParams {
+    /// Lines to measure along, e.g. {"axis": "x", "at": [0, 5]} for the width at y=0, z=5
+    #[serde(default)]
+    pub probes: Option<Vec<MeasureProbeParams>>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CaptureViewsParams {
+    /// Up to 8 camera views to capture, in order
+    pub views: Vec<CaptureViewParams>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ExportFileParams {
+    /// Export format: stl, obj, amf, 3mf, off, wrl, pov, csg, svg, or dxf
+    pub format: String,
+    /// Absolute output path, or workspace-relative path when a workspace root is open
+    pub file_path: String,
+    /// Workspace-relative customizer parameter file (.json) to apply (`-p`);
+    /// needs `parameter_set`
+    #[serde(default)]
+    pub parameter_file: Option<String>,
+    /// Parameter set name inside `parameter_file` (`-P`)
+    #[serde(default)]
+    pub parameter_set: Option<String>,
+    /// STL only: decimate the exported mesh down to this many triangles
+    #[serde(default)]
+    pub target_triangles: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GenerateQrCodeParams {
+    /// Text or URL to encode
+    pub data: String,
+    /// Error correction level: low, medium (default), quartile or high
+    #[serde(default)]
+    pub ecc: Option<String>,
+    /// Symbol side length in mm (default 30)
+    #[serde(default)]
+    pub size: Option<f64>,
+    /// "emboss" (default) or "deboss"
+    #[serde(default)]
+    pub relief: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchDocsParams {
+    /// Free-text query, e.g. "rotate_extrude angle", "$preview" or "cuboid rounding"
+    pub query: String,
+    /// Only search one source: "OpenSCAD" or a library name such as "BOSL2"
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Maximum number of sections to return (default 5)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchSnippetsParams {
+    /// Free-text query, e.g. "chamfer", "thread" or "honeycomb"
+    pub query: String,
+    /// Maximum number of snippets to return (default 3)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WriteFileParams {
+    /// Workspace-relative path of a .scad, .h or .json file, e.g. "lib/bolts.scad"
+    pub file_path: String,
+    /// Complete file content
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ApplyEditsParams {
+    /// Workspace-relative path of the file to edit, e.g. "main.scad"
+    pub file_path: String,
+    /// Exact-string replacements, applied in order, each to the result of the previous one
+    pub edits: Vec<Replacement>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct EditLinesParams {
+    /// Workspace-relative path of the file to edit, e.g. "main.scad"
+    pub file_path: String,
+    /// First line to replace (1-based, as numbered by `get_current_code`)
+    pub start_line: usize,
+    /// Last line to replace, inclusive; `start_line - 1` inserts before `start_line`
+    pub end_line: usize,
+    /// Text to put in place of those lines
+    pub new_text: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetCurrentCodeParams {
+    /// Workspace-relative path of the file to read, e.g. "main.scad"
+    pub file_path: String,
+    /// Prefix each line with its line number, for `edit_lines`
+    #[serde(default)]
+    pub numbered: bool,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SweepParameterParams {
+    /// Name of a top-level variable in the current file, e.g. "fillet_radius"
+    pub name: String,
+    /// OpenSCAD literal values to try, e.g. ["1", "2", "4"] (at most 12)
+    pub values: Vec<String>,
+    /// Edge length of each preview tile in pixels (default 256)
+    #[serde(default)]
+    pub tile_size: Option<u32>,
+}
+
+// ── rmcp handler ──────────────────────────────────────────────────────────────
+
+#[derive(Clone)]
+struct OpenScadMcpHandler {
+    tool_router: ToolRouter<OpenScadMcpHandler>,
+    app: AppHandle,
+    shared_state: Arc<Mutex<McpStateInner>>,
+    session_id: String,
+}
+
+impl Drop for OpenScadMcpHandler {
+    fn drop(&mut self) {
+        let mut inner = self.shared_state.lock().unwrap();
+        inner.sessions.remove(&self.session_id);
+    }
+}
+
+impl OpenScadMcpHandler {
+    fn new(app: AppHandle, shared_state: Arc<Mutex<McpStateInner>>) -> Self {
+        let session_id = Uuid::new_v4().to_string();
+        {
+            let mut inner = shared_state.lock().unwrap();
+            inner.sessions.entry(session_id.clone()).or_default();
+        }
+        Self {
+            tool_router: Self::tool_router(),
+            app,
+            shared_state,
+            session_id,
+        }
+    }
+
+    /// Check the tool against the user's AI tool permissions, asking in the
+    /// bound window when it needs approval. `Some` is the result to return
+    /// instead of running the tool.
+    async fn denied(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Result<Option<CallToolResult>, McpError> {
+        let window_id = self
+            .shared_state
+            .lock()
+            .unwrap()
+            .sessions
+            .get(&self.session_id)
+            .and_then(|session| session.bound_window_id.clone());
+        let app = self.app.clone();
+        let tool = tool_name.to_string();
+
+        let result = tokio::task::spawn_blocking(move || {
+            authorize_tool(&app, window_id.as_deref(), &tool, &arguments)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        Ok(result
+            .err()
+            .map(|e| mcp_response_to_call_tool_result(text_tool_response(e, true))))
+    }
+
+    async fn call_frontend(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(denied) = self.denied(tool_name, arguments.clone()).await? {
+            return Ok(denied);
+        }
+        let window_id = {
+            let mut locked = self.shared_state.lock().unwrap();
+            match require_bound_window_id(&mut locked, &self.session_id) {
+                Ok(id) => id,
+                Err(resp) => return Ok(mcp_response_to_call_tool_result(resp)),
+            }
+        };
+
+        let app = self.app.clone();
+        let state = self.shared_state.clone();
+        let tool = tool_name.to_string();
+
+        let result = tokio::task::spawn_blocking(move || {
+            call_frontend_tool(&app, &state, &window_id, &tool, arguments)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        let response = result.unwrap_or_else(|e| text_tool_response(e, true));
+        Ok(mcp_response_to_call_tool_result(response))
+    }
+
+    async fn write_workspace_file(
+        &self,
+        params: WriteFileParams,
+        overwrite: bool,
+    ) -> Result<CallToolResult, McpError> {
+        let tool_name = if overwrite {
+            "write_file"
+        } else {
+            "create_file"
+        };
+        let arguments = serde_json::json!({ "file_path": params.file_path });
+        if let Some(denied) = self.denied(tool_name, arguments).await? {
+            return Ok(denied);
+        }
+        let app = self.app.clone();
+        let state = self.shared_state.clone();
+        let session_id = self.session_id.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            write_workspace_file_response(&app, &state, &session_id, params, overwrite)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        Ok(mcp_response_to_call_tool_result(result))
+    }
+
+    /// Edit an existing workspace file with `edit` after checking the tool's
+    /// permission for that file
+    async fn edit_workspace_file(
+        &self,
+        tool_name: &str,
+        file_path: String,
+        edit: impl FnOnce(&str) -> Result<String, String> + Send + 'static,
+        summary: String,
+    ) -> Result<CallToolResult, McpError> {
+        let arguments = serde_json::json!({ "file_path": file_path });
+        if let Some(denied) = self.denied(tool_name, arguments).await? {
+            return Ok(denied);
+        }
+        let app = self.app.clone();
+        let state = self.shared_state.clone();
+        let session_id = self.session_id.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            edit_workspace_file_response(&app, &state, &session_id, &file_path, edit, &summary)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        Ok(mcp_response_to_call_tool_result(result))
+    }
+}
+
+#[tool_router]
+impl OpenScadMcpHandler {
+    #[tool(
+        description = "Ensure this MCP session is bound to the exact requested workspace folder by attaching to an already-open match or opening/initializing it in OpenSCAD Studio."
+    )]
+    async fn get_or_create_workspace(
+        &self,
+        Parameters(params): Parameters<GetOrCreateWorkspaceParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let args = serde_json::json!({ "folder_path": params.folder_path });
+        let app = self.app.clone();
+        let state = self.shared_state.clone();
+        let session_id = self.session_id.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            get_or_create_workspace_response(&app, &state, &session_id, &args)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        Ok(mcp_response_to_call_tool_result(result))
+    }
+
+    #[tool(
+        description = "Get the current OpenSCAD Studio render target and workspace summary for the selected workspace."
+    )]
+    async fn get_project_context(&self) -> Result<CallToolResult, McpError> {
+        let state = self.shared_state.clone();
+        let session_id = self.session_id.clone();
+
+        let result =
+            tokio::task::spawn_blocking(move || get_project_context_response(&state, &session_id))
+                .await
+                .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        Ok(mcp_response_to_call_tool_result(result))
+    }
+
+    #[tool(description = "Change which workspace-relative file Studio compiles and previews.")]
+    async fn set_render_target(
+        &self,
+        Parameters(params): Parameters<SetRenderTargetParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let args = serde_json::json!({ "file_path": params.file_path });
+        self.call_frontend("set_render_target", args).await
     }
 
     #[tool(
@@ -1006,7 +2026,18 @@ impl OpenScadMcpHandler {
     }
 
     #[tool(
-        description = "Export the current render target to a file path on desktop. If export cannot proceed, the response explains how to verify the render target and diagnostics."
+        description = "Capture PNG screenshots of the latest settled render artifact from several camera views in one call. Each view is a named view, azimuth/elevation angles, or an explicit camera direction vector."
+    )]
+    async fn capture_views(
+        &self,
+        Parameters(params): Parameters<CaptureViewsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let args = serde_json::json!({ "views": params.views });
+        self.call_frontend("capture_views", args).await
+    }
+
+    #[tool(
+        description = "Export the current render target to a file path on desktop. Optionally apply a customizer parameter set from a project .json file via parameter_file and parameter_set, and reduce an STL export to target_triangles. If export cannot proceed, the response explains how to verify the render target and diagnostics."
     )]
     async fn export_file(
         &self,
@@ -1015,9 +2046,258 @@ impl OpenScadMcpHandler {
         let args = serde_json::json!({
             "format": params.format,
             "file_path": params.file_path,
+            "parameter_file": params.parameter_file,
+            "parameter_set": params.parameter_set,
+            "target_triangles": params.target_triangles,
         });
         self.call_frontend("export_file", args).await
     }
+
+    #[tool(
+        description = "Create a new file in the workspace folder, e.g. a library module to `include`/`use` from the render target. Fails if the file already exists."
+    )]
+    async fn create_file(
+        &self,
+        Parameters(params): Parameters<WriteFileParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.write_workspace_file(params, false).await
+    }
+
+    #[tool(
+        description = "Create or replace a file in the workspace folder with the given content. The previous content is kept as a checkpoint."
+    )]
+    async fn write_file(
+        &self,
+        Parameters(params): Parameters<WriteFileParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.write_workspace_file(params, true).await
+    }
+
+    #[tool(
+        description = "Apply several exact-string replacements to one workspace file as a single edit. They run in order, each on the result of the previous one; if any old_string does not appear exactly once, nothing is changed. The previous content is kept as one checkpoint."
+    )]
+    async fn apply_edits(
+        &self,
+        Parameters(params): Parameters<ApplyEditsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let summary = match params.edits.len() {
+            1 => "Applied 1 replacement".to_string(),
+            n => format!("Applied {n} replacements"),
+        };
+        let edits = params.edits;
+        self.edit_workspace_file(
+            "apply_edits",
+            params.file_path,
+            move |code| apply_replacements(code, &edits),
+            summary,
+        )
+        .await
+    }
+
+    #[tool(
+        description = "Get the content of a workspace file as it is on disk. Set numbered to prefix each line with its line number for edit_lines."
+    )]
+    async fn get_current_code(
+        &self,
+        Parameters(params): Parameters<GetCurrentCodeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let state = self.shared_state.clone();
+        let session_id = self.session_id.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            workspace_code_response(&state, &session_id, params)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        Ok(mcp_response_to_call_tool_result(result))
+    }
+
+    #[tool(
+        description = "Replace a range of lines in a workspace file, using the line numbers from get_current_code with numbered set. Set end_line to start_line - 1 to insert before start_line. Reliable when the same code appears several times. The previous content is kept as a checkpoint."
+    )]
+    async fn edit_lines(
+        &self,
+        Parameters(params): Parameters<EditLinesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let EditLinesParams {
+            file_path,
+            start_line,
+            end_line,
+            new_text,
+        } = params;
+        let summary = if end_line < start_line {
+            format!("Inserted text before line {start_line}")
+        } else {
+            format!("Replaced lines {start_line}-{end_line}")
+        };
+        self.edit_workspace_file(
+            "edit_lines",
+            file_path,
+            move |code| replace_lines(code, start_line, end_line, &new_text),
+            summary,
+        )
+        .await
+    }
+
+    #[tool(
+        description = "Render a small preview for each candidate value of a top-level variable in the current editor code and return them as one contact-sheet image (left to right, top to bottom in the given order)."
+    )]
+    async fn sweep_parameter(
+        &self,
+        Parameters(params): Parameters<SweepParameterParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(denied) = self
+            .denied(
+                "sweep_parameter",
+                serde_json::json!({ "name": params.name }),
+            )
+            .await?
+        {
+            return Ok(denied);
+        }
+        let app = self.app.clone();
+        let result = tokio::task::spawn_blocking(move || sweep_parameter_response(&app, params))
+            .await
+            .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        Ok(mcp_response_to_call_tool_result(result))
+    }
+
+    #[tool(
+        description = "Render the current editor code to a mesh and report its size, bounding box, volume, surface area, triangle count and whether it is manifold. Use this to verify dimensional requirements."
+    )]
+    async fn analyze_geometry(&self) -> Result<CallToolResult, McpError> {
+        if let Some(denied) = self
+            .denied("analyze_geometry", serde_json::json!({}))
+            .await?
+        {
+            return Ok(denied);
+        }
+        let app = self.app.clone();
+        let result = tokio::task::spawn_blocking(move || analyze_geometry_response(&app))
+            .await
+            .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        Ok(mcp_response_to_call_tool_result(result))
+    }
+
+    #[tool(
+        description = "Render the current editor code to a mesh and measure it: overall size and bounding box, plus, for each probe line parallel to an axis, the solid spans (widths, wall thicknesses) and gaps (holes, slots) it passes through. Use this to verify requested dimensions numerically instead of judging from screenshots."
+    )]
+    async fn measure(
+        &self,
+        Parameters(params): Parameters<MeasureParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(denied) = self.denied("measure", serde_json::json!({})).await? {
+            return Ok(denied);
+        }
+        let app = self.app.clone();
+        let result = tokio::task::spawn_blocking(move || measure_response(&app, params))
+            .await
+            .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        Ok(mcp_response_to_call_tool_result(result))
+    }
+
+    #[tool(
+        description = "List the OpenSCAD libraries installed in the user's library folder, with versions and the include/use statement for each. Check this before writing code that depends on a library such as BOSL2."
+    )]
+    async fn list_libraries(&self) -> Result<CallToolResult, McpError> {
+        if let Some(denied) = self.denied("list_libraries", serde_json::json!({})).await? {
+            return Ok(denied);
+        }
+        Ok(mcp_response_to_call_tool_result(list_libraries_response(
+            &self.app,
+        )))
+    }
+
+    #[tool(
+        description = "List the meshes and drawings (STL, OFF, 3MF, SVG, DXF) in the project with their size, units and the import() statement that loads each at the right scale. Check this before writing import() calls."
+    )]
+    async fn list_reference_geometry(&self) -> Result<CallToolResult, McpError> {
+        if let Some(denied) = self
+            .denied("list_reference_geometry", serde_json::json!({}))
+            .await?
+        {
+            return Ok(denied);
+        }
+        let app = self.app.clone();
+        let result = tokio::task::spawn_blocking(move || list_reference_geometry_response(&app))
+            .await
+            .map_err(|e| McpError::internal_error(format!("{e}"), None))?;
+
+        Ok(mcp_response_to_call_tool_result(result))
+    }
+
+    #[tool(
+        description = "Generate self-contained OpenSCAD code for an embossed or debossed QR code plate encoding the given text or URL. Returns the code to insert; no library is required."
+    )]
+    async fn generate_qr_code(
+        &self,
+        Parameters(params): Parameters<GenerateQrCodeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(denied) = self
+            .denied(
+                "generate_qr_code",
+                serde_json::json!({ "data": params.data }),
+            )
+            .await?
+        {
+            return Ok(denied);
+        }
+        Ok(mcp_response_to_call_tool_result(generate_qr_code_response(
+            params,
+        )))
+    }
+
+    #[tool(
+        description = "Search the OpenSCAD language reference, the BOSL2 and MCAD cheat sheets and the installed libraries' documented modules and functions, returning the most relevant sections. Use this to ground answers about OpenSCAD syntax, built-ins and library APIs instead of guessing them."
+    )]
+    async fn search_docs(
+        &self,
+        Parameters(params): Parameters<SearchDocsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(denied) = self
+            .denied("search_docs", serde_json::json!({ "query": params.query }))
+            .await?
+        {
+            return Ok(denied);
+        }
+        let results = crate::cmd::docs_index::search_index(
+            self.app.clone(),
+            params.query.clone(),
+            params.source,
+            params.limit,
+        )
+        .await;
+        Ok(mcp_response_to_call_tool_result(search_docs_response(
+            &params.query,
+            results,
+        )))
+    }
+
+    #[tool(
+        description = "Search the curated OpenSCAD snippet library (chamfers, fillets, threads, honeycomb fill, screw holes, arrays, ...) and return matching modules with example usage. Adapt these proven idioms instead of writing them from scratch."
+    )]
+    async fn search_snippets(
+        &self,
+        Parameters(params): Parameters<SearchSnippetsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(denied) = self
+            .denied(
+                "search_snippets",
+                serde_json::json!({ "query": params.query }),
+            )
+            .await?
+        {
+            return Ok(denied);
+        }
+        Ok(mcp_response_to_call_tool_result(search_snippets_response(
+            &params.query,
+            params.limit,
+        )))
+    }
 }
 
 #[tool_handler]
@@ -1380,8 +2660,65 @@ pub async fn mcp_submit_tool_response(
         .map_err(|error| format!("Failed to send MCP tool response: {error}"))
 }
 
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolTimeout {
+    pub tool_name: String,
+    pub timeout_secs: u64,
+    pub default_secs: u64,
+    pub is_custom: bool,
+}
+
+/// List the effective timeout for every tool with a default of its own,
+/// whether MCP clients or the in-app agent call it
+#[tauri::command]
+pub fn get_tool_timeouts(settings: State<'_, SettingsState>) -> Result<Vec<ToolTimeout>, String> {
+    let settings = settings.settings.lock().unwrap();
+    Ok(tool_timeout_defaults()
+        .tools
+        .iter()
+        .map(|(tool_name, default_secs)| ToolTimeout {
+            tool_name: tool_name.clone(),
+            timeout_secs: resolve_tool_timeout(&settings.tool_timeouts, tool_name).as_secs(),
+            default_secs: *default_secs,
+            is_custom: settings.tool_timeouts.contains_key(tool_name),
+        })
+        .collect())
+}
+
+/// Override a tool's timeout in seconds (`None` restores the default)
+#[tauri::command]
+pub fn set_tool_timeout(
+    app: AppHandle,
+    tool_name: String,
+    timeout_secs: Option<u64>,
+) -> Result<(), String> {
+    if !tool_timeout_defaults().tools.contains_key(&tool_name) {
+        return Err(format!("Unknown tool: {tool_name}"));
+    }
+    if let Some(secs) = timeout_secs {
+        if secs == 0 || secs > MAX_TOOL_TIMEOUT_SECS {
+            return Err(format!(
+                "Tool timeout must be between 1 and {MAX_TOOL_TIMEOUT_SECS} seconds."
+            ));
+        }
+    }
+
+    update_settings(&app, |settings| {
+        match timeout_secs {
+            Some(secs) => settings.tool_timeouts.insert(tool_name, secs),
+            None => settings.tool_timeouts.remove(&tool_name),
+        };
+        Ok(())
+    })
+}
+
 pub fn update_window_focus(state: &McpServerState, window_id: &str, is_focused: bool) {
     let mut inner = state.inner.lock().unwrap();
+    update_window_focus_locked(&mut inner, window_id, is_focused);
+}
+
+fn update_window_focus_locked(inner: &mut McpStateInner, window_id: &str, is_focused: bool) {
     if is_focused {
         inner.next_focus_order += 1;
     }
@@ -1399,6 +2736,20 @@ pub fn remove_window(state: &McpServerState, window_id: &str) {
     remove_window_and_invalidate_sessions_locked(&mut inner, window_id);
 }
 
+/// The focused window, or else the one focused most recently. Windows
+/// hidden in background mode stay registered, so this still finds one
+/// when none is focused.
+pub fn most_recent_window(state: &McpServerState) -> Option<String> {
+    let inner = state.inner.lock().unwrap();
+    most_recent_window_locked(&inner)
+}
+
+fn most_recent_window_locked(inner: &McpStateInner) -> Option<String> {
+    ordered_registered_workspaces(inner)
+        .first()
+        .map(|(window_id, _)| (*window_id).clone())
+}
+
 // ── Tests ─────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -1465,6 +2816,30 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn most_recent_window_survives_hiding_every_window() {
+        let mut inner = make_inner();
+        for window_id in ["main", "window-a"] {
+            inner.workspaces.insert(
+                window_id.into(),
+                workspace(window_id, None, window_id, false),
+            );
+        }
+
+        update_window_focus_locked(&mut inner, "window-a", true);
+        update_window_focus_locked(&mut inner, "window-a", false);
+        update_window_focus_locked(&mut inner, "main", true);
+        // Background mode: main loses focus when hidden, then closes.
+        update_window_focus_locked(&mut inner, "main", false);
+        assert_eq!(most_recent_window_locked(&inner).as_deref(), Some("main"));
+
+        remove_window_and_invalidate_sessions_locked(&mut inner, "main");
+        assert_eq!(
+            most_recent_window_locked(&inner).as_deref(),
+            Some("window-a")
+        );
+    }
+
     #[test]
     fn remove_window_invalidates_bound_sessions() {
         let mut inner = make_inner();
@@ -1590,4 +2965,89 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn tool_timeouts_use_overrides_and_clamp() {
+        let mut overrides = BTreeMap::new();
+        assert_eq!(
+            resolve_tool_timeout(&overrides, "trigger_render"),
+            Duration::from_secs(150)
+        );
+        assert_eq!(
+            resolve_tool_timeout(&overrides, "unknown_tool"),
+            Duration::from_secs(tool_timeout_defaults().default_secs)
+        );
+
+        overrides.insert("trigger_render".to_string(), 10);
+        overrides.insert("export_file".to_string(), 10_000);
+        assert_eq!(
+            resolve_tool_timeout(&overrides, "trigger_render"),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            resolve_tool_timeout(&overrides, "export_file"),
+            Duration::from_secs(MAX_TOOL_TIMEOUT_SECS)
+        );
+    }
+
+    #[test]
+    fn tool_results_carry_structured_payload() {
+        let response = McpToolResponse {
+            content: vec![McpContentItem::Text {
+                text: "✅ Render succeeded\n⚠️ 1 warning".into(),
+            }],
+            data: Some(serde_json::json!({ "triangles": 12 })),
+            checkpoint_id: Some("cp-1".into()),
+            ..Default::default()
+        };
+
+        let result = mcp_response_to_call_tool_result(response);
+        let structured = result.structured_content.expect("structured content");
+        assert_eq!(structured["status"], "ok");
+        assert_eq!(structured["summary"], "Render succeeded\n⚠️ 1 warning");
+        assert_eq!(structured["data"]["triangles"], 12);
+        assert_eq!(structured["checkpoint_id"], "cp-1");
+        assert_eq!(result.content.len(), 2);
+
+        let error = mcp_response_to_call_tool_result(text_tool_response("❌ Failed", true));
+        assert_eq!(error.structured_content.unwrap()["status"], "error");
+    }
+
+    #[test]
+    fn status_glyph_is_only_stripped_from_the_start() {
+        assert_eq!(strip_status_emoji("✅ Found 1 snippet"), "Found 1 snippet");
+        let code = "module m() {\n    a = b * 2; // ★ → ✓\n}";
+        assert_eq!(strip_status_emoji(code), code);
+    }
+
+    #[test]
+    fn workspace_file_paths_stay_inside_the_root() {
+        let root = std::env::temp_dir()
+            .join("openscad-studio-mcp-tests")
+            .join(Uuid::new_v4().to_string());
+        fs::create_dir_all(root.join("lib")).unwrap();
+        let root_str = root.to_str().unwrap();
+
+        let resolved = resolve_workspace_file(root_str, "lib/bolts.scad").unwrap();
+        assert!(resolved.ends_with("lib/bolts.scad"));
+        assert!(resolve_workspace_file(root_str, "parts/new/lid.scad").is_ok());
+        assert!(resolve_workspace_file(root_str, "../outside.scad").is_err());
+        assert!(resolve_workspace_file(root_str, "/etc/passwd.scad").is_err());
+        assert!(resolve_workspace_file(root_str, "notes.txt").is_err());
+        assert!(resolve_workspace_file(root_str, "  ").is_err());
+
+        #[cfg(unix)]
+        {
+            let outside = root.with_extension("outside");
+            fs::create_dir_all(&outside).unwrap();
+            std::os::unix::fs::symlink(&outside, root.join("linked")).unwrap();
+            assert!(resolve_workspace_file(root_str, "linked/escape.scad").is_err());
+            std::os::unix::fs::symlink(outside.join("missing.scad"), root.join("dangling.scad"))
+                .unwrap();
+            assert!(resolve_workspace_file(root_str, "dangling.scad").is_err());
+            let _ = fs::remove_dir_all(outside);
+        }
+
+        let _ = fs::remove_dir_all(root);
+    }
 }
@@ -0,0 +1,236 @@
+/**
+ * Measurements along axis-aligned probe lines
+ *
+ * A probe is a line parallel to the X, Y or Z axis through a point given by
+ * the other two coordinates. Intersecting it with the rendered mesh gives
+ * the surface crossings along the line; the facing of each crossed triangle
+ * tells entries from exits, which yields the solid spans (part widths, wall
+ * thicknesses) and the gaps between them (holes, slots).
+ */
+use crate::geometry::Triangle;
+use serde::{Deserialize, Serialize};
+
+/// Crossings closer than this (in mm) are the same point on the surface
+const CROSSING_EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "x" => Ok(Axis::X),
+            "y" => Ok(Axis::Y),
+            "z" => Ok(Axis::Z),
+            _ => Err(format!("Unknown axis {name:?} (expected x, y or z)")),
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+
+    /// The other two axes in x, y, z order, as `Probe::at` lists them
+    fn others(self) -> [usize; 2] {
+        match self {
+            Axis::X => [1, 2],
+            Axis::Y => [0, 2],
+            Axis::Z => [0, 1],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Probe {
+    pub axis: Axis,
+    /// Coordinates on the other two axes in x, y, z order: (y, z) for an X
+    /// probe, (x, z) for Y, (x, y) for Z
+    pub at: [f64; 2],
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeResult {
+    pub axis: Axis,
+    pub at: [f64; 2],
+    /// Where the line crosses the surface, ascending
+    pub crossings: Vec<f64>,
+    /// Stretches of the line inside the model, as [start, end]
+    pub solid: Vec<[f64; 2]>,
+    /// Empty stretches between solid ones
+    pub gaps: Vec<[f64; 2]>,
+    /// Distance from the first to the last crossing
+    pub extent: Option<f64>,
+}
+
+/// Surface crossings of the probe line with their direction: -1 entering
+/// the model (outward normal against the axis), +1 leaving it
+fn signed_crossings(triangles: &[Triangle], probe: &Probe) -> Vec<(f64, f64)> {
+    let axis = probe.axis.index();
+    // (j, k) in cyclic order after the probe axis, so the 2D cross product
+    // below is the normal's component along the axis
+    let (j, k) = ((axis + 1) % 3, (axis + 2) % 3);
+    let mut point = [0.0; 3];
+    for (other, value) in probe.axis.others().into_iter().zip(probe.at) {
+        point[other] = value;
+    }
+    let (pj, pk) = (point[j], point[k]);
+
+    let mut crossings = Vec::new();
+    for [a, b, c] in triangles {
+        let normal = (b[j] - a[j]) * (c[k] - a[k]) - (b[k] - a[k]) * (c[j] - a[j]);
+        if normal.abs() < f64::EPSILON {
+            continue; // parallel to the probe
+        }
+        let wa = ((b[j] - pj) * (c[k] - pk) - (b[k] - pk) * (c[j] - pj)) / normal;
+        let wb = ((c[j] - pj) * (a[k] - pk) - (c[k] - pk) * (a[j] - pj)) / normal;
+        let wc = 1.0 - wa - wb;
+        if wa < -1e-9 || wb < -1e-9 || wc < -1e-9 {
+            continue;
+        }
+        let at = wa * a[axis] + wb * b[axis] + wc * c[axis];
+        crossings.push((at, normal.signum()));
+    }
+    crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+    // A line through a shared edge or vertex hits each adjacent triangle.
+    crossings.dedup_by(|(next, next_direction), (kept, kept_direction)| {
+        next_direction == kept_direction && (*next - *kept).abs() < CROSSING_EPSILON
+    });
+    crossings
+}
+
+/// Intersect a probe line with a closed mesh
+pub fn probe(triangles: &[Triangle], probe: &Probe) -> ProbeResult {
+    let crossings = signed_crossings(triangles, probe);
+
+    let mut solid: Vec<[f64; 2]> = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0.0;
+    for &(at, direction) in &crossings {
+        if direction < 0.0 {
+            if depth == 0 {
+                start = at;
+            }
+            depth += 1;
+        } else if depth > 0 {
+            depth -= 1;
+            if depth == 0 && at - start > CROSSING_EPSILON {
+                solid.push([start, at]);
+            }
+        }
+    }
+    let gaps = solid
+        .windows(2)
+        .map(|pair| [pair[0][1], pair[1][0]])
+        .filter(|[from, to]| to - from > CROSSING_EPSILON)
+        .collect();
+
+    let mut positions: Vec<f64> = crossings.iter().map(|&(at, _)| at).collect();
+    positions.dedup_by(|next, kept| (*next - *kept).abs() < CROSSING_EPSILON);
+    let extent = match (positions.first(), positions.last()) {
+        (Some(first), Some(last)) => Some(last - first),
+        _ => None,
+    };
+    ProbeResult {
+        axis: probe.axis,
+        at: probe.at,
+        crossings: positions,
+        solid,
+        gaps,
+        extent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Outward-facing triangles of an axis-aligned box
+    fn cuboid(min: [f64; 3], max: [f64; 3]) -> Vec<Triangle> {
+        let corner = |i: usize| {
+            [
+                if i & 1 == 0 { min[0] } else { max[0] },
+                if i & 2 == 0 { min[1] } else { max[1] },
+                if i & 4 == 0 { min[2] } else { max[2] },
+            ]
+        };
+        // Counter-clockwise seen from outside
+        let faces = [
+            [0, 2, 3, 1], // -z
+            [4, 5, 7, 6], // +z
+            [0, 1, 5, 4], // -y
+            [2, 6, 7, 3], // +y
+            [0, 4, 6, 2], // -x
+            [1, 3, 7, 5], // +x
+        ];
+        faces
+            .iter()
+            .flat_map(|[a, b, c, d]| {
+                [
+                    [corner(*a), corner(*b), corner(*c)],
+                    [corner(*a), corner(*c), corner(*d)],
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn measures_width_through_a_box() {
+        let mesh = cuboid([-20.0, 0.0, 0.0], [20.0, 10.0, 5.0]);
+        let result = probe(
+            &mesh,
+            &Probe {
+                axis: Axis::X,
+                at: [5.0, 2.5],
+            },
+        );
+        assert_eq!(result.solid, vec![[-20.0, 20.0]]);
+        assert_eq!(result.extent, Some(40.0));
+        assert!(result.gaps.is_empty());
+
+        // Through a shared face diagonal the hits are deduplicated.
+        let diagonal = probe(
+            &mesh,
+            &Probe {
+                axis: Axis::Z,
+                at: [0.0, 5.0],
+            },
+        );
+        assert_eq!(diagonal.crossings, vec![0.0, 5.0]);
+
+        let miss = probe(
+            &mesh,
+            &Probe {
+                axis: Axis::Y,
+                at: [50.0, 1.0],
+            },
+        );
+        assert!(miss.solid.is_empty() && miss.extent.is_none());
+    }
+
+    #[test]
+    fn reports_gaps_between_parts() {
+        let mut mesh = cuboid([0.0, 0.0, 0.0], [3.0, 10.0, 10.0]);
+        mesh.extend(cuboid([8.0, 0.0, 0.0], [10.0, 10.0, 10.0]));
+        let result = probe(
+            &mesh,
+            &Probe {
+                axis: Axis::parse("X").unwrap(),
+                at: [5.0, 5.0],
+            },
+        );
+        assert_eq!(result.solid, vec![[0.0, 3.0], [8.0, 10.0]]);
+        assert_eq!(result.gaps, vec![[3.0, 8.0]]);
+        assert_eq!(result.extent, Some(10.0));
+        assert!(Axis::parse("w").is_err());
+    }
+}
@@ -0,0 +1,356 @@
+use crate::cmd::actions::{action_title, effective_accelerator, layout_preset};
+use crate::cmd::session::recent_projects;
+use crate::session::{recent_menu_id, CLEAR_RECENTS_MENU_ID};
+use crate::settings::SettingsState;
+/**
+ * Native application menu
+ *
+ * The layout is declared as data so the menu can be rebuilt at runtime
+ * (e.g. after the user changes a keyboard shortcut). Every entry refers to
+ * an action ID from the action registry, which supplies its title and
+ * default accelerator.
+ */
+use serde::Deserialize;
+use std::sync::Mutex;
+use tauri::menu::{
+    CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, MenuItemKind, Submenu, SubmenuBuilder,
+};
+use tauri::{AppHandle, Manager, State, Wry};
+
+enum MenuEntry {
+    Action(&'static str),
+    /// Action rendered as a check item (e.g. the active layout preset).
+    Check(&'static str),
+    /// File → Open Recent, listing the recent projects
+    OpenRecent,
+    Separator,
+}
+
+use MenuEntry::{Action, Check, OpenRecent, Separator};
+
+// ============================================================================
+// Menu state
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+pub enum GeometryKind {
+    #[serde(rename = "2d")]
+    TwoD,
+    #[serde(rename = "3d")]
+    ThreeD,
+}
+
+/// Editor context reported by the focused window
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MenuContext {
+    pub has_document: bool,
+    pub is_dirty: bool,
+    pub has_dirty_files: bool,
+    /// Kind of the last successful render, if any
+    pub geometry: Option<GeometryKind>,
+    /// Active workspace layout preset (e.g. `ai-first`)
+    pub layout: Option<String>,
+    /// Whether edits are rendered automatically once the editor is idle
+    pub auto_render: bool,
+}
+
+/// Global menu state (managed by Tauri). `None` until the frontend reports,
+/// in which case every item stays enabled.
+#[derive(Default)]
+pub struct MenuState {
+    pub context: Mutex<Option<MenuContext>>,
+}
+
+pub(crate) fn is_enabled_in_context(id: &str, context: &MenuContext) -> bool {
+    match id {
+        "save" => context.is_dirty,
+        "save_all" => context.is_dirty || context.has_dirty_files,
+        "save_as" | "render" => context.has_document,
+        "export_stl" | "export_obj" | "export_amf" | "export_3mf" | "export_off" | "export_wrl"
+        | "export_pov" => context.geometry == Some(GeometryKind::ThreeD),
+        "export_svg" | "export_dxf" => context.geometry == Some(GeometryKind::TwoD),
+        // CSG is the evaluated tree, so it exists for either kind.
+        "export_png" | "export_csg" | "export_last" => context.geometry.is_some(),
+        _ => true,
+    }
+}
+
+pub(crate) fn is_checked_in_context(id: &str, context: &MenuContext) -> bool {
+    match id {
+        "toggle_auto_render" => !context.auto_render,
+        _ => layout_preset(id).is_some() && layout_preset(id) == context.layout.as_deref(),
+    }
+}
+
+// ============================================================================
+// Layout
+// ============================================================================
+
+const FILE_MENU: &[MenuEntry] = &[
+    Action("new"),
+    Action("new_window"),
+    Action("open"),
+    Action("open_folder"),
+    OpenRecent,
+    Separator,
+    Action("save"),
+    Action("save_as"),
+    Action("save_all"),
+    Separator,
+    Action("export_stl"),
+    Action("export_obj"),
+    Action("export_amf"),
+    Action("export_3mf"),
+    Action("export_off"),
+    Action("export_wrl"),
+    Action("export_pov"),
+    Action("export_png"),
+    Action("export_svg"),
+    Action("export_dxf"),
+    Action("export_csg"),
+    Separator,
+    Action("export_last"),
+];
+
+const DESIGN_MENU: &[MenuEntry] = &[
+    Action("render"),
+    Check("toggle_auto_render"),
+    Separator,
+    Action("generate_lithophane"),
+];
+
+const VIEW_MENU: &[MenuEntry] = &[
+    Check("layout_default"),
+    Check("layout_ai_first"),
+    Check("layout_customizer_first"),
+    Check("layout_wide_editor"),
+    Check("layout_wide_preview"),
+    Check("layout_minimal"),
+    Separator,
+    Action("ai_toggle_panel"),
+    Action("ai_new_conversation"),
+];
+
+/// Action IDs that appear in the native menu (and can carry accelerators)
+pub(crate) fn menu_action_ids() -> impl Iterator<Item = &'static str> {
+    [FILE_MENU, DESIGN_MENU, VIEW_MENU]
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| match entry {
+            Action(id) | Check(id) => Some(*id),
+            OpenRecent | Separator => None,
+        })
+}
+
+fn build_submenu(
+    app: &AppHandle,
+    title: &str,
+    entries: &[MenuEntry],
+) -> tauri::Result<Submenu<Wry>> {
+    let settings = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .clone();
+    let context = app.state::<MenuState>().context.lock().unwrap().clone();
+    let enabled = |id: &str| {
+        context
+            .as_ref()
+            .map(|context| is_enabled_in_context(id, context))
+            .unwrap_or(true)
+    };
+    let mut builder = SubmenuBuilder::new(app, title);
+
+    for entry in entries {
+        builder = match entry {
+            Action(id) => {
+                let mut item = MenuItemBuilder::with_id(*id, action_title(id).unwrap_or(id))
+                    .enabled(enabled(id));
+                if let Some(accelerator) = effective_accelerator(&settings, id) {
+                    item = item.accelerator(accelerator);
+                }
+                builder.item(&item.build(app)?)
+            }
+            Check(id) => {
+                let checked = context
+                    .as_ref()
+                    .map(|context| is_checked_in_context(id, context))
+                    .unwrap_or(false);
+                let mut item = CheckMenuItemBuilder::with_id(*id, action_title(id).unwrap_or(id))
+                    .enabled(enabled(id))
+                    .checked(checked);
+                if let Some(accelerator) = effective_accelerator(&settings, id) {
+                    item = item.accelerator(accelerator);
+                }
+                builder.item(&item.build(app)?)
+            }
+            OpenRecent => builder.item(&build_recent_submenu(app)?),
+            Separator => builder.separator(),
+        };
+    }
+
+    builder.build()
+}
+
+fn build_recent_submenu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let recents = recent_projects(app);
+    let mut builder = SubmenuBuilder::new(app, "Open Recent");
+    if recents.is_empty() {
+        builder = builder.item(
+            &MenuItemBuilder::with_id("no_recents", "No Recent Projects")
+                .enabled(false)
+                .build(app)?,
+        );
+    }
+    for (index, recent) in recents.iter().enumerate() {
+        builder = builder
+            .item(&MenuItemBuilder::with_id(recent_menu_id(index), &recent.name).build(app)?);
+    }
+    builder
+        .separator()
+        .item(
+            &MenuItemBuilder::with_id(CLEAR_RECENTS_MENU_ID, "Clear Recent")
+                .enabled(!recents.is_empty())
+                .build(app)?,
+        )
+        .build()
+}
+
+fn find_menu_item(app: &AppHandle, id: &str) -> Option<MenuItemKind<Wry>> {
+    let menu = app.menu()?;
+    menu.items().ok()?.into_iter().find_map(|item| match item {
+        MenuItemKind::Submenu(submenu) => submenu.get(id),
+        _ => None,
+    })
+}
+
+/// Push the current menu context onto the live menu items
+pub(crate) fn apply_menu_state(app: &AppHandle) {
+    let Some(context) = app.state::<MenuState>().context.lock().unwrap().clone() else {
+        return;
+    };
+
+    for entry in [FILE_MENU, DESIGN_MENU, VIEW_MENU].into_iter().flatten() {
+        let (id, is_check) = match entry {
+            Action(id) => (*id, false),
+            Check(id) => (*id, true),
+            OpenRecent | Separator => continue,
+        };
+        let Some(item) = find_menu_item(app, id) else {
+            continue;
+        };
+        let enabled = is_enabled_in_context(id, &context);
+        if is_check {
+            if let Some(check) = item.as_check_menuitem() {
+                let _ = check.set_enabled(enabled);
+                let _ = check.set_checked(is_checked_in_context(id, &context));
+            }
+        } else if let Some(menu_item) = item.as_menuitem() {
+            let _ = menu_item.set_enabled(enabled);
+        }
+    }
+    crate::tray::apply_tray_state(app);
+}
+
+/// Build the full application menu from the current settings
+pub(crate) fn build_app_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    // Create app menu (About, Hide, Quit, etc.)
+    let app_menu = SubmenuBuilder::new(app, "OpenSCAD Studio")
+        .about(None)
+        .separator()
+        .hide()
+        .hide_others()
+        .show_all()
+        .separator()
+        .quit()
+        .build()?;
+
+    let file_menu = build_submenu(app, "File", FILE_MENU)?;
+
+    // Create Edit menu
+    let edit_menu = SubmenuBuilder::new(app, "Edit")
+        .undo()
+        .redo()
+        .separator()
+        .cut()
+        .copy()
+        .paste()
+        .separator()
+        .select_all()
+        .build()?;
+
+    let design_menu = build_submenu(app, "Design", DESIGN_MENU)?;
+    let view_menu = build_submenu(app, "View", VIEW_MENU)?;
+
+    MenuBuilder::new(app)
+        .item(&app_menu)
+        .item(&file_menu)
+        .item(&edit_menu)
+        .item(&design_menu)
+        .item(&view_menu)
+        .build()
+}
+
+/// Rebuild and install the application menu (after settings changes)
+pub(crate) fn rebuild_app_menu(app: &AppHandle) -> Result<(), String> {
+    let menu = build_app_menu(app).map_err(|e| format!("Failed to build menu: {e}"))?;
+    app.set_menu(menu)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to install menu: {e}"))
+}
+
+/// Update menu enablement and check marks from the focused window's context
+#[tauri::command]
+pub fn update_menu_state(
+    app: AppHandle,
+    context: MenuContext,
+    state: State<'_, MenuState>,
+) -> Result<(), String> {
+    *state.context.lock().unwrap() = Some(context);
+    apply_menu_state(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_items_follow_rendered_geometry_kind() {
+        let context = MenuContext {
+            has_document: true,
+            geometry: Some(GeometryKind::TwoD),
+            ..Default::default()
+        };
+
+        assert!(is_enabled_in_context("export_svg", &context));
+        assert!(is_enabled_in_context("export_png", &context));
+        assert!(is_enabled_in_context("export_csg", &context));
+        assert!(!is_enabled_in_context("export_stl", &context));
+        assert!(!is_enabled_in_context("export_wrl", &context));
+        assert!(!is_enabled_in_context("save", &context));
+    }
+
+    #[test]
+    fn only_the_active_layout_is_checked() {
+        let context = MenuContext {
+            layout: Some("ai-first".into()),
+            ..Default::default()
+        };
+
+        assert!(is_checked_in_context("layout_ai_first", &context));
+        assert!(!is_checked_in_context("layout_default", &context));
+        assert!(!is_checked_in_context("render", &context));
+    }
+
+    #[test]
+    fn pause_auto_render_is_checked_while_auto_render_is_off() {
+        let mut context = MenuContext::default();
+        assert!(is_checked_in_context("toggle_auto_render", &context));
+
+        context.auto_render = true;
+        assert!(!is_checked_in_context("toggle_auto_render", &context));
+    }
+}
@@ -0,0 +1,357 @@
+use crate::geometry::{non_manifold_edges, Triangle};
+use crate::types::{Diagnostic, DiagnosticSeverity};
+/**
+ * 3D printability checks
+ *
+ * Inspects an exported mesh for problems slicers either reject or print
+ * badly: open/non-manifold edges, faces wound the wrong way, zero-area
+ * triangles, walls thinner than the smallest printable feature and steep
+ * overhangs. Results are plain warnings that the UI shows next to the
+ * OpenSCAD compiler diagnostics.
+ */
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Thin-wall detection casts a ray per triangle against every other one, so
+/// larger meshes skip it
+const MAX_THICKNESS_TRIANGLES: usize = 20_000;
+const DEGENERATE_AREA: f64 = 1e-9;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MeshCheckOptions {
+    /// Smallest wall thickness the printer can reproduce, in mm
+    pub min_feature_size: f64,
+    /// Faces pointing down more than this many degrees from vertical count
+    /// as overhangs
+    pub max_overhang_angle: f64,
+}
+
+impl Default for MeshCheckOptions {
+    fn default() -> Self {
+        Self {
+            min_feature_size: 0.8,
+            max_overhang_angle: 45.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MeshIssueKind {
+    NonManifoldEdges,
+    InvertedNormals,
+    DegenerateTriangles,
+    ThinWalls,
+    Overhangs,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeshWarning {
+    pub kind: MeshIssueKind,
+    /// Affected edges or triangles
+    pub count: usize,
+    pub message: String,
+}
+
+impl MeshWarning {
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let severity = match self.kind {
+            MeshIssueKind::Overhangs | MeshIssueKind::DegenerateTriangles => {
+                DiagnosticSeverity::Info
+            }
+            _ => DiagnosticSeverity::Warning,
+        };
+        Diagnostic {
+            severity,
+            line: None,
+            col: None,
+            end_col: None,
+            file: None,
+            category: None,
+            message: format!("Printability: {}", self.message),
+        }
+    }
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn length(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Unnormalized face normal (length is twice the area)
+fn face_normal([a, b, c]: &Triangle) -> [f64; 3] {
+    cross(sub(*b, *a), sub(*c, *a))
+}
+
+/// Directed edges used twice in the same direction. On a consistently wound
+/// closed mesh every edge is walked once each way, so these point at faces
+/// flipped relative to their neighbours.
+fn flipped_edges(triangles: &[Triangle]) -> usize {
+    let key = |vertex: &[f64; 3]| vertex.map(|coord| (coord as f32).to_bits());
+    let mut directed: HashMap<([u32; 3], [u32; 3]), usize> = HashMap::new();
+    for triangle in triangles {
+        for i in 0..3 {
+            let (a, b) = (key(&triangle[i]), key(&triangle[(i + 1) % 3]));
+            if a != b {
+                *directed.entry((a, b)).or_default() += 1;
+            }
+        }
+    }
+    directed.values().filter(|&&count| count > 1).count()
+}
+
+fn signed_volume(triangles: &[Triangle]) -> f64 {
+    triangles
+        .iter()
+        .map(|[a, b, c]| dot(*a, cross(*b, *c)) / 6.0)
+        .sum()
+}
+
+/// Distance along `direction` from `origin` to `triangle` (Möller–Trumbore)
+fn ray_hit(origin: [f64; 3], direction: [f64; 3], [a, b, c]: &Triangle) -> Option<f64> {
+    let edge1 = sub(*b, *a);
+    let edge2 = sub(*c, *a);
+    let p = cross(direction, edge2);
+    let det = dot(edge1, p);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let t_vec = sub(origin, *a);
+    let u = dot(t_vec, p) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = cross(t_vec, edge1);
+    let v = dot(direction, q) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = dot(edge2, q) / det;
+    (t > 1e-6).then_some(t)
+}
+
+/// Triangles whose inward ray hits the opposite surface closer than
+/// `min_thickness`
+fn thin_wall_triangles(triangles: &[Triangle], min_thickness: f64) -> usize {
+    triangles
+        .iter()
+        .enumerate()
+        .filter(|(index, triangle)| {
+            let normal = face_normal(triangle);
+            let normal_length = length(normal);
+            if normal_length / 2.0 < DEGENERATE_AREA {
+                return false;
+            }
+            let inward = normal.map(|n| -n / normal_length);
+            let [a, b, c] = triangle;
+            let centroid = [0, 1, 2].map(|axis| (a[axis] + b[axis] + c[axis]) / 3.0);
+            triangles.iter().enumerate().any(|(other_index, other)| {
+                other_index != *index
+                    && ray_hit(centroid, inward, other).is_some_and(|t| t < min_thickness)
+            })
+        })
+        .count()
+}
+
+/// Triangles facing down more steeply than `max_angle` degrees from
+/// vertical, ignoring faces resting on the build plate
+fn overhang_triangles(triangles: &[Triangle], max_angle: f64) -> usize {
+    let floor = triangles
+        .iter()
+        .flatten()
+        .map(|vertex| vertex[2])
+        .fold(f64::INFINITY, f64::min);
+    // A face overhangs when its normal points down more than
+    // (90° - max_angle) below the horizontal.
+    let threshold = -(90.0 - max_angle).to_radians().sin();
+    triangles
+        .iter()
+        .filter(|triangle| {
+            let normal = face_normal(triangle);
+            let normal_length = length(normal);
+            if normal_length / 2.0 < DEGENERATE_AREA {
+                return false;
+            }
+            let on_bed = triangle
+                .iter()
+                .all(|vertex| (vertex[2] - floor).abs() < 1e-6);
+            !on_bed && normal[2] / normal_length < threshold
+        })
+        .count()
+}
+
+/// Run every printability check on `triangles`
+pub fn check_mesh(triangles: &[Triangle], options: &MeshCheckOptions) -> Vec<MeshWarning> {
+    let mut warnings = Vec::new();
+    let mut warn = |kind, count: usize, message: String| {
+        if count > 0 {
+            warnings.push(MeshWarning {
+                kind,
+                count,
+                message,
+            });
+        }
+    };
+
+    let open_edges = non_manifold_edges(triangles);
+    warn(
+        MeshIssueKind::NonManifoldEdges,
+        open_edges,
+        format!("{open_edges} non-manifold edges; the mesh is not watertight and may not slice correctly"),
+    );
+
+    let flipped = flipped_edges(triangles);
+    if flipped > 0 {
+        warn(
+            MeshIssueKind::InvertedNormals,
+            flipped,
+            format!("{flipped} edges join faces with opposite winding; some normals are inverted"),
+        );
+    } else if open_edges == 0 && signed_volume(triangles) < 0.0 {
+        warn(
+            MeshIssueKind::InvertedNormals,
+            triangles.len(),
+            "All face normals point inward; the mesh is inside out".to_string(),
+        );
+    }
+
+    let degenerate = triangles
+        .iter()
+        .filter(|triangle| length(face_normal(triangle)) / 2.0 < DEGENERATE_AREA)
+        .count();
+    warn(
+        MeshIssueKind::DegenerateTriangles,
+        degenerate,
+        format!("{degenerate} zero-area triangles"),
+    );
+
+    if options.min_feature_size > 0.0 && triangles.len() <= MAX_THICKNESS_TRIANGLES {
+        let thin = thin_wall_triangles(triangles, options.min_feature_size);
+        warn(
+            MeshIssueKind::ThinWalls,
+            thin,
+            format!(
+                "{thin} faces belong to walls thinner than {} mm",
+                options.min_feature_size
+            ),
+        );
+    }
+
+    let overhangs = overhang_triangles(triangles, options.max_overhang_angle);
+    warn(
+        MeshIssueKind::Overhangs,
+        overhangs,
+        format!(
+            "{overhangs} faces overhang more than {}° and may need supports",
+            options.max_overhang_angle
+        ),
+    );
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Axis-aligned box as 12 outward-facing triangles
+    fn cuboid(size: [f64; 3]) -> Vec<Triangle> {
+        let [x, y, z] = size;
+        let v = |i: usize| {
+            [
+                if i & 1 != 0 { x } else { 0.0 },
+                if i & 2 != 0 { y } else { 0.0 },
+                if i & 4 != 0 { z } else { 0.0 },
+            ]
+        };
+        let quads = [
+            [0, 2, 3, 1], // bottom
+            [4, 5, 7, 6], // top
+            [0, 1, 5, 4], // front
+            [2, 6, 7, 3], // back
+            [0, 4, 6, 2], // left
+            [1, 3, 7, 5], // right
+        ];
+        quads
+            .iter()
+            .flat_map(|[a, b, c, d]| [[v(*a), v(*b), v(*c)], [v(*a), v(*c), v(*d)]])
+            .collect()
+    }
+
+    fn kinds(warnings: &[MeshWarning]) -> Vec<MeshIssueKind> {
+        warnings.iter().map(|warning| warning.kind).collect()
+    }
+
+    #[test]
+    fn solid_box_is_printable() {
+        let warnings = check_mesh(&cuboid([10.0, 10.0, 10.0]), &MeshCheckOptions::default());
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+
+    #[test]
+    fn reports_thin_walls_and_open_edges() {
+        let plate = cuboid([10.0, 10.0, 0.4]);
+        assert_eq!(
+            kinds(&check_mesh(&plate, &MeshCheckOptions::default())),
+            vec![MeshIssueKind::ThinWalls]
+        );
+
+        let open = &plate[..11];
+        assert!(kinds(&check_mesh(open, &MeshCheckOptions::default()))
+            .contains(&MeshIssueKind::NonManifoldEdges));
+    }
+
+    #[test]
+    fn reports_inverted_and_degenerate_faces() {
+        let mut inside_out: Vec<Triangle> = cuboid([10.0, 10.0, 10.0])
+            .into_iter()
+            .map(|[a, b, c]| [a, c, b])
+            .collect();
+        let warnings = check_mesh(&inside_out, &MeshCheckOptions::default());
+        assert!(kinds(&warnings).contains(&MeshIssueKind::InvertedNormals));
+
+        inside_out.push([[0.0; 3], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]]);
+        let warnings = check_mesh(&inside_out, &MeshCheckOptions::default());
+        assert!(kinds(&warnings).contains(&MeshIssueKind::DegenerateTriangles));
+        assert_eq!(
+            warnings[0].to_diagnostic().severity,
+            DiagnosticSeverity::Warning
+        );
+    }
+
+    #[test]
+    fn lifted_box_underside_overhangs() {
+        let lifted: Vec<Triangle> = cuboid([10.0, 10.0, 10.0])
+            .into_iter()
+            .chain(
+                cuboid([2.0, 2.0, 2.0])
+                    .into_iter()
+                    .map(|t| t.map(|v| [v[0] + 20.0, v[1], v[2] + 5.0])),
+            )
+            .collect();
+        let warnings = check_mesh(&lifted, &MeshCheckOptions::default());
+        let overhang = warnings
+            .iter()
+            .find(|warning| warning.kind == MeshIssueKind::Overhangs)
+            .expect("overhang warning");
+        // Only the floating cube's bottom face (two triangles)
+        assert_eq!(overhang.count, 2);
+    }
+}
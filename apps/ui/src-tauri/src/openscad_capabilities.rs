@@ -0,0 +1,314 @@
+/**
+ * What an OpenSCAD binary supports
+ *
+ * Builds differ in how (or whether) they select the Manifold backend, in
+ * which experimental features they accept and in which formats they export
+ * (2021.01 has no OBJ or POV-Ray). Asking `--help` isn't reliable,
+ * since older builds exit 0 for any option list that ends in `--help`, so
+ * each binary is probed once by compiling a one-cube model with the options
+ * in question and reading what OpenSCAD complains about.
+ */
+use crate::locate::{launch_command, temp_root_for, LaunchContext};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Experimental features worth knowing about, as passed to `--enable`
+pub const PROBED_FEATURES: &[&str] = &[
+    "lazy-union",
+    "roof",
+    "textmetrics",
+    "import-function",
+    "predictible-output",
+];
+
+/// Export formats missing from older releases (2021.01 writes neither)
+pub const PROBED_EXPORT_FORMATS: &[&str] = &["obj", "pov"];
+
+const PROBE_MODEL: &str = "cube(1);\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ManifoldSupport {
+    /// `--backend=manifold` (2024 and later)
+    Backend,
+    /// `--enable=manifold` (2023 snapshots)
+    Feature,
+    /// CGAL only
+    Unsupported,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenScadCapabilities {
+    pub manifold: ManifoldSupport,
+    /// `--backend=` is understood at all
+    pub backend_option: bool,
+    /// Probed experimental features and whether `--enable` accepts them
+    pub features: BTreeMap<String, bool>,
+    /// Probed export formats (by extension) and whether they can be written
+    pub export_formats: BTreeMap<String, bool>,
+}
+
+impl OpenScadCapabilities {
+    /// Assumed when probing fails, so renders keep the arguments they asked for
+    pub fn assumed() -> Self {
+        Self {
+            manifold: ManifoldSupport::Backend,
+            backend_option: true,
+            features: BTreeMap::new(),
+            export_formats: BTreeMap::new(),
+        }
+    }
+
+    /// Whether the binary can write `format` (an output extension). Formats
+    /// that weren't probed are assumed to work.
+    pub fn supports_export(&self, format: &str) -> bool {
+        self.export_formats
+            .get(&format.to_ascii_lowercase())
+            .copied()
+            .unwrap_or(true)
+    }
+}
+
+/// Outcome of one probe run
+pub struct ProbeRun<'a> {
+    pub success: bool,
+    pub stderr: &'a str,
+}
+
+fn rejects_option(stderr: &str) -> bool {
+    let stderr = stderr.to_ascii_lowercase();
+    [
+        "unrecognised option",
+        "unrecognized option",
+        "unknown option",
+    ]
+    .iter()
+    .any(|message| stderr.contains(message))
+}
+
+/// Whether the run complained about `subject` (a backend or feature name)
+fn rejects(stderr: &str, subject: &str) -> bool {
+    let quoted = [format!("'{subject}'"), format!("\"{subject}\"")];
+    stderr.lines().any(|line| {
+        let line = line.to_ascii_lowercase();
+        quoted.iter().any(|quoted| line.contains(quoted.as_str()))
+            && ["unknown", "invalid", "unsupported", "not supported"]
+                .iter()
+                .any(|word| line.contains(word))
+    })
+}
+
+/// Result of compiling with `--backend=manifold`: whether `--backend=` is a
+/// known option, and whether Manifold was accepted through it
+pub fn backend_probe(run: &ProbeRun) -> (bool, bool) {
+    let option_known = !rejects_option(run.stderr);
+    let accepted = option_known && run.success && !rejects(run.stderr, "manifold");
+    (option_known, accepted)
+}
+
+/// Whether each of `features` was accepted by a run enabling all of them
+pub fn feature_probe(run: &ProbeRun, features: &[&str]) -> BTreeMap<String, bool> {
+    let enable_known = !rejects_option(run.stderr);
+    features
+        .iter()
+        .map(|feature| {
+            let accepted = enable_known && run.success && !rejects(run.stderr, feature);
+            (feature.to_string(), accepted)
+        })
+        .collect()
+}
+
+/// Rewrite `--backend=` arguments into the form this binary understands,
+/// dropping them where it only has CGAL
+pub fn adapt_backend_args(args: &[String], capabilities: &OpenScadCapabilities) -> Vec<String> {
+    args.iter()
+        .filter_map(|arg| match arg.strip_prefix("--backend=") {
+            Some("manifold") => match capabilities.manifold {
+                ManifoldSupport::Backend => Some(arg.clone()),
+                ManifoldSupport::Feature => Some("--enable=manifold".to_string()),
+                ManifoldSupport::Unsupported => None,
+            },
+            Some(_) if !capabilities.backend_option => None,
+            _ => Some(arg.clone()),
+        })
+        .collect()
+}
+
+fn run_probe(
+    binary: &Path,
+    dir: &Path,
+    options: &[String],
+    output: &str,
+) -> Option<(bool, String)> {
+    let context = LaunchContext {
+        folders: vec![dir],
+        ..Default::default()
+    };
+    let output = launch_command(binary, &context)
+        .args(options)
+        .arg("-o")
+        .arg(dir.join(output))
+        .arg(dir.join("probe.scad"))
+        .output()
+        .ok()?;
+    let mut stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    stderr.push_str(&String::from_utf8_lossy(&output.stdout));
+    Some((output.status.success(), stderr))
+}
+
+fn probe_in(binary: &Path, dir: &Path) -> Result<OpenScadCapabilities, String> {
+    let run_to = |options: &[String], output: &str| {
+        run_probe(binary, dir, options, output)
+            .ok_or_else(|| format!("Failed to run OpenSCAD at {binary:?}"))
+    };
+    let run = |options: &[String]| run_to(options, "probe.stl");
+
+    let (success, stderr) = run(&["--backend=manifold".to_string()])?;
+    let (backend_option, manifold_backend) = backend_probe(&ProbeRun {
+        success,
+        stderr: &stderr,
+    });
+    let manifold = if manifold_backend {
+        ManifoldSupport::Backend
+    } else {
+        let (success, stderr) = run(&["--enable=manifold".to_string()])?;
+        let run = ProbeRun {
+            success,
+            stderr: &stderr,
+        };
+        if feature_probe(&run, &["manifold"])["manifold"] {
+            ManifoldSupport::Feature
+        } else {
+            ManifoldSupport::Unsupported
+        }
+    };
+
+    let options: Vec<String> = PROBED_FEATURES
+        .iter()
+        .map(|feature| format!("--enable={feature}"))
+        .collect();
+    let (success, stderr) = run(&options)?;
+    let run = ProbeRun {
+        success,
+        stderr: &stderr,
+    };
+
+    let features = feature_probe(&run, PROBED_FEATURES);
+
+    // Builds that can't write a format reject its extension ("Unknown suffix")
+    let mut export_formats = BTreeMap::new();
+    for format in PROBED_EXPORT_FORMATS {
+        let (success, _) = run_to(&[], &format!("probe.{format}"))?;
+        export_formats.insert(format.to_string(), success);
+    }
+
+    Ok(OpenScadCapabilities {
+        manifold,
+        backend_option,
+        features,
+        export_formats,
+    })
+}
+
+/// Compile the probe model with each option set and work out what `binary`
+/// supports
+pub fn probe(binary: &Path) -> Result<OpenScadCapabilities, String> {
+    let dir = temp_root_for(binary).join(format!("capability-probe-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+    fs::write(dir.join("probe.scad"), PROBE_MODEL)
+        .map_err(|e| format!("Failed to write probe model: {e}"))?;
+    let result = probe_in(binary, &dir);
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+fn cache() -> &'static Mutex<HashMap<PathBuf, OpenScadCapabilities>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, OpenScadCapabilities>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Capabilities of `binary`, probed on first use and remembered for the
+/// rest of the session
+pub fn capabilities(binary: &Path) -> OpenScadCapabilities {
+    if let Some(known) = cache().lock().unwrap().get(binary) {
+        return known.clone();
+    }
+    let probed = probe(binary).unwrap_or_else(|e| {
+        eprintln!("[capabilities] Probe failed, assuming defaults: {e}");
+        OpenScadCapabilities::assumed()
+    });
+    eprintln!("[capabilities] {binary:?}: {probed:?}");
+    cache()
+        .lock()
+        .unwrap()
+        .insert(binary.to_path_buf(), probed.clone());
+    probed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_probe_output() {
+        let old = ProbeRun {
+            success: false,
+            stderr: "unrecognised option '--backend=manifold'\n",
+        };
+        assert_eq!(backend_probe(&old), (false, false));
+
+        let current = ProbeRun {
+            success: true,
+            stderr: "Geometries in cache: 1\nTotal rendering time: 0:00:00.012\n",
+        };
+        assert_eq!(backend_probe(&current), (true, true));
+
+        let unknown_backend = ProbeRun {
+            success: false,
+            stderr: "ERROR: Unknown backend 'manifold'\n",
+        };
+        assert_eq!(backend_probe(&unknown_backend), (true, false));
+
+        let features = feature_probe(
+            &ProbeRun {
+                success: true,
+                stderr: "Ignoring request to enable unknown feature 'roof'.\n",
+            },
+            &["lazy-union", "roof"],
+        );
+        assert!(features["lazy-union"]);
+        assert!(!features["roof"]);
+    }
+
+    #[test]
+    fn adapts_backend_args() {
+        let args: Vec<String> = ["/input.scad", "--backend=manifold", "-o", "/output.off"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let mut capabilities = OpenScadCapabilities::assumed();
+        assert_eq!(adapt_backend_args(&args, &capabilities), args);
+
+        capabilities.manifold = ManifoldSupport::Feature;
+        capabilities.backend_option = false;
+        assert_eq!(
+            adapt_backend_args(&args, &capabilities)[1],
+            "--enable=manifold"
+        );
+
+        assert!(capabilities.supports_export("pov"));
+        capabilities.export_formats.insert("pov".to_string(), false);
+        assert!(!capabilities.supports_export("POV"));
+
+        capabilities.manifold = ManifoldSupport::Unsupported;
+        let cgal = vec![
+            "--backend=cgal".to_string(),
+            "--backend=manifold".to_string(),
+        ];
+        assert!(adapt_backend_args(&cgal, &capabilities).is_empty());
+    }
+}
@@ -0,0 +1,336 @@
+/**
+ * Downloadable OpenSCAD builds
+ *
+ * Besides the bundled binary and one found on PATH, the app can download
+ * the stable release or the latest nightly snapshot from files.openscad.org
+ * into its data folder. Each download gets its own folder holding the
+ * unpacked build and an `install.json` manifest recording the channel,
+ * version and where the binary sits inside the folder. Packages are only
+ * installed when they match the SHA-256 checksum published beside them.
+ */
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+pub const INSTALLS_DIR: &str = "openscad";
+pub const INSTALL_MANIFEST: &str = "install.json";
+pub const STABLE_VERSION: &str = "2021.01";
+pub const RELEASES_URL: &str = "https://files.openscad.org";
+pub const SNAPSHOTS_URL: &str = "https://files.openscad.org/snapshots";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReleaseChannel {
+    Stable,
+    Nightly,
+}
+
+impl ReleaseChannel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Nightly => "nightly",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    LinuxX86_64,
+    LinuxAarch64,
+    MacOs,
+    WindowsX86_64,
+}
+
+impl Platform {
+    /// The platform this build runs on, if OpenSCAD publishes builds for it
+    pub fn current() -> Option<Self> {
+        match (std::env::consts::OS, std::env::consts::ARCH) {
+            ("linux", "x86_64") => Some(Self::LinuxX86_64),
+            ("linux", "aarch64") => Some(Self::LinuxAarch64),
+            ("macos", _) => Some(Self::MacOs),
+            ("windows", "x86_64") => Some(Self::WindowsX86_64),
+            _ => None,
+        }
+    }
+
+    /// File name ending that identifies this platform's package
+    fn package_suffix(self) -> &'static str {
+        match self {
+            Self::LinuxX86_64 => "-x86_64.AppImage",
+            Self::LinuxAarch64 => "-aarch64.AppImage",
+            Self::MacOs => ".dmg",
+            Self::WindowsX86_64 => "-x86-64.zip",
+        }
+    }
+
+    pub fn package_format(self) -> PackageFormat {
+        match self {
+            Self::LinuxX86_64 | Self::LinuxAarch64 => PackageFormat::AppImage,
+            Self::MacOs => PackageFormat::Dmg,
+            Self::WindowsX86_64 => PackageFormat::Zip,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageFormat {
+    /// Self-contained executable, used as the binary as is
+    AppImage,
+    /// Disk image holding `OpenSCAD.app`
+    Dmg,
+    /// Archive with a single top-level folder holding `openscad.exe`
+    Zip,
+}
+
+/// A package to download for one channel and platform
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseAsset {
+    pub channel: ReleaseChannel,
+    pub version: String,
+    pub url: String,
+    pub format: PackageFormat,
+}
+
+/// The stable release package for `platform`
+pub fn stable_asset(platform: Platform) -> Option<ReleaseAsset> {
+    // 2021.01 predates the aarch64 AppImage
+    if platform == Platform::LinuxAarch64 {
+        return None;
+    }
+    Some(ReleaseAsset {
+        channel: ReleaseChannel::Stable,
+        version: STABLE_VERSION.to_string(),
+        url: format!(
+            "{RELEASES_URL}/OpenSCAD-{STABLE_VERSION}{}",
+            platform.package_suffix()
+        ),
+        format: platform.package_format(),
+    })
+}
+
+/// The newest snapshot for `platform` in the snapshots folder listing
+pub fn latest_snapshot_asset(listing: &str, platform: Platform) -> Option<ReleaseAsset> {
+    let suffix = platform.package_suffix();
+    listing
+        .split("href=\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .map(|href| href.rsplit('/').next().unwrap_or(href))
+        .filter_map(|name| {
+            let version = name.strip_prefix("OpenSCAD-")?.strip_suffix(suffix)?;
+            // Snapshot versions are dates, so the greatest sorts last
+            is_valid_version(version).then(|| (version.to_string(), name.to_string()))
+        })
+        .max()
+        .map(|(version, name)| ReleaseAsset {
+            channel: ReleaseChannel::Nightly,
+            version,
+            url: format!("{SNAPSHOTS_URL}/{name}"),
+            format: platform.package_format(),
+        })
+}
+
+/// Checksum file OpenSCAD publishes next to each package
+pub fn checksum_url(asset: &ReleaseAsset) -> String {
+    format!("{}.sha256", asset.url)
+}
+
+/// The lowercase SHA-256 digest a `sha256sum`-style checksum file starts with
+pub fn parse_sha256(text: &str) -> Option<String> {
+    let digest = text.split_whitespace().next()?;
+    (digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| digest.to_ascii_lowercase())
+}
+
+fn is_valid_version(version: &str) -> bool {
+    !version.is_empty()
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+}
+
+/// Folder name for a downloaded build
+pub fn install_id(channel: ReleaseChannel, version: &str) -> String {
+    format!("{}-{version}", channel.as_str())
+}
+
+/// Whether `id` names a single folder inside the installs folder
+pub fn is_valid_install_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallManifest {
+    pub id: String,
+    pub channel: ReleaseChannel,
+    pub version: String,
+    /// Binary path relative to the install folder, `/`-separated
+    pub binary: String,
+    /// Unix seconds
+    pub installed_at: u64,
+}
+
+impl InstallManifest {
+    pub fn binary_path(&self, installs_dir: &Path) -> PathBuf {
+        self.binary
+            .split('/')
+            .fold(installs_dir.join(&self.id), |path, segment| {
+                path.join(segment)
+            })
+    }
+}
+
+pub fn read_manifest(install_dir: &Path) -> Option<InstallManifest> {
+    let text = fs::read_to_string(install_dir.join(INSTALL_MANIFEST)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+pub fn write_manifest(install_dir: &Path, manifest: &InstallManifest) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize install manifest: {e}"))?;
+    fs::write(install_dir.join(INSTALL_MANIFEST), json)
+        .map_err(|e| format!("Failed to write install manifest: {e}"))
+}
+
+/// Downloaded builds whose manifest and binary are both present, newest
+/// install first
+pub fn list_installs(installs_dir: &Path) -> Vec<InstallManifest> {
+    let Ok(entries) = fs::read_dir(installs_dir) else {
+        return Vec::new();
+    };
+    let mut installs: Vec<InstallManifest> = entries
+        .flatten()
+        .filter_map(|entry| read_manifest(&entry.path()))
+        .filter(|manifest| {
+            is_valid_install_id(&manifest.id)
+                && is_relative_binary(&manifest.binary)
+                && manifest.binary_path(installs_dir).is_file()
+        })
+        .collect();
+    installs.sort_by(|a, b| {
+        b.installed_at
+            .cmp(&a.installed_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    installs
+}
+
+fn is_relative_binary(binary: &str) -> bool {
+    !binary.is_empty()
+        && Path::new(binary)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Locate the binary inside an unpacked package, relative to `install_dir`
+pub fn find_binary(install_dir: &Path, format: PackageFormat) -> Option<String> {
+    let candidate = match format {
+        PackageFormat::AppImage => "OpenSCAD.AppImage".to_string(),
+        PackageFormat::Dmg => "OpenSCAD.app/Contents/MacOS/OpenSCAD".to_string(),
+        PackageFormat::Zip => {
+            if install_dir.join("openscad.exe").is_file() {
+                return Some("openscad.exe".to_string());
+            }
+            let folder = fs::read_dir(install_dir)
+                .ok()?
+                .flatten()
+                .find(|entry| entry.path().join("openscad.exe").is_file())?;
+            format!("{}/openscad.exe", folder.file_name().to_string_lossy())
+        }
+    };
+    install_dir.join(&candidate).is_file().then_some(candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_newest_snapshot_for_platform() {
+        let listing = r#"
+            <a href="OpenSCAD-2025.01.02.ai12001-x86_64.AppImage">a</a>
+            <a href="OpenSCAD-2025.03.10.ai13250-x86_64.AppImage">b</a>
+            <a href="OpenSCAD-2025.03.10.ai13250-aarch64.AppImage">c</a>
+            <a href="OpenSCAD-2025.03.11.ai13300-x86-64.zip">d</a>
+            <a href="OpenSCAD-2025.03.11.ai13300-x86-64-Installer.exe">e</a>
+            <a href="2026/OpenSCAD-2026.01.01-x86_64.AppImage">f</a>
+        "#;
+        let linux = latest_snapshot_asset(listing, Platform::LinuxX86_64).unwrap();
+        assert_eq!(linux.version, "2026.01.01");
+        assert_eq!(
+            linux.url,
+            "https://files.openscad.org/snapshots/OpenSCAD-2026.01.01-x86_64.AppImage"
+        );
+
+        let windows = latest_snapshot_asset(listing, Platform::WindowsX86_64).unwrap();
+        assert_eq!(windows.version, "2025.03.11.ai13300");
+        assert_eq!(windows.format, PackageFormat::Zip);
+        assert!(latest_snapshot_asset(listing, Platform::MacOs).is_none());
+
+        assert_eq!(
+            stable_asset(Platform::MacOs).unwrap().url,
+            "https://files.openscad.org/OpenSCAD-2021.01.dmg"
+        );
+        assert!(stable_asset(Platform::LinuxAarch64).is_none());
+    }
+
+    #[test]
+    fn reads_published_checksums() {
+        let asset = stable_asset(Platform::MacOs).unwrap();
+        assert_eq!(
+            checksum_url(&asset),
+            "https://files.openscad.org/OpenSCAD-2021.01.dmg.sha256"
+        );
+
+        let digest = "A".repeat(64);
+        assert_eq!(
+            parse_sha256(&format!("{digest}  OpenSCAD-2021.01.dmg\n")),
+            Some("a".repeat(64))
+        );
+        assert_eq!(parse_sha256("abc123  OpenSCAD-2021.01.dmg"), None);
+        assert_eq!(parse_sha256(&"g".repeat(64)), None);
+        assert_eq!(parse_sha256(""), None);
+    }
+
+    #[test]
+    fn lists_complete_installs_newest_first() {
+        let dir =
+            std::env::temp_dir().join(format!("openscad-installs-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        for (id, installed_at, binary) in [
+            ("stable-2021.01", 10, "OpenSCAD.AppImage"),
+            ("nightly-2025.03.10", 20, "OpenSCAD.AppImage"),
+            ("nightly-broken", 30, "../outside"),
+        ] {
+            let install_dir = dir.join(id);
+            fs::create_dir_all(&install_dir).unwrap();
+            fs::write(install_dir.join("OpenSCAD.AppImage"), b"").unwrap();
+            let manifest = InstallManifest {
+                id: id.to_string(),
+                channel: ReleaseChannel::Stable,
+                version: id.to_string(),
+                binary: binary.to_string(),
+                installed_at,
+            };
+            write_manifest(&install_dir, &manifest).unwrap();
+        }
+        fs::create_dir_all(dir.join("nightly-unfinished")).unwrap();
+
+        let ids: Vec<String> = list_installs(&dir).into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, ["nightly-2025.03.10", "stable-2021.01"]);
+        assert_eq!(
+            find_binary(&dir.join("stable-2021.01"), PackageFormat::AppImage).as_deref(),
+            Some("OpenSCAD.AppImage")
+        );
+        assert!(!is_valid_install_id("../stable"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,934 @@
+/**
+ * SVG/DXF outline to polygon() conversion
+ *
+ * Parses SVG path data (and basic shapes) or DXF polylines, flattens curves
+ * and arcs to within a tolerance, and emits a single `polygon()` with one
+ * path per closed outline. OpenSCAD fills multi-path polygons with the
+ * even-odd rule, so letter counters and logo cut-outs come out as holes.
+ * SVG's Y axis points down and is flipped so the result reads upright.
+ */
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+const MAX_CURVE_SEGMENTS: usize = 256;
+const DXF_JOIN_EPSILON: f64 = 1e-6;
+
+pub type Point = [f64; 2];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OutlineOptions {
+    /// Maximum distance between a curve and its flattened segments
+    pub tolerance: f64,
+    /// Multiplier applied to source units (SVG user units or DXF drawing units)
+    pub scale: f64,
+    /// Center the outline on the origin instead of keeping source coordinates
+    pub center: bool,
+}
+
+impl Default for OutlineOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.1,
+            scale: 1.0,
+            center: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineConversion {
+    pub code: String,
+    pub path_count: usize,
+    pub point_count: usize,
+    pub width: f64,
+    pub height: f64,
+    /// Unsupported input that was skipped (e.g. SVG transforms)
+    pub warnings: Vec<String>,
+}
+
+#[derive(Default)]
+struct Outlines {
+    paths: Vec<Vec<Point>>,
+    warnings: Vec<String>,
+}
+
+impl Outlines {
+    fn push(&mut self, mut path: Vec<Point>) {
+        path.dedup_by(|a, b| distance(*a, *b) < DXF_JOIN_EPSILON);
+        if path.len() > 1 && distance(path[0], path[path.len() - 1]) < DXF_JOIN_EPSILON {
+            path.pop();
+        }
+        if path.len() >= 3 {
+            self.paths.push(path);
+        }
+    }
+
+    fn warn(&mut self, message: String) {
+        if !self.warnings.contains(&message) {
+            self.warnings.push(message);
+        }
+    }
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    (a[0] - b[0]).hypot(a[1] - b[1])
+}
+
+fn segments_for(deviation: f64, tolerance: f64) -> usize {
+    ((deviation / tolerance).sqrt().ceil() as usize).clamp(1, MAX_CURVE_SEGMENTS)
+}
+
+fn flatten_quadratic(out: &mut Vec<Point>, p0: Point, p1: Point, p2: Point, tolerance: f64) {
+    let dd = (p0[0] - 2.0 * p1[0] + p2[0]).hypot(p0[1] - 2.0 * p1[1] + p2[1]);
+    let n = segments_for(dd / 4.0, tolerance);
+    for i in 1..=n {
+        let t = i as f64 / n as f64;
+        let mt = 1.0 - t;
+        out.push([
+            mt * mt * p0[0] + 2.0 * mt * t * p1[0] + t * t * p2[0],
+            mt * mt * p0[1] + 2.0 * mt * t * p1[1] + t * t * p2[1],
+        ]);
+    }
+}
+
+fn flatten_cubic(out: &mut Vec<Point>, p0: Point, p1: Point, p2: Point, p3: Point, tolerance: f64) {
+    let dd1 = (p0[0] - 2.0 * p1[0] + p2[0]).hypot(p0[1] - 2.0 * p1[1] + p2[1]);
+    let dd2 = (p1[0] - 2.0 * p2[0] + p3[0]).hypot(p1[1] - 2.0 * p2[1] + p3[1]);
+    let n = segments_for(0.75 * dd1.max(dd2), tolerance);
+    for i in 1..=n {
+        let t = i as f64 / n as f64;
+        let mt = 1.0 - t;
+        let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+        out.push([
+            a * p0[0] + b * p1[0] + c * p2[0] + d * p3[0],
+            a * p0[1] + b * p1[1] + c * p2[1] + d * p3[1],
+        ]);
+    }
+}
+
+fn arc_segments(radius: f64, sweep: f64, tolerance: f64) -> usize {
+    if radius <= tolerance {
+        return 4;
+    }
+    let step = 2.0 * (1.0 - tolerance / radius).acos();
+    ((sweep.abs() / step).ceil() as usize).clamp(2, MAX_CURVE_SEGMENTS)
+}
+
+/// Flatten a circle/ellipse-section centered at `c`, appending points after `start`
+#[allow(clippy::too_many_arguments)]
+fn flatten_elliptic_arc(
+    out: &mut Vec<Point>,
+    c: Point,
+    rx: f64,
+    ry: f64,
+    phi: f64,
+    start: f64,
+    sweep: f64,
+    tolerance: f64,
+) {
+    let n = arc_segments(rx.max(ry), sweep, tolerance);
+    let (sin_phi, cos_phi) = phi.sin_cos();
+    for i in 1..=n {
+        let angle = start + sweep * i as f64 / n as f64;
+        let (x, y) = (rx * angle.cos(), ry * angle.sin());
+        out.push([
+            c[0] + cos_phi * x - sin_phi * y,
+            c[1] + sin_phi * x + cos_phi * y,
+        ]);
+    }
+}
+
+/// SVG endpoint arc (spec F.6.5) flattened from `p0` to `p1`
+#[allow(clippy::too_many_arguments)]
+fn flatten_svg_arc(
+    out: &mut Vec<Point>,
+    p0: Point,
+    mut rx: f64,
+    mut ry: f64,
+    rotation_degrees: f64,
+    large_arc: bool,
+    sweep_flag: bool,
+    p1: Point,
+    tolerance: f64,
+) {
+    rx = rx.abs();
+    ry = ry.abs();
+    if rx == 0.0 || ry == 0.0 || distance(p0, p1) == 0.0 {
+        out.push(p1);
+        return;
+    }
+    let phi = rotation_degrees.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+    let dx = (p0[0] - p1[0]) / 2.0;
+    let dy = (p0[1] - p1[1]) / 2.0;
+    let x1 = cos_phi * dx + sin_phi * dy;
+    let y1 = -sin_phi * dx + cos_phi * dy;
+
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut factor = (numerator / denominator).max(0.0).sqrt();
+    if large_arc == sweep_flag {
+        factor = -factor;
+    }
+    let cx1 = factor * rx * y1 / ry;
+    let cy1 = -factor * ry * x1 / rx;
+    let center = [
+        cos_phi * cx1 - sin_phi * cy1 + (p0[0] + p1[0]) / 2.0,
+        sin_phi * cx1 + cos_phi * cy1 + (p0[1] + p1[1]) / 2.0,
+    ];
+
+    let angle = |ux: f64, uy: f64| uy.atan2(ux);
+    let start = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let end = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+    let mut sweep = end - start;
+    if sweep_flag && sweep < 0.0 {
+        sweep += 2.0 * PI;
+    } else if !sweep_flag && sweep > 0.0 {
+        sweep -= 2.0 * PI;
+    }
+    flatten_elliptic_arc(out, center, rx, ry, phi, start, sweep, tolerance);
+    // Land exactly on the endpoint despite rounding.
+    if let Some(last) = out.last_mut() {
+        *last = p1;
+    }
+}
+
+// ============================================================================
+// SVG
+// ============================================================================
+
+struct PathTokens<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl PathTokens<'_> {
+    fn skip_separators(&mut self) {
+        while self.pos < self.bytes.len()
+            && (self.bytes[self.pos].is_ascii_whitespace() || self.bytes[self.pos] == b',')
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let byte = *self.bytes.get(self.pos)?;
+        if byte.is_ascii_alphabetic() && byte != b'e' && byte != b'E' {
+            self.pos += 1;
+            Some(byte)
+        } else {
+            None
+        }
+    }
+
+    fn has_number(&mut self) -> bool {
+        self.skip_separators();
+        matches!(
+            self.bytes.get(self.pos),
+            Some(b'0'..=b'9' | b'-' | b'+' | b'.')
+        )
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        self.skip_separators();
+        let start = self.pos;
+        let mut seen_dot = false;
+        let mut seen_exponent = false;
+        if matches!(self.bytes.get(self.pos), Some(b'-' | b'+')) {
+            self.pos += 1;
+        }
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            match byte {
+                b'0'..=b'9' => {}
+                b'.' if !seen_dot && !seen_exponent => seen_dot = true,
+                b'e' | b'E' if !seen_exponent => {
+                    seen_exponent = true;
+                    if matches!(self.bytes.get(self.pos + 1), Some(b'-' | b'+')) {
+                        self.pos += 1;
+                    }
+                }
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()
+            .and_then(|text| text.parse().ok())
+            .ok_or_else(|| format!("Invalid number in path data at offset {start}"))
+    }
+
+    /// Arc flags may be written without separators (`a1 1 0 011 1`)
+    fn flag(&mut self) -> Result<bool, String> {
+        self.skip_separators();
+        match self.bytes.get(self.pos) {
+            Some(b'0') => {
+                self.pos += 1;
+                Ok(false)
+            }
+            Some(b'1') => {
+                self.pos += 1;
+                Ok(true)
+            }
+            _ => Err(format!("Invalid arc flag at offset {}", self.pos)),
+        }
+    }
+
+    fn point(&mut self) -> Result<Point, String> {
+        Ok([self.number()?, self.number()?])
+    }
+}
+
+/// Flatten SVG path data (`d` attribute) into outlines
+fn parse_path_data(d: &str, tolerance: f64, outlines: &mut Outlines) -> Result<(), String> {
+    let mut tokens = PathTokens {
+        bytes: d.as_bytes(),
+        pos: 0,
+    };
+    let mut current: Vec<Point> = Vec::new();
+    let mut pen = [0.0, 0.0];
+    let mut start = [0.0, 0.0];
+    // Reflected control point for S/T, with the command family it came from.
+    let mut last_control: Option<(Point, bool)> = None;
+    let mut command = None;
+
+    loop {
+        if let Some(next) = tokens.command() {
+            command = Some(next);
+        } else if !tokens.has_number() {
+            break;
+        }
+        let Some(cmd) = command else {
+            return Err("Path data must start with a command".into());
+        };
+        let relative = cmd.is_ascii_lowercase();
+        let offset = |p: Point, pen: Point| {
+            if relative {
+                [p[0] + pen[0], p[1] + pen[1]]
+            } else {
+                p
+            }
+        };
+
+        match cmd.to_ascii_uppercase() {
+            b'M' => {
+                outlines.push(std::mem::take(&mut current));
+                pen = offset(tokens.point()?, pen);
+                start = pen;
+                current.push(pen);
+                // Further coordinate pairs are implicit line-tos.
+                command = Some(if relative { b'l' } else { b'L' });
+                last_control = None;
+            }
+            b'L' => {
+                pen = offset(tokens.point()?, pen);
+                current.push(pen);
+                last_control = None;
+            }
+            b'H' => {
+                let x = tokens.number()?;
+                pen[0] = if relative { pen[0] + x } else { x };
+                current.push(pen);
+                last_control = None;
+            }
+            b'V' => {
+                let y = tokens.number()?;
+                pen[1] = if relative { pen[1] + y } else { y };
+                current.push(pen);
+                last_control = None;
+            }
+            b'C' | b'S' => {
+                let c1 = if cmd.eq_ignore_ascii_case(&b'C') {
+                    offset(tokens.point()?, pen)
+                } else {
+                    match last_control {
+                        Some((control, true)) => {
+                            [2.0 * pen[0] - control[0], 2.0 * pen[1] - control[1]]
+                        }
+                        _ => pen,
+                    }
+                };
+                let c2 = offset(tokens.point()?, pen);
+                let end = offset(tokens.point()?, pen);
+                flatten_cubic(&mut current, pen, c1, c2, end, tolerance);
+                last_control = Some((c2, true));
+                pen = end;
+            }
+            b'Q' | b'T' => {
+                let control = if cmd.eq_ignore_ascii_case(&b'Q') {
+                    offset(tokens.point()?, pen)
+                } else {
+                    match last_control {
+                        Some((control, false)) => {
+                            [2.0 * pen[0] - control[0], 2.0 * pen[1] - control[1]]
+                        }
+                        _ => pen,
+                    }
+                };
+                let end = offset(tokens.point()?, pen);
+                flatten_quadratic(&mut current, pen, control, end, tolerance);
+                last_control = Some((control, false));
+                pen = end;
+            }
+            b'A' => {
+                let (rx, ry, rotation) = (tokens.number()?, tokens.number()?, tokens.number()?);
+                let (large_arc, sweep) = (tokens.flag()?, tokens.flag()?);
+                let end = offset(tokens.point()?, pen);
+                flatten_svg_arc(
+                    &mut current,
+                    pen,
+                    rx,
+                    ry,
+                    rotation,
+                    large_arc,
+                    sweep,
+                    end,
+                    tolerance,
+                );
+                last_control = None;
+                pen = end;
+            }
+            b'Z' => {
+                outlines.push(std::mem::take(&mut current));
+                pen = start;
+                current.push(pen);
+                last_control = None;
+                command = None;
+            }
+            other => return Err(format!("Unsupported path command '{}'", other as char)),
+        }
+    }
+    outlines.push(current);
+    Ok(())
+}
+
+/// Value of `name="..."` (or single-quoted) inside one tag's attribute text
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut search = tag;
+    while let Some(index) = search.find(name) {
+        let before = search[..index].chars().last();
+        let rest = search[index + name.len()..].trim_start();
+        if before.is_some_and(char::is_whitespace) {
+            if let Some(rest) = rest.strip_prefix('=') {
+                let rest = rest.trim_start();
+                let quote = rest.chars().next()?;
+                if quote == '"' || quote == '\'' {
+                    return rest[1..].split(quote).next();
+                }
+            }
+        }
+        search = &search[index + name.len()..];
+    }
+    None
+}
+
+fn number_attribute(tag: &str, name: &str) -> f64 {
+    attribute(tag, name)
+        .and_then(|value| value.trim().trim_end_matches("px").parse().ok())
+        .unwrap_or(0.0)
+}
+
+fn point_list(text: &str) -> Result<Vec<Point>, String> {
+    let mut tokens = PathTokens {
+        bytes: text.as_bytes(),
+        pos: 0,
+    };
+    let mut points = Vec::new();
+    while tokens.has_number() {
+        points.push(tokens.point()?);
+    }
+    Ok(points)
+}
+
+/// Outlines from an SVG document, or from bare path data
+fn parse_svg(svg: &str, tolerance: f64) -> Result<Outlines, String> {
+    let mut outlines = Outlines::default();
+    if !svg.trim_start().starts_with('<') {
+        parse_path_data(svg, tolerance, &mut outlines)?;
+        return Ok(outlines);
+    }
+
+    for raw_tag in svg.split('<').skip(1) {
+        let tag = raw_tag.split('>').next().unwrap_or_default();
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        if !matches!(
+            name,
+            "path" | "polygon" | "polyline" | "rect" | "circle" | "ellipse"
+        ) {
+            continue;
+        }
+        if attribute(tag, "transform").is_some() {
+            outlines.warn("SVG transform attributes are ignored".into());
+        }
+        match name {
+            "path" => {
+                if let Some(d) = attribute(tag, "d") {
+                    parse_path_data(d, tolerance, &mut outlines)?;
+                }
+            }
+            "polygon" | "polyline" => {
+                outlines.push(point_list(attribute(tag, "points").unwrap_or_default())?);
+            }
+            "rect" => {
+                if attribute(tag, "rx").is_some() || attribute(tag, "ry").is_some() {
+                    outlines.warn("Rounded rect corners are drawn square".into());
+                }
+                let (x, y) = (number_attribute(tag, "x"), number_attribute(tag, "y"));
+                let (w, h) = (
+                    number_attribute(tag, "width"),
+                    number_attribute(tag, "height"),
+                );
+                outlines.push(vec![[x, y], [x + w, y], [x + w, y + h], [x, y + h]]);
+            }
+            _ => {
+                let center = [number_attribute(tag, "cx"), number_attribute(tag, "cy")];
+                let (rx, ry) = if name == "circle" {
+                    let r = number_attribute(tag, "r");
+                    (r, r)
+                } else {
+                    (number_attribute(tag, "rx"), number_attribute(tag, "ry"))
+                };
+                let mut points = Vec::new();
+                flatten_elliptic_arc(&mut points, center, rx, ry, 0.0, 0.0, 2.0 * PI, tolerance);
+                outlines.push(points);
+            }
+        }
+    }
+    Ok(outlines)
+}
+
+// ============================================================================
+// DXF
+// ============================================================================
+
+#[derive(Default)]
+struct DxfEntity {
+    kind: String,
+    vertices: Vec<Point>,
+    x: Option<f64>,
+    closed: bool,
+    has_bulge: bool,
+    values: std::collections::HashMap<u32, f64>,
+}
+
+/// Outlines from the ENTITIES section of an ASCII DXF
+fn parse_dxf(dxf: &str, tolerance: f64) -> Result<Outlines, String> {
+    let lines: Vec<&str> = dxf.lines().map(str::trim).collect();
+    let mut pairs = Vec::with_capacity(lines.len() / 2);
+    for chunk in lines.chunks_exact(2) {
+        let code: u32 = chunk[0]
+            .parse()
+            .map_err(|_| format!("Invalid DXF group code \"{}\"", chunk[0]))?;
+        pairs.push((code, chunk[1]));
+    }
+
+    let mut entities: Vec<DxfEntity> = Vec::new();
+    let mut in_entities = false;
+    for (index, &(code, value)) in pairs.iter().enumerate() {
+        if code == 2 && value == "ENTITIES" && index > 0 && pairs[index - 1] == (0, "SECTION") {
+            in_entities = true;
+            continue;
+        }
+        if !in_entities {
+            continue;
+        }
+        if code == 0 {
+            if value == "ENDSEC" {
+                break;
+            }
+            entities.push(DxfEntity {
+                kind: value.to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(entity) = entities.last_mut() else {
+            continue;
+        };
+        let number = value.parse::<f64>().ok();
+        match (code, number) {
+            (10, Some(x)) => entity.x = Some(x),
+            (20, Some(y)) => {
+                if let Some(x) = entity.x.take() {
+                    entity.vertices.push([x, y]);
+                }
+            }
+            (42, Some(bulge)) if bulge != 0.0 => entity.has_bulge = true,
+            (70, Some(flags)) => entity.closed = (flags as i64) & 1 == 1,
+            (_, Some(number)) => {
+                entity.values.insert(code, number);
+            }
+            _ => {}
+        }
+    }
+
+    let mut outlines = Outlines::default();
+    let mut segments: Vec<[Point; 2]> = Vec::new();
+    let mut polyline: Option<Vec<Point>> = None;
+    for entity in &entities {
+        if entity.has_bulge {
+            outlines.warn("DXF polyline bulges (arc segments) are drawn straight".into());
+        }
+        match entity.kind.as_str() {
+            "LWPOLYLINE" => outlines.push(entity.vertices.clone()),
+            "POLYLINE" => polyline = Some(Vec::new()),
+            "VERTEX" => {
+                if let (Some(points), Some(&vertex)) = (polyline.as_mut(), entity.vertices.first())
+                {
+                    points.push(vertex);
+                }
+            }
+            "SEQEND" => outlines.push(polyline.take().unwrap_or_default()),
+            "LINE" => {
+                let start = entity.vertices.first().copied();
+                let end = entity
+                    .values
+                    .get(&11)
+                    .zip(entity.values.get(&21))
+                    .map(|(&x, &y)| [x, y]);
+                if let (Some(start), Some(end)) = (start, end) {
+                    segments.push([start, end]);
+                }
+            }
+            "CIRCLE" | "ARC" => {
+                let (Some(&center), Some(&radius)) =
+                    (entity.vertices.first(), entity.values.get(&40))
+                else {
+                    continue;
+                };
+                let (start, sweep) = if entity.kind == "ARC" {
+                    let start = entity.values.get(&50).copied().unwrap_or(0.0);
+                    let mut end = entity.values.get(&51).copied().unwrap_or(360.0);
+                    if end <= start {
+                        end += 360.0;
+                    }
+                    (start.to_radians(), (end - start).to_radians())
+                } else {
+                    (0.0, 2.0 * PI)
+                };
+                let mut points = vec![[
+                    center[0] + radius * start.cos(),
+                    center[1] + radius * start.sin(),
+                ]];
+                flatten_elliptic_arc(
+                    &mut points,
+                    center,
+                    radius,
+                    radius,
+                    0.0,
+                    start,
+                    sweep,
+                    tolerance,
+                );
+                if entity.kind == "ARC" {
+                    segments.extend(points.windows(2).map(|pair| [pair[0], pair[1]]));
+                } else {
+                    outlines.push(points);
+                }
+            }
+            "SPLINE" | "ELLIPSE" | "TEXT" | "MTEXT" | "HATCH" => {
+                outlines.warn(format!("DXF {} entities are not supported", entity.kind));
+            }
+            _ => {}
+        }
+    }
+
+    for chain in chain_segments(segments) {
+        outlines.push(chain);
+    }
+    Ok(outlines)
+}
+
+/// Join loose LINE/ARC segments end to end into paths
+fn chain_segments(mut segments: Vec<[Point; 2]>) -> Vec<Vec<Point>> {
+    let mut chains = Vec::new();
+    while let Some([first, second]) = segments.pop() {
+        let mut chain = vec![first, second];
+        loop {
+            let tail = chain[chain.len() - 1];
+            let next = segments.iter().position(|segment| {
+                distance(segment[0], tail) < DXF_JOIN_EPSILON
+                    || distance(segment[1], tail) < DXF_JOIN_EPSILON
+            });
+            let Some(index) = next else { break };
+            let segment = segments.swap_remove(index);
+            chain.push(if distance(segment[0], tail) < DXF_JOIN_EPSILON {
+                segment[1]
+            } else {
+                segment[0]
+            });
+        }
+        chains.push(chain);
+    }
+    chains
+}
+
+// ============================================================================
+// OpenSCAD output
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutlineFormat {
+    Svg,
+    Dxf,
+}
+
+impl OutlineFormat {
+    /// Guess from a file name, falling back to the content
+    pub fn detect(path: Option<&str>, content: &str) -> Self {
+        let extension = path
+            .and_then(|path| std::path::Path::new(path).extension())
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("dxf") => OutlineFormat::Dxf,
+            Some("svg") => OutlineFormat::Svg,
+            _ if content.contains("SECTION") && !content.trim_start().starts_with('<') => {
+                OutlineFormat::Dxf
+            }
+            _ => OutlineFormat::Svg,
+        }
+    }
+}
+
+fn format_coordinate(value: f64) -> String {
+    let text = format!("{value:.3}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// OpenSCAD identifier derived from a file name (`my-logo.svg` -> `my_logo`)
+fn identifier(name: &str) -> String {
+    let stem = std::path::Path::new(name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("outline");
+    let mut ident: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert_str(0, "outline_");
+    }
+    ident
+}
+
+/// Closed outlines of SVG or DXF text in drawing units with Y up (SVG is
+/// flipped), curves flattened to within `tolerance`, plus warnings about
+/// skipped input
+pub fn outline_paths(
+    content: &str,
+    format: OutlineFormat,
+    tolerance: f64,
+) -> Result<(Vec<Vec<Point>>, Vec<String>), String> {
+    let outlines = match format {
+        OutlineFormat::Svg => parse_svg(content, tolerance)?,
+        OutlineFormat::Dxf => parse_dxf(content, tolerance)?,
+    };
+    let flip = if format == OutlineFormat::Svg {
+        -1.0
+    } else {
+        1.0
+    };
+    let paths = outlines
+        .paths
+        .into_iter()
+        .map(|path| path.into_iter().map(|p| [p[0], p[1] * flip]).collect())
+        .collect();
+    Ok((paths, outlines.warnings))
+}
+
+/// Convert SVG or DXF text into a `polygon()` definition
+pub fn convert_outline(
+    content: &str,
+    format: OutlineFormat,
+    source_name: &str,
+    options: &OutlineOptions,
+) -> Result<OutlineConversion, String> {
+    if options.tolerance <= 0.0 || options.scale <= 0.0 {
+        return Err("Tolerance and scale must be positive".into());
+    }
+    let (paths, warnings) = outline_paths(content, format, options.tolerance / options.scale)?;
+    if paths.is_empty() {
+        return Err(format!("No closed outlines found in {source_name}"));
+    }
+
+    let mut paths: Vec<Vec<Point>> = paths
+        .iter()
+        .map(|path| {
+            path.iter()
+                .map(|p| [p[0] * options.scale, p[1] * options.scale])
+                .collect()
+        })
+        .collect();
+
+    let all = paths.iter().flatten();
+    let (mut min, mut max) = ([f64::MAX; 2], [f64::MIN; 2]);
+    for p in all {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(p[axis]);
+            max[axis] = max[axis].max(p[axis]);
+        }
+    }
+    if options.center {
+        let mid = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+        for p in paths.iter_mut().flatten() {
+            p[0] -= mid[0];
+            p[1] -= mid[1];
+        }
+    }
+
+    let name = identifier(source_name);
+    let point_count: usize = paths.iter().map(Vec::len).sum();
+    let (width, height) = (max[0] - min[0], max[1] - min[1]);
+
+    let mut code = format!(
+        "// Outline of {source_name}: {} paths, {point_count} points, {} x {}\n{name}_points = [\n",
+        paths.len(),
+        format_coordinate(width),
+        format_coordinate(height),
+    );
+    let mut index = 0;
+    let mut path_indices = Vec::with_capacity(paths.len());
+    for (path_number, path) in paths.iter().enumerate() {
+        let points: Vec<String> = path
+            .iter()
+            .map(|p| format!("[{},{}]", format_coordinate(p[0]), format_coordinate(p[1])))
+            .collect();
+        code.push_str("    ");
+        code.push_str(&points.join(","));
+        code.push_str(if path_number + 1 < paths.len() {
+            ",\n"
+        } else {
+            "\n"
+        });
+        path_indices.push(format!(
+            "[{}]",
+            (index..index + path.len())
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ));
+        index += path.len();
+    }
+    code.push_str(&format!(
+        "];\n{name}_paths = [\n    {}\n];\n\npolygon(points = {name}_points, paths = {name}_paths);\n",
+        path_indices.join(",\n    ")
+    ));
+
+    Ok(OutlineConversion {
+        code,
+        path_count: paths.len(),
+        point_count,
+        width,
+        height,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_svg_paths_and_shapes() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg">
+            <path d="M0,0 h10 v10 h-10 z m2 2 l6 0 0 6 -6 0 z"/>
+            <rect x="20" y="0" width="5" height="5" transform="rotate(10)"/>
+            <circle cx="40" cy="5" r="5"/>
+        </svg>"#;
+        let outlines = parse_svg(svg, 0.01).unwrap();
+        assert_eq!(outlines.paths.len(), 4);
+        assert_eq!(
+            outlines.paths[0],
+            vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]
+        );
+        assert_eq!(outlines.paths[1][0], [2.0, 2.0]);
+        assert_eq!(
+            outlines.warnings,
+            vec!["SVG transform attributes are ignored"]
+        );
+        // Circle points all lie on the radius.
+        assert!(outlines.paths[3]
+            .iter()
+            .all(|p| (distance(*p, [40.0, 5.0]) - 5.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn flattens_curves_within_tolerance() {
+        // Half circle of radius 10 as an arc with compact flags, closed by a line.
+        let outlines = parse_svg("M0 0A10 10 0 0010 10 10 10 0 0020 0z", 0.05).unwrap();
+        let center = [10.0, 0.0];
+        let path = &outlines.paths[0];
+        assert!(path.len() > 10);
+        assert!(path
+            .iter()
+            .all(|p| (distance(*p, center) - 10.0).abs() < 1e-6));
+
+        let outlines = parse_svg("M0 0 C0 10 10 10 10 0 S20 -10 20 0 Z", 0.01).unwrap();
+        let path = &outlines.paths[0];
+        assert!(path.contains(&[10.0, 0.0]));
+        assert!(path.iter().any(|p| p[1] > 7.0) && path.iter().any(|p| p[1] < -7.0));
+    }
+
+    #[test]
+    fn parses_dxf_polylines_and_chains_lines() {
+        let dxf = "0\nSECTION\n2\nENTITIES\n\
+                   0\nLWPOLYLINE\n70\n1\n10\n0\n20\n0\n10\n4\n20\n0\n10\n4\n20\n3\n\
+                   0\nLINE\n10\n10\n20\n0\n11\n12\n21\n0\n\
+                   0\nLINE\n10\n11\n20\n2\n11\n10\n21\n0\n\
+                   0\nLINE\n10\n12\n20\n0\n11\n11\n21\n2\n\
+                   0\nENDSEC\n0\nEOF\n";
+        let outlines = parse_dxf(dxf, 0.1).unwrap();
+        assert_eq!(outlines.paths.len(), 2);
+        assert_eq!(outlines.paths[0], vec![[0.0, 0.0], [4.0, 0.0], [4.0, 3.0]]);
+        assert_eq!(outlines.paths[1].len(), 3);
+    }
+
+    #[test]
+    fn emits_polygon_with_flipped_svg_axis() {
+        let conversion = convert_outline(
+            "M0 0 L10 0 L10 5 Z",
+            OutlineFormat::Svg,
+            "2-logo.svg",
+            &OutlineOptions::default(),
+        )
+        .unwrap();
+        assert!(conversion
+            .code
+            .contains("outline_2_logo_points = [\n    [0,0],[10,0],[10,-5]\n];"));
+        assert!(conversion
+            .code
+            .contains("outline_2_logo_paths = [\n    [0,1,2]\n];"));
+        assert_eq!((conversion.width, conversion.height), (10.0, 5.0));
+        assert_eq!(OutlineFormat::detect(Some("a.DXF"), ""), OutlineFormat::Dxf);
+        assert!(convert_outline(
+            "M0 0 L1 1",
+            OutlineFormat::Svg,
+            "x.svg",
+            &OutlineOptions::default()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,362 @@
+/**
+ * OpenSCAD stderr diagnostics
+ *
+ * Turns `ERROR:`/`WARNING:`/`ECHO:` lines into diagnostics. OpenSCAD only
+ * reports line numbers, so the column range is best-effort: the first quoted
+ * name in the message (`unknown variable "r"`, `unknown module 'gear'`) is
+ * looked up on the reported source line and its extent becomes the range.
+ * Columns are 1-based UTF-16 positions with an exclusive end, as Monaco
+ * expects; they stay unset when no token can be located.
+ *
+ * Messages name the file they refer to (`in file lib/util.scad, line 4`),
+ * but with the path OpenSCAD was given: the render's temp copy of the input
+ * or of an unsaved buffer, or a file in the temp workspace. Those paths are
+ * mapped back to project-relative paths so multi-file errors land on the
+ * right file.
+ *
+ * Unknown variable/module/function warnings and failed assertions get a
+ * category, and an assertion's range covers its failed expression. Repeated
+ * warnings (e.g. from a loop) are reported once.
+ */
+use crate::types::{Diagnostic, DiagnosticCategory, DiagnosticSeverity};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Errors OpenSCAD prints without an `ERROR:` prefix
+const IMPLICIT_ERRORS: &[&str] = &["Parser error", "syntax error", "Can't open"];
+
+fn severity_and_message(line: &str) -> Option<(DiagnosticSeverity, &str)> {
+    let (prefix, rest) = line.split_once(':').unwrap_or(("", line));
+    match prefix.trim().to_ascii_uppercase().as_str() {
+        "ERROR" => Some((DiagnosticSeverity::Error, rest.trim())),
+        "WARNING" => Some((DiagnosticSeverity::Warning, rest.trim())),
+        "ECHO" => Some((DiagnosticSeverity::Info, rest.trim())),
+        _ if IMPLICIT_ERRORS.iter().any(|error| line.contains(error)) => {
+            Some((DiagnosticSeverity::Error, line))
+        }
+        _ => None,
+    }
+}
+
+/// Number following the last `line ` in the message
+fn line_number(message: &str) -> Option<i32> {
+    let lower = message.to_ascii_lowercase();
+    let index = lower.rfind("line ")?;
+    let digits: String = lower[index + 5..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+/// Number following `column ` in the message, for messages that give one
+fn column_number(message: &str) -> Option<i32> {
+    let lower = message.to_ascii_lowercase();
+    let index = lower.rfind("column ")?;
+    let digits: String = lower[index + 7..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok().filter(|col| *col > 0)
+}
+
+fn category(message: &str) -> Option<DiagnosticCategory> {
+    let lower = message.to_ascii_lowercase();
+    if lower.contains("unknown variable") {
+        Some(DiagnosticCategory::UnknownVariable)
+    } else if lower.contains("unknown module") {
+        Some(DiagnosticCategory::UnknownModule)
+    } else if lower.contains("unknown function") {
+        Some(DiagnosticCategory::UnknownFunction)
+    } else if lower.starts_with("assertion") {
+        Some(DiagnosticCategory::AssertionFailed)
+    } else {
+        None
+    }
+}
+
+/// Expression of `Assertion '<expr>' failed`
+pub fn assertion_expression(message: &str) -> Option<&str> {
+    let rest = message.strip_prefix("Assertion '")?;
+    let end = rest.find("' failed")?;
+    Some(&rest[..end])
+}
+
+/// Column range of the first occurrence of `text` on `source_line`
+fn text_range(source_line: &str, text: &str) -> Option<(i32, i32)> {
+    let utf16_col = |byte: usize| source_line[..byte].encode_utf16().count() as i32 + 1;
+    let start = source_line.find(text)?;
+    Some((utf16_col(start), utf16_col(start + text.len())))
+}
+
+/// Range of a failed assertion: its expression as written (OpenSCAD may
+/// add outer parentheses), or else the `assert` keyword
+fn assertion_range(source_line: &str, expression: &str) -> Option<(i32, i32)> {
+    let unwrapped = expression
+        .strip_prefix('(')
+        .and_then(|inner| inner.strip_suffix(')'))
+        .filter(|inner| !inner.is_empty());
+    text_range(source_line, expression)
+        .or_else(|| unwrapped.and_then(|inner| text_range(source_line, inner)))
+        .or_else(|| token_range(source_line, "assert"))
+}
+
+/// File named by the last `in file ...` of the message, as printed
+fn file_reference(message: &str) -> Option<&str> {
+    let rest = &message[message.rfind("in file ")? + "in file ".len()..];
+    let path = match rest.strip_prefix('"') {
+        Some(quoted) => &quoted[..quoted.find('"')?],
+        None => rest.find(", line").map_or(rest, |end| &rest[..end]),
+    };
+    let path = path.trim();
+    (!path.is_empty()).then_some(path)
+}
+
+/// `name` with a render temp-copy name (`.openscad-studio-<stem>-<id>.scad`)
+/// turned back into the original `<stem>.scad`
+fn original_file_name(name: &str) -> String {
+    let Some(rest) = name
+        .strip_prefix(".openscad-studio-")
+        .and_then(|rest| rest.strip_suffix(".scad"))
+    else {
+        return name.to_string();
+    };
+    match rest.rsplit_once('-') {
+        Some((stem, id)) if id.len() == 8 && id.chars().all(|c| c.is_ascii_hexdigit()) => {
+            format!("{stem}.scad")
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// Project-relative form of a file path OpenSCAD reported. Files outside
+/// the project (libraries) keep their path.
+pub fn project_file(reported: &str, project_root: Option<&Path>) -> String {
+    let path = reported.replace('\\', "/");
+    let relative = if let Some((_, inside)) = path.split_once("/input_dir/") {
+        // Renders without a project run in a temp workspace
+        inside.to_string()
+    } else {
+        project_root
+            .map(|root| root.to_string_lossy().replace('\\', "/"))
+            .and_then(|root| {
+                path.strip_prefix(root.trim_end_matches('/'))
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .map(str::to_string)
+            })
+            .unwrap_or(path)
+    };
+    match relative.rsplit_once('/') {
+        Some((dir, name)) => format!("{dir}/{}", original_file_name(name)),
+        None => original_file_name(&relative),
+    }
+}
+
+/// First `"..."`, `'...'` or `` `...` `` quoted identifier in the message
+pub fn quoted_token(message: &str) -> Option<&str> {
+    message.char_indices().find_map(|(start, quote)| {
+        if !matches!(quote, '"' | '\'' | '`') {
+            return None;
+        }
+        let rest = &message[start + 1..];
+        let token = &rest[..rest.find(quote)?];
+        let is_name = !token.is_empty()
+            && token
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+        is_name.then_some(token)
+    })
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Column range of `token` on `source_line` as a whole word
+pub fn token_range(source_line: &str, token: &str) -> Option<(i32, i32)> {
+    let utf16_col = |byte: usize| source_line[..byte].encode_utf16().count() as i32 + 1;
+    source_line.match_indices(token).find_map(|(start, _)| {
+        let end = start + token.len();
+        let before = source_line[..start].chars().next_back();
+        let after = source_line[end..].chars().next();
+        let whole_word = !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char);
+        whole_word.then(|| (utf16_col(start), utf16_col(end)))
+    })
+}
+
+/// Parse OpenSCAD stderr from a render of `input_path` (project-relative)
+/// in `project_root`, attributing each diagnostic to its project file.
+/// `code` is the rendered source, used to locate the offending token of
+/// diagnostics in the input file; without it diagnostics carry only line
+/// numbers.
+pub fn parse_openscad_stderr(
+    stderr: &str,
+    code: Option<&str>,
+    input_path: Option<&str>,
+    project_root: Option<&Path>,
+) -> Vec<Diagnostic> {
+    let source_lines: Vec<&str> = code.map(|code| code.lines().collect()).unwrap_or_default();
+    let mut seen_warnings = HashSet::new();
+    stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let (severity, message) = severity_and_message(line)?;
+            if severity == DiagnosticSeverity::Warning && !seen_warnings.insert(line) {
+                return None;
+            }
+            let line_no = line_number(message);
+            let file = file_reference(message).map(|file| project_file(file, project_root));
+            let in_input = match (&file, input_path) {
+                (Some(file), Some(input)) => *file == input.replace('\\', "/"),
+                _ => true,
+            };
+            let source_line = line_no
+                .filter(|_| in_input)
+                .and_then(|n| source_lines.get(usize::try_from(n).ok()?.checked_sub(1)?));
+            let category = category(message);
+            let range = source_line.and_then(|source_line| match assertion_expression(message) {
+                Some(expression) => assertion_range(source_line, expression),
+                None => token_range(source_line, quoted_token(message)?),
+            });
+            let col = range
+                .map(|(start, _)| start)
+                .or_else(|| column_number(message));
+            Some(Diagnostic {
+                severity,
+                line: line_no,
+                col,
+                end_col: range.map(|(_, end)| end),
+                file,
+                category,
+                message: line.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_cover_the_named_token() {
+        let code = "radius = 5;\nsphere(r = raduis);\ngear(teeth = 12);\n";
+        let stderr = "\
+WARNING: Ignoring unknown variable \"raduis\" in file input.scad, line 2
+WARNING: Ignoring unknown module 'gear' in file input.scad, line 3
+ERROR: Parser error in file \"input.scad\", line 7: syntax error
+ECHO: \"done\"
+Rendering finished.";
+        let diagnostics = parse_openscad_stderr(stderr, Some(code), None, None);
+
+        assert_eq!(diagnostics.len(), 4);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!(
+            (
+                diagnostics[0].line,
+                diagnostics[0].col,
+                diagnostics[0].end_col
+            ),
+            (Some(2), Some(12), Some(18))
+        );
+        assert_eq!(
+            (diagnostics[1].col, diagnostics[1].end_col),
+            (Some(1), Some(5))
+        );
+        // Quoted file names aren't identifiers, and line 7 doesn't exist.
+        assert_eq!(diagnostics[2].severity, DiagnosticSeverity::Error);
+        assert_eq!((diagnostics[2].line, diagnostics[2].col), (Some(7), None));
+        assert_eq!(diagnostics[3].severity, DiagnosticSeverity::Info);
+        assert_eq!(diagnostics[3].line, None);
+    }
+
+    #[test]
+    fn attributes_diagnostics_to_project_files() {
+        let code = "include <lib/util.scad>\nsphere(r = raduis);\n";
+        let stderr = "\
+WARNING: Ignoring unknown variable \"raduis\" in file /proj/models/.openscad-studio-main-1a2b3c4d.scad, line 2
+WARNING: Ignoring unknown variable \"raduis\" in file /proj/models/lib/util.scad, line 2
+ERROR: Parser error in file \"/proj/models/lib/.openscad-studio-util-1a2b3c4d.scad\", line 9: syntax error
+WARNING: Ignoring unknown module 'x' in file /usr/share/openscad/libraries/BOSL2/std.scad, line 1";
+        let diagnostics = parse_openscad_stderr(
+            stderr,
+            Some(code),
+            Some("models/main.scad"),
+            Some(Path::new("/proj")),
+        );
+        let files: Vec<_> = diagnostics.iter().map(|d| d.file.as_deref()).collect();
+        assert_eq!(
+            files,
+            [
+                Some("models/main.scad"),
+                Some("models/lib/util.scad"),
+                Some("models/lib/util.scad"),
+                Some("/usr/share/openscad/libraries/BOSL2/std.scad"),
+            ]
+        );
+        // Only the input file's diagnostics are located in `code`.
+        assert_eq!(diagnostics[0].col, Some(12));
+        assert_eq!(diagnostics[1].col, None);
+
+        assert_eq!(
+            project_file("/tmp/openscad-studio/abc/input_dir/parts/a.scad", None),
+            "parts/a.scad"
+        );
+        assert_eq!(
+            project_file(".openscad-studio-x-y.scad", None),
+            ".openscad-studio-x-y.scad"
+        );
+        assert_eq!(file_reference("Can't open file 'x.scad'"), None);
+    }
+
+    #[test]
+    fn classifies_assertions_and_unknown_names() {
+        let code =
+            "size = -1;\nassert(size > 0, \"size must be positive\");\nfor (i = [0:2]) foo();\n";
+        let stderr = "\
+ERROR: Assertion '(size > 0)' failed: \"size must be positive\" in file input.scad, line 2
+WARNING: Ignoring unknown module 'foo' in file input.scad, line 3
+WARNING: Ignoring unknown module 'foo' in file input.scad, line 3
+WARNING: Ignoring unknown module 'foo' in file input.scad, line 3
+ERROR: Parser error: syntax error in file input.scad, line 9, column 14";
+        let diagnostics = parse_openscad_stderr(stderr, Some(code), None, None);
+
+        assert_eq!(diagnostics.len(), 3, "repeated warnings are merged");
+        assert_eq!(
+            diagnostics[0].category,
+            Some(DiagnosticCategory::AssertionFailed)
+        );
+        assert_eq!(
+            (diagnostics[0].col, diagnostics[0].end_col),
+            (Some(8), Some(16))
+        );
+        assert_eq!(
+            diagnostics[1].category,
+            Some(DiagnosticCategory::UnknownModule)
+        );
+        assert_eq!(
+            (diagnostics[1].col, diagnostics[1].end_col),
+            (Some(17), Some(20))
+        );
+        assert_eq!(diagnostics[2].category, None);
+        assert_eq!(
+            (diagnostics[2].line, diagnostics[2].col),
+            (Some(9), Some(14))
+        );
+
+        // Without a matching expression the `assert` keyword is underlined.
+        assert_eq!(assertion_range("  assert(ok);", "(valid)"), Some((3, 9)));
+        assert_eq!(assertion_expression("Assertion 'x' failed"), Some("x"));
+    }
+
+    #[test]
+    fn token_lookup_matches_whole_words_in_utf16_columns() {
+        assert_eq!(token_range("rr = r + 1;", "r"), Some((6, 7)));
+        assert_eq!(token_range("s = \"ü\"; x = y;", "y"), Some((14, 15)));
+        assert_eq!(token_range("width = 1;", "w"), None);
+        assert_eq!(quoted_token("unknown function 'foo_2'"), Some("foo_2"));
+        assert_eq!(quoted_token("in file \"a b.scad\""), None);
+    }
+}
@@ -0,0 +1,251 @@
+/**
+ * Pending AI edits
+ *
+ * In "ask before apply" mode an AI `apply_edit` is not written to the buffer
+ * straight away. It is validated, recorded here with a line diff and an id,
+ * and only reaches the buffer when the user accepts it. Accepting re-applies
+ * the replacement to the buffer as it is then, so edits made in the meantime
+ * are kept (or the accept fails if the original text is gone).
+ *
+ * Besides exact-string replacements, edits can target a line range, which
+ * stays reliable when the same code appears several times in a file.
+ */
+use crate::history::line_diff;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingEdit {
+    pub id: String,
+    /// Document (tab) the edit is for, or `None` for the active one
+    pub document_id: Option<String>,
+    /// Project-relative file, or `None` for the document's buffer
+    pub file_path: Option<String>,
+    pub old_string: String,
+    pub new_string: String,
+    pub diff: String,
+    pub added_lines: usize,
+    pub removed_lines: usize,
+    pub created_at: i64,
+}
+
+/// Replace the single occurrence of `old` in `code`
+pub fn replace_unique(code: &str, old: &str, new: &str) -> Result<String, String> {
+    if old.is_empty() {
+        return Err("old_string must not be empty".into());
+    }
+    match code.matches(old).count() {
+        0 => Err("old_string not found in the file".into()),
+        1 => Ok(code.replacen(old, new, 1)),
+        n => Err(format!("old_string found {n} times — it must be unique")),
+    }
+}
+
+/// One exact-string replacement of a multi-edit transaction
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct Replacement {
+    /// Exact text to replace; must appear exactly once at that point
+    pub old_string: String,
+    /// Replacement text
+    pub new_string: String,
+}
+
+/// Apply `replacements` in order, each against the result of the previous
+/// one; fails without a partial result if any of them doesn't match once
+pub fn apply_replacements(code: &str, replacements: &[Replacement]) -> Result<String, String> {
+    if replacements.is_empty() {
+        return Err("No replacements given".into());
+    }
+    replacements
+        .iter()
+        .enumerate()
+        .try_fold(code.to_string(), |code, (index, replacement)| {
+            replace_unique(&code, &replacement.old_string, &replacement.new_string)
+                .map_err(|e| format!("Replacement {}: {e}", index + 1))
+        })
+}
+
+/// Replace lines `start..=end` (1-based) with `text`. An `end` of
+/// `start - 1` inserts `text` before line `start` without removing any.
+/// `text` always ends up on lines of its own, and the file keeps its final
+/// newline, or lack of one.
+pub fn replace_lines(code: &str, start: usize, end: usize, text: &str) -> Result<String, String> {
+    let lines: Vec<&str> = code.split_inclusive('\n').collect();
+    if start == 0 || start > lines.len() + 1 || end + 1 < start || end > lines.len() {
+        return Err(format!(
+            "Invalid line range {start}-{end}: the file has {} lines",
+            lines.len()
+        ));
+    }
+    let newline = if code.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut result = lines[..start - 1].concat();
+    if !text.is_empty() {
+        // Appending after a last line that has no newline yet
+        if !result.is_empty() && !result.ends_with('\n') {
+            result.push_str(newline);
+        }
+        result.push_str(text);
+        if !text.ends_with('\n') && (end < lines.len() || code.ends_with('\n')) {
+            result.push_str(newline);
+        }
+    }
+    result.push_str(&lines[end..].concat());
+    Ok(result)
+}
+
+/// `code` with a right-aligned line number before each line
+pub fn numbered_lines(code: &str) -> String {
+    let count = code.lines().count();
+    let width = count.to_string().len();
+    code.lines()
+        .enumerate()
+        .map(|(index, line)| format!("{:>width$} | {line}\n", index + 1))
+        .collect()
+}
+
+#[derive(Default)]
+pub struct PendingEdits {
+    edits: Mutex<HashMap<String, PendingEdit>>,
+}
+
+impl PendingEdits {
+    /// Validate an edit against `code` and hold it for review
+    pub fn propose(
+        &self,
+        document_id: Option<String>,
+        file_path: Option<String>,
+        code: &str,
+        old_string: String,
+        new_string: String,
+    ) -> Result<PendingEdit, String> {
+        let next = replace_unique(code, &old_string, &new_string)?;
+        let (diff, added_lines, removed_lines) = line_diff(code, &next);
+        let edit = PendingEdit {
+            id: uuid::Uuid::new_v4().to_string(),
+            document_id,
+            file_path,
+            old_string,
+            new_string,
+            diff,
+            added_lines,
+            removed_lines,
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        self.edits
+            .lock()
+            .unwrap()
+            .insert(edit.id.clone(), edit.clone());
+        Ok(edit)
+    }
+
+    /// Remove a pending edit so it can be applied or dropped
+    pub fn take(&self, id: &str) -> Option<PendingEdit> {
+        self.edits.lock().unwrap().remove(id)
+    }
+
+    /// Pending edits, oldest first
+    pub fn list(&self) -> Vec<PendingEdit> {
+        let mut edits: Vec<PendingEdit> = self.edits.lock().unwrap().values().cloned().collect();
+        edits.sort_by_key(|edit| edit.created_at);
+        edits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_a_unique_match() {
+        assert_eq!(
+            replace_unique("cube(10);", "10", "20").unwrap(),
+            "cube(20);"
+        );
+        assert!(replace_unique("cube(10);", "sphere", "x").is_err());
+        assert!(replace_unique("cube(1); cube(1);", "cube(1)", "x").is_err());
+        assert!(replace_unique("cube(1);", "", "x").is_err());
+    }
+
+    #[test]
+    fn applies_replacements_in_order_or_not_at_all() {
+        let replacement = |old: &str, new: &str| Replacement {
+            old_string: old.into(),
+            new_string: new.into(),
+        };
+        let code = "width = 10;\ncube(width);\n";
+        assert_eq!(
+            apply_replacements(
+                code,
+                &[
+                    replacement("width = 10;", "width = 20;\nheight = 5;"),
+                    replacement("cube(width);", "cube([width, width, height]);"),
+                ]
+            )
+            .unwrap(),
+            "width = 20;\nheight = 5;\ncube([width, width, height]);\n"
+        );
+
+        let error = apply_replacements(
+            code,
+            &[
+                replacement("width = 10;", "width = 20;"),
+                replacement("sphere(1);", "sphere(2);"),
+            ],
+        )
+        .unwrap_err();
+        assert!(error.starts_with("Replacement 2:"));
+        assert!(apply_replacements(code, &[]).is_err());
+    }
+
+    #[test]
+    fn replaces_line_ranges() {
+        let code = "a\nb\nc\n";
+        assert_eq!(replace_lines(code, 2, 2, "B").unwrap(), "a\nB\nc\n");
+        assert_eq!(replace_lines(code, 2, 3, "x\n").unwrap(), "a\nx\n");
+        assert_eq!(replace_lines(code, 1, 2, "").unwrap(), "c\n");
+        assert_eq!(replace_lines(code, 2, 1, "new").unwrap(), "a\nnew\nb\nc\n");
+        assert_eq!(replace_lines(code, 4, 3, "d\n").unwrap(), "a\nb\nc\nd\n");
+        assert_eq!(replace_lines(code, 3, 3, "C").unwrap(), "a\nb\nC\n");
+        assert_eq!(replace_lines(code, 4, 3, "d").unwrap(), "a\nb\nc\nd\n");
+        assert_eq!(replace_lines("a\nb", 3, 2, "c").unwrap(), "a\nb\nc");
+        assert_eq!(replace_lines("a\nb", 2, 2, "B").unwrap(), "a\nB");
+        assert_eq!(
+            replace_lines("a\r\nb\r\n", 1, 1, "A").unwrap(),
+            "A\r\nb\r\n"
+        );
+        assert!(replace_lines(code, 0, 1, "x").is_err());
+        assert!(replace_lines(code, 3, 4, "x").is_err());
+        assert!(replace_lines(code, 3, 1, "x").is_err());
+
+        let numbered = numbered_lines(&"x\n".repeat(10));
+        assert!(numbered.starts_with(" 1 | x\n"));
+        assert!(numbered.ends_with("10 | x\n"));
+    }
+
+    #[test]
+    fn holds_edits_until_taken() {
+        let pending = PendingEdits::default();
+        let edit = pending
+            .propose(
+                Some("doc".into()),
+                None,
+                "cube(10);\nsphere(5);\n",
+                "sphere(5);".into(),
+                "sphere(8);".into(),
+            )
+            .unwrap();
+        assert_eq!(edit.diff, " cube(10);\n-sphere(5);\n+sphere(8);\n");
+        assert_eq!((edit.added_lines, edit.removed_lines), (1, 1));
+        assert_eq!(edit.document_id.as_deref(), Some("doc"));
+        assert_eq!(pending.list().len(), 1);
+
+        assert!(pending.take(&edit.id).is_some());
+        assert!(pending.take(&edit.id).is_none());
+        assert!(pending.list().is_empty());
+        assert!(pending
+            .propose(None, None, "cube(10);", "sphere".into(), "x".into())
+            .is_err());
+    }
+}
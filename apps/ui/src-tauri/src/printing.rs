@@ -0,0 +1,273 @@
+/**
+ * Direct upload to networked printers
+ *
+ * Sends exported files to OctoPrint (`POST /api/files/local`) or to Klipper
+ * through Moonraker (`POST /server/files/upload`). Both take a multipart
+ * form with a `file` part and authenticate with an `X-Api-Key` header.
+ * Printer connections, API keys included, live in the settings store.
+ * Moonraker only stores sliced G-code; OctoPrint also takes STL for its
+ * slicing plugins.
+ */
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const UPLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+pub const STATUS_TIMEOUT: Duration = Duration::from_secs(10);
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+const GCODE_EXTENSIONS: &[&str] = &["gcode", "gco", "g", "bgcode"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrinterKind {
+    OctoPrint,
+    Moonraker,
+}
+
+impl PrinterKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            PrinterKind::OctoPrint => "OctoPrint",
+            PrinterKind::Moonraker => "Moonraker",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrinterConnection {
+    pub id: String,
+    pub name: String,
+    pub kind: PrinterKind,
+    /// Base URL, e.g. `http://octopi.local`
+    pub url: String,
+    pub api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrintingSettings {
+    pub printers: Vec<PrinterConnection>,
+}
+
+/// A printer as shown in the UI, without its API key
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrinterSummary {
+    pub id: String,
+    pub name: String,
+    pub kind: PrinterKind,
+    pub url: String,
+    pub has_api_key: bool,
+}
+
+impl From<&PrinterConnection> for PrinterSummary {
+    fn from(printer: &PrinterConnection) -> Self {
+        Self {
+            id: printer.id.clone(),
+            name: printer.name.clone(),
+            kind: printer.kind,
+            url: printer.url.clone(),
+            has_api_key: printer.api_key.is_some(),
+        }
+    }
+}
+
+/// `url` with a scheme (http assumed) and without a trailing slash
+pub fn normalize_base_url(url: &str) -> Result<String, String> {
+    let url = url.trim().trim_end_matches('/');
+    if url.is_empty() {
+        return Err("Enter the printer's address".into());
+    }
+    let url = if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("http://{url}")
+    };
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(format!("{url} is not an http(s) address"));
+    }
+    Ok(url)
+}
+
+pub fn upload_url(printer: &PrinterConnection) -> String {
+    match printer.kind {
+        PrinterKind::OctoPrint => format!("{}/api/files/local", printer.url),
+        PrinterKind::Moonraker => format!("{}/server/files/upload", printer.url),
+    }
+}
+
+pub fn status_url(printer: &PrinterConnection) -> String {
+    match printer.kind {
+        PrinterKind::OctoPrint => format!("{}/api/version", printer.url),
+        PrinterKind::Moonraker => format!("{}/server/info", printer.url),
+    }
+}
+
+pub fn is_gcode(file_name: &str) -> bool {
+    file_name
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| GCODE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Why `file_name` can't be sent to `kind`, if it can't
+pub fn check_upload(kind: PrinterKind, file_name: &str, start_print: bool) -> Result<(), String> {
+    let is_stl = file_name.to_ascii_lowercase().ends_with(".stl");
+    match kind {
+        _ if is_gcode(file_name) => Ok(()),
+        PrinterKind::OctoPrint if is_stl && !start_print => Ok(()),
+        PrinterKind::OctoPrint if is_stl => Err(
+            "OctoPrint can't print an STL directly; upload it without printing and slice it there"
+                .into(),
+        ),
+        _ => Err(format!(
+            "{} only accepts sliced G-code, not {file_name}",
+            kind.label()
+        )),
+    }
+}
+
+/// Multipart form around the file contents: the bytes before and after
+/// the file, and the `Content-Type` header value
+pub struct MultipartForm {
+    pub head: Vec<u8>,
+    pub tail: Vec<u8>,
+    pub content_type: String,
+}
+
+pub fn multipart_form(kind: PrinterKind, file_name: &str, start_print: bool) -> MultipartForm {
+    let boundary = format!("----OpenSCADStudio{}", uuid::Uuid::new_v4().simple());
+    let mut fields: Vec<(&str, &str)> = Vec::new();
+    match kind {
+        PrinterKind::OctoPrint if start_print => {
+            fields.extend([("select", "true"), ("print", "true")])
+        }
+        PrinterKind::OctoPrint => {}
+        PrinterKind::Moonraker => {
+            fields.push(("root", "gcodes"));
+            if start_print {
+                fields.push(("print", "true"));
+            }
+        }
+    }
+    let mut head = String::new();
+    for (name, value) in fields {
+        head.push_str(&format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+        ));
+    }
+    let file_name = file_name.replace(['"', '\r', '\n'], "_");
+    head.push_str(&format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+    ));
+    MultipartForm {
+        head: head.into_bytes(),
+        tail: format!("\r\n--{boundary}--\r\n").into_bytes(),
+        content_type: format!("multipart/form-data; boundary={boundary}"),
+    }
+}
+
+/// Server description from a status response, e.g. "OctoPrint 1.10.2"
+pub fn parse_status(kind: PrinterKind, body: &str) -> Result<String, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|_| format!("Not a {} server", kind.label()))?;
+    match kind {
+        PrinterKind::OctoPrint => value["text"]
+            .as_str()
+            .map(str::to_string)
+            .or_else(|| value["server"].as_str().map(|v| format!("OctoPrint {v}"))),
+        PrinterKind::Moonraker => {
+            let result = &value["result"];
+            result["moonraker_version"].as_str().map(|version| {
+                match result["klippy_state"].as_str() {
+                    Some(state) => format!("Moonraker {version} (Klipper {state})"),
+                    None => format!("Moonraker {version}"),
+                }
+            })
+        }
+    }
+    .ok_or_else(|| format!("Not a {} server", kind.label()))
+}
+
+/// Error message from a failed request's body, falling back to the status
+pub fn error_message(status: u16, body: &str) -> String {
+    let value: Option<serde_json::Value> = serde_json::from_str(body).ok();
+    let detail = value.as_ref().and_then(|value| {
+        value["error"]["message"]
+            .as_str()
+            .or_else(|| value["error"].as_str())
+            .map(str::to_string)
+    });
+    match (status, detail) {
+        (_, Some(detail)) => detail,
+        (401 | 403, None) => "The printer rejected the API key".to_string(),
+        (status, None) => format!("The printer returned HTTP {status}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_upload_requests() {
+        assert_eq!(
+            normalize_base_url(" octopi.local/ ").unwrap(),
+            "http://octopi.local"
+        );
+        assert_eq!(
+            normalize_base_url("https://printer:7125").unwrap(),
+            "https://printer:7125"
+        );
+        assert!(normalize_base_url("ftp://printer").is_err());
+        assert!(normalize_base_url("").is_err());
+
+        assert!(check_upload(PrinterKind::Moonraker, "part.GCODE", true).is_ok());
+        assert!(check_upload(PrinterKind::Moonraker, "part.stl", false).is_err());
+        assert!(check_upload(PrinterKind::OctoPrint, "part.stl", false).is_ok());
+        assert!(check_upload(PrinterKind::OctoPrint, "part.stl", true).is_err());
+
+        let form = multipart_form(PrinterKind::Moonraker, "part \"1\".gcode", true);
+        let head = String::from_utf8(form.head).unwrap();
+        let boundary = form.content_type.split("boundary=").nth(1).unwrap();
+        assert!(head.starts_with(&format!("--{boundary}\r\n")));
+        assert!(head.contains("name=\"root\"\r\n\r\ngcodes\r\n"));
+        assert!(head.contains("name=\"print\"\r\n\r\ntrue\r\n"));
+        assert!(head.contains("filename=\"part _1_.gcode\""));
+        assert_eq!(form.tail, format!("\r\n--{boundary}--\r\n").into_bytes());
+
+        let form = multipart_form(PrinterKind::OctoPrint, "part.stl", false);
+        assert!(!String::from_utf8(form.head)
+            .unwrap()
+            .contains("name=\"print\""));
+    }
+
+    #[test]
+    fn reads_server_responses() {
+        assert_eq!(
+            parse_status(
+                PrinterKind::OctoPrint,
+                r#"{"api":"0.1","server":"1.10.2","text":"OctoPrint 1.10.2"}"#
+            ),
+            Ok("OctoPrint 1.10.2".to_string())
+        );
+        assert_eq!(
+            parse_status(
+                PrinterKind::Moonraker,
+                r#"{"result":{"klippy_state":"ready","moonraker_version":"v0.9.3"}}"#
+            ),
+            Ok("Moonraker v0.9.3 (Klipper ready)".to_string())
+        );
+        assert!(parse_status(PrinterKind::Moonraker, "<html></html>").is_err());
+
+        assert_eq!(
+            error_message(400, r#"{"error":{"code":400,"message":"Invalid root"}}"#),
+            "Invalid root"
+        );
+        assert_eq!(
+            error_message(409, r#"{"error":"File is being printed"}"#),
+            "File is being printed"
+        );
+        assert_eq!(error_message(403, ""), "The printer rejected the API key");
+    }
+}
@@ -0,0 +1,204 @@
+/**
+ * Resource limits for OpenSCAD processes
+ *
+ * Every OpenSCAD run has a wall-clock timeout, and on Linux it can also get
+ * an address-space limit (`RLIMIT_AS`), applied in the child before OpenSCAD
+ * starts. With that limit, runaway recursion or a huge `minkowski()` fails
+ * inside OpenSCAD instead of exhausting the machine. macOS barely enforces
+ * `RLIMIT_AS`, so the memory limit isn't offered there, and Flatpak builds
+ * run unlimited because the limit would land on the `flatpak run` wrapper
+ * rather than OpenSCAD in its sandbox. A run stopped by a limit reports an
+ * `ERROR:` line, which the diagnostics parser handles like any other
+ * OpenSCAD error.
+ */
+use crate::locate::flatpak_app_id;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+pub const DEFAULT_TIMEOUT_SECS: u64 = 120;
+pub const MAX_TIMEOUT_SECS: u64 = 60 * 60;
+/// OpenSCAD itself needs a few hundred MB of address space to start
+pub const MIN_MEMORY_LIMIT_MB: u64 = 512;
+/// Whether `RLIMIT_AS` actually bounds OpenSCAD's memory on this platform
+pub const MEMORY_LIMIT_SUPPORTED: bool = cfg!(all(unix, not(target_os = "macos")));
+
+/// Memory limit for new OpenSCAD processes in MB; 0 means none
+static MEMORY_LIMIT_MB: AtomicU64 = AtomicU64::new(0);
+
+/// Limit that stopped a render
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExceededLimit {
+    Timeout,
+    Memory,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RenderLimitSettings {
+    /// Wall-clock limit for one OpenSCAD run
+    pub timeout_secs: u64,
+    /// Address-space limit per OpenSCAD process; `None` is unlimited. Only
+    /// enforced where `MEMORY_LIMIT_SUPPORTED`, and not for Flatpak builds.
+    pub memory_limit_mb: Option<u64>,
+}
+
+impl Default for RenderLimitSettings {
+    fn default() -> Self {
+        Self {
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            memory_limit_mb: None,
+        }
+    }
+}
+
+impl RenderLimitSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_TIMEOUT_SECS).contains(&self.timeout_secs) {
+            return Err(format!(
+                "Render timeout must be between 1 and {MAX_TIMEOUT_SECS} seconds"
+            ));
+        }
+        if self.memory_limit_mb.is_some() && !MEMORY_LIMIT_SUPPORTED {
+            return Err("Render memory limits aren't supported on this platform".into());
+        }
+        if self
+            .memory_limit_mb
+            .is_some_and(|limit| limit < MIN_MEMORY_LIMIT_MB)
+        {
+            return Err(format!(
+                "Render memory limit must be at least {MIN_MEMORY_LIMIT_MB} MB"
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.clamp(1, MAX_TIMEOUT_SECS))
+    }
+}
+
+/// Set the memory limit for OpenSCAD processes started from now on
+pub fn set_memory_limit(limit_mb: Option<u64>) {
+    MEMORY_LIMIT_MB.store(limit_mb.unwrap_or(0), Ordering::SeqCst);
+}
+
+pub fn memory_limit_mb() -> Option<u64> {
+    Some(MEMORY_LIMIT_MB.load(Ordering::SeqCst)).filter(|limit| *limit > 0)
+}
+
+/// Memory limit to apply when running `binary`: none where `RLIMIT_AS`
+/// doesn't reach OpenSCAD
+pub fn memory_limit_for(binary: &Path) -> Option<u64> {
+    memory_limit_mb().filter(|_| MEMORY_LIMIT_SUPPORTED && flatpak_app_id(binary).is_none())
+}
+
+/// Cap the address space of the process `command` starts
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn limit_memory(command: &mut Command, limit_mb: u64) {
+    use std::os::unix::process::CommandExt;
+    let bytes = limit_mb.saturating_mul(1024 * 1024) as libc::rlim_t;
+    // SAFETY: the hook only calls setrlimit, which is async-signal-safe
+    unsafe {
+        command.pre_exec(move || {
+            let limit = libc::rlimit {
+                rlim_cur: bytes,
+                rlim_max: bytes,
+            };
+            if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn limit_memory(_command: &mut Command, _limit_mb: u64) {}
+
+/// Whether a run under a memory limit failed for lack of memory, judged by
+/// OpenSCAD reporting the failed allocation. A signal death alone (exit code
+/// -1) isn't enough: renders are also stopped by timeouts, cancellation or
+/// crashes unrelated to memory.
+pub fn ran_out_of_memory(stderr: &str, exit_code: i32) -> bool {
+    let stderr = stderr.to_ascii_lowercase();
+    exit_code != 0
+        && (stderr.contains("bad_alloc")
+            || stderr.contains("out of memory")
+            || stderr.contains("cannot allocate memory"))
+}
+
+/// Diagnostic line for a run stopped by `limit`
+pub fn limit_message(limit: ExceededLimit, timeout: Duration, memory_limit_mb: u64) -> String {
+    match limit {
+        ExceededLimit::Timeout => format!(
+            "ERROR: Render timed out after {}s and was stopped. Simplify the model or raise the render timeout in settings.",
+            timeout.as_secs()
+        ),
+        ExceededLimit::Memory => format!(
+            "ERROR: Render ran out of memory (limit {memory_limit_mb} MB) and was stopped. Simplify the model or raise the memory limit in settings."
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_limits() {
+        assert!(RenderLimitSettings::default().validate().is_ok());
+        let no_timeout = RenderLimitSettings {
+            timeout_secs: 0,
+            ..Default::default()
+        };
+        assert!(no_timeout.validate().is_err());
+        let tiny = RenderLimitSettings {
+            memory_limit_mb: Some(64),
+            ..Default::default()
+        };
+        assert!(tiny.validate().is_err());
+        let limited = RenderLimitSettings {
+            memory_limit_mb: Some(2048),
+            ..Default::default()
+        };
+        assert_eq!(limited.validate().is_ok(), MEMORY_LIMIT_SUPPORTED);
+    }
+
+    #[test]
+    fn leaves_flatpak_builds_unlimited() {
+        set_memory_limit(Some(2048));
+        let flatpak = Path::new("/var/lib/flatpak/exports/bin/org.openscad.OpenSCAD");
+        assert_eq!(memory_limit_for(flatpak), None);
+        let native = memory_limit_for(Path::new("/usr/bin/openscad"));
+        set_memory_limit(None);
+        assert_eq!(native, MEMORY_LIMIT_SUPPORTED.then_some(2048));
+    }
+
+    #[test]
+    fn recognizes_allocation_failures() {
+        assert!(ran_out_of_memory(
+            "terminate called after throwing an instance of 'std::bad_alloc'",
+            134
+        ));
+        assert!(ran_out_of_memory("ERROR: Cannot allocate memory", -1));
+        assert!(!ran_out_of_memory("", -1));
+        assert!(!ran_out_of_memory("ERROR: Parser error", 1));
+
+        let message = limit_message(ExceededLimit::Timeout, Duration::from_secs(30), 0);
+        assert!(message.starts_with("ERROR: Render timed out after 30s"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn memory_limit_applies_to_the_child() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "ulimit -v"]);
+        limit_memory(&mut command, 1024);
+        let output = command.output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "1048576");
+    }
+}
@@ -0,0 +1,299 @@
+/**
+ * 2D profile measurements and drawings
+ *
+ * A profile is the set of closed outlines a 2D design exports to (SVG or
+ * DXF). OpenSCAD fills them with the even-odd rule, so an outline nested
+ * inside an odd number of others is a hole and its area is subtracted.
+ * Drawings are standalone SVGs in millimetres with Y up, optionally
+ * annotated with overall width and height dimension lines and the area, the
+ * way a laser-cutting or machining drawing would show them.
+ */
+use crate::outline::Point;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileMeasurements {
+    pub bounding_box_min: Point,
+    pub bounding_box_max: Point,
+    pub width: f64,
+    pub height: f64,
+    /// Filled area, holes excluded
+    pub area: f64,
+    /// Total length of all outlines (the cut length when laser cutting)
+    pub perimeter: f64,
+    pub outline_count: usize,
+    pub hole_count: usize,
+}
+
+/// Shoelace area, positive for counter-clockwise outlines
+pub fn signed_area(path: &[Point]) -> f64 {
+    let twice: f64 = path
+        .iter()
+        .zip(path.iter().cycle().skip(1))
+        .map(|(a, b)| a[0] * b[1] - b[0] * a[1])
+        .sum();
+    twice / 2.0
+}
+
+fn perimeter(path: &[Point]) -> f64 {
+    path.iter()
+        .zip(path.iter().cycle().skip(1))
+        .map(|(a, b)| (a[0] - b[0]).hypot(a[1] - b[1]))
+        .sum()
+}
+
+/// Even-odd point-in-polygon test
+fn contains(path: &[Point], point: Point) -> bool {
+    let mut inside = false;
+    for (a, b) in path.iter().zip(path.iter().cycle().skip(1)) {
+        if (a[1] > point[1]) != (b[1] > point[1]) {
+            let x = a[0] + (point[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+            if point[0] < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// How many other outlines each outline lies inside
+fn nesting_depths(paths: &[Vec<Point>]) -> Vec<usize> {
+    paths
+        .iter()
+        .enumerate()
+        .map(|(index, path)| {
+            let probe = path[0];
+            paths
+                .iter()
+                .enumerate()
+                .filter(|(other, outline)| *other != index && contains(outline, probe))
+                .count()
+        })
+        .collect()
+}
+
+pub fn measure_profile(paths: &[Vec<Point>]) -> Result<ProfileMeasurements, String> {
+    let paths: Vec<Vec<Point>> = paths.iter().filter(|p| p.len() >= 3).cloned().collect();
+    if paths.is_empty() {
+        return Err("The profile has no closed outlines".into());
+    }
+    let (mut min, mut max) = ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]);
+    for point in paths.iter().flatten() {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(point[axis]);
+            max[axis] = max[axis].max(point[axis]);
+        }
+    }
+
+    let depths = nesting_depths(&paths);
+    let area = paths
+        .iter()
+        .zip(&depths)
+        .map(|(path, depth)| {
+            let area = signed_area(path).abs();
+            if depth % 2 == 0 {
+                area
+            } else {
+                -area
+            }
+        })
+        .sum::<f64>()
+        .max(0.0);
+
+    Ok(ProfileMeasurements {
+        bounding_box_min: min,
+        bounding_box_max: max,
+        width: max[0] - min[0],
+        height: max[1] - min[1],
+        area,
+        perimeter: paths.iter().map(|path| perimeter(path)).sum(),
+        outline_count: paths.len(),
+        hole_count: depths.iter().filter(|depth| *depth % 2 == 1).count(),
+    })
+}
+
+fn number(value: f64) -> String {
+    let text = format!("{value:.3}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Label for a length in millimetres
+fn length_label(value: f64) -> String {
+    format!("{} mm", number((value * 100.0).round() / 100.0))
+}
+
+/// SVG of the profile, with dimension lines when `dimensions` is given.
+/// Y is flipped so the drawing reads upright; one user unit is 1 mm.
+pub fn profile_svg(paths: &[Vec<Point>], dimensions: Option<&ProfileMeasurements>) -> String {
+    let measurements = match dimensions {
+        Some(measurements) => measurements.clone(),
+        None => match measure_profile(paths) {
+            Ok(measurements) => measurements,
+            Err(_) => return r#"<svg xmlns="http://www.w3.org/2000/svg"/>"#.to_string(),
+        },
+    };
+    let [min_x, min_y] = measurements.bounding_box_min;
+    let [max_x, max_y] = measurements.bounding_box_max;
+    let extent = measurements.width.max(measurements.height).max(1e-3);
+    let font = extent * 0.045;
+    let gap = extent * 0.08;
+    // Room for the dimension lines and labels around the profile
+    let (left, bottom) = match dimensions {
+        Some(_) => (gap + font * 2.0, gap + font * 3.5),
+        None => (extent * 0.05, extent * 0.05),
+    };
+    let margin = extent * 0.05;
+    let view = [
+        min_x - left,
+        -max_y - margin,
+        measurements.width + left + margin,
+        measurements.height + bottom + margin,
+    ];
+
+    let outline_data: Vec<String> = paths
+        .iter()
+        .filter(|path| path.len() >= 3)
+        .map(|path| {
+            let points: Vec<String> = path
+                .iter()
+                .map(|p| format!("{},{}", number(p[0]), number(-p[1])))
+                .collect();
+            format!("M{}Z", points.join(" L"))
+        })
+        .collect();
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}mm" height="{}mm" viewBox="{} {} {} {}">"#,
+        number(view[2]),
+        number(view[3]),
+        number(view[0]),
+        number(view[1]),
+        number(view[2]),
+        number(view[3]),
+    );
+    svg.push_str(&format!(
+        r##"<path d="{}" fill="#dbe7f7" fill-rule="evenodd" stroke="#1f5fae" stroke-width="1" vector-effect="non-scaling-stroke"/>"##,
+        outline_data.join(" ")
+    ));
+
+    if dimensions.is_some() {
+        let style = r##"stroke="#444" stroke-width="1" vector-effect="non-scaling-stroke""##;
+        let text = |x: f64, y: f64, extra: &str, label: &str| {
+            format!(
+                r##"<text x="{}" y="{}" font-size="{}" font-family="sans-serif" fill="#222" text-anchor="middle"{extra}>{label}</text>"##,
+                number(x),
+                number(y),
+                number(font),
+            )
+        };
+        let arrow = font * 0.6;
+
+        // Width, below the profile
+        let y = -min_y + gap;
+        svg.push_str(&format!(
+            r#"<g {style} fill="none"><path d="M{x0},{top} V{y} M{x1},{top} V{y} M{x0},{y} H{x1} M{x0},{y} l{arrow},{half} M{x0},{y} l{arrow},-{half} M{x1},{y} l-{arrow},{half} M{x1},{y} l-{arrow},-{half}"/></g>"#,
+            x0 = number(min_x),
+            x1 = number(max_x),
+            top = number(-min_y + gap * 0.25),
+            y = number(y),
+            arrow = number(arrow),
+            half = number(arrow / 2.0),
+        ));
+        svg.push_str(&text(
+            (min_x + max_x) / 2.0,
+            y + font * 1.2,
+            "",
+            &length_label(measurements.width),
+        ));
+
+        // Height, left of the profile
+        let x = min_x - gap;
+        svg.push_str(&format!(
+            r#"<g {style} fill="none"><path d="M{side},{y0} H{x} M{side},{y1} H{x} M{x},{y0} V{y1} M{x},{y0} l{half},{arrow} M{x},{y0} l-{half},{arrow} M{x},{y1} l{half},-{arrow} M{x},{y1} l-{half},-{arrow}"/></g>"#,
+            side = number(min_x - gap * 0.25),
+            x = number(x),
+            y0 = number(-max_y),
+            y1 = number(-min_y),
+            arrow = number(arrow),
+            half = number(arrow / 2.0),
+        ));
+        let label_x = x - font * 0.5;
+        let label_y = -(min_y + max_y) / 2.0;
+        svg.push_str(&text(
+            label_x,
+            label_y,
+            &format!(
+                r#" transform="rotate(-90 {} {})""#,
+                number(label_x),
+                number(label_y)
+            ),
+            &length_label(measurements.height),
+        ));
+
+        svg.push_str(&text(
+            (min_x + max_x) / 2.0,
+            y + font * 2.6,
+            "",
+            &format!(
+                "Area {} mm² · Perimeter {}",
+                number((measurements.area * 100.0).round() / 100.0),
+                length_label(measurements.perimeter)
+            ),
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(origin: Point, size: f64) -> Vec<Point> {
+        let [x, y] = origin;
+        vec![[x, y], [x + size, y], [x + size, y + size], [x, y + size]]
+    }
+
+    #[test]
+    fn measures_profiles_with_holes() {
+        // A 10 mm plate with a 4 mm hole holding a 2 mm island
+        let paths = vec![
+            square([0.0, 0.0], 10.0),
+            square([3.0, 3.0], 4.0),
+            square([4.0, 4.0], 2.0),
+            square([20.0, 0.0], 1.0),
+        ];
+        let measurements = measure_profile(&paths).unwrap();
+        assert_eq!(measurements.bounding_box_min, [0.0, 0.0]);
+        assert_eq!(measurements.bounding_box_max, [21.0, 10.0]);
+        assert_eq!(measurements.area, 100.0 - 16.0 + 4.0 + 1.0);
+        assert_eq!(measurements.perimeter, 40.0 + 16.0 + 8.0 + 4.0);
+        assert_eq!(measurements.outline_count, 4);
+        assert_eq!(measurements.hole_count, 1);
+        assert!(measure_profile(&[]).is_err());
+
+        let mut outline = square([0.0, 0.0], 2.0);
+        assert_eq!(signed_area(&outline), 4.0);
+        outline.reverse();
+        assert_eq!(signed_area(&outline), -4.0);
+    }
+
+    #[test]
+    fn draws_dimensioned_profiles() {
+        let paths = vec![square([0.0, 0.0], 10.0)];
+        let plain = profile_svg(&paths, None);
+        assert!(plain.contains("M0,0 L10,0 L10,-10 L0,-10Z"));
+        assert!(!plain.contains("<text"));
+
+        let measurements = measure_profile(&paths).unwrap();
+        let dimensioned = profile_svg(&paths, Some(&measurements));
+        assert_eq!(dimensioned.matches("10 mm</text>").count(), 2);
+        assert!(dimensioned.contains("Area 100 mm² · Perimeter 40 mm"));
+    }
+}
@@ -0,0 +1,353 @@
+/**
+ * Multi-file project model
+ *
+ * An open project folder tracks its file list, which file renders start from
+ * (the entry file) and which one the editor shows (the active file). The
+ * active file's text lives in `EditorState::current_code` like any other
+ * buffer; every other file that has been opened keeps its buffer here, with
+ * the last saved text so dirty state survives switching between files.
+ */
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub struct ProjectBuffer {
+    pub content: String,
+    /// Text as last read from or written to disk
+    pub saved: String,
+}
+
+impl ProjectBuffer {
+    pub fn is_dirty(&self) -> bool {
+        self.content != self.saved
+    }
+}
+
+/// How a file's new content on disk relates to its editor buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskChange {
+    /// Matches what was last loaded or saved (e.g. the app's own save)
+    Unchanged,
+    /// Not open, or open without unsaved edits
+    Clean,
+    /// Open with unsaved edits that the disk version would replace
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFileEntry {
+    /// Root-relative, `/`-separated path
+    pub path: String,
+    pub is_open: bool,
+    pub is_dirty: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSnapshot {
+    pub root: String,
+    pub entry_file: String,
+    pub active_file: String,
+    pub files: Vec<ProjectFileEntry>,
+}
+
+/// Inputs for rendering the entry file against the project directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectRenderInputs {
+    pub code: String,
+    pub input_path: String,
+    pub working_dir: String,
+    /// Unsaved buffers other than the entry file
+    pub auxiliary_files: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub root: String,
+    pub entry_file: String,
+    pub active_file: String,
+    files: Vec<String>,
+    buffers: BTreeMap<String, ProjectBuffer>,
+}
+
+impl Project {
+    /// `files` are root-relative paths; `entry_file` becomes the active file
+    pub fn new(root: String, files: Vec<String>, entry_file: String) -> Self {
+        Self {
+            root,
+            active_file: entry_file.clone(),
+            entry_file,
+            files,
+            buffers: BTreeMap::new(),
+        }
+    }
+
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    pub fn set_files(&mut self, files: Vec<String>) {
+        self.files = files;
+    }
+
+    pub fn buffer(&self, path: &str) -> Option<&ProjectBuffer> {
+        self.buffers.get(path)
+    }
+
+    /// Track a file that was just loaded from disk
+    pub fn insert_loaded(&mut self, path: &str, content: String) {
+        self.buffers.insert(
+            path.to_string(),
+            ProjectBuffer {
+                saved: content.clone(),
+                content,
+            },
+        );
+        if !self.files.iter().any(|file| file == path) {
+            self.files.push(path.to_string());
+            self.files.sort();
+        }
+    }
+
+    /// Record the editor text of `path`; returns whether it is now dirty
+    pub fn update(&mut self, path: &str, content: String) -> bool {
+        match self.buffers.get_mut(path) {
+            Some(buffer) => {
+                buffer.content = content;
+                buffer.is_dirty()
+            }
+            None => false,
+        }
+    }
+
+    pub fn mark_saved(&mut self, path: &str) {
+        if let Some(buffer) = self.buffers.get_mut(path) {
+            buffer.saved = buffer.content.clone();
+        }
+    }
+
+    /// Classify a change of `path` on disk (`None` when it was removed)
+    pub fn disk_change(&self, path: &str, disk: Option<&str>) -> DiskChange {
+        match self.buffers.get(path) {
+            None => DiskChange::Clean,
+            Some(buffer) if disk == Some(buffer.saved.as_str()) => DiskChange::Unchanged,
+            Some(buffer) if buffer.is_dirty() => DiskChange::Conflict,
+            Some(_) => DiskChange::Clean,
+        }
+    }
+
+    /// Keep the editor text of `path` over a newer disk version, which
+    /// becomes its saved state so the buffer stays dirty
+    pub fn keep_over_disk(&mut self, path: &str, disk: String) {
+        if let Some(buffer) = self.buffers.get_mut(path) {
+            buffer.saved = disk;
+        }
+    }
+
+    /// Root-relative, `/`-separated form of the absolute `path`, or `None`
+    /// when it is outside the project
+    pub fn relative_path(&self, path: &Path) -> Option<String> {
+        let relative = path
+            .strip_prefix(&self.root)
+            .ok()?
+            .components()
+            .map(|component| component.as_os_str().to_str())
+            .collect::<Option<Vec<_>>>()?;
+        (!relative.is_empty()).then(|| relative.join("/"))
+    }
+
+    /// The active file, if the editor document at `document_path` shows it.
+    /// Untitled documents and files outside the project show no project file.
+    pub fn editor_file(&self, document_path: Option<&str>) -> Option<&str> {
+        let relative = self.relative_path(Path::new(document_path?))?;
+        (relative == self.active_file).then_some(self.active_file.as_str())
+    }
+
+    /// Record that the app itself wrote `content` to the absolute `path`, so
+    /// the file watcher doesn't report the write as an external change. Open
+    /// buffers take the written text as both their content and saved state.
+    pub fn mark_written(&mut self, path: &Path, content: &str) {
+        let Some(relative) = self.relative_path(path) else {
+            return;
+        };
+        if let Some(buffer) = self.buffers.get_mut(&relative) {
+            buffer.content = content.to_string();
+            buffer.saved = content.to_string();
+        }
+    }
+
+    pub fn dirty_files(&self) -> Vec<String> {
+        self.buffers
+            .iter()
+            .filter(|(_, buffer)| buffer.is_dirty())
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    pub fn snapshot(&self) -> ProjectSnapshot {
+        ProjectSnapshot {
+            root: self.root.clone(),
+            entry_file: self.entry_file.clone(),
+            active_file: self.active_file.clone(),
+            files: self
+                .files
+                .iter()
+                .map(|path| ProjectFileEntry {
+                    path: path.clone(),
+                    is_open: self.buffers.contains_key(path),
+                    is_dirty: self.buffers.get(path).is_some_and(ProjectBuffer::is_dirty),
+                })
+                .collect(),
+        }
+    }
+
+    /// Render inputs for the entry file. `active_code` is the editor text of
+    /// the active file, which may be newer than its tracked buffer.
+    pub fn render_inputs(&self, active_code: &str) -> Result<ProjectRenderInputs, String> {
+        let text = |path: &str| -> Result<String, String> {
+            if path == self.active_file {
+                return Ok(active_code.to_string());
+            }
+            match self.buffers.get(path) {
+                Some(buffer) => Ok(buffer.content.clone()),
+                None => std::fs::read_to_string(Path::new(&self.root).join(path))
+                    .map_err(|e| format!("Failed to read entry file {path}: {e}")),
+            }
+        };
+
+        let mut auxiliary_files = HashMap::new();
+        for (path, buffer) in &self.buffers {
+            if *path == self.entry_file {
+                continue;
+            }
+            let content = text(path)?;
+            if content != buffer.saved {
+                auxiliary_files.insert(path.clone(), content);
+            }
+        }
+        Ok(ProjectRenderInputs {
+            code: text(&self.entry_file)?,
+            input_path: self.entry_file.clone(),
+            working_dir: self.root.clone(),
+            auxiliary_files,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> Project {
+        let mut project = Project::new(
+            "/tmp/project".into(),
+            vec!["main.scad".into(), "parts/gear.scad".into()],
+            "main.scad".into(),
+        );
+        project.insert_loaded("main.scad", "use <parts/gear.scad>\ngear();".into());
+        project.insert_loaded("parts/gear.scad", "module gear() cube(1);".into());
+        project
+    }
+
+    #[test]
+    fn tracks_dirty_buffers() {
+        let mut project = project();
+        assert!(project.dirty_files().is_empty());
+
+        assert!(project.update("parts/gear.scad", "module gear() cube(2);".into()));
+        assert_eq!(project.dirty_files(), vec!["parts/gear.scad"]);
+        let snapshot = project.snapshot();
+        assert!(snapshot.files[1].is_dirty && snapshot.files[1].is_open);
+
+        project.mark_saved("parts/gear.scad");
+        assert!(project.dirty_files().is_empty());
+        assert!(!project.update("missing.scad", "x".into()));
+    }
+
+    #[test]
+    fn classifies_disk_changes() {
+        let mut project = project();
+        let saved = "module gear() cube(1);";
+        assert_eq!(
+            project.disk_change("parts/gear.scad", Some(saved)),
+            DiskChange::Unchanged
+        );
+        assert_eq!(
+            project.disk_change("parts/gear.scad", Some("module gear() cube(5);")),
+            DiskChange::Clean
+        );
+        assert_eq!(project.disk_change("other.scad", None), DiskChange::Clean);
+
+        project.update("parts/gear.scad", "module gear() cube(2);".into());
+        assert_eq!(
+            project.disk_change("parts/gear.scad", Some("module gear() cube(5);")),
+            DiskChange::Conflict
+        );
+        project.keep_over_disk("parts/gear.scad", "module gear() cube(5);".into());
+        assert_eq!(project.dirty_files(), vec!["parts/gear.scad"]);
+        assert_eq!(
+            project.disk_change("parts/gear.scad", Some("module gear() cube(5);")),
+            DiskChange::Unchanged
+        );
+    }
+
+    #[test]
+    fn restored_files_are_not_external_changes() {
+        let mut project = project();
+        project.update("parts/gear.scad", "module gear() cube(2);".into());
+
+        let restored = "module gear() cube(3);";
+        project.mark_written(Path::new("/tmp/project/parts/gear.scad"), restored);
+        assert!(project.dirty_files().is_empty());
+        assert_eq!(
+            project.disk_change("parts/gear.scad", Some(restored)),
+            DiskChange::Unchanged
+        );
+
+        project.mark_written(Path::new("/tmp/elsewhere/main.scad"), restored);
+        assert_eq!(
+            project.buffer("main.scad").unwrap().content,
+            "use <parts/gear.scad>\ngear();"
+        );
+    }
+
+    #[test]
+    fn only_the_active_file_is_shown_in_the_editor() {
+        let mut project = project();
+        project.active_file = "parts/gear.scad".into();
+
+        assert_eq!(
+            project.editor_file(Some("/tmp/project/parts/gear.scad")),
+            Some("parts/gear.scad")
+        );
+        assert_eq!(project.editor_file(Some("/tmp/project/main.scad")), None);
+        assert_eq!(project.editor_file(Some("/tmp/elsewhere/gear.scad")), None);
+        assert_eq!(project.editor_file(None), None);
+        assert_eq!(project.relative_path(Path::new("/tmp/project")), None);
+    }
+
+    #[test]
+    fn renders_entry_file_with_unsaved_dependencies() {
+        let mut project = project();
+        project.active_file = "parts/gear.scad".into();
+        let inputs = project.render_inputs("module gear() cube(3);").unwrap();
+
+        assert_eq!(inputs.code, "use <parts/gear.scad>\ngear();");
+        assert_eq!(inputs.input_path, "main.scad");
+        assert_eq!(inputs.working_dir, "/tmp/project");
+        assert_eq!(
+            inputs
+                .auxiliary_files
+                .get("parts/gear.scad")
+                .map(String::as_str),
+            Some("module gear() cube(3);")
+        );
+
+        project.active_file = "main.scad".into();
+        let inputs = project.render_inputs("gear();").unwrap();
+        assert_eq!(inputs.code, "gear();");
+        assert!(inputs.auxiliary_files.is_empty());
+    }
+}
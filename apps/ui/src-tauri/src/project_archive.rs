@@ -0,0 +1,220 @@
+/**
+ * Project archives (.zip)
+ *
+ * A project archive holds the project files (.scad, .h, .json parameter
+ * files) and data assets under their root-relative paths. Unpacking ignores
+ * any other entries and refuses paths that would land outside the target
+ * directory.
+ */
+use crate::project_files::{is_asset_file, is_project_file};
+use std::fs;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+pub const MAX_ARCHIVE_ENTRIES: usize = 2048;
+/// Total uncompressed size accepted when unpacking
+pub const MAX_ARCHIVE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Write `files` (root-relative, `/`-separated) from `root` into a zip
+pub fn pack<W: Write + Seek>(root: &Path, files: &[String], writer: W) -> Result<(), String> {
+    let mut zip = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for file in files {
+        let bytes = fs::read(root.join(file)).map_err(|e| format!("Failed to read {file}: {e}"))?;
+        zip.start_file(file.as_str(), options)
+            .map_err(|e| format!("Failed to add {file} to the archive: {e}"))?;
+        zip.write_all(&bytes)
+            .map_err(|e| format!("Failed to add {file} to the archive: {e}"))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish the archive: {e}"))?;
+    Ok(())
+}
+
+/// Extract the project files of an archive into `dest`, returning their
+/// sorted relative paths. A single top-level folder wrapping everything
+/// (as produced by "compress folder") is stripped.
+pub fn unpack<R: Read + Seek>(reader: R, dest: &Path) -> Result<Vec<String>, String> {
+    unpack_with_limit(reader, dest, MAX_ARCHIVE_ENTRIES)
+}
+
+/// `unpack` for archives with up to `max_entries` entries of any kind, such
+/// as library source archives that also carry docs and images
+pub fn unpack_with_limit<R: Read + Seek>(
+    reader: R,
+    dest: &Path,
+    max_entries: usize,
+) -> Result<Vec<String>, String> {
+    unpack_matching(reader, dest, max_entries, |path| {
+        is_project_file(path) || is_asset_file(path)
+    })
+}
+
+/// `unpack_with_limit` extracting the entries `keep` accepts instead of
+/// project files and data assets
+pub fn unpack_matching<R: Read + Seek>(
+    reader: R,
+    dest: &Path,
+    max_entries: usize,
+    keep: impl Fn(&str) -> bool,
+) -> Result<Vec<String>, String> {
+    let mut archive = ZipArchive::new(reader).map_err(|e| format!("Invalid zip archive: {e}"))?;
+    if archive.len() > max_entries {
+        return Err(format!(
+            "Archive has {} entries; at most {max_entries} are supported",
+            archive.len()
+        ));
+    }
+
+    let mut entries = Vec::new();
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read archive entry: {e}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(path) = entry.enclosed_name() else {
+            continue;
+        };
+        let relative = path.to_string_lossy().replace('\\', "/");
+        let hidden = relative
+            .split('/')
+            .any(|segment| segment.starts_with('.') || segment == "__MACOSX");
+        if !hidden && keep(&relative) {
+            entries.push((index, relative));
+        }
+    }
+    if entries.is_empty() {
+        return Err("Archive contains no OpenSCAD project files".into());
+    }
+
+    let wrapper = entries[0]
+        .1
+        .split_once('/')
+        .map(|(dir, _)| format!("{dir}/"));
+    let strip = wrapper.filter(|prefix| entries.iter().all(|(_, path)| path.starts_with(prefix)));
+
+    let mut total = 0u64;
+    let mut files = Vec::new();
+    for (index, relative) in entries {
+        let entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read archive entry: {e}"))?;
+        let size = entry.size();
+        total += size;
+        if total > MAX_ARCHIVE_BYTES {
+            return Err(format!(
+                "Archive expands to more than {} MB",
+                MAX_ARCHIVE_BYTES / (1024 * 1024)
+            ));
+        }
+        let relative = match &strip {
+            Some(prefix) => relative[prefix.len()..].to_string(),
+            None => relative,
+        };
+        let target = dest.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        let mut bytes = Vec::with_capacity(size as usize);
+        entry
+            .take(size)
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to extract {relative}: {e}"))?;
+        fs::write(&target, bytes).map_err(|e| format!("Failed to write {relative}: {e}"))?;
+        files.push(relative);
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Entry file of an unpacked project: `main.scad`, else a top-level .scad
+/// named like the archive, else the first top-level .scad
+pub fn main_file(files: &[String], archive_stem: &str) -> Option<String> {
+    let scad: Vec<&String> = files
+        .iter()
+        .filter(|file| file.to_ascii_lowercase().ends_with(".scad"))
+        .collect();
+    let top_level: Vec<&String> = scad
+        .iter()
+        .copied()
+        .filter(|file| !file.contains('/'))
+        .collect();
+    let preferred = ["main.scad".to_string(), format!("{archive_stem}.scad")];
+    preferred
+        .iter()
+        .find(|name| top_level.contains(name))
+        .or(top_level.first().copied())
+        .or(scad.first().copied())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn round_trips_project_structure() {
+        let base = std::env::temp_dir()
+            .join("openscad-studio-archive-tests")
+            .join(uuid::Uuid::new_v4().to_string());
+        let root = base.join("project");
+        fs::create_dir_all(root.join("lib")).unwrap();
+        fs::write(root.join("box.scad"), "include <lib/consts.h>\ncube(size);").unwrap();
+        fs::write(root.join("lib/consts.h"), "size = 10;").unwrap();
+        fs::write(root.join("lib/height.dat"), "1 2\n3 4\n").unwrap();
+        let files = vec![
+            "box.scad".to_string(),
+            "lib/consts.h".to_string(),
+            "lib/height.dat".to_string(),
+        ];
+
+        let mut buffer = Cursor::new(Vec::new());
+        pack(&root, &files, &mut buffer).unwrap();
+        let dest = base.join("unpacked");
+        let unpacked = unpack(Cursor::new(buffer.into_inner()), &dest).unwrap();
+
+        assert_eq!(unpacked, files);
+        assert_eq!(
+            fs::read_to_string(dest.join("lib/consts.h")).unwrap(),
+            "size = 10;"
+        );
+        assert_eq!(main_file(&unpacked, "project").as_deref(), Some("box.scad"));
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn strips_wrapper_folder_and_skips_unsafe_entries() {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut zip = ZipWriter::new(&mut buffer);
+            let options = SimpleFileOptions::default();
+            for (name, body) in [
+                ("gear/main.scad", "use <parts/tooth.scad>"),
+                ("gear/parts/tooth.scad", "module tooth() {}"),
+                ("gear/notes.txt", "ignored"),
+                ("gear/../../escape.scad", "cube(1);"),
+                ("__MACOSX/gear/._main.scad", ""),
+            ] {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(body.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+
+        let dest = std::env::temp_dir()
+            .join("openscad-studio-archive-tests")
+            .join(uuid::Uuid::new_v4().to_string());
+        let files = unpack(Cursor::new(buffer.into_inner()), &dest).unwrap();
+        assert_eq!(files, vec!["main.scad", "parts/tooth.scad"]);
+        assert_eq!(main_file(&files, "gear").as_deref(), Some("main.scad"));
+
+        let _ = fs::remove_dir_all(dest);
+    }
+}
@@ -0,0 +1,492 @@
+/**
+ * Project file classification
+ *
+ * Besides `.scad` sources, projects carry `.h` include headers and `.json`
+ * customizer parameter files. Parameter files use OpenSCAD's customizer
+ * format: `{"parameterSets": {"<name>": {"<variable>": "<value>"}}, ...}`.
+ * Data assets (`.csv`, `.dat`, `.png`) are read by `import()`/`surface()`
+ * and count as render dependencies, as do sources pulled in through
+ * `include`/`use`.
+ */
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+pub const PROJECT_FILE_EXTENSIONS: &[&str] = &["scad", "h", "json"];
+pub const ASSET_FILE_EXTENSIONS: &[&str] = &["csv", "dat", "png"];
+
+/// Statements/arguments that make OpenSCAD read files
+const FILE_READERS: &[&str] = &["import", "include", "use", "surface", "file"];
+
+fn extension(path: &str) -> Option<String> {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+/// Files that belong to the project model (listed, editable, sent to renders)
+pub fn is_project_file(path: &str) -> bool {
+    extension(path).is_some_and(|ext| PROJECT_FILE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+pub fn is_asset_file(path: &str) -> bool {
+    extension(path).is_some_and(|ext| ASSET_FILE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+pub fn is_parameter_file(path: &str) -> bool {
+    extension(path).as_deref() == Some("json")
+}
+
+/// Sources OpenSCAD can pull in through `include`/`use`
+fn is_source_file(path: &str) -> bool {
+    matches!(extension(path).as_deref(), Some("scad" | "h"))
+}
+
+/// `code` with `//` and `/* */` comments blanked out (newlines kept), leaving
+/// string literals alone
+fn strip_comments(code: &str) -> String {
+    let mut out = String::with_capacity(code.len());
+    let mut chars = code.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&next| next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                    }
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Quoted or `<...>` path literals following a file-reading keyword, outside
+/// comments. Keywords only count as whole words, so `my_import(...)` or
+/// `use_count` don't.
+pub fn referenced_paths(code: &str) -> Vec<String> {
+    let code = strip_comments(code);
+    let mut paths = Vec::new();
+    for keyword in FILE_READERS {
+        for (index, _) in code.match_indices(keyword) {
+            let end = index + keyword.len();
+            let before = code[..index].chars().next_back();
+            let next = code[end..].chars().next();
+            if before.is_some_and(is_identifier_char) || next.is_some_and(is_identifier_char) {
+                continue;
+            }
+            let after = code[end..].trim_start();
+            let after = after
+                .strip_prefix('(')
+                .or_else(|| after.strip_prefix('='))
+                .unwrap_or(after)
+                .trim_start();
+            let after = after.strip_prefix("file").unwrap_or(after).trim_start();
+            let after = after.strip_prefix('=').unwrap_or(after).trim_start();
+            let literal = after
+                .strip_prefix('"')
+                .and_then(|s| s.split('"').next())
+                .or_else(|| after.strip_prefix('<').and_then(|s| s.split('>').next()));
+            if let Some(literal) = literal {
+                paths.push(literal.to_string());
+            }
+        }
+    }
+    paths
+}
+
+/// Calls that read a file named by their first or `file =` argument
+const FILE_CALLS: &[&str] = &["import", "surface"];
+
+/// The argument list of the call whose `(` starts `code`, up to the matching
+/// `)`, split at top-level commas
+fn call_arguments(code: &str) -> Vec<&str> {
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let mut start = 1;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, c) in code.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    arguments.push(&code[start..index]);
+                    break;
+                }
+            }
+            ',' if depth == 1 => {
+                arguments.push(&code[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    arguments
+}
+
+/// Whether `value` is a single string literal and nothing else
+fn is_string_literal(value: &str) -> bool {
+    let Some(rest) = value.trim().strip_prefix('"') else {
+        return false;
+    };
+    let mut chars = rest.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return rest[index + 1..].trim().is_empty(),
+            _ => {}
+        }
+    }
+    false
+}
+
+/// `import(...)`/`surface(...)` calls, outside comments, whose file argument
+/// is computed (a variable, `str(...)`, ...) rather than a string literal, so
+/// the path it reads can't be known before the render runs.
+pub fn computed_file_reads(code: &str) -> Vec<String> {
+    let code = strip_comments(code);
+    let mut calls = Vec::new();
+    for keyword in FILE_CALLS {
+        for (index, _) in code.match_indices(keyword) {
+            let end = index + keyword.len();
+            let before = code[..index].chars().next_back();
+            let next = code[end..].chars().next();
+            if before.is_some_and(is_identifier_char) || next.is_some_and(is_identifier_char) {
+                continue;
+            }
+            let call = code[end..].trim_start();
+            if !call.starts_with('(') {
+                continue;
+            }
+            let arguments = call_arguments(call);
+            let named_file = arguments.iter().find_map(|argument| {
+                let value = argument.trim_start().strip_prefix("file")?.trim_start();
+                value
+                    .strip_prefix('=')
+                    .filter(|value| !value.starts_with('='))
+            });
+            let positional = arguments.first().filter(|argument| {
+                let argument = argument.trim_start();
+                let name_len = argument
+                    .find(|c: char| !is_identifier_char(c))
+                    .unwrap_or(argument.len());
+                let rest = argument[name_len..].trim_start();
+                name_len == 0 || !rest.starts_with('=') || rest.starts_with("==")
+            });
+            let Some(file) = named_file.or(positional.copied()) else {
+                continue;
+            };
+            if !file.trim().is_empty() && !is_string_literal(file) {
+                let arguments = arguments.join(",");
+                calls.push(format!("{keyword}({})", arguments.trim()));
+            }
+        }
+    }
+    calls
+}
+
+/// Digest of an asset's contents, or `None` when it can't be read. Assets
+/// can be large (heightmaps, images) and are hashed on every cache lookup,
+/// so digests are kept per path and only recomputed when the file's
+/// modification time or size changes.
+fn asset_digest(path: &Path) -> Option<u64> {
+    static DIGESTS: OnceLock<Mutex<HashMap<PathBuf, (SystemTime, u64, u64)>>> = OnceLock::new();
+    let digests = DIGESTS.get_or_init(Default::default);
+    let Ok(metadata) = std::fs::metadata(path) else {
+        digests.lock().unwrap().remove(path);
+        return None;
+    };
+    let modified = metadata.modified().ok();
+    if let Some(modified) = modified {
+        if let Some(&(cached_modified, len, digest)) = digests.lock().unwrap().get(path) {
+            if cached_modified == modified && len == metadata.len() {
+                return Some(digest);
+            }
+        }
+    }
+
+    let contents = std::fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    let digest = hasher.finish();
+    if let Some(modified) = modified {
+        digests
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), (modified, metadata.len(), digest));
+    }
+    Some(digest)
+}
+
+/// Hash the contents of every data asset referenced by `code` or the
+/// auxiliary sources, resolved against `base_dir`, adding their paths to
+/// `dependencies`. Missing files hash as absent so creating one later still
+/// changes the key.
+pub fn hash_referenced_assets<H: Hasher>(
+    hasher: &mut H,
+    code: &str,
+    auxiliary_files: &Option<HashMap<String, String>>,
+    base_dir: &Path,
+    dependencies: &mut Vec<PathBuf>,
+) {
+    let sources = std::iter::once(code).chain(
+        auxiliary_files
+            .iter()
+            .flat_map(|files| files.values().map(String::as_str)),
+    );
+    let mut assets: Vec<String> = sources
+        .flat_map(referenced_paths)
+        .filter(|path| is_asset_file(path))
+        .collect();
+    assets.sort();
+    assets.dedup();
+
+    for asset in assets {
+        asset.hash(hasher);
+        let path = base_dir.join(&asset);
+        asset_digest(&path).hash(hasher);
+        dependencies.push(path);
+    }
+}
+
+/// Hash every source reachable through `include`/`use` from `sources`,
+/// following nested includes. Each source is paired with the directory its
+/// relative includes resolve against (`None` when it has no location on
+/// disk); library paths are tried after it, as OpenSCAD does. Unresolved
+/// includes hash as absent so creating the file later still changes the key.
+/// Every path read, or tried for an unresolved include, is added to
+/// `dependencies`.
+pub fn hash_included_sources<H: Hasher>(
+    hasher: &mut H,
+    sources: &[(Option<PathBuf>, &str)],
+    library_paths: &[PathBuf],
+    dependencies: &mut Vec<PathBuf>,
+) {
+    let mut visited = HashSet::new();
+    let mut pending: Vec<(Option<PathBuf>, String)> = sources
+        .iter()
+        .map(|(dir, code)| (dir.clone(), code.to_string()))
+        .collect();
+
+    while let Some((dir, code)) = pending.pop() {
+        let mut includes: Vec<String> = referenced_paths(&code)
+            .into_iter()
+            .filter(|path| is_source_file(path))
+            .collect();
+        includes.sort();
+        includes.dedup();
+
+        for include in includes {
+            include.hash(hasher);
+            let candidates: Vec<PathBuf> = dir
+                .iter()
+                .chain(library_paths)
+                .map(|base| base.join(&include))
+                .collect();
+            let Some(path) = candidates.iter().find(|path| path.is_file()).cloned() else {
+                None::<String>.hash(hasher);
+                dependencies.extend(candidates);
+                continue;
+            };
+            dependencies.push(path.clone());
+            if !visited.insert(path.clone()) {
+                continue;
+            }
+            let contents = std::fs::read_to_string(&path).ok();
+            contents.hash(hasher);
+            if let Some(contents) = contents {
+                pending.push((path.parent().map(Path::to_path_buf), contents));
+            }
+        }
+    }
+}
+
+/// Names of the parameter sets in a customizer parameter file, sorted by name
+pub fn parameter_set_names(json: &str) -> Result<Vec<String>, String> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid parameter file: {e}"))?;
+    let sets = value
+        .get("parameterSets")
+        .and_then(Value::as_object)
+        .ok_or_else(|| "Parameter file has no \"parameterSets\" object".to_string())?;
+    Ok(sets.keys().cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_project_files() {
+        assert!(is_project_file("main.scad"));
+        assert!(is_project_file("lib/consts.H"));
+        assert!(is_project_file("presets.json"));
+        assert!(!is_project_file("logo.svg"));
+        assert!(is_parameter_file("presets.json"));
+        assert!(!is_parameter_file("main.scad"));
+        assert!(is_asset_file("terrain/height.DAT"));
+        assert!(is_asset_file("points.csv"));
+        assert!(!is_asset_file("main.scad"));
+    }
+
+    #[test]
+    fn finds_references_outside_comments_only() {
+        let code = r#"include <parts/gear.scad>
+// use <../commented.scad>
+/* import("/etc/passwd");
+   surface(file = "/tmp/x.dat"); */
+my_import("fake.stl");
+use_count = 3 > 2;
+label = "// not a comment"; import("logo.svg");
+"#;
+        assert_eq!(referenced_paths(code), vec!["logo.svg", "parts/gear.scad"]);
+    }
+
+    #[test]
+    fn finds_file_reads_with_computed_paths() {
+        let code = r#"p = "/etc/passwd";
+import(p);
+import(str("/et", "c/passwd"), convexity = 3);
+surface(file = p, center = true);
+import("logo.svg", center = true);
+surface(center = true, file = "map.png");
+import(file = "a, b.stl");
+// import(p);
+my_import(p);
+"#;
+        assert_eq!(
+            computed_file_reads(code),
+            vec![
+                "import(p)",
+                "import(str(\"/et\", \"c/passwd\"), convexity = 3)",
+                "surface(file = p, center = true)",
+            ]
+        );
+    }
+
+    #[test]
+    fn asset_changes_alter_dependency_hash() {
+        let dir = std::env::temp_dir()
+            .join("openscad-studio-project-files-tests")
+            .join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        let code = "surface(file = \"height.dat\");\nimport(\"logo.svg\");";
+        let key = || {
+            let mut hasher = DefaultHasher::new();
+            hash_referenced_assets(&mut hasher, code, &None, &dir, &mut Vec::new());
+            hasher.finish()
+        };
+
+        let missing = key();
+        std::fs::write(dir.join("height.dat"), "1 2\n3 4\n").unwrap();
+        let first = key();
+        // A different size changes the digest even within the same mtime tick
+        std::fs::write(dir.join("height.dat"), "1 2\n3 45\n").unwrap();
+        let second = key();
+        // Non-asset imports are not read.
+        std::fs::write(dir.join("logo.svg"), "<svg/>").unwrap();
+
+        assert_ne!(missing, first);
+        assert_ne!(first, second);
+        assert_eq!(second, key());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn nested_include_changes_alter_dependency_hash() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let dir = std::env::temp_dir()
+            .join("openscad-studio-project-files-tests")
+            .join(uuid::Uuid::new_v4().to_string());
+        let library = dir.join("libraries");
+        std::fs::create_dir_all(dir.join("parts")).unwrap();
+        std::fs::create_dir_all(&library).unwrap();
+        std::fs::write(dir.join("parts/body.scad"), "use <bolts.scad>\ncube(1);").unwrap();
+        let code = "include <parts/body.scad>\nbody();";
+        let key = || {
+            let mut hasher = DefaultHasher::new();
+            hash_included_sources(
+                &mut hasher,
+                &[(Some(dir.clone()), code)],
+                std::slice::from_ref(&library),
+                &mut Vec::new(),
+            );
+            hasher.finish()
+        };
+
+        let missing = key();
+        // Found through the library path, relative to nothing in the project.
+        std::fs::write(library.join("bolts.scad"), "module bolt() {}").unwrap();
+        let first = key();
+        std::fs::write(library.join("bolts.scad"), "module bolt() { cube(2); }").unwrap();
+        let second = key();
+        std::fs::write(dir.join("parts/body.scad"), "use <bolts.scad>\ncube(3);").unwrap();
+        let third = key();
+
+        assert_ne!(missing, first);
+        assert_ne!(first, second);
+        assert_ne!(second, third);
+        assert_eq!(third, key());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn reads_parameter_set_names() {
+        let json = r#"{
+            "parameterSets": { "small": { "size": "10" }, "large": { "size": "40" } },
+            "fileFormatVersion": "1"
+        }"#;
+        assert_eq!(parameter_set_names(json).unwrap(), vec!["large", "small"]);
+        assert!(parameter_set_names(r#"{"size": 1}"#).is_err());
+        assert!(parameter_set_names("not json").is_err());
+    }
+}
@@ -0,0 +1,670 @@
+/**
+ * QR code generation for embossed/debossed labels
+ *
+ * A small self-contained QR encoder (byte mode, versions 1–40, all four
+ * error-correction levels, automatic mask selection) following ISO/IEC
+ * 18004, plus an OpenSCAD emitter that merges dark modules into horizontal
+ * runs so the generated code stays compact.
+ */
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrEcc {
+    Low,
+    #[default]
+    Medium,
+    Quartile,
+    High,
+}
+
+impl QrEcc {
+    fn ordinal(self) -> usize {
+        self as usize
+    }
+
+    fn format_bits(self) -> u32 {
+        match self {
+            QrEcc::Low => 1,
+            QrEcc::Medium => 0,
+            QrEcc::Quartile => 3,
+            QrEcc::High => 2,
+        }
+    }
+}
+
+const ECC_CODEWORDS_PER_BLOCK: [[i8; 41]; 4] = [
+    [
+        -1, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        -1, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        -1, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        -1, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+const NUM_ERROR_CORRECTION_BLOCKS: [[i8; 41]; 4] = [
+    [
+        -1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12,
+        13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        -1, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        -1, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27,
+        29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        -1, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// Encoded symbol; `modules[y][x]` is true for dark modules
+#[derive(Debug, Clone)]
+pub struct QrCode {
+    pub version: usize,
+    pub size: usize,
+    pub modules: Vec<Vec<bool>>,
+}
+
+// ============================================================================
+// Capacity and error correction
+// ============================================================================
+
+fn num_raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let num_align = version / 7 + 2;
+        result -= (25 * num_align - 10) * num_align - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn num_data_codewords(version: usize, ecc: QrEcc) -> usize {
+    num_raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[ecc.ordinal()][version] as usize
+            * NUM_ERROR_CORRECTION_BLOCKS[ecc.ordinal()][version] as usize
+}
+
+/// GF(2^8) multiplication modulo x^8 + x^4 + x^3 + x^2 + 1
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    z as u8
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (value, &coefficient) in result.iter_mut().zip(divisor) {
+            *value ^= gf_multiply(coefficient, factor);
+        }
+    }
+    result
+}
+
+/// Split data into blocks, append ECC to each and interleave
+fn add_ecc_and_interleave(data: &[u8], version: usize, ecc: QrEcc) -> Vec<u8> {
+    let num_blocks = NUM_ERROR_CORRECTION_BLOCKS[ecc.ordinal()][version] as usize;
+    let block_ecc_len = ECC_CODEWORDS_PER_BLOCK[ecc.ordinal()][version] as usize;
+    let raw_codewords = num_raw_data_modules(version) / 8;
+    let num_short_blocks = num_blocks - raw_codewords % num_blocks;
+    let short_block_len = raw_codewords / num_blocks;
+
+    let divisor = reed_solomon_divisor(block_ecc_len);
+    let mut blocks = Vec::with_capacity(num_blocks);
+    let mut offset = 0;
+    for i in 0..num_blocks {
+        let len = short_block_len - block_ecc_len + usize::from(i >= num_short_blocks);
+        let mut block = data[offset..offset + len].to_vec();
+        offset += len;
+        let remainder = reed_solomon_remainder(&block, &divisor);
+        if i < num_short_blocks {
+            block.push(0);
+        }
+        block.extend(remainder);
+        blocks.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw_codewords);
+    for i in 0..blocks[0].len() {
+        for (j, block) in blocks.iter().enumerate() {
+            // Skip the padding byte of short blocks.
+            if i != short_block_len - block_ecc_len || j >= num_short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+/// Byte-mode data codewords for the smallest version that fits
+fn encode_data(data: &[u8], ecc: QrEcc) -> Result<(usize, Vec<u8>), String> {
+    let version = (1..=40)
+        .find(|&version| {
+            let count_bits = if version <= 9 { 8 } else { 16 };
+            4 + count_bits + data.len() * 8 <= num_data_codewords(version, ecc) * 8
+        })
+        .ok_or_else(|| format!("{} bytes is too long for a QR code", data.len()))?;
+
+    let mut bits: Vec<bool> = Vec::new();
+    let mut push = |value: u32, count: usize| {
+        bits.extend((0..count).rev().map(|i| (value >> i) & 1 != 0));
+    };
+    push(0b0100, 4);
+    push(data.len() as u32, if version <= 9 { 8 } else { 16 });
+    for &byte in data {
+        push(byte as u32, 8);
+    }
+
+    let capacity = num_data_codewords(version, ecc) * 8;
+    let terminator = (capacity - bits.len()).min(4);
+    bits.extend(std::iter::repeat_n(false, terminator));
+    bits.extend(std::iter::repeat_n(false, (8 - bits.len() % 8) % 8));
+
+    let mut codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|chunk| chunk.iter().fold(0u8, |acc, &bit| (acc << 1) | bit as u8))
+        .collect();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if codewords.len() * 8 >= capacity {
+            break;
+        }
+        codewords.push(pad);
+    }
+    Ok((version, codewords))
+}
+
+// ============================================================================
+// Module placement
+// ============================================================================
+
+struct Canvas {
+    size: usize,
+    modules: Vec<Vec<bool>>,
+    is_function: Vec<Vec<bool>>,
+}
+
+impl Canvas {
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y][x] = dark;
+        self.is_function[y][x] = true;
+    }
+
+    fn draw_finder(&mut self, cx: i32, cy: i32) {
+        for dy in -4..=4 {
+            for dx in -4..=4 {
+                let (x, y) = (cx + dx, cy + dy);
+                if (0..self.size as i32).contains(&x) && (0..self.size as i32).contains(&y) {
+                    let distance = dx.abs().max(dy.abs());
+                    self.set_function(x as usize, y as usize, distance != 2 && distance != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment(&mut self, cx: usize, cy: usize) {
+        for dy in -2i32..=2 {
+            for dx in -2i32..=2 {
+                let (x, y) = ((cx as i32 + dx) as usize, (cy as i32 + dy) as usize);
+                self.set_function(x, y, dx.abs().max(dy.abs()) != 1);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, ecc: QrEcc, mask: u32) {
+        let data = ecc.format_bits() << 3 | mask;
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = (data << 10 | remainder) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 != 0;
+        let size = self.size;
+
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self, version: usize) {
+        if version < 7 {
+            return;
+        }
+        let mut remainder = version as u32;
+        for _ in 0..12 {
+            remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+        }
+        let bits = (version as u32) << 12 | remainder;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    fn draw_codewords(&mut self, data: &[u8]) {
+        let mut index = 0;
+        let mut right = self.size as i32 - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..self.size {
+                for j in 0..2 {
+                    let x = (right - j) as usize;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        self.size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !self.is_function[y][x] && index < data.len() * 8 {
+                        self.modules[y][x] = (data[index >> 3] >> (7 - (index & 7))) & 1 != 0;
+                        index += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.is_function[y][x] {
+                    self.modules[y][x] = !self.modules[y][x];
+                }
+            }
+        }
+    }
+
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let get = |x: usize, y: usize, transpose: bool| {
+            if transpose {
+                self.modules[x][y]
+            } else {
+                self.modules[y][x]
+            }
+        };
+        let mut score = 0;
+
+        // Runs of five or more same-colored modules, and finder-like patterns.
+        const FINDER_LIKE: [bool; 11] = [
+            true, false, true, true, true, false, true, false, false, false, false,
+        ];
+        for transpose in [false, true] {
+            for y in 0..size {
+                let mut run = 1;
+                for x in 1..size {
+                    if get(x, y, transpose) == get(x - 1, y, transpose) {
+                        run += 1;
+                    } else {
+                        if run >= 5 {
+                            score += run - 2;
+                        }
+                        run = 1;
+                    }
+                }
+                if run >= 5 {
+                    score += run - 2;
+                }
+
+                for x in 0..size.saturating_sub(10) {
+                    let window = |i: usize| get(x + i, y, transpose);
+                    if (0..11).all(|i| window(i) == FINDER_LIKE[i])
+                        || (0..11).all(|i| window(i) == FINDER_LIKE[10 - i])
+                    {
+                        score += 40;
+                    }
+                }
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = self.modules[y][x];
+                if color == self.modules[y][x + 1]
+                    && color == self.modules[y + 1][x]
+                    && color == self.modules[y + 1][x + 1]
+                {
+                    score += 3;
+                }
+            }
+        }
+
+        let dark = self.modules.iter().flatten().filter(|&&m| m).count() as i64;
+        let total = (size * size) as i64;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        score + k as usize * 10
+    }
+}
+
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let size = version * 4 + 17;
+    let num_align = version / 7 + 2;
+    let step = (version * 8 + num_align * 3 + 5) / (num_align * 4 - 4) * 2;
+    let mut result = vec![6];
+    for i in 0..num_align - 1 {
+        result.insert(1, size - 7 - i * step);
+    }
+    result
+}
+
+/// Encode `data` as a QR symbol (byte mode) at the smallest fitting version
+pub fn encode(data: &[u8], ecc: QrEcc) -> Result<QrCode, String> {
+    let (version, codewords) = encode_data(data, ecc)?;
+    let all_codewords = add_ecc_and_interleave(&codewords, version, ecc);
+
+    let size = version * 4 + 17;
+    let mut canvas = Canvas {
+        size,
+        modules: vec![vec![false; size]; size],
+        is_function: vec![vec![false; size]; size],
+    };
+    for i in 0..size {
+        canvas.set_function(6, i, i % 2 == 0);
+        canvas.set_function(i, 6, i % 2 == 0);
+    }
+    canvas.draw_finder(3, 3);
+    canvas.draw_finder(size as i32 - 4, 3);
+    canvas.draw_finder(3, size as i32 - 4);
+    let positions = alignment_positions(version);
+    let last = positions.len().saturating_sub(1);
+    for (i, &y) in positions.iter().enumerate() {
+        for (j, &x) in positions.iter().enumerate() {
+            let is_edge = |k: usize| k == 0 || k == last;
+            let overlaps_finder = (i == 0 || j == 0) && is_edge(i) && is_edge(j);
+            if !overlaps_finder {
+                canvas.draw_alignment(x, y);
+            }
+        }
+    }
+    canvas.draw_format_bits(ecc, 0);
+    canvas.draw_version(version);
+    canvas.draw_codewords(&all_codewords);
+
+    let best_mask = (0..8)
+        .min_by_key(|&mask| {
+            canvas.apply_mask(mask);
+            canvas.draw_format_bits(ecc, mask);
+            let penalty = canvas.penalty();
+            canvas.apply_mask(mask);
+            penalty
+        })
+        .unwrap_or(0);
+    canvas.apply_mask(best_mask);
+    canvas.draw_format_bits(ecc, best_mask);
+
+    Ok(QrCode {
+        version,
+        size,
+        modules: canvas.modules,
+    })
+}
+
+// ============================================================================
+// OpenSCAD output
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrRelief {
+    /// Modules raised above the plate
+    #[default]
+    Emboss,
+    /// Modules cut into the plate
+    Deboss,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QrCodeOptions {
+    pub ecc: QrEcc,
+    /// Side length of the symbol in mm (excluding the quiet zone)
+    pub size: f64,
+    /// Emboss height / deboss depth in mm
+    pub height: f64,
+    /// Plate thickness in mm; 0 emits the modules alone
+    pub base_thickness: f64,
+    /// Quiet-zone margin in modules
+    pub quiet_zone: u32,
+    pub relief: QrRelief,
+}
+
+impl Default for QrCodeOptions {
+    fn default() -> Self {
+        Self {
+            ecc: QrEcc::Medium,
+            size: 30.0,
+            height: 1.0,
+            base_thickness: 2.0,
+            quiet_zone: 2,
+            relief: QrRelief::Emboss,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QrCodeSnippet {
+    pub code: String,
+    pub version: usize,
+    pub modules: usize,
+}
+
+/// Horizontal runs of dark modules as `[x, y, length]`, with y pointing up
+fn dark_runs(qr: &QrCode) -> Vec<[usize; 3]> {
+    let mut runs = Vec::new();
+    for (row, line) in qr.modules.iter().enumerate() {
+        let y = qr.size - 1 - row;
+        let mut x = 0;
+        while x < qr.size {
+            if line[x] {
+                let start = x;
+                while x < qr.size && line[x] {
+                    x += 1;
+                }
+                runs.push([start, y, x - start]);
+            } else {
+                x += 1;
+            }
+        }
+    }
+    runs
+}
+
+fn escape_comment(text: &str) -> String {
+    let text: String = text.chars().filter(|c| !c.is_control()).take(60).collect();
+    text.replace("*/", "* /")
+}
+
+/// OpenSCAD code for a QR plate encoding `data`
+pub fn qr_code_snippet(data: &str, options: &QrCodeOptions) -> Result<QrCodeSnippet, String> {
+    if data.is_empty() {
+        return Err("QR code data cannot be empty".into());
+    }
+    if options.size <= 0.0 || options.height <= 0.0 || options.base_thickness < 0.0 {
+        return Err("QR code size and height must be positive".into());
+    }
+    if options.relief == QrRelief::Deboss && options.base_thickness <= options.height {
+        return Err("Debossed QR codes need a base thicker than the cut depth".into());
+    }
+
+    let qr = encode(data.as_bytes(), options.ecc)?;
+    let runs: Vec<String> = dark_runs(&qr)
+        .iter()
+        .map(|[x, y, len]| format!("[{x},{y},{len}]"))
+        .collect();
+    let mut run_lines = String::new();
+    for (index, chunk) in runs.chunks(10).enumerate() {
+        if index > 0 {
+            run_lines.push_str(",\n");
+        }
+        run_lines.push_str("    ");
+        run_lines.push_str(&chunk.join(", "));
+    }
+
+    let body = match (options.relief, options.base_thickness > 0.0) {
+        (_, false) => "qr_modules(qr_height);".to_string(),
+        (QrRelief::Emboss, true) => "qr_plate();\ntranslate([0, 0, qr_base]) qr_modules(qr_height);"
+            .to_string(),
+        (QrRelief::Deboss, true) => {
+            "difference() {\n    qr_plate();\n    translate([0, 0, qr_base - qr_height]) qr_modules(qr_height + 0.01);\n}"
+                .to_string()
+        }
+    };
+
+    let code = format!(
+        "// QR code: \"{label}\" (version {version}, {count}x{count} modules)\n\
+         qr_size = {size};\n\
+         qr_height = {height};\n\
+         qr_base = {base};\n\
+         qr_quiet_zone = {quiet};\n\
+         qr_count = {count};\n\
+         qr_runs = [\n{run_lines}\n];\n\n\
+         module qr_modules(h) {{\n    \
+         scale([qr_size / qr_count, qr_size / qr_count, 1])\n        \
+         linear_extrude(h)\n            \
+         for (r = qr_runs) translate([r[0], r[1]]) square([r[2], 1]);\n}}\n\n\
+         module qr_plate() {{\n    \
+         margin = qr_quiet_zone * qr_size / qr_count;\n    \
+         translate([-margin, -margin, 0])\n        \
+         cube([qr_size + 2 * margin, qr_size + 2 * margin, qr_base]);\n}}\n\n\
+         {body}\n",
+        label = escape_comment(data),
+        version = qr.version,
+        count = qr.size,
+        size = options.size,
+        height = options.height,
+        base = options.base_thickness,
+        quiet = options.quiet_zone,
+    );
+
+    Ok(QrCodeSnippet {
+        code,
+        version: qr.version,
+        modules: qr.size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reed_solomon_matches_reference_codewords() {
+        // "HELLO WORLD" at 1-M, from the ISO 18004 worked example.
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        assert_eq!(
+            reed_solomon_remainder(&data, &reed_solomon_divisor(10)),
+            vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+        assert_eq!(num_data_codewords(1, QrEcc::Medium), 16);
+        assert_eq!(num_data_codewords(40, QrEcc::Low), 2956);
+        assert_eq!(alignment_positions(7), vec![6, 22, 38]);
+    }
+
+    #[test]
+    fn encodes_function_patterns_and_picks_version() {
+        let qr = encode(b"https://example.com", QrEcc::Medium).unwrap();
+        assert_eq!((qr.version, qr.size), (2, 25));
+        // Finder corners, separators, timing and the dark module.
+        for (x, y) in [(0, 0), (24, 0), (0, 24), (3, 3), (8, 17)] {
+            assert!(qr.modules[y][x], "({x}, {y}) should be dark");
+        }
+        for (x, y) in [(7, 7), (17, 7), (7, 17), (1, 1)] {
+            assert!(!qr.modules[y][x], "({x}, {y}) should be light");
+        }
+        assert!((8..17).all(|i| qr.modules[6][i] == (i % 2 == 0)));
+
+        let large = encode(&[b'a'; 500], QrEcc::High).unwrap();
+        assert_eq!(large.size, large.version * 4 + 17);
+        assert!(encode(&[0; 3000], QrEcc::Low).is_err());
+    }
+
+    #[test]
+    fn snippet_lists_dark_runs() {
+        let snippet = qr_code_snippet("hello", &QrCodeOptions::default()).unwrap();
+        assert_eq!(snippet.modules, 21);
+        assert!(snippet.code.contains("qr_count = 21;"));
+        // Top-left finder row: 7 dark modules starting at x = 0 on the top row.
+        assert!(snippet.code.contains("[0,20,7]"));
+
+        let deboss = QrCodeOptions {
+            relief: QrRelief::Deboss,
+            base_thickness: 0.5,
+            ..Default::default()
+        };
+        assert!(qr_code_snippet("hello", &deboss).is_err());
+        assert!(qr_code_snippet("", &QrCodeOptions::default()).is_err());
+    }
+}
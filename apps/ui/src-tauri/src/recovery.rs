@@ -0,0 +1,233 @@
+/**
+ * Autosave and crash recovery
+ *
+ * Each window periodically replaces its set of unsaved-buffer snapshots under
+ * `recovery/autosave/<window>/`. A `running` marker exists while the app is
+ * open; finding it at launch means the last run didn't shut down cleanly, so
+ * its snapshots move to `recovery/recovered/` until they are restored or
+ * discarded. A clean exit clears the snapshots and the marker.
+ */
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+pub const RECOVERY_DIR_NAME: &str = "recovery";
+const AUTOSAVE_DIR: &str = "autosave";
+const RECOVERED_DIR: &str = "recovered";
+const RUNNING_MARKER: &str = "running";
+
+/// An unsaved buffer as sent by the frontend
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutosaveBuffer {
+    /// File on disk, or `None` for an untitled buffer
+    pub path: Option<String>,
+    pub name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredFile {
+    pub id: String,
+    pub path: Option<String>,
+    pub name: String,
+    /// Unix milliseconds of the snapshot
+    pub saved_at: i64,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredContent {
+    #[serde(flatten)]
+    pub file: RecoveredFile,
+    pub content: String,
+}
+
+/// Stable snapshot ID for a window's buffer, so each autosave overwrites the last
+fn buffer_id(window: &str, buffer: &AutosaveBuffer) -> String {
+    let mut hasher = DefaultHasher::new();
+    window.hash(&mut hasher);
+    buffer
+        .path
+        .as_deref()
+        .unwrap_or(&buffer.name)
+        .hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn snapshot_files(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect()
+}
+
+fn read_snapshot(path: &Path) -> Option<RecoveredContent> {
+    let json = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&json)
+        .inspect_err(|e| eprintln!("[recovery] Ignoring unreadable {}: {e}", path.display()))
+        .ok()
+}
+
+/// Replace the snapshots of `window` with `buffers`; returns how many were written
+pub fn write_autosave(
+    root: &Path,
+    window: &str,
+    buffers: &[AutosaveBuffer],
+    now: i64,
+) -> Result<usize, String> {
+    let dir = root.join(AUTOSAVE_DIR).join(window);
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear {}: {e}", dir.display()))?;
+    }
+    if buffers.is_empty() {
+        return Ok(0);
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    for buffer in buffers {
+        let snapshot = RecoveredContent {
+            file: RecoveredFile {
+                id: buffer_id(window, buffer),
+                path: buffer.path.clone(),
+                name: buffer.name.clone(),
+                saved_at: now,
+                size: buffer.content.len(),
+            },
+            content: buffer.content.clone(),
+        };
+        let path = dir.join(format!("{}.json", snapshot.file.id));
+        let json = serde_json::to_string(&snapshot)
+            .map_err(|e| format!("Failed to serialize snapshot: {e}"))?;
+        fs::write(&path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+    Ok(buffers.len())
+}
+
+/// Mark the app as running. If the previous run never finished, its
+/// snapshots become recovered files; returns how many there are in total.
+pub fn begin_run(root: &Path) -> Result<usize, String> {
+    fs::create_dir_all(root).map_err(|e| format!("Failed to create {}: {e}", root.display()))?;
+    let autosave = root.join(AUTOSAVE_DIR);
+    if root.join(RUNNING_MARKER).exists() {
+        let recovered = root.join(RECOVERED_DIR);
+        fs::create_dir_all(&recovered)
+            .map_err(|e| format!("Failed to create {}: {e}", recovered.display()))?;
+        let windows = fs::read_dir(&autosave).into_iter().flatten().flatten();
+        for path in windows.flat_map(|window| snapshot_files(&window.path())) {
+            if let Some(name) = path.file_name() {
+                let _ = fs::rename(&path, recovered.join(name));
+            }
+        }
+    }
+    let _ = fs::remove_dir_all(&autosave);
+    fs::write(root.join(RUNNING_MARKER), std::process::id().to_string())
+        .map_err(|e| format!("Failed to mark the app as running: {e}"))?;
+    Ok(list_recovered(root).len())
+}
+
+/// Clean shutdown: drop this run's snapshots and the running marker
+pub fn end_run(root: &Path) {
+    let _ = fs::remove_dir_all(root.join(AUTOSAVE_DIR));
+    let _ = fs::remove_file(root.join(RUNNING_MARKER));
+}
+
+/// Files recovered from an unclean shutdown, newest first
+pub fn list_recovered(root: &Path) -> Vec<RecoveredFile> {
+    let mut files: Vec<_> = snapshot_files(&root.join(RECOVERED_DIR))
+        .iter()
+        .filter_map(|path| read_snapshot(path))
+        .map(|snapshot| snapshot.file)
+        .collect();
+    files.sort_by_key(|file| std::cmp::Reverse(file.saved_at));
+    files
+}
+
+fn recovered_path(root: &Path, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid recovered file id: {id}"));
+    }
+    Ok(root.join(RECOVERED_DIR).join(format!("{id}.json")))
+}
+
+/// Read a recovered file and remove it from the recovery directory
+pub fn take_recovered(root: &Path, id: &str) -> Result<RecoveredContent, String> {
+    let path = recovered_path(root, id)?;
+    let snapshot = read_snapshot(&path).ok_or_else(|| format!("Recovered file not found: {id}"))?;
+    let _ = fs::remove_file(&path);
+    Ok(snapshot)
+}
+
+pub fn discard_recovered(root: &Path, id: &str) -> Result<(), String> {
+    let path = recovered_path(root, id)?;
+    fs::remove_file(&path).map_err(|e| format!("Failed to discard recovered file {id}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(path: Option<&str>, name: &str, content: &str) -> AutosaveBuffer {
+        AutosaveBuffer {
+            path: path.map(str::to_string),
+            name: name.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn unclean_shutdown_recovers_last_snapshots() {
+        let root = std::env::temp_dir()
+            .join("openscad-studio-recovery-tests")
+            .join(uuid::Uuid::new_v4().to_string());
+
+        assert_eq!(begin_run(&root).unwrap(), 0);
+        let gear = buffer(Some("/work/gear.scad"), "gear.scad", "gear(10);");
+        write_autosave(&root, "main", &[gear], 1).unwrap();
+        let gear = buffer(Some("/work/gear.scad"), "gear.scad", "gear(12);");
+        let untitled = buffer(None, "Untitled", "cube(5);");
+        write_autosave(&root, "main", &[gear, untitled], 2).unwrap();
+        write_autosave(&root, "window-2", &[buffer(None, "Other", "sphere(1);")], 3).unwrap();
+        write_autosave(&root, "window-2", &[], 4).unwrap();
+
+        // The app died without end_run.
+        assert_eq!(begin_run(&root).unwrap(), 2);
+        let recovered = list_recovered(&root);
+        let names: Vec<_> = recovered.iter().map(|file| file.name.as_str()).collect();
+        assert!(names.contains(&"gear.scad") && names.contains(&"Untitled"));
+
+        let gear = recovered
+            .iter()
+            .find(|file| file.name == "gear.scad")
+            .unwrap();
+        let content = take_recovered(&root, &gear.id).unwrap();
+        assert_eq!(content.content, "gear(12);");
+        assert_eq!(content.file.path.as_deref(), Some("/work/gear.scad"));
+        assert_eq!(list_recovered(&root).len(), 1);
+        assert!(take_recovered(&root, "../running").is_err());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn clean_shutdown_leaves_nothing_to_recover() {
+        let root = std::env::temp_dir()
+            .join("openscad-studio-recovery-tests")
+            .join(uuid::Uuid::new_v4().to_string());
+
+        begin_run(&root).unwrap();
+        write_autosave(&root, "main", &[buffer(None, "Untitled", "cube(5);")], 1).unwrap();
+        end_run(&root);
+
+        assert_eq!(begin_run(&root).unwrap(), 0);
+        assert!(list_recovered(&root).is_empty());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,499 @@
+/**
+ * Reference geometry for `import()`
+ *
+ * Meshes (STL, OFF, 3MF) and drawings (SVG, DXF) copied into a project are
+ * checked before the model relies on them. STL is accepted as ASCII or
+ * binary; a binary file is recognised by its size matching the triangle
+ * count, even when its header starts with `solid`. OpenSCAD reads mesh and
+ * DXF coordinates as millimetres, so files that declare other units (3MF
+ * `unit`, DXF `$INSUNITS`) get the `scale()` that converts them. SVG sizes
+ * in physical units are honoured by OpenSCAD itself; pixel and unitless
+ * sizes depend on `import()`'s `dpi`. STL and OFF carry no units, so
+ * suspiciously small meshes are flagged as possibly metres or inches.
+ */
+use crate::geometry::{is_binary_stl, parse_stl, triangle_stats};
+use serde::Serialize;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// Largest reference file accepted
+pub const MAX_REFERENCE_BYTES: u64 = 256 * 1024 * 1024;
+/// Meshes whose largest extent is below this are probably not in millimetres
+const SMALL_MESH_EXTENT: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceFormat {
+    Stl,
+    Off,
+    #[serde(rename = "3mf")]
+    ThreeMf,
+    Svg,
+    Dxf,
+}
+
+impl ReferenceFormat {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "stl" => Some(Self::Stl),
+            "off" => Some(Self::Off),
+            "3mf" => Some(Self::ThreeMf),
+            "svg" => Some(Self::Svg),
+            "dxf" => Some(Self::Dxf),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceGeometry {
+    /// Path as written in `import()`
+    pub path: String,
+    pub format: ReferenceFormat,
+    /// `ascii` or `binary` for STL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<&'static str>,
+    /// Triangles (STL, 3MF) or faces (OFF)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub face_count: Option<usize>,
+    /// Bounding box extents in millimetres once imported as suggested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<[f64; 3]>,
+    /// Unit the file declares
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub units: Option<String>,
+    /// `scale()` factor applied by `import_statement`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    pub warnings: Vec<String>,
+    /// Statement that imports the file at the right size
+    pub import_statement: String,
+}
+
+impl ReferenceGeometry {
+    fn new(path: &str, format: ReferenceFormat) -> Self {
+        Self {
+            path: path.to_string(),
+            format,
+            encoding: None,
+            face_count: None,
+            size: None,
+            units: None,
+            scale: None,
+            warnings: Vec::new(),
+            import_statement: String::new(),
+        }
+    }
+}
+
+pub fn is_reference_file(path: &str) -> bool {
+    ReferenceFormat::from_path(path).is_some()
+}
+
+fn bounding_size(points: impl IntoIterator<Item = [f64; 3]>) -> Option<[f64; 3]> {
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    for point in points {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(point[axis]);
+            max[axis] = max[axis].max(point[axis]);
+        }
+    }
+    min[0]
+        .is_finite()
+        .then(|| [0, 1, 2].map(|axis| max[axis] - min[axis]))
+}
+
+/// Value of `name="..."` inside a tag's attribute text
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(index) = rest.find(name) {
+        let before = rest[..index].chars().last();
+        let after = &rest[index + name.len()..];
+        rest = after;
+        if before.is_some_and(|c| !c.is_whitespace()) {
+            continue;
+        }
+        let Some(after) = after.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let after = after.trim_start();
+        let quote = after.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = &after[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
+/// Attribute text of each `<name ...>` tag in `xml`
+fn tags<'a>(xml: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    xml.match_indices('<').filter_map(move |(index, _)| {
+        let rest = xml[index + 1..].strip_prefix(name)?;
+        let boundary = rest.chars().next()?;
+        if !(boundary.is_whitespace() || boundary == '>' || boundary == '/') {
+            return None;
+        }
+        rest.find('>').map(|end| &rest[..end])
+    })
+}
+
+fn mesh_warnings(size: Option<[f64; 3]>, scale: Option<f64>, warnings: &mut Vec<String>) {
+    let Some(size) = size else {
+        return;
+    };
+    let extent = size.iter().copied().fold(0.0, f64::max);
+    if scale.is_none() && extent > 0.0 && extent < SMALL_MESH_EXTENT {
+        warnings.push(format!(
+            "The largest extent is only {extent:.3} mm; the file may be in metres \
+             (scale(1000)) or inches (scale(25.4))"
+        ));
+    }
+}
+
+fn inspect_stl(bytes: &[u8], geometry: &mut ReferenceGeometry) -> Result<(), String> {
+    let binary = is_binary_stl(bytes);
+    if !binary
+        && !String::from_utf8_lossy(&bytes[..bytes.len().min(512)])
+            .trim_start()
+            .starts_with("solid")
+    {
+        return Err(
+            "Neither ASCII nor binary STL (binary size doesn't match its triangle count)".into(),
+        );
+    }
+    let triangles = parse_stl(bytes)?;
+    if triangles.is_empty() {
+        return Err("STL contains no triangles".into());
+    }
+    geometry.encoding = Some(if binary { "binary" } else { "ascii" });
+    geometry.face_count = Some(triangles.len());
+    geometry.size = Some(triangle_stats(&triangles).size);
+    Ok(())
+}
+
+fn inspect_off(text: &str, geometry: &mut ReferenceGeometry) -> Result<(), String> {
+    let mut lines = text
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty());
+    let header = lines.next().unwrap_or_default();
+    let (keyword, counts_line) = header
+        .split_once(char::is_whitespace)
+        .unwrap_or((header, ""));
+    if !keyword.ends_with("OFF") {
+        return Err("OFF file doesn't start with an OFF header".into());
+    }
+    // Counts may share the header line
+    let counts_line = match counts_line.trim() {
+        "" => lines.next().unwrap_or_default(),
+        counts => counts,
+    };
+    let counts: Vec<usize> = counts_line
+        .split_whitespace()
+        .filter_map(|value| value.parse().ok())
+        .collect();
+    let [vertices, faces, ..] = counts[..] else {
+        return Err("OFF header has no vertex and face counts".into());
+    };
+    let points: Vec<[f64; 3]> = lines
+        .take(vertices)
+        .filter_map(|line| {
+            let coords: Vec<f64> = line
+                .split_whitespace()
+                .take(3)
+                .filter_map(|value| value.parse().ok())
+                .collect();
+            coords.try_into().ok()
+        })
+        .collect();
+    if points.len() != vertices {
+        return Err(format!(
+            "OFF declares {vertices} vertices but has {}",
+            points.len()
+        ));
+    }
+    geometry.face_count = Some(faces);
+    geometry.size = bounding_size(points);
+    Ok(())
+}
+
+/// Millimetres per 3MF `unit`
+fn three_mf_scale(unit: &str) -> Option<f64> {
+    match unit {
+        "micron" => Some(0.001),
+        "millimeter" => Some(1.0),
+        "centimeter" => Some(10.0),
+        "inch" => Some(25.4),
+        "foot" => Some(304.8),
+        "meter" => Some(1000.0),
+        _ => None,
+    }
+}
+
+fn three_mf_model(bytes: &[u8]) -> Result<String, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| format!("3MF isn't a valid zip package: {e}"))?;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read 3MF package: {e}"))?;
+        let name = entry.name().to_ascii_lowercase();
+        if name.starts_with("3d/") && name.ends_with(".model") {
+            let mut xml = String::new();
+            entry
+                .read_to_string(&mut xml)
+                .map_err(|e| format!("Failed to read {name}: {e}"))?;
+            return Ok(xml);
+        }
+    }
+    Err("3MF package has no 3D model part".into())
+}
+
+fn inspect_3mf_model(xml: &str, geometry: &mut ReferenceGeometry) -> Result<(), String> {
+    let unit = tags(xml, "model")
+        .next()
+        .ok_or("3MF model part has no <model> element")?;
+    let unit = attribute(unit, "unit").unwrap_or("millimeter");
+    let scale = three_mf_scale(unit).ok_or_else(|| format!("Unknown 3MF unit `{unit}`"))?;
+    let points = tags(xml, "vertex").filter_map(|vertex| {
+        let coord = |name| attribute(vertex, name)?.parse::<f64>().ok();
+        Some([coord("x")?, coord("y")?, coord("z")?].map(|value| value * scale))
+    });
+    let size = bounding_size(points);
+    let triangles = tags(xml, "triangle").count();
+    if triangles == 0 {
+        return Err("3MF model contains no triangles".into());
+    }
+    geometry.face_count = Some(triangles);
+    geometry.size = size;
+    geometry.units = Some(unit.to_string());
+    geometry.scale = (scale != 1.0).then_some(scale);
+    Ok(())
+}
+
+/// Millimetres per SVG length unit, for physical units
+fn svg_unit_scale(unit: &str) -> Option<f64> {
+    match unit {
+        "mm" => Some(1.0),
+        "cm" => Some(10.0),
+        "in" => Some(25.4),
+        "pt" => Some(25.4 / 72.0),
+        "pc" => Some(25.4 / 6.0),
+        _ => None,
+    }
+}
+
+/// Split an SVG length such as `20mm` into its value and unit
+fn svg_length(value: &str) -> Option<(f64, &str)> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic() || c == '%')
+        .unwrap_or(value.len());
+    let number = value[..split].trim().parse().ok()?;
+    Some((number, value[split..].trim()))
+}
+
+fn inspect_svg(text: &str, geometry: &mut ReferenceGeometry) -> Result<(), String> {
+    let svg = tags(text, "svg")
+        .next()
+        .ok_or("File has no <svg> element")?;
+    let width = attribute(svg, "width").and_then(svg_length);
+    let height = attribute(svg, "height").and_then(svg_length);
+    match (width, height) {
+        (Some((width, unit)), Some((height, height_unit))) if unit == height_unit => {
+            if let Some(scale) = svg_unit_scale(unit) {
+                geometry.size = Some([width * scale, height * scale, 0.0]);
+            } else {
+                geometry.warnings.push(format!(
+                    "Its size is given in {}, so the imported size depends on import()'s dpi",
+                    if unit.is_empty() { "user units" } else { unit }
+                ));
+            }
+            geometry.units = Some(if unit.is_empty() { "px" } else { unit }.to_string());
+        }
+        _ => geometry.warnings.push(
+            "It has no width/height in consistent units, so the imported size depends on \
+             its viewBox and import()'s dpi"
+                .into(),
+        ),
+    }
+    Ok(())
+}
+
+/// Millimetres per DXF `$INSUNITS` code, when known
+fn dxf_units(code: u32) -> Option<(&'static str, f64)> {
+    match code {
+        1 => Some(("inches", 25.4)),
+        2 => Some(("feet", 304.8)),
+        4 => Some(("millimeters", 1.0)),
+        5 => Some(("centimeters", 10.0)),
+        6 => Some(("meters", 1000.0)),
+        _ => None,
+    }
+}
+
+fn inspect_dxf(text: &str, geometry: &mut ReferenceGeometry) -> Result<(), String> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    if !lines.contains(&"ENTITIES") {
+        return Err("DXF has no ENTITIES section".into());
+    }
+    // Group code/value pairs: `$INSUNITS` is followed by code 70 and its value
+    let insunits = lines
+        .iter()
+        .position(|line| *line == "$INSUNITS")
+        .and_then(|index| lines.get(index + 2))
+        .and_then(|value| value.parse::<u32>().ok());
+    if let Some((units, scale)) = insunits.and_then(dxf_units) {
+        geometry.units = Some(units.to_string());
+        geometry.scale = (scale != 1.0).then_some(scale);
+    }
+    Ok(())
+}
+
+fn format_number(value: f64) -> String {
+    let text = format!("{value:.4}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Check that OpenSCAD can load `bytes` as the reference file at `path`
+/// (written into `import()` as is) and describe it
+pub fn inspect_reference(path: &str, bytes: &[u8]) -> Result<ReferenceGeometry, String> {
+    let format = ReferenceFormat::from_path(path)
+        .ok_or_else(|| format!("{path} is not an STL, OFF, 3MF, SVG or DXF file"))?;
+    if bytes.is_empty() {
+        return Err(format!("{path} is empty"));
+    }
+    let mut geometry = ReferenceGeometry::new(path, format);
+    let text = || String::from_utf8_lossy(bytes);
+    match format {
+        ReferenceFormat::Stl => inspect_stl(bytes, &mut geometry),
+        ReferenceFormat::Off => inspect_off(&text(), &mut geometry),
+        ReferenceFormat::ThreeMf => {
+            three_mf_model(bytes).and_then(|xml| inspect_3mf_model(&xml, &mut geometry))
+        }
+        ReferenceFormat::Svg => inspect_svg(&text(), &mut geometry),
+        ReferenceFormat::Dxf => inspect_dxf(&text(), &mut geometry),
+    }
+    .map_err(|e| format!("{path}: {e}"))?;
+
+    if matches!(
+        format,
+        ReferenceFormat::Stl | ReferenceFormat::Off | ReferenceFormat::ThreeMf
+    ) {
+        mesh_warnings(geometry.size, geometry.scale, &mut geometry.warnings);
+    }
+    let import = format!("import(\"{}\");", path.replace('\\', "/"));
+    geometry.import_statement = match geometry.scale {
+        Some(scale) => format!("scale({}) {import}", format_number(scale)),
+        None => import,
+    };
+    Ok(geometry)
+}
+
+fn collect(root: &Path, dir: &Path, files: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            collect(root, &path, files);
+        } else if is_reference_file(&name) {
+            if let Ok(relative) = path.strip_prefix(root) {
+                files.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+}
+
+/// Reference files under `root`, as sorted root-relative paths
+pub fn list_reference_files(root: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    collect(root, root, &mut files);
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary_stl(header: &[u8], triangles: &[[[f32; 3]; 3]]) -> Vec<u8> {
+        let mut bytes = vec![0u8; 80];
+        bytes[..header.len()].copy_from_slice(header);
+        bytes.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+        for triangle in triangles {
+            bytes.extend_from_slice(&[0u8; 12]);
+            for value in triangle.iter().flatten() {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.extend_from_slice(&[0, 0]);
+        }
+        bytes
+    }
+
+    #[test]
+    fn inspects_stl_encodings_and_scale() {
+        let triangle = [[0.0, 0.0, 0.0], [20.0, 0.0, 0.0], [0.0, 10.0, 5.0]];
+        // Binary despite the `solid` header
+        let binary =
+            inspect_reference("parts/base.stl", &binary_stl(b"solid base", &[triangle])).unwrap();
+        assert_eq!(binary.encoding, Some("binary"));
+        assert_eq!(binary.face_count, Some(1));
+        assert_eq!(binary.size, Some([20.0, 10.0, 5.0]));
+        assert!(binary.warnings.is_empty());
+        assert_eq!(binary.import_statement, "import(\"parts/base.stl\");");
+
+        let ascii = "solid tiny\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\n\
+                     vertex 0.05 0 0\nvertex 0 0.02 0\nendloop\nendfacet\nendsolid tiny\n";
+        let ascii = inspect_reference("tiny.stl", ascii.as_bytes()).unwrap();
+        assert_eq!(ascii.encoding, Some("ascii"));
+        assert_eq!(ascii.warnings.len(), 1);
+
+        assert!(inspect_reference("broken.stl", &[1, 2, 3]).is_err());
+        assert!(inspect_reference("notes.txt", b"solid").is_err());
+    }
+
+    #[test]
+    fn reads_declared_units() {
+        let xml = r#"<model unit="inch" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02">
+            <resources><object id="1"><mesh><vertices>
+            <vertex x="0" y="0" z="0"/><vertex x="1" y="0" z="0"/><vertex x="0" y="2" z="0.5"/>
+            </vertices><triangles><triangle v1="0" v2="1" v3="2"/></triangles></mesh></object></resources>
+            </model>"#;
+        let mut geometry = ReferenceGeometry::new("bracket.3mf", ReferenceFormat::ThreeMf);
+        inspect_3mf_model(xml, &mut geometry).unwrap();
+        assert_eq!(geometry.face_count, Some(1));
+        assert_eq!(geometry.scale, Some(25.4));
+        assert_eq!(geometry.size, Some([25.4, 50.8, 12.7]));
+
+        let svg = inspect_reference(
+            "logo.svg",
+            b"<svg width=\"2in\" height=\"1in\" viewBox=\"0 0 2 1\">",
+        )
+        .unwrap();
+        assert_eq!(svg.size, Some([50.8, 25.4, 0.0]));
+        assert!(svg.warnings.is_empty());
+        let pixels = inspect_reference("icon.svg", b"<svg width=\"24\" height=\"24\">").unwrap();
+        assert_eq!(pixels.units.as_deref(), Some("px"));
+        assert_eq!(pixels.warnings.len(), 1);
+
+        let dxf = "0\nSECTION\n2\nHEADER\n9\n$INSUNITS\n70\n1\n0\nENDSEC\n0\nSECTION\n2\n\
+                   ENTITIES\n0\nENDSEC\n0\nEOF\n";
+        let dxf = inspect_reference("plate.dxf", dxf.as_bytes()).unwrap();
+        assert_eq!(dxf.units.as_deref(), Some("inches"));
+        assert_eq!(dxf.import_statement, "scale(25.4) import(\"plate.dxf\");");
+
+        let off =
+            inspect_reference("tri.off", b"OFF 3 1 0\n0 0 0\n4 0 0\n0 3 2\n3 0 1 2\n").unwrap();
+        assert_eq!(off.face_count, Some(1));
+        assert_eq!(off.size, Some([4.0, 3.0, 2.0]));
+    }
+}
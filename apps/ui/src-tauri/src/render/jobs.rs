@@ -0,0 +1,114 @@
+/**
+ * Render job tracking
+ *
+ * Every native render registers a job holding a cancellation flag. The
+ * process wait loop polls the flag and kills the OpenSCAD child when it is
+ * set, so `cancel_render` (one job) and `render_cancel` (all jobs) can stop
+ * renders that are already running. Jobs are removed when their render
+ * finishes.
+ */
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+pub struct RenderJobManager {
+    jobs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl RenderJobManager {
+    /// Register a new job, returning its id and cancellation flag
+    pub fn start(&self) -> (String, Arc<AtomicBool>) {
+        let job_id = uuid::Uuid::new_v4().to_string();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.jobs
+            .lock()
+            .unwrap()
+            .insert(job_id.clone(), cancelled.clone());
+        (job_id, cancelled)
+    }
+
+    /// Register a job under an id chosen by the caller, so it can be
+    /// cancelled before the command that runs it returns. Without an id this
+    /// is `start`.
+    pub fn start_as(&self, job_id: Option<String>) -> Result<(String, Arc<AtomicBool>), String> {
+        let Some(job_id) = job_id else {
+            return Ok(self.start());
+        };
+        let mut jobs = self.jobs.lock().unwrap();
+        if jobs.contains_key(&job_id) {
+            return Err(format!("Render job {} is already running", job_id));
+        }
+        let cancelled = Arc::new(AtomicBool::new(false));
+        jobs.insert(job_id.clone(), cancelled.clone());
+        Ok((job_id, cancelled))
+    }
+
+    pub fn finish(&self, job_id: &str) {
+        self.jobs.lock().unwrap().remove(job_id);
+    }
+
+    /// Whether no render is running
+    pub fn is_idle(&self) -> bool {
+        self.jobs.lock().unwrap().is_empty()
+    }
+
+    /// Flag a running job for cancellation; false when it isn't running
+    pub fn cancel(&self, job_id: &str) -> bool {
+        match self.jobs.lock().unwrap().get(job_id) {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Flag every running job; returns how many there were
+    pub fn cancel_all(&self) -> usize {
+        let jobs = self.jobs.lock().unwrap();
+        for cancelled in jobs.values() {
+            cancelled.store(true, Ordering::SeqCst);
+        }
+        jobs.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancels_running_jobs_only() {
+        let manager = RenderJobManager::default();
+        let (first, first_flag) = manager.start();
+        let (second, second_flag) = manager.start();
+
+        assert!(manager.cancel(&first));
+        assert!(first_flag.load(Ordering::SeqCst));
+        assert!(!second_flag.load(Ordering::SeqCst));
+
+        manager.finish(&first);
+        assert!(!manager.cancel(&first));
+        assert_eq!(manager.cancel_all(), 1);
+        assert!(second_flag.load(Ordering::SeqCst));
+        assert!(!manager.is_idle());
+        manager.finish(&second);
+        assert_eq!(manager.cancel_all(), 0);
+        assert!(manager.is_idle());
+    }
+
+    #[test]
+    fn callers_can_name_their_jobs() {
+        let manager = RenderJobManager::default();
+        let (job_id, flag) = manager.start_as(Some("preview-1".into())).unwrap();
+        assert_eq!(job_id, "preview-1");
+        assert!(manager.start_as(Some("preview-1".into())).is_err());
+
+        assert!(manager.cancel("preview-1"));
+        assert!(flag.load(Ordering::SeqCst));
+        manager.finish("preview-1");
+        assert!(manager.start_as(Some("preview-1".into())).is_ok());
+        assert!(manager.start_as(None).is_ok());
+    }
+}
@@ -0,0 +1,7 @@
+/**
+ * Native render coordination: job cancellation, the priority queue that
+ * hands out process slots, and auto-render scheduling
+ */
+pub mod jobs;
+pub mod queue;
+pub mod scheduler;
@@ -0,0 +1,211 @@
+/**
+ * Render priority queue
+ *
+ * OpenSCAD processes compete for the same cores, so renders wait for a slot
+ * before they start. At most `limit` run at once; when a slot frees up the
+ * most urgent waiting render takes it (interactive previews, then exports,
+ * then background compiles such as AI test-compiles), in request order
+ * within a priority. The limit defaults to half the CPU count because
+ * OpenSCAD's geometry backend is itself multi-threaded.
+ */
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+pub const MAX_CONCURRENCY: usize = 64;
+/// How often a waiting render checks whether it was cancelled
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Ordered from least to most urgent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RenderPriority {
+    Background,
+    Export,
+    Preview,
+}
+
+/// Concurrent renders when the user hasn't set a limit
+pub fn default_concurrency() -> usize {
+    std::thread::available_parallelism()
+        .map_or(1, |cpus| cpus.get() / 2)
+        .max(1)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueStatus {
+    pub limit: usize,
+    pub running: usize,
+    pub waiting: usize,
+}
+
+struct QueueState {
+    limit: usize,
+    running: usize,
+    /// Most urgent first, then oldest ticket first
+    waiting: BinaryHeap<(RenderPriority, Reverse<u64>)>,
+    next_ticket: u64,
+}
+
+pub struct RenderQueue {
+    state: Mutex<QueueState>,
+    changed: Condvar,
+}
+
+impl Default for RenderQueue {
+    fn default() -> Self {
+        Self::new(default_concurrency())
+    }
+}
+
+/// A render slot, released when dropped
+pub struct RenderPermit<'a> {
+    queue: &'a RenderQueue,
+}
+
+impl Drop for RenderPermit<'_> {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().running -= 1;
+        self.queue.changed.notify_all();
+    }
+}
+
+impl RenderQueue {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                limit: limit.clamp(1, MAX_CONCURRENCY),
+                running: 0,
+                waiting: BinaryHeap::new(),
+                next_ticket: 0,
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Change how many renders may run at once; running renders finish
+    pub fn set_limit(&self, limit: usize) {
+        self.state.lock().unwrap().limit = limit.clamp(1, MAX_CONCURRENCY);
+        self.changed.notify_all();
+    }
+
+    pub fn status(&self) -> QueueStatus {
+        let state = self.state.lock().unwrap();
+        QueueStatus {
+            limit: state.limit,
+            running: state.running,
+            waiting: state.waiting.len(),
+        }
+    }
+
+    /// Wait for a render slot. Gives up (leaving the queue) when
+    /// `cancelled` is set while waiting.
+    pub fn acquire(
+        &self,
+        priority: RenderPriority,
+        cancelled: Option<&AtomicBool>,
+    ) -> Result<RenderPermit<'_>, String> {
+        let mut state = self.state.lock().unwrap();
+        let entry = (priority, Reverse(state.next_ticket));
+        state.next_ticket += 1;
+        state.waiting.push(entry);
+
+        loop {
+            if state.running < state.limit && state.waiting.peek() == Some(&entry) {
+                state.waiting.pop();
+                state.running += 1;
+                // The next waiter may fit in a slot that is still free
+                self.changed.notify_all();
+                return Ok(RenderPermit { queue: self });
+            }
+            if cancelled.is_some_and(|cancelled| cancelled.load(Ordering::SeqCst)) {
+                state.waiting.retain(|waiting| *waiting != entry);
+                self.changed.notify_all();
+                return Err("Render cancelled while queued".into());
+            }
+            state = self
+                .changed
+                .wait_timeout(state, CANCEL_POLL_INTERVAL)
+                .unwrap()
+                .0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn runs_the_most_urgent_waiting_render_first() {
+        let queue = Arc::new(RenderQueue::new(1));
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let first = queue.acquire(RenderPriority::Export, None).unwrap();
+
+        let waiters: Vec<_> = [
+            RenderPriority::Background,
+            RenderPriority::Export,
+            RenderPriority::Preview,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, priority)| {
+            let (waiter_queue, order) = (queue.clone(), order.clone());
+            let handle = std::thread::spawn(move || {
+                let _permit = waiter_queue.acquire(priority, None).unwrap();
+                order.lock().unwrap().push(priority);
+            });
+            // Queue them one at a time so their tickets are in this order
+            while queue.status().waiting <= index {
+                std::thread::yield_now();
+            }
+            handle
+        })
+        .collect();
+
+        drop(first);
+        for waiter in waiters {
+            waiter.join().unwrap();
+        }
+        assert_eq!(
+            *order.lock().unwrap(),
+            [
+                RenderPriority::Preview,
+                RenderPriority::Export,
+                RenderPriority::Background
+            ]
+        );
+        assert_eq!(queue.status().running, 0);
+    }
+
+    #[test]
+    fn cancelled_renders_leave_the_queue() {
+        let queue = RenderQueue::new(1);
+        let _running = queue.acquire(RenderPriority::Preview, None).unwrap();
+        let cancelled = AtomicBool::new(true);
+        assert!(queue
+            .acquire(RenderPriority::Export, Some(&cancelled))
+            .is_err());
+        assert_eq!(
+            queue.status(),
+            QueueStatus {
+                limit: 1,
+                running: 1,
+                waiting: 0
+            }
+        );
+    }
+
+    #[test]
+    fn the_limit_never_drops_below_one() {
+        let queue = RenderQueue::new(2);
+        queue.set_limit(0);
+        assert_eq!(queue.status().limit, 1);
+        assert!(default_concurrency() >= 1);
+    }
+}
@@ -0,0 +1,79 @@
+/**
+ * Auto-render scheduling
+ *
+ * Edits schedule a render instead of starting one. A request waits out the
+ * debounce interval, and any newer request for the same document supersedes
+ * it, so a burst of keystrokes turns into one render of the final text.
+ * Scheduled renders can also be held until no other render is running and
+ * skipped while the code doesn't parse.
+ */
+use crate::settings::RenderSchedulerSettings;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub const MAX_DEBOUNCE_MS: u64 = 10_000;
+
+/// Why a scheduled render didn't start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkipReason {
+    /// A newer request for the document replaced it
+    Superseded,
+    /// The code has syntax errors and `only_when_clean` is set
+    SyntaxErrors,
+}
+
+/// Latest scheduled request per document
+#[derive(Default)]
+pub struct RenderScheduler {
+    generations: Mutex<HashMap<String, u64>>,
+}
+
+impl RenderScheduler {
+    /// Queue a request for `document_id`, superseding any it had waiting,
+    /// and return its generation
+    pub fn schedule(&self, document_id: &str) -> u64 {
+        let mut generations = self.generations.lock().unwrap();
+        let generation = generations.entry(document_id.to_string()).or_default();
+        *generation += 1;
+        *generation
+    }
+
+    pub fn is_latest(&self, document_id: &str, generation: u64) -> bool {
+        self.generations.lock().unwrap().get(document_id) == Some(&generation)
+    }
+}
+
+pub fn validate_settings(settings: &RenderSchedulerSettings) -> Result<(), String> {
+    if settings.debounce_ms > MAX_DEBOUNCE_MS {
+        return Err(format!(
+            "Render debounce must be at most {MAX_DEBOUNCE_MS} ms"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_requests_supersede_waiting_ones() {
+        let scheduler = RenderScheduler::default();
+        let first = scheduler.schedule("a");
+        let other = scheduler.schedule("b");
+        let second = scheduler.schedule("a");
+
+        assert!(!scheduler.is_latest("a", first));
+        assert!(scheduler.is_latest("a", second));
+        assert!(scheduler.is_latest("b", other));
+
+        let settings = RenderSchedulerSettings {
+            debounce_ms: MAX_DEBOUNCE_MS + 1,
+            ..Default::default()
+        };
+        assert!(validate_settings(&settings).is_err());
+        assert!(validate_settings(&RenderSchedulerSettings::default()).is_ok());
+    }
+}
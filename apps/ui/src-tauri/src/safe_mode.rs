@@ -0,0 +1,121 @@
+/**
+ * Safe-mode rendering for untrusted projects
+ *
+ * When safe mode is enabled, projects the user hasn't explicitly trusted are
+ * rendered in an isolated temp workspace (no project working directory or
+ * library paths), with a short timeout, and code that reaches outside the
+ * project via absolute or parent-relative paths is refused, as is code that
+ * computes the path it imports, since that path can't be checked up front.
+ */
+use crate::project_files::{computed_file_reads, referenced_paths};
+use crate::settings::AppSettings;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+pub const SAFE_MODE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Canonical form used for trust comparisons
+pub fn canonical_project_path(path: &str) -> String {
+    std::fs::canonicalize(path)
+        .ok()
+        .and_then(|resolved| resolved.into_os_string().into_string().ok())
+        .unwrap_or_else(|| path.trim_end_matches(['/', '\\']).to_string())
+}
+
+/// Whether renders for `working_dir` must go through safe mode
+pub fn requires_safe_mode(settings: &AppSettings, working_dir: Option<&str>) -> bool {
+    if !settings.safe_mode.enabled {
+        return false;
+    }
+    match working_dir {
+        Some(dir) => !settings
+            .safe_mode
+            .trusted_projects
+            .contains(&canonical_project_path(dir)),
+        // Unsaved buffers only ever see the temp workspace.
+        None => false,
+    }
+}
+
+pub fn is_escaping_path(path: &str) -> bool {
+    let normalized = path.replace('\\', "/");
+    normalized.starts_with('/')
+        || normalized.starts_with('~')
+        || Path::new(&normalized).has_root()
+        || normalized.as_bytes().get(1) == Some(&b':')
+        || normalized.split('/').any(|segment| segment == "..")
+}
+
+/// Refuse code (including auxiliary files) that reads outside the project or
+/// reads a file whose path is only known at render time
+pub fn check_untrusted_code(
+    code: &str,
+    auxiliary_files: &Option<HashMap<String, String>>,
+) -> Result<(), String> {
+    let sources = std::iter::once(code).chain(
+        auxiliary_files
+            .iter()
+            .flat_map(|files| files.values().map(String::as_str)),
+    );
+    for source in sources {
+        if let Some(path) = referenced_paths(source)
+            .into_iter()
+            .find(|path| is_escaping_path(path))
+        {
+            return Err(format!(
+                "Safe mode blocked a reference to \"{path}\" outside the project. Trust this project to render it."
+            ));
+        }
+        if let Some(call) = computed_file_reads(source).into_iter().next() {
+            return Err(format!(
+                "Safe mode blocked `{call}` because its file path isn't a string literal. Trust this project to render it."
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_paths_outside_the_project() {
+        assert!(
+            check_untrusted_code("include <parts/gear.scad>\nimport(\"logo.svg\");", &None).is_ok()
+        );
+        assert!(check_untrusted_code("import(\"/etc/passwd\");", &None).is_err());
+        assert!(check_untrusted_code("use <../../secrets.scad>", &None).is_err());
+        assert!(check_untrusted_code("surface(file = \"C:/data.png\");", &None).is_err());
+        assert!(check_untrusted_code("// import(\"/etc/passwd\");\ncube(1);", &None).is_ok());
+
+        let mut aux = HashMap::new();
+        aux.insert("lib.scad".to_string(), "include <~/x.scad>".to_string());
+        assert!(check_untrusted_code("cube(1);", &Some(aux)).is_err());
+    }
+
+    #[test]
+    fn blocks_computed_import_paths() {
+        assert!(check_untrusted_code("p = \"/etc/passwd\";\nimport(p);", &None).is_err());
+        assert!(check_untrusted_code("import(str(\"/et\", \"c/passwd\"));", &None).is_err());
+        assert!(check_untrusted_code("surface(file = str(\"/tmp/\", \"x.dat\"));", &None).is_err());
+        assert!(check_untrusted_code("import(file = \"logo.svg\", center = true);", &None).is_ok());
+    }
+
+    #[test]
+    fn trusted_projects_skip_safe_mode() {
+        let mut settings = AppSettings::default();
+        assert!(!requires_safe_mode(&settings, Some("/tmp/project")));
+
+        settings.safe_mode.enabled = true;
+        assert!(requires_safe_mode(&settings, Some("/tmp/project-x")));
+        assert!(!requires_safe_mode(&settings, None));
+
+        settings
+            .safe_mode
+            .trusted_projects
+            .insert(canonical_project_path("/tmp/project-x"));
+        assert!(!requires_safe_mode(&settings, Some("/tmp/project-x/")));
+    }
+}
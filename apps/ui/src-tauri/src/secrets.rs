@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+/**
+ * AI provider API keys
+ *
+ * Keys live either in the store plugin's `secrets.json` (plaintext, the
+ * default) or in the OS keychain, selected in settings. Switching backends
+ * copies every key across before removing it from the old one, so a failed
+ * write never loses a key.
+ */
+use std::fmt;
+
+pub const STORE_FILE_NAME: &str = "secrets.json";
+/// Keychain service name the entries are filed under
+pub const KEYCHAIN_SERVICE: &str = "com.openscad.studio";
+/// Providers that can hold an API key
+pub const API_KEY_NAMES: &[&str] = &[
+    "anthropic",
+    "openai",
+    "azure-openai",
+    "gemini",
+    "openrouter",
+    "openai-compatible",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyStorageBackend {
+    #[default]
+    File,
+    Keychain,
+}
+
+impl fmt::Display for KeyStorageBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            KeyStorageBackend::File => "secrets file",
+            KeyStorageBackend::Keychain => "system keychain",
+        })
+    }
+}
+
+pub trait SecretStore {
+    fn get(&self, name: &str) -> Result<Option<String>, String>;
+    fn set(&self, name: &str, value: &str) -> Result<(), String>;
+    /// Removing a missing entry is not an error
+    fn delete(&self, name: &str) -> Result<(), String>;
+}
+
+/// The OS keychain: macOS Keychain, Windows Credential Manager or the
+/// Secret Service on Linux
+pub struct KeychainStore;
+
+impl KeychainStore {
+    fn entry(name: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, name)
+            .map_err(|e| format!("Failed to open keychain entry {name}: {e}"))
+    }
+}
+
+impl SecretStore for KeychainStore {
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        match Self::entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to read {name} from the keychain: {e}")),
+        }
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), String> {
+        Self::entry(name)?
+            .set_password(value)
+            .map_err(|e| format!("Failed to write {name} to the keychain: {e}"))
+    }
+
+    fn delete(&self, name: &str) -> Result<(), String> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to remove {name} from the keychain: {e}")),
+        }
+    }
+}
+
+pub fn validate_key_name(name: &str) -> Result<(), String> {
+    if API_KEY_NAMES.contains(&name) {
+        Ok(())
+    } else {
+        Err(format!("Unknown API key provider: {name}"))
+    }
+}
+
+/// Move the keys in `names` from one store to another; returns how many moved.
+/// Nothing is removed from `from` unless every key was written to `to`.
+pub fn migrate_secrets(
+    from: &dyn SecretStore,
+    to: &dyn SecretStore,
+    names: &[&str],
+) -> Result<usize, String> {
+    let mut moved = Vec::new();
+    for &name in names {
+        if let Some(value) = from.get(name)? {
+            to.set(name, &value)?;
+            moved.push(name);
+        }
+    }
+    for name in &moved {
+        from.delete(name)?;
+    }
+    Ok(moved.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        values: RefCell<BTreeMap<String, String>>,
+        read_only: bool,
+    }
+
+    impl SecretStore for MemoryStore {
+        fn get(&self, name: &str) -> Result<Option<String>, String> {
+            Ok(self.values.borrow().get(name).cloned())
+        }
+
+        fn set(&self, name: &str, value: &str) -> Result<(), String> {
+            if self.read_only {
+                return Err("locked".into());
+            }
+            self.values
+                .borrow_mut()
+                .insert(name.to_string(), value.to_string());
+            Ok(())
+        }
+
+        fn delete(&self, name: &str) -> Result<(), String> {
+            self.values.borrow_mut().remove(name);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn migration_moves_keys_between_stores() {
+        let file = MemoryStore::default();
+        file.set("anthropic", "sk-ant-1").unwrap();
+        file.set("openai", "sk-2").unwrap();
+        let keychain = MemoryStore::default();
+
+        assert_eq!(migrate_secrets(&file, &keychain, API_KEY_NAMES).unwrap(), 2);
+        assert_eq!(
+            keychain.get("anthropic").unwrap().as_deref(),
+            Some("sk-ant-1")
+        );
+        assert_eq!(keychain.get("openai-compatible").unwrap(), None);
+        assert!(file.values.borrow().is_empty());
+
+        assert_eq!(migrate_secrets(&keychain, &file, API_KEY_NAMES).unwrap(), 2);
+        assert_eq!(file.get("openai").unwrap().as_deref(), Some("sk-2"));
+        assert!(validate_key_name("openai-compatible").is_ok());
+        assert!(validate_key_name("../settings").is_err());
+    }
+
+    #[test]
+    fn failed_migration_keeps_the_source() {
+        let file = MemoryStore::default();
+        file.set("anthropic", "sk-ant-1").unwrap();
+        let locked = MemoryStore {
+            read_only: true,
+            ..Default::default()
+        };
+
+        assert!(migrate_secrets(&file, &locked, API_KEY_NAMES).is_err());
+        assert_eq!(file.get("anthropic").unwrap().as_deref(), Some("sk-ant-1"));
+    }
+}
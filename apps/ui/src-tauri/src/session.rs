@@ -0,0 +1,176 @@
+/**
+ * Recent projects and the last session
+ *
+ * Both live in the store plugin's `session.json`. The recent list backs the
+ * File → Open Recent submenu; the session snapshot (open files, cursors,
+ * viewer camera, unsent chat draft) is written by the frontend as it changes
+ * and handed back once on the next launch.
+ */
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const STORE_FILE_NAME: &str = "session.json";
+pub const RECENT_PROJECTS_KEY: &str = "recentProjects";
+pub const SESSION_KEY: &str = "session";
+pub const MAX_RECENT_PROJECTS: usize = 10;
+
+const RECENT_MENU_PREFIX: &str = "open_recent:";
+pub const CLEAR_RECENTS_MENU_ID: &str = "clear_recents";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecentKind {
+    #[default]
+    File,
+    Folder,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentProject {
+    pub path: String,
+    pub name: String,
+    #[serde(default)]
+    pub kind: RecentKind,
+    /// Unix milliseconds
+    pub last_opened: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CursorPosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFile {
+    pub path: String,
+    #[serde(default)]
+    pub cursor: Option<CursorPosition>,
+}
+
+/// Orbit camera of the 3D viewer
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SessionCamera {
+    pub position: [f64; 3],
+    pub target: [f64; 3],
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionSnapshot {
+    /// Project folder, if one was open
+    pub working_dir: Option<String>,
+    /// Open tabs backed by files on disk, in tab order
+    pub open_files: Vec<SessionFile>,
+    pub active_file: Option<String>,
+    pub camera: Option<SessionCamera>,
+    pub chat_draft: String,
+}
+
+impl SessionSnapshot {
+    /// The snapshot without files or folders that no longer exist, or `None`
+    /// when nothing is left to reopen
+    pub fn restorable(mut self, exists: impl Fn(&str) -> bool) -> Option<Self> {
+        if self.working_dir.as_deref().is_some_and(|dir| !exists(dir)) {
+            self.working_dir = None;
+        }
+        self.open_files.retain(|file| exists(&file.path));
+        if !self
+            .open_files
+            .iter()
+            .any(|file| Some(&file.path) == self.active_file.as_ref())
+        {
+            self.active_file = self.open_files.first().map(|file| file.path.clone());
+        }
+        (self.working_dir.is_some() || !self.open_files.is_empty()).then_some(self)
+    }
+}
+
+/// Move `path` to the front of the recent list, dropping the oldest entries
+/// beyond `MAX_RECENT_PROJECTS`
+pub fn record_recent(recents: &mut Vec<RecentProject>, path: &str, kind: RecentKind, now: i64) {
+    recents.retain(|recent| recent.path != path);
+    let name = Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+    recents.insert(
+        0,
+        RecentProject {
+            path: path.to_string(),
+            name,
+            kind,
+            last_opened: now,
+        },
+    );
+    recents.truncate(MAX_RECENT_PROJECTS);
+}
+
+pub fn recent_menu_id(index: usize) -> String {
+    format!("{RECENT_MENU_PREFIX}{index}")
+}
+
+/// Index into the recent list of an Open Recent menu item
+pub fn parse_recent_menu_id(id: &str) -> Option<usize> {
+    id.strip_prefix(RECENT_MENU_PREFIX)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recents_move_to_front_without_duplicates() {
+        let mut recents = Vec::new();
+        record_recent(&mut recents, "/work/gear.scad", RecentKind::File, 1);
+        record_recent(&mut recents, "/work/enclosure", RecentKind::Folder, 2);
+        record_recent(&mut recents, "/work/gear.scad", RecentKind::File, 3);
+
+        let paths: Vec<_> = recents.iter().map(|recent| recent.path.as_str()).collect();
+        assert_eq!(paths, ["/work/gear.scad", "/work/enclosure"]);
+        assert_eq!(recents[0].name, "gear.scad");
+        assert_eq!(recents[0].last_opened, 3);
+        assert_eq!(recents[1].kind, RecentKind::Folder);
+
+        for i in 0..20 {
+            record_recent(
+                &mut recents,
+                &format!("/work/{i}.scad"),
+                RecentKind::File,
+                i,
+            );
+        }
+        assert_eq!(recents.len(), MAX_RECENT_PROJECTS);
+        assert_eq!(recents[0].path, "/work/19.scad");
+        assert_eq!(parse_recent_menu_id(&recent_menu_id(4)), Some(4));
+        assert_eq!(parse_recent_menu_id("open"), None);
+    }
+
+    #[test]
+    fn restore_skips_missing_files() {
+        let file = |path: &str| SessionFile {
+            path: path.to_string(),
+            cursor: None,
+        };
+        let snapshot = SessionSnapshot {
+            working_dir: Some("/gone".into()),
+            open_files: vec![file("/work/a.scad"), file("/work/b.scad")],
+            active_file: Some("/work/b.scad".into()),
+            chat_draft: "make it taller".into(),
+            ..Default::default()
+        };
+
+        let restored = snapshot
+            .clone()
+            .restorable(|path| path == "/work/a.scad")
+            .unwrap();
+        assert_eq!(restored.working_dir, None);
+        assert_eq!(restored.open_files, [file("/work/a.scad")]);
+        assert_eq!(restored.active_file.as_deref(), Some("/work/a.scad"));
+        assert_eq!(restored.chat_draft, "make it taller");
+
+        assert_eq!(snapshot.restorable(|_| false), None);
+    }
+}
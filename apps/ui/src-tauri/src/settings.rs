@@ -0,0 +1,247 @@
+use crate::export_presets::{builtin_presets, ExportPreset};
+use crate::printing::PrintingSettings;
+use crate::process_limits::RenderLimitSettings;
+use crate::secrets::KeyStorageBackend;
+use crate::tool_permissions::ToolPermissionSettings;
+use serde::{Deserialize, Serialize};
+/**
+ * Persistent application settings
+ *
+ * Stored as JSON in the app config directory. Every field carries a serde
+ * default so settings files written by older versions keep loading.
+ */
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    /// Accelerator overrides keyed by action ID. `None` unbinds the action.
+    pub shortcuts: BTreeMap<String, Option<String>>,
+    pub tray: TraySettings,
+    pub ai_providers: AiProviderSettings,
+    /// Per-tool timeout overrides in seconds, keyed by tool name; they apply
+    /// to MCP clients and the in-app agent
+    pub tool_timeouts: BTreeMap<String, u64>,
+    pub safe_mode: SafeModeSettings,
+    pub step_export: StepExportSettings,
+    pub libraries: LibrarySettings,
+    pub history: HistorySettings,
+    pub export_presets: ExportPresetSettings,
+    pub render_scheduler: RenderSchedulerSettings,
+    /// Renders allowed to run at once; `None` derives it from the CPU count
+    pub render_concurrency: Option<usize>,
+    pub render_limits: RenderLimitSettings,
+    pub tool_permissions: ToolPermissionSettings,
+    pub openscad: OpenScadSettings,
+    pub slicer: SlicerSettings,
+    /// OctoPrint and Moonraker connections, API keys included
+    pub printing: PrintingSettings,
+    /// Where AI provider API keys are kept
+    pub key_storage: KeyStorageBackend,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RenderSchedulerSettings {
+    /// Quiet time after the last edit before an automatic render starts
+    pub debounce_ms: u64,
+    /// Hold scheduled renders until no other render is running
+    pub only_when_idle: bool,
+    /// Skip scheduled renders of code that doesn't parse
+    pub only_when_clean: bool,
+}
+
+impl Default for RenderSchedulerSettings {
+    fn default() -> Self {
+        Self {
+            debounce_ms: 300,
+            only_when_idle: false,
+            only_when_clean: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportPresetSettings {
+    pub presets: Vec<ExportPreset>,
+    /// Preset applied to exports that don't pick one
+    pub active: String,
+}
+
+impl Default for ExportPresetSettings {
+    fn default() -> Self {
+        Self {
+            presets: builtin_presets(),
+            active: "normal".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistorySettings {
+    /// Save checkpoints per project folder so they survive restarts
+    pub persist: bool,
+    /// Drop saved checkpoints older than this many days; 0 keeps them all
+    pub max_age_days: u32,
+    /// Upper bound on the size of each project's saved history
+    pub max_bytes: u64,
+    /// Also write the code undo, redo and checkpoint restores land on to the
+    /// document's file, keeping a `.bak` copy of what the file held
+    pub write_to_disk: bool,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            persist: true,
+            max_age_days: 30,
+            max_bytes: 10 * 1024 * 1024,
+            write_to_disk: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LibrarySettings {
+    /// Versions installs and updates stick to, keyed by library name
+    pub pinned: BTreeMap<String, String>,
+    /// Extra `OPENSCADPATH` entries keyed by canonical project folder.
+    /// Relative entries resolve against the project.
+    pub project_paths: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OpenScadSettings {
+    /// OpenSCAD build renders use: `bundled`, `system` or a downloaded
+    /// build's install ID. `None` picks the first one found.
+    pub active: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SlicerSettings {
+    /// Known slicer ID (`prusaslicer`, `cura`, `bambustudio`); `None` uses
+    /// the first one detected
+    pub slicer: Option<String>,
+    /// Slicer executable or app bundle, overriding detection
+    pub executable: Option<String>,
+    /// `stl` or `3mf`; `None` uses the slicer's preferred format
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StepExportSettings {
+    /// External STL -> STEP converter; FreeCAD is auto-detected when unset
+    pub converter_command: Option<String>,
+    /// Converter arguments with `{input}`/`{output}` placeholders
+    pub converter_args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SafeModeSettings {
+    /// Render untrusted projects in an isolated, restricted workspace
+    pub enabled: bool,
+    /// Canonical paths of project folders the user has trusted
+    pub trusted_projects: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TraySettings {
+    /// Show the system tray icon with quick actions
+    pub enabled: bool,
+    /// Hide the last window instead of quitting so watch renders keep running
+    pub background_mode: bool,
+}
+
+/// Optional routing applied to outbound AI provider requests (enterprise keys)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AiProviderSettings {
+    /// Sent as `OpenAI-Organization`
+    pub openai_organization: Option<String>,
+    /// Sent as `OpenAI-Project`
+    pub openai_project: Option<String>,
+    /// Extra headers for Anthropic requests (e.g. gateway workspace routing)
+    pub anthropic_headers: BTreeMap<String, String>,
+    /// Bedrock region; falls back to `AWS_REGION`
+    pub bedrock_region: Option<String>,
+    /// Profile in `~/.aws/credentials`; falls back to `AWS_PROFILE`
+    pub aws_profile: Option<String>,
+    /// Vertex AI project; falls back to `GOOGLE_CLOUD_PROJECT`
+    pub vertex_project: Option<String>,
+    /// Vertex AI region; falls back to `GOOGLE_CLOUD_LOCATION`
+    pub vertex_region: Option<String>,
+}
+
+/// Global settings state (managed by Tauri)
+#[derive(Default)]
+pub struct SettingsState {
+    pub settings: Mutex<AppSettings>,
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(SETTINGS_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app config dir: {e}"))
+}
+
+/// Load settings from disk, falling back to defaults when missing or invalid
+pub fn load_settings(app: &AppHandle) -> AppSettings {
+    let Ok(path) = settings_path(app) else {
+        return AppSettings::default();
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            eprintln!(
+                "[settings] Ignoring unreadable settings file {:?}: {}",
+                path, e
+            );
+            AppSettings::default()
+        }),
+        Err(_) => AppSettings::default(),
+    }
+}
+
+/// Write settings to disk atomically (temp file + rename)
+pub fn save_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    let path = settings_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {e}"))?;
+    }
+
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {e}"))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).map_err(|e| format!("Failed to write settings: {e}"))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to replace settings file: {e}"))
+}
+
+/// Apply a mutation to the managed settings and persist the result
+pub fn update_settings<T>(
+    app: &AppHandle,
+    mutate: impl FnOnce(&mut AppSettings) -> Result<T, String>,
+) -> Result<T, String> {
+    let state = app.state::<SettingsState>();
+    let mut settings = state.settings.lock().unwrap();
+    let mut next = settings.clone();
+    let result = mutate(&mut next)?;
+    save_settings(app, &next)?;
+    *settings = next;
+    Ok(result)
+}
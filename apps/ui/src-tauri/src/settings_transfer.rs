@@ -0,0 +1,220 @@
+use crate::settings::AppSettings;
+use serde::{Deserialize, Serialize};
+/**
+ * Settings export/import and named profiles
+ *
+ * A bundle pairs the backend settings with the frontend's own preferences
+ * (editor, viewer, AI model...), which the backend carries as opaque JSON.
+ * Secrets — printer API keys and AI provider keys — are left out unless an
+ * export asks for them; profiles never hold them. Profiles are bundles kept
+ * by name in `settings-profiles.json`.
+ */
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub const BUNDLE_VERSION: u32 = 1;
+pub const PROFILES_FILE_NAME: &str = "settings-profiles.json";
+const MAX_PROFILE_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+    pub version: u32,
+    /// Unix milliseconds
+    pub exported_at: i64,
+    pub app: AppSettings,
+    /// Frontend preferences, stored as the frontend hands them over
+    #[serde(default)]
+    pub ui: serde_json::Value,
+    /// AI provider keys by provider; only present when secrets were exported
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub api_keys: BTreeMap<String, String>,
+}
+
+impl SettingsBundle {
+    pub fn new(app: AppSettings, ui: serde_json::Value, now: i64) -> Self {
+        Self {
+            version: BUNDLE_VERSION,
+            exported_at: now,
+            app,
+            ui,
+            api_keys: BTreeMap::new(),
+        }
+    }
+
+    pub fn strip_secrets(&mut self) {
+        for printer in &mut self.app.printing.printers {
+            printer.api_key = None;
+        }
+        self.api_keys.clear();
+    }
+}
+
+/// A saved profile as listed in the UI
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsProfile {
+    pub name: String,
+    pub saved_at: i64,
+}
+
+pub fn parse_bundle(json: &str) -> Result<SettingsBundle, String> {
+    let bundle: SettingsBundle =
+        serde_json::from_str(json).map_err(|e| format!("Not a settings export: {e}"))?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Settings were exported by a newer version (format {})",
+            bundle.version
+        ));
+    }
+    Ok(bundle)
+}
+
+/// Settings to apply from an import, keeping what only makes sense on this
+/// machine: the key storage backend, and printer API keys the bundle left out
+pub fn merge_imported(current: &AppSettings, mut imported: AppSettings) -> AppSettings {
+    imported.key_storage = current.key_storage;
+    for printer in &mut imported.printing.printers {
+        if printer.api_key.is_none() {
+            printer.api_key = current
+                .printing
+                .printers
+                .iter()
+                .find(|local| local.id == printer.id)
+                .and_then(|local| local.api_key.clone());
+        }
+    }
+    imported
+}
+
+/// Trimmed profile name, rejecting empty or overlong ones
+pub fn profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".into());
+    }
+    if name.chars().count() > MAX_PROFILE_NAME_LEN || name.chars().any(char::is_control) {
+        return Err(format!(
+            "Profile names are up to {MAX_PROFILE_NAME_LEN} printable characters"
+        ));
+    }
+    Ok(name.to_string())
+}
+
+pub fn read_profiles(path: &Path) -> BTreeMap<String, SettingsBundle> {
+    let Ok(json) = fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        eprintln!("[settings] Ignoring unreadable {}: {e}", path.display());
+        BTreeMap::new()
+    })
+}
+
+pub fn write_profiles(
+    path: &Path,
+    profiles: &BTreeMap<String, SettingsBundle>,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let json = serde_json::to_string_pretty(profiles)
+        .map_err(|e| format!("Failed to serialize profiles: {e}"))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+pub fn list_profiles(profiles: &BTreeMap<String, SettingsBundle>) -> Vec<SettingsProfile> {
+    profiles
+        .iter()
+        .map(|(name, bundle)| SettingsProfile {
+            name: name.clone(),
+            saved_at: bundle.exported_at,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::printing::{PrinterConnection, PrinterKind, PrintingSettings};
+    use crate::secrets::KeyStorageBackend;
+
+    fn printer(api_key: Option<&str>) -> PrinterConnection {
+        PrinterConnection {
+            id: "octopi".into(),
+            name: "OctoPi".into(),
+            kind: PrinterKind::OctoPrint,
+            url: "http://octopi.local".into(),
+            api_key: api_key.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn export_without_secrets_keeps_local_keys_on_import() {
+        let local = AppSettings {
+            key_storage: KeyStorageBackend::Keychain,
+            printing: PrintingSettings {
+                printers: vec![printer(Some("local-key"))],
+            },
+            ..Default::default()
+        };
+
+        let mut exported = local.clone();
+        exported.key_storage = KeyStorageBackend::File;
+        exported.openscad.active = Some("system".into());
+        let mut bundle = SettingsBundle::new(exported, serde_json::json!({ "theme": "dark" }), 5);
+        bundle.api_keys.insert("anthropic".into(), "sk-ant".into());
+        bundle.strip_secrets();
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains("local-key") && !json.contains("sk-ant"));
+
+        let imported = parse_bundle(&json).unwrap();
+        assert_eq!(imported.ui["theme"], "dark");
+        let merged = merge_imported(&local, imported.app);
+        assert_eq!(merged.key_storage, KeyStorageBackend::Keychain);
+        assert_eq!(merged.openscad.active.as_deref(), Some("system"));
+        assert_eq!(
+            merged.printing.printers[0].api_key.as_deref(),
+            Some("local-key")
+        );
+
+        let future = json.replace("\"version\":1", "\"version\":99");
+        assert!(parse_bundle(&future).is_err());
+        assert!(parse_bundle("{}").is_err());
+    }
+
+    #[test]
+    fn profiles_round_trip_by_name() {
+        let path = std::env::temp_dir()
+            .join("openscad-studio-settings-transfer-tests")
+            .join(uuid::Uuid::new_v4().to_string())
+            .join(PROFILES_FILE_NAME);
+
+        assert!(read_profiles(&path).is_empty());
+        assert_eq!(profile_name("  laptop ").unwrap(), "laptop");
+        assert!(profile_name("   ").is_err());
+        assert!(profile_name(&"x".repeat(65)).is_err());
+
+        let mut profiles = BTreeMap::new();
+        let name = profile_name("workstation with nightly OpenSCAD").unwrap();
+        profiles.insert(
+            name,
+            SettingsBundle::new(AppSettings::default(), serde_json::Value::Null, 7),
+        );
+        profiles.insert(
+            "laptop".into(),
+            SettingsBundle::new(AppSettings::default(), serde_json::Value::Null, 3),
+        );
+        write_profiles(&path, &profiles).unwrap();
+
+        let listed = list_profiles(&read_profiles(&path));
+        let names: Vec<_> = listed.iter().map(|profile| profile.name.as_str()).collect();
+        assert_eq!(names, ["laptop", "workstation with nightly OpenSCAD"]);
+        assert_eq!(listed[1].saved_at, 7);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
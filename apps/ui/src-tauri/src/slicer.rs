@@ -0,0 +1,355 @@
+/**
+ * Slicer hand-off
+ *
+ * "Send to slicer" exports the model to a temporary STL or 3MF and opens it
+ * in PrusaSlicer, UltiMaker Cura or Bambu Studio. Slicers are found on the
+ * PATH or in their usual install locations; a configured executable always
+ * wins. Install locations may contain one `*` per path component (versioned
+ * folders, AppImages) and a leading `~` for the home folder; the newest
+ * match is used.
+ */
+use crate::settings::SlicerSettings;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+pub struct SlicerApp {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Command names looked up on the PATH
+    pub binaries: &'static [&'static str],
+    /// Model format the slicer opens best
+    pub format: &'static str,
+    pub locations: &'static [&'static str],
+}
+
+#[cfg(target_os = "macos")]
+pub const SLICERS: &[SlicerApp] = &[
+    SlicerApp {
+        id: "prusaslicer",
+        name: "PrusaSlicer",
+        binaries: &["prusa-slicer"],
+        format: "3mf",
+        locations: &[
+            "/Applications/PrusaSlicer.app",
+            "/Applications/Original Prusa Drivers/PrusaSlicer.app",
+            "~/Applications/PrusaSlicer.app",
+        ],
+    },
+    SlicerApp {
+        id: "cura",
+        name: "UltiMaker Cura",
+        binaries: &[],
+        format: "stl",
+        locations: &[
+            "/Applications/UltiMaker Cura.app",
+            "/Applications/Ultimaker Cura.app",
+            "~/Applications/UltiMaker Cura.app",
+        ],
+    },
+    SlicerApp {
+        id: "bambustudio",
+        name: "Bambu Studio",
+        binaries: &[],
+        format: "3mf",
+        locations: &[
+            "/Applications/BambuStudio.app",
+            "~/Applications/BambuStudio.app",
+        ],
+    },
+];
+#[cfg(target_os = "windows")]
+pub const SLICERS: &[SlicerApp] = &[
+    SlicerApp {
+        id: "prusaslicer",
+        name: "PrusaSlicer",
+        binaries: &["prusa-slicer"],
+        format: "3mf",
+        locations: &[
+            "C:\\Program Files\\Prusa3D\\PrusaSlicer\\prusa-slicer.exe",
+            "C:\\Program Files\\PrusaSlicer*\\prusa-slicer.exe",
+        ],
+    },
+    SlicerApp {
+        id: "cura",
+        name: "UltiMaker Cura",
+        binaries: &[],
+        format: "stl",
+        locations: &[
+            "C:\\Program Files\\UltiMaker Cura *\\UltiMaker-Cura.exe",
+            "C:\\Program Files\\Ultimaker Cura *\\Ultimaker-Cura.exe",
+            "C:\\Program Files\\Ultimaker Cura *\\Cura.exe",
+        ],
+    },
+    SlicerApp {
+        id: "bambustudio",
+        name: "Bambu Studio",
+        binaries: &["bambu-studio"],
+        format: "3mf",
+        locations: &["C:\\Program Files\\Bambu Studio\\bambu-studio.exe"],
+    },
+];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub const SLICERS: &[SlicerApp] = &[
+    SlicerApp {
+        id: "prusaslicer",
+        name: "PrusaSlicer",
+        binaries: &["prusa-slicer", "PrusaSlicer"],
+        format: "3mf",
+        locations: &[
+            "/var/lib/flatpak/exports/bin/com.prusa3d.PrusaSlicer",
+            "~/.local/share/flatpak/exports/bin/com.prusa3d.PrusaSlicer",
+            "~/Applications/PrusaSlicer*.AppImage",
+            "~/Downloads/PrusaSlicer*.AppImage",
+        ],
+    },
+    SlicerApp {
+        id: "cura",
+        name: "UltiMaker Cura",
+        binaries: &["cura", "UltiMaker-Cura"],
+        format: "stl",
+        locations: &[
+            "/var/lib/flatpak/exports/bin/com.ultimaker.cura",
+            "~/.local/share/flatpak/exports/bin/com.ultimaker.cura",
+            "~/Applications/UltiMaker-Cura*.AppImage",
+            "~/Downloads/UltiMaker-Cura*.AppImage",
+        ],
+    },
+    SlicerApp {
+        id: "bambustudio",
+        name: "Bambu Studio",
+        binaries: &["bambu-studio"],
+        format: "3mf",
+        locations: &[
+            "/var/lib/flatpak/exports/bin/com.bambulab.BambuStudio",
+            "~/.local/share/flatpak/exports/bin/com.bambulab.BambuStudio",
+            "~/Applications/Bambu_Studio*.AppImage",
+            "~/Downloads/Bambu_Studio*.AppImage",
+        ],
+    },
+];
+
+pub const MODEL_FORMATS: &[&str] = &["stl", "3mf"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Slicer {
+    /// Known slicer ID, or "custom" for a configured executable
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    /// Format models are exported in for this slicer
+    pub format: String,
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any run of characters
+pub fn matches_pattern(name: &str, pattern: &str) -> bool {
+    match pattern.split_once('*') {
+        None => name == pattern,
+        Some((prefix, rest)) => {
+            name.len() >= prefix.len()
+                && name.starts_with(prefix)
+                && (0..=name.len() - prefix.len()).any(|skip| {
+                    name.is_char_boundary(prefix.len() + skip)
+                        && matches_pattern(&name[prefix.len() + skip..], rest)
+                })
+        }
+    }
+}
+
+/// Existing path for an install location, picking the newest (greatest)
+/// name wherever a component has a wildcard
+pub fn expand_location(location: &str, home: Option<&Path>) -> Option<PathBuf> {
+    let relative = location
+        .strip_prefix("~/")
+        .or_else(|| location.strip_prefix("~\\"));
+    let mut path = match relative {
+        Some(_) => home?.to_path_buf(),
+        None => PathBuf::new(),
+    };
+    for component in Path::new(relative.unwrap_or(location)).components() {
+        let part = component.as_os_str().to_string_lossy();
+        if part.contains('*') {
+            let newest = std::fs::read_dir(&path)
+                .ok()?
+                .flatten()
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| matches_pattern(name, &part))
+                .max()?;
+            path.push(newest);
+        } else {
+            path.push(component);
+        }
+    }
+    path.exists().then_some(path)
+}
+
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        [name.to_string(), format!("{name}.exe")]
+            .into_iter()
+            .map(|file| dir.join(file))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+fn detect(app: &SlicerApp, home: Option<&Path>) -> Option<Slicer> {
+    let path = app
+        .binaries
+        .iter()
+        .find_map(|name| find_on_path(name))
+        .or_else(|| {
+            app.locations
+                .iter()
+                .find_map(|location| expand_location(location, home))
+        })?;
+    Some(Slicer {
+        id: app.id.to_string(),
+        name: app.name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        format: app.format.to_string(),
+    })
+}
+
+/// Every known slicer installed on this machine
+pub fn detect_slicers() -> Vec<Slicer> {
+    let home =
+        std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }).map(PathBuf::from);
+    SLICERS
+        .iter()
+        .filter_map(|app| detect(app, home.as_deref()))
+        .collect()
+}
+
+/// Slicer to hand models to: the configured executable, else the chosen
+/// (or first) detected slicer. The configured format overrides the
+/// slicer's own.
+pub fn resolve_slicer(settings: &SlicerSettings, detected: &[Slicer]) -> Option<Slicer> {
+    let mut slicer = match settings.executable.as_deref().map(str::trim) {
+        Some(executable) if !executable.is_empty() => {
+            let known = SLICERS
+                .iter()
+                .find(|app| Some(app.id) == settings.slicer.as_deref());
+            Slicer {
+                id: known.map_or("custom", |app| app.id).to_string(),
+                name: known.map_or_else(
+                    || {
+                        Path::new(executable)
+                            .file_stem()
+                            .map_or(executable.to_string(), |stem| {
+                                stem.to_string_lossy().to_string()
+                            })
+                    },
+                    |app| app.name.to_string(),
+                ),
+                path: executable.to_string(),
+                format: known.map_or("stl", |app| app.format).to_string(),
+            }
+        }
+        _ => match settings.slicer.as_deref() {
+            Some(id) => detected.iter().find(|slicer| slicer.id == id)?.clone(),
+            None => detected.first()?.clone(),
+        },
+    };
+    if let Some(format) = settings
+        .format
+        .as_deref()
+        .filter(|format| MODEL_FORMATS.contains(format))
+    {
+        slicer.format = format.to_string();
+    }
+    Some(slicer)
+}
+
+/// Command opening `model` in `slicer`, detached from the app's stdio.
+/// macOS app bundles are opened through Launch Services.
+pub fn slicer_command(slicer: &Path, model: &Path) -> Command {
+    let mut command =
+        if cfg!(target_os = "macos") && slicer.extension().is_some_and(|ext| ext == "app") {
+            let mut command = Command::new("open");
+            command.arg("-a").arg(slicer);
+            command
+        } else {
+            Command::new(slicer)
+        };
+    command
+        .arg(model)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_install_location_patterns() {
+        assert!(matches_pattern(
+            "PrusaSlicer-2.8.1+linux-x64.AppImage",
+            "PrusaSlicer*.AppImage"
+        ));
+        assert!(matches_pattern("UltiMaker Cura 5.8.0", "UltiMaker Cura *"));
+        assert!(!matches_pattern("UltiMaker Cura", "UltiMaker Cura *"));
+        assert!(!matches_pattern(
+            "PrusaSlicer.AppImage.zsync",
+            "PrusaSlicer*.AppImage"
+        ));
+        assert!(matches_pattern("bambu-studio", "bambu-studio"));
+
+        let root = std::env::temp_dir().join(format!("slicer-test-{}", uuid::Uuid::new_v4()));
+        for version in ["5.7.2", "5.8.0"] {
+            let dir = root.join(format!("UltiMaker Cura {version}"));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("UltiMaker-Cura.exe"), "").unwrap();
+        }
+        assert_eq!(
+            expand_location("~/UltiMaker Cura */UltiMaker-Cura.exe", Some(&root)),
+            Some(root.join("UltiMaker Cura 5.8.0").join("UltiMaker-Cura.exe"))
+        );
+        assert_eq!(expand_location("~/Missing*/cura", Some(&root)), None);
+        assert_eq!(expand_location("~/cura", None), None);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn configured_slicer_takes_precedence() {
+        let detected = vec![
+            Slicer {
+                id: "prusaslicer".into(),
+                name: "PrusaSlicer".into(),
+                path: "/usr/bin/prusa-slicer".into(),
+                format: "3mf".into(),
+            },
+            Slicer {
+                id: "cura".into(),
+                name: "UltiMaker Cura".into(),
+                path: "/usr/bin/cura".into(),
+                format: "stl".into(),
+            },
+        ];
+        let mut settings = SlicerSettings::default();
+        assert_eq!(
+            resolve_slicer(&settings, &detected),
+            Some(detected[0].clone())
+        );
+        assert_eq!(resolve_slicer(&settings, &[]), None);
+
+        settings.slicer = Some("cura".into());
+        assert_eq!(
+            resolve_slicer(&settings, &detected),
+            Some(detected[1].clone())
+        );
+        settings.format = Some("3mf".into());
+        assert_eq!(resolve_slicer(&settings, &detected).unwrap().format, "3mf");
+
+        settings.slicer = None;
+        settings.executable = Some("/opt/OrcaSlicer/orca-slicer".into());
+        let custom = resolve_slicer(&settings, &[]).unwrap();
+        assert_eq!(custom.id, "custom");
+        assert_eq!(custom.name, "orca-slicer");
+        assert_eq!(custom.format, "3mf");
+    }
+}
@@ -0,0 +1,189 @@
+/**
+ * OpenSCAD snippet library
+ *
+ * A curated collection of idioms (chamfers, threads, honeycomb fill, ...)
+ * compiled into the binary from `docs/openscad-snippets.md`. Each `##`
+ * heading is one snippet with a `tags:` line, a description and a single
+ * `openscad` code block. Search ranks like the documentation search, with
+ * tags weighted between titles and descriptions.
+ */
+use crate::docs::tokenize;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+const SNIPPETS: &str = include_str!("../docs/openscad-snippets.md");
+const DEFAULT_LIMIT: usize = 5;
+const TITLE_WEIGHT: usize = 10;
+const TAG_WEIGHT: usize = 5;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub id: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub description: String,
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetSearchResult {
+    #[serde(flatten)]
+    pub snippet: Snippet,
+    pub score: usize,
+}
+
+fn snippet_id(title: &str) -> String {
+    tokenize(title).join("-")
+}
+
+fn parse_snippets(markdown: &str) -> Vec<Snippet> {
+    let mut snippets = Vec::new();
+    let mut current: Option<Snippet> = None;
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        if let Some(title) = line.strip_prefix("## ") {
+            snippets.extend(current.take());
+            current = Some(Snippet {
+                id: snippet_id(title),
+                title: title.trim().to_string(),
+                tags: Vec::new(),
+                description: String::new(),
+                code: String::new(),
+            });
+            continue;
+        }
+        let Some(snippet) = current.as_mut() else {
+            continue;
+        };
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        } else if in_code {
+            snippet.code.push_str(line);
+            snippet.code.push('\n');
+        } else if let Some(tags) = line.strip_prefix("tags:") {
+            snippet.tags = tags.split_whitespace().map(str::to_lowercase).collect();
+        } else if snippet.code.is_empty() {
+            snippet.description.push_str(line);
+            snippet.description.push('\n');
+        }
+    }
+    snippets.extend(current);
+
+    for snippet in &mut snippets {
+        snippet.description = snippet
+            .description
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+    snippets
+}
+
+pub fn snippets() -> &'static [Snippet] {
+    static SNIPPETS_CELL: OnceLock<Vec<Snippet>> = OnceLock::new();
+    SNIPPETS_CELL.get_or_init(|| parse_snippets(SNIPPETS))
+}
+
+pub fn snippet(id: &str) -> Option<&'static Snippet> {
+    snippets().iter().find(|snippet| snippet.id == id)
+}
+
+fn score_snippet(snippet: &Snippet, terms: &[String]) -> usize {
+    let title_tokens = tokenize(&snippet.title);
+    let body_tokens = tokenize(&format!("{} {}", snippet.description, snippet.code));
+    let mut score = 0;
+    let mut matched_terms = 0;
+
+    for term in terms {
+        let in_title = title_tokens.iter().filter(|t| *t == term).count();
+        let in_tags = snippet.tags.iter().filter(|t| *t == term).count();
+        let in_body = body_tokens.iter().filter(|t| *t == term).count();
+        if in_title + in_tags + in_body > 0 {
+            matched_terms += 1;
+        }
+        score += in_title * TITLE_WEIGHT + in_tags * TAG_WEIGHT + in_body;
+    }
+
+    if matched_terms == terms.len() {
+        score *= 2;
+    }
+    score
+}
+
+/// Rank snippets for `query` (best first); an empty query lists them all
+pub fn search_snippets(query: &str, limit: Option<usize>) -> Vec<SnippetSearchResult> {
+    let terms = tokenize(query);
+    if terms.is_empty() {
+        return snippets()
+            .iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|snippet| SnippetSearchResult {
+                snippet: snippet.clone(),
+                score: 0,
+            })
+            .collect();
+    }
+
+    let mut results: Vec<SnippetSearchResult> = snippets()
+        .iter()
+        .filter_map(|snippet| {
+            let score = score_snippet(snippet, &terms);
+            (score > 0).then(|| SnippetSearchResult {
+                snippet: snippet.clone(),
+                score,
+            })
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.snippet.title.cmp(&b.snippet.title))
+    });
+    results.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_snippets_parse() {
+        assert!(snippets().len() >= 10);
+        for snippet in snippets() {
+            assert!(!snippet.tags.is_empty(), "{} has no tags", snippet.id);
+            assert!(
+                !snippet.description.is_empty(),
+                "{} has no description",
+                snippet.id
+            );
+            assert!(
+                snippet.code.contains("module "),
+                "{} has no module",
+                snippet.id
+            );
+            assert!(!snippet.code.contains("```"));
+        }
+        let chamfer = snippet("chamfered-cube").unwrap();
+        assert_eq!(chamfer.title, "Chamfered cube");
+        assert!(chamfer
+            .code
+            .starts_with("module chamfered_cube(size, c = 1) {\n"));
+    }
+
+    #[test]
+    fn searches_titles_tags_and_code() {
+        assert_eq!(
+            search_snippets("honeycomb", None)[0].snippet.id,
+            "honeycomb-fill"
+        );
+        assert_eq!(search_snippets("bolt thread", None)[0].snippet.id, "thread");
+        assert_eq!(search_snippets("countersink", Some(1)).len(), 1);
+        assert_eq!(search_snippets("", None).len(), snippets().len());
+        assert!(search_snippets("zzzz", None).is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+/**
+ * STEP export through an external converter
+ *
+ * OpenSCAD has no STEP writer, so STEP export converts an exported STL with
+ * an external tool: either a user-configured command (with `{input}` and
+ * `{output}` placeholders) or an auto-detected FreeCAD command-line binary
+ * driven by a small bundled script. The result is a faceted solid — exact
+ * CSG surfaces are not recovered.
+ */
+use crate::settings::StepExportSettings;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+pub const STEP_EXPORT_TIMEOUT: Duration = Duration::from_secs(300);
+pub const INPUT_ENV: &str = "OPENSCAD_STUDIO_STEP_INPUT";
+pub const OUTPUT_ENV: &str = "OPENSCAD_STUDIO_STEP_OUTPUT";
+
+const FREECAD_BINARIES: &[&str] = &["freecadcmd", "FreeCADCmd", "freecad.cmd"];
+
+#[cfg(target_os = "macos")]
+const FREECAD_LOCATIONS: &[&str] = &[
+    "/Applications/FreeCAD.app/Contents/Resources/bin/freecadcmd",
+    "/Applications/FreeCAD.app/Contents/MacOS/FreeCADCmd",
+];
+#[cfg(target_os = "windows")]
+const FREECAD_LOCATIONS: &[&str] = &[
+    "C:\\Program Files\\FreeCAD 1.0\\bin\\FreeCADCmd.exe",
+    "C:\\Program Files\\FreeCAD 0.21\\bin\\FreeCADCmd.exe",
+    "C:\\Program Files\\FreeCAD\\bin\\FreeCADCmd.exe",
+];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const FREECAD_LOCATIONS: &[&str] = &["/usr/bin/freecadcmd", "/usr/local/bin/freecadcmd"];
+
+/// FreeCAD script: mesh -> sewn shell -> solid -> STEP
+pub const FREECAD_SCRIPT: &str = r#"import os
+import FreeCAD
+import Mesh
+import Part
+
+mesh = Mesh.Mesh(os.environ["OPENSCAD_STUDIO_STEP_INPUT"])
+shape = Part.Shape()
+shape.makeShapeFromMesh(mesh.Topology, 0.05)
+shape.sewShape()
+try:
+    shape = Part.makeSolid(shape)
+except Exception as error:
+    print("Could not close the mesh into a solid, exporting a shell: %s" % error)
+shape.exportStep(os.environ["OPENSCAD_STUDIO_STEP_OUTPUT"])
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepConverter {
+    FreeCad(PathBuf),
+    Custom { command: String, args: Vec<String> },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepCapability {
+    pub available: bool,
+    /// "freecad" or "custom" when available
+    pub kind: Option<String>,
+    pub converter: Option<String>,
+    pub message: String,
+}
+
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        [name.to_string(), format!("{name}.exe")]
+            .into_iter()
+            .map(|file| dir.join(file))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+pub fn detect_freecad() -> Option<PathBuf> {
+    FREECAD_BINARIES
+        .iter()
+        .find_map(|name| find_on_path(name))
+        .or_else(|| {
+            FREECAD_LOCATIONS
+                .iter()
+                .map(PathBuf::from)
+                .find(|path| path.is_file())
+        })
+}
+
+/// Configured converter first, then an auto-detected FreeCAD
+pub fn resolve_converter(settings: &StepExportSettings) -> Option<StepConverter> {
+    match settings.converter_command.as_deref().map(str::trim) {
+        Some(command) if !command.is_empty() => Some(StepConverter::Custom {
+            command: command.to_string(),
+            args: if settings.converter_args.is_empty() {
+                vec!["{input}".into(), "{output}".into()]
+            } else {
+                settings.converter_args.clone()
+            },
+        }),
+        _ => detect_freecad().map(StepConverter::FreeCad),
+    }
+}
+
+pub fn capability(converter: Option<&StepConverter>) -> StepCapability {
+    match converter {
+        Some(StepConverter::FreeCad(path)) => StepCapability {
+            available: true,
+            kind: Some("freecad".into()),
+            converter: Some(path.to_string_lossy().to_string()),
+            message: "STEP export uses FreeCAD to convert the exported mesh.".into(),
+        },
+        Some(StepConverter::Custom { command, .. }) => StepCapability {
+            available: true,
+            kind: Some("custom".into()),
+            converter: Some(command.clone()),
+            message: "STEP export uses the configured converter command.".into(),
+        },
+        None => StepCapability {
+            available: false,
+            kind: None,
+            converter: None,
+            message: "STEP export needs FreeCAD (freecadcmd) on the PATH or a converter command configured in settings.".into(),
+        },
+    }
+}
+
+/// Substitute `{input}`/`{output}` in converter arguments
+pub fn expand_args(args: &[String], input: &Path, output: &Path) -> Vec<String> {
+    args.iter()
+        .map(|arg| {
+            arg.replace("{input}", &input.to_string_lossy())
+                .replace("{output}", &output.to_string_lossy())
+        })
+        .collect()
+}
+
+/// Command converting `input` (STL) to `output` (STEP). FreeCAD runs
+/// `script_path`, which must contain [`FREECAD_SCRIPT`].
+pub fn converter_command(
+    converter: &StepConverter,
+    input: &Path,
+    output: &Path,
+    script_path: &Path,
+) -> Command {
+    let mut command = match converter {
+        StepConverter::FreeCad(binary) => {
+            let mut command = Command::new(binary);
+            command.arg(script_path);
+            command
+        }
+        StepConverter::Custom {
+            command: program,
+            args,
+        } => {
+            let mut command = Command::new(program);
+            command.args(expand_args(args, input, output));
+            command
+        }
+    };
+    command.env(INPUT_ENV, input).env(OUTPUT_ENV, output);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_converter_takes_precedence() {
+        let settings = StepExportSettings {
+            converter_command: Some("mesh2step".into()),
+            converter_args: Vec::new(),
+        };
+        let converter = resolve_converter(&settings).unwrap();
+        assert_eq!(
+            converter,
+            StepConverter::Custom {
+                command: "mesh2step".into(),
+                args: vec!["{input}".into(), "{output}".into()],
+            }
+        );
+        assert!(capability(Some(&converter)).available);
+        assert!(!capability(None).available);
+
+        let args = expand_args(
+            &["--in={input}".into(), "-o".into(), "{output}".into()],
+            Path::new("/tmp/a.stl"),
+            Path::new("/tmp/a.step"),
+        );
+        assert_eq!(args, vec!["--in=/tmp/a.stl", "-o", "/tmp/a.step"]);
+    }
+}
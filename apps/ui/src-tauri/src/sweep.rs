@@ -0,0 +1,145 @@
+/**
+ * Parameter sweep contact sheets
+ *
+ * Renders one small PNG preview per candidate value of a top-level variable
+ * and tiles them into a single contact-sheet image, left to right and top to
+ * bottom in the order the values were given.
+ */
+use std::io::Cursor;
+
+pub const MAX_SWEEP_VALUES: usize = 12;
+pub const DEFAULT_TILE_SIZE: u32 = 256;
+const GUTTER: u32 = 4;
+const BACKGROUND: [u8; 4] = [255, 255, 255, 255];
+const FAILED_TILE: [u8; 4] = [224, 224, 224, 255];
+
+/// Decoded RGBA8 image
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    fn filled(width: u32, height: u32, color: [u8; 4]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: color.repeat((width * height) as usize),
+        }
+    }
+
+    /// Copy `tile` into this image at (`x`, `y`), clipping at the edges
+    fn blit(&mut self, tile: &RgbaImage, x: u32, y: u32) {
+        let copy_width = tile.width.min(self.width.saturating_sub(x)) as usize;
+        for row in 0..tile.height.min(self.height.saturating_sub(y)) {
+            let src = (row * tile.width) as usize * 4;
+            let dst = (((y + row) * self.width + x) as usize) * 4;
+            self.pixels[dst..dst + copy_width * 4]
+                .copy_from_slice(&tile.pixels[src..src + copy_width * 4]);
+        }
+    }
+}
+
+pub fn decode_png(bytes: &[u8]) -> Result<RgbaImage, String> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| format!("Failed to read PNG: {e}"))?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(|e| format!("Failed to decode PNG: {e}"))?;
+    buffer.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer
+            .chunks_exact(3)
+            .flat_map(|px| [px[0], px[1], px[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buffer
+            .chunks_exact(2)
+            .flat_map(|px| [px[0], px[0], px[0], px[1]])
+            .collect(),
+        png::ColorType::Grayscale => buffer.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::Indexed => return Err("Unexpected indexed PNG after expansion".into()),
+    };
+
+    Ok(RgbaImage {
+        width: info.width,
+        height: info.height,
+        pixels,
+    })
+}
+
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, image.width, image.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&image.pixels))
+        .map_err(|e| format!("Failed to encode PNG: {e}"))?;
+    Ok(out)
+}
+
+/// Number of columns for a roughly square sheet
+pub fn sheet_columns(tile_count: usize) -> u32 {
+    (tile_count as f64).sqrt().ceil().max(1.0) as u32
+}
+
+/// Tile previews into one sheet. `None` tiles (failed renders) are drawn as
+/// grey placeholders so positions still line up with the requested values.
+pub fn compose_contact_sheet(tiles: &[Option<RgbaImage>], tile_size: u32) -> RgbaImage {
+    let columns = sheet_columns(tiles.len());
+    let rows = (tiles.len() as u32).div_ceil(columns).max(1);
+    let mut sheet = RgbaImage::filled(
+        columns * tile_size + (columns + 1) * GUTTER,
+        rows * tile_size + (rows + 1) * GUTTER,
+        BACKGROUND,
+    );
+    let placeholder = RgbaImage::filled(tile_size, tile_size, FAILED_TILE);
+
+    for (index, tile) in tiles.iter().enumerate() {
+        let column = index as u32 % columns;
+        let row = index as u32 / columns;
+        let x = GUTTER + column * (tile_size + GUTTER);
+        let y = GUTTER + row * (tile_size + GUTTER);
+        sheet.blit(tile.as_ref().unwrap_or(&placeholder), x, y);
+    }
+
+    sheet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contact_sheet_round_trips_through_png() {
+        let red = RgbaImage::filled(8, 8, [255, 0, 0, 255]);
+        let tiles = vec![Some(red), None, None];
+
+        let sheet = compose_contact_sheet(&tiles, 8);
+        // 3 tiles -> 2x2 grid
+        assert_eq!(sheet.width, 2 * 8 + 3 * GUTTER);
+        assert_eq!(sheet.height, 2 * 8 + 3 * GUTTER);
+
+        let decoded = decode_png(&encode_png(&sheet).unwrap()).unwrap();
+        let pixel = |x: u32, y: u32| {
+            let i = ((y * decoded.width + x) * 4) as usize;
+            [
+                decoded.pixels[i],
+                decoded.pixels[i + 1],
+                decoded.pixels[i + 2],
+                decoded.pixels[i + 3],
+            ]
+        };
+        assert_eq!(pixel(GUTTER, GUTTER), [255, 0, 0, 255]);
+        assert_eq!(pixel(2 * GUTTER + 8, GUTTER), FAILED_TILE);
+        assert_eq!(pixel(0, 0), BACKGROUND);
+    }
+}
@@ -0,0 +1,370 @@
+/**
+ * OpenSCAD symbol index
+ *
+ * Finds the module, function and variable definitions at file scope and the
+ * files pulled in with `include <...>` / `use <...>`, from the
+ * tree-sitter-openscad syntax tree (the grammar the editor's formatter and
+ * customizer parse with). It backs the editor's document outline,
+ * go-to-definition and completion.
+ *
+ * Included files are followed the way OpenSCAD sees them: `include` makes
+ * everything in the file visible, `use` only its modules and functions, and
+ * a used file's own `use` statements are private to it.
+ */
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser, Tree};
+
+const MAX_COMPLETIONS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Module,
+    Function,
+    Variable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// 1-based line of the name
+    pub line: usize,
+    /// 1-based column of the name
+    pub col: usize,
+    /// Signature of a module or function, value of a variable
+    pub detail: String,
+    /// File the symbol is defined in, when it isn't the document itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncludeKind {
+    Include,
+    Use,
+}
+
+/// Syntax tree of `code`, or `None` if the grammar couldn't be loaded
+fn parse(code: &str) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_openscad::LANGUAGE.into())
+        .ok()?;
+    parser.parse(code, None)
+}
+
+/// Statements at file scope. Statements the parser couldn't make sense of
+/// end up in `ERROR` nodes, whose children are still looked at, so a file
+/// that is mid-edit keeps the rest of its outline.
+fn top_level_nodes(tree: &Tree) -> Vec<Node<'_>> {
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let mut nodes = Vec::new();
+    for node in root.named_children(&mut cursor) {
+        if node.is_error() {
+            let mut inner = node.walk();
+            nodes.extend(node.named_children(&mut inner));
+        } else {
+            nodes.push(node);
+        }
+    }
+    nodes
+}
+
+fn node_text<'a>(node: Node, code: &'a str) -> &'a str {
+    node.utf8_text(code.as_bytes()).unwrap_or_default()
+}
+
+/// First named child of `node` that is one of `kinds`
+fn child_of_kind<'tree>(node: Node<'tree>, kinds: &[&str]) -> Option<Node<'tree>> {
+    let mut cursor = node.walk();
+    let found = node
+        .named_children(&mut cursor)
+        .find(|child| kinds.contains(&child.kind()));
+    found
+}
+
+/// Parameter names of a `parameters_declaration`, without defaults
+fn parameter_names(parameters: Node, code: &str) -> Vec<String> {
+    let mut cursor = parameters.walk();
+    let names = parameters
+        .named_children(&mut cursor)
+        .filter(|parameter| parameter.kind() == "parameter")
+        .filter_map(|parameter| {
+            let name = node_text(parameter, code).split('=').next()?.trim();
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .collect();
+    names
+}
+
+/// The symbol a file-scope statement defines, if it defines one
+fn definition(node: Node, code: &str) -> Option<Symbol> {
+    let kind = match node.kind() {
+        "module_declaration" => SymbolKind::Module,
+        "function_declaration" => SymbolKind::Function,
+        "assignment" => SymbolKind::Variable,
+        _ => return None,
+    };
+    let name_node = child_of_kind(node, &["identifier", "special_variable"])?;
+    let name = node_text(name_node, code).to_string();
+    let detail = match kind {
+        SymbolKind::Variable => node
+            .child_by_field_name("value")
+            .or_else(|| node.named_child(1))
+            .map(|value| node_text(value, code).to_string())
+            .unwrap_or_default(),
+        SymbolKind::Module | SymbolKind::Function => {
+            let parameters = child_of_kind(node, &["parameters_declaration"])
+                .map(|parameters| parameter_names(parameters, code))
+                .unwrap_or_default();
+            format!("{name}({})", parameters.join(", "))
+        }
+    };
+    let position = name_node.start_position();
+    Some(Symbol {
+        name,
+        kind,
+        line: position.row + 1,
+        col: position.column + 1,
+        detail,
+        file: None,
+    })
+}
+
+/// Module, function and variable definitions at file scope, in source
+/// order. A variable assigned more than once is listed at its last
+/// assignment, which is the one OpenSCAD uses.
+pub fn document_symbols(code: &str) -> Vec<Symbol> {
+    let Some(tree) = parse(code) else {
+        return Vec::new();
+    };
+    let mut symbols: Vec<Symbol> = Vec::new();
+    for symbol in top_level_nodes(&tree)
+        .into_iter()
+        .filter_map(|node| definition(node, code))
+    {
+        if symbol.kind == SymbolKind::Variable {
+            symbols.retain(|existing| {
+                existing.kind != SymbolKind::Variable || existing.name != symbol.name
+            });
+        }
+        symbols.push(symbol);
+    }
+    symbols
+}
+
+/// `include <...>` and `use <...>` statements, in source order
+pub fn include_statements(code: &str) -> Vec<(IncludeKind, String)> {
+    let Some(tree) = parse(code) else {
+        return Vec::new();
+    };
+    top_level_nodes(&tree)
+        .into_iter()
+        .filter_map(|node| {
+            let kind = match node.kind() {
+                "include_statement" => IncludeKind::Include,
+                "use_statement" => IncludeKind::Use,
+                _ => return None,
+            };
+            let path = node_text(child_of_kind(node, &["include_path"])?, code);
+            let path = path.trim().trim_start_matches('<').trim_end_matches('>');
+            Some((kind, path.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Symbols visible through the includes of `code`, each naming its file.
+/// Relative includes resolve against `dir` and then `library_paths`, as in
+/// OpenSCAD; `read` supplies file contents (e.g. unsaved editor buffers).
+pub fn included_symbols(
+    code: &str,
+    dir: Option<&Path>,
+    library_paths: &[PathBuf],
+    read: impl Fn(&Path) -> Option<String>,
+) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    let mut visited = HashSet::new();
+    // (source, directory it resolves against, reached through `use`)
+    let mut pending = vec![(code.to_string(), dir.map(Path::to_path_buf), false)];
+
+    while let Some((source, dir, used)) = pending.pop() {
+        for (kind, include) in include_statements(&source) {
+            if used && kind == IncludeKind::Use {
+                continue;
+            }
+            let resolved = dir
+                .iter()
+                .chain(library_paths)
+                .map(|base| base.join(&include))
+                .find_map(|path| Some((read(&path)?, path)));
+            let Some((contents, path)) = resolved else {
+                continue;
+            };
+            if !visited.insert(path.clone()) {
+                continue;
+            }
+            let used = used || kind == IncludeKind::Use;
+            let file = path.to_string_lossy().to_string();
+            symbols.extend(
+                document_symbols(&contents)
+                    .into_iter()
+                    .filter(|symbol| !used || symbol.kind != SymbolKind::Variable)
+                    .map(|symbol| Symbol {
+                        file: Some(file.clone()),
+                        ..symbol
+                    }),
+            );
+            pending.push((contents, path.parent().map(Path::to_path_buf), used));
+        }
+    }
+    symbols
+}
+
+/// Where `name` is defined: the document's own (last) definition, or else
+/// the first one found through its includes
+pub fn find_definition(
+    name: &str,
+    code: &str,
+    dir: Option<&Path>,
+    library_paths: &[PathBuf],
+    read: impl Fn(&Path) -> Option<String>,
+) -> Option<Symbol> {
+    document_symbols(code)
+        .into_iter()
+        .rev()
+        .find(|symbol| symbol.name == name)
+        .or_else(|| {
+            included_symbols(code, dir, library_paths, read)
+                .into_iter()
+                .find(|symbol| symbol.name == name)
+        })
+}
+
+/// Definitions starting with `prefix` (ignoring case), the document's own
+/// first, one entry per name and kind
+pub fn completion_candidates(
+    prefix: &str,
+    code: &str,
+    dir: Option<&Path>,
+    library_paths: &[PathBuf],
+    read: impl Fn(&Path) -> Option<String>,
+) -> Vec<Symbol> {
+    let prefix = prefix.to_lowercase();
+    let mut seen = HashSet::new();
+    document_symbols(code)
+        .into_iter()
+        .chain(included_symbols(code, dir, library_paths, read))
+        .filter(|symbol| symbol.name.to_lowercase().starts_with(&prefix))
+        .filter(|symbol| seen.insert((symbol.name.clone(), symbol.kind)))
+        .take(MAX_COMPLETIONS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const CODE: &str = "\
+include <shapes.scad>
+use <lib/util.scad>
+// module commented_out() {}
+size = 10;
+module box(w, h = size, center = [0, 0]) {
+    module inner() {}
+    cube([w, h, 1]);
+}
+function area(w, h) = w * h;
+label = \"function fake() = 1;\";
+";
+
+    fn files() -> HashMap<PathBuf, String> {
+        HashMap::from([
+            (
+                PathBuf::from("/project/shapes.scad"),
+                "wall = 2;\nmodule rounded(r) {}\n".to_string(),
+            ),
+            (
+                PathBuf::from("/project/lib/util.scad"),
+                "use <private.scad>\nutil_gap = 1;\nfunction lerp(a, b, t) = a;\n".to_string(),
+            ),
+            (
+                PathBuf::from("/project/lib/private.scad"),
+                "module hidden() {}\n".to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn finds_top_level_definitions() {
+        let symbols = document_symbols(CODE);
+        let summary: Vec<_> = symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.line, symbol.col))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("size", SymbolKind::Variable, 4, 1),
+                ("box", SymbolKind::Module, 5, 8),
+                ("area", SymbolKind::Function, 9, 10),
+                ("label", SymbolKind::Variable, 10, 1),
+            ]
+        );
+        assert_eq!(symbols[1].detail, "box(w, h, center)");
+        assert_eq!(
+            include_statements(CODE),
+            [
+                (IncludeKind::Include, "shapes.scad".to_string()),
+                (IncludeKind::Use, "lib/util.scad".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_a_reassigned_variable_once() {
+        let symbols = document_symbols("width = 1;\nmodule part() {}\nwidth = 2; // wins\n");
+        let summary: Vec<_> = symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.line, symbol.detail.as_str()))
+            .collect();
+        assert_eq!(summary, [("part", 2, "part()"), ("width", 3, "2")]);
+    }
+
+    #[test]
+    fn follows_includes_like_openscad() {
+        let files = files();
+        let read = |path: &Path| files.get(path).cloned();
+        let dir = Some(Path::new("/project"));
+
+        let names: Vec<_> = included_symbols(CODE, dir, &[], read)
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect();
+        assert!(names.contains(&"wall".to_string()));
+        assert!(names.contains(&"lerp".to_string()));
+        // Variables of used files and their own `use`s aren't visible
+        assert!(!names.contains(&"util_gap".to_string()));
+        assert!(!names.contains(&"hidden".to_string()));
+
+        let definition = find_definition("rounded", CODE, dir, &[], read).unwrap();
+        assert_eq!(definition.file.as_deref(), Some("/project/shapes.scad"));
+        assert_eq!((definition.line, definition.col), (2, 8));
+        assert_eq!(
+            find_definition("box", CODE, dir, &[], read).unwrap().file,
+            None
+        );
+        assert!(find_definition("inner", CODE, dir, &[], read).is_none());
+
+        let completions: Vec<_> = completion_candidates("L", CODE, dir, &[], read)
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect();
+        assert_eq!(completions, ["label", "lerp"]);
+    }
+}
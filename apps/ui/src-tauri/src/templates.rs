@@ -0,0 +1,213 @@
+/**
+ * Project templates
+ *
+ * Starters for new projects: built-in parametric designs (sources under
+ * `src-tauri/templates/`) and user templates saved from a project. A
+ * template's parameters are the customizer parameters of its main file;
+ * creating a project writes the chosen values into those assignments. User
+ * templates are folders of project files with a `.template.json` manifest,
+ * which project listings skip like any dotfile.
+ */
+use crate::customizer::{customizer_schema, CustomizerParameter};
+use crate::variables::find_top_level_variables;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+pub const MANIFEST_FILE_NAME: &str = ".template.json";
+
+pub struct BuiltinTemplate {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub main_file: &'static str,
+    pub source: &'static str,
+}
+
+pub const BUILTIN_TEMPLATES: &[BuiltinTemplate] = &[
+    BuiltinTemplate {
+        id: "box-with-lid",
+        name: "Box with lid",
+        description: "Rounded box with a friction-fit lid",
+        main_file: "box_with_lid.scad",
+        source: include_str!("../templates/box_with_lid.scad"),
+    },
+    BuiltinTemplate {
+        id: "gridfinity-bin",
+        name: "Gridfinity bin",
+        description: "Storage bin for 42 mm Gridfinity baseplates",
+        main_file: "gridfinity_bin.scad",
+        source: include_str!("../templates/gridfinity_bin.scad"),
+    },
+    BuiltinTemplate {
+        id: "enclosure",
+        name: "Electronics enclosure",
+        description: "Box with circuit board standoffs and a screw-down lid",
+        main_file: "enclosure.scad",
+        source: include_str!("../templates/enclosure.scad"),
+    },
+    BuiltinTemplate {
+        id: "gear",
+        name: "Spur gear",
+        description: "Involute spur gear with a bore",
+        main_file: "gear.scad",
+        source: include_str!("../templates/gear.scad"),
+    },
+];
+
+/// `.template.json` of a user template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub main_file: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateSummary {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub builtin: bool,
+    pub main_file: String,
+    /// Template-relative paths of its files
+    pub files: Vec<String>,
+    pub parameters: Vec<CustomizerParameter>,
+}
+
+impl BuiltinTemplate {
+    pub fn summary(&self) -> TemplateSummary {
+        TemplateSummary {
+            id: self.id.to_string(),
+            name: self.name.to_string(),
+            description: self.description.to_string(),
+            builtin: true,
+            main_file: self.main_file.to_string(),
+            files: vec![self.main_file.to_string()],
+            parameters: customizer_schema(self.source).parameters,
+        }
+    }
+}
+
+pub fn builtin_template(id: &str) -> Option<&'static BuiltinTemplate> {
+    BUILTIN_TEMPLATES.iter().find(|template| template.id == id)
+}
+
+/// Folder name for a user template called `name`
+pub fn template_slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "template".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Whether `path` stays inside the folder it is relative to
+pub fn is_contained(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Replace the values of top-level assignments in `code`. Values are
+/// OpenSCAD literals, as passed to `-D`.
+pub fn apply_parameters(code: &str, params: &BTreeMap<String, String>) -> Result<String, String> {
+    let variables = find_top_level_variables(code);
+    let mut edits = Vec::with_capacity(params.len());
+    for (name, value) in params {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(format!("Value for '{name}' is empty"));
+        }
+        let variable = variables
+            .iter()
+            .find(|variable| &variable.name == name)
+            .ok_or_else(|| format!("Unknown template parameter: {name}"))?;
+        let line_start: usize = code
+            .split_inclusive('\n')
+            .take(variable.line - 1)
+            .map(str::len)
+            .sum();
+        let name_start = line_start + variable.col - 1;
+        let value_start = code[name_start..]
+            .find('=')
+            .and_then(|eq| {
+                let after = name_start + eq + 1;
+                code[after..]
+                    .find(variable.value.as_str())
+                    .map(|offset| after + offset)
+            })
+            .ok_or_else(|| format!("Could not find the value of {name}"))?;
+        edits.push((value_start, variable.value.len(), value));
+    }
+    edits.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+    let mut code = code.to_string();
+    for (start, len, value) in edits {
+        code.replace_range(start..start + len, value);
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_templates_are_parametric() {
+        for template in BUILTIN_TEMPLATES {
+            let summary = template.summary();
+            assert!(
+                summary.parameters.len() >= 3,
+                "{} has too few parameters",
+                template.id
+            );
+            assert!(summary
+                .parameters
+                .iter()
+                .all(|parameter| parameter.group != "Hidden"));
+        }
+        assert!(builtin_template("gear").is_some());
+        assert_eq!(template_slug("  My Bracket (v2) "), "my-bracket-v2");
+        assert_eq!(template_slug("!!"), "template");
+        assert!(is_contained("parts/lid.scad"));
+        assert!(!is_contained("../lid.scad"));
+        assert!(!is_contained("/etc/passwd"));
+    }
+
+    #[test]
+    fn applies_parameter_values() {
+        let code = "// Width\nwidth = 20; // [10:100]\nlabel = \"a\";\nsize = [width, 10];\n";
+        let params = BTreeMap::from([
+            ("width".to_string(), "35".to_string()),
+            ("label".to_string(), "\"lid\"".to_string()),
+        ]);
+        assert_eq!(
+            apply_parameters(code, &params).unwrap(),
+            "// Width\nwidth = 35; // [10:100]\nlabel = \"lid\";\nsize = [width, 10];\n"
+        );
+
+        let gear = builtin_template("gear").unwrap().source;
+        let params = BTreeMap::from([("teeth".to_string(), "32".to_string())]);
+        assert!(apply_parameters(gear, &params)
+            .unwrap()
+            .contains("\nteeth = 32; // [6:1:120]\n"));
+
+        let unknown = BTreeMap::from([("depth".to_string(), "1".to_string())]);
+        assert!(apply_parameters(code, &unknown).is_err());
+        let empty = BTreeMap::from([("width".to_string(), " ".to_string())]);
+        assert!(apply_parameters(code, &empty).is_err());
+    }
+}
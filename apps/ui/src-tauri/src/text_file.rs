@@ -0,0 +1,198 @@
+/**
+ * Encoding-aware text files
+ *
+ * Source files are handed to the editor as plain LF-terminated UTF-8, while
+ * the original encoding, BOM and line endings are reported alongside so a
+ * save writes the file back in the same format it was read.
+ *
+ * Files without a BOM that aren't valid UTF-8 are read as Latin-1 (older
+ * Windows editors save that way); every byte maps to a character, so such
+ * files always open. They are written back as Latin-1 unless the text no
+ * longer fits it, in which case they become UTF-8.
+ */
+use serde::{Deserialize, Serialize};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16le,
+    Utf16be,
+    Latin1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextFormat {
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+}
+
+impl TextFormat {
+    /// The format `text` is written in: this one, or UTF-8 when the text has
+    /// characters Latin-1 can't hold
+    pub fn for_text(&self, text: &str) -> TextFormat {
+        let encoding = match self.encoding {
+            TextEncoding::Latin1 if text.chars().any(|c| c > '\u{FF}') => TextEncoding::Utf8,
+            encoding => encoding,
+        };
+        TextFormat {
+            encoding,
+            line_ending: self.line_ending,
+        }
+    }
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        Self {
+            encoding: TextEncoding::Utf8,
+            line_ending: LineEnding::Lf,
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Result<String, String> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err("UTF-16 file has an odd number of bytes".into());
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16(&units).map_err(|e| format!("Invalid UTF-16 text: {e}"))
+}
+
+/// Dominant line ending; files without CRLF are treated as LF
+fn detect_line_ending(text: &str) -> LineEnding {
+    let crlf = text.matches("\r\n").count();
+    let lf = text.matches('\n').count() - crlf;
+    if crlf > lf {
+        LineEnding::Crlf
+    } else {
+        LineEnding::Lf
+    }
+}
+
+/// Decode file bytes into LF-normalized text plus the detected format
+pub fn decode(bytes: &[u8]) -> Result<(String, TextFormat), String> {
+    let (text, encoding) = if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        (
+            String::from_utf8(rest.to_vec()).map_err(|e| format!("Invalid UTF-8 text: {e}"))?,
+            TextEncoding::Utf8Bom,
+        )
+    } else if let Some(rest) = bytes.strip_prefix(UTF16LE_BOM) {
+        (
+            decode_utf16(rest, u16::from_le_bytes)?,
+            TextEncoding::Utf16le,
+        )
+    } else if let Some(rest) = bytes.strip_prefix(UTF16BE_BOM) {
+        (
+            decode_utf16(rest, u16::from_be_bytes)?,
+            TextEncoding::Utf16be,
+        )
+    } else {
+        match String::from_utf8(bytes.to_vec()) {
+            Ok(text) => (text, TextEncoding::Utf8),
+            Err(_) => (
+                bytes.iter().map(|&byte| char::from(byte)).collect(),
+                TextEncoding::Latin1,
+            ),
+        }
+    };
+
+    let line_ending = detect_line_ending(&text);
+    let text = text.replace("\r\n", "\n");
+    Ok((
+        text,
+        TextFormat {
+            encoding,
+            line_ending,
+        },
+    ))
+}
+
+/// Encode editor text (LF or mixed line endings) back into `format`, or
+/// into `format.for_text(text)` when Latin-1 can't hold it
+pub fn encode(text: &str, format: &TextFormat) -> Vec<u8> {
+    let format = format.for_text(text);
+    let normalized = text.replace("\r\n", "\n");
+    let text = match format.line_ending {
+        LineEnding::Lf => normalized,
+        LineEnding::Crlf => normalized.replace('\n', "\r\n"),
+    };
+
+    match format.encoding {
+        TextEncoding::Utf8 => text.into_bytes(),
+        TextEncoding::Utf8Bom => [UTF8_BOM, text.as_bytes()].concat(),
+        TextEncoding::Utf16le => UTF16LE_BOM
+            .iter()
+            .copied()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect(),
+        TextEncoding::Utf16be => UTF16BE_BOM
+            .iter()
+            .copied()
+            .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect(),
+        TextEncoding::Latin1 => text.chars().map(|c| c as u8).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_format() {
+        let source = "// größe\ncube(1);\n";
+        for encoding in [
+            TextEncoding::Utf8,
+            TextEncoding::Utf8Bom,
+            TextEncoding::Utf16le,
+            TextEncoding::Utf16be,
+            TextEncoding::Latin1,
+        ] {
+            for line_ending in [LineEnding::Lf, LineEnding::Crlf] {
+                let format = TextFormat {
+                    encoding,
+                    line_ending,
+                };
+                let bytes = encode(source, &format);
+                assert_eq!(decode(&bytes).unwrap(), (source.to_string(), format));
+            }
+        }
+    }
+
+    #[test]
+    fn detects_dominant_line_ending() {
+        let (text, format) = decode(b"a\r\nb\r\nc\n").unwrap();
+        assert_eq!(text, "a\nb\nc\n");
+        assert_eq!(format.line_ending, LineEnding::Crlf);
+        assert!(decode(&[0xFF, 0xFE, 0x41]).is_err());
+    }
+
+    #[test]
+    fn reads_non_utf8_files_as_latin1() {
+        let (text, format) = decode(b"// gr\xf6\xdfe\ncube(1);\n").unwrap();
+        assert_eq!(text, "// größe\ncube(1);\n");
+        assert_eq!(format.encoding, TextEncoding::Latin1);
+        assert_eq!(encode(&text, &format), b"// gr\xf6\xdfe\ncube(1);\n");
+
+        let wider = "// größe ≥ 1\n";
+        assert_eq!(format.for_text(wider).encoding, TextEncoding::Utf8);
+        assert_eq!(encode(wider, &format), wider.as_bytes());
+    }
+}
@@ -0,0 +1,184 @@
+/**
+ * Import "things" from Thingiverse and Printables
+ *
+ * A thing page URL is turned into a download of the zip holding all of the
+ * thing's files: Thingiverse serves it at `<thing page>/zip`, Printables
+ * hands out a short-lived link through the GraphQL API its website uses.
+ * Besides project files and data assets, the meshes and drawings a remix
+ * may `import()` are kept; images, licenses and readmes are not.
+ */
+use crate::project_files::{is_asset_file, is_project_file};
+use serde_json::{json, Value};
+
+pub const PRINTABLES_GRAPHQL_URL: &str = "https://api.printables.com/graphql/";
+/// Largest thing zip downloaded
+pub const MAX_THING_DOWNLOAD_BYTES: usize = 128 * 1024 * 1024;
+/// Files `import()` can read besides data assets
+pub const IMPORTABLE_EXTENSIONS: &[&str] = &["stl", "off", "obj", "3mf", "amf", "dxf", "svg"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThingSite {
+    Thingiverse,
+    Printables,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thing {
+    pub site: ThingSite,
+    pub id: String,
+}
+
+impl Thing {
+    /// Folder name for the imported project, e.g. `thingiverse-12345`
+    pub fn folder_name(&self) -> String {
+        let site = match self.site {
+            ThingSite::Thingiverse => "thingiverse",
+            ThingSite::Printables => "printables",
+        };
+        format!("{site}-{}", self.id)
+    }
+}
+
+fn leading_digits(segment: &str) -> Option<String> {
+    let id: String = segment.chars().take_while(char::is_ascii_digit).collect();
+    (!id.is_empty()).then_some(id)
+}
+
+/// Recognise a Thingiverse (`thingiverse.com/thing:12345`) or Printables
+/// (`printables.com/[<lang>/]model/12345-name`) thing page
+pub fn parse_thing_url(url: &str) -> Result<Thing, String> {
+    let trimmed = url.trim();
+    let rest = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"))
+        .ok_or_else(|| format!("Only http(s) URLs can be imported: {trimmed}"))?;
+    let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+    let mut segments = rest.split('/').filter(|segment| !segment.is_empty());
+    let host = segments.next().unwrap_or_default().to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let segments: Vec<&str> = segments.collect();
+
+    let thing = match host {
+        "thingiverse.com" => segments
+            .first()
+            .and_then(|segment| segment.strip_prefix("thing:"))
+            .and_then(leading_digits)
+            .map(|id| Thing {
+                site: ThingSite::Thingiverse,
+                id,
+            }),
+        "printables.com" => segments
+            .iter()
+            .position(|segment| *segment == "model")
+            .and_then(|index| segments.get(index + 1))
+            .and_then(|segment| leading_digits(segment))
+            .map(|id| Thing {
+                site: ThingSite::Printables,
+                id,
+            }),
+        _ => None,
+    };
+    thing.ok_or_else(|| format!("Not a Thingiverse or Printables thing page: {trimmed}"))
+}
+
+/// Download URL of all files of a Thingiverse thing
+pub fn thingiverse_zip_url(id: &str) -> String {
+    format!("https://www.thingiverse.com/thing:{id}/zip")
+}
+
+/// GraphQL request asking Printables for a download link to all files of a
+/// model
+pub fn printables_download_request(id: &str) -> Value {
+    json!({
+        "operationName": "GetDownloadLink",
+        "variables": {
+            "id": id,
+            "printId": id,
+            "fileType": "pack",
+            "source": "model_detail",
+        },
+        "query": "mutation GetDownloadLink($id: ID!, $printId: ID!, \
+            $fileType: DownloadFileTypeEnum!, $source: DownloadSourceEnum!) { \
+            getDownloadLink(id: $id, printId: $printId, fileType: $fileType, \
+            source: $source) { ok errors { field messages } output { link } } }",
+    })
+}
+
+/// Download link from the response to `printables_download_request`
+pub fn printables_download_link(response: &Value) -> Result<String, String> {
+    let result = &response["data"]["getDownloadLink"];
+    if let Some(link) = result["output"]["link"].as_str() {
+        return Ok(link.to_string());
+    }
+    let messages: Vec<&str> = result["errors"]
+        .as_array()
+        .into_iter()
+        .chain(response["errors"].as_array())
+        .flatten()
+        .filter_map(|error| {
+            error["message"]
+                .as_str()
+                .or_else(|| error["messages"][0].as_str())
+        })
+        .collect();
+    Err(match messages.as_slice() {
+        [] => "Printables returned no download link".to_string(),
+        messages => format!("Printables refused the download: {}", messages.join("; ")),
+    })
+}
+
+/// Files of a thing that belong in the imported project
+pub fn is_thing_file(path: &str) -> bool {
+    let importable = path
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .is_some_and(|ext| IMPORTABLE_EXTENSIONS.contains(&ext.as_str()));
+    is_project_file(path) || is_asset_file(path) || importable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_thing_pages() {
+        assert_eq!(
+            parse_thing_url("https://www.thingiverse.com/thing:4711/files").unwrap(),
+            Thing {
+                site: ThingSite::Thingiverse,
+                id: "4711".into(),
+            }
+        );
+        let printables =
+            parse_thing_url("https://www.printables.com/de/model/123456-gear-box?lang=de").unwrap();
+        assert_eq!(printables.site, ThingSite::Printables);
+        assert_eq!(printables.folder_name(), "printables-123456");
+        assert!(parse_thing_url("https://www.thingiverse.com/about").is_err());
+        assert!(parse_thing_url("https://example.com/model/1").is_err());
+        assert!(parse_thing_url("ftp://www.thingiverse.com/thing:1").is_err());
+
+        assert!(is_thing_file("files/base.STL"));
+        assert!(is_thing_file("files/gear.scad"));
+        assert!(!is_thing_file("images/photo.jpg"));
+        assert!(!is_thing_file("LICENSE.txt"));
+    }
+
+    #[test]
+    fn reads_printables_download_link() {
+        let ok = json!({
+            "data": { "getDownloadLink": { "ok": true, "output": { "link": "https://files/x.zip" } } }
+        });
+        assert_eq!(
+            printables_download_link(&ok).unwrap(),
+            "https://files/x.zip"
+        );
+
+        let refused = json!({
+            "data": { "getDownloadLink": { "ok": false, "errors": [{ "field": "id", "messages": ["Model not found"] }], "output": null } }
+        });
+        assert_eq!(
+            printables_download_link(&refused).unwrap_err(),
+            "Printables refused the download: Model not found"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+/**
+ * AI tool permissions
+ *
+ * The user decides which tools the AI may call, whether it is the in-app
+ * assistant or an external agent connected over MCP. Each tool is allowed,
+ * denied, or needs the user's approval for every call. Read-only mode denies
+ * every tool that changes files or the workspace, whatever its own setting.
+ * Tools without a setting are allowed.
+ *
+ * A call that needs approval waits here until the user answers the prompt,
+ * or is declined once `APPROVAL_TIMEOUT` passes.
+ */
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+pub const APPROVAL_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Tools that write files or change what Studio renders
+pub const PRIVILEGED_TOOLS: &[&str] = &[
+    "apply_edit",
+    "apply_edits",
+    "edit_lines",
+    "create_file",
+    "write_file",
+    "export_file",
+    "set_render_target",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToolPermission {
+    Allow,
+    /// Ask the user before each call
+    Ask,
+    Deny,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolPermissionSettings {
+    /// Deny every tool in `PRIVILEGED_TOOLS`
+    pub read_only: bool,
+    /// Per-tool setting keyed by tool name
+    pub tools: BTreeMap<String, ToolPermission>,
+}
+
+impl ToolPermissionSettings {
+    pub fn permission(&self, tool_name: &str) -> ToolPermission {
+        if self.read_only && is_privileged(tool_name) {
+            return ToolPermission::Deny;
+        }
+        self.tools
+            .get(tool_name)
+            .copied()
+            .unwrap_or(ToolPermission::Allow)
+    }
+}
+
+pub fn is_privileged(tool_name: &str) -> bool {
+    PRIVILEGED_TOOLS.contains(&tool_name)
+}
+
+/// Tool calls waiting for the user's answer, keyed by request id
+#[derive(Default)]
+pub struct ToolApprovals {
+    pending: Mutex<HashMap<String, mpsc::Sender<bool>>>,
+}
+
+impl ToolApprovals {
+    /// Start waiting for an answer; the receiver gets it from `resolve`
+    pub fn request(&self) -> (String, mpsc::Receiver<bool>) {
+        let id = Uuid::new_v4().to_string();
+        let (tx, rx) = mpsc::channel();
+        self.pending.lock().unwrap().insert(id.clone(), tx);
+        (id, rx)
+    }
+
+    /// Deliver the user's answer. Fails when the request is unknown or was
+    /// already answered or abandoned.
+    pub fn resolve(&self, id: &str, approved: bool) -> Result<(), String> {
+        let tx = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(id)
+            .ok_or_else(|| format!("Tool approval request {id} is no longer pending"))?;
+        // The caller may have stopped waiting in the meantime
+        let _ = tx.send(approved);
+        Ok(())
+    }
+
+    /// Stop waiting for an answer
+    pub fn abandon(&self, id: &str) {
+        self.pending.lock().unwrap().remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_denies_privileged_tools() {
+        let mut settings = ToolPermissionSettings::default();
+        assert_eq!(settings.permission("apply_edit"), ToolPermission::Allow);
+
+        settings
+            .tools
+            .insert("apply_edit".into(), ToolPermission::Ask);
+        settings
+            .tools
+            .insert("get_diagnostics".into(), ToolPermission::Deny);
+        assert_eq!(settings.permission("apply_edit"), ToolPermission::Ask);
+        assert_eq!(settings.permission("get_diagnostics"), ToolPermission::Deny);
+
+        settings.read_only = true;
+        assert_eq!(settings.permission("apply_edit"), ToolPermission::Deny);
+        assert_eq!(settings.permission("read_file"), ToolPermission::Allow);
+    }
+
+    #[test]
+    fn approvals_are_answered_once() {
+        let approvals = ToolApprovals::default();
+        let (id, rx) = approvals.request();
+        approvals.resolve(&id, true).unwrap();
+        assert!(rx.recv().unwrap());
+        assert!(approvals.resolve(&id, false).is_err());
+
+        let (id, _rx) = approvals.request();
+        approvals.abandon(&id);
+        assert!(approvals.resolve(&id, true).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::menu::{is_checked_in_context, MenuState};
+use crate::settings::{update_settings, SettingsState, TraySettings};
+/**
+ * System tray icon
+ *
+ * Optional tray icon with quick render/export actions. Tray menu items reuse
+ * action registry IDs, so they are dispatched by the app-wide menu handler;
+ * only the tray-specific items are handled here.
+ */
+use tauri::menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, Window, Wry};
+
+const TRAY_ID: &str = "main-tray";
+const TRAY_SHOW_ID: &str = "tray_show";
+const TRAY_QUIT_ID: &str = "tray_quit";
+
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let paused = app
+        .state::<MenuState>()
+        .context
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|context| is_checked_in_context("toggle_auto_render", context))
+        .unwrap_or(false);
+    MenuBuilder::new(app)
+        .item(&MenuItemBuilder::with_id("render", "Re-render").build(app)?)
+        .item(&MenuItemBuilder::with_id("export_last", "Export Last Format").build(app)?)
+        .item(
+            &CheckMenuItemBuilder::with_id("toggle_auto_render", "Pause Watch Mode")
+                .checked(paused)
+                .build(app)?,
+        )
+        .separator()
+        .item(&MenuItemBuilder::with_id(TRAY_SHOW_ID, "Show OpenSCAD Studio").build(app)?)
+        .item(&MenuItemBuilder::with_id(TRAY_QUIT_ID, "Quit").build(app)?)
+        .build()
+}
+
+fn build_tray(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_tray_menu(app)?;
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("OpenSCAD Studio")
+        .menu(&menu);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Create or remove the tray icon to match the settings
+pub(crate) fn sync_tray(app: &AppHandle, settings: &TraySettings) -> Result<(), String> {
+    let exists = app.tray_by_id(TRAY_ID).is_some();
+    if settings.enabled && !exists {
+        build_tray(app).map_err(|e| format!("Failed to create tray icon: {e}"))?;
+    } else if !settings.enabled && exists {
+        app.remove_tray_by_id(TRAY_ID);
+    }
+    Ok(())
+}
+
+/// Rebuild the tray menu so its check item follows the reported context
+pub(crate) fn apply_tray_state(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_tray_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("[tray] Failed to rebuild tray menu: {e}"),
+    }
+}
+
+fn show_windows(app: &AppHandle) {
+    for window in app.webview_windows().values() {
+        let _ = window.show();
+    }
+    if let Some((_, window)) = app.webview_windows().into_iter().next() {
+        let _ = window.set_focus();
+    }
+}
+
+/// Handle tray-only menu items. Returns `true` when the event was consumed.
+pub(crate) fn handle_tray_menu_event(app: &AppHandle, id: &str) -> bool {
+    match id {
+        TRAY_SHOW_ID => {
+            show_windows(app);
+            true
+        }
+        TRAY_QUIT_ID => {
+            app.exit(0);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// In background mode, closing the last visible window hides it instead so
+/// renders driven by the (hidden) webview keep running.
+pub(crate) fn should_hide_instead_of_close(window: &Window) -> bool {
+    let app = window.app_handle();
+    let tray = app
+        .state::<SettingsState>()
+        .settings
+        .lock()
+        .unwrap()
+        .tray
+        .clone();
+    if !tray.enabled || !tray.background_mode {
+        return false;
+    }
+
+    let other_visible_windows = app
+        .webview_windows()
+        .values()
+        .filter(|other| other.label() != window.label())
+        .filter(|other| other.is_visible().unwrap_or(false))
+        .count();
+    other_visible_windows == 0
+}
+
+/// Get the tray settings
+#[tauri::command]
+pub fn get_tray_settings(state: tauri::State<'_, SettingsState>) -> Result<TraySettings, String> {
+    Ok(state.settings.lock().unwrap().tray.clone())
+}
+
+/// Update the tray settings, creating or removing the tray icon as needed
+#[tauri::command]
+pub fn set_tray_settings(app: AppHandle, settings: TraySettings) -> Result<TraySettings, String> {
+    let tray = update_settings(&app, |current| {
+        current.tray = settings;
+        Ok(current.tray.clone())
+    })?;
+    sync_tray(&app, &tray)?;
+    Ok(tray)
+}
@@ -5,11 +5,30 @@ pub struct Diagnostic {
     pub severity: DiagnosticSeverity,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub line: Option<i32>,
+    /// 1-based start column of the offending token, when it could be located
     #[serde(skip_serializing_if = "Option::is_none")]
     pub col: Option<i32>,
+    /// Exclusive end column of the offending token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_col: Option<i32>,
+    /// Project-relative file the message refers to, when OpenSCAD names one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Kind of problem, for messages the editor can point at precisely
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<DiagnosticCategory>,
     pub message: String,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticCategory {
+    UnknownVariable,
+    UnknownModule,
+    UnknownFunction,
+    AssertionFailed,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum DiagnosticSeverity {
@@ -40,6 +59,52 @@ pub struct EditorCheckpoint {
     pub diagnostics: Vec<Diagnostic>,
     pub description: String,
     pub change_type: ChangeType,
+    /// Rendered geometry statistics, computed lazily
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<GeometryStats>,
+    /// AI conversation whose request made the checkpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<String>,
+    /// User message of that request; the checkpoint holds the code from
+    /// before the request's edits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    /// Position of the message in the conversation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_index: Option<usize>,
+    /// Project file, other than the document, whose earlier content the
+    /// checkpoint holds; restoring it writes that file instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    /// The file at `file_path` didn't exist yet; restoring removes it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub file_absent: bool,
+}
+
+/// The chat message a checkpoint is made for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationLink {
+    pub conversation_id: String,
+    pub message_id: String,
+    pub message_index: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GeometryStats {
+    pub triangle_count: usize,
+    pub bounding_box_min: [f64; 3],
+    pub bounding_box_max: [f64; 3],
+    /// Bounding box extents (max - min)
+    pub size: [f64; 3],
+    pub volume: f64,
+    #[serde(default)]
+    pub surface_area: f64,
+    /// Edges not shared by exactly two triangles (0 for a closed mesh)
+    #[serde(default)]
+    pub non_manifold_edges: usize,
+    #[serde(default)]
+    pub manifold: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,246 @@
+/**
+ * Import OpenSCAD projects from a URL
+ *
+ * A raw `.scad` URL (GitHub raw or blob, gist, Thingiverse file download) is
+ * downloaded together with the files it references via `include`/`use`/
+ * `import()`/`surface()`. References are resolved relative to the including
+ * file's URL; library (`<BOSL2/...>`) paths that aren't next to the source
+ * and anything pointing outside the project are reported as missing rather
+ * than fetched.
+ */
+use crate::project_files::{is_asset_file, is_project_file, referenced_paths};
+use crate::safe_mode::is_escaping_path;
+use std::collections::{BTreeMap, VecDeque};
+
+pub const MAX_REMOTE_FILES: usize = 64;
+pub const MAX_REMOTE_FILE_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteSource {
+    /// URL prefix (ending in `/`) that project-relative paths are appended to
+    pub base_url: String,
+    /// Project-relative path of the entry file
+    pub main_file: String,
+}
+
+#[derive(Debug, Default)]
+pub struct RemoteProject {
+    /// Project-relative path of the entry file
+    pub main_file: String,
+    /// Project-relative path -> file contents, including the entry file
+    pub files: BTreeMap<String, Vec<u8>>,
+    /// References that couldn't be fetched, with the reason
+    pub missing: Vec<(String, String)>,
+}
+
+fn strip_query(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+fn scad_file_name(segment: &str) -> String {
+    if is_project_file(segment) && !segment.ends_with(".json") {
+        segment.to_string()
+    } else {
+        "main.scad".to_string()
+    }
+}
+
+/// Map a user-supplied URL to the raw download location of its entry file
+pub fn resolve_source(url: &str) -> Result<RemoteSource, String> {
+    let url = url.trim();
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| format!("Only http(s) URLs can be opened: {url}"))?;
+    let scheme = &url[..url.len() - rest.len()];
+    let rest = strip_query(rest).trim_end_matches('/');
+    let segments: Vec<&str> = rest.split('/').collect();
+
+    let (base_url, main_file) = match segments.as_slice() {
+        // github.com/<owner>/<repo>/blob/<ref>/<path...>
+        ["github.com", owner, repo, "blob", git_ref, path @ ..] if !path.is_empty() => {
+            let (file, dirs) = path.split_last().unwrap();
+            let mut base = format!("https://raw.githubusercontent.com/{owner}/{repo}/{git_ref}/");
+            for dir in dirs {
+                base.push_str(dir);
+                base.push('/');
+            }
+            (base, file.to_string())
+        }
+        // gist.github.com/<user>/<id>: `raw/<file>` serves any file in the gist
+        ["gist.github.com", user, id] => (
+            format!("https://gist.githubusercontent.com/{user}/{id}/raw/"),
+            String::new(),
+        ),
+        [host, path @ ..] if !path.is_empty() => {
+            let (file, dirs) = path.split_last().unwrap();
+            let mut base = format!("{scheme}{host}/");
+            for dir in dirs {
+                base.push_str(dir);
+                base.push('/');
+            }
+            (base, file.to_string())
+        }
+        _ => return Err(format!("URL does not point to a file: {url}")),
+    };
+    Ok(RemoteSource {
+        base_url,
+        main_file,
+    })
+}
+
+/// Project-relative path of `reference` as seen from `from_file`, or `None`
+/// when it would leave the project
+pub fn resolve_reference(from_file: &str, reference: &str) -> Option<String> {
+    if reference.is_empty() || is_escaping_path(reference) {
+        return None;
+    }
+    let dir = from_file.rsplit_once('/').map(|(dir, _)| dir);
+    let segments = dir
+        .into_iter()
+        .flat_map(|dir| dir.split('/'))
+        .chain(reference.split(['/', '\\']))
+        .filter(|segment| !segment.is_empty() && *segment != ".");
+    Some(segments.collect::<Vec<_>>().join("/"))
+}
+
+fn looks_like_html(bytes: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(256)]).to_ascii_lowercase();
+    let head = head.trim_start();
+    head.starts_with("<!doctype html") || head.starts_with("<html")
+}
+
+/// Download the entry file and, breadth-first, everything it references.
+/// `fetch` receives a full URL and returns the response body.
+pub fn fetch_project(
+    source: &RemoteSource,
+    mut fetch: impl FnMut(&str) -> Result<Vec<u8>, String>,
+) -> Result<RemoteProject, String> {
+    let entry_url = format!("{}{}", source.base_url, source.main_file);
+    let entry = fetch(entry_url.trim_end_matches('/'))?;
+    if looks_like_html(&entry) {
+        return Err(format!(
+            "{entry_url} returned a web page, not OpenSCAD source. Use the raw file URL."
+        ));
+    }
+    let entry_text =
+        String::from_utf8(entry).map_err(|_| format!("{entry_url} is not a UTF-8 text file"))?;
+
+    let main_file = scad_file_name(&source.main_file);
+    let mut project = RemoteProject {
+        main_file: main_file.clone(),
+        ..Default::default()
+    };
+    let mut queue = VecDeque::from([(main_file.clone(), entry_text.clone())]);
+    project.files.insert(main_file, entry_text.into_bytes());
+
+    while let Some((file, text)) = queue.pop_front() {
+        for reference in referenced_paths(&text) {
+            if !is_project_file(&reference) && !is_asset_file(&reference) {
+                continue;
+            }
+            let Some(path) = resolve_reference(&file, &reference) else {
+                project
+                    .missing
+                    .push((reference, "points outside the project".into()));
+                continue;
+            };
+            if project.files.contains_key(&path)
+                || project.missing.iter().any(|(missing, _)| *missing == path)
+            {
+                continue;
+            }
+            if project.files.len() >= MAX_REMOTE_FILES {
+                project
+                    .missing
+                    .push((path, format!("project exceeds {MAX_REMOTE_FILES} files")));
+                continue;
+            }
+
+            match fetch(&format!("{}{}", source.base_url, path)) {
+                Ok(bytes) if looks_like_html(&bytes) => project
+                    .missing
+                    .push((path, "server returned a web page".into())),
+                Ok(bytes) => {
+                    if is_project_file(&path) {
+                        if let Ok(text) = String::from_utf8(bytes.clone()) {
+                            queue.push_back((path.clone(), text));
+                        }
+                    }
+                    project.files.insert(path, bytes);
+                }
+                Err(error) => project.missing.push((path, error)),
+            }
+        }
+    }
+    Ok(project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_hosting_urls() {
+        assert_eq!(
+            resolve_source("https://github.com/acme/parts/blob/main/gears/gear.scad").unwrap(),
+            RemoteSource {
+                base_url: "https://raw.githubusercontent.com/acme/parts/main/gears/".into(),
+                main_file: "gear.scad".into(),
+            }
+        );
+        assert_eq!(
+            resolve_source("https://gist.github.com/alice/abc123").unwrap(),
+            RemoteSource {
+                base_url: "https://gist.githubusercontent.com/alice/abc123/raw/".into(),
+                main_file: String::new(),
+            }
+        );
+        assert_eq!(
+            resolve_source("http://example.com/models/box.scad?download=1")
+                .unwrap()
+                .base_url,
+            "http://example.com/models/"
+        );
+        assert!(resolve_source("file:///etc/passwd").is_err());
+        assert!(resolve_source("https://example.com").is_err());
+
+        assert_eq!(
+            resolve_reference("lib/a.scad", "./b.scad").as_deref(),
+            Some("lib/b.scad")
+        );
+        assert_eq!(resolve_reference("main.scad", "../x.scad"), None);
+    }
+
+    #[test]
+    fn fetches_dependencies_transitively() {
+        let source = resolve_source("https://example.com/p/main.scad").unwrap();
+        let mut requested = Vec::new();
+        let project = fetch_project(&source, |url| {
+            requested.push(url.to_string());
+            match url {
+                "https://example.com/p/main.scad" => {
+                    Ok(b"include <lib/a.scad>\nuse <../evil.scad>\ncube(1);".to_vec())
+                }
+                "https://example.com/p/lib/a.scad" => {
+                    Ok(b"surface(file = \"h.dat\");\ninclude <missing.h>".to_vec())
+                }
+                "https://example.com/p/lib/h.dat" => Ok(b"1 2\n3 4\n".to_vec()),
+                _ => Err("404 Not Found".into()),
+            }
+        })
+        .unwrap();
+
+        assert_eq!(project.main_file, "main.scad");
+        assert_eq!(
+            project.files.keys().collect::<Vec<_>>(),
+            vec!["lib/a.scad", "lib/h.dat", "main.scad"]
+        );
+        let missing: Vec<&str> = project.missing.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(missing, vec!["../evil.scad", "lib/missing.h"]);
+        assert!(!requested.iter().any(|url| url.contains("evil")));
+
+        let html = fetch_project(&source, |_| Ok(b"<!DOCTYPE html><html>".to_vec()));
+        assert!(html.is_err());
+    }
+}
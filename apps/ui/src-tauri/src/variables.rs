@@ -0,0 +1,255 @@
+/**
+ * Top-level variable introspection
+ *
+ * A lightweight scanner (not a full OpenSCAD parser) that finds assignments
+ * at file scope. These are exactly the variables OpenSCAD lets you override
+ * with `-D name=value`, which powers the quick variable override panel.
+ */
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableKind {
+    Number,
+    Boolean,
+    String,
+    Vector,
+    Expression,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopLevelVariable {
+    pub name: String,
+    /// Source text of the assigned expression
+    pub value: String,
+    pub kind: VariableKind,
+    /// 1-based line of the variable name
+    pub line: usize,
+    /// 1-based column of the variable name
+    pub col: usize,
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn classify(value: &str) -> VariableKind {
+    if value == "true" || value == "false" {
+        VariableKind::Boolean
+    } else if value.parse::<f64>().is_ok() {
+        VariableKind::Number
+    } else if value.starts_with('"') && value.ends_with('"') && value.len() >= 2 {
+        VariableKind::String
+    } else if value.starts_with('[') && value.ends_with(']') {
+        VariableKind::Vector
+    } else {
+        VariableKind::Expression
+    }
+}
+
+fn line_col(code: &str, offset: usize) -> (usize, usize) {
+    let before = &code[..offset];
+    let line = before.matches('\n').count() + 1;
+    let col = before.rfind('\n').map_or(offset, |nl| offset - nl - 1) + 1;
+    (line, col)
+}
+
+/// `include <...>` or `use <...>`, as opposed to a variable like `use_count`
+fn is_file_import(statement: &str) -> bool {
+    ["include", "use"].iter().any(|keyword| {
+        statement
+            .strip_prefix(keyword)
+            .is_some_and(|rest| rest.trim_start().starts_with('<'))
+    })
+}
+
+/// Try to read `statement` (starting at `offset` in `code`) as `name = value`
+fn parse_assignment(code: &str, offset: usize, statement: &str) -> Option<TopLevelVariable> {
+    let leading = statement.len() - statement.trim_start().len();
+    let statement = statement.trim();
+    let eq = statement.find('=')?;
+    let name = statement[..eq].trim();
+    let value = statement[eq + 1..].trim();
+
+    if !is_identifier(name) || value.is_empty() || value.starts_with('=') {
+        return None;
+    }
+
+    let (line, col) = line_col(code, offset + leading);
+    Some(TopLevelVariable {
+        name: name.to_string(),
+        value: value.to_string(),
+        kind: classify(value),
+        line,
+        col,
+    })
+}
+
+/// Find all assignments at file scope. When a variable is assigned more than
+/// once the last assignment wins, matching OpenSCAD.
+pub fn find_top_level_variables(code: &str) -> Vec<TopLevelVariable> {
+    let bytes = code.as_bytes();
+    let mut variables: Vec<TopLevelVariable> = Vec::new();
+    let mut depth = 0usize;
+    let mut statement_start = 0usize;
+    let mut i = 0usize;
+
+    let mut push = |variable: TopLevelVariable| {
+        variables.retain(|existing| existing.name != variable.name);
+        variables.push(variable);
+    };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let end = code[i..].find('\n').map_or(bytes.len(), |n| i + n);
+                if depth == 0 && code[statement_start..i].trim().is_empty() {
+                    statement_start = end;
+                }
+                i = end;
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = code[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |n| i + 2 + n + 2);
+                if depth == 0 && code[statement_start..i].trim().is_empty() {
+                    statement_start = end;
+                }
+                i = end;
+                continue;
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' => depth = depth.saturating_sub(1),
+            b'}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    // End of a module/function body or a top-level block
+                    statement_start = i + 1;
+                }
+            }
+            b'>' if depth == 0 => {
+                let statement = code[statement_start..i].trim_start();
+                if is_file_import(statement) {
+                    statement_start = i + 1;
+                }
+            }
+            b';' if depth == 0 => {
+                if let Some(variable) =
+                    parse_assignment(code, statement_start, &code[statement_start..i])
+                {
+                    push(variable);
+                }
+                statement_start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    variables
+}
+
+/// Turn temporary overrides into `-D name=value` arguments, rejecting names
+/// that are not top-level variables of `code`.
+pub fn override_args(
+    code: &str,
+    overrides: &HashMap<String, String>,
+) -> Result<Vec<String>, String> {
+    let known = find_top_level_variables(code);
+    let mut names: Vec<&String> = overrides.keys().collect();
+    names.sort();
+
+    let mut args = Vec::with_capacity(names.len() * 2);
+    for name in names {
+        let value = overrides[name].trim();
+        if !known.iter().any(|variable| &variable.name == name) {
+            return Err(format!("Unknown top-level variable: {}", name));
+        }
+        if value.is_empty() {
+            return Err(format!("Override for '{}' is empty", name));
+        }
+        args.push("-D".to_string());
+        args.push(format!("{}={}", name, value));
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_only_file_scope_assignments() {
+        let code = r#"include <BOSL2/std.scad>
+// width = 99;
+width = 20; // mm
+label = "a;b";
+size = [width, 10, 5];
+/* height = 1; */
+module box(h = 3) {
+    inner = 2;
+    cube(size);
+}
+function half(x) = x / 2;
+$fn = 32;
+show = true;
+width = 25;
+box();
+"#;
+
+        let variables = find_top_level_variables(code);
+        let names: Vec<_> = variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["label", "size", "$fn", "show", "width"]);
+
+        let label = &variables[0];
+        assert_eq!(label.value, "\"a;b\"");
+        assert_eq!(label.kind, VariableKind::String);
+        assert_eq!((label.line, label.col), (4, 1));
+
+        assert_eq!(variables[1].kind, VariableKind::Vector);
+        assert_eq!(variables[3].kind, VariableKind::Boolean);
+        assert_eq!(variables[4].value, "25");
+        assert_eq!(variables[4].line, 14);
+    }
+
+    #[test]
+    fn keeps_variables_starting_with_import_keywords() {
+        let code = "use <lib.scad>\nuse_count = 3 > 2;\nincluded = 1;\n";
+        let names: Vec<_> = find_top_level_variables(code)
+            .into_iter()
+            .map(|v| v.name)
+            .collect();
+        assert_eq!(names, vec!["use_count", "included"]);
+    }
+
+    #[test]
+    fn override_args_rejects_unknown_variables() {
+        let code = "width = 20;\ncube(width);";
+        let mut overrides = HashMap::new();
+        overrides.insert("width".to_string(), "30".to_string());
+        assert_eq!(
+            override_args(code, &overrides).unwrap(),
+            vec!["-D", "width=30"]
+        );
+
+        overrides.insert("depth".to_string(), "1".to_string());
+        assert!(override_args(code, &overrides).is_err());
+    }
+}
@@ -37,6 +37,8 @@ import {
   clearSavedLayout,
   MOBILE_LAYOUT_MEDIA_QUERY,
   openPanel,
+  togglePanel,
+  type WorkspacePreset,
 } from './stores/layoutStore';
 import { useRenderOrchestrator } from './hooks/useRenderOrchestrator';
 import { useAiAgent } from './hooks/useAiAgent';
@@ -51,11 +53,49 @@ import {
   syncDesktopMcpWindowContext,
 } from './services/desktopMcp';
 import { exportModelWithContext } from './services/exportService';
+import {
+  closeProject,
+  onFileChanged,
+  onFileRestored,
+  onRestoreWriteFailed,
+  onWorkspaceFileWritten,
+  openProject,
+  settleFileChange,
+} from './services/fileChanges';
+import {
+  generateLithophane,
+  lithophaneFileName,
+  pickLithophaneImage,
+} from './services/lithophane';
+import { documentIdForTab, startEditorSync } from './services/editorSync';
+import { updateMenuState } from './services/nativeMenu';
+import {
+  onPreviewDivergence,
+  onRenderRevalidated,
+  onUntrustedProject,
+} from './services/renderEvents';
+import { trustProject } from './services/safeMode';
 import { getPreviewSceneStyle } from './services/previewSceneConfig';
 import { isShareEnabled } from './services/shareService';
 import { openFileInWindow, openWorkspaceFolderInWindow } from './services/windowOpenService';
+import {
+  takeRestoredChatDraft,
+  trackSessionChatDraft,
+  trackSessionWorkspace,
+} from './services/session';
+import {
+  discardRecoveredFile,
+  listRecoveredFiles,
+  restoreRecoveredFile,
+  startAutosave,
+  type RecoveredFile,
+} from './services/recovery';
 import { useSettings, loadSettings, updateSetting } from './stores/settingsStore';
-import { getApiKey, getOpenAiCompatibleConfig } from './stores/apiKeyStore';
+import {
+  getOpenAiCompatibleConfig,
+  hasAzureOpenAiConfig,
+  isProviderConfigured,
+} from './stores/apiKeyStore';
 import {
   selectActiveRender,
   selectActiveTab,
@@ -75,7 +115,12 @@ import { DEFAULT_TAB_NAME } from './stores/workspaceFactories';
 import { formatOpenScadCode } from './utils/formatter';
 import { addRecentFile, removeRecentFile } from './utils/recentFiles';
 import { captureCurrentPreview, MAIN_PREVIEW_VIEWER_ID } from './utils/capturePreview';
-import { normalizeAppError, notifyError, notifySuccess } from './utils/notifications';
+import {
+  normalizeAppError,
+  notifyError,
+  notifySuccess,
+  notifyWarning,
+} from './utils/notifications';
 import { exportProjectZip } from './utils/projectZip';
 import {
   getInitialMacDownloadArch,
@@ -88,7 +133,7 @@ import { generateRandomProjectName } from './utils/projectNaming';
 import { resolveFolderImport } from './utils/folderImport';
 import { useShareEntry } from './hooks/useShareEntry';
 import { TbBrandGithub, TbSettings, TbDownload, TbShare3 } from 'react-icons/tb';
-import { Toaster } from 'sonner';
+import { Toaster, toast } from 'sonner';
 import type { AiDraft } from './types/aiChat';
 import type { WorkspaceTab as WorkspaceDocumentTab } from './stores/workspaceTypes';
 import {
@@ -485,6 +530,14 @@ function App() {
     getRenderArtifactState().setActiveRenderTarget(renderTargetPath ?? null, projectRoot);
   }, [projectRoot, renderTargetPath]);
 
+  useEffect(() => {
+    trackSessionWorkspace(
+      projectRoot,
+      tabs.map((tab) => ({ id: tab.id, filePath: tab.filePath })),
+      activeTabId || null
+    );
+  }, [projectRoot, tabs, activeTabId]);
+
   const initializeProject = useCallback(
     async (filePath: string | null, fileName: string, content: string) => {
       if (!filePath) {
@@ -591,6 +644,7 @@ function App() {
   const {
     isStreaming,
     streamingResponse,
+    streamingReasoning,
     proposedDiff,
     error: aiError,
     errorObject: aiErrorObject,
@@ -603,6 +657,7 @@ function App() {
     draftVisionWarningMessage,
     canSubmitDraft,
     isProcessingAttachments,
+    currentConversationId,
     currentToolCalls,
     currentProvider,
     currentModel,
@@ -618,6 +673,20 @@ function App() {
     rejectDiff,
     clearError: clearAiError,
     newConversation,
+    exportConversation,
+    importConversation,
+    interruptedQueries,
+    resumeInterruptedQuery,
+    discardInterruptedQuery,
+    verifyEditsWithPreview,
+    setVerifyEditsWithPreview,
+    reviewEdits,
+    setReviewEdits,
+    pendingEdits,
+    acceptPendingEdit,
+    rejectPendingEdit,
+    reasoningEffort,
+    setReasoningEffort,
     setCurrentModel,
     handleRestoreCheckpoint,
     updateCapturePreview,
@@ -627,6 +696,15 @@ function App() {
     loadModelAndProviders,
   } = useAiAgent();
 
+  useEffect(() => {
+    const restoredDraft = takeRestoredChatDraft();
+    if (restoredDraft) setDraftText(restoredDraft);
+  }, [setDraftText]);
+
+  useEffect(() => {
+    trackSessionChatDraft(draft.text);
+  }, [draft.text]);
+
   // Tab management functions
   const createNewTab = useCallback(
     (filePath?: string | null, content?: string, name?: string): string => {
@@ -1456,6 +1534,12 @@ function App() {
     [analytics]
   );
 
+  // Presets beyond the header's three are only reachable from the View menu
+  // and are not persisted, so the applied preset is tracked here.
+  const [activeLayout, setActiveLayout] = useState<WorkspacePreset>(
+    settings.ui.defaultLayoutPreset
+  );
+
   const handleHeaderLayoutSelect = useCallback(
     (preset: HeaderLayoutPreset) => {
       const changed = settings.ui.defaultLayoutPreset !== preset;
@@ -1474,10 +1558,23 @@ function App() {
       }
 
       applyWorkspacePreset(preset);
+      setActiveLayout(preset);
     },
     [analytics, settings.ui.defaultLayoutPreset]
   );
 
+  useEffect(
+    () =>
+      eventBus.on('menu:view:layout', (preset) => {
+        if (preset === 'default' || preset === 'ai-first' || preset === 'customizer-first') {
+          updateSetting('ui', { hasCompletedNux: true, defaultLayoutPreset: preset });
+        }
+        applyWorkspacePreset(preset);
+        setActiveLayout(preset);
+      }),
+    []
+  );
+
   const handleOpenCustomizerAiRefine = useCallback(() => {
     openPanel('ai-chat', 'ai-chat', 'AI');
     window.setTimeout(() => {
@@ -1488,7 +1585,9 @@ function App() {
   const hasCurrentModelApiKey =
     currentProvider === 'openai-compatible'
       ? Boolean(getOpenAiCompatibleConfig().baseUrl && currentModel.trim())
-      : Boolean(getApiKey(currentProvider));
+      : currentProvider === 'azure-openai'
+        ? hasAzureOpenAiConfig() && Boolean(currentModel.trim())
+        : isProviderConfigured(currentProvider);
   const canAttachViewerAnnotation = !isStreaming && !isProcessingAttachments;
 
   const attachViewerAnnotationFile = useCallback<WorkspaceState['attachViewerAnnotationFile']>(
@@ -1537,6 +1636,67 @@ function App() {
     setShowShareDialog(true);
   }, []);
 
+  // Reopen files recovered after a crash as unsaved buffers
+  const restoreRecoveredFiles = useCallback(
+    async (files: RecoveredFile[]) => {
+      for (const file of files) {
+        try {
+          const recovered = await restoreRecoveredFile(file.id);
+          const store = getProjectStore().getState();
+          const projectPath =
+            getRelativeProjectPath(store.projectRoot, recovered.path) ?? recovered.name;
+          if (!store.files[projectPath]) {
+            createNewTab(recovered.path, recovered.content, projectPath);
+            continue;
+          }
+          store.updateFileContent(projectPath, recovered.content);
+          const tab = tabsRef.current.find((t) => t.projectPath === projectPath);
+          if (tab) {
+            setActiveTab(tab.id);
+          } else {
+            createNewTab(recovered.path, undefined, projectPath);
+          }
+        } catch (err) {
+          notifyError({
+            operation: 'restore-recovered-file',
+            error: err,
+            fallbackMessage: `Failed to restore ${file.name}`,
+            logLabel: 'Failed to restore recovered file',
+          });
+        }
+      }
+      hideWelcomeScreen();
+    },
+    [createNewTab, hideWelcomeScreen, setActiveTab]
+  );
+
+  useEffect(() => {
+    if (!capabilities.hasFileSystem) return;
+    const stopAutosave = startAutosave();
+    void listRecoveredFiles()
+      .then((files) => {
+        if (files.length === 0) return;
+        toast(`Recovered ${files.length} unsaved file${files.length === 1 ? '' : 's'}`, {
+          id: 'recovered-files',
+          description: 'OpenSCAD Studio did not shut down cleanly last time.',
+          duration: Infinity,
+          action: { label: 'Restore', onClick: () => void restoreRecoveredFiles(files) },
+          cancel: {
+            label: 'Discard',
+            onClick: () => {
+              for (const file of files) void discardRecoveredFile(file.id);
+            },
+          },
+        });
+      })
+      .catch((error) => {
+        console.warn('[App] Failed to list recovered files:', error);
+      });
+    return stopAutosave;
+    // Only checked once per window
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
   const handleOpenRecent = useCallback(
     async (path: string, type?: 'file' | 'folder') => {
       try {
@@ -1745,6 +1905,10 @@ function App() {
             obj: { label: 'OBJ (3D Model)', ext: 'obj' },
             amf: { label: 'AMF (3D Model)', ext: 'amf' },
             '3mf': { label: '3MF (3D Model)', ext: '3mf' },
+            off: { label: 'OFF (3D Model)', ext: 'off' },
+            wrl: { label: 'VRML (3D Model)', ext: 'wrl' },
+            pov: { label: 'POV-Ray Scene', ext: 'pov' },
+            csg: { label: 'CSG (OpenSCAD Tree)', ext: 'csg' },
             png: { label: 'PNG (Image)', ext: 'png' },
             svg: { label: 'SVG (2D Vector)', ext: 'svg' },
             dxf: { label: 'DXF (2D CAD)', ext: 'dxf' },
@@ -1853,6 +2017,71 @@ function App() {
     showWelcomeScreen,
   ]);
 
+  useEffect(() => {
+    const unlistenFns = [
+      eventBus.on('menu:render', () => manualRender()),
+      eventBus.on('menu:render:toggle_auto_render', () => {
+        const { editor } = loadSettings();
+        updateSetting('editor', { autoRenderOnIdle: !editor.autoRenderOnIdle });
+      }),
+      eventBus.on('menu:ai:toggle_panel', () => togglePanel('ai-chat', 'ai-chat', 'AI')),
+      eventBus.on('menu:ai:new_conversation', () => {
+        openPanel('ai-chat', 'ai-chat', 'AI');
+        newConversation();
+      }),
+    ];
+    return () => unlistenFns.forEach((fn) => fn());
+  }, [manualRender, newConversation]);
+
+  useEffect(
+    () =>
+      eventBus.on('menu:design:lithophane', async () => {
+        try {
+          const imagePath = await pickLithophaneImage();
+          if (!imagePath) return;
+          const lithophane = await generateLithophane(imagePath);
+
+          const store = getProjectStore().getState();
+          const fileName = lithophaneFileName(imagePath);
+          let projectPath = fileName;
+          for (let n = 2; store.files[projectPath]; n++) {
+            projectPath = fileName.replace(/\.scad$/, `-${n}.scad`);
+          }
+          createNewTab(null, lithophane.code, projectPath);
+          hideWelcomeScreen();
+          requestRender('file_open', { immediate: true });
+        } catch (err) {
+          notifyError({
+            operation: 'generate-lithophane',
+            error: err,
+            fallbackMessage: 'Failed to generate lithophane',
+            toastId: 'lithophane-error',
+            logLabel: 'Lithophane failed',
+          });
+        }
+      }),
+    [createNewTab, hideWelcomeScreen]
+  );
+
+  useEffect(
+    () =>
+      eventBus.on('editor:large-file', ({ documentId, bytes }) => {
+        notifyWarning('This file is very large', {
+          toastId: `large-file-${documentId}`,
+          description: `At ${(bytes / (1024 * 1024)).toFixed(1)} MB, editing and rendering may be slow.`,
+        });
+      }),
+    []
+  );
+
+  useEffect(
+    () =>
+      eventBus.on('menu:file:open_recent', ({ path, kind }) => {
+        void handleOpenRecent(path, kind);
+      }),
+    [handleOpenRecent]
+  );
+
   useEffect(() => {
     const platform = getPlatform();
     const unlisten = platform.onCloseRequested(async () => {
@@ -1901,6 +2130,30 @@ function App() {
     showWelcome,
   ]);
 
+  const hasRenderedGeometry = Boolean(activePreviewSrc);
+  useEffect(() => {
+    if (!capabilities.hasNativeMenu) return;
+    void updateMenuState({
+      hasDocument: !showWelcome,
+      isDirty: activeFileDirty,
+      hasDirtyFiles: anyFileDirty,
+      geometry: hasRenderedGeometry ? (activePreviewKind === 'svg' ? '2d' : '3d') : null,
+      layout: activeLayout,
+      autoRender: settings.editor.autoRenderOnIdle,
+    }).catch((error) => {
+      console.error('[App] Failed to update menu state:', error);
+    });
+  }, [
+    activeFileDirty,
+    activeLayout,
+    activePreviewKind,
+    anyFileDirty,
+    capabilities.hasNativeMenu,
+    hasRenderedGeometry,
+    settings.editor.autoRenderOnIdle,
+    showWelcome,
+  ]);
+
   useEffect(() => {
     const platform = getPlatform();
     if ('setDirtyState' in platform) {
@@ -1947,6 +2200,241 @@ function App() {
     };
   }, [projectRoot]);
 
+  // Open the folder in the backend as well, whose watcher reports the
+  // external changes handled below; it is closed with the folder
+  useEffect(() => {
+    if (!projectRoot) return;
+    if (!getPlatform().capabilities.hasFileSystem) return;
+
+    const entryFile = getProjectStore().getState().renderTargetPath ?? undefined;
+    openProject(projectRoot, entryFile).catch((error) => {
+      console.warn('[App] Failed to open the project folder in the backend:', error);
+    });
+
+    return () => {
+      closeProject(projectRoot).catch((error) => {
+        console.warn('[App] Failed to close the project folder in the backend:', error);
+      });
+    };
+  }, [projectRoot]);
+
+  // Ask before the backend's watcher replaces a file with unsaved edits
+  useEffect(() => {
+    if (!projectRoot) return;
+    const platform = getPlatform();
+    if (!platform.capabilities.hasFileSystem) return;
+
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+
+    onFileChanged(async (change) => {
+      if (change.root !== projectRoot) return;
+      try {
+        // The backend only tracks unsaved edits for the project it has open
+        const dirty = getProjectStore().getState().files[change.path]?.isDirty ?? false;
+        const reloaded = await settleFileChange(
+          { ...change, conflict: change.conflict || dirty },
+          (message) =>
+            platform.confirm(message, {
+              title: 'File Changed on Disk',
+              kind: 'warning',
+              okLabel: 'Reload',
+              cancelLabel: 'Keep My Edits',
+            })
+        );
+        if (!reloaded) return;
+        const content = await platform.readTextFile(`${change.root}/${change.path}`);
+        const store = getProjectStore().getState();
+        if (content === null || !(change.path in store.files)) return;
+        store.updateFileContent(change.path, content);
+        store.markFileSaved(change.path, content);
+      } catch (err) {
+        notifyError({ operation: 'reload-changed-file', error: err });
+      }
+    }).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [projectRoot]);
+
+  // Show files the backend wrote itself: by an MCP client's create_file or
+  // write_file, or by restoring a file checkpoint. Unsaved edits are kept.
+  useEffect(() => {
+    if (!projectRoot) return;
+    const platform = getPlatform();
+    if (!platform.capabilities.hasFileSystem) return;
+
+    let disposed = false;
+    const unlisteners: Array<() => void> = [];
+    const keep = (fn: () => void) => {
+      if (disposed) fn();
+      else unlisteners.push(fn);
+    };
+    const showFile = (relativePath: string, content: string) => {
+      const store = getProjectStore().getState();
+      const file = store.files[relativePath];
+      if (!file) {
+        store.addFile(relativePath, content);
+      } else if (file.isDirty || file.content === content) {
+        return;
+      } else {
+        store.updateFileContent(relativePath, content);
+      }
+      store.markFileSaved(relativePath, content);
+    };
+
+    onWorkspaceFileWritten(async ({ filePath }) => {
+      try {
+        const content = await platform.readTextFile(`${projectRoot}/${filePath}`);
+        if (content !== null) showFile(filePath, content);
+      } catch (err) {
+        notifyError({ operation: 'reload-written-file', error: err });
+      }
+    }).then(keep);
+
+    onFileRestored(({ path, code, removed }) => {
+      const prefix = `${projectRoot}/`;
+      if (!path.startsWith(prefix)) return;
+      const relativePath = path.slice(prefix.length);
+      if (!removed) {
+        showFile(relativePath, code);
+        return;
+      }
+      const store = getProjectStore().getState();
+      if (!store.files[relativePath]?.isDirty) store.removeFile(relativePath);
+    }).then(keep);
+
+    return () => {
+      disposed = true;
+      unlisteners.forEach((fn) => fn());
+    };
+  }, [projectRoot]);
+
+  // The editor was restored but its file on disk couldn't follow
+  useEffect(() => {
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+
+    onRestoreWriteFailed(({ path, error }) => {
+      notifyError({
+        operation: 'write-restored-file',
+        error,
+        capture: false,
+        displayMessage: `Restored in the editor, but ${path} could not be updated`,
+        description: error,
+        toastId: 'restore-write-failed',
+      });
+    }).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
+  // Mirror the tabs into backend documents so backend commands see each tab's code
+  useEffect(() => {
+    let disposed = false;
+    let stop: (() => void) | null = null;
+
+    startEditorSync().then((fn) => {
+      if (disposed) fn();
+      else stop = fn;
+    });
+
+    return () => {
+      disposed = true;
+      stop?.();
+    };
+  }, []);
+
+  // Warn when the exported ($preview=false) model won't match the preview
+  useEffect(() => {
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+
+    onPreviewDivergence(({ warnings }) => {
+      notifyWarning('Exports may differ from the preview', {
+        toastId: 'preview-divergence',
+        description: warnings.join('\n'),
+      });
+    }).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
+  // Offer to trust a project folder safe mode is isolating, once per folder
+  useEffect(() => {
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+    const offered = new Set<string>();
+
+    onUntrustedProject((folder) => {
+      if (offered.has(folder)) return;
+      offered.add(folder);
+      toast('Rendering in safe mode', {
+        id: 'untrusted-project',
+        description: `${folder} isn't trusted, so renders can't read files outside it or use library paths.`,
+        duration: Infinity,
+        action: {
+          label: 'Trust Folder',
+          onClick: () => {
+            trustProject(folder)
+              .then(() => {
+                notifySuccess('Folder trusted', { toastId: 'untrusted-project' });
+                requestRender('manual', { immediate: true });
+              })
+              .catch((error) => {
+                notifyError({ operation: 'trust-project', error, toastId: 'untrusted-project' });
+              });
+          },
+        },
+      });
+    }).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
+  // Replace a stale preview once its background render finishes; the render
+  // is served from the backend cache
+  useEffect(() => {
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+
+    onRenderRevalidated((documentId) => {
+      if (documentId !== documentIdForTab(getWorkspaceState().activeTabId)) return;
+      requestRender('revalidated', { immediate: true });
+    }).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
   useEffect(() => {
     const unlisten = eventBus.on('render-requested', ({ source }) => {
       requestRender(source === 'ai' ? 'ai_edit' : 'manual', { immediate: true });
@@ -2130,6 +2618,7 @@ function App() {
       onPreviewVisualReady: isShareEntry ? markSharePreviewReady : undefined,
       isStreaming,
       streamingResponse,
+      streamingReasoning,
       proposedDiff,
       aiError,
       isApplyingDiff,
@@ -2141,6 +2630,7 @@ function App() {
       draftVisionWarningMessage,
       canSubmitDraft,
       isProcessingAttachments,
+      currentConversationId,
       currentToolCalls,
       currentProvider,
       currentModel,
@@ -2158,6 +2648,20 @@ function App() {
       rejectDiff,
       clearAiError,
       newConversation,
+      exportConversation,
+      importConversation,
+      interruptedQueries,
+      resumeInterruptedQuery,
+      discardInterruptedQuery,
+      verifyEditsWithPreview,
+      setVerifyEditsWithPreview,
+      reviewEdits,
+      setReviewEdits,
+      pendingEdits,
+      acceptPendingEdit,
+      rejectPendingEdit,
+      reasoningEffort,
+      setReasoningEffort,
       setCurrentModel,
       handleRestoreCheckpoint,
       aiPromptPanelRef,
@@ -2194,6 +2698,7 @@ function App() {
       markSharePreviewReady,
       isStreaming,
       streamingResponse,
+      streamingReasoning,
       proposedDiff,
       aiError,
       isApplyingDiff,
@@ -2205,6 +2710,7 @@ function App() {
       draftVisionWarningMessage,
       canSubmitDraft,
       isProcessingAttachments,
+      currentConversationId,
       currentToolCalls,
       currentProvider,
       currentModel,
@@ -2222,6 +2728,20 @@ function App() {
       rejectDiff,
       clearAiError,
       newConversation,
+      exportConversation,
+      importConversation,
+      interruptedQueries,
+      resumeInterruptedQuery,
+      discardInterruptedQuery,
+      verifyEditsWithPreview,
+      setVerifyEditsWithPreview,
+      reviewEdits,
+      setReviewEdits,
+      pendingEdits,
+      acceptPendingEdit,
+      rejectPendingEdit,
+      reasoningEffort,
+      setReasoningEffort,
       setCurrentModel,
       handleRestoreCheckpoint,
       handleOpenCustomizerAiRefine,
@@ -25,7 +25,8 @@ export type RenderTrigger =
   | 'file_open'
   | 'history_restore'
   | 'code_update'
-  | 'ai_edit';
+  | 'ai_edit'
+  | 'revalidated';
 
 export type SettingsSection = 'appearance' | 'viewer' | 'editor' | 'privacy' | 'ai' | 'libraries';
 export type ModelSelectionSurface = 'welcome' | 'ai_panel' | 'viewer_annotation' | 'unknown';
@@ -1,21 +1,39 @@
-import { useRef, useEffect, useState, forwardRef, useImperativeHandle } from 'react';
+import { useRef, useEffect, useState, forwardRef, useImperativeHandle, useMemo } from 'react';
+import * as Diff from 'diff';
 import { ChatImage, ChatImageGrid } from './ChatImage';
-import { Button } from './ui';
+import { TbEyeCheck, TbFileDiff } from 'react-icons/tb';
+import {
+  Button,
+  IconButton,
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from './ui';
 import { MarkdownMessage } from './MarkdownMessage';
 import { ModelSelector } from './ModelSelector';
 import { AiComposer, type AiComposerRef } from './AiComposer';
 import { AiAccessEmptyState } from './AiAccessEmptyState';
 import { useAnalytics, type ModelSelectionSurface } from '../analytics/runtime';
 import { useHistory } from '../hooks/useHistory';
-import { getPlatform } from '../platform';
+import * as documentHistory from '../services/documentHistory';
+import type { EditorCheckpoint } from '../platform/historyService';
+import { ConversationTimeline, buildTimeline } from './ConversationTimeline';
+import { eventBus, getPlatform } from '../platform';
 import { useHasApiKey } from '../stores/apiKeyStore';
 import type { AiProvider } from '../stores/apiKeyStore';
+import type { StreamRetry } from '../services/aiStream';
+import { pendingEditFile, type PendingEdit } from '../services/pendingEdits';
+import type { InterruptedQuery } from '../services/aiTranscripts';
 import { notifyError, notifySuccess } from '../utils/notifications';
+import { supportsReasoning } from '../utils/aiModels';
 import type {
   AiDraft,
   AssistantMessage,
   AttachmentStore,
   Message,
+  ReasoningEffort,
   ToolCall,
   ToolCallMessage,
   ToolCallState,
@@ -23,6 +41,22 @@ import type {
 import { getUserMessageText } from '../types/aiChat';
 
 const AUTO_SCROLL_BOTTOM_THRESHOLD_PX = 48;
+/** Unchanged lines shown on each side of a pending edit's changes */
+const PENDING_EDIT_CONTEXT_LINES = 2;
+/** Tools whose results may carry a preview screenshot */
+const SCREENSHOT_TOOL_NAMES = new Set([
+  'get_preview_screenshot',
+  'apply_edit',
+  'apply_edits',
+  'edit_lines',
+]);
+
+const REASONING_EFFORT_LABELS: Record<ReasoningEffort, string> = {
+  off: 'Thinking off',
+  low: 'Think: low',
+  medium: 'Think: medium',
+  high: 'Think: high',
+};
 
 function getImageDataUrlFromResult(result: unknown): string | null {
   if (!result) return null;
@@ -237,6 +271,126 @@ function ToolCallDetailSection({ label, value, emptyLabel }: ToolCallDetailSecti
   );
 }
 
+/** The edit an `apply_edit` call proposes, updated as its arguments stream in */
+function LiveEditPreview({ oldString, newString }: { oldString: string; newString: string }) {
+  const parts = useMemo(() => Diff.diffLines(oldString, newString), [oldString, newString]);
+
+  return (
+    <div
+      className="mt-2 max-h-48 overflow-y-auto rounded-md border px-2 py-1"
+      style={{ backgroundColor: 'var(--bg-secondary)', borderColor: 'var(--border-secondary)' }}
+      data-testid="ai-live-edit-preview"
+    >
+      <pre className="m-0 whitespace-pre-wrap break-words font-mono text-[11px] leading-relaxed">
+        {parts.map((part, index) => (
+          <span
+            key={index}
+            style={{
+              color: part.added
+                ? 'var(--color-success)'
+                : part.removed
+                  ? 'var(--color-error)'
+                  : 'var(--text-secondary)',
+              textDecoration: part.removed ? 'line-through' : undefined,
+            }}
+          >
+            {part.value}
+          </span>
+        ))}
+      </pre>
+    </div>
+  );
+}
+
+/** Unchanged text of a diff cut down to the lines next to the changes around it */
+function trimUnchangedText(value: string, changeBefore: boolean, changeAfter: boolean) {
+  const lines = value.match(/[^\n]*\n|[^\n]+$/g) ?? [];
+  const head = changeBefore ? PENDING_EDIT_CONTEXT_LINES : 0;
+  const tail = changeAfter ? PENDING_EDIT_CONTEXT_LINES : 0;
+  if (lines.length <= head + tail + 1) return value;
+  return [...lines.slice(0, head), '…\n', ...lines.slice(lines.length - tail)].join('');
+}
+
+/** Runs of unchanged, removed and added lines in a backend line diff */
+function diffParts(diff: string) {
+  const parts: { value: string; added: boolean; removed: boolean }[] = [];
+  for (const line of diff.match(/[^\n]*\n|[^\n]+$/g) ?? []) {
+    const added = line.startsWith('+');
+    const removed = line.startsWith('-');
+    const last = parts[parts.length - 1];
+    if (last && last.added === added && last.removed === removed) {
+      last.value += line.slice(1);
+    } else {
+      parts.push({ value: line.slice(1), added, removed });
+    }
+  }
+  return parts;
+}
+
+/** An edit held for review, with its changes and buttons to accept or reject it */
+function PendingEditCard({
+  edit,
+  onAccept,
+  onReject,
+}: {
+  edit: PendingEdit;
+  onAccept: () => void;
+  onReject: () => void;
+}) {
+  const parts = useMemo(() => diffParts(edit.diff), [edit.diff]);
+  const file = pendingEditFile(edit);
+
+  return (
+    <div
+      data-testid="ai-pending-edit"
+      className="px-4 py-2 text-sm"
+      style={{
+        backgroundColor: 'var(--bg-primary)',
+        borderBottom: '1px solid var(--border-primary)',
+        color: 'var(--text-secondary)',
+      }}
+    >
+      <div className="flex items-center gap-2">
+        <div className="flex-1 min-w-0 truncate" title={file}>
+          Proposed edit to {file}{' '}
+          <span style={{ color: 'var(--color-success)' }}>+{edit.addedLines}</span>{' '}
+          <span style={{ color: 'var(--color-error)' }}>-{edit.removedLines}</span>
+        </div>
+        <Button size="sm" variant="primary" onClick={onAccept}>
+          Accept
+        </Button>
+        <Button size="sm" variant="secondary" onClick={onReject}>
+          Reject
+        </Button>
+      </div>
+      <div
+        className="mt-2 max-h-48 overflow-y-auto rounded-md border px-2 py-1"
+        style={{ backgroundColor: 'var(--bg-secondary)', borderColor: 'var(--border-secondary)' }}
+      >
+        <pre className="m-0 whitespace-pre-wrap break-words font-mono text-[11px] leading-relaxed">
+          {parts.map((part, index) => (
+            <span
+              key={index}
+              style={{
+                color: part.added
+                  ? 'var(--color-success)'
+                  : part.removed
+                    ? 'var(--color-error)'
+                    : 'var(--text-tertiary)',
+                textDecoration: part.removed ? 'line-through' : undefined,
+              }}
+            >
+              {part.added || part.removed
+                ? part.value
+                : trimUnchangedText(part.value, index > 0, index < parts.length - 1)}
+            </span>
+          ))}
+        </pre>
+      </div>
+    </div>
+  );
+}
+
 interface ToolCallCardProps {
   toolName: string;
   state: ToolCallState;
@@ -248,8 +402,9 @@ interface ToolCallCardProps {
 function ToolCallCard({ toolName, state, args, result, errorText }: ToolCallCardProps) {
   const [expanded, setExpanded] = useState(false);
   const toolStateMeta = getToolStateMeta(state);
-  const imageDataUrl =
-    toolName === 'get_preview_screenshot' ? getImageDataUrlFromResult(result) : null;
+  const imageDataUrl = SCREENSHOT_TOOL_NAMES.has(toolName)
+    ? getImageDataUrlFromResult(result)
+    : null;
 
   return (
     <div
@@ -299,6 +454,12 @@ function ToolCallCard({ toolName, state, args, result, errorText }: ToolCallCard
           </svg>
         </span>
       </Button>
+      {state === 'pending' && toolName === 'apply_edit' && typeof args?.new_string === 'string' && (
+        <LiveEditPreview
+          oldString={typeof args.old_string === 'string' ? args.old_string : ''}
+          newString={args.new_string}
+        />
+      )}
       {imageDataUrl && !expanded && (
         <div className="mt-2">
           <ChatImage src={imageDataUrl} alt="Preview screenshot" filename="preview.png" />
@@ -331,6 +492,12 @@ function ToolCallCard({ toolName, state, args, result, errorText }: ToolCallCard
   );
 }
 
+function describeInterruptedQuery(query: InterruptedQuery): string {
+  const count = query.completedToolCalls;
+  const progress = count > 0 ? ` (${count} tool call${count === 1 ? '' : 's'} done)` : '';
+  return `Interrupted: ${query.title}${progress}`;
+}
+
 export interface AiPromptPanelProps {
   onSubmit: () => void;
   onTextChange: (text: string) => void;
@@ -345,9 +512,16 @@ export interface AiPromptPanelProps {
   isProcessingAttachments: boolean;
   isStreaming: boolean;
   streamingResponse: string | null;
+  streamingReasoning?: string | null;
+  /** Conversation whose `ai-stream` events (e.g. retries) the panel shows */
+  conversationId?: string;
   onCancel: () => void;
   messages?: Message[];
   onNewConversation?: () => void;
+  /** Export the current conversation to a file (desktop) */
+  onExportConversation?: () => void;
+  /** Open an exported conversation (desktop) */
+  onImportConversation?: () => void;
   currentToolCalls?: ToolCall[];
   currentProvider?: AiProvider;
   currentModel?: string;
@@ -359,6 +533,19 @@ export interface AiPromptPanelProps {
   ) => void;
   onRestoreCheckpoint?: (checkpointId: string, truncatedMessages: Message[]) => void;
   onOpenSettings?: () => void;
+  interruptedQueries?: InterruptedQuery[];
+  onResumeInterruptedQuery?: (conversationId: string) => void;
+  onDiscardInterruptedQuery?: (conversationId: string) => void;
+  verifyEditsWithPreview?: boolean;
+  onVerifyEditsWithPreviewChange?: (enabled: boolean) => void;
+  reviewEdits?: boolean;
+  onReviewEditsChange?: (enabled: boolean) => void;
+  /** Edits held for review; accepting one returns why it no longer applies, if it doesn't */
+  pendingEdits?: PendingEdit[];
+  onAcceptPendingEdit?: (id: string) => Promise<string | null>;
+  onRejectPendingEdit?: (id: string) => void;
+  reasoningEffort?: ReasoningEffort;
+  onReasoningEffortChange?: (effort: ReasoningEffort) => void;
 }
 
 export interface AiPromptPanelRef {
@@ -381,9 +568,13 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
       isProcessingAttachments,
       isStreaming,
       streamingResponse,
+      streamingReasoning = null,
+      conversationId,
       onCancel,
       messages = [],
       onNewConversation,
+      onExportConversation,
+      onImportConversation,
       currentToolCalls = [],
       currentProvider,
       currentModel = 'claude-sonnet-4-5',
@@ -391,6 +582,18 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
       onModelChange,
       onRestoreCheckpoint,
       onOpenSettings,
+      interruptedQueries = [],
+      onResumeInterruptedQuery,
+      onDiscardInterruptedQuery,
+      verifyEditsWithPreview = false,
+      onVerifyEditsWithPreviewChange,
+      reviewEdits = false,
+      onReviewEditsChange,
+      pendingEdits = [],
+      onAcceptPendingEdit,
+      onRejectPendingEdit,
+      reasoningEffort = 'off',
+      onReasoningEffortChange,
     },
     ref
   ) => {
@@ -404,6 +607,10 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
       'stacked'
     );
     const [showJumpToLatest, setShowJumpToLatest] = useState(false);
+    const [streamRetry, setStreamRetry] = useState<StreamRetry | null>(null);
+    const [conversationCheckpoints, setConversationCheckpoints] = useState<EditorCheckpoint[]>([]);
+    const [showTimeline, setShowTimeline] = useState(false);
+    const timeline = useMemo(() => buildTimeline(conversationCheckpoints), [conversationCheckpoints]);
     const { restoreToCheckpoint } = useHistory();
 
     useImperativeHandle(ref, () => ({
@@ -451,6 +658,35 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
       setShowJumpToLatest(false);
     }, [messages.length, streamingResponse, currentToolCalls.length]);
 
+    useEffect(() => {
+      setStreamRetry(null);
+      if (!conversationId) return;
+      return eventBus.on(`ai-stream:${conversationId}`, (event) => {
+        // A retry notice lasts until the next attempt reports anything
+        setStreamRetry(event.status === 'retrying' ? (event.retry ?? null) : null);
+      });
+    }, [conversationId]);
+
+    // Re-read the conversation's checkpoints whenever its messages change
+    useEffect(() => {
+      if (!conversationId) {
+        setConversationCheckpoints([]);
+        return;
+      }
+      let stale = false;
+      documentHistory.getForConversation(conversationId).then(
+        (checkpoints) => {
+          if (!stale) setConversationCheckpoints(checkpoints);
+        },
+        (error) => {
+          console.error('[AiPromptPanel] Failed to read conversation checkpoints:', error);
+        }
+      );
+      return () => {
+        stale = true;
+      };
+    }, [conversationId, messages]);
+
     useEffect(() => {
       if (import.meta.env?.DEV) {
         console.log('[AiPromptPanel] Messages updated. Count:', messages.length);
@@ -494,7 +730,8 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
       return () => observer.disconnect();
     }, []);
 
-    const handleRestoreCheckpoint = async (checkpointId: string, messageId: string) => {
+    /** Restore to before a message, by its linked checkpoints or the one stored on it */
+    const handleRestoreCheckpoint = async (messageId: string, checkpointId?: string) => {
       try {
         const messageIndex = messages.findIndex((message) => message.id === messageId);
         const hasLaterMessages = messageIndex !== -1 && messageIndex < messages.length - 1;
@@ -513,14 +750,18 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
           if (!shouldProceed) return;
         }
 
-        const checkpoint = await restoreToCheckpoint(checkpointId);
+        const checkpoint = timeline.some((entry) => entry.messageId === messageId)
+          ? await documentHistory.restoreToMessage(messageId)
+          : checkpointId
+            ? await restoreToCheckpoint(checkpointId)
+            : null;
         if (!checkpoint) {
           throw new Error('Checkpoint could not be restored.');
         }
 
         if (messageIndex !== -1 && onRestoreCheckpoint) {
           const truncatedMessages = messages.slice(0, messageIndex);
-          onRestoreCheckpoint(checkpointId, truncatedMessages);
+          onRestoreCheckpoint(checkpoint.id, truncatedMessages);
         }
 
         notifySuccess('Restored checkpoint', {
@@ -537,6 +778,20 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
       }
     };
 
+    const handleAcceptPendingEdit = async (id: string) => {
+      const error = await onAcceptPendingEdit?.(id);
+      if (error) {
+        notifyError({
+          operation: 'accept-pending-edit',
+          error: new Error(error),
+          capture: false,
+          fallbackMessage: 'Failed to apply the edit',
+          toastId: 'accept-pending-edit-error',
+          logLabel: '[AiPromptPanel] Failed to apply pending edit',
+        });
+      }
+    };
+
     if (!hasApiKey) {
       const isDesktop = getPlatform().capabilities.hasFileSystem;
       return (
@@ -575,20 +830,117 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
         className="relative h-full flex flex-col ph-no-capture"
         style={{ backgroundColor: 'var(--bg-primary)' }}
       >
-        {onNewConversation && (
-          <Button
-            size="sm"
-            variant="secondary"
-            onClick={onNewConversation}
-            title="Start new conversation"
-            data-testid="ai-new-conversation-button"
+        {(onNewConversation || onExportConversation || onImportConversation) && (
+          <div className="absolute top-3 right-3 z-10 flex gap-2">
+            {timeline.length > 0 && (
+              <Button
+                size="sm"
+                variant="secondary"
+                onClick={() => setShowTimeline((shown) => !shown)}
+                title="Points in this conversation where code changed"
+                data-testid="ai-timeline-button"
+                aria-pressed={showTimeline}
+                className="shadow-sm"
+              >
+                Timeline
+              </Button>
+            )}
+            {onImportConversation && (
+              <Button
+                size="sm"
+                variant="secondary"
+                onClick={onImportConversation}
+                title="Open an exported conversation"
+                data-testid="ai-import-conversation-button"
+                disabled={isStreaming}
+                className="shadow-sm"
+              >
+                Import
+              </Button>
+            )}
+            {onExportConversation && (
+              <Button
+                size="sm"
+                variant="secondary"
+                onClick={onExportConversation}
+                title="Export conversation as JSON or Markdown"
+                data-testid="ai-export-conversation-button"
+                disabled={messages.length === 0}
+                className="shadow-sm"
+              >
+                Export
+              </Button>
+            )}
+            {onNewConversation && (
+              <Button
+                size="sm"
+                variant="secondary"
+                onClick={onNewConversation}
+                title="Start new conversation"
+                data-testid="ai-new-conversation-button"
+                disabled={isStreaming}
+                className="shadow-sm"
+              >
+                + New
+              </Button>
+            )}
+          </div>
+        )}
+
+        {showTimeline && timeline.length > 0 && (
+          <ConversationTimeline
+            entries={timeline}
+            messages={messages}
             disabled={isStreaming}
-            className="absolute top-3 right-3 z-10 shadow-sm"
-          >
-            + New
-          </Button>
+            onRestore={(messageId) => void handleRestoreCheckpoint(messageId)}
+          />
         )}
 
+        {onResumeInterruptedQuery &&
+          interruptedQueries.map((query) => (
+            <div
+              key={query.conversationId}
+              data-testid="ai-interrupted-query"
+              className="flex items-center gap-2 px-4 py-2 text-sm"
+              style={{
+                backgroundColor: 'var(--bg-primary)',
+                borderBottom: '1px solid var(--border-primary)',
+                color: 'var(--text-secondary)',
+              }}
+            >
+              <div className="flex-1 min-w-0 truncate" title={query.title}>
+                {describeInterruptedQuery(query)}
+              </div>
+              <Button
+                size="sm"
+                variant="primary"
+                onClick={() => onResumeInterruptedQuery(query.conversationId)}
+              >
+                Resume
+              </Button>
+              {onDiscardInterruptedQuery && (
+                <Button
+                  size="sm"
+                  variant="secondary"
+                  onClick={() => onDiscardInterruptedQuery(query.conversationId)}
+                >
+                  Dismiss
+                </Button>
+              )}
+            </div>
+          ))}
+
+        {onAcceptPendingEdit &&
+          onRejectPendingEdit &&
+          pendingEdits.map((edit) => (
+            <PendingEditCard
+              key={edit.id}
+              edit={edit}
+              onAccept={() => void handleAcceptPendingEdit(edit.id)}
+              onReject={() => onRejectPendingEdit(edit.id)}
+            />
+          ))}
+
         {messages.length === 0 && !streamingResponse ? (
           <div
             className="flex-1 flex items-center justify-center px-4"
@@ -661,12 +1013,13 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
                         )}
                       </div>
                     </div>
-                    {message.checkpointId && (
+                    {(message.checkpointId ||
+                      timeline.some((entry) => entry.messageId === message.id)) && (
                       <div className="flex justify-end">
                         <Button
                           size="sm"
                           variant="secondary"
-                          onClick={() => handleRestoreCheckpoint(message.checkpointId!, message.id)}
+                          onClick={() => handleRestoreCheckpoint(message.id, message.checkpointId)}
                           title="Restore code to before this turn"
                         >
                           ↶ Restore to before this turn
@@ -741,6 +1094,38 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
               </div>
             )}
 
+            {isStreaming && streamRetry && (
+              <div
+                className="text-xs text-center"
+                style={{ color: 'var(--text-tertiary)' }}
+                data-testid="ai-stream-retry"
+              >
+                {streamRetry.statusCode === 429 ? 'Rate limited' : 'The model call failed'};
+                retrying in {Math.ceil(streamRetry.delayMs / 1000)}s (attempt {streamRetry.attempt}{' '}
+                of {streamRetry.maxAttempts})…
+              </div>
+            )}
+
+            {isStreaming && streamingReasoning && (
+              <div className="flex gap-2 justify-start" data-testid="ai-streaming-reasoning">
+                <div
+                  className="max-w-[85%] rounded-lg px-3 py-2 border"
+                  style={{
+                    backgroundColor: 'var(--bg-secondary)',
+                    color: 'var(--text-secondary)',
+                    borderColor: 'var(--border-secondary)',
+                  }}
+                >
+                  <div className="text-xs mb-1" style={{ color: 'var(--text-tertiary)' }}>
+                    Thinking
+                  </div>
+                  <div className="text-xs italic whitespace-pre-wrap max-h-40 overflow-y-auto">
+                    {streamingReasoning}
+                  </div>
+                </div>
+              </div>
+            )}
+
             {streamingResponse && (
               <div className="flex gap-2 justify-start">
                 <div
@@ -831,14 +1216,70 @@ export const AiPromptPanel = forwardRef<AiPromptPanelRef, AiPromptPanelProps>(
             submitLabel="Send"
             submitTitle="Send (Enter). Shift+Enter adds a newline."
             trailingControls={
-              <ModelSelector
-                currentModel={currentModel}
-                currentProvider={currentProvider}
-                availableProviders={availableProviders}
-                onChange={(model, provider) => onModelChange?.(model, 'ai_panel', provider)}
-                disabled={isStreaming}
-                compact
-              />
+              <>
+                {onVerifyEditsWithPreviewChange && (
+                  <IconButton
+                    size="sm"
+                    isActive={verifyEditsWithPreview}
+                    aria-pressed={verifyEditsWithPreview}
+                    onClick={() => onVerifyEditsWithPreviewChange(!verifyEditsWithPreview)}
+                    title={
+                      verifyEditsWithPreview
+                        ? 'Edits are checked against a preview screenshot'
+                        : 'Check each edit against a preview screenshot'
+                    }
+                    data-testid="ai-verify-edits-toggle"
+                  >
+                    <TbEyeCheck size={16} />
+                  </IconButton>
+                )}
+                {onReviewEditsChange && (
+                  <IconButton
+                    size="sm"
+                    isActive={reviewEdits}
+                    aria-pressed={reviewEdits}
+                    onClick={() => onReviewEditsChange(!reviewEdits)}
+                    title={
+                      reviewEdits
+                        ? 'Edits wait for you to accept them'
+                        : 'Review each edit before it is applied'
+                    }
+                    data-testid="ai-review-edits-toggle"
+                  >
+                    <TbFileDiff size={16} />
+                  </IconButton>
+                )}
+                {onReasoningEffortChange && supportsReasoning(currentModel) && (
+                  <Select
+                    value={reasoningEffort}
+                    onValueChange={(value) => onReasoningEffortChange(value as ReasoningEffort)}
+                    disabled={isStreaming}
+                  >
+                    <SelectTrigger
+                      size="sm"
+                      title="How long the model reasons before answering, for this conversation"
+                      data-testid="ai-reasoning-effort"
+                    >
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      {(Object.keys(REASONING_EFFORT_LABELS) as ReasoningEffort[]).map((effort) => (
+                        <SelectItem key={effort} value={effort}>
+                          {REASONING_EFFORT_LABELS[effort]}
+                        </SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                )}
+                <ModelSelector
+                  currentModel={currentModel}
+                  currentProvider={currentProvider}
+                  availableProviders={availableProviders}
+                  onChange={(model, provider) => onModelChange?.(model, 'ai_panel', provider)}
+                  disabled={isStreaming}
+                  compact
+                />
+              </>
             }
             onTextChange={onTextChange}
             onFilesSelected={onFilesSelected}
@@ -0,0 +1,100 @@
+import { Button } from './ui';
+import type { EditorCheckpoint } from '../platform/historyService';
+import { getUserMessageText, type Message } from '../types/aiChat';
+
+/** A chat message whose request changed code, and the files it changed */
+export interface TimelineEntry {
+  messageId: string;
+  messageIndex: number;
+  timestamp: number;
+  /** Project files other than the editor's; the editor's own code is `null` */
+  files: (string | null)[];
+}
+
+/** Group a conversation's checkpoints by the message that made them, in chat order */
+export function buildTimeline(checkpoints: EditorCheckpoint[]): TimelineEntry[] {
+  const entries = new Map<string, TimelineEntry>();
+  for (const checkpoint of checkpoints) {
+    if (!checkpoint.message_id) continue;
+    let entry = entries.get(checkpoint.message_id);
+    if (!entry) {
+      entry = {
+        messageId: checkpoint.message_id,
+        messageIndex: checkpoint.message_index ?? 0,
+        timestamp: checkpoint.timestamp,
+        files: [],
+      };
+      entries.set(checkpoint.message_id, entry);
+    }
+    const file = checkpoint.file_path ?? null;
+    if (!entry.files.includes(file)) entry.files.push(file);
+  }
+  return [...entries.values()].sort((a, b) => a.messageIndex - b.messageIndex);
+}
+
+function describeEntry(entry: TimelineEntry, messages: Message[]): string {
+  const message = messages.find((candidate) => candidate.id === entry.messageId);
+  const text = message?.type === 'user' ? getUserMessageText(message) : '';
+  return text.split('\n')[0] || `Message ${entry.messageIndex + 1}`;
+}
+
+function describeFiles(files: (string | null)[]): string {
+  const names = files.map((file) => file ?? 'editor');
+  return names.length <= 2 ? names.join(', ') : `${names[0]} and ${names.length - 1} more`;
+}
+
+interface ConversationTimelineProps {
+  entries: TimelineEntry[];
+  messages: Message[];
+  disabled?: boolean;
+  onRestore: (messageId: string) => void;
+}
+
+/** The points in a conversation where code changed, each restorable */
+export function ConversationTimeline({
+  entries,
+  messages,
+  disabled = false,
+  onRestore,
+}: ConversationTimelineProps) {
+  return (
+    <ol
+      data-testid="ai-conversation-timeline"
+      className="px-4 py-2 text-sm flex flex-col gap-1 max-h-48 overflow-y-auto"
+      style={{
+        backgroundColor: 'var(--bg-secondary)',
+        borderBottom: '1px solid var(--border-primary)',
+        color: 'var(--text-secondary)',
+      }}
+    >
+      {entries.map((entry) => {
+        const label = describeEntry(entry, messages);
+        return (
+          <li key={entry.messageId} className="flex items-center gap-2">
+            <span className="text-xs shrink-0" style={{ color: 'var(--text-tertiary)' }}>
+              {new Date(entry.timestamp).toLocaleTimeString([], {
+                hour: '2-digit',
+                minute: '2-digit',
+              })}
+            </span>
+            <span className="flex-1 min-w-0 truncate" title={label}>
+              {label}
+            </span>
+            <span className="text-xs shrink-0" style={{ color: 'var(--text-tertiary)' }}>
+              {describeFiles(entry.files)}
+            </span>
+            <Button
+              size="sm"
+              variant="secondary"
+              onClick={() => onRestore(entry.messageId)}
+              disabled={disabled}
+              title="Restore code to before this message"
+            >
+              Restore
+            </Button>
+          </li>
+        );
+      })}
+    </ol>
+  );
+}
@@ -7,9 +7,12 @@ import { formatOpenScadCode } from '../utils/formatter';
 import { loadSettings, type Settings } from '../stores/settingsStore';
 import { getTheme } from '../themes';
 import { ensureOpenScadLanguage } from '../languages/openscadLanguage';
+import { registerOpenScadSymbolProviders } from '../languages/openscadSymbols';
 import { initVimMode } from 'monaco-vim';
 import { applyVimConfig } from '../utils/vimConfig';
 import { EditorTabs, type EditorTab } from './EditorTabs';
+import { sessionCursor, trackSessionCursor, type CursorPosition } from '../services/session';
+import { sendEditorEdits } from '../services/editorSync';
 
 interface EditorProps {
   value: string;
@@ -120,6 +123,18 @@ export function Editor({
     };
   }, [settings.editor.vimMode, settings.editor.vimConfig, statusBarMounted, editorMounted]);
 
+  // Put the cursor where the last session left it in a file opened for the first time.
+  // Read the cursor before setModel, which reports its own cursor change.
+  const restoreSessionCursor = useCallback(
+    (editor: Monaco.editor.IStandaloneCodeEditor, cursor: CursorPosition | undefined) => {
+      if (!cursor) return;
+      const position = { lineNumber: cursor.line, column: cursor.column };
+      editor.setPosition(position);
+      editor.revealPositionInCenter(position);
+    },
+    []
+  );
+
   // Get or create a Monaco model for a given file ID
   const getOrCreateModel = useCallback(
     (fileId: string, content: string): Monaco.editor.ITextModel | null => {
@@ -148,9 +163,11 @@ export function Editor({
     const editor = editorRef.current;
     if (!editor) return;
 
-    contentListenerRef.current = editor.onDidChangeModelContent(() => {
+    contentListenerRef.current = editor.onDidChangeModelContent((event) => {
       if (suppressOnChangeRef.current) return;
       const content = editor.getModel()?.getValue() ?? '';
+      // Monaco lists the changes last to first, so each applies to the result of the previous
+      sendEditorEdits(activeFileIdRef.current, event.changes, content);
       onChangeRef.current(content);
     });
   }, []);
@@ -177,6 +194,7 @@ export function Editor({
     if (!model) return;
 
     // Switch to the new model
+    const savedCursor = sessionCursor(activeFileId);
     suppressOnChangeRef.current = true;
     editor.setModel(model);
     suppressOnChangeRef.current = false;
@@ -185,6 +203,8 @@ export function Editor({
     const entry = modelsRef.current.get(activeFileId);
     if (entry?.viewState) {
       editor.restoreViewState(entry.viewState);
+    } else {
+      restoreSessionCursor(editor, savedCursor);
     }
 
     // Re-attach content listener to the new model
@@ -254,6 +274,24 @@ export function Editor({
     };
   }, [editorMounted, replaceModelContent]);
 
+  // Insert snippets (and other generated code) at the cursor, replacing the
+  // selection. The content listener propagates the change as a normal edit.
+  useEffect(() => {
+    const unlisten = eventBus.on('editor:insert-snippet', ({ code }) => {
+      const editor = editorRef.current;
+      const selection = editor?.getSelection();
+      if (!editor || !selection) return;
+      editor.pushUndoStop();
+      editor.executeEdits('snippet', [{ range: selection, text: code, forceMoveMarkers: true }]);
+      editor.pushUndoStop();
+      editor.focus();
+    });
+
+    return () => {
+      unlisten();
+    };
+  }, [editorMounted]);
+
   // Update markers when diagnostics change
   useEffect(() => {
     if (!monacoRef.current || !editorRef.current) return;
@@ -377,6 +415,7 @@ export function Editor({
 
     // Ensure full OpenSCAD language support (syntax, config, tokens)
     ensureOpenScadLanguage(monaco);
+    registerOpenScadSymbolProviders(monaco);
 
     // Register document formatting provider for OpenSCAD
     monaco.languages.registerDocumentFormattingEditProvider('openscad', {
@@ -606,6 +645,7 @@ export function Editor({
     // Now take over model management: create the initial model
     // Dispose the default model that @monaco-editor/react created
     const defaultModel = editor.getModel();
+    const savedCursor = sessionCursor(activeFileIdRef.current);
     const initialModel = getOrCreateModel(activeFileIdRef.current, value);
     if (initialModel && initialModel !== defaultModel) {
       editor.setModel(initialModel);
@@ -613,6 +653,13 @@ export function Editor({
         defaultModel.dispose();
       }
     }
+    restoreSessionCursor(editor, savedCursor);
+    editor.onDidChangeCursorPosition((event) => {
+      trackSessionCursor(activeFileIdRef.current, {
+        line: event.position.lineNumber,
+        column: event.position.column,
+      });
+    });
 
     // Set up content change listener
     setupContentListener();
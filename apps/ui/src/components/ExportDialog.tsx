@@ -4,6 +4,7 @@ import { getPlatform, type ExportFormat } from '../platform';
 import { isExportValidationError } from '../services/exportErrors';
 import { exportModelWithContext } from '../services/exportService';
 import { useSettings } from '../stores/settingsStore';
+import { useUnsupportedExportFormats } from '../hooks/useUnsupportedExportFormats';
 import {
   Button,
   IconButton,
@@ -31,11 +32,16 @@ const FORMAT_OPTIONS_3D: { value: ExportFormat; label: string; ext: string }[] =
   { value: 'obj', label: 'OBJ', ext: 'obj' },
   { value: 'amf', label: 'AMF', ext: 'amf' },
   { value: '3mf', label: '3MF', ext: '3mf' },
+  { value: 'off', label: 'OFF', ext: 'off' },
+  { value: 'wrl', label: 'VRML', ext: 'wrl' },
+  { value: 'pov', label: 'POV-Ray', ext: 'pov' },
+  { value: 'csg', label: 'CSG', ext: 'csg' },
 ];
 
 const FORMAT_OPTIONS_2D: { value: ExportFormat; label: string; ext: string }[] = [
   { value: 'svg', label: 'SVG', ext: 'svg' },
   { value: 'dxf', label: 'DXF', ext: 'dxf' },
+  { value: 'csg', label: 'CSG', ext: 'csg' },
 ];
 
 export function ExportDialog({ isOpen, onClose, source, previewKind }: ExportDialogProps) {
@@ -44,6 +50,7 @@ export function ExportDialog({ isOpen, onClose, source, previewKind }: ExportDia
   const [format, setFormat] = useState<ExportFormat>(previewKind === 'svg' ? 'svg' : 'stl');
   const [isExporting, setIsExporting] = useState(false);
   const [error, setError] = useState<string>('');
+  const unsupportedFormats = useUnsupportedExportFormats(isOpen);
 
   // Reset format each time the dialog opens so the default reflects the current preview kind.
   // useState only runs once at mount, but this component stays mounted with isOpen=false.
@@ -56,7 +63,9 @@ export function ExportDialog({ isOpen, onClose, source, previewKind }: ExportDia
 
   if (!isOpen) return null;
 
-  const formatOptions = previewKind === 'svg' ? FORMAT_OPTIONS_2D : FORMAT_OPTIONS_3D;
+  const formatOptions = (previewKind === 'svg' ? FORMAT_OPTIONS_2D : FORMAT_OPTIONS_3D).filter(
+    (option) => !unsupportedFormats.includes(option.value)
+  );
 
   const handleExport = async () => {
     setError('');
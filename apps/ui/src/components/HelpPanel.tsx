@@ -0,0 +1,101 @@
+import { useEffect, useState } from 'react';
+import { MarkdownMessage } from './MarkdownMessage';
+import { SearchInput, Text } from './ui';
+import { searchDocs, type DocsSearchResult } from '../services/docsIndex';
+import { notifyError } from '../utils/notifications';
+
+/** Pause in typing before the search runs */
+const SEARCH_DELAY_MS = 200;
+const RESULT_LIMIT = 20;
+
+/**
+ * Searches the bundled OpenSCAD reference and installed library docs,
+ * best matches first.
+ */
+export function HelpPanel() {
+  const [query, setQuery] = useState('');
+  const [results, setResults] = useState<DocsSearchResult[]>([]);
+  const [isSearching, setIsSearching] = useState(false);
+
+  useEffect(() => {
+    const trimmed = query.trim();
+    if (!trimmed) {
+      setResults([]);
+      setIsSearching(false);
+      return;
+    }
+    let cancelled = false;
+    setIsSearching(true);
+    const timer = setTimeout(() => {
+      searchDocs(trimmed, { limit: RESULT_LIMIT })
+        .then((next) => {
+          if (!cancelled) setResults(next);
+        })
+        .catch((error) => {
+          if (cancelled) return;
+          setResults([]);
+          notifyError({
+            operation: 'search-docs',
+            error,
+            fallbackMessage: 'Documentation search failed',
+            toastId: 'search-docs-error',
+          });
+        })
+        .finally(() => {
+          if (!cancelled) setIsSearching(false);
+        });
+    }, SEARCH_DELAY_MS);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [query]);
+
+  const hasQuery = query.trim().length > 0;
+
+  return (
+    <div
+      className="flex h-full flex-col"
+      style={{ backgroundColor: 'var(--bg-secondary)' }}
+      data-testid="help-panel"
+    >
+      <div className="p-3" style={{ borderBottom: '1px solid var(--border-primary)' }}>
+        <SearchInput
+          value={query}
+          onChange={(event) => setQuery(event.target.value)}
+          onClear={() => setQuery('')}
+          placeholder="Search OpenSCAD and library docs"
+          aria-label="Search documentation"
+        />
+      </div>
+      <div className="flex-1 overflow-y-auto">
+        {!hasQuery && (
+          <Text variant="caption" color="tertiary" className="block p-3">
+            Look up OpenSCAD modules and functions, or those of BOSL2, MCAD and your installed
+            libraries.
+          </Text>
+        )}
+        {hasQuery && !isSearching && results.length === 0 && (
+          <Text variant="caption" color="tertiary" className="block p-3">
+            No documentation matched &ldquo;{query.trim()}&rdquo;.
+          </Text>
+        )}
+        {results.map((result, index) => (
+          <section
+            key={`${result.source}:${result.file ?? ''}:${result.title}:${index}`}
+            className="p-3"
+            style={index > 0 ? { borderTop: '1px solid var(--border-primary)' } : undefined}
+          >
+            <Text variant="section-heading">{result.title}</Text>
+            <Text variant="caption" color="tertiary" className="block">
+              {result.file ? `${result.source}, ${result.file}` : result.source}
+            </Text>
+            <div className="mt-2 text-sm" style={{ color: 'var(--text-primary)' }}>
+              <MarkdownMessage content={result.content} />
+            </div>
+          </section>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -3,6 +3,7 @@ import { toast } from 'sonner';
 import { getPlatform, type ExportFormat } from '../platform';
 import { exportModelWithContext } from '../services/exportService';
 import { loadSettings } from '../stores/settingsStore';
+import { useUnsupportedExportFormats } from '../hooks/useUnsupportedExportFormats';
 import { normalizeAppError } from '../utils/notifications';
 import { OPENSCAD_PROJECT_FILE_EXTENSIONS } from '../../../../packages/shared/src/openscadProjectFiles';
 
@@ -18,6 +19,10 @@ const EXPORT_FORMATS: { value: ExportFormat; label: string; ext: string }[] = [
   { value: 'obj', label: 'OBJ (3D Model)', ext: 'obj' },
   { value: 'amf', label: 'AMF (3D Model)', ext: 'amf' },
   { value: '3mf', label: '3MF (3D Model)', ext: '3mf' },
+  { value: 'off', label: 'OFF (3D Model)', ext: 'off' },
+  { value: 'wrl', label: 'VRML (3D Model)', ext: 'wrl' },
+  { value: 'pov', label: 'POV-Ray Scene', ext: 'pov' },
+  { value: 'csg', label: 'CSG (OpenSCAD Tree)', ext: 'csg' },
   { value: 'svg', label: 'SVG (2D Vector)', ext: 'svg' },
   { value: 'dxf', label: 'DXF (2D CAD)', ext: 'dxf' },
 ];
@@ -34,6 +39,7 @@ export function MenuBar({
 }: MenuBarProps) {
   const [fileMenuOpen, setFileMenuOpen] = useState(false);
   const [exportMenuOpen, setExportMenuOpen] = useState(false);
+  const unsupportedFormats = useUnsupportedExportFormats(exportMenuOpen);
   const menuRef = useRef<HTMLDivElement>(null);
 
   // Close menu when clicking outside
@@ -151,7 +157,9 @@ export function MenuBar({
                 className="absolute left-full top-0 ml-1 w-48 bg-gray-800 border border-gray-700 rounded-lg shadow-lg"
                 onMouseLeave={() => setExportMenuOpen(false)}
               >
-                {EXPORT_FORMATS.map((format) => (
+                {EXPORT_FORMATS.filter(
+                  (format) => !unsupportedFormats.includes(format.value)
+                ).map((format) => (
                   // eslint-disable-next-line no-restricted-syntax -- export format menu item; same gray scheme, can't use <Button> with .map() key on a Fragment wrapper
                   <button
                     key={format.value}
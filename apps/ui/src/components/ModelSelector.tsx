@@ -11,7 +11,11 @@ import {
   SelectLabel,
 } from './ui';
 import { notifyError } from '../utils/notifications';
-import { getProviderFromModel, type AiProvider } from '../stores/apiKeyStore';
+import { getProviderFromModel, isAiProvider, type AiProvider } from '../stores/apiKeyStore';
+import { AI_PROVIDER_LABELS } from '../utils/aiModels';
+
+/** Providers whose models come from the user's own configuration */
+const CONFIGURED_PROVIDERS: AiProvider[] = ['azure-openai', 'openai-compatible'];
 
 interface ModelSelectorProps {
   currentModel: string;
@@ -29,11 +33,7 @@ function encodeModelValue(provider: AiProvider, modelId: string): string {
 function decodeModelValue(value: string): { provider: AiProvider; modelId: string } {
   try {
     const parsed = JSON.parse(value);
-    if (
-      Array.isArray(parsed) &&
-      (parsed[0] === 'anthropic' || parsed[0] === 'openai' || parsed[0] === 'openai-compatible') &&
-      typeof parsed[1] === 'string'
-    ) {
+    if (Array.isArray(parsed) && isAiProvider(parsed[0]) && typeof parsed[1] === 'string') {
       return { provider: parsed[0], modelId: parsed[1] };
     }
   } catch {
@@ -53,13 +53,17 @@ export function ModelSelector({
   const { groupedByProvider, isLoading, error, fromCache, refreshModels } =
     useModels(availableProviders);
 
-  const {
-    anthropic: anthropicModels,
-    openai: openaiModels,
-    openaiCompatible: openAiCompatibleModels,
-  } = groupedByProvider;
-  const hasModels =
-    anthropicModels.length > 0 || openaiModels.length > 0 || openAiCompatibleModels.length > 0;
+  const groups = [
+    { provider: 'anthropic' as const, models: groupedByProvider.anthropic },
+    { provider: 'bedrock' as const, models: groupedByProvider.bedrock },
+    { provider: 'vertex' as const, models: groupedByProvider.vertex },
+    { provider: 'openai' as const, models: groupedByProvider.openai },
+    { provider: 'azure-openai' as const, models: groupedByProvider.azureOpenai },
+    { provider: 'gemini' as const, models: groupedByProvider.gemini },
+    { provider: 'openrouter' as const, models: groupedByProvider.openrouter },
+    { provider: 'openai-compatible' as const, models: groupedByProvider.openaiCompatible },
+  ].filter((group) => group.models.length > 0);
+  const hasModels = groups.length > 0;
   const selectedProvider = currentProvider ?? getProviderFromModel(currentModel);
   const selectedValue = encodeModelValue(selectedProvider, currentModel);
 
@@ -74,19 +78,21 @@ export function ModelSelector({
     });
   }, [error]);
 
+  const selectedGroupModels = groups.find((group) => group.provider === selectedProvider)?.models;
+
   useEffect(() => {
     if (
       disabled ||
       isLoading ||
-      selectedProvider !== 'openai-compatible' ||
-      openAiCompatibleModels.length === 0 ||
-      openAiCompatibleModels.some((model) => model.id === currentModel)
+      !CONFIGURED_PROVIDERS.includes(selectedProvider) ||
+      !selectedGroupModels ||
+      selectedGroupModels.some((model) => model.id === currentModel)
     ) {
       return;
     }
 
-    onChange(openAiCompatibleModels[0].id, 'openai-compatible');
-  }, [currentModel, disabled, isLoading, onChange, openAiCompatibleModels, selectedProvider]);
+    onChange(selectedGroupModels[0].id, selectedProvider);
+  }, [currentModel, disabled, isLoading, onChange, selectedGroupModels, selectedProvider]);
 
   if (!hasModels && !isLoading) {
     return (
@@ -123,46 +129,16 @@ export function ModelSelector({
           <SelectValue />
         </SelectTrigger>
         <SelectContent>
-          {anthropicModels.length > 0 && (
-            <SelectGroup>
-              <SelectLabel>Anthropic</SelectLabel>
-              {anthropicModels.map((model) => (
-                <SelectItem
-                  key={`${model.provider}:${model.id}`}
-                  value={encodeModelValue(model.provider, model.id)}
-                >
-                  {model.display_name}
-                </SelectItem>
-              ))}
-            </SelectGroup>
-          )}
-          {anthropicModels.length > 0 &&
-            (openaiModels.length > 0 || openAiCompatibleModels.length > 0) && (
-              <div
-                className="my-1 mx-2 h-px"
-                style={{ backgroundColor: 'var(--border-primary)' }}
-              />
-            )}
-          {openaiModels.length > 0 && (
-            <SelectGroup>
-              <SelectLabel>OpenAI</SelectLabel>
-              {openaiModels.map((model) => (
-                <SelectItem
-                  key={`${model.provider}:${model.id}`}
-                  value={encodeModelValue(model.provider, model.id)}
-                >
-                  {model.display_name}
-                </SelectItem>
-              ))}
-            </SelectGroup>
-          )}
-          {openaiModels.length > 0 && openAiCompatibleModels.length > 0 && (
-            <div className="my-1 mx-2 h-px" style={{ backgroundColor: 'var(--border-primary)' }} />
-          )}
-          {openAiCompatibleModels.length > 0 && (
-            <SelectGroup>
-              <SelectLabel>OpenAI-compatible</SelectLabel>
-              {openAiCompatibleModels.map((model) => (
+          {groups.map((group, index) => (
+            <SelectGroup key={group.provider}>
+              {index > 0 && (
+                <div
+                  className="my-1 mx-2 h-px"
+                  style={{ backgroundColor: 'var(--border-primary)' }}
+                />
+              )}
+              <SelectLabel>{AI_PROVIDER_LABELS[group.provider]}</SelectLabel>
+              {group.models.map((model) => (
                 <SelectItem
                   key={`${model.provider}:${model.id}`}
                   value={encodeModelValue(model.provider, model.id)}
@@ -171,7 +147,7 @@ export function ModelSelector({
                 </SelectItem>
               ))}
             </SelectGroup>
-          )}
+          ))}
         </SelectContent>
       </Select>
 
@@ -27,9 +27,14 @@ import {
   EditorSettings,
   PrivacySettings,
   LibrariesSettings,
+  OpenScadVersionsCard,
+  ManagedLibrariesCard,
+  SafeModeCard,
   AiSettings,
 } from './settings';
 import type { AiSettingsHandle } from './settings/AiSettings';
+import { HistorySettingsCard } from './settings/HistorySettingsCard';
+import { RenderSchedulerCard } from './settings/RenderSchedulerCard';
 
 export type SettingsSection =
   | 'appearance'
@@ -120,6 +125,15 @@ export function SettingsDialog({ isOpen, onClose, initialTab }: SettingsDialogPr
     [settings]
   );
 
+  const handleSettingsReplaced = useCallback(
+    (updated: Settings) => {
+      setSettings(updated);
+      setLocalVimConfig(updated.editor.vimConfig);
+      updateTheme(updated.appearance.theme);
+    },
+    [updateTheme]
+  );
+
   const handleViewerChange = useCallback(
     <K extends keyof Settings['viewer']>(key: K, value: Settings['viewer'][K]) => {
       const updated = { ...settings, viewer: { ...settings.viewer, [key]: value } };
@@ -228,7 +242,7 @@ export function SettingsDialog({ isOpen, onClose, initialTab }: SettingsDialogPr
     { key: 'project', label: 'Project', icon: <TbRuler size={16} /> },
     { key: 'privacy', label: 'Privacy', icon: <TbShield size={16} /> },
     ...(isDesktop
-      ? [{ key: 'libraries' as const, label: 'Libraries', icon: <TbBooks size={16} /> }]
+      ? [{ key: 'libraries' as const, label: 'OpenSCAD', icon: <TbBooks size={16} /> }]
       : []),
     { key: 'ai', label: 'AI Assistant', icon: <TbSparkles size={16} /> },
   ];
@@ -239,7 +253,7 @@ export function SettingsDialog({ isOpen, onClose, initialTab }: SettingsDialogPr
     editor: 'Editor',
     project: 'Project',
     privacy: 'Privacy',
-    libraries: 'Libraries',
+    libraries: 'OpenSCAD & Libraries',
     ai: 'AI Assistant',
   };
 
@@ -356,27 +370,39 @@ export function SettingsDialog({ isOpen, onClose, initialTab }: SettingsDialogPr
                 settings={settings}
                 onViewerChange={handleViewerChange}
                 onProjectChange={handleProjectChange}
+                onSettingsReplaced={handleSettingsReplaced}
               />
             )}
             {activeSection === 'editor' && (
-              <EditorSettings
-                settings={settings}
-                onEditorChange={handleEditorChange}
-                localVimConfig={localVimConfig}
-                onLocalVimConfigChange={setLocalVimConfig}
-              />
+              <div className="flex flex-col" style={{ gap: 'var(--space-section-gap)' }}>
+                <EditorSettings
+                  settings={settings}
+                  onEditorChange={handleEditorChange}
+                  localVimConfig={localVimConfig}
+                  onLocalVimConfigChange={setLocalVimConfig}
+                />
+                {isDesktop && <RenderSchedulerCard isOpen={isOpen} />}
+                {isDesktop && <HistorySettingsCard isOpen={isOpen} />}
+              </div>
             )}
             {activeSection === 'privacy' && (
-              <PrivacySettings settings={settings} onPrivacyChange={handlePrivacyChange} />
+              <div className="flex flex-col" style={{ gap: 'var(--space-section-gap)' }}>
+                <PrivacySettings settings={settings} onPrivacyChange={handlePrivacyChange} />
+                {isDesktop && <SafeModeCard isOpen={isOpen} />}
+              </div>
             )}
             {activeSection === 'libraries' && (
-              <LibrariesSettings
-                settings={settings}
-                autoDiscoveredPaths={autoDiscoveredPaths}
-                onLibraryChange={handleLibraryChange}
-                onAddPath={handleAddLibraryPath}
-                onRemovePath={handleRemoveLibraryPath}
-              />
+              <div className="flex flex-col" style={{ gap: 'var(--space-section-gap)' }}>
+                <OpenScadVersionsCard isOpen={isOpen} />
+                <ManagedLibrariesCard isOpen={isOpen} />
+                <LibrariesSettings
+                  settings={settings}
+                  autoDiscoveredPaths={autoDiscoveredPaths}
+                  onLibraryChange={handleLibraryChange}
+                  onAddPath={handleAddLibraryPath}
+                  onRemovePath={handleRemoveLibraryPath}
+                />
+              </div>
             )}
             {activeSection === 'ai' && (
               <AiSettings ref={aiRef} isOpen={isOpen} onCanSaveChange={setAiCanSave} />
@@ -90,6 +90,8 @@ import {
   type ViewerAnnotationAttachResult,
 } from './viewer-annotation';
 import { notifyError, notifySuccess } from '../utils/notifications';
+import { MAIN_PREVIEW_VIEWER_ID } from '../utils/capturePreview';
+import { takeRestoredCamera, trackSessionCamera } from '../services/session';
 import type Konva from 'konva';
 
 interface ThreeViewerProps {
@@ -741,6 +743,7 @@ function ViewerCameraManager({
   selectionActive,
   measurementCount,
   sectionState,
+  tracksSession,
 }: {
   cameraControlsRef: React.RefObject<CameraControlsType | null>;
   modelFrame: ModelFrame | null;
@@ -753,6 +756,8 @@ function ViewerCameraManager({
   selectionActive: boolean;
   measurementCount: number;
   sectionState: SectionPlaneState | null;
+  /** Save the camera with the session and restore it on the first framed model */
+  tracksSession: boolean;
 }) {
   const camera = useThree((state) => state.camera);
   const gl = useThree((state) => state.gl);
@@ -846,11 +851,19 @@ function ViewerCameraManager({
         orthographic,
         sceneStyle,
         enableTransition,
-      }).then(() => {
+      }).then(async () => {
+        const restoredCamera = tracksSession ? takeRestoredCamera() : null;
+        if (restoredCamera) {
+          await cameraControls.setLookAt(
+            ...restoredCamera.position,
+            ...restoredCamera.target,
+            false
+          );
+        }
         publishTestState();
       });
     },
-    [cameraControlsRef, orthographic, publishTestState, sceneStyle]
+    [cameraControlsRef, orthographic, publishTestState, sceneStyle, tracksSession]
   );
 
   useEffect(() => {
@@ -866,6 +879,12 @@ function ViewerCameraManager({
 
     const handleRest = () => {
       isUserControllingRef.current = false;
+      if (tracksSession) {
+        trackSessionCamera({
+          position: tupleFromVector(cameraControls.getPosition(new THREE.Vector3(), true)),
+          target: tupleFromVector(cameraControls.getTarget(new THREE.Vector3(), true)),
+        });
+      }
 
       const pendingVersion = pendingAutoFitVersionRef.current;
       const pendingFrame = latestModelFrameRef.current;
@@ -891,7 +910,7 @@ function ViewerCameraManager({
       cameraControls.removeEventListener('rest', handleRest);
       gl.domElement.removeEventListener('wheel', handleWheel);
     };
-  }, [cameraControlsRef, fitModelToView, gl.domElement, publishTestState]);
+  }, [cameraControlsRef, fitModelToView, gl.domElement, publishTestState, tracksSession]);
 
   useEffect(() => {
     publishTestState(modelFrame);
@@ -1818,6 +1837,7 @@ export function ThreeViewer({
               selectionActive={!!selection.objectUuid}
               measurementCount={measurements.length}
               sectionState={sectionState}
+              tracksSession={viewerId === MAIN_PREVIEW_VIEWER_ID}
             />
 
             <EnvironmentWithFallback preset={sceneStyle.environmentPreset} />
@@ -1,6 +1,6 @@
 /** @jest-environment jsdom */
 
-import { fireEvent, screen } from '@testing-library/react';
+import { act, fireEvent, screen, waitFor, within } from '@testing-library/react';
 import { jest } from '@jest/globals';
 import { forwardRef } from 'react';
 import type { AiPromptPanelProps } from '../AiPromptPanel';
@@ -37,6 +37,8 @@ jest.unstable_mockModule('@/stores/apiKeyStore', () => ({
 }));
 
 let AiPromptPanel: typeof import('../AiPromptPanel').AiPromptPanel;
+let eventBus: typeof import('../../platform').eventBus;
+let historyService: typeof import('../../platform/historyService').historyService;
 
 function createBaseProps(overrides: Partial<AiPromptPanelProps> = {}): AiPromptPanelProps {
   return {
@@ -164,6 +166,79 @@ function installScrollMetrics(
 describe('AiPromptPanel', () => {
   beforeAll(async () => {
     ({ AiPromptPanel } = await import('@/components/AiPromptPanel'));
+    ({ eventBus } = await import('@/platform'));
+    ({ historyService } = await import('@/platform/historyService'));
+  });
+
+  it("lists the conversation's code changes and restores to before one", async () => {
+    historyService.clear();
+    const checkpointId = historyService.createCheckpoint('cube(1);', [], 'Before AI edit', 'ai', {
+      conversationId: 'conversation-1',
+      messageId: 'user-1',
+      messageIndex: 0,
+    });
+    const onRestoreCheckpoint = jest.fn();
+    renderWithProviders(
+      <AiPromptPanel
+        {...createBaseProps({
+          messages: [createUserMessage()],
+          conversationId: 'conversation-1',
+          onNewConversation: () => {},
+          onRestoreCheckpoint,
+        })}
+      />
+    );
+
+    fireEvent.click(await screen.findByTestId('ai-timeline-button'));
+    const timeline = screen.getByTestId('ai-conversation-timeline');
+    expect(timeline.textContent).toContain('Inspect the project files.');
+    expect(timeline.textContent).toContain('editor');
+
+    fireEvent.click(within(timeline).getByRole('button', { name: 'Restore' }));
+
+    await waitFor(() => expect(onRestoreCheckpoint).toHaveBeenCalledWith(checkpointId, []));
+  });
+
+  it("shows a retry from the conversation's stream events until the next attempt reports", () => {
+    renderWithProviders(
+      <AiPromptPanel
+        {...createBaseProps({
+          messages: [createUserMessage()],
+          isStreaming: true,
+          conversationId: 'conversation-1',
+        })}
+      />
+    );
+
+    act(() => {
+      eventBus.emit('ai-stream:conversation-2', {
+        conversationId: 'conversation-2',
+        status: 'retrying',
+        messages: [],
+        retry: { attempt: 1, maxAttempts: 3, delayMs: 2000 },
+      });
+    });
+    expect(screen.queryByTestId('ai-stream-retry')).toBeNull();
+
+    act(() => {
+      eventBus.emit('ai-stream:conversation-1', {
+        conversationId: 'conversation-1',
+        status: 'retrying',
+        messages: [],
+        retry: { attempt: 2, maxAttempts: 3, delayMs: 4000, statusCode: 429 },
+      });
+    });
+    expect(screen.getByTestId('ai-stream-retry').textContent).toContain('Rate limited');
+    expect(screen.getByTestId('ai-stream-retry').textContent).toContain('attempt 2 of 3');
+
+    act(() => {
+      eventBus.emit('ai-stream:conversation-1', {
+        conversationId: 'conversation-1',
+        status: 'streaming',
+        messages: [],
+      });
+    });
+    expect(screen.queryByTestId('ai-stream-retry')).toBeNull();
   });
 
   it('keeps completed tool payloads collapsed until expanded', () => {
@@ -200,6 +275,74 @@ describe('AiPromptPanel', () => {
     expect(screen.getByText('Waiting for result...')).toBeTruthy();
   });
 
+  it('shows a live diff of an apply_edit call whose arguments are still streaming', () => {
+    renderWithProviders(
+      <AiPromptPanel
+        {...createBaseProps({
+          messages: [createUserMessage()],
+          isStreaming: true,
+          currentToolCalls: [
+            {
+              toolCallId: 'tool-2',
+              name: 'apply_edit',
+              args: { old_string: 'cube(1);\n', new_string: 'cube([1, ' },
+              state: 'pending',
+            },
+          ],
+        })}
+      />
+    );
+
+    const preview = screen.getByTestId('ai-live-edit-preview');
+    expect(preview.textContent).toContain('cube(1);');
+    expect(preview.textContent).toContain('cube([1, ');
+  });
+
+  it('lists pending edits with their changes and accepts or rejects them', () => {
+    const onAcceptPendingEdit = jest.fn(async (_id: string) => null);
+    const onRejectPendingEdit = jest.fn();
+    const lines = Array.from({ length: 10 }, (_, index) => ` cube(${index});\n`);
+    renderWithProviders(
+      <AiPromptPanel
+        {...createBaseProps({
+          pendingEdits: [
+            {
+              id: 'edit-1',
+              documentId: null,
+              filePath: 'main.scad',
+              oldString: 'cube(5);\n',
+              newString: 'sphere(5);\n',
+              diff: [
+                ...lines.slice(0, 5),
+                '-cube(5);\n',
+                '+sphere(5);\n',
+                ...lines.slice(6),
+              ].join(''),
+              addedLines: 1,
+              removedLines: 1,
+              createdAt: 0,
+            },
+          ],
+          onAcceptPendingEdit,
+          onRejectPendingEdit,
+        })}
+      />
+    );
+
+    const card = screen.getByTestId('ai-pending-edit');
+    expect(card.textContent).toContain('Proposed edit to main.scad +1 -1');
+    expect(card.textContent).toContain('sphere(5);');
+    // Only the lines next to the change are shown
+    expect(card.textContent).toContain('cube(4);');
+    expect(card.textContent).not.toContain('cube(1);');
+    expect(card.textContent).not.toContain('cube(9);');
+
+    fireEvent.click(screen.getByRole('button', { name: 'Accept' }));
+    expect(onAcceptPendingEdit).toHaveBeenCalledWith('edit-1');
+    fireEvent.click(screen.getByRole('button', { name: 'Reject' }));
+    expect(onRejectPendingEdit).toHaveBeenCalledWith('edit-1');
+  });
+
   it('keeps screenshot thumbnails visible while the raw screenshot payload stays collapsed', () => {
     renderWithProviders(
       <AiPromptPanel {...createBaseProps({ messages: [createScreenshotToolMessage()] })} />
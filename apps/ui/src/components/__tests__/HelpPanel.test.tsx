@@ -0,0 +1,62 @@
+/** @jest-environment jsdom */
+
+import { fireEvent, screen, waitFor } from '@testing-library/react';
+import { jest } from '@jest/globals';
+import type { DocsSearchResult } from '../../services/docsIndex';
+import { renderWithProviders } from './test-utils';
+
+const mockSearchDocs =
+  jest.fn<(query: string, options?: { limit?: number }) => Promise<DocsSearchResult[]>>();
+
+jest.unstable_mockModule('@/services/docsIndex', () => ({
+  searchDocs: mockSearchDocs,
+}));
+
+let HelpPanel: typeof import('../HelpPanel').HelpPanel;
+
+describe('HelpPanel', () => {
+  beforeAll(async () => {
+    ({ HelpPanel } = await import('../HelpPanel'));
+  });
+
+  beforeEach(() => {
+    mockSearchDocs.mockReset();
+  });
+
+  it('shows ranked sections for the query', async () => {
+    mockSearchDocs.mockResolvedValue([
+      { source: 'OpenSCAD', title: 'cylinder', content: 'Creates a cylinder.', score: 9 },
+      {
+        source: 'BOSL2',
+        file: 'shapes3d.scad',
+        title: 'cyl',
+        content: 'Cylinder with rounding.',
+        score: 4,
+      },
+    ]);
+
+    renderWithProviders(<HelpPanel />);
+    fireEvent.change(screen.getByLabelText('Search documentation'), {
+      target: { value: 'cylinder ' },
+    });
+
+    await waitFor(() => expect(screen.getByText('cyl')).toBeTruthy());
+    expect(mockSearchDocs).toHaveBeenCalledWith('cylinder', { limit: 20 });
+    const headings = screen.getAllByRole('heading').map((heading) => heading.textContent);
+    expect(headings).toEqual(['cylinder', 'cyl']);
+    expect(screen.getByText('BOSL2, shapes3d.scad')).toBeTruthy();
+  });
+
+  it('says when nothing matched', async () => {
+    mockSearchDocs.mockResolvedValue([]);
+
+    renderWithProviders(<HelpPanel />);
+    fireEvent.change(screen.getByLabelText('Search documentation'), {
+      target: { value: 'nonexistent' },
+    });
+
+    await waitFor(() =>
+      expect(screen.getByText(/No documentation matched/).textContent).toContain('nonexistent')
+    );
+  });
+});
@@ -1,6 +1,6 @@
 import React, { useCallback, useRef, useState } from 'react';
 import type { IDockviewPanelProps, IDockviewPanelHeaderProps } from 'dockview';
-import { TbCode, TbEye, TbSparkles, TbTerminal2 } from 'react-icons/tb';
+import { TbCode, TbEye, TbHelp, TbSparkles, TbTerminal2 } from 'react-icons/tb';
 import type { IconType } from 'react-icons';
 import { Editor } from '../Editor';
 import { Preview } from '../Preview';
@@ -8,15 +8,22 @@ import { AiPromptPanel, type AiPromptPanelRef } from '../AiPromptPanel';
 import { DiagnosticsPanel } from '../DiagnosticsPanel';
 import { DiffViewer } from '../DiffViewer';
 import { CustomizerPanel } from '../CustomizerPanel';
+import { HelpPanel } from '../HelpPanel';
 import { PanelErrorBoundary } from '../ErrorBoundary';
 import { useWorkspace } from '../../contexts/WorkspaceContext';
 import { useProjectStore } from '../../stores/projectStore';
 import { isExportValidationError } from '../../services/exportErrors';
 import { exportModelWithContext } from '../../services/exportService';
+import {
+  conversationExportFormat,
+  pickConversationExportPath,
+  pickConversationImportPath,
+} from '../../services/savedConversations';
 import { getPlatform } from '../../platform';
-import { notifyError } from '../../utils/notifications';
+import { notifyError, notifySuccess } from '../../utils/notifications';
 import { MAIN_PREVIEW_VIEWER_ID } from '../../utils/capturePreview';
 import { useAnalytics } from '../../analytics/runtime';
+import { useSyntaxCheck } from '../../hooks/useSyntaxCheck';
 
 const EditorPanel: React.FC<IDockviewPanelProps> = () => {
   const {
@@ -35,6 +42,12 @@ const EditorPanel: React.FC<IDockviewPanelProps> = () => {
     activeTab?.projectPath ? (s.files[activeTab.projectPath]?.content ?? '') : ''
   );
   const projectFiles = useProjectStore((s) => s.files);
+  // Syntax errors from linting show straight away; the last render's
+  // diagnostics show once the code parses
+  const syntaxErrors = useSyntaxCheck(editorContent).filter(
+    (d) => !d.file || d.file === activeTab?.projectPath
+  );
+  const editorDiagnostics = syntaxErrors.length > 0 ? syntaxErrors : diagnostics;
   return (
     <PanelErrorBoundary panelId="editor" panelName="Editor">
       <Editor
@@ -48,7 +61,7 @@ const EditorPanel: React.FC<IDockviewPanelProps> = () => {
         }))}
         onTabClick={onTabClick}
         onTabClose={onTabClose}
-        diagnostics={diagnostics.filter((d) => !d.message.match(/^ECHO:/i))}
+        diagnostics={editorDiagnostics.filter((d) => !d.message.match(/^ECHO:/i))}
         onManualRender={onManualRender}
         settings={settings}
         focusRequestKey={editorFocusRequestKey}
@@ -87,6 +100,30 @@ const PreviewPanel: React.FC<IDockviewPanelProps> = () => {
 
 const AiChatPanel: React.FC<IDockviewPanelProps> = () => {
   const ws = useWorkspace();
+  const { exportConversation, importConversation } = ws;
+  const canExchangeConversations = getPlatform().capabilities.hasFileSystem;
+
+  const handleExportConversation = useCallback(async () => {
+    try {
+      const path = await pickConversationExportPath();
+      if (!path) return;
+      await exportConversation(conversationExportFormat(path), path);
+      notifySuccess('Conversation exported', { toastId: 'conversation-export' });
+    } catch (err) {
+      notifyError({ operation: 'export-conversation', error: err });
+    }
+  }, [exportConversation]);
+
+  const handleImportConversation = useCallback(async () => {
+    try {
+      const path = await pickConversationImportPath();
+      if (!path) return;
+      await importConversation(path);
+    } catch (err) {
+      notifyError({ operation: 'import-conversation', error: err });
+    }
+  }, [importConversation]);
+
   return (
     <PanelErrorBoundary panelId="ai-chat" panelName="AI Chat">
       <AiPromptPanel
@@ -106,9 +143,17 @@ const AiChatPanel: React.FC<IDockviewPanelProps> = () => {
         isProcessingAttachments={ws.isProcessingAttachments}
         isStreaming={ws.isStreaming}
         streamingResponse={ws.streamingResponse}
+        streamingReasoning={ws.streamingReasoning}
+        conversationId={ws.currentConversationId}
         onCancel={ws.cancelStream}
         messages={ws.messages}
         onNewConversation={ws.newConversation}
+        onExportConversation={
+          canExchangeConversations ? () => void handleExportConversation() : undefined
+        }
+        onImportConversation={
+          canExchangeConversations ? () => void handleImportConversation() : undefined
+        }
         currentToolCalls={ws.currentToolCalls}
         currentProvider={ws.currentProvider}
         currentModel={ws.currentModel}
@@ -116,6 +161,20 @@ const AiChatPanel: React.FC<IDockviewPanelProps> = () => {
         onModelChange={ws.setCurrentModel}
         onRestoreCheckpoint={ws.handleRestoreCheckpoint}
         onOpenSettings={ws.onOpenAiSettings}
+        interruptedQueries={ws.interruptedQueries}
+        onResumeInterruptedQuery={(conversationId) => {
+          void ws.resumeInterruptedQuery(conversationId);
+        }}
+        onDiscardInterruptedQuery={ws.discardInterruptedQuery}
+        verifyEditsWithPreview={ws.verifyEditsWithPreview}
+        onVerifyEditsWithPreviewChange={ws.setVerifyEditsWithPreview}
+        reviewEdits={ws.reviewEdits}
+        onReviewEditsChange={ws.setReviewEdits}
+        pendingEdits={ws.pendingEdits}
+        onAcceptPendingEdit={ws.acceptPendingEdit}
+        onRejectPendingEdit={ws.rejectPendingEdit}
+        reasoningEffort={ws.reasoningEffort}
+        onReasoningEffortChange={ws.setReasoningEffort}
       />
     </PanelErrorBoundary>
   );
@@ -130,6 +189,12 @@ const ConsolePanel: React.FC<IDockviewPanelProps> = () => {
   );
 };
 
+const HelpPanelWrapper: React.FC<IDockviewPanelProps> = () => (
+  <PanelErrorBoundary panelId="help" panelName="Help">
+    <HelpPanel />
+  </PanelErrorBoundary>
+);
+
 const DiffViewerPanel: React.FC<IDockviewPanelProps> = () => {
   const { source, acceptDiff, rejectDiff, isApplyingDiff } = useWorkspace();
   return (
@@ -252,6 +317,7 @@ export const panelComponents: Record<string, React.FC<IDockviewPanelProps>> = {
   preview: PreviewPanel,
   'ai-chat': AiChatPanel,
   console: ConsolePanel,
+  help: HelpPanelWrapper,
   'diff-viewer': DiffViewerPanel,
   customizer: CustomizerPanelWrapper,
 };
@@ -268,6 +334,10 @@ export const PANEL_TYPES: PanelTypeInfo[] = [
   { id: 'preview', label: 'Preview', icon: TbEye },
   { id: 'ai-chat', label: 'AI', icon: TbSparkles },
   { id: 'console', label: 'Console', icon: TbTerminal2 },
+  // Documentation search runs in the desktop backend
+  ...(typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window
+    ? [{ id: 'help', label: 'Help', icon: TbHelp }]
+    : []),
 ];
 
 export const WorkspaceTab: React.FC<IDockviewPanelHeaderProps> = (props) => {
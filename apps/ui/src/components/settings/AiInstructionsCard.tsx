@@ -0,0 +1,146 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button, Label, Text } from '../ui';
+import {
+  getAiInstructions,
+  setGlobalAiInstructions,
+  setProjectAiInstructions,
+  type AiInstructions,
+} from '../../services/aiInstructions';
+import { notifyError, notifySuccess } from '../../utils/notifications';
+import { SettingsCard, SettingsCardHeader, SettingsCardSection } from './SettingsPrimitives';
+
+interface AiInstructionsCardProps {
+  isOpen: boolean;
+}
+
+interface InstructionsFieldProps {
+  id: string;
+  label: string;
+  hint: string;
+  value: string;
+  placeholder: string;
+  onChange: (value: string) => void;
+}
+
+function InstructionsField({
+  id,
+  label,
+  hint,
+  value,
+  placeholder,
+  onChange,
+}: InstructionsFieldProps) {
+  return (
+    <div className="flex flex-col" style={{ gap: 'var(--space-label-gap)' }}>
+      <Label htmlFor={id}>{label}</Label>
+      <div
+        className="overflow-hidden rounded-lg border"
+        style={{
+          backgroundColor: 'var(--bg-elevated)',
+          borderColor: 'var(--border-primary)',
+        }}
+      >
+        <textarea
+          id={id}
+          value={value}
+          rows={5}
+          placeholder={placeholder}
+          onChange={(event) => onChange(event.target.value)}
+          className="block min-h-[5.5rem] w-full resize-y bg-transparent px-3 py-2 text-sm leading-relaxed outline-none font-mono"
+          style={{ color: 'var(--text-primary)' }}
+        />
+      </div>
+      <Text variant="caption" color="tertiary">
+        {hint}
+      </Text>
+    </div>
+  );
+}
+
+export function AiInstructionsCard({ isOpen }: AiInstructionsCardProps) {
+  const [saved, setSaved] = useState<AiInstructions | null>(null);
+  const [globalDraft, setGlobalDraft] = useState('');
+  const [projectDraft, setProjectDraft] = useState('');
+  const [isSaving, setIsSaving] = useState(false);
+
+  const load = useCallback(async () => {
+    try {
+      const instructions = await getAiInstructions();
+      setSaved(instructions);
+      setGlobalDraft(instructions.global);
+      setProjectDraft(instructions.project ?? '');
+    } catch (error) {
+      notifyError({ operation: 'load-ai-instructions', error });
+    }
+  }, []);
+
+  useEffect(() => {
+    if (isOpen) void load();
+  }, [isOpen, load]);
+
+  if (!saved) return null;
+
+  const hasProject = saved.project !== null;
+  const isDirty =
+    globalDraft !== saved.global || (hasProject && projectDraft !== (saved.project ?? ''));
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    try {
+      if (globalDraft !== saved.global) {
+        await setGlobalAiInstructions(globalDraft);
+      }
+      if (hasProject && projectDraft !== saved.project) {
+        await setProjectAiInstructions(projectDraft);
+      }
+      await load();
+      notifySuccess('AI instructions saved');
+    } catch (error) {
+      notifyError({ operation: 'save-ai-instructions', error });
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <SettingsCard>
+      <SettingsCardHeader
+        title="Custom Instructions"
+        description="Added to the assistant's system prompt for every request. Project instructions apply on top of your own."
+      />
+      <SettingsCardSection className="flex flex-col" style={{ gap: 'var(--space-field-gap)' }}>
+        <InstructionsField
+          id="ai-global-instructions"
+          label="Your instructions"
+          hint="Apply to every project."
+          value={globalDraft}
+          placeholder="e.g. Use metric units and name modules in snake_case."
+          onChange={setGlobalDraft}
+        />
+        {hasProject ? (
+          <InstructionsField
+            id="ai-project-instructions"
+            label="Project instructions"
+            hint={`Saved to ${saved.projectPath}, so they can be shared with the project.`}
+            value={projectDraft}
+            placeholder="e.g. All parts print on a 220mm bed with 0.2mm clearance."
+            onChange={setProjectDraft}
+          />
+        ) : (
+          <Text variant="caption" color="tertiary">
+            Open a project folder to add instructions for that project.
+          </Text>
+        )}
+        <div className="flex justify-end">
+          <Button
+            variant="primary"
+            onClick={() => void handleSave()}
+            disabled={!isDirty || isSaving}
+          >
+            {isSaving ? 'Saving…' : 'Save Instructions'}
+          </Button>
+        </div>
+      </SettingsCardSection>
+    </SettingsCard>
+  );
+}
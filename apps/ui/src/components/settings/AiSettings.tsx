@@ -4,20 +4,27 @@ import { useAnalytics } from '../../analytics/runtime';
 import {
   DEFAULT_OPENAI_COMPATIBLE_BASE_URL,
   clearStoredModelSelectionForProvider,
+  clearAzureOpenAiConfig,
   clearOpenAiCompatibleConfig,
   storeApiKey as storeApiKeyToStorage,
   clearApiKey as clearApiKeyFromStorage,
   getApiKey,
+  getAzureOpenAiConfig,
   getOpenAiCompatibleConfig,
   hasApiKeyForProvider,
+  hasAzureOpenAiConfig,
   hasOpenAiCompatibleConfig,
+  normalizeAzureOpenAiEndpoint,
   normalizeOpenAiCompatibleBaseUrl,
+  parseDeployments,
+  storeAzureOpenAiConfig,
   storeOpenAiCompatibleConfig,
   getAvailableProviders as getAvailableProvidersFromStore,
   type AiProvider,
 } from '../../stores/apiKeyStore';
 import { useSettings } from '../../stores/settingsStore';
 import { getPlatform } from '../../platform';
+import { AI_PROVIDER_LABELS } from '../../utils/aiModels';
 import { notifyError, notifySuccess } from '../../utils/notifications';
 import {
   SettingsCard,
@@ -26,7 +33,11 @@ import {
   SettingsSupportBlock,
 } from './SettingsPrimitives';
 import { ApiProviderCard } from './ApiProviderCard';
+import { AiInstructionsCard } from './AiInstructionsCard';
+import { CloudProvidersCard } from './CloudProvidersCard';
 import { ExternalAgentsCard } from './ExternalAgentsCard';
+import { KeyStorageCard } from './KeyStorageCard';
+import { ToolTimeoutsCard } from './ToolTimeoutsCard';
 
 const MASKED_KEY = '••••••••••••••••••••••••••••••••••••••••••••';
 
@@ -47,6 +58,13 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
     const [apiKey, setApiKey] = useState('');
     const [hasAnthropicKey, setHasAnthropicKey] = useState(false);
     const [hasOpenAIKey, setHasOpenAIKey] = useState(false);
+    const [hasGeminiKey, setHasGeminiKey] = useState(false);
+    const [hasOpenRouterKey, setHasOpenRouterKey] = useState(false);
+    const [hasAzureProvider, setHasAzureProvider] = useState(false);
+    const [azureEndpoint, setAzureEndpoint] = useState(() => getAzureOpenAiConfig().endpoint);
+    const [azureDeployments, setAzureDeployments] = useState(() =>
+      getAzureOpenAiConfig().deployments.join(', ')
+    );
     const [hasOpenAiCompatibleProvider, setHasOpenAiCompatibleProvider] = useState(false);
     const [customBaseUrl, setCustomBaseUrl] = useState(
       () => getOpenAiCompatibleConfig().baseUrl || DEFAULT_OPENAI_COMPATIBLE_BASE_URL
@@ -63,8 +81,15 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
       const availableProviders = getAvailableProvidersFromStore();
       setHasAnthropicKey(availableProviders.includes('anthropic'));
       setHasOpenAIKey(availableProviders.includes('openai'));
+      setHasGeminiKey(availableProviders.includes('gemini'));
+      setHasOpenRouterKey(availableProviders.includes('openrouter'));
+      setHasAzureProvider(hasAzureOpenAiConfig());
       setHasOpenAiCompatibleProvider(hasOpenAiCompatibleConfig());
 
+      const azureConfig = getAzureOpenAiConfig();
+      setAzureEndpoint(azureConfig.endpoint);
+      setAzureDeployments(azureConfig.deployments.join(', '));
+
       const customConfig = getOpenAiCompatibleConfig();
       setCustomBaseUrl(customConfig.baseUrl || DEFAULT_OPENAI_COMPATIBLE_BASE_URL);
 
@@ -86,8 +111,25 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
         onCanSaveChange(!isLoading && !!normalizeOpenAiCompatibleBaseUrl(customBaseUrl));
         return;
       }
+      if (provider === 'azure-openai') {
+        onCanSaveChange(
+          !isLoading &&
+            !!normalizeAzureOpenAiEndpoint(azureEndpoint) &&
+            parseDeployments(azureDeployments).length > 0 &&
+            !!apiKey.trim()
+        );
+        return;
+      }
       onCanSaveChange(!isLoading && !!apiKey.trim() && !apiKey.startsWith('•'));
-    }, [apiKey, customBaseUrl, isLoading, onCanSaveChange, provider]);
+    }, [
+      apiKey,
+      azureDeployments,
+      azureEndpoint,
+      customBaseUrl,
+      isLoading,
+      onCanSaveChange,
+      provider,
+    ]);
 
     const handleSave = useCallback(() => {
       if (provider === 'openai-compatible') {
@@ -131,6 +173,42 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
         return;
       }
 
+      if (provider === 'azure-openai') {
+        const endpoint = normalizeAzureOpenAiEndpoint(azureEndpoint);
+        const deployments = parseDeployments(azureDeployments);
+        const keyToStore = apiKey.startsWith('•') ? getApiKey('azure-openai') : apiKey.trim();
+        if (!endpoint || deployments.length === 0 || !keyToStore) {
+          setError('Enter an endpoint, at least one deployment, and an API key for Azure OpenAI');
+          return;
+        }
+
+        setError(null);
+
+        try {
+          const previousConfig = getAzureOpenAiConfig();
+          storeAzureOpenAiConfig({ endpoint, deployments, apiKey: keyToStore });
+          if (previousConfig.endpoint !== endpoint) {
+            clearStoredModelSelectionForProvider('azure-openai');
+          }
+          analytics.track('api key saved', { provider });
+          notifySuccess('Azure OpenAI provider saved', { toastId: 'save-api-key-azure-openai' });
+          setHasAzureProvider(true);
+          setAzureEndpoint(endpoint);
+          setAzureDeployments(deployments.join(', '));
+          setApiKey(MASKED_KEY);
+          setShowKey(false);
+        } catch (err) {
+          notifyError({
+            operation: 'save-azure-openai-provider',
+            error: err,
+            fallbackMessage: 'Failed to save Azure OpenAI provider',
+            toastId: 'save-api-key-error-azure-openai',
+            logLabel: '[AiSettings] Failed to save Azure OpenAI provider',
+          });
+        }
+        return;
+      }
+
       if (!apiKey.trim() || apiKey.startsWith('•')) {
         setError('Please enter a valid API key');
         return;
@@ -141,12 +219,16 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
       try {
         storeApiKeyToStorage(provider, apiKey);
         analytics.track('api key saved', { provider });
-        notifySuccess(`${provider === 'anthropic' ? 'Anthropic' : 'OpenAI'} API key saved`, {
+        notifySuccess(`${AI_PROVIDER_LABELS[provider]} API key saved`, {
           toastId: `save-api-key-${provider}`,
         });
 
         if (provider === 'anthropic') {
           setHasAnthropicKey(true);
+        } else if (provider === 'gemini') {
+          setHasGeminiKey(true);
+        } else if (provider === 'openrouter') {
+          setHasOpenRouterKey(true);
         } else {
           setHasOpenAIKey(true);
         }
@@ -162,19 +244,13 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
           logLabel: '[AiSettings] Failed to save API key',
         });
       }
-    }, [apiKey, customBaseUrl, provider, analytics]);
+    }, [apiKey, azureDeployments, azureEndpoint, customBaseUrl, provider, analytics]);
 
     useImperativeHandle(ref, () => ({ save: handleSave }), [handleSave]);
 
     const handleClear = async (targetProvider: AiProvider) => {
-      const providerLabel =
-        targetProvider === 'anthropic'
-          ? 'Anthropic'
-          : targetProvider === 'openai'
-            ? 'OpenAI'
-            : 'OpenAI-compatible';
       const confirmed = await getPlatform().confirm(
-        `Are you sure you want to remove your ${providerLabel} AI settings?`,
+        `Are you sure you want to remove your ${AI_PROVIDER_LABELS[targetProvider]} AI settings?`,
         { title: 'Remove AI Settings', kind: 'warning', okLabel: 'Remove', cancelLabel: 'Cancel' }
       );
       if (!confirmed) return;
@@ -184,6 +260,8 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
       try {
         if (targetProvider === 'openai-compatible') {
           clearOpenAiCompatibleConfig();
+        } else if (targetProvider === 'azure-openai') {
+          clearAzureOpenAiConfig();
         } else {
           clearApiKeyFromStorage(targetProvider);
         }
@@ -194,6 +272,14 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
           setHasAnthropicKey(false);
         } else if (targetProvider === 'openai') {
           setHasOpenAIKey(false);
+        } else if (targetProvider === 'gemini') {
+          setHasGeminiKey(false);
+        } else if (targetProvider === 'openrouter') {
+          setHasOpenRouterKey(false);
+        } else if (targetProvider === 'azure-openai') {
+          setHasAzureProvider(false);
+          setAzureEndpoint('');
+          setAzureDeployments('');
         } else {
           setHasOpenAiCompatibleProvider(false);
           setCustomBaseUrl(DEFAULT_OPENAI_COMPATIBLE_BASE_URL);
@@ -261,8 +347,8 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
     return (
       <div className="flex flex-col ph-no-capture" style={{ gap: 'var(--space-section-gap)' }}>
         <Text variant="body" color="secondary">
-          Connect hosted API keys or a local OpenAI-compatible server, then choose the model from
-          the chat composer.
+          Connect hosted API keys, Azure OpenAI, Google Gemini, OpenRouter, or a local
+          OpenAI-compatible server, then choose the model from the chat composer.
         </Text>
 
         <ApiProviderCard
@@ -328,6 +414,185 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
           }}
         />
 
+        <ApiProviderCard
+          title="Google Gemini API Key"
+          description="Required for Gemini models."
+          placeholder="AIza..."
+          keyLink={{
+            label: 'Get one from Google AI Studio',
+            href: 'https://aistudio.google.com/apikey',
+          }}
+          isActive={provider === 'gemini'}
+          hasKey={hasGeminiKey}
+          apiKey={apiKey}
+          showKey={showKey}
+          isLoading={isLoading}
+          onFocus={() => {
+            if (provider !== 'gemini') {
+              setProvider('gemini');
+              setApiKey('');
+              setShowKey(false);
+            } else {
+              setProvider('gemini');
+            }
+          }}
+          onChange={(value) => {
+            setProvider('gemini');
+            setApiKey(value);
+          }}
+          onToggleShow={() => setShowKey((prev) => !prev)}
+          onClear={() => {
+            setProvider('gemini');
+            handleClear('gemini');
+          }}
+        />
+
+        <ApiProviderCard
+          title="OpenRouter API Key"
+          description="One key for models from many providers."
+          placeholder="sk-or-..."
+          keyLink={{ label: 'Get one from OpenRouter', href: 'https://openrouter.ai/keys' }}
+          isActive={provider === 'openrouter'}
+          hasKey={hasOpenRouterKey}
+          apiKey={apiKey}
+          showKey={showKey}
+          isLoading={isLoading}
+          onFocus={() => {
+            if (provider !== 'openrouter') {
+              setProvider('openrouter');
+              setApiKey('');
+              setShowKey(false);
+            } else {
+              setProvider('openrouter');
+            }
+          }}
+          onChange={(value) => {
+            setProvider('openrouter');
+            setApiKey(value);
+          }}
+          onToggleShow={() => setShowKey((prev) => !prev)}
+          onClear={() => {
+            setProvider('openrouter');
+            handleClear('openrouter');
+          }}
+        />
+
+        <SettingsCard className="ph-no-capture">
+          <SettingsCardHeader
+            title="Azure OpenAI"
+            description="For models deployed to your own Azure OpenAI resource."
+            action={
+              <span
+                className="text-xs px-2 py-0.5 rounded-full font-medium"
+                style={{
+                  backgroundColor: hasAzureProvider
+                    ? 'rgba(133, 153, 0, 0.15)'
+                    : 'rgba(128, 128, 128, 0.1)',
+                  color: hasAzureProvider ? 'var(--color-success)' : 'var(--text-tertiary)',
+                }}
+              >
+                {hasAzureProvider ? 'Configured' : 'Not configured'}
+              </span>
+            }
+          />
+          <SettingsCardSection className="flex flex-col" style={{ gap: 'var(--space-field-gap)' }}>
+            <label className="flex flex-col" style={{ gap: 'var(--space-helper-gap)' }}>
+              <Text variant="caption" color="secondary">
+                Endpoint
+              </Text>
+              <Input
+                value={azureEndpoint}
+                onFocus={() => {
+                  setProvider('azure-openai');
+                  setApiKey(hasApiKeyForProvider('azure-openai') ? MASKED_KEY : '');
+                  setShowKey(false);
+                }}
+                onChange={(event) => {
+                  setProvider('azure-openai');
+                  setAzureEndpoint(event.target.value);
+                }}
+                placeholder="https://my-resource.openai.azure.com"
+                className="font-mono text-sm ph-no-capture"
+                disabled={isLoading}
+              />
+            </label>
+
+            <label className="flex flex-col" style={{ gap: 'var(--space-helper-gap)' }}>
+              <Text variant="caption" color="secondary">
+                Deployments
+              </Text>
+              <Input
+                value={azureDeployments}
+                onFocus={() => {
+                  setProvider('azure-openai');
+                  setApiKey(hasApiKeyForProvider('azure-openai') ? MASKED_KEY : '');
+                }}
+                onChange={(event) => {
+                  setProvider('azure-openai');
+                  setAzureDeployments(event.target.value);
+                }}
+                placeholder="gpt-4o, gpt-4.1-mini"
+                className="font-mono text-sm ph-no-capture"
+                disabled={isLoading}
+              />
+            </label>
+
+            <label className="flex flex-col" style={{ gap: 'var(--space-helper-gap)' }}>
+              <Text variant="caption" color="secondary">
+                API key
+              </Text>
+              <div className="relative">
+                <Input
+                  type={showKey && provider === 'azure-openai' ? 'text' : 'password'}
+                  value={provider === 'azure-openai' ? apiKey : ''}
+                  onFocus={() => {
+                    setProvider('azure-openai');
+                    setApiKey(hasApiKeyForProvider('azure-openai') ? MASKED_KEY : '');
+                  }}
+                  onChange={(event) => {
+                    setProvider('azure-openai');
+                    setApiKey(event.target.value);
+                  }}
+                  placeholder="Key 1 or Key 2 from the resource's Keys and Endpoint page"
+                  className="pr-20 font-mono text-sm ph-no-capture"
+                  disabled={isLoading}
+                />
+                {provider === 'azure-openai' && apiKey && !apiKey.startsWith('•') ? (
+                  // eslint-disable-next-line no-restricted-syntax -- absolute-positioned inline toggle overlay on a password input; matches API key cards above
+                  <button
+                    type="button"
+                    onClick={() => setShowKey((prev) => !prev)}
+                    className="absolute right-2 top-1/2 -translate-y-1/2 text-xs px-2 py-1 rounded-lg transition-colors"
+                    style={{ color: 'var(--text-secondary)' }}
+                  >
+                    {showKey ? 'Hide' : 'Show'}
+                  </button>
+                ) : null}
+              </div>
+            </label>
+
+            <Text variant="caption" color="tertiary">
+              Separate deployment names with commas. Deployments found on the resource are listed
+              alongside them in the model picker.
+            </Text>
+
+            <div className="flex justify-end">
+              <Button
+                type="button"
+                size="sm"
+                variant="ghost"
+                onClick={() => {
+                  setProvider('azure-openai');
+                  void handleClear('azure-openai');
+                }}
+                disabled={isLoading || !hasAzureProvider}
+              >
+                Clear
+              </Button>
+            </div>
+          </SettingsCardSection>
+        </SettingsCard>
+
         <SettingsCard className="ph-no-capture">
           <SettingsCardHeader
             title="OpenAI-compatible Provider"
@@ -461,7 +726,13 @@ export const AiSettings = forwardRef<AiSettingsHandle, AiSettingsProps>(
         )}
 
         {getPlatform().capabilities.hasFileSystem ? (
-          <ExternalAgentsCard settings={settings} isOpen={isOpen} />
+          <>
+            <CloudProvidersCard isOpen={isOpen} />
+            <KeyStorageCard isOpen={isOpen} />
+            <AiInstructionsCard isOpen={isOpen} />
+            <ToolTimeoutsCard isOpen={isOpen} />
+            <ExternalAgentsCard settings={settings} isOpen={isOpen} />
+          </>
         ) : null}
       </div>
     );
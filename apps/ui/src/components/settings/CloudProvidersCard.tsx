@@ -0,0 +1,149 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button, Input, Text } from '../ui';
+import {
+  getAiProviderSettings,
+  setAiProviderSettings,
+  type AiProviderSettings,
+  type CloudAiProvider,
+  type CloudProviderStatus,
+} from '../../services/cloudAi';
+import { loadCloudProviders } from '../../stores/apiKeyStore';
+import { notifyError, notifySuccess } from '../../utils/notifications';
+import {
+  SettingsCard,
+  SettingsCardHeader,
+  SettingsCardSection,
+  SettingsControlRow,
+  SettingsSupportBlock,
+} from './SettingsPrimitives';
+
+type CloudField = 'bedrockRegion' | 'awsProfile' | 'vertexProject' | 'vertexRegion';
+
+const CLOUD_FIELDS: { field: CloudField; label: string; placeholder: string }[] = [
+  { field: 'bedrockRegion', label: 'Bedrock region', placeholder: 'AWS_REGION, e.g. us-east-1' },
+  { field: 'awsProfile', label: 'AWS profile', placeholder: 'AWS_PROFILE or default' },
+  { field: 'vertexProject', label: 'Vertex AI project', placeholder: 'GOOGLE_CLOUD_PROJECT' },
+  {
+    field: 'vertexRegion',
+    label: 'Vertex AI region',
+    placeholder: 'GOOGLE_CLOUD_LOCATION or us-east5',
+  },
+];
+
+interface CloudProvidersCardProps {
+  isOpen: boolean;
+}
+
+function StatusRow({ label, status }: { label: string; status?: CloudProviderStatus }) {
+  const available = status?.error === null;
+  return (
+    <SettingsControlRow
+      divided
+      label={label}
+      description={available ? 'Credentials found' : (status?.error ?? 'Checking…')}
+      control={
+        <span
+          className="text-xs px-2 py-0.5 rounded-full font-medium"
+          style={{
+            backgroundColor: available ? 'rgba(133, 153, 0, 0.15)' : 'rgba(128, 128, 128, 0.1)',
+            color: available ? 'var(--color-success)' : 'var(--text-tertiary)',
+          }}
+        >
+          {available ? 'Available' : 'Not available'}
+        </span>
+      }
+    />
+  );
+}
+
+export function CloudProvidersCard({ isOpen }: CloudProvidersCardProps) {
+  const [settings, setSettings] = useState<AiProviderSettings | null>(null);
+  const [statuses, setStatuses] = useState<CloudProviderStatus[]>([]);
+  const [isSaving, setIsSaving] = useState(false);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    getAiProviderSettings()
+      .then(setSettings)
+      .catch((error) => {
+        notifyError({ operation: 'load-ai-provider-settings', error });
+      });
+    loadCloudProviders()
+      .then(setStatuses)
+      .catch((error) => {
+        notifyError({ operation: 'check-cloud-ai-credentials', error });
+      });
+  }, [isOpen]);
+
+  const handleSave = useCallback(async () => {
+    if (!settings) return;
+    setIsSaving(true);
+    try {
+      setSettings(await setAiProviderSettings(settings));
+      setStatuses(await loadCloudProviders());
+      notifySuccess('Cloud provider settings saved', { toastId: 'cloud-ai-settings' });
+    } catch (error) {
+      notifyError({
+        operation: 'save-ai-provider-settings',
+        error,
+        fallbackMessage: 'Failed to save cloud provider settings',
+        toastId: 'cloud-ai-settings-error',
+      });
+    } finally {
+      setIsSaving(false);
+    }
+  }, [settings]);
+
+  const statusFor = (provider: CloudAiProvider) =>
+    statuses.find((status) => status.provider === provider);
+
+  return (
+    <SettingsCard className="ph-no-capture">
+      <SettingsCardHeader
+        title="Claude on Amazon Bedrock and Google Vertex AI"
+        description="Uses AWS credentials from the environment or ~/.aws/credentials, and Google credentials from GOOGLE_OAUTH_ACCESS_TOKEN or the gcloud CLI. Leave fields blank to use the environment."
+      />
+      <SettingsCardSection className="flex flex-col" style={{ gap: 'var(--space-field-gap)' }}>
+        <SettingsSupportBlock>
+          <Text variant="caption" color="secondary">
+            Bedrock only reads static access keys (AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY, or
+            aws_access_key_id in ~/.aws/credentials). SSO, assume-role and credential_process
+            profiles aren&apos;t supported; export temporary keys, e.g. with{' '}
+            <code className="font-mono">aws configure export-credentials --format env</code>, before
+            starting the app.
+          </Text>
+        </SettingsSupportBlock>
+        {CLOUD_FIELDS.map(({ field, label, placeholder }) => (
+          <label key={field} className="flex flex-col" style={{ gap: 'var(--space-helper-gap)' }}>
+            <Text variant="caption" color="secondary">
+              {label}
+            </Text>
+            <Input
+              value={settings?.[field] ?? ''}
+              onChange={(event) =>
+                setSettings((current) =>
+                  current ? { ...current, [field]: event.target.value || null } : current
+                )
+              }
+              placeholder={placeholder}
+              className="font-mono text-sm"
+              disabled={!settings || isSaving}
+            />
+          </label>
+        ))}
+        <div className="flex justify-end">
+          <Button
+            size="sm"
+            variant="secondary"
+            onClick={() => void handleSave()}
+            disabled={!settings || isSaving}
+          >
+            {isSaving ? 'Checking…' : 'Save and Check'}
+          </Button>
+        </div>
+      </SettingsCardSection>
+      <StatusRow label="Amazon Bedrock" status={statusFor('bedrock')} />
+      <StatusRow label="Google Vertex AI" status={statusFor('vertex')} />
+    </SettingsCard>
+  );
+}
@@ -40,6 +40,8 @@ export function EditorSettings({
   onLocalVimConfigChange,
 }: EditorSettingsProps) {
   const vimEditorRef = useRef<Monaco.editor.IStandaloneCodeEditor | null>(null);
+  // The desktop backend schedules auto-renders with its own delay (RenderSchedulerCard)
+  const isDesktop = '__TAURI_INTERNALS__' in window;
 
   return (
     <div className="flex flex-col" style={{ gap: 'var(--space-section-gap)' }}>
@@ -102,7 +104,7 @@ export function EditorSettings({
           }
         />
 
-        {settings.editor.autoRenderOnIdle && (
+        {settings.editor.autoRenderOnIdle && !isDesktop && (
           <SettingsCardSection
             divided
             className="flex flex-col"
@@ -0,0 +1,139 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button, Input, Toggle } from '../ui';
+import {
+  getHistorySettings,
+  setHistorySettings,
+  type HistorySettings,
+} from '../../services/documentHistory';
+import { notifyError } from '../../utils/notifications';
+import { SettingsCard, SettingsCardHeader, SettingsControlRow } from './SettingsPrimitives';
+
+const BYTES_PER_MB = 1024 * 1024;
+
+interface HistorySettingsCardProps {
+  isOpen: boolean;
+}
+
+export function HistorySettingsCard({ isOpen }: HistorySettingsCardProps) {
+  const [settings, setSettings] = useState<HistorySettings | null>(null);
+  const [maxAgeDraft, setMaxAgeDraft] = useState('');
+  const [maxSizeDraft, setMaxSizeDraft] = useState('');
+  const [isSaving, setIsSaving] = useState(false);
+
+  const show = (loaded: HistorySettings) => {
+    setSettings(loaded);
+    setMaxAgeDraft(String(loaded.maxAgeDays));
+    setMaxSizeDraft(String(Math.round(loaded.maxBytes / BYTES_PER_MB)));
+  };
+
+  useEffect(() => {
+    if (!isOpen) return;
+    getHistorySettings()
+      .then(show)
+      .catch((error) => {
+        notifyError({ operation: 'load-history-settings', error });
+      });
+  }, [isOpen]);
+
+  const save = useCallback(async (next: HistorySettings) => {
+    setIsSaving(true);
+    try {
+      await setHistorySettings(next);
+      show(next);
+    } catch (error) {
+      notifyError({
+        operation: 'set-history-settings',
+        error,
+        fallbackMessage: 'Failed to save the history settings',
+        toastId: 'history-settings-error',
+      });
+    } finally {
+      setIsSaving(false);
+    }
+  }, []);
+
+  const handleApplyLimits = () => {
+    if (!settings) return;
+    const maxAgeDays = Number(maxAgeDraft.trim());
+    const maxMb = Number(maxSizeDraft.trim());
+    if (!Number.isInteger(maxAgeDays) || maxAgeDays < 0 || !Number.isInteger(maxMb) || maxMb < 1) {
+      notifyError({
+        operation: 'set-history-settings',
+        displayMessage: 'Enter whole numbers: days of 0 or more, and at least 1 MB',
+        toastId: 'history-settings-error',
+      });
+      return;
+    }
+    void save({ ...settings, maxAgeDays, maxBytes: maxMb * BYTES_PER_MB });
+  };
+
+  if (!settings) return null;
+
+  return (
+    <SettingsCard>
+      <SettingsCardHeader
+        title="Checkpoint History"
+        description="Checkpoints let you undo, redo and restore earlier versions of each file."
+      />
+      <SettingsControlRow
+        label="Keep history between sessions"
+        description="Saves each project folder's checkpoints in the app data folder."
+        control={
+          <Toggle
+            checked={settings.persist}
+            onChange={(persist) => void save({ ...settings, persist })}
+            disabled={isSaving}
+          />
+        }
+      />
+      <SettingsControlRow
+        divided
+        label="Saved history limits"
+        htmlFor="history-max-age"
+        description="Checkpoints older than this many days (0 keeps them all) or beyond this size per project are dropped."
+        control={
+          <div className="flex items-center" style={{ gap: 'var(--space-control-gap)' }}>
+            <Input
+              id="history-max-age"
+              type="number"
+              aria-label="Days"
+              value={maxAgeDraft}
+              onChange={(event) => setMaxAgeDraft(event.target.value)}
+              className="w-20 font-mono"
+              min={0}
+            />
+            <Input
+              type="number"
+              aria-label="Megabytes"
+              value={maxSizeDraft}
+              onChange={(event) => setMaxSizeDraft(event.target.value)}
+              className="w-20 font-mono"
+              min={1}
+            />
+            <Button
+              type="button"
+              size="sm"
+              variant="ghost"
+              onClick={handleApplyLimits}
+              disabled={isSaving}
+            >
+              Apply
+            </Button>
+          </div>
+        }
+      />
+      <SettingsControlRow
+        divided
+        label="Write restores to disk"
+        description="Undo, redo and checkpoint restores also overwrite the file, keeping its previous contents in a .bak file next to it."
+        control={
+          <Toggle
+            checked={settings.writeToDisk}
+            onChange={(writeToDisk) => void save({ ...settings, writeToDisk })}
+            disabled={isSaving}
+          />
+        }
+      />
+    </SettingsCard>
+  );
+}
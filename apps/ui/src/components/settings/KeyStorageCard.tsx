@@ -0,0 +1,71 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Toggle } from '../ui';
+import {
+  getKeyStorageBackend,
+  setKeyStorageBackend,
+  type KeyStorageBackend,
+} from '../../services/keyStorage';
+import { notifyError, notifySuccess } from '../../utils/notifications';
+import { SettingsCard, SettingsCardHeader, SettingsControlRow } from './SettingsPrimitives';
+
+interface KeyStorageCardProps {
+  isOpen: boolean;
+}
+
+export function KeyStorageCard({ isOpen }: KeyStorageCardProps) {
+  const [backend, setBackend] = useState<KeyStorageBackend>('file');
+  const [isSwitching, setIsSwitching] = useState(false);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    getKeyStorageBackend()
+      .then(setBackend)
+      .catch((error) => {
+        notifyError({ operation: 'load-key-storage-backend', error });
+      });
+  }, [isOpen]);
+
+  const handleChange = useCallback(async (useKeychain: boolean) => {
+    const next: KeyStorageBackend = useKeychain ? 'keychain' : 'file';
+    setIsSwitching(true);
+    try {
+      await setKeyStorageBackend(next);
+      setBackend(next);
+      notifySuccess(
+        useKeychain
+          ? 'API keys moved to the system keychain'
+          : 'API keys moved to the secrets file',
+        { toastId: 'key-storage-backend' }
+      );
+    } catch (error) {
+      notifyError({
+        operation: 'set-key-storage-backend',
+        error,
+        fallbackMessage: 'Failed to move API keys',
+        toastId: 'key-storage-backend-error',
+      });
+    } finally {
+      setIsSwitching(false);
+    }
+  }, []);
+
+  return (
+    <SettingsCard className="ph-no-capture">
+      <SettingsCardHeader
+        title="Key Storage"
+        description="API keys are kept in a plaintext file in the app data folder unless you store them in the system keychain."
+      />
+      <SettingsControlRow
+        label="Store API keys in the system keychain"
+        description="Uses macOS Keychain, Windows Credential Manager, or the Secret Service on Linux. Existing keys move across when you switch."
+        control={
+          <Toggle
+            checked={backend === 'keychain'}
+            onChange={(checked) => void handleChange(checked)}
+            disabled={isSwitching}
+          />
+        }
+      />
+    </SettingsCard>
+  );
+}
@@ -0,0 +1,162 @@
+import { useCallback, useEffect, useState } from 'react';
+import { TbDownload } from 'react-icons/tb';
+import { Button, Input, Text } from '../ui';
+import { getPlatform } from '../../platform';
+import {
+  installLibrary,
+  listLibraries,
+  pinLibrary,
+  type AvailableLibrary,
+  type LibraryOverview,
+} from '../../services/libraries';
+import { notifyError, notifySuccess } from '../../utils/notifications';
+import {
+  SettingsCard,
+  SettingsCardHeader,
+  SettingsCardSection,
+  SettingsSupportBlock,
+} from './SettingsPrimitives';
+
+interface ManagedLibrariesCardProps {
+  isOpen: boolean;
+}
+
+export function ManagedLibrariesCard({ isOpen }: ManagedLibrariesCardProps) {
+  const [overview, setOverview] = useState<LibraryOverview | null>(null);
+  const [pinDrafts, setPinDrafts] = useState<Record<string, string>>({});
+  const [installing, setInstalling] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      const loaded = await listLibraries();
+      setOverview(loaded);
+      setPinDrafts(
+        Object.fromEntries(
+          loaded.available.map((library) => [
+            library.name,
+            loaded.installed.find((i) => i.name === library.name)?.pinnedVersion ?? '',
+          ])
+        )
+      );
+    } catch (error) {
+      notifyError({ operation: 'list-libraries', error });
+    }
+  }, []);
+
+  useEffect(() => {
+    if (isOpen) void load();
+  }, [isOpen, load]);
+
+  const handleInstall = async (library: AvailableLibrary) => {
+    const existing = overview?.installed.find((i) => i.name === library.name);
+    let replaceUnmanaged = false;
+    if (existing && !existing.managed) {
+      replaceUnmanaged = await getPlatform().confirm(
+        `${existing.path} was not installed by OpenSCAD Studio. Replace it with ${library.name} from GitHub? Its current contents will be deleted.`,
+        {
+          title: `Replace ${library.name}`,
+          kind: 'warning',
+          okLabel: 'Replace',
+          cancelLabel: 'Cancel',
+        }
+      );
+      if (!replaceUnmanaged) return;
+    }
+
+    setInstalling(library.name);
+    try {
+      const installed = await installLibrary(library.name, { replaceUnmanaged });
+      notifySuccess(`Installed ${installed.name} ${installed.version ?? ''}`.trim());
+      await load();
+    } catch (error) {
+      notifyError({
+        operation: 'install-library',
+        error,
+        fallbackMessage: `Failed to install ${library.name}`,
+        toastId: 'install-library-error',
+      });
+    } finally {
+      setInstalling(null);
+    }
+  };
+
+  const handlePin = async (name: string) => {
+    const version = pinDrafts[name]?.trim() || null;
+    const current = overview?.installed.find((i) => i.name === name)?.pinnedVersion ?? null;
+    if (version === current) return;
+    try {
+      await pinLibrary(name, version);
+      await load();
+    } catch (error) {
+      notifyError({
+        operation: 'pin-library',
+        error,
+        fallbackMessage: `Failed to pin ${name}`,
+        toastId: 'pin-library-error',
+      });
+    }
+  };
+
+  if (!overview) return null;
+
+  return (
+    <SettingsCard>
+      <SettingsCardHeader
+        title="Managed Libraries"
+        description={`Install libraries from GitHub into ${overview.libraryDir}, where OpenSCAD finds them. Pin a release tag to keep installs and updates on it.`}
+      />
+      <SettingsCardSection className="flex flex-col" style={{ gap: 'var(--space-control-gap)' }}>
+        {overview.available.map((library) => {
+          const installed = overview.installed.find((i) => i.name === library.name);
+          const status = installed?.managed
+            ? (installed.version ?? 'installed')
+            : installed && 'installed by hand';
+          return (
+            <SettingsSupportBlock
+              key={library.name}
+              className="flex items-center justify-between"
+              style={{ gap: 'var(--space-control-gap)', backgroundColor: 'var(--bg-primary)' }}
+            >
+              <div className="flex flex-col min-w-0">
+                <span className="text-sm" style={{ color: 'var(--text-primary)' }}>
+                  {library.name}
+                  {status && (
+                    <Text as="span" variant="caption" color="tertiary">
+                      {` · ${status}`}
+                    </Text>
+                  )}
+                </span>
+                <span className="text-xs truncate" style={{ color: 'var(--text-tertiary)' }}>
+                  {library.description}
+                </span>
+              </div>
+              <div className="flex items-center shrink-0" style={{ gap: 'var(--space-1)' }}>
+                <Input
+                  aria-label={`${library.name} pinned version`}
+                  placeholder="latest"
+                  value={pinDrafts[library.name] ?? ''}
+                  onChange={(event) =>
+                    setPinDrafts((drafts) => ({ ...drafts, [library.name]: event.target.value }))
+                  }
+                  onBlur={() => void handlePin(library.name)}
+                  className="w-24 font-mono"
+                />
+                <Button
+                  size="sm"
+                  variant="ghost"
+                  onClick={() => void handleInstall(library)}
+                  disabled={installing !== null}
+                  className="flex items-center"
+                  style={{ gap: 'var(--space-1)' }}
+                >
+                  <TbDownload size={14} />
+                  {installing === library.name ? 'Installing…' : installed ? 'Update' : 'Install'}
+                </Button>
+              </div>
+            </SettingsSupportBlock>
+          );
+        })}
+      </SettingsCardSection>
+    </SettingsCard>
+  );
+}
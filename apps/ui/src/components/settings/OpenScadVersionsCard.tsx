@@ -0,0 +1,205 @@
+import { useCallback, useEffect, useState } from 'react';
+import { TbDownload, TbTrash } from 'react-icons/tb';
+import { Button, IconButton, Text } from '../ui';
+import {
+  installOpenScad,
+  listOpenScadVersions,
+  removeOpenScadVersion,
+  setActiveOpenScad,
+  type OpenScadBuild,
+  type OpenScadBuilds,
+  type OpenScadChannel,
+  type OpenScadInstallProgress,
+} from '../../services/openscadInstaller';
+import { notifyError, notifySuccess } from '../../utils/notifications';
+import {
+  SettingsCard,
+  SettingsCardHeader,
+  SettingsCardSection,
+  SettingsSupportBlock,
+} from './SettingsPrimitives';
+
+interface OpenScadVersionsCardProps {
+  isOpen: boolean;
+}
+
+const SOURCE_LABELS: Record<OpenScadBuild['source'], string> = {
+  bundled: 'Bundled',
+  system: 'System',
+  downloaded: 'Downloaded',
+};
+
+function describeBuild(build: OpenScadBuild): string {
+  const label = build.channel
+    ? build.channel === 'stable'
+      ? 'Stable'
+      : 'Nightly'
+    : SOURCE_LABELS[build.source];
+  return `${label} · ${build.version}`;
+}
+
+function describeProgress({ receivedBytes, totalBytes }: OpenScadInstallProgress): string {
+  const receivedMb = (receivedBytes / (1024 * 1024)).toFixed(0);
+  if (!totalBytes) return `${receivedMb} MB`;
+  return `${Math.round((receivedBytes / totalBytes) * 100)}%`;
+}
+
+export function OpenScadVersionsCard({ isOpen }: OpenScadVersionsCardProps) {
+  const [overview, setOverview] = useState<OpenScadBuilds | null>(null);
+  const [installing, setInstalling] = useState<OpenScadChannel | null>(null);
+  const [progress, setProgress] = useState<OpenScadInstallProgress | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+
+  const load = useCallback(async () => {
+    try {
+      setOverview(await listOpenScadVersions());
+    } catch (error) {
+      notifyError({ operation: 'list-openscad-versions', error });
+    }
+  }, []);
+
+  useEffect(() => {
+    if (isOpen) void load();
+  }, [isOpen, load]);
+
+  const handleInstall = async (channel: OpenScadChannel) => {
+    setInstalling(channel);
+    setProgress(null);
+    try {
+      const build = await installOpenScad(channel, setProgress);
+      const version = await setActiveOpenScad(build.id);
+      notifySuccess(`Now rendering with ${version}`);
+      await load();
+    } catch (error) {
+      notifyError({ operation: 'install-openscad', error });
+    } finally {
+      setInstalling(null);
+      setProgress(null);
+    }
+  };
+
+  const handleActivate = async (id: string) => {
+    setIsBusy(true);
+    try {
+      const version = await setActiveOpenScad(id);
+      notifySuccess(`Now rendering with ${version}`);
+      await load();
+    } catch (error) {
+      notifyError({ operation: 'set-active-openscad', error });
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleRemove = async (id: string) => {
+    setIsBusy(true);
+    try {
+      await removeOpenScadVersion(id);
+      await load();
+    } catch (error) {
+      notifyError({ operation: 'remove-openscad-version', error });
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  if (!overview) return null;
+
+  const disabled = isBusy || installing !== null;
+
+  return (
+    <SettingsCard>
+      <SettingsCardHeader
+        title="OpenSCAD"
+        description="The OpenSCAD build used for renders and exports. Download the stable release or the latest nightly to render without installing OpenSCAD yourself."
+      />
+      <SettingsCardSection className="flex flex-col" style={{ gap: 'var(--space-control-gap)' }}>
+        {overview.builds.length === 0 ? (
+          <SettingsSupportBlock
+            className="text-sm italic"
+            style={{ color: 'var(--text-tertiary)' }}
+          >
+            No OpenSCAD found. Download a build below; until then previews render with the
+            slower built-in WebAssembly version.
+          </SettingsSupportBlock>
+        ) : (
+          overview.builds.map((build) => (
+            <SettingsSupportBlock
+              key={build.id}
+              className="flex items-center justify-between"
+              style={{ gap: 'var(--space-control-gap)', backgroundColor: 'var(--bg-primary)' }}
+            >
+              <div className="flex flex-col min-w-0">
+                <span className="text-sm" style={{ color: 'var(--text-primary)' }}>
+                  {describeBuild(build)}
+                </span>
+                <span
+                  className="font-mono text-xs truncate"
+                  style={{ color: 'var(--text-tertiary)' }}
+                  title={build.path}
+                >
+                  {build.path}
+                </span>
+              </div>
+              <div className="flex items-center shrink-0" style={{ gap: 'var(--space-1)' }}>
+                {build.active ? (
+                  <Text variant="caption" color="secondary">
+                    In use
+                  </Text>
+                ) : (
+                  <Button
+                    size="sm"
+                    variant="ghost"
+                    onClick={() => void handleActivate(build.id)}
+                    disabled={disabled}
+                  >
+                    Use
+                  </Button>
+                )}
+                {build.source === 'downloaded' && (
+                  <IconButton
+                    size="sm"
+                    onClick={() => void handleRemove(build.id)}
+                    disabled={disabled}
+                    style={{ color: 'var(--text-tertiary)' }}
+                    title="Remove build"
+                  >
+                    <TbTrash size={14} />
+                  </IconButton>
+                )}
+              </div>
+            </SettingsSupportBlock>
+          ))
+        )}
+      </SettingsCardSection>
+      {overview.canDownload && (
+        <SettingsCardSection
+          divided
+          className="flex items-center justify-end"
+          style={{ gap: 'var(--space-control-gap)' }}
+        >
+          {installing && (
+            <Text variant="caption" color="tertiary">
+              Downloading {installing}
+              {progress ? ` (${describeProgress(progress)})` : '…'}
+            </Text>
+          )}
+          {(['stable', 'nightly'] as const).map((channel) => (
+            <Button
+              key={channel}
+              size="sm"
+              variant="ghost"
+              onClick={() => void handleInstall(channel)}
+              disabled={disabled}
+              className="flex items-center"
+              style={{ gap: 'var(--space-1)' }}
+            >
+              <TbDownload size={14} />
+              {channel === 'stable' ? 'Download Stable' : 'Download Nightly'}
+            </Button>
+          ))}
+        </SettingsCardSection>
+      )}
+    </SettingsCard>
+  );
+}
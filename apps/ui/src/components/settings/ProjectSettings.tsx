@@ -11,6 +11,7 @@ import {
 } from '../ui';
 import type { Settings, MeasurementUnit } from '../../stores/settingsStore';
 import { SettingsCard, SettingsCardHeader, SettingsCardSection } from './SettingsPrimitives';
+import { SettingsProfilesCard } from './SettingsProfilesCard';
 import { getPlatform } from '../../platform';
 import { TbFolder } from 'react-icons/tb';
 
@@ -24,12 +25,15 @@ interface ProjectSettingsProps {
     key: K,
     value: Settings['project'][K]
   ) => void;
+  /** Called after a settings profile or import replaced the settings */
+  onSettingsReplaced?: (settings: Settings) => void;
 }
 
 export function ProjectSettings({
   settings,
   onViewerChange,
   onProjectChange,
+  onSettingsReplaced,
 }: ProjectSettingsProps) {
   const { capabilities } = getPlatform();
   const [resolvedDefault, setResolvedDefault] = useState<string | null>(null);
@@ -128,6 +132,10 @@ export function ProjectSettings({
           </Select>
         </SettingsCardSection>
       </SettingsCard>
+
+      {capabilities.hasFileSystem && onSettingsReplaced && (
+        <SettingsProfilesCard onSettingsReplaced={onSettingsReplaced} />
+      )}
     </div>
   );
 }
@@ -0,0 +1,112 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue, Toggle } from '../ui';
+import {
+  getRenderSchedulerSettings,
+  setRenderSchedulerSettings,
+  type RenderSchedulerSettings,
+} from '../../services/renderScheduler';
+import { notifyError } from '../../utils/notifications';
+import { SettingsCard, SettingsCardHeader, SettingsControlRow } from './SettingsPrimitives';
+
+const DELAY_OPTIONS: { value: number; label: string }[] = [
+  { value: 300, label: '300ms (default)' },
+  { value: 500, label: '500ms' },
+  { value: 1000, label: '1 second' },
+  { value: 2000, label: '2 seconds' },
+];
+
+interface RenderSchedulerCardProps {
+  isOpen: boolean;
+}
+
+export function RenderSchedulerCard({ isOpen }: RenderSchedulerCardProps) {
+  const [settings, setSettings] = useState<RenderSchedulerSettings | null>(null);
+  const [isSaving, setIsSaving] = useState(false);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    getRenderSchedulerSettings()
+      .then(setSettings)
+      .catch((error) => {
+        notifyError({ operation: 'load-render-scheduler-settings', error });
+      });
+  }, [isOpen]);
+
+  const save = useCallback(async (next: RenderSchedulerSettings) => {
+    setIsSaving(true);
+    try {
+      await setRenderSchedulerSettings(next);
+      setSettings(next);
+    } catch (error) {
+      notifyError({
+        operation: 'set-render-scheduler-settings',
+        error,
+        fallbackMessage: 'Failed to save the auto-render settings',
+        toastId: 'render-scheduler-settings-error',
+      });
+    } finally {
+      setIsSaving(false);
+    }
+  }, []);
+
+  if (!settings) return null;
+
+  const delayOptions = DELAY_OPTIONS.some((option) => option.value === settings.debounceMs)
+    ? DELAY_OPTIONS
+    : [...DELAY_OPTIONS, { value: settings.debounceMs, label: `${settings.debounceMs}ms` }];
+
+  return (
+    <SettingsCard>
+      <SettingsCardHeader
+        title="Auto-Render Scheduling"
+        description="When Auto-Render on Idle is on, edits wait for a pause in typing and a newer edit replaces one still waiting."
+      />
+      <SettingsControlRow
+        label="Render Delay"
+        description="Quiet time after the last edit before the render starts."
+        control={
+          <Select
+            value={String(settings.debounceMs)}
+            onValueChange={(value) => void save({ ...settings, debounceMs: Number(value) })}
+            disabled={isSaving}
+          >
+            <SelectTrigger className="w-40">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {delayOptions.map((option) => (
+                <SelectItem key={option.value} value={String(option.value)}>
+                  {option.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        }
+      />
+      <SettingsControlRow
+        divided
+        label="Wait for running renders"
+        description="Holds automatic renders until no other render or export is running."
+        control={
+          <Toggle
+            checked={settings.onlyWhenIdle}
+            onChange={(onlyWhenIdle) => void save({ ...settings, onlyWhenIdle })}
+            disabled={isSaving}
+          />
+        }
+      />
+      <SettingsControlRow
+        divided
+        label="Skip code with syntax errors"
+        description="Keeps the last preview while the code doesn't parse."
+        control={
+          <Toggle
+            checked={settings.onlyWhenClean}
+            onChange={(onlyWhenClean) => void save({ ...settings, onlyWhenClean })}
+            disabled={isSaving}
+          />
+        }
+      />
+    </SettingsCard>
+  );
+}
@@ -0,0 +1,135 @@
+import { useCallback, useEffect, useState } from 'react';
+import { TbFolderOpen, TbTrash } from 'react-icons/tb';
+import { Button, IconButton, Toggle } from '../ui';
+import {
+  getSafeModeSettings,
+  isProjectTrusted,
+  setSafeModeEnabled,
+  trustProject,
+  untrustProject,
+  type SafeModeSettings,
+} from '../../services/safeMode';
+import { getProjectStore } from '../../stores/projectStore';
+import { notifyError } from '../../utils/notifications';
+import {
+  SettingsCard,
+  SettingsCardSection,
+  SettingsControlRow,
+  SettingsSubsectionLabel,
+  SettingsSupportBlock,
+} from './SettingsPrimitives';
+
+interface SafeModeCardProps {
+  isOpen: boolean;
+}
+
+export function SafeModeCard({ isOpen }: SafeModeCardProps) {
+  const [settings, setSettings] = useState<SafeModeSettings | null>(null);
+  const [currentTrusted, setCurrentTrusted] = useState(true);
+  const projectRoot = getProjectStore().getState().projectRoot;
+
+  const load = useCallback(async () => {
+    try {
+      setSettings(await getSafeModeSettings());
+      setCurrentTrusted(projectRoot ? await isProjectTrusted(projectRoot) : true);
+    } catch (error) {
+      notifyError({ operation: 'load-safe-mode-settings', error });
+    }
+  }, [projectRoot]);
+
+  useEffect(() => {
+    if (isOpen) void load();
+  }, [isOpen, load]);
+
+  const run = async (operation: string, change: () => Promise<SafeModeSettings>) => {
+    try {
+      setSettings(await change());
+      setCurrentTrusted(projectRoot ? await isProjectTrusted(projectRoot) : true);
+    } catch (error) {
+      notifyError({ operation, error, toastId: 'safe-mode-error' });
+    }
+  };
+
+  if (!settings) return null;
+
+  return (
+    <SettingsCard>
+      <SettingsControlRow
+        label="Safe mode for untrusted projects"
+        description="Render project folders you haven't trusted in isolation: no library paths, a short timeout, and no reading files outside the project."
+        control={
+          <Toggle
+            checked={settings.enabled}
+            onChange={(enabled) =>
+              void run('set-safe-mode-enabled', () => setSafeModeEnabled(enabled))
+            }
+          />
+        }
+      />
+      {settings.enabled && (
+        <SettingsCardSection
+          divided
+          className="flex flex-col"
+          style={{ gap: 'var(--space-label-gap)' }}
+        >
+          <div className="flex items-center justify-between">
+            <SettingsSubsectionLabel>Trusted Folders</SettingsSubsectionLabel>
+            {projectRoot && !currentTrusted && (
+              <Button
+                type="button"
+                size="sm"
+                variant="ghost"
+                onClick={() => void run('trust-project', () => trustProject(projectRoot))}
+              >
+                Trust Current Project
+              </Button>
+            )}
+          </div>
+          {settings.trustedProjects.length === 0 ? (
+            <SettingsSupportBlock
+              className="text-sm italic"
+              style={{ color: 'var(--text-tertiary)' }}
+            >
+              No trusted folders
+            </SettingsSupportBlock>
+          ) : (
+            <div className="flex flex-col" style={{ gap: 'var(--space-control-gap)' }}>
+              {settings.trustedProjects.map((path) => (
+                <SettingsSupportBlock
+                  key={path}
+                  className="flex items-center justify-between group"
+                  style={{
+                    gap: 'var(--space-control-gap)',
+                    backgroundColor: 'var(--bg-primary)',
+                  }}
+                >
+                  <div
+                    className="flex items-center min-w-0"
+                    style={{ gap: 'var(--space-control-gap)' }}
+                  >
+                    <TbFolderOpen size={16} style={{ color: 'var(--text-tertiary)' }} />
+                    <span
+                      className="font-mono text-xs truncate"
+                      style={{ color: 'var(--text-primary)' }}
+                    >
+                      {path}
+                    </span>
+                  </div>
+                  <IconButton
+                    size="sm"
+                    onClick={() => void run('untrust-project', () => untrustProject(path))}
+                    className="opacity-0 group-hover:opacity-100 transition-opacity"
+                    style={{ color: 'var(--text-tertiary)' }}
+                    title="Stop trusting folder"
+                  >
+                    <TbTrash size={14} />
+                  </IconButton>
+                </SettingsSupportBlock>
+              ))}
+            </div>
+          )}
+        </SettingsCardSection>
+      )}
+    </SettingsCard>
+  );
+}
@@ -0,0 +1,174 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button, Input, Text, Toggle } from '../ui';
+import type { Settings } from '../../stores/settingsStore';
+import {
+  applySettingsProfile,
+  deleteSettingsProfile,
+  exportSettings,
+  importSettings,
+  listSettingsProfiles,
+  saveSettingsProfile,
+  type SettingsProfile,
+} from '../../services/settingsTransfer';
+import { notifyError, notifySuccess } from '../../utils/notifications';
+import {
+  SettingsCard,
+  SettingsCardHeader,
+  SettingsCardSection,
+  SettingsControlRow,
+} from './SettingsPrimitives';
+
+const SETTINGS_FILE_FILTERS = [{ name: 'Settings', extensions: ['json'] }];
+
+interface SettingsProfilesCardProps {
+  /** Called with the new settings after a profile or import is applied */
+  onSettingsReplaced: (settings: Settings) => void;
+}
+
+export function SettingsProfilesCard({ onSettingsReplaced }: SettingsProfilesCardProps) {
+  const [profiles, setProfiles] = useState<SettingsProfile[]>([]);
+  const [profileName, setProfileName] = useState('');
+  const [includeSecrets, setIncludeSecrets] = useState(false);
+  const [isBusy, setIsBusy] = useState(false);
+
+  useEffect(() => {
+    listSettingsProfiles()
+      .then(setProfiles)
+      .catch((error) => {
+        notifyError({ operation: 'list-settings-profiles', error });
+      });
+  }, []);
+
+  const run = useCallback(async (operation: string, action: () => Promise<void>) => {
+    setIsBusy(true);
+    try {
+      await action();
+    } catch (error) {
+      notifyError({ operation, error, toastId: `${operation}-error` });
+    } finally {
+      setIsBusy(false);
+    }
+  }, []);
+
+  const handleSave = () =>
+    run('save-settings-profile', async () => {
+      const name = profileName.trim();
+      setProfiles(await saveSettingsProfile(name));
+      setProfileName('');
+      notifySuccess(`Saved profile "${name}"`, { toastId: 'settings-profile' });
+    });
+
+  const handleApply = (name: string) =>
+    run('apply-settings-profile', async () => {
+      onSettingsReplaced(await applySettingsProfile(name));
+      notifySuccess(`Switched to profile "${name}"`, { toastId: 'settings-profile' });
+    });
+
+  const handleDelete = (name: string) =>
+    run('delete-settings-profile', async () => {
+      setProfiles(await deleteSettingsProfile(name));
+    });
+
+  const handleExport = () =>
+    run('export-settings', async () => {
+      const { save } = await import('@tauri-apps/plugin-dialog');
+      const path = await save({
+        filters: SETTINGS_FILE_FILTERS,
+        defaultPath: 'openscad-studio-settings.json',
+      });
+      if (!path) return;
+      await exportSettings(path, includeSecrets);
+      notifySuccess('Settings exported', { toastId: 'settings-export' });
+    });
+
+  const handleImport = () =>
+    run('import-settings', async () => {
+      const { open } = await import('@tauri-apps/plugin-dialog');
+      const path = await open({ filters: SETTINGS_FILE_FILTERS, multiple: false });
+      if (typeof path !== 'string') return;
+      onSettingsReplaced(await importSettings(path));
+      notifySuccess('Settings imported', { toastId: 'settings-import' });
+    });
+
+  return (
+    <SettingsCard>
+      <SettingsCardHeader
+        title="Settings Profiles"
+        description="Save named sets of settings, such as OpenSCAD build, AI model, render defaults, and library paths, and switch between them or move them to another machine."
+      />
+      <SettingsCardSection className="flex flex-col" style={{ gap: 'var(--space-field-gap)' }}>
+        <div className="flex items-center" style={{ gap: 'var(--space-control-gap)' }}>
+          <Input
+            value={profileName}
+            onChange={(event) => setProfileName(event.target.value)}
+            placeholder="Profile name, e.g. laptop"
+            className="flex-1 text-sm"
+            disabled={isBusy}
+          />
+          <Button
+            variant="secondary"
+            size="sm"
+            onClick={() => void handleSave()}
+            disabled={isBusy || !profileName.trim()}
+          >
+            Save Profile
+          </Button>
+        </div>
+        {profiles.length === 0 ? (
+          <Text variant="caption" color="tertiary">
+            No saved profiles yet.
+          </Text>
+        ) : (
+          profiles.map((profile) => (
+            <div
+              key={profile.name}
+              className="flex items-center justify-between"
+              style={{ gap: 'var(--space-control-gap)' }}
+            >
+              <div className="flex flex-col min-w-0">
+                <Text variant="body" className="truncate">
+                  {profile.name}
+                </Text>
+                <Text variant="caption" color="tertiary">
+                  Saved {new Date(profile.savedAt).toLocaleString()}
+                </Text>
+              </div>
+              <div className="flex shrink-0" style={{ gap: 'var(--space-control-gap)' }}>
+                <Button
+                  variant="secondary"
+                  size="sm"
+                  onClick={() => void handleApply(profile.name)}
+                  disabled={isBusy}
+                >
+                  Apply
+                </Button>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  onClick={() => void handleDelete(profile.name)}
+                  disabled={isBusy}
+                >
+                  Delete
+                </Button>
+              </div>
+            </div>
+          ))
+        )}
+      </SettingsCardSection>
+      <SettingsControlRow
+        divided
+        label="Include API keys in exports"
+        description="Exported files store keys in plain text. Profiles never include them."
+        control={<Toggle checked={includeSecrets} onChange={setIncludeSecrets} />}
+      />
+      <SettingsCardSection divided className="flex" style={{ gap: 'var(--space-control-gap)' }}>
+        <Button variant="secondary" size="sm" onClick={() => void handleExport()} disabled={isBusy}>
+          Export…
+        </Button>
+        <Button variant="secondary" size="sm" onClick={() => void handleImport()} disabled={isBusy}>
+          Import…
+        </Button>
+      </SettingsCardSection>
+    </SettingsCard>
+  );
+}
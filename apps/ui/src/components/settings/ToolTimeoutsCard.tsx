@@ -0,0 +1,110 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button, Input } from '../ui';
+import { getToolTimeouts, setToolTimeout, type ToolTimeout } from '../../services/toolTimeouts';
+import { notifyError, notifySuccess } from '../../utils/notifications';
+import { SettingsCard, SettingsCardHeader, SettingsControlRow } from './SettingsPrimitives';
+
+interface ToolTimeoutsCardProps {
+  isOpen: boolean;
+}
+
+export function ToolTimeoutsCard({ isOpen }: ToolTimeoutsCardProps) {
+  const [timeouts, setTimeouts] = useState<ToolTimeout[]>([]);
+  /** Input text per tool; empty restores the default */
+  const [drafts, setDrafts] = useState<Record<string, string>>({});
+  const [isSaving, setIsSaving] = useState(false);
+
+  const load = useCallback(async () => {
+    try {
+      const loaded = await getToolTimeouts();
+      setTimeouts(loaded);
+      setDrafts(
+        Object.fromEntries(
+          loaded.map((timeout) => [
+            timeout.toolName,
+            timeout.isCustom ? String(timeout.timeoutSecs) : '',
+          ])
+        )
+      );
+    } catch (error) {
+      notifyError({ operation: 'load-tool-timeouts', error });
+    }
+  }, []);
+
+  useEffect(() => {
+    if (isOpen) void load();
+  }, [isOpen, load]);
+
+  const handleApply = async (timeout: ToolTimeout) => {
+    const draft = (drafts[timeout.toolName] ?? '').trim();
+    const timeoutSecs = draft === '' ? null : Number(draft);
+    if (timeoutSecs !== null && !Number.isInteger(timeoutSecs)) {
+      notifyError({
+        operation: 'set-tool-timeout',
+        displayMessage: 'Enter a whole number of seconds',
+        toastId: 'tool-timeout-error',
+      });
+      return;
+    }
+    setIsSaving(true);
+    try {
+      await setToolTimeout(timeout.toolName, timeoutSecs);
+      await load();
+      notifySuccess(`Timeout for ${timeout.toolName} saved`, { toastId: 'tool-timeout' });
+    } catch (error) {
+      notifyError({
+        operation: 'set-tool-timeout',
+        error,
+        fallbackMessage: 'Failed to save the timeout',
+        toastId: 'tool-timeout-error',
+      });
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  if (timeouts.length === 0) return null;
+
+  return (
+    <SettingsCard>
+      <SettingsCardHeader
+        title="Tool Timeouts"
+        description="How long the assistant and MCP clients wait for a tool before giving up. Leave a field empty to use the default."
+      />
+      {timeouts.map((timeout, index) => (
+        <SettingsControlRow
+          key={timeout.toolName}
+          divided={index > 0}
+          label={timeout.toolName}
+          htmlFor={`tool-timeout-${timeout.toolName}`}
+          description={`Default: ${timeout.defaultSecs}s`}
+          control={
+            <div className="flex items-center" style={{ gap: 'var(--space-control-gap)' }}>
+              <Input
+                id={`tool-timeout-${timeout.toolName}`}
+                type="number"
+                value={drafts[timeout.toolName] ?? ''}
+                placeholder={String(timeout.defaultSecs)}
+                onChange={(event) =>
+                  setDrafts((prev) => ({ ...prev, [timeout.toolName]: event.target.value }))
+                }
+                className="w-24 font-mono"
+                min={1}
+                max={600}
+              />
+              <Button
+                type="button"
+                size="sm"
+                variant="ghost"
+                onClick={() => void handleApply(timeout)}
+                disabled={isSaving}
+              >
+                Apply
+              </Button>
+            </div>
+          }
+        />
+      ))}
+    </SettingsCard>
+  );
+}
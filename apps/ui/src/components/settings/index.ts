@@ -15,5 +15,9 @@ export { ProjectSettings } from './ProjectSettings';
 export { EditorSettings } from './EditorSettings';
 export { PrivacySettings } from './PrivacySettings';
 export { LibrariesSettings } from './LibrariesSettings';
+export { OpenScadVersionsCard } from './OpenScadVersionsCard';
+export { ManagedLibrariesCard } from './ManagedLibrariesCard';
+export { SafeModeCard } from './SafeModeCard';
+export { RenderSchedulerCard } from './RenderSchedulerCard';
 export { AiSettings } from './AiSettings';
 export { ApiProviderCard } from './ApiProviderCard';
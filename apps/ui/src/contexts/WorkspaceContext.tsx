@@ -6,9 +6,19 @@ import type { AddDraftFilesResult } from '../hooks/useAiAgent';
 import type { AiPromptPanelRef } from '../components/AiPromptPanel';
 import type { ViewerAnnotationAttachResult } from '../components/viewer-annotation';
 import type { AiProvider } from '../stores/apiKeyStore';
+import type { PendingEdit } from '../services/pendingEdits';
+import type { InterruptedQuery } from '../services/aiTranscripts';
+import type { ConversationExportFormat } from '../services/savedConversations';
 import type { Settings } from '../stores/settingsStore';
 import type { WorkspaceTab } from '../stores/workspaceTypes';
-import type { AiDraft, AttachmentStore, Message, ToolCall, VisionSupport } from '../types/aiChat';
+import type {
+  AiDraft,
+  AttachmentStore,
+  Message,
+  ReasoningEffort,
+  ToolCall,
+  VisionSupport,
+} from '../types/aiChat';
 
 export interface WorkspaceState {
   // Editor
@@ -38,6 +48,7 @@ export interface WorkspaceState {
   // AI
   isStreaming: boolean;
   streamingResponse: string | null;
+  streamingReasoning: string | null;
   proposedDiff: unknown;
   aiError: string | null;
   isApplyingDiff: boolean;
@@ -49,6 +60,7 @@ export interface WorkspaceState {
   draftVisionWarningMessage: string | null;
   canSubmitDraft: boolean;
   isProcessingAttachments: boolean;
+  currentConversationId: string;
   currentToolCalls: ToolCall[];
   currentProvider: AiProvider;
   currentModel: string;
@@ -69,6 +81,21 @@ export interface WorkspaceState {
   rejectDiff: () => void;
   clearAiError: () => void;
   newConversation: () => void;
+  exportConversation: (format: ConversationExportFormat, path: string) => Promise<void>;
+  importConversation: (path: string) => Promise<void>;
+  interruptedQueries: InterruptedQuery[];
+  resumeInterruptedQuery: (conversationId: string) => Promise<void>;
+  discardInterruptedQuery: (conversationId: string) => void;
+  verifyEditsWithPreview: boolean;
+  setVerifyEditsWithPreview: (enabled: boolean) => void;
+  reviewEdits: boolean;
+  /** Unset where edits can't be held for review */
+  setReviewEdits?: (enabled: boolean) => void;
+  pendingEdits: PendingEdit[];
+  acceptPendingEdit: (id: string) => Promise<string | null>;
+  rejectPendingEdit: (id: string) => void;
+  reasoningEffort: ReasoningEffort;
+  setReasoningEffort: (effort: ReasoningEffort) => void;
   setCurrentModel: (
     model: string,
     sourceSurface?: ModelSelectionSurface,
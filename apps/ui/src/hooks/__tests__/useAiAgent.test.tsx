@@ -136,6 +136,47 @@ describe('useAiAgent', () => {
     expect(localStorage.getItem('openscad_studio_openai_api_key')).toMatch(/^obf1:/);
   });
 
+  it('sizes output tokens per model and refuses models without tool use', async () => {
+    storeApiKey('openai', 'openai-test-key');
+    setStoredModelSelection({ provider: 'openai', modelId: 'gpt-4o' });
+    const startAiStream = jest.fn(async () =>
+      createStreamResult([
+        {
+          type: 'finish',
+          finishReason: 'stop',
+          rawFinishReason: 'stop',
+          totalUsage: {} as never,
+        },
+      ] satisfies StreamChunk[])
+    );
+
+    const hook = createHarness({
+      testOverrides: {
+        availableProviders: ['openai'],
+        createModel: (() => ({ id: 'openai-model' })) as never,
+        buildTools: (() => ({})) as never,
+        messagesToModelMessages: (() => []) as never,
+        startAiStream: startAiStream as never,
+      },
+    });
+
+    await act(async () => {
+      await hook.current().submitPrompt('Make a cube');
+    });
+
+    expect(startAiStream).toHaveBeenCalledWith(expect.objectContaining({ maxOutputTokens: 16384 }));
+
+    act(() => {
+      hook.current().setCurrentModel('o1-mini', 'unknown', 'openai');
+    });
+    await act(async () => {
+      await hook.current().submitPrompt('Make a sphere');
+    });
+
+    expect(startAiStream).toHaveBeenCalledTimes(1);
+    expect(hook.current().error).toContain("can't use tools");
+  });
+
   it('routes legacy bare Anthropic model storage through the Anthropic provider', async () => {
     localStorage.setItem('openscad_studio_anthropic_api_key', 'plain-anthropic-key');
     localStorage.setItem('openscad_studio_ai_model', 'claude-3-5-sonnet-20241022');
@@ -764,6 +805,176 @@ describe('useAiAgent', () => {
     );
   });
 
+  it('summarizes older messages once the conversation outgrows the context window', async () => {
+    storeApiKey('anthropic', 'test-key');
+    const eventBus = { emit: jest.fn() };
+    const createModel = jest.fn((_provider: string, _apiKey: string, modelId: string) => ({
+      id: modelId,
+    }));
+    const summarizeConversation = jest.fn(async () => 'The user is designing a 40mm box.');
+    const startAiStream = jest.fn(async (_options: { messages: unknown[] }) =>
+      createStreamResult([
+        {
+          type: 'finish',
+          finishReason: 'stop',
+          rawFinishReason: 'stop',
+          totalUsage: {} as never,
+        },
+      ] satisfies StreamChunk[])
+    );
+    const history = [
+      { role: 'user', content: `Make a box. ${'x'.repeat(800000)}` },
+      { role: 'assistant', content: 'Done.' },
+      { role: 'user', content: 'Add a lid' },
+      { role: 'assistant', content: 'Added.' },
+      { role: 'user', content: 'Make it taller' },
+    ];
+
+    const hook = createHarness({
+      testOverrides: {
+        availableProviders: ['anthropic'],
+        createModel: createModel as never,
+        summarizeConversation: summarizeConversation as never,
+        buildTools: (() => ({})) as never,
+        messagesToModelMessages: (() => history) as never,
+        startAiStream: startAiStream as never,
+        eventBus: eventBus as never,
+      },
+    });
+    const conversationId = hook.current().currentConversationId;
+
+    await act(async () => {
+      await hook.current().submitPrompt('Make it taller');
+    });
+
+    expect(summarizeConversation).toHaveBeenCalledWith(
+      { id: 'claude-haiku-3-5' },
+      expect.stringContaining('user: Make a box.')
+    );
+    const { messages } = startAiStream.mock.calls[0][0];
+    expect(messages).toHaveLength(3);
+    expect(messages[0]).toMatchObject({
+      role: 'user',
+      content: [
+        { type: 'text', text: expect.stringContaining('The user is designing a 40mm box.') },
+        { type: 'text', text: 'Add a lid' },
+      ],
+    });
+    expect(eventBus.emit).toHaveBeenCalledWith(
+      'conversation:compacted',
+      expect.objectContaining({ conversationId, summarizedMessages: 2 })
+    );
+  });
+
+  it('reports retries of rate-limited model calls as events', async () => {
+    storeApiKey('anthropic', 'test-key');
+    const eventBus = { emit: jest.fn() };
+    const retry = { attempt: 1, maxAttempts: 4, delayMs: 2000, statusCode: 429 };
+    const startAiStream = jest.fn(
+      async (_options: unknown, hooks?: { onRetry?: (value: typeof retry) => void }) => {
+        hooks?.onRetry?.(retry);
+        return createStreamResult([
+          {
+            type: 'finish',
+            finishReason: 'stop',
+            rawFinishReason: 'stop',
+            totalUsage: {} as never,
+          },
+        ] satisfies StreamChunk[]);
+      }
+    );
+
+    const hook = createHarness({
+      testOverrides: {
+        availableProviders: ['anthropic'],
+        createModel: (() => ({})) as never,
+        buildTools: (() => ({})) as never,
+        messagesToModelMessages: (() => []) as never,
+        startAiStream: startAiStream as never,
+        eventBus: eventBus as never,
+      },
+    });
+    const conversationId = hook.current().currentConversationId;
+
+    await act(async () => {
+      await hook.current().submitPrompt('Make a cube');
+    });
+
+    expect(eventBus.emit).toHaveBeenCalledWith(
+      `ai-stream:${conversationId}`,
+      expect.objectContaining({ status: 'retrying', retry })
+    );
+  });
+
+  it('titles a conversation with a small model after its first exchange', async () => {
+    storeApiKey('anthropic', 'test-key');
+    const eventBus = { emit: jest.fn() };
+    const createModel = jest.fn((_provider: string, _apiKey: string, modelId: string) => ({
+      id: modelId,
+    }));
+    const generateConversationTitle = jest.fn(async () => 'Parametric Cube');
+    const startAiStream = jest.fn(async () =>
+      createStreamResult([
+        { type: 'text-start', id: 'text-1' },
+        { type: 'text-delta', id: 'text-1', text: 'Here is a cube.' },
+        { type: 'text-end', id: 'text-1' },
+        {
+          type: 'finish',
+          finishReason: 'stop',
+          rawFinishReason: 'stop',
+          totalUsage: {} as never,
+        },
+      ] satisfies StreamChunk[])
+    );
+
+    const hook = createHarness({
+      testOverrides: {
+        availableProviders: ['anthropic'],
+        createModel: createModel as never,
+        generateConversationTitle: generateConversationTitle as never,
+        buildTools: (() => ({})) as never,
+        messagesToModelMessages: (() => []) as never,
+        startAiStream: startAiStream as never,
+        eventBus: eventBus as never,
+      },
+    });
+    const conversationId = hook.current().currentConversationId;
+
+    await act(async () => {
+      await hook.current().submitPrompt('Make a cube I can resize');
+    });
+    await waitFor(() => {
+      expect(hook.current().conversationTitles[conversationId]).toBe('Parametric Cube');
+    });
+
+    expect(createModel).toHaveBeenCalledWith('anthropic', 'test-key', 'claude-haiku-3-5');
+    expect(generateConversationTitle).toHaveBeenCalledWith(
+      { id: 'claude-haiku-3-5' },
+      'Make a cube I can resize',
+      'Here is a cube.'
+    );
+    expect(eventBus.emit).toHaveBeenCalledWith('conversation:titled', {
+      conversationId,
+      title: 'Parametric Cube',
+    });
+
+    await act(async () => {
+      await hook.current().submitPrompt('Make it taller');
+    });
+    await waitFor(() => {
+      expect(hook.current().isStreaming).toBe(false);
+    });
+    expect(generateConversationTitle).toHaveBeenCalledTimes(1);
+
+    act(() => {
+      hook.current().newConversation();
+    });
+    expect(hook.current().conversations[0]).toMatchObject({
+      id: conversationId,
+      title: 'Parametric Cube',
+    });
+  });
+
   it('restores the submitted draft when the request fails before any response arrives', async () => {
     storeApiKey('anthropic', 'test-key');
 
@@ -1055,7 +1266,7 @@ describe('useAiAgent', () => {
   it('restores the first checkpoint for multi-edit turns and truncates later conversation', async () => {
     storeApiKey('anthropic', 'test-key');
     const analytics = createAnalyticsSpy();
-    const history = { restoreTo: jest.fn(() => ({ code: 'cube(1);' })) };
+    const history = { restoreTo: jest.fn(async () => ({ code: 'cube(1);' })) };
     const eventBus = { emit: jest.fn() };
 
     const hook = createHarness({
@@ -1123,7 +1334,7 @@ describe('useAiAgent', () => {
       checkpointId: 'cp-1',
     });
 
-    act(() => {
+    await act(async () => {
       hook.current().handleRestoreCheckpoint('cp-1', truncatedMessages);
     });
 
@@ -1193,6 +1404,131 @@ describe('useAiAgent', () => {
     );
   });
 
+  it('streams the raw tool argument text as it arrives', async () => {
+    storeApiKey('anthropic', 'test-key');
+    const eventBus = { emit: jest.fn() };
+
+    const hook = createHarness({
+      testOverrides: {
+        availableProviders: ['anthropic'],
+        createModel: (() => ({ id: 'model' })) as never,
+        buildTools: (() => ({})) as never,
+        messagesToModelMessages: (() => []) as never,
+        startAiStream: (async () =>
+          createStreamResult([
+            { type: 'tool-input-start', id: 'tool-1', toolName: 'apply_edit' },
+            { type: 'tool-input-delta', id: 'tool-1', delta: '{"old_string":"cube(' },
+            { type: 'tool-input-delta', id: 'tool-1', delta: '10);"' },
+            {
+              type: 'finish',
+              finishReason: 'stop',
+              rawFinishReason: 'stop',
+              totalUsage: {} as never,
+            },
+          ] satisfies StreamChunk[])) as never,
+        eventBus: eventBus as never,
+      },
+    });
+
+    const conversationId = hook.current().currentConversationId;
+    await act(async () => {
+      await hook.current().submitPrompt('Make the cube bigger');
+    });
+
+    expect(eventBus.emit).toHaveBeenCalledWith(
+      `ai-stream:${conversationId}`,
+      expect.objectContaining({
+        status: 'tool-args-delta',
+        args: { old_string: 'cube(10);' },
+        argsDelta: '10);"',
+        argsText: '{"old_string":"cube(10);"',
+        toolCall: expect.objectContaining({
+          toolCallId: 'tool-1',
+          args: { old_string: 'cube(10);' },
+        }),
+      })
+    );
+  });
+
+  it('keeps streaming a conversation in the background while another one runs', async () => {
+    storeApiKey('anthropic', 'test-key');
+    const eventBus = { emit: jest.fn() };
+
+    const startAiStream = jest.fn(async ({ abortSignal }: { abortSignal: AbortSignal }) => ({
+      fullStream: (async function* () {
+        yield { type: 'text-start', id: 'text-1' };
+        yield { type: 'text-delta', id: 'text-1', text: 'Working' };
+        await new Promise<void>((resolve) => {
+          abortSignal.addEventListener('abort', () => resolve(), { once: true });
+        });
+      })(),
+    }));
+
+    const hook = createHarness({
+      testOverrides: {
+        availableProviders: ['anthropic'],
+        createModel: (() => ({ id: 'model' })) as never,
+        buildTools: (() => ({})) as never,
+        messagesToModelMessages: (() => []) as never,
+        startAiStream: startAiStream as never,
+        eventBus: eventBus as never,
+      },
+    });
+
+    const firstConversationId = hook.current().currentConversationId;
+    act(() => {
+      void hook.current().submitPrompt('First request');
+    });
+    await waitFor(() => {
+      expect(hook.current().streamingResponse).toBe('Working');
+    });
+
+    act(() => {
+      hook.current().newConversation();
+    });
+    expect(hook.current().isStreaming).toBe(false);
+    expect(hook.current().messages).toEqual([]);
+
+    act(() => {
+      void hook.current().submitPrompt('Second request');
+    });
+    await waitFor(() => {
+      expect(hook.current().streamingConversationIds).toHaveLength(2);
+    });
+    const secondConversationId = hook.current().currentConversationId;
+
+    act(() => {
+      hook.current().cancelStream(firstConversationId);
+    });
+
+    expect(hook.current().streamingConversationIds).toEqual([secondConversationId]);
+    expect(hook.current().isStreaming).toBe(true);
+    expect(hook.current().conversations[0]).toMatchObject({
+      id: firstConversationId,
+      title: 'First request',
+    });
+    expect(hook.current().conversations[0].messages[1]).toMatchObject({
+      type: 'assistant',
+      content: 'Working',
+      state: 'cancelled',
+    });
+    expect(eventBus.emit).toHaveBeenCalledWith(
+      `ai-stream:${firstConversationId}`,
+      expect.objectContaining({ status: 'cancelled' })
+    );
+
+    act(() => {
+      hook.current().loadConversation(firstConversationId);
+    });
+    expect(hook.current().currentConversationId).toBe(firstConversationId);
+    expect(hook.current().isStreaming).toBe(false);
+    expect(hook.current().conversations.map(({ id }) => id)).toEqual([secondConversationId]);
+
+    act(() => {
+      hook.current().cancelStream(secondConversationId);
+    });
+  });
+
   it('adds a completion notice when the stream stops because the tool step budget was exhausted', async () => {
     storeApiKey('anthropic', 'test-key');
 
@@ -1240,7 +1576,7 @@ describe('useAiAgent', () => {
   it('restores checkpoints through the injected history and event services', async () => {
     storeApiKey('anthropic', 'test-key');
     const analytics = createAnalyticsSpy();
-    const history = { restoreTo: jest.fn(() => ({ code: 'cube(42);' })) };
+    const history = { restoreTo: jest.fn(async () => ({ code: 'cube(42);' })) };
     const eventBus = { emit: jest.fn() };
 
     const hook = createHarness({
@@ -1276,7 +1612,7 @@ describe('useAiAgent', () => {
 
     const truncatedMessages = [hook.current().messages[0]];
 
-    act(() => {
+    await act(async () => {
       hook.current().handleRestoreCheckpoint('checkpoint-1', truncatedMessages);
     });
 
@@ -1359,7 +1695,7 @@ describe('useAiAgent', () => {
 
   it('logs restore failures without emitting history events when a checkpoint is missing', () => {
     const analytics = createAnalyticsSpy();
-    const history = { restoreTo: jest.fn(() => null) };
+    const history = { restoreTo: jest.fn(async () => null) };
     const eventBus = { emit: jest.fn() };
 
     const hook = createHarness({
@@ -1371,7 +1707,7 @@ describe('useAiAgent', () => {
       },
     });
 
-    act(() => {
+    await act(async () => {
       hook.current().handleRestoreCheckpoint('missing', []);
     });
 
@@ -6,8 +6,10 @@ import { useModels } from '../useModels';
 import type { AiProvider } from '../../stores/apiKeyStore';
 import {
   clearApiKey,
+  clearAzureOpenAiConfig,
   clearOpenAiCompatibleConfig,
   storeApiKey,
+  storeAzureOpenAiConfig,
   storeOpenAiCompatibleConfig,
 } from '../../stores/apiKeyStore';
 
@@ -37,6 +39,40 @@ function createFetchMock() {
       });
     }
 
+    if (url.startsWith('https://generativelanguage.googleapis.com/v1beta/models')) {
+      return createJsonResponse({
+        models: [
+          {
+            name: 'models/gemini-2.5-pro',
+            displayName: 'Gemini 2.5 Pro',
+            supportedGenerationMethods: ['generateContent', 'countTokens'],
+          },
+          {
+            name: 'models/gemini-embedding-001',
+            supportedGenerationMethods: ['embedContent'],
+          },
+        ],
+      });
+    }
+
+    if (url === 'https://openrouter.ai/api/v1/models') {
+      return createJsonResponse({
+        data: [
+          {
+            id: 'anthropic/claude-sonnet-4.5',
+            name: 'Anthropic: Claude Sonnet 4.5',
+            architecture: { input_modalities: ['text', 'image'] },
+            supported_parameters: ['tools', 'temperature'],
+          },
+          {
+            id: 'some-lab/text-only',
+            name: 'Text Only',
+            supported_parameters: ['temperature'],
+          },
+        ],
+      });
+    }
+
     if (url === 'http://127.0.0.1:11434/v1/models') {
       return createJsonResponse({
         data: [{ id: 'gemma4:12b' }, { id: 'qwen3-coder:latest' }],
@@ -62,6 +98,13 @@ function UseModelsHarness({ availableProviders }: { availableProviders: AiProvid
         {groupedByProvider.anthropic.map((model) => model.id).join(',')}
       </div>
       <div data-testid="openai">{groupedByProvider.openai.map((model) => model.id).join(',')}</div>
+      <div data-testid="azure-openai">
+        {groupedByProvider.azureOpenai.map((model) => model.id).join(',')}
+      </div>
+      <div data-testid="gemini">{groupedByProvider.gemini.map((model) => model.id).join(',')}</div>
+      <div data-testid="openrouter">
+        {groupedByProvider.openrouter.map((model) => model.id).join(',')}
+      </div>
       <div data-testid="openai-compatible">
         {groupedByProvider.openaiCompatible.map((model) => model.id).join(',')}
       </div>
@@ -74,6 +117,9 @@ describe('useModels', () => {
     localStorage.clear();
     clearApiKey('anthropic');
     clearApiKey('openai');
+    clearApiKey('gemini');
+    clearApiKey('openrouter');
+    clearAzureOpenAiConfig();
     clearOpenAiCompatibleConfig();
 
     Object.defineProperty(globalThis, 'fetch', {
@@ -153,6 +199,16 @@ describe('useModels', () => {
     });
   });
 
+  it('lists Gemini chat models without their resource prefix', async () => {
+    storeApiKey('gemini', 'gemini-test-key');
+
+    render(<UseModelsHarness availableProviders={['gemini']} />);
+
+    await waitFor(() => {
+      expect(screen.getByTestId('gemini').textContent).toBe('gemini-2.5-pro');
+    });
+  });
+
   it('fetches OpenAI-compatible models without filtering local model ids', async () => {
     storeOpenAiCompatibleConfig({
       baseUrl: 'http://127.0.0.1:11434/v1',
@@ -186,4 +242,34 @@ describe('useModels', () => {
       expect(screen.getByTestId('openai-compatible').textContent).toBe('lm-studio-model');
     });
   });
+
+  it('lists only tool-capable OpenRouter models', async () => {
+    storeApiKey('openrouter', 'openrouter-test-key');
+
+    render(<UseModelsHarness availableProviders={['openrouter']} />);
+
+    await waitFor(() => {
+      expect(screen.getByTestId('openrouter').textContent).toBe('anthropic/claude-sonnet-4.5');
+    });
+  });
+
+  it('falls back to configured Azure deployments when listing fails', async () => {
+    storeAzureOpenAiConfig({
+      endpoint: 'https://studio.openai.azure.com/openai/v1/',
+      deployments: ['gpt-4o-prod', 'gpt-4.1-mini'],
+      apiKey: 'azure-test-key',
+    });
+
+    render(<UseModelsHarness availableProviders={['azure-openai']} />);
+
+    await waitFor(() => {
+      expect(screen.getByTestId('azure-openai').textContent).toContain('gpt-4o-prod');
+      expect(screen.getByTestId('azure-openai').textContent).toContain('gpt-4.1-mini');
+    });
+
+    expect(globalThis.fetch as jest.Mock).toHaveBeenCalledWith(
+      'https://studio.openai.azure.com/openai/deployments?api-version=2022-12-01',
+      { headers: { 'api-key': 'azure-test-key' } }
+    );
+  });
 });
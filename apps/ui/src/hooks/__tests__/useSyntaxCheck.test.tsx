@@ -0,0 +1,70 @@
+/** @jest-environment jsdom */
+
+import { render, act, screen } from '@testing-library/react';
+import { jest } from '@jest/globals';
+import type { Diagnostic } from '../../platform/historyService';
+
+const mockCheckSyntax = jest.fn(async (_code: string): Promise<Diagnostic[]> => []);
+
+jest.unstable_mockModule('@/services/syntaxCheck', () => ({
+  isSyntaxCheckAvailable: () => true,
+  checkSyntax: mockCheckSyntax,
+}));
+
+let useSyntaxCheck: typeof import('../useSyntaxCheck').useSyntaxCheck;
+
+function Harness({ code }: { code: string }) {
+  const errors = useSyntaxCheck(code, 100);
+  return <div data-testid="errors">{errors.map((error) => error.message).join('|')}</div>;
+}
+
+describe('useSyntaxCheck', () => {
+  beforeAll(async () => {
+    ({ useSyntaxCheck } = await import('../useSyntaxCheck'));
+  });
+
+  beforeEach(() => {
+    jest.useFakeTimers();
+    mockCheckSyntax.mockClear();
+  });
+
+  afterEach(() => {
+    jest.useRealTimers();
+  });
+
+  it('checks the code once typing pauses and keeps only errors', async () => {
+    mockCheckSyntax.mockImplementation(async (code) => [
+      { severity: 'error', line: 1, message: `syntax error in ${code}` },
+      { severity: 'warning', line: 1, message: 'unused' },
+    ]);
+    const { rerender } = render(<Harness code="cube(" />);
+    rerender(<Harness code="cube(1" />);
+    expect(mockCheckSyntax).not.toHaveBeenCalled();
+
+    await act(async () => {
+      jest.advanceTimersByTime(100);
+    });
+
+    expect(mockCheckSyntax).toHaveBeenCalledTimes(1);
+    expect(mockCheckSyntax).toHaveBeenCalledWith('cube(1');
+    expect(screen.getByTestId('errors').textContent).toBe('syntax error in cube(1');
+  });
+
+  it('ignores a check whose code has changed since', async () => {
+    let finish: (diagnostics: Diagnostic[]) => void = () => {};
+    mockCheckSyntax.mockImplementationOnce(
+      () => new Promise<Diagnostic[]>((resolve) => (finish = resolve))
+    );
+    const { rerender } = render(<Harness code="cube(" />);
+    await act(async () => {
+      jest.advanceTimersByTime(100);
+    });
+    rerender(<Harness code="cube(1);" />);
+
+    await act(async () => {
+      finish([{ severity: 'error', line: 1, message: 'stale' }]);
+    });
+
+    expect(screen.getByTestId('errors').textContent).toBe('');
+  });
+});
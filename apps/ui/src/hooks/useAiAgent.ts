@@ -1,7 +1,12 @@
 import { useState, useCallback, useRef, useEffect, useMemo } from 'react';
-import { type ToolSet, stepCountIs } from 'ai';
+import { type ModelMessage, type TextStreamPart, type ToolSet, stepCountIs } from 'ai';
 import { bucketCount, useAnalytics, type ModelSelectionSurface } from '../analytics/runtime';
-import { historyService, eventBus, getPlatform } from '../platform';
+import {
+  eventBus,
+  getPlatform,
+  type AiStreamEvent,
+  type ConversationLink,
+} from '../platform';
 import {
   getProjectState,
   getProjectStore,
@@ -10,6 +15,10 @@ import {
 import { requestRender } from '../stores/renderRequestStore';
 import {
   createModel,
+  EDIT_TOOL_NAMES,
+  generateConversationTitle,
+  getReasoningRequestOptions,
+  summarizeConversation,
   SYSTEM_PROMPT,
   buildTools,
   type AiToolCallbacks,
@@ -21,24 +30,30 @@ import {
 } from '../services/projectRenderInputs';
 import {
   getApiKey,
+  getAzureOpenAiConfig,
   getOpenAiCompatibleConfig,
   getPreferredDefaultModelSelection,
   getProviderFromModel,
   getStoredModelSelection,
+  isProviderConfigured,
   setStoredModelSelection,
   useAvailableProviders,
   type AiProvider,
 } from '../stores/apiKeyStore';
-import type {
-  AiDraft,
-  AttachmentStore,
-  Conversation,
-  Message,
-  ToolCall,
-  UserImagePart,
-  UserMessage,
-  UserTextPart,
-  VisionSupport,
+import { isCloudAiProvider } from '../services/cloudAi';
+import {
+  getUserMessageText,
+  type AiDraft,
+  type AssistantMessage,
+  type AttachmentStore,
+  type Conversation,
+  type Message,
+  type ToolCall,
+  type UserImagePart,
+  type UserMessage,
+  type ReasoningEffort,
+  type UserTextPart,
+  type VisionSupport,
 } from '../types/aiChat';
 import {
   getDraftCanSubmit,
@@ -47,10 +62,26 @@ import {
   processAttachmentFiles,
 } from '../utils/aiAttachments';
 import { getVisionSupportForModelId, messagesToModelMessages } from '../utils/aiMessages';
-import { getPreferredDefaultModel } from '../utils/aiModels';
+import {
+  AI_PROVIDER_LABELS,
+  getContextWindowForModelId,
+  getMaxOutputTokensForModelId,
+  getPreferredDefaultModel,
+  getSmallModelId,
+  getToolSupportForModelId,
+  MAX_AGENT_OUTPUT_TOKENS,
+} from '../utils/aiModels';
+import {
+  applyCompactionSummary,
+  estimateTokens,
+  getCompactionBudget,
+  splitForCompaction,
+  transcriptForSummary,
+} from '../utils/aiCompaction';
 import {
   createActiveTurnState,
   deriveCurrentToolCalls,
+  deriveStreamingReasoning,
   deriveStreamingResponse,
   finalizeActiveTurn,
   finalizeConversationTurn,
@@ -58,7 +89,35 @@ import {
   reduceActiveTurnChunk,
   type ActiveTurnState,
 } from '../utils/aiTurnState';
+import {
+  createAgentTranscriptState,
+  reduceTranscriptChunk,
+  type AgentTranscriptState,
+} from '../utils/aiTranscript';
 import { startAiStream } from '../services/aiStream';
+import * as documentHistory from '../services/documentHistory';
+import * as pendingEditService from '../services/pendingEdits';
+import type { PendingEdit } from '../services/pendingEdits';
+import { authorizeToolCall } from '../services/toolPermissions';
+import { loadToolTimeoutOverrides } from '../services/toolTimeouts';
+import { describeReferenceGeometry, listReferenceGeometry } from '../services/referenceGeometry';
+import { describeSnippets, searchSnippets } from '../services/snippets';
+import { describeDocs, searchDocs } from '../services/docsIndex';
+import { getEffectiveSystemPrompt } from '../services/aiInstructions';
+import {
+  discardAiTranscript,
+  listInterruptedAiQueries,
+  resumeAiQuery,
+  saveAiTranscript,
+  type InterruptedQuery,
+  type ResumedQuery,
+} from '../services/aiTranscripts';
+import {
+  exportConversation as writeConversationExport,
+  importConversation as readConversationExport,
+  saveConversation as storeConversation,
+  type ConversationExportFormat,
+} from '../services/savedConversations';
 import {
   FALLBACK_PREVIEW_SCENE_STYLE,
   type PreviewSceneStyle,
@@ -85,16 +144,27 @@ function humanizeStreamError(errorText: string, provider?: AiProvider): string {
   return `Failed: ${errorText}`;
 }
 
+/** Replace a project file's content, saving it when the project is on disk */
+function writeProjectFileContent(path: string, content: string) {
+  getProjectStore().getState().updateFileContent(path, content);
+
+  const { projectRoot } = getProjectState();
+  if (projectRoot) {
+    const platform = getPlatform();
+    void platform
+      .writeTextFile(`${projectRoot}/${path}`, content)
+      .then(() => getProjectStore().getState().markFileSaved(path, content))
+      .catch((err) => console.warn('[writeProjectFile] Failed to persist to disk:', err));
+  }
+}
+
 function extractApplyEditCheckpointId(output: unknown): string | null {
   if (typeof output === 'object' && output !== null && '__checkpointId' in output) {
     const checkpointId = (output as { __checkpointId?: unknown }).__checkpointId;
     return typeof checkpointId === 'string' ? checkpointId : null;
   }
 
-  if (typeof output !== 'string') return null;
-
-  const checkpointMatch = output.match(/\[CHECKPOINT:([\w-]+)\]/);
-  return checkpointMatch?.[1] ?? null;
+  return null;
 }
 
 const EMPTY_DRAFT: AiDraft = {
@@ -109,6 +179,10 @@ const IS_DEV =
   typeof window !== 'undefined' &&
   !window.navigator.userAgent.includes('jsdom') &&
   (window.location.hostname === 'localhost' || window.location.hostname === '127.0.0.1');
+// How long apply_edit waits for the preview to re-render before giving up
+// on attaching a screenshot
+const PREVIEW_UPDATE_TIMEOUT_MS = 20_000;
+const PREVIEW_UPDATE_POLL_MS = 100;
 const VIEWER_ANNOTATION_GUIDANCE_TEXT =
   'The attached viewer screenshot includes intentional user annotations. Colored boxes, ovals, circles, and freehand marks highlight the area to focus on and are not part of the OpenSCAD geometry unless the user explicitly asks about the annotations.';
 
@@ -182,9 +256,122 @@ function getVisionWarningMessage(
   return 'This model may reject image inputs. If the request fails, switch to a vision-capable model and try again.';
 }
 
+/** The in-flight request of one conversation; conversations stream independently */
+interface ConversationStream {
+  conversationId: string;
+  abortController: AbortController;
+  activeTurn: ActiveTurnState | null;
+  /** Conversation messages before this turn */
+  committedMessages: Message[];
+  /** Null for a request resumed after a restart */
+  submittedDraft: AiDraft | null;
+  submittedReadyIds: string[];
+  pendingCheckpointId: string | null;
+  didReceiveResponse: boolean;
+  startedAt: number;
+  provider: AiProvider;
+  modelId: string;
+  transcript: AgentTranscriptState;
+  /** Last transcript save or removal, so they reach the disk in order */
+  transcriptWrite: Promise<void>;
+}
+
+interface ModelAccess {
+  apiKey: string;
+  modelOptions: CreateModelOptions;
+  /** Unset for unknown models, leaving the provider's default */
+  maxOutputTokens?: number;
+}
+
+type ConversationView = Partial<
+  Pick<
+    AiAgentState,
+    | 'messages'
+    | 'isStreaming'
+    | 'streamingResponse'
+    | 'streamingReasoning'
+    | 'currentToolCalls'
+    | 'error'
+    | 'errorObject'
+    | 'draft'
+  >
+>;
+
+/** Ready image attachments of `messages`, without their session-only preview URLs */
+function referencedImageAttachments(messages: Message[], attachments: AttachmentStore) {
+  const referenced: AttachmentStore = {};
+  for (const message of messages) {
+    if (message.type !== 'user') continue;
+    for (const part of message.parts) {
+      const attachment = part.type === 'image' ? attachments[part.attachmentId] : undefined;
+      if (attachment?.status !== 'ready' || !attachment.normalizedData) continue;
+      const { previewUrl, ...persisted } = attachment;
+      void previewUrl;
+      referenced[attachment.id] = persisted;
+    }
+  }
+  return referenced;
+}
+
+/** Attachments restored from disk, previewed from their own data */
+function restoreAttachments(attachments: AttachmentStore): AttachmentStore {
+  const restored: AttachmentStore = {};
+  for (const [id, attachment] of Object.entries(attachments)) {
+    restored[id] = {
+      ...attachment,
+      previewUrl: `data:${attachment.normalizedMimeType};base64,${attachment.normalizedData}`,
+    };
+  }
+  return restored;
+}
+
+function conversationTitle(messages: Message[]): string {
+  const firstUserMessage = messages.find(
+    (message): message is UserMessage => message.type === 'user'
+  );
+  const text = firstUserMessage ? getUserMessageText(firstUserMessage).trim() : '';
+  if (!text) return 'New conversation';
+  return text.length > 60 ? `${text.slice(0, 57)}...` : text;
+}
+
+/** Add or replace `conversation` in the background conversation list */
+function upsertConversation(conversations: Conversation[], conversation: Conversation) {
+  return [conversation, ...conversations.filter(({ id }) => id !== conversation.id)];
+}
+
+/**
+ * Apply a view update to `conversationId`: directly when it is the current
+ * conversation, otherwise only its messages, to its background entry.
+ */
+function updateConversationView(
+  prev: AiAgentState,
+  conversationId: string,
+  view: ConversationView
+): AiAgentState {
+  if (prev.currentConversationId === conversationId) {
+    return { ...prev, ...view };
+  }
+  const { messages } = view;
+  if (!messages) return prev;
+  return {
+    ...prev,
+    conversations: prev.conversations.map((conversation) =>
+      conversation.id === conversationId
+        ? {
+            ...conversation,
+            messages,
+            title: prev.conversationTitles[conversationId] ?? conversationTitle(messages),
+          }
+        : conversation
+    ),
+  };
+}
+
 export interface AiAgentState {
   isStreaming: boolean;
   streamingResponse: string | null;
+  /** Reasoning the model is streaming, while it thinks before answering */
+  streamingReasoning: string | null;
   proposedDiff: {
     diff: string;
     rationale: string;
@@ -193,8 +380,11 @@ export interface AiAgentState {
   errorObject: Error | null;
   isApplyingDiff: boolean;
   messages: Message[];
+  /** Conversations other than the current one, most recent first */
   conversations: Conversation[];
-  currentConversationId: string | null;
+  currentConversationId: string;
+  /** Conversations with a request in flight, including background ones */
+  streamingConversationIds: string[];
   currentToolCalls: ToolCall[];
   currentProvider: AiProvider;
   currentModel: string;
@@ -203,6 +393,18 @@ export interface AiAgentState {
   attachments: AttachmentStore;
   draftErrors: string[];
   isProcessingAttachments: boolean;
+  /** Requests the app quit in the middle of, which can be resumed */
+  interruptedQueries: InterruptedQuery[];
+  /** Conversations whose edits are checked against a preview screenshot */
+  previewVerificationConversationIds: string[];
+  /** Conversations whose edits wait for the user to accept them */
+  reviewEditsConversationIds: string[];
+  /** Edits held for review in the backend, oldest first */
+  pendingEdits: PendingEdit[];
+  /** Reasoning effort of each conversation that changed it from `off` */
+  reasoningEfforts: Record<string, ReasoningEffort>;
+  /** Titles a model wrote for conversations after their first exchange */
+  conversationTitles: Record<string, string>;
 }
 
 export interface AddDraftFilesResult {
@@ -217,13 +419,16 @@ interface UseAiAgentOptions {
     analytics?: ReturnType<typeof useAnalytics>;
     availableProviders?: ReturnType<typeof useAvailableProviders>;
     createModel?: typeof createModel;
+    summarizeConversation?: typeof summarizeConversation;
+    generateConversationTitle?: typeof generateConversationTitle;
     buildTools?: typeof buildTools;
     startAiStream?: typeof startAiStream;
     processAttachmentFiles?: typeof processAttachmentFiles;
     getVisionSupportForModelId?: typeof getVisionSupportForModelId;
     messagesToModelMessages?: typeof messagesToModelMessages;
     getPreferredDefaultModel?: typeof getPreferredDefaultModel;
-    historyService?: typeof historyService;
+    historyService?: Pick<typeof documentHistory, 'restoreTo'>;
+    pendingEdits?: typeof pendingEditService;
     eventBus?: typeof eventBus;
     updateSetting?: typeof updateSetting;
     loadSettings?: typeof loadSettings;
@@ -237,13 +442,17 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
   const analytics = overrides?.analytics ?? defaultAnalytics;
   const availableProviders = overrides?.availableProviders ?? defaultAvailableProviders;
   const createModelImpl = overrides?.createModel ?? createModel;
+  const summarizeConversationImpl = overrides?.summarizeConversation ?? summarizeConversation;
+  const generateConversationTitleImpl =
+    overrides?.generateConversationTitle ?? generateConversationTitle;
   const buildToolsImpl = overrides?.buildTools ?? buildTools;
   const startAiStreamImpl = overrides?.startAiStream ?? startAiStream;
   const processAttachmentFilesImpl = overrides?.processAttachmentFiles ?? processAttachmentFiles;
   const getVisionSupportForModelIdImpl =
     overrides?.getVisionSupportForModelId ?? getVisionSupportForModelId;
   const messagesToModelMessagesImpl = overrides?.messagesToModelMessages ?? messagesToModelMessages;
-  const historyServiceImpl = overrides?.historyService ?? historyService;
+  const historyServiceImpl = overrides?.historyService ?? documentHistory;
+  const pendingEditsImpl = overrides?.pendingEdits ?? pendingEditService;
   const eventBusImpl = overrides?.eventBus ?? eventBus;
   const updateSettingImpl = overrides?.updateSetting ?? updateSetting;
   const loadSettingsImpl = overrides?.loadSettings ?? loadSettings;
@@ -251,13 +460,15 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
   const [state, setState] = useState<AiAgentState>({
     isStreaming: false,
     streamingResponse: null,
+    streamingReasoning: null,
     proposedDiff: null,
     error: null,
     errorObject: null,
     isApplyingDiff: false,
     messages: [],
     conversations: [],
-    currentConversationId: null,
+    currentConversationId: createRandomId(),
+    streamingConversationIds: [],
     currentToolCalls: [],
     currentProvider: initialSelection.provider,
     currentModel: initialSelection.modelId,
@@ -266,6 +477,12 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
     attachments: {},
     draftErrors: [],
     isProcessingAttachments: false,
+    interruptedQueries: [],
+    previewVerificationConversationIds: [],
+    reviewEditsConversationIds: [],
+    pendingEdits: [],
+    reasoningEfforts: {},
+    conversationTitles: {},
   });
 
   const stateRef = useRef(state);
@@ -278,16 +495,27 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
   const previewSceneStyleRef = useRef<PreviewSceneStyle>(FALLBACK_PREVIEW_SCENE_STYLE);
   const useModelColorsRef = useRef<boolean>(loadSettingsImpl().viewer.showModelColors);
   const measurementUnitRef = useRef<MeasurementUnit>(loadSettingsImpl().viewer.measurementUnit);
-  const abortControllerRef = useRef<AbortController | null>(null);
-  const activeTurnRef = useRef<ActiveTurnState | null>(null);
+  const streamsRef = useRef(new Map<string, ConversationStream>());
+  /** Latest summary per conversation and how many leading model messages it covers */
+  const compactionsRef = useRef(new Map<string, { count: number; summary: string }>());
   const committedMessagesRef = useRef<Message[]>(state.messages);
-  const activeTurnDraftRef = useRef<{
-    submittedDraft: AiDraft;
-    submittedReadyIds: string[];
-  } | null>(null);
-  const pendingCheckpointIdRef = useRef<string | null>(null);
-  const didReceiveResponseRef = useRef(false);
-  const requestStartedAtRef = useRef<number | null>(null);
+  /** The user's per-tool timeouts, reloaded when settings change */
+  const [toolTimeouts, setToolTimeouts] = useState<Record<string, number>>({});
+
+  useEffect(() => {
+    let cancelled = false;
+    const reload = () => {
+      void loadToolTimeoutOverrides().then((timeouts) => {
+        if (!cancelled) setToolTimeouts(timeouts);
+      });
+    };
+    reload();
+    const unsubscribe = eventBus.on('settings:changed', reload);
+    return () => {
+      cancelled = true;
+      unsubscribe();
+    };
+  }, []);
 
   useEffect(() => {
     if (!state.isStreaming) {
@@ -295,6 +523,21 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
     }
   }, [state.isStreaming, state.messages]);
 
+  useEffect(() => {
+    let disposed = false;
+    let unlisten: (() => void) | undefined;
+    void pendingEditsImpl
+      .onPendingEdits((pendingEdits) => setState((prev) => ({ ...prev, pendingEdits })))
+      .then((fn) => {
+        if (disposed) fn();
+        else unlisten = fn;
+      });
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [pendingEditsImpl]);
+
   const callbacks: AiToolCallbacks = useMemo(
     () => ({
       captureCurrentView: async () => {
@@ -314,6 +557,10 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
         return state.files[normalizedPath]?.content ?? null;
       },
       getRenderTargetPath: () => getProjectState().renderTargetPath,
+      listReferenceGeometry: async () => describeReferenceGeometry(await listReferenceGeometry()),
+      searchDocs: async (query: string, source?: string) =>
+        describeDocs(query, await searchDocs(query, { source })),
+      searchSnippets: async (query: string) => describeSnippets(query, await searchSnippets(query)),
       getRenderValidationInputs: async () => {
         const state = getProjectState();
         const platform = getPlatform();
@@ -365,19 +612,12 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
         if (occurrences === 0) return 'old_string not found in the file';
         if (occurrences > 1) return `old_string found ${occurrences} times — it must be unique`;
 
-        const newContent = file.content.replace(oldString, newString);
-        getProjectStore().getState().updateFileContent(path, newContent);
-
-        // Auto-persist to disk when project is disk-backed
-        const { projectRoot } = getProjectState();
-        if (projectRoot) {
-          const platform = getPlatform();
-          void platform
-            .writeTextFile(`${projectRoot}/${path}`, newContent)
-            .then(() => getProjectStore().getState().markFileSaved(path, newContent))
-            .catch((err) => console.warn('[editProjectFile] Failed to persist to disk:', err));
-        }
-
+        writeProjectFileContent(path, file.content.replace(oldString, newString));
+        return null;
+      },
+      writeProjectFile: (path: string, content: string) => {
+        if (!(path in getProjectState().files)) return `File not found: ${path}`;
+        writeProjectFileContent(path, content);
         return null;
       },
       requestRender: (trigger: string, opts) => {
@@ -394,12 +634,36 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
         measurementUnitRef.current = unit;
         updateSettingImpl('viewer', { measurementUnit: unit });
       },
+      authorizeTool: authorizeToolCall,
+      toolTimeouts,
     }),
-    [loadSettingsImpl, updateSettingImpl]
+    [loadSettingsImpl, toolTimeouts, updateSettingImpl]
   );
 
   const tools: ToolSet = useMemo(() => buildToolsImpl(callbacks), [buildToolsImpl, callbacks]);
 
+  /** Resolve once the preview shows a new render, or false after a timeout */
+  const waitForPreviewUpdate = useCallback(async () => {
+    // The preview hands over a new capture function for every render
+    const previousCapture = capturePreviewRef.current;
+    const deadline = performance.now() + PREVIEW_UPDATE_TIMEOUT_MS;
+    while (capturePreviewRef.current === previousCapture || !capturePreviewRef.current) {
+      if (performance.now() > deadline) return false;
+      await new Promise((resolve) => setTimeout(resolve, PREVIEW_UPDATE_POLL_MS));
+    }
+    return true;
+  }, []);
+
+  const previewVerificationTools: ToolSet = useMemo(
+    () => buildToolsImpl({ ...callbacks, waitForPreviewUpdate }),
+    [buildToolsImpl, callbacks, waitForPreviewUpdate]
+  );
+
+  const reviewEditsTools: ToolSet = useMemo(
+    () => buildToolsImpl({ ...callbacks, proposeEdit: pendingEditsImpl.proposeEdit }),
+    [buildToolsImpl, callbacks, pendingEditsImpl]
+  );
+
   const updateCapturePreview = useCallback((fn: (() => Promise<string | null>) | null) => {
     capturePreviewRef.current = fn;
   }, []);
@@ -459,19 +723,92 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
     }
   }, []);
 
-  const syncActiveTurnState = useCallback((activeTurn: ActiveTurnState | null) => {
-    setState((prev) => ({
-      ...prev,
-      messages: activeTurn
-        ? [...committedMessagesRef.current, ...activeTurn.persistedMessages]
-        : committedMessagesRef.current,
-      currentToolCalls: activeTurn ? deriveCurrentToolCalls(activeTurn) : [],
-      streamingResponse: activeTurn ? deriveStreamingResponse(activeTurn) : null,
-    }));
+  const emitStreamEvent = useCallback(
+    (
+      conversationId: string,
+      status: AiStreamEvent['status'],
+      messages: Message[],
+      details: Pick<
+        AiStreamEvent,
+        'thinking' | 'toolCall' | 'args' | 'argsDelta' | 'argsText' | 'retry'
+      > = {}
+    ) => {
+      eventBusImpl.emit(`ai-stream:${conversationId}`, {
+        conversationId,
+        status,
+        messages,
+        ...details,
+      });
+    },
+    [eventBusImpl]
+  );
+
+  /**
+   * Publish the turn's progress. Reasoning and tool input deltas in `chunk`
+   * are emitted as `thinking` and `tool-args-delta` events.
+   */
+  const syncActiveTurnState = useCallback(
+    (stream: ConversationStream, chunk?: TextStreamPart<ToolSet>) => {
+      const { activeTurn } = stream;
+      const messages = activeTurn
+        ? [...stream.committedMessages, ...activeTurn.persistedMessages]
+        : stream.committedMessages;
+      setState((prev) =>
+        updateConversationView(prev, stream.conversationId, {
+          messages,
+          currentToolCalls: activeTurn ? deriveCurrentToolCalls(activeTurn) : [],
+          streamingResponse: activeTurn ? deriveStreamingResponse(activeTurn) : null,
+          streamingReasoning: activeTurn ? deriveStreamingReasoning(activeTurn) : null,
+        })
+      );
+      let toolCall: ToolCall | undefined;
+      if (chunk?.type === 'tool-input-delta' && activeTurn) {
+        const toolCallId = chunk.id;
+        toolCall = deriveCurrentToolCalls(activeTurn).find((t) => t.toolCallId === toolCallId);
+      }
+      if (chunk?.type === 'reasoning-delta') {
+        emitStreamEvent(stream.conversationId, 'thinking', messages, { thinking: chunk.text });
+      } else if (toolCall && chunk?.type === 'tool-input-delta') {
+        emitStreamEvent(stream.conversationId, 'tool-args-delta', messages, {
+          toolCall,
+          args: toolCall.args ?? {},
+          argsDelta: chunk.delta,
+          argsText: activeTurn?.pendingToolCallsById[chunk.id]?.inputText ?? chunk.delta,
+        });
+      } else {
+        emitStreamEvent(stream.conversationId, 'streaming', messages);
+      }
+    },
+    [emitStreamEvent]
+  );
+
+  /** Save the transcript of a running request so it can be resumed after a restart */
+  const persistTranscript = useCallback((stream: ConversationStream) => {
+    const messages = stream.activeTurn
+      ? [...stream.committedMessages, ...stream.activeTurn.persistedMessages]
+      : stream.committedMessages;
+    const saved = {
+      conversationId: stream.conversationId,
+      title:
+        stateRef.current.conversationTitles[stream.conversationId] ?? conversationTitle(messages),
+      provider: stream.provider,
+      modelId: stream.modelId,
+      messages,
+      attachments: referencedImageAttachments(messages, stateRef.current.attachments),
+      transcript: stream.transcript,
+    };
+    stream.transcriptWrite = stream.transcriptWrite.then(() => saveAiTranscript(saved));
+  }, []);
+
+  const discardTranscript = useCallback((stream: ConversationStream) => {
+    stream.transcriptWrite = stream.transcriptWrite.then(() =>
+      discardAiTranscript(stream.conversationId)
+    );
   }, []);
 
   const finalizeStreamTurn = useCallback(
     (
+      stream: ConversationStream,
       activeTurn: ActiveTurnState,
       options: {
         reason: 'complete' | 'cancelled' | 'error';
@@ -487,71 +824,78 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
       });
       logTurnWarnings(finalizedTurn.warnings);
 
-      const submittedDraft = activeTurnDraftRef.current?.submittedDraft;
-      const submittedReadyIds = activeTurnDraftRef.current?.submittedReadyIds ?? [];
+      const { conversationId, submittedDraft, submittedReadyIds } = stream;
+      stream.activeTurn = null;
+      streamsRef.current.delete(conversationId);
+      discardTranscript(stream);
+
+      const submittedAttachmentIds = submittedDraft?.attachmentIds ?? [];
+      const transientIds = submittedAttachmentIds.filter((id) => !submittedReadyIds.includes(id));
+      // Transient attachments are dropped from the latest state below
+      const { messages: turnMessages } = finalizeConversationTurn({
+        baseMessages: stream.committedMessages,
+        attachments: {},
+        activeTurn: finalizedTurn.state,
+        submittedAttachmentIds,
+        submittedReadyIds,
+        checkpointId: stream.pendingCheckpointId,
+      });
+      const nextMessages = options.completionNotice
+        ? [
+            ...turnMessages,
+            {
+              type: 'assistant' as const,
+              id: createRandomId(),
+              turnId: activeTurn.turnId,
+              content: options.completionNotice,
+              state: 'complete' as const,
+              timestamp: Date.now(),
+            },
+          ]
+        : turnMessages;
 
       setState((prev) => {
-        if (submittedDraft) {
-          const transientIds = submittedDraft.attachmentIds.filter(
-            (id) => !submittedReadyIds.includes(id)
-          );
-          revokePreviewUrlsForIds(transientIds, prev.attachments);
+        revokePreviewUrlsForIds(transientIds, prev.attachments);
+        const attachments = { ...prev.attachments };
+        for (const id of transientIds) {
+          delete attachments[id];
+        }
+        const isCurrent = prev.currentConversationId === conversationId;
+        if (isCurrent) {
+          committedMessagesRef.current = nextMessages;
         }
-
-        const nextConversation = finalizeConversationTurn({
-          baseMessages: committedMessagesRef.current,
-          attachments: prev.attachments,
-          activeTurn: finalizedTurn.state,
-          submittedAttachmentIds: submittedDraft?.attachmentIds ?? [],
-          submittedReadyIds,
-          checkpointId: pendingCheckpointIdRef.current,
-        });
-        const nextMessages = options.completionNotice
-          ? [
-              ...nextConversation.messages,
-              {
-                type: 'assistant' as const,
-                id: createRandomId(),
-                turnId: activeTurn.turnId,
-                content: options.completionNotice,
-                state: 'complete' as const,
-                timestamp: Date.now(),
-              },
-            ]
-          : nextConversation.messages;
-
-        committedMessagesRef.current = nextMessages;
-        activeTurnRef.current = null;
-        activeTurnDraftRef.current = null;
-        pendingCheckpointIdRef.current = null;
 
         return {
-          ...prev,
-          isStreaming: false,
-          streamingResponse: null,
-          currentToolCalls: [],
-          error: options.errorText
-            ? humanizeStreamError(options.errorText, stateRef.current.currentProvider)
-            : null,
-          errorObject: options.errorObject ?? null,
-          messages: nextMessages,
-          attachments: nextConversation.attachments,
-          draft: options.restoreDraft && submittedDraft ? submittedDraft : prev.draft,
+          ...updateConversationView(prev, conversationId, {
+            isStreaming: false,
+            streamingResponse: null,
+            streamingReasoning: null,
+            currentToolCalls: [],
+            error: options.errorText
+              ? humanizeStreamError(options.errorText, stream.provider)
+              : null,
+            errorObject: options.errorObject ?? null,
+            messages: nextMessages,
+            draft:
+              options.restoreDraft && isCurrent && submittedDraft ? submittedDraft : prev.draft,
+          }),
+          attachments,
+          streamingConversationIds: prev.streamingConversationIds.filter(
+            (id) => id !== conversationId
+          ),
         };
       });
+      emitStreamEvent(conversationId, options.reason, nextMessages);
 
-      const durationMs =
-        requestStartedAtRef.current === null
-          ? undefined
-          : Math.round(performance.now() - requestStartedAtRef.current);
-      requestStartedAtRef.current = null;
-
+      const durationMs = Math.round(performance.now() - stream.startedAt);
       const toolMessages = finalizedTurn.state.completedToolCalls;
       const toolNamesUsed = Array.from(new Set(toolMessages.map((tool) => tool.toolName))).sort();
-      const appliedEditCount = toolMessages.filter((tool) => tool.toolName === 'apply_edit').length;
+      const appliedEditCount = toolMessages.filter((tool) =>
+        EDIT_TOOL_NAMES.has(tool.toolName)
+      ).length;
       const baseProperties = {
-        provider: stateRef.current.currentProvider,
-        model_id: stateRef.current.currentModel,
+        provider: stream.provider,
+        model_id: stream.modelId,
         duration_ms: durationMs,
         tool_call_count: toolMessages.length,
         tool_names_used: toolNamesUsed,
@@ -568,9 +912,20 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
         });
       }
     },
-    [analytics, logTurnWarnings]
+    [analytics, discardTranscript, emitStreamEvent, logTurnWarnings]
   );
 
+  useEffect(() => {
+    let cancelled = false;
+    void listInterruptedAiQueries().then((interruptedQueries) => {
+      if (cancelled || interruptedQueries.length === 0) return;
+      setState((prev) => ({ ...prev, interruptedQueries }));
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
   useEffect(() => {
     return () => {
       revokePreviewUrlsForIds(
@@ -721,108 +1076,121 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
     });
   }, []);
 
-  const submitDraft = useCallback(
-    async (draftOverride?: AiDraft) => {
-      const currentState = stateRef.current;
-      const draft = draftOverride ?? currentState.draft;
-      const draftParts = draftToUserParts(draft, currentState.attachments);
-
-      if (!draftParts.length || getDraftHasPendingAttachments(draft, currentState.attachments)) {
-        return;
-      }
+  /**
+   * Once a conversation outgrows the model's context window, have a small
+   * model summarise all but the last requests, extending the previous summary
+   * rather than starting over, and publish `conversation:compacted`. Failures
+   * send the conversation as it is.
+   */
+  const compactModelMessages = useCallback(
+    async (
+      stream: ConversationStream,
+      access: ModelAccess,
+      messages: ModelMessage[]
+    ): Promise<ModelMessage[]> => {
+      const { conversationId, provider, modelId } = stream;
+      const budget = getCompactionBudget(
+        getContextWindowForModelId(modelId),
+        access.maxOutputTokens
+      );
+      const split = splitForCompaction(messages, budget);
+      if (!split) return messages;
 
-      const visionBlockMessage = getVisionBlockMessage(
-        draft,
-        currentState.attachments,
-        currentState.currentModelVisionSupport
+      const previous = compactionsRef.current.get(conversationId);
+      const extendsPrevious = previous !== undefined && previous.count <= split.older.length;
+      const olderTranscript = transcriptForSummary(
+        extendsPrevious ? split.older.slice(previous.count) : split.older
       );
-      if (visionBlockMessage) {
-        setState((prev) => ({
-          ...prev,
-          draftErrors: [visionBlockMessage],
-        }));
-        return;
-      }
+      const transcript = extendsPrevious
+        ? `Summary so far:\n${previous.summary}\n\nLater messages:\n${olderTranscript}`
+        : olderTranscript;
 
-      const provider = currentState.currentProvider;
-      const modelOptions: CreateModelOptions = {};
-      let apiKey = getApiKey(provider);
+      const summaryModelId = getSmallModelId(provider, modelId);
+      try {
+        const model =
+          provider === 'openai-compatible' || provider === 'azure-openai'
+            ? createModelImpl(provider, access.apiKey, summaryModelId, access.modelOptions)
+            : createModelImpl(provider, access.apiKey, summaryModelId);
+        const summary =
+          extendsPrevious && previous.count === split.older.length
+            ? previous.summary
+            : await summarizeConversationImpl(model, transcript);
+        compactionsRef.current.set(conversationId, { count: split.older.length, summary });
+
+        const compacted = applyCompactionSummary(summary, split.recent);
+        eventBusImpl.emit('conversation:compacted', {
+          conversationId,
+          summarizedMessages: split.older.length,
+          tokensBefore: estimateTokens(messages),
+          tokensAfter: estimateTokens(compacted),
+        });
+        return compacted;
+      } catch (error) {
+        console.warn('[useAiAgent] Failed to compact conversation:', error);
+        return messages;
+      }
+    },
+    [createModelImpl, eventBusImpl, summarizeConversationImpl]
+  );
 
-      if (provider === 'openai-compatible') {
-        const config = getOpenAiCompatibleConfig();
-        modelOptions.baseUrl = config.baseUrl;
-        apiKey = config.apiKey ?? 'local';
+  /**
+   * Have a small model title a conversation after its first exchange, then
+   * publish the title as `conversation:titled`. Failures keep the title taken
+   * from the first message.
+   */
+  const titleConversation = useCallback(
+    async (stream: ConversationStream, access: ModelAccess, reply: string | null) => {
+      const { conversationId, provider } = stream;
+      const firstUserMessage = stream.committedMessages.find(
+        (message): message is UserMessage => message.type === 'user'
+      );
+      const request = firstUserMessage ? getUserMessageText(firstUserMessage).trim() : '';
+      if (!request && !reply) return;
 
-        if (!config.baseUrl || !currentState.currentModel.trim()) {
-          setState((prev) => ({
-            ...prev,
-            error: 'Configure an OpenAI-compatible provider in Settings first',
-          }));
-          return;
-        }
-      }
+      const modelId = getSmallModelId(provider, stream.modelId);
+      try {
+        const model =
+          provider === 'openai-compatible' || provider === 'azure-openai'
+            ? createModelImpl(provider, access.apiKey, modelId, access.modelOptions)
+            : createModelImpl(provider, access.apiKey, modelId);
+        const title = await generateConversationTitleImpl(model, request, reply);
+        if (!title) return;
 
-      if (!apiKey) {
         setState((prev) => ({
           ...prev,
-          error: 'Please set your API key in Settings first',
+          conversationTitles: { ...prev.conversationTitles, [conversationId]: title },
+          conversations: prev.conversations.map((conversation) =>
+            conversation.id === conversationId ? { ...conversation, title } : conversation
+          ),
         }));
-        return;
+        eventBusImpl.emit('conversation:titled', { conversationId, title });
+      } catch (error) {
+        console.warn('[useAiAgent] Failed to title conversation:', error);
       }
+    },
+    [createModelImpl, eventBusImpl, generateConversationTitleImpl]
+  );
 
-      const userMessage: UserMessage = {
-        type: 'user',
-        id: createRandomId(),
-        parts: draftParts,
-        timestamp: Date.now(),
-      };
-
-      const updatedMessages = [...currentState.messages, userMessage];
-      const submittedDraft = draft;
-      const submittedReadyIds = getReadyAttachmentIds(draft, currentState.attachments);
-      const turnId = createRandomId();
-      const activeTurn = createActiveTurnState(turnId, userMessage.id);
-
-      committedMessagesRef.current = updatedMessages;
-      activeTurnRef.current = activeTurn;
-      activeTurnDraftRef.current = {
-        submittedDraft,
-        submittedReadyIds,
-      };
-      setState((prev) => ({
-        ...prev,
-        isStreaming: true,
-        streamingResponse: null,
-        error: null,
-        messages: updatedMessages,
-        currentToolCalls: [],
-        draft: EMPTY_DRAFT,
-        draftErrors: [],
-      }));
-      pendingCheckpointIdRef.current = null;
-      didReceiveResponseRef.current = false;
-      requestStartedAtRef.current = performance.now();
-      analytics.track('ai request submitted', {
-        provider,
-        model_id: currentState.currentModel,
-        attachment_count: submittedReadyIds.length,
-        has_project_files: callbacks.listProjectFiles().length > 0,
-        prompt_length_bucket: bucketCount(draft.text.trim().length, [20, 80, 200, 500]),
-        conversation_length_bucket: bucketCount(updatedMessages.length, [2, 5, 10, 20]),
-      });
-
-      const abortController = new AbortController();
-      abortControllerRef.current = abortController;
-
+  /**
+   * Drive a request's tool loop until it finishes, fails or is cancelled.
+   * `loadModelMessages` runs inside the error handling, so a conversation
+   * that cannot be converted ends the turn like any other failure.
+   */
+  const runStream = useCallback(
+    async (
+      stream: ConversationStream,
+      access: ModelAccess,
+      loadModelMessages: () => ModelMessage[]
+    ) => {
+      const { abortController } = stream;
       try {
+        const { provider, modelId } = stream;
         const model =
-          provider === 'openai-compatible'
-            ? createModelImpl(provider, apiKey, currentState.currentModel, modelOptions)
-            : createModelImpl(provider, apiKey, currentState.currentModel);
-        const modelMessages = messagesToModelMessagesImpl(
-          updatedMessages,
-          currentState.attachments
-        );
+          provider === 'openai-compatible' || provider === 'azure-openai'
+            ? createModelImpl(provider, access.apiKey, modelId, access.modelOptions)
+            : createModelImpl(provider, access.apiKey, modelId);
+        const modelMessages = await compactModelMessages(stream, access, loadModelMessages());
+        stream.transcript = createAgentTranscriptState(modelMessages);
 
         const measurementUnit = callbacks.getMeasurementUnit();
         const unitLabels: Record<MeasurementUnit, string> = {
@@ -833,14 +1201,61 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
         };
         const dynamicSystem = `${SYSTEM_PROMPT}\n\nCurrent measurement unit: ${measurementUnit} (${unitLabels[measurementUnit]}) — all displayed dimensions use this unit`;
 
-        const result = await startAiStreamImpl({
-          model,
-          system: dynamicSystem,
-          messages: modelMessages,
-          tools,
-          stopWhen: stepCountIs(MAX_AGENT_STEPS),
-          abortSignal: abortController.signal,
-        });
+        const system = await getEffectiveSystemPrompt(dynamicSystem);
+        const verifyEdits = stateRef.current.previewVerificationConversationIds.includes(
+          stream.conversationId
+        );
+        const reviewEdits =
+          pendingEditsImpl.isEditReviewAvailable() &&
+          stateRef.current.reviewEditsConversationIds.includes(stream.conversationId);
+        // Transcripts keep no thinking blocks, which Claude needs to continue a
+        // tool loop with thinking on, so resumed requests run without reasoning
+        const reasoningEffort =
+          stream.submittedDraft === null
+            ? 'off'
+            : (stateRef.current.reasoningEfforts[stream.conversationId] ?? 'off');
+        // apply_edit links its checkpoints to the message that started the request
+        const messageIndex = stream.committedMessages
+          .map((message) => message.type)
+          .lastIndexOf('user');
+        const conversationLink: ConversationLink | undefined =
+          messageIndex === -1
+            ? undefined
+            : {
+                conversationId: stream.conversationId,
+                messageId: stream.committedMessages[messageIndex].id,
+                messageIndex,
+              };
+        const requestOptions = getReasoningRequestOptions(
+          provider,
+          modelId,
+          reasoningEffort,
+          access.maxOutputTokens
+        );
+
+        const result = await startAiStreamImpl(
+          {
+            model,
+            system,
+            messages: modelMessages,
+            // Reviewed edits are not rendered until accepted, so there is no
+            // preview to verify them against
+            tools: reviewEdits ? reviewEditsTools : verifyEdits ? previewVerificationTools : tools,
+            stopWhen: stepCountIs(MAX_AGENT_STEPS),
+            ...requestOptions,
+            experimental_context: conversationLink,
+            abortSignal: abortController.signal,
+          },
+          {
+            onRetry: (retry) => {
+              console.warn('[useAiAgent] Retrying model call:', retry);
+              const messages = stream.activeTurn
+                ? [...stream.committedMessages, ...stream.activeTurn.persistedMessages]
+                : stream.committedMessages;
+              emitStreamEvent(stream.conversationId, 'retrying', messages, { retry });
+            },
+          }
+        );
 
         let streamErrorText: string | null = null;
         let streamErrorObject: Error | null = null;
@@ -863,30 +1278,36 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
             chunk.type === 'tool-error' ||
             chunk.type === 'tool-output-denied'
           ) {
-            didReceiveResponseRef.current = true;
+            stream.didReceiveResponse = true;
           }
 
-          const currentActiveTurn = activeTurnRef.current;
+          const currentActiveTurn = stream.activeTurn;
           if (!currentActiveTurn) break;
 
           const turnUpdate = reduceActiveTurnChunk(currentActiveTurn, chunk);
-          activeTurnRef.current = turnUpdate.state;
+          stream.activeTurn = turnUpdate.state;
           logTurnWarnings(turnUpdate.warnings);
 
+          const transcriptUpdate = reduceTranscriptChunk(stream.transcript, chunk);
+          stream.transcript = transcriptUpdate.state;
+          if (transcriptUpdate.shouldPersist) {
+            persistTranscript(stream);
+          }
+
           if (
             chunk.type === 'tool-result' &&
-            chunk.toolName === 'apply_edit' &&
-            pendingCheckpointIdRef.current === null
+            EDIT_TOOL_NAMES.has(chunk.toolName) &&
+            stream.pendingCheckpointId === null
           ) {
             // The restore button is turn-scoped: it should return to the code
             // from before this user request, not before the last edit in the turn.
             const checkpointId = extractApplyEditCheckpointId(chunk.output);
             if (checkpointId) {
-              pendingCheckpointIdRef.current = checkpointId;
+              stream.pendingCheckpointId = checkpointId;
             }
           }
 
-          syncActiveTurnState(turnUpdate.state);
+          syncActiveTurnState(stream, chunk);
 
           if (chunk.type === 'error') {
             streamErrorText = extractErrorText(chunk.error);
@@ -906,18 +1327,32 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
           return;
         }
 
-        if (activeTurnRef.current) {
+        if (stream.activeTurn) {
           const completionNotice =
             !streamErrorText && streamFinishReason === 'tool-calls'
               ? `Stopped before the final AI summary because the tool step budget (${MAX_AGENT_STEPS}) was reached.`
               : null;
-          finalizeStreamTurn(activeTurnRef.current, {
+          const lastAssistantMessage = [...stream.activeTurn.persistedMessages]
+            .reverse()
+            .find((message): message is AssistantMessage => message.type === 'assistant');
+          const reply =
+            deriveStreamingResponse(stream.activeTurn) ?? lastAssistantMessage?.content ?? null;
+          const isFirstExchange =
+            stream.committedMessages.filter((message) => message.type === 'user').length === 1;
+          finalizeStreamTurn(stream, stream.activeTurn, {
             reason: streamErrorText ? 'error' : 'complete',
             errorText: streamErrorText,
             errorObject: streamErrorObject,
-            restoreDraft: Boolean(streamErrorText) && !didReceiveResponseRef.current,
+            restoreDraft: Boolean(streamErrorText) && !stream.didReceiveResponse,
             completionNotice,
           });
+          if (
+            !streamErrorText &&
+            isFirstExchange &&
+            !stateRef.current.conversationTitles[stream.conversationId]
+          ) {
+            void titleConversation(stream, access, reply);
+          }
         }
       } catch (error) {
         if (abortController.signal.aborted) {
@@ -929,41 +1364,190 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
         const errorText = extractErrorText(error);
         const errorObject = error instanceof Error ? error : new Error(errorText);
 
-        if (activeTurnRef.current) {
-          finalizeStreamTurn(activeTurnRef.current, {
+        if (stream.activeTurn) {
+          finalizeStreamTurn(stream, stream.activeTurn, {
             reason: 'error',
             errorText,
             errorObject,
-            restoreDraft: !didReceiveResponseRef.current,
+            restoreDraft: !stream.didReceiveResponse,
           });
-        } else {
-          setState((prev) => ({
-            ...prev,
-            error: humanizeStreamError(errorText, currentState.currentProvider),
-            errorObject,
-            isStreaming: false,
-            streamingResponse: null,
-            currentToolCalls: [],
-            draft: didReceiveResponseRef.current ? prev.draft : submittedDraft,
-          }));
         }
       } finally {
-        abortControllerRef.current = null;
+        if (streamsRef.current.get(stream.conversationId) === stream) {
+          streamsRef.current.delete(stream.conversationId);
+        }
       }
     },
     [
-      analytics,
       callbacks,
+      compactModelMessages,
       createModelImpl,
+      emitStreamEvent,
       finalizeStreamTurn,
       logTurnWarnings,
-      messagesToModelMessagesImpl,
+      pendingEditsImpl,
+      persistTranscript,
+      previewVerificationTools,
+      reviewEditsTools,
       startAiStreamImpl,
       syncActiveTurnState,
+      titleConversation,
       tools,
     ]
   );
 
+  /** API key and options for a provider and model, or why requests cannot be sent */
+  const resolveModelAccess = useCallback(
+    (provider: AiProvider, modelId: string): ModelAccess | { error: string } => {
+      if (getToolSupportForModelId(modelId) === 'no') {
+        return {
+          error: `${modelId} can't use tools, which the assistant needs to read and edit code. Choose another model.`,
+        };
+      }
+
+      const modelOptions: CreateModelOptions = {};
+      let apiKey = getApiKey(provider);
+
+      if (provider === 'openai-compatible') {
+        const config = getOpenAiCompatibleConfig();
+        modelOptions.baseUrl = config.baseUrl;
+        apiKey = config.apiKey ?? 'local';
+
+        if (!config.baseUrl || !modelId.trim()) {
+          return { error: 'Configure an OpenAI-compatible provider in Settings first' };
+        }
+      }
+
+      if (provider === 'azure-openai') {
+        const config = getAzureOpenAiConfig();
+        modelOptions.baseUrl = config.endpoint;
+
+        if (!config.endpoint || !modelId.trim()) {
+          return { error: 'Configure an Azure OpenAI endpoint and deployment in Settings first' };
+        }
+      }
+
+      if (isCloudAiProvider(provider)) {
+        if (!isProviderConfigured(provider)) {
+          return {
+            error: `No ${AI_PROVIDER_LABELS[provider]} credentials found; check Settings → AI`,
+          };
+        }
+        apiKey = 'cloud';
+      }
+
+      if (!apiKey) {
+        return { error: 'Please set your API key in Settings first' };
+      }
+      const modelLimit = getMaxOutputTokensForModelId(modelId);
+      const maxOutputTokens =
+        modelLimit === undefined ? undefined : Math.min(modelLimit, MAX_AGENT_OUTPUT_TOKENS);
+      return { apiKey, modelOptions, maxOutputTokens };
+    },
+    []
+  );
+
+  const submitDraft = useCallback(
+    async (draftOverride?: AiDraft) => {
+      const currentState = stateRef.current;
+      const conversationId = currentState.currentConversationId;
+      const draft = draftOverride ?? currentState.draft;
+      const draftParts = draftToUserParts(draft, currentState.attachments);
+
+      if (streamsRef.current.has(conversationId)) {
+        return;
+      }
+
+      if (!draftParts.length || getDraftHasPendingAttachments(draft, currentState.attachments)) {
+        return;
+      }
+
+      const visionBlockMessage = getVisionBlockMessage(
+        draft,
+        currentState.attachments,
+        currentState.currentModelVisionSupport
+      );
+      if (visionBlockMessage) {
+        setState((prev) => ({
+          ...prev,
+          draftErrors: [visionBlockMessage],
+        }));
+        return;
+      }
+
+      const provider = currentState.currentProvider;
+      const access = resolveModelAccess(provider, currentState.currentModel);
+      if ('error' in access) {
+        setState((prev) => ({ ...prev, error: access.error }));
+        return;
+      }
+
+      const userMessage: UserMessage = {
+        type: 'user',
+        id: createRandomId(),
+        parts: draftParts,
+        timestamp: Date.now(),
+      };
+
+      const updatedMessages = [...currentState.messages, userMessage];
+      const submittedDraft = draft;
+      const submittedReadyIds = getReadyAttachmentIds(draft, currentState.attachments);
+      const turnId = createRandomId();
+      const abortController = new AbortController();
+      const stream: ConversationStream = {
+        conversationId,
+        abortController,
+        activeTurn: createActiveTurnState(turnId, userMessage.id),
+        committedMessages: updatedMessages,
+        submittedDraft,
+        submittedReadyIds,
+        pendingCheckpointId: null,
+        didReceiveResponse: false,
+        startedAt: performance.now(),
+        provider,
+        modelId: currentState.currentModel,
+        transcript: createAgentTranscriptState([]),
+        transcriptWrite: Promise.resolve(),
+      };
+
+      committedMessagesRef.current = updatedMessages;
+      streamsRef.current.set(conversationId, stream);
+      setState((prev) => ({
+        ...prev,
+        isStreaming: true,
+        streamingResponse: null,
+        streamingReasoning: null,
+        error: null,
+        messages: updatedMessages,
+        currentToolCalls: [],
+        draft: EMPTY_DRAFT,
+        draftErrors: [],
+        streamingConversationIds: [...prev.streamingConversationIds, conversationId],
+      }));
+      emitStreamEvent(conversationId, 'streaming', updatedMessages);
+      analytics.track('ai request submitted', {
+        provider,
+        model_id: currentState.currentModel,
+        attachment_count: submittedReadyIds.length,
+        has_project_files: callbacks.listProjectFiles().length > 0,
+        prompt_length_bucket: bucketCount(draft.text.trim().length, [20, 80, 200, 500]),
+        conversation_length_bucket: bucketCount(updatedMessages.length, [2, 5, 10, 20]),
+      });
+
+      await runStream(stream, access, () =>
+        messagesToModelMessagesImpl(updatedMessages, currentState.attachments)
+      );
+    },
+    [
+      analytics,
+      callbacks,
+      emitStreamEvent,
+      messagesToModelMessagesImpl,
+      resolveModelAccess,
+      runStream,
+    ]
+  );
+
   const submitPrompt = useCallback(
     async (prompt: string) => {
       await submitDraft({ text: prompt, attachmentIds: [] });
@@ -971,28 +1555,69 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
     [submitDraft]
   );
 
-  const cancelStream = useCallback(() => {
-    if (IS_DEV) console.log('[useAiAgent] Cancelling stream...');
-    if (abortControllerRef.current) {
-      abortControllerRef.current.abort();
-    }
-    const activeTurn = activeTurnRef.current;
-    if (activeTurn) {
-      finalizeStreamTurn(activeTurn, { reason: 'cancelled' });
-      return;
-    }
-    setState((prev) => ({
-      ...prev,
-      isStreaming: false,
-      streamingResponse: null,
-      currentToolCalls: [],
-    }));
-    pendingCheckpointIdRef.current = null;
-  }, [finalizeStreamTurn]);
+  /** Cancel the request of `conversationId` (the current conversation by default) */
+  const cancelStream = useCallback(
+    (conversationId: string = stateRef.current.currentConversationId) => {
+      if (IS_DEV) console.log('[useAiAgent] Cancelling stream...', conversationId);
+      const stream = streamsRef.current.get(conversationId);
+      stream?.abortController.abort();
+      if (stream?.activeTurn) {
+        finalizeStreamTurn(stream, stream.activeTurn, { reason: 'cancelled' });
+        return;
+      }
+      streamsRef.current.delete(conversationId);
+      setState((prev) => ({
+        ...updateConversationView(prev, conversationId, {
+          isStreaming: false,
+          streamingResponse: null,
+          streamingReasoning: null,
+          currentToolCalls: [],
+        }),
+        streamingConversationIds: prev.streamingConversationIds.filter(
+          (id) => id !== conversationId
+        ),
+      }));
+    },
+    [finalizeStreamTurn]
+  );
 
   const acceptDiff = useCallback(() => {}, []);
   const rejectDiff = useCallback(() => {}, []);
 
+  /**
+   * Apply a pending edit to its file as it is now and re-render. Returns
+   * null on success, or why it no longer applies; either way it stops
+   * being pending.
+   */
+  const acceptPendingEdit = useCallback(
+    async (id: string): Promise<string | null> => {
+      try {
+        const { projectPath, code } = await pendingEditsImpl.acceptPendingEdit(id);
+        if (projectPath) {
+          callbacks.writeProjectFile(projectPath, code);
+          if (projectPath === callbacks.getRenderTargetPath()) {
+            eventBusImpl.emit('code-updated', { code, source: 'ai' });
+          }
+        }
+        callbacks.requestRender('ai_edit', { immediate: true });
+        return null;
+      } catch (error) {
+        return error instanceof Error ? error.message : String(error);
+      }
+    },
+    [callbacks, eventBusImpl, pendingEditsImpl]
+  );
+
+  /** Discard a pending edit without touching its file */
+  const rejectPendingEdit = useCallback(
+    (id: string) => {
+      void pendingEditsImpl.rejectPendingEdit(id).catch((error) => {
+        console.error('[useAiAgent] Failed to reject pending edit:', error);
+      });
+    },
+    [pendingEditsImpl]
+  );
+
   const clearError = useCallback(() => {
     setState((prev) => ({ ...prev, error: null, errorObject: null }));
   }, []);
@@ -1020,6 +1645,86 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
     [analytics, getVisionSupportForModelIdImpl]
   );
 
+  /** Current conversation as a background entry, or null when it is empty */
+  const snapshotCurrentConversation = useCallback((current: AiAgentState) => {
+    if (current.messages.length === 0) return null;
+    return {
+      id: current.currentConversationId,
+      title:
+        current.conversationTitles[current.currentConversationId] ??
+        conversationTitle(current.messages),
+      timestamp: Date.now(),
+      messages: current.messages,
+    } satisfies Conversation;
+  }, []);
+
+  /** Save the current conversation so it can be searched and reopened (desktop) */
+  const saveConversation = useCallback(async () => {
+    const current = stateRef.current;
+    const conversation = snapshotCurrentConversation(current);
+    if (!conversation) return;
+    await storeConversation(
+      conversation,
+      referencedImageAttachments(conversation.messages, current.attachments)
+    );
+  }, [snapshotCurrentConversation]);
+
+  /** Write the current conversation to `path` as JSON or Markdown (desktop) */
+  const exportConversation = useCallback(
+    async (format: ConversationExportFormat, path: string) => {
+      const current = stateRef.current;
+      const conversation = snapshotCurrentConversation(current);
+      if (!conversation) return;
+      await writeConversationExport(
+        conversation,
+        referencedImageAttachments(conversation.messages, current.attachments),
+        format,
+        path
+      );
+    },
+    [snapshotCurrentConversation]
+  );
+
+  /**
+   * Open a JSON export as the current conversation, with its tool calls,
+   * checkpoints and images. The conversation it replaces moves to the
+   * background.
+   */
+  const importConversation = useCallback(
+    async (path: string) => {
+      const imported = await readConversationExport(path);
+      const { conversation } = imported;
+      committedMessagesRef.current = conversation.messages;
+      setState((prev) => {
+        const previous = snapshotCurrentConversation(prev);
+        const conversations = prev.conversations.filter(({ id }) => id !== conversation.id);
+        return {
+          ...prev,
+          conversations:
+            previous && previous.id !== conversation.id
+              ? upsertConversation(conversations, previous)
+              : conversations,
+          conversationTitles: { ...prev.conversationTitles, [conversation.id]: conversation.title },
+          currentConversationId: conversation.id,
+          messages: conversation.messages,
+          attachments: { ...prev.attachments, ...restoreAttachments(imported.attachments) },
+          isStreaming: false,
+          streamingResponse: null,
+          streamingReasoning: null,
+          currentToolCalls: [],
+          error: null,
+          errorObject: null,
+          draftErrors: [],
+        };
+      });
+    },
+    [snapshotCurrentConversation]
+  );
+
+  /**
+   * Start a new conversation. A request still running in the current one
+   * keeps going in the background.
+   */
   const newConversation = useCallback(() => {
     const currentState = stateRef.current;
     analytics.track('conversation started', {
@@ -1029,53 +1734,254 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
       draft_attachment_count: currentState.draft.attachmentIds.length,
       previous_message_count_bucket: bucketCount(currentState.messages.length, [1, 3, 8, 20]),
     });
-    activeTurnRef.current = null;
-    activeTurnDraftRef.current = null;
     committedMessagesRef.current = [];
-    pendingCheckpointIdRef.current = null;
     setState((prev) => {
-      revokePreviewUrlsForIds(Object.keys(prev.attachments), prev.attachments);
+      // Attachments shown in the previous conversation stay with it
+      const removableIds = getUnreferencedAttachmentIds(prev.draft.attachmentIds, prev.messages);
+      revokePreviewUrlsForIds(removableIds, prev.attachments);
+      const nextAttachments = { ...prev.attachments };
+      for (const id of removableIds) {
+        delete nextAttachments[id];
+      }
+      const previous = snapshotCurrentConversation(prev);
+
       return {
         ...prev,
+        conversations: previous
+          ? upsertConversation(prev.conversations, previous)
+          : prev.conversations,
+        currentConversationId: createRandomId(),
+        isStreaming: false,
         messages: [],
-        attachments: {},
+        attachments: nextAttachments,
         draft: EMPTY_DRAFT,
         draftErrors: [],
         streamingResponse: null,
+        streamingReasoning: null,
         error: null,
         errorObject: null,
         currentToolCalls: [],
       };
     });
-  }, [analytics]);
+  }, [analytics, snapshotCurrentConversation]);
+
+  /** Switch to a background conversation, which may still be streaming */
+  const loadConversation = useCallback(
+    (conversationId: string) => {
+      const target = stateRef.current.conversations.find(({ id }) => id === conversationId);
+      if (!target) return;
+      const stream = streamsRef.current.get(conversationId);
+      const activeTurn = stream?.activeTurn ?? null;
+      committedMessagesRef.current = stream ? stream.committedMessages : target.messages;
+
+      setState((prev) => {
+        const previous = snapshotCurrentConversation(prev);
+        const conversations = prev.conversations.filter(({ id }) => id !== conversationId);
+        return {
+          ...prev,
+          conversations: previous ? upsertConversation(conversations, previous) : conversations,
+          currentConversationId: conversationId,
+          messages: target.messages,
+          isStreaming: Boolean(stream),
+          streamingResponse: activeTurn ? deriveStreamingResponse(activeTurn) : null,
+          streamingReasoning: activeTurn ? deriveStreamingReasoning(activeTurn) : null,
+          currentToolCalls: activeTurn ? deriveCurrentToolCalls(activeTurn) : [],
+          error: null,
+          errorObject: null,
+          draftErrors: [],
+        };
+      });
+    },
+    [snapshotCurrentConversation]
+  );
 
   const handleRestoreCheckpoint = useCallback(
     (checkpointId: string, truncatedMessages: Message[]) => {
       if (IS_DEV) console.log('[useAiAgent] Restoring checkpoint:', checkpointId);
 
-      const checkpoint = historyServiceImpl.restoreTo(checkpointId);
-      if (checkpoint) {
-        eventBusImpl.emit('code-updated', { code: checkpoint.code, source: 'history' });
-        eventBusImpl.emit('history:restore', { code: checkpoint.code });
-      } else {
-        console.error('[useAiAgent] Failed to restore checkpoint: not found', checkpointId);
-      }
+      void historyServiceImpl.restoreTo(checkpointId).then((checkpoint) => {
+        // A checkpoint of another project file was written back to that file, not the editor
+        if (checkpoint && !checkpoint.file_path) {
+          eventBusImpl.emit('code-updated', { code: checkpoint.code, source: 'history' });
+          eventBusImpl.emit('history:restore', { code: checkpoint.code });
+        } else if (!checkpoint) {
+          console.error('[useAiAgent] Failed to restore checkpoint: not found', checkpointId);
+        }
+      });
 
+      // A request still running in this conversation is dropped with the
+      // messages after the checkpoint
+      const conversationId = stateRef.current.currentConversationId;
+      const stream = streamsRef.current.get(conversationId);
+      if (stream) {
+        stream.activeTurn = null;
+        stream.abortController.abort();
+        streamsRef.current.delete(conversationId);
+        discardTranscript(stream);
+      }
       committedMessagesRef.current = truncatedMessages;
-      activeTurnRef.current = null;
-      activeTurnDraftRef.current = null;
 
       setState((prev) => ({
         ...prev,
         messages: truncatedMessages,
+        isStreaming: false,
+        streamingResponse: null,
+        streamingReasoning: null,
+        currentToolCalls: [],
+        streamingConversationIds: prev.streamingConversationIds.filter(
+          (id) => id !== conversationId
+        ),
       }));
       analytics.track('checkpoint restored', {
         had_later_messages: stateRef.current.messages.length > truncatedMessages.length,
       });
     },
-    [analytics, eventBusImpl, historyServiceImpl]
+    [analytics, discardTranscript, eventBusImpl, historyServiceImpl]
+  );
+
+  /**
+   * Continue a request the app quit in the middle of, from its last
+   * completed tool call. The conversation becomes the current one.
+   */
+  const resumeInterruptedQuery = useCallback(
+    async (conversationId: string) => {
+      if (streamsRef.current.has(conversationId)) return;
+
+      let resumed: ResumedQuery;
+      try {
+        resumed = await resumeAiQuery(conversationId);
+      } catch (error) {
+        setState((prev) => ({
+          ...prev,
+          error: `Could not resume the interrupted request: ${extractErrorText(error)}`,
+        }));
+        return;
+      }
+
+      const provider = resumed.provider as AiProvider;
+      const access = resolveModelAccess(provider, resumed.modelId);
+      if ('error' in access) {
+        setState((prev) => ({ ...prev, error: access.error }));
+        return;
+      }
+
+      const messages = resumed.messages;
+      const userMessage = [...messages]
+        .reverse()
+        .find((message): message is UserMessage => message.type === 'user');
+      const stream: ConversationStream = {
+        conversationId,
+        abortController: new AbortController(),
+        activeTurn: createActiveTurnState(createRandomId(), userMessage?.id ?? createRandomId()),
+        committedMessages: messages,
+        submittedDraft: null,
+        submittedReadyIds: [],
+        pendingCheckpointId: userMessage?.checkpointId ?? null,
+        didReceiveResponse: false,
+        startedAt: performance.now(),
+        provider,
+        modelId: resumed.modelId,
+        transcript: createAgentTranscriptState([]),
+        transcriptWrite: Promise.resolve(),
+      };
+
+      committedMessagesRef.current = messages;
+      streamsRef.current.set(conversationId, stream);
+      setState((prev) => {
+        const previous =
+          prev.currentConversationId === conversationId ? null : snapshotCurrentConversation(prev);
+        const conversations = prev.conversations.filter(({ id }) => id !== conversationId);
+        return {
+          ...prev,
+          conversations: previous ? upsertConversation(conversations, previous) : conversations,
+          currentConversationId: conversationId,
+          messages,
+          attachments: { ...prev.attachments, ...restoreAttachments(resumed.attachments) },
+          isStreaming: true,
+          streamingResponse: null,
+          streamingReasoning: null,
+          currentToolCalls: [],
+          error: null,
+          errorObject: null,
+          draftErrors: [],
+          streamingConversationIds: [...prev.streamingConversationIds, conversationId],
+          interruptedQueries: prev.interruptedQueries.filter(
+            (query) => query.conversationId !== conversationId
+          ),
+        };
+      });
+      emitStreamEvent(conversationId, 'streaming', messages);
+      analytics.track('ai request resumed', {
+        provider,
+        model_id: resumed.modelId,
+        conversation_length_bucket: bucketCount(messages.length, [2, 5, 10, 20]),
+      });
+
+      await runStream(stream, access, () => resumed.apiMessages);
+    },
+    [analytics, emitStreamEvent, resolveModelAccess, runStream, snapshotCurrentConversation]
   );
 
+  /**
+   * Have `apply_edit` in the current conversation wait for the re-rendered
+   * preview and attach a screenshot, so the model can check its own change.
+   * Applies from the conversation's next request.
+   */
+  const setVerifyEditsWithPreview = useCallback((enabled: boolean) => {
+    setState((prev) => {
+      const ids = prev.previewVerificationConversationIds.filter(
+        (id) => id !== prev.currentConversationId
+      );
+      return {
+        ...prev,
+        previewVerificationConversationIds: enabled ? [...ids, prev.currentConversationId] : ids,
+      };
+    });
+  }, []);
+
+  /**
+   * Hold the current conversation's edits for the user to accept or reject
+   * instead of writing them straight away. Applies from the conversation's
+   * next request.
+   */
+  const setReviewEdits = useCallback((enabled: boolean) => {
+    setState((prev) => {
+      const ids = prev.reviewEditsConversationIds.filter(
+        (id) => id !== prev.currentConversationId
+      );
+      return {
+        ...prev,
+        reviewEditsConversationIds: enabled ? [...ids, prev.currentConversationId] : ids,
+      };
+    });
+  }, []);
+
+  /** Reasoning effort for the current conversation's next requests */
+  const setReasoningEffort = useCallback((effort: ReasoningEffort) => {
+    setState((prev) => {
+      const reasoningEfforts = { ...prev.reasoningEfforts };
+      if (effort === 'off') {
+        delete reasoningEfforts[prev.currentConversationId];
+      } else {
+        reasoningEfforts[prev.currentConversationId] = effort;
+      }
+      return { ...prev, reasoningEfforts };
+    });
+  }, []);
+
+  /** Forget an interrupted request instead of resuming it */
+  const discardInterruptedQuery = useCallback((conversationId: string) => {
+    void discardAiTranscript(conversationId);
+    setState((prev) => ({
+      ...prev,
+      interruptedQueries: prev.interruptedQueries.filter(
+        (query) => query.conversationId !== conversationId
+      ),
+    }));
+  }, []);
+
+  const canReviewEdits = pendingEditsImpl.isEditReviewAvailable();
+
   return {
     ...state,
     availableProviders,
@@ -1084,10 +1990,26 @@ export function useAiAgent(options: UseAiAgentOptions = {}) {
     cancelStream,
     acceptDiff,
     rejectDiff,
+    acceptPendingEdit,
+    rejectPendingEdit,
     clearError,
     newConversation,
-    loadConversation: () => {},
-    saveConversation: async () => {},
+    loadConversation,
+    resumeInterruptedQuery,
+    discardInterruptedQuery,
+    verifyEditsWithPreview: state.previewVerificationConversationIds.includes(
+      state.currentConversationId
+    ),
+    setVerifyEditsWithPreview,
+    // Only a window mirroring its tabs into backend documents can hold edits for review
+    reviewEdits:
+      canReviewEdits && state.reviewEditsConversationIds.includes(state.currentConversationId),
+    setReviewEdits: canReviewEdits ? setReviewEdits : undefined,
+    reasoningEffort: state.reasoningEfforts[state.currentConversationId] ?? 'off',
+    setReasoningEffort,
+    saveConversation,
+    exportConversation,
+    importConversation,
     setCurrentModel,
     loadModelAndProviders,
     handleRestoreCheckpoint,
@@ -1,6 +1,8 @@
 import { useState, useCallback, useEffect } from 'react';
-import { historyService, eventBus } from '../platform';
+import { eventBus } from '../platform';
 import type { EditorCheckpoint, CheckpointDiff } from '../platform';
+import * as documentHistory from '../services/documentHistory';
+import { useWorkspaceStore } from '../stores/workspaceStore';
 
 export type { EditorCheckpoint, CheckpointDiff };
 
@@ -10,18 +12,18 @@ interface HistoryState {
   history: EditorCheckpoint[];
 }
 
+/** History of the active tab */
 export function useHistory() {
   const [state, setState] = useState<HistoryState>({
     canUndo: false,
     canRedo: false,
     history: [],
   });
+  const activeTabId = useWorkspaceStore((s) => s.activeTabId);
 
   const refreshHistoryState = useCallback(() => {
-    setState({
-      canUndo: historyService.canUndo(),
-      canRedo: historyService.canRedo(),
-      history: historyService.getAll(),
+    void documentHistory.getHistoryState().then(setState, (error) => {
+      console.error('[useHistory] Failed to read history:', error);
     });
   }, []);
 
@@ -40,7 +42,7 @@ export function useHistory() {
 
   useEffect(() => {
     refreshHistoryState();
-  }, []); // eslint-disable-line react-hooks/exhaustive-deps
+  }, [activeTabId, refreshHistoryState]);
 
   const createCheckpoint = useCallback(
     async (
@@ -48,7 +50,7 @@ export function useHistory() {
       description: string,
       changeType: 'user' | 'ai' | 'fileload' | 'undo' | 'redo' = 'user'
     ): Promise<string> => {
-      const id = historyService.createCheckpoint(code, [], description, changeType);
+      const id = await documentHistory.createCheckpoint(code, description, changeType);
       refreshHistoryState();
       return id;
     },
@@ -56,20 +58,20 @@ export function useHistory() {
   );
 
   const undo = useCallback(async (): Promise<EditorCheckpoint | null> => {
-    const checkpoint = historyService.undo();
+    const checkpoint = await documentHistory.undo();
     refreshHistoryState();
     return checkpoint;
   }, [refreshHistoryState]);
 
   const redo = useCallback(async (): Promise<EditorCheckpoint | null> => {
-    const checkpoint = historyService.redo();
+    const checkpoint = await documentHistory.redo();
     refreshHistoryState();
     return checkpoint;
   }, [refreshHistoryState]);
 
   const restoreToCheckpoint = useCallback(
     async (checkpointId: string): Promise<EditorCheckpoint | null> => {
-      const checkpoint = historyService.restoreTo(checkpointId);
+      const checkpoint = await documentHistory.restoreTo(checkpointId);
       refreshHistoryState();
       return checkpoint;
     },
@@ -78,7 +80,7 @@ export function useHistory() {
 
   const getCheckpointDiff = useCallback(
     async (fromId: string, toId: string): Promise<CheckpointDiff | null> => {
-      return historyService.getDiff(fromId, toId);
+      return documentHistory.getDiff(fromId, toId);
     },
     []
   );
@@ -1,29 +1,44 @@
 import { useState, useEffect, useCallback, useMemo, useRef } from 'react';
 import {
+  GEMINI_API_URL,
+  OPENROUTER_BASE_URL,
   getApiKey,
+  getAzureOpenAiConfig,
   getOpenAiCompatibleConfig,
   type AiProvider,
+  type AzureOpenAiConfig,
   type OpenAiCompatibleConfig,
 } from '../stores/apiKeyStore';
+import { isCloudAiProvider } from '../services/cloudAi';
 import { getVisionSupportForModelId } from '../utils/aiMessages';
 import {
   compareModelsByFreshness,
   DEFAULT_MODEL_CATALOG,
+  getMaxOutputTokensForModelId,
+  getToolSupportForModelId,
   KNOWN_DISPLAY_NAMES,
   normalizeProviders,
 } from '../utils/aiModels';
-import type { VisionSupport } from '../types/aiChat';
+import type { ToolSupport, VisionSupport } from '../types/aiChat';
 
 export interface ModelInfo {
   id: string;
   display_name: string;
   provider: AiProvider;
   visionSupport: VisionSupport;
+  toolSupport: ToolSupport;
+  /** Output token limit, when known */
+  maxOutputTokens?: number;
 }
 
 export interface GroupedModels {
   anthropic: ModelInfo[];
+  bedrock: ModelInfo[];
+  vertex: ModelInfo[];
   openai: ModelInfo[];
+  azureOpenai: ModelInfo[];
+  gemini: ModelInfo[];
+  openrouter: ModelInfo[];
   openaiCompatible: ModelInfo[];
 }
 
@@ -45,11 +60,23 @@ interface CachedModels {
   fetchedAt: number;
   providers?: AiProvider[];
   openAiCompatibleBaseUrl?: string;
+  azureOpenAiEndpoint?: string;
+}
+
+/** Capabilities known from the model id alone */
+function getModelCapabilities(
+  modelId: string
+): Pick<ModelInfo, 'visionSupport' | 'toolSupport' | 'maxOutputTokens'> {
+  return {
+    visionSupport: getVisionSupportForModelId(modelId),
+    toolSupport: getToolSupportForModelId(modelId),
+    maxOutputTokens: getMaxOutputTokensForModelId(modelId),
+  };
 }
 
 const DEFAULT_MODELS: ModelInfo[] = DEFAULT_MODEL_CATALOG.map((model) => ({
   ...model,
-  visionSupport: getVisionSupportForModelId(model.id),
+  ...getModelCapabilities(model.id),
 }));
 
 function isRelevantOpenAiModel(id: string): boolean {
@@ -80,6 +107,35 @@ interface OpenAiModelsResponse {
   data: OpenAiModel[];
 }
 
+interface OpenRouterModel {
+  id: string;
+  name?: string;
+  architecture?: { input_modalities?: string[] };
+  supported_parameters?: string[];
+  top_provider?: { max_completion_tokens?: number | null };
+}
+
+interface GeminiModel {
+  /** `models/gemini-2.5-pro` */
+  name: string;
+  displayName?: string;
+  supportedGenerationMethods?: string[];
+  outputTokenLimit?: number;
+}
+
+interface GeminiModelsResponse {
+  models?: GeminiModel[];
+  nextPageToken?: string;
+}
+
+interface AzureDeployment {
+  id: string;
+  model?: string;
+}
+
+/** Listing API that still reports deployments; newer API versions dropped it */
+const AZURE_DEPLOYMENTS_API_VERSION = '2022-12-01';
+
 async function fetchAnthropicModels(apiKey: string): Promise<ModelInfo[]> {
   const allModels: ModelInfo[] = [];
   let afterId: string | undefined;
@@ -108,7 +164,7 @@ async function fetchAnthropicModels(apiKey: string): Promise<ModelInfo[]> {
         id: m.id,
         display_name: KNOWN_DISPLAY_NAMES[m.id] || m.display_name,
         provider: 'anthropic',
-        visionSupport: getVisionSupportForModelId(m.id),
+        ...getModelCapabilities(m.id),
       });
     }
 
@@ -136,16 +192,52 @@ async function fetchOpenAiModels(apiKey: string): Promise<ModelInfo[]> {
       id: m.id,
       display_name: KNOWN_DISPLAY_NAMES[m.id] || m.id,
       provider: 'openai' as const,
-      visionSupport: getVisionSupportForModelId(m.id),
+      ...getModelCapabilities(m.id),
     }));
 }
 
+async function fetchGeminiModels(apiKey: string): Promise<ModelInfo[]> {
+  const models: ModelInfo[] = [];
+  let pageToken: string | undefined;
+
+  do {
+    let url = `${GEMINI_API_URL}/models?pageSize=1000`;
+    if (pageToken) url += `&pageToken=${encodeURIComponent(pageToken)}`;
+
+    const resp = await fetch(url, { headers: { 'x-goog-api-key': apiKey } });
+
+    if (!resp.ok) {
+      throw new Error(`Gemini API error (${resp.status}): ${await resp.text()}`);
+    }
+
+    const data: GeminiModelsResponse = await resp.json();
+    for (const m of data.models ?? []) {
+      const id = m.name.replace(/^models\//, '');
+      // Embedding and image models don't chat
+      if (!id.startsWith('gemini-') || !m.supportedGenerationMethods?.includes('generateContent')) {
+        continue;
+      }
+      models.push({
+        id,
+        display_name: m.displayName || KNOWN_DISPLAY_NAMES[id] || id,
+        provider: 'gemini',
+        visionSupport: 'yes',
+        toolSupport: getToolSupportForModelId(id),
+        maxOutputTokens: m.outputTokenLimit ?? getMaxOutputTokensForModelId(id),
+      });
+    }
+    pageToken = data.nextPageToken;
+  } while (pageToken);
+
+  return models;
+}
+
 function createConfiguredOpenAiCompatibleModel(config: OpenAiCompatibleConfig): ModelInfo {
   return {
     id: config.modelId,
     display_name: config.modelId,
     provider: 'openai-compatible',
-    visionSupport: getVisionSupportForModelId(config.modelId),
+    ...getModelCapabilities(config.modelId),
   };
 }
 
@@ -166,7 +258,7 @@ async function fetchOpenAiCompatibleModels(config: OpenAiCompatibleConfig): Prom
     id: m.id,
     display_name: m.id,
     provider: 'openai-compatible' as const,
-    visionSupport: getVisionSupportForModelId(m.id),
+    ...getModelCapabilities(m.id),
   }));
 
   if (config.modelId && !models.some((model) => model.id === config.modelId)) {
@@ -176,6 +268,81 @@ async function fetchOpenAiCompatibleModels(config: OpenAiCompatibleConfig): Prom
   return models;
 }
 
+async function fetchOpenRouterModels(apiKey: string): Promise<ModelInfo[]> {
+  const resp = await fetch(`${OPENROUTER_BASE_URL}/models`, {
+    headers: { Authorization: `Bearer ${apiKey}` },
+  });
+
+  if (!resp.ok) {
+    throw new Error(`OpenRouter API error (${resp.status}): ${await resp.text()}`);
+  }
+
+  const data: { data: OpenRouterModel[] } = await resp.json();
+
+  // The agent edits code through tool calls, so skip models without tool support
+  return data.data
+    .filter((m) => !m.supported_parameters || m.supported_parameters.includes('tools'))
+    .map((m): ModelInfo => {
+      const modalities = m.architecture?.input_modalities;
+      return {
+        id: m.id,
+        display_name: m.name || m.id,
+        provider: 'openrouter',
+        visionSupport: modalities ? (modalities.includes('image') ? 'yes' : 'no') : 'unknown',
+        toolSupport: m.supported_parameters ? 'yes' : 'unknown',
+        maxOutputTokens:
+          m.top_provider?.max_completion_tokens ?? getMaxOutputTokensForModelId(m.id),
+      };
+    });
+}
+
+function createAzureDeploymentModel(deployment: string, model?: string): ModelInfo {
+  return {
+    id: deployment,
+    display_name: model && model !== deployment ? `${deployment} (${model})` : deployment,
+    provider: 'azure-openai',
+    ...getModelCapabilities(model ?? deployment),
+  };
+}
+
+/** Deployments are what Azure requests name, so they stand in for model ids */
+async function fetchAzureOpenAiModels(config: AzureOpenAiConfig): Promise<ModelInfo[]> {
+  const resp = await fetch(
+    `${config.endpoint}/openai/deployments?api-version=${AZURE_DEPLOYMENTS_API_VERSION}`,
+    { headers: { 'api-key': config.apiKey ?? '' } }
+  );
+
+  if (!resp.ok) {
+    throw new Error(`Azure OpenAI API error (${resp.status}): ${await resp.text()}`);
+  }
+
+  const data: { data: AzureDeployment[] } = await resp.json();
+  const models = data.data.map((d) => createAzureDeploymentModel(d.id, d.model));
+  for (const deployment of config.deployments) {
+    if (!models.some((model) => model.id === deployment)) {
+      models.push(createAzureDeploymentModel(deployment));
+    }
+  }
+  return models;
+}
+
+/** Models the user configured by hand, shown when listing isn't possible */
+function configuredModels(providers: readonly string[]): ModelInfo[] {
+  const models: ModelInfo[] = [];
+  if (providers.includes('azure-openai')) {
+    models.push(
+      ...getAzureOpenAiConfig().deployments.map((deployment) =>
+        createAzureDeploymentModel(deployment)
+      )
+    );
+  }
+  const customConfig = getOpenAiCompatibleConfig();
+  if (providers.includes('openai-compatible') && customConfig.modelId) {
+    models.push(createConfiguredOpenAiCompatibleModel(customConfig));
+  }
+  return models;
+}
+
 function sortModels(models: ModelInfo[]): ModelInfo[] {
   return [...models].sort(compareModelsByFreshness);
 }
@@ -193,9 +360,17 @@ function hasCacheCoverage(cached: CachedModels, providers: readonly string[]): b
   if (!requestedProviders.every((provider) => cachedProviders.includes(provider))) {
     return false;
   }
-  if (requestedProviders.includes('openai-compatible')) {
-    const config = getOpenAiCompatibleConfig();
-    return cached.openAiCompatibleBaseUrl === config.baseUrl;
+  if (
+    requestedProviders.includes('openai-compatible') &&
+    cached.openAiCompatibleBaseUrl !== getOpenAiCompatibleConfig().baseUrl
+  ) {
+    return false;
+  }
+  if (
+    requestedProviders.includes('azure-openai') &&
+    cached.azureOpenAiEndpoint !== getAzureOpenAiConfig().endpoint
+  ) {
+    return false;
   }
   return true;
 }
@@ -213,6 +388,8 @@ function loadCache(providers: string[]): { models: ModelInfo[]; ageMinutes: numb
       .map((model) => ({
         ...model,
         visionSupport: model.visionSupport || getVisionSupportForModelId(model.id),
+        toolSupport: model.toolSupport || getToolSupportForModelId(model.id),
+        maxOutputTokens: model.maxOutputTokens ?? getMaxOutputTokensForModelId(model.id),
       }));
     if (filtered.length === 0) return null;
     return { models: filtered, ageMinutes: Math.floor(age / 60000) };
@@ -229,6 +406,9 @@ function saveCache(models: ModelInfo[], providers: readonly string[]): void {
       fetchedAt: Date.now(),
       providers: normalizeProviders(providers),
       openAiCompatibleBaseUrl: providers.includes('openai-compatible') ? config.baseUrl : undefined,
+      azureOpenAiEndpoint: providers.includes('azure-openai')
+        ? getAzureOpenAiConfig().endpoint
+        : undefined,
     };
     localStorage.setItem(CACHE_KEY, JSON.stringify(cached));
   } catch {
@@ -308,6 +488,52 @@ export function useModels(availableProviders: string[]): UseModelsReturn {
             );
           }
         }
+        if (providers.includes('azure-openai')) {
+          const config = getAzureOpenAiConfig();
+          if (config.endpoint && config.apiKey) {
+            fetches.push(
+              fetchAzureOpenAiModels(config)
+                .then((models) => ({ models, error: null }))
+                .catch((error) => ({
+                  models: configuredModels(['azure-openai']),
+                  error: error instanceof Error ? error.message : String(error),
+                }))
+            );
+          }
+        }
+        if (providers.includes('gemini')) {
+          const key = getApiKey('gemini');
+          if (key) {
+            fetches.push(
+              fetchGeminiModels(key)
+                .then((models) => ({ models, error: null }))
+                .catch((error) => ({
+                  models: [],
+                  error: error instanceof Error ? error.message : String(error),
+                }))
+            );
+          }
+        }
+        if (providers.includes('openrouter')) {
+          const key = getApiKey('openrouter');
+          if (key) {
+            fetches.push(
+              fetchOpenRouterModels(key)
+                .then((models) => ({ models, error: null }))
+                .catch((error) => ({
+                  models: [],
+                  error: error instanceof Error ? error.message : String(error),
+                }))
+            );
+          }
+        }
+        // Bedrock and Vertex have no model listing for Claude; offer the known models
+        const cloudModels = DEFAULT_MODELS.filter(
+          (m) => isCloudAiProvider(m.provider) && providers.includes(m.provider)
+        );
+        if (cloudModels.length > 0) {
+          fetches.push(Promise.resolve({ models: cloudModels, error: null }));
+        }
         if (providers.includes('openai-compatible')) {
           const config = getOpenAiCompatibleConfig();
           if (config.baseUrl) {
@@ -323,7 +549,10 @@ export function useModels(availableProviders: string[]): UseModelsReturn {
         }
 
         const results = await Promise.all(fetches);
-        const allModels = results.flatMap((result) => result.models);
+        // The agent edits code through tool calls, so drop models known to lack them
+        const allModels = results
+          .flatMap((result) => result.models)
+          .filter((model) => model.toolSupport !== 'no');
         const errors = results
           .map((result) => result.error)
           .filter((value): value is string => Boolean(value));
@@ -337,14 +566,9 @@ export function useModels(availableProviders: string[]): UseModelsReturn {
           setCacheAgeMinutes(null);
           saveCache(sorted, providers);
         } else {
-          const customConfig = getOpenAiCompatibleConfig();
-          const customDefaults =
-            providers.includes('openai-compatible') && customConfig.modelId
-              ? [createConfiguredOpenAiCompatibleModel(customConfig)]
-              : [];
           const defaults = [
             ...DEFAULT_MODELS.filter((m) => providers.includes(m.provider)),
-            ...customDefaults,
+            ...configuredModels(providers),
           ];
           setModels(defaults);
           setError(errors.length > 0 ? errors.join('\n') : null);
@@ -354,14 +578,9 @@ export function useModels(availableProviders: string[]): UseModelsReturn {
       } catch (e) {
         if (requestId !== requestIdRef.current) return;
         setError(String(e));
-        const customConfig = getOpenAiCompatibleConfig();
-        const customDefaults =
-          providersRef.current.includes('openai-compatible') && customConfig.modelId
-            ? [createConfiguredOpenAiCompatibleModel(customConfig)]
-            : [];
         const defaults = [
           ...DEFAULT_MODELS.filter((m) => providersRef.current.includes(m.provider)),
-          ...customDefaults,
+          ...configuredModels(providersRef.current),
         ];
         setModels(defaults);
         setFromCache(false);
@@ -386,7 +605,12 @@ export function useModels(availableProviders: string[]): UseModelsReturn {
   const groupedByProvider = useMemo(
     (): GroupedModels => ({
       anthropic: models.filter((m) => m.provider === 'anthropic'),
+      bedrock: models.filter((m) => m.provider === 'bedrock'),
+      vertex: models.filter((m) => m.provider === 'vertex'),
       openai: models.filter((m) => m.provider === 'openai'),
+      azureOpenai: models.filter((m) => m.provider === 'azure-openai'),
+      gemini: models.filter((m) => m.provider === 'gemini'),
+      openrouter: models.filter((m) => m.provider === 'openrouter'),
       openaiCompatible: models.filter((m) => m.provider === 'openai-compatible'),
     }),
     [models]
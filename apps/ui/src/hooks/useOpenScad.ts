@@ -15,6 +15,7 @@ import { getPlatform } from '../platform';
 import type { LibrarySettings } from '../stores/settingsStore';
 import { resolveWorkingDirDeps } from '../utils/resolveWorkingDirDeps';
 import { getProjectState } from '../stores/projectStore';
+import { documentIdForTab } from '../services/editorSync';
 import { notifyError } from '../utils/notifications';
 import { hasRenderableOutput } from './renderOutput';
 export type RenderKind = 'mesh' | 'svg';
@@ -157,7 +158,8 @@ export function useOpenScad(options: UseOpenScadOptions = {}) {
   }, [options.workingDir]);
 
   // Initialize render service on mount.
-  // On Tauri, this waits for NativeRenderService to load before calling init().
+  // On Tauri, this waits for NativeRenderService to load before calling init(),
+  // or keeps WasmRenderService when no OpenSCAD binary is found.
   // On web, ensureRenderService() resolves immediately with WasmRenderService.
   useEffect(() => {
     const initService = testOverrides?.renderService
@@ -247,6 +249,7 @@ export function useOpenScad(options: UseOpenScadOptions = {}) {
           ...renderInputs.renderOptions,
           view: dimension,
           backend: 'manifold',
+          documentId: documentIdForTab(owner?.tabId),
         } as const;
 
         const cached = await renderServiceRef.current.getCached(code, renderOptions);
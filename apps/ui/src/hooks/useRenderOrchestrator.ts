@@ -9,6 +9,7 @@ import { useAnalytics } from '../analytics/runtime';
 import { getPlatform } from '../platform';
 import { resolveWorkingDirDeps } from '../utils/resolveWorkingDirDeps';
 import { notifyError } from '../utils/notifications';
+import { isDesktopRenderScheduler, scheduleRender } from '../services/renderScheduler';
 
 interface UseRenderOrchestratorOptions {
   source: string;
@@ -32,6 +33,7 @@ interface UseRenderOrchestratorOptions {
     resolveWorkingDirDeps?: typeof resolveWorkingDirDeps;
     notifyError?: typeof notifyError;
     isDevRuntime?: boolean;
+    scheduleRender?: typeof scheduleRender;
   };
 }
 
@@ -42,7 +44,11 @@ export function useRenderOrchestrator(options: UseRenderOrchestratorOptions) {
     autoRenderOnIdle = false,
     autoRenderDelayMs = 500,
     suppressInitialRender = false,
+    testOverrides,
   } = options;
+  // On desktop the backend decides when auto-renders run (debounce, idle and clean policies)
+  const scheduleRenderImpl =
+    testOverrides?.scheduleRender ?? (isDesktopRenderScheduler() ? scheduleRender : undefined);
 
   // Delegate WASM, caching, blob URLs, etc. to useOpenScad.
   // Disable its internal auto-render and initial-render — we handle both.
@@ -127,6 +133,25 @@ export function useRenderOrchestrator(options: UseRenderOrchestratorOptions) {
       return;
     }
 
+    if (scheduleRenderImpl) {
+      // The preview shows one render target, so all of its requests coalesce
+      let superseded = false;
+      scheduleRenderImpl(source)
+        .catch((error) => {
+          console.error('[render] Failed to schedule an automatic render:', error);
+          return { skipped: null };
+        })
+        .then(({ skipped }) => {
+          if (superseded || skipped) return;
+          lastRenderedSourceRef.current = source;
+          lastRenderedVersionRef.current = contentVersion;
+          renderWithTrigger('auto_idle');
+        });
+      return () => {
+        superseded = true;
+      };
+    }
+
     if (autoRenderTimerRef.current) {
       clearTimeout(autoRenderTimerRef.current);
     }
@@ -142,7 +167,15 @@ export function useRenderOrchestrator(options: UseRenderOrchestratorOptions) {
         clearTimeout(autoRenderTimerRef.current);
       }
     };
-  }, [source, contentVersion, autoRenderOnIdle, autoRenderDelayMs, ready, renderWithTrigger]);
+  }, [
+    source,
+    contentVersion,
+    autoRenderOnIdle,
+    autoRenderDelayMs,
+    ready,
+    renderWithTrigger,
+    scheduleRenderImpl,
+  ]);
 
   return openscad;
 }
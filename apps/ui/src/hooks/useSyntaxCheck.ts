@@ -0,0 +1,35 @@
+import { useEffect, useState } from 'react';
+import type { Diagnostic } from '../platform/historyService';
+import { checkSyntax, isSyntaxCheckAvailable } from '../services/syntaxCheck';
+
+/** How long typing must pause before the code is checked */
+export const SYNTAX_CHECK_DELAY_MS = 300;
+
+/**
+ * Syntax errors in `code`, checked a moment after it stops changing. Keeps
+ * the last result until a newer check finishes, so markers don't flicker
+ * while typing. Always empty where the check isn't available.
+ */
+export function useSyntaxCheck(code: string, delayMs = SYNTAX_CHECK_DELAY_MS): Diagnostic[] {
+  const [errors, setErrors] = useState<Diagnostic[]>([]);
+
+  useEffect(() => {
+    if (!isSyntaxCheckAvailable()) return;
+    let stale = false;
+    const timer = setTimeout(() => {
+      checkSyntax(code).then(
+        (diagnostics) => {
+          if (!stale) setErrors(diagnostics.filter((d) => d.severity === 'error'));
+        },
+        // Superseded by a newer check, or no OpenSCAD binary yet
+        () => {}
+      );
+    }, delayMs);
+    return () => {
+      stale = true;
+      clearTimeout(timer);
+    };
+  }, [code, delayMs]);
+
+  return errors;
+}
@@ -0,0 +1,28 @@
+import { useEffect, useState } from 'react';
+import { getUnsupportedExportFormats, type ExportFormat } from '../services/renderService';
+
+/**
+ * Export formats the OpenSCAD binary can't write, refreshed whenever
+ * `active` turns on (e.g. an export menu opening) so a switched binary is
+ * picked up.
+ */
+export function useUnsupportedExportFormats(active: boolean): ExportFormat[] {
+  const [unsupported, setUnsupported] = useState<ExportFormat[]>([]);
+
+  useEffect(() => {
+    if (!active) return;
+    let disposed = false;
+    getUnsupportedExportFormats()
+      .then((formats) => {
+        if (!disposed) setUnsupported(formats);
+      })
+      .catch((error) => {
+        console.warn('[export] Failed to read the OpenSCAD capabilities:', error);
+      });
+    return () => {
+      disposed = true;
+    };
+  }, [active]);
+
+  return unsupported;
+}
@@ -0,0 +1,114 @@
+/**
+ * OpenSCAD editor features backed by the desktop symbol index: document
+ * outline, go-to-definition (into included files too) and completion of
+ * user-defined modules, functions and variables.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import type * as Monaco from 'monaco-editor';
+
+const LANGUAGE_ID = 'openscad';
+
+interface OpenScadSymbol {
+  name: string;
+  kind: 'module' | 'function' | 'variable';
+  line: number;
+  col: number;
+  /** Signature of a module or function, value of a variable */
+  detail: string;
+  /** Defining file, when it isn't the edited document */
+  file?: string;
+}
+
+let registered = false;
+
+function symbolRange(monaco: typeof Monaco, symbol: OpenScadSymbol): Monaco.IRange {
+  return new monaco.Range(symbol.line, symbol.col, symbol.line, symbol.col + symbol.name.length);
+}
+
+function symbolKind(monaco: typeof Monaco, kind: OpenScadSymbol['kind']) {
+  const kinds = monaco.languages.SymbolKind;
+  return kind === 'variable' ? kinds.Variable : kind === 'function' ? kinds.Function : kinds.Module;
+}
+
+function completionKind(monaco: typeof Monaco, kind: OpenScadSymbol['kind']) {
+  const kinds = monaco.languages.CompletionItemKind;
+  return kind === 'variable' ? kinds.Variable : kind === 'function' ? kinds.Function : kinds.Module;
+}
+
+/** Read-only model of an included file, so definitions in it can be peeked */
+async function includedFileModel(
+  monaco: typeof Monaco,
+  file: string
+): Promise<Monaco.editor.ITextModel> {
+  const uri = monaco.Uri.file(file);
+  const existing = monaco.editor.getModel(uri);
+  if (existing) return existing;
+  const { content } = await invoke<{ content: string }>('read_text_file', { path: file });
+  return monaco.editor.getModel(uri) ?? monaco.editor.createModel(content, LANGUAGE_ID, uri);
+}
+
+export function registerOpenScadSymbolProviders(monaco: typeof Monaco): void {
+  if (registered || typeof window === 'undefined' || !('__TAURI_INTERNALS__' in window)) return;
+
+  monaco.languages.registerDocumentSymbolProvider(LANGUAGE_ID, {
+    provideDocumentSymbols: async (model) => {
+      const symbols = await invoke<OpenScadSymbol[]>('document_symbols', {
+        code: model.getValue(),
+      }).catch(() => []);
+      return symbols.map((symbol) => ({
+        name: symbol.name,
+        detail: symbol.detail,
+        kind: symbolKind(monaco, symbol.kind),
+        tags: [],
+        range: symbolRange(monaco, symbol),
+        selectionRange: symbolRange(monaco, symbol),
+      }));
+    },
+  });
+
+  monaco.languages.registerDefinitionProvider(LANGUAGE_ID, {
+    provideDefinition: async (model, position) => {
+      const word = model.getWordAtPosition(position);
+      if (!word) return null;
+      try {
+        const symbol = await invoke<OpenScadSymbol | null>('find_definition', {
+          name: word.word,
+          code: model.getValue(),
+        });
+        if (!symbol) return null;
+        const target = symbol.file ? await includedFileModel(monaco, symbol.file) : model;
+        return { uri: target.uri, range: symbolRange(monaco, symbol) };
+      } catch (error) {
+        console.error('[openscadSymbols] Definition lookup failed:', error);
+        return null;
+      }
+    },
+  });
+
+  monaco.languages.registerCompletionItemProvider(LANGUAGE_ID, {
+    provideCompletionItems: async (model, position) => {
+      const word = model.getWordUntilPosition(position);
+      const range = {
+        startLineNumber: position.lineNumber,
+        endLineNumber: position.lineNumber,
+        startColumn: word.startColumn,
+        endColumn: word.endColumn,
+      };
+      const symbols = await invoke<OpenScadSymbol[]>('completion_candidates', {
+        prefix: word.word,
+        code: model.getValue(),
+      }).catch(() => []);
+      return {
+        suggestions: symbols.map((symbol) => ({
+          label: symbol.name,
+          kind: completionKind(monaco, symbol.kind),
+          detail: symbol.file ? `${symbol.detail} (${symbol.file})` : symbol.detail,
+          insertText: symbol.name,
+          range,
+        })),
+      };
+    },
+  });
+
+  registered = true;
+}
@@ -23,8 +23,14 @@ import {
   type DesktopWindowLaunchIntent,
   type DesktopWindowOpenRequest,
 } from './services/desktopMcp';
-import { openFileInWindow, openWorkspaceFolderInWindow } from './services/windowOpenService';
+import { restoreSession, type SessionSnapshot } from './services/session';
+import {
+  openFileInWindow,
+  openWorkspaceFolderInWindow,
+  reopenSessionTabs,
+} from './services/windowOpenService';
 import { captureSentryException } from './sentry';
+import { loadCloudProviders, loadDesktopApiKeys } from './stores/apiKeyStore';
 import { getProjectState, getProjectStore } from './stores/projectStore';
 import { loadSettings } from './stores/settingsStore';
 import { workspaceStore } from './stores/workspaceStore';
@@ -288,6 +294,15 @@ function intentToRequest(intent: DesktopWindowLaunchIntent): DesktopWindowOpenRe
   }
 }
 
+/** The folder, or else the active file, that a restored session reopens first */
+function sessionToRequest(session: SessionSnapshot): DesktopWindowOpenRequest | null {
+  if (session.workingDir) {
+    return { kind: 'open_folder', folder_path: session.workingDir, create_if_empty: false };
+  }
+  const filePath = session.activeFile ?? session.openFiles[0]?.path;
+  return filePath ? { kind: 'open_file', file_path: filePath } : null;
+}
+
 function normalizeErrorMessage(error: unknown, fallback: string): string {
   return error instanceof Error ? error.message : fallback;
 }
@@ -552,6 +567,12 @@ function BootstrapApp() {
         });
 
         if (platform.capabilities.hasFileSystem) {
+          void loadDesktopApiKeys().catch((error) => {
+            console.error('[main] Failed to load API keys:', error);
+          });
+          void loadCloudProviders().catch((error) => {
+            console.error('[main] Failed to check cloud AI credentials:', error);
+          });
           reportStartupPhase('bridge_initializing');
           setBootDetail('initializeDesktopMcpBridge');
           bridgeCleanup = await initializeDesktopMcpBridge({
@@ -596,6 +617,20 @@ function BootstrapApp() {
           }
         }
 
+        const session = platform.capabilities.hasFileSystem
+          ? await restoreSession().catch((error) => {
+              console.warn('[main] Failed to read the previous session:', error);
+              return null;
+            })
+          : null;
+        const sessionRequest = session ? sessionToRequest(session) : null;
+        if (session && sessionRequest) {
+          setBootDetail('restoring previous session');
+          await runOpenRequest(sessionRequest);
+          reopenSessionTabs(session);
+          return;
+        }
+
         reportStartupPhase('launch_intent_none');
         setBootDetail('welcome_ready');
         resetWindowToWelcomeState();
@@ -32,4 +32,22 @@ describe('historyService', () => {
     expect(checkpointId).toBe('00010203-0405-4607-8809-0a0b0c0d0e0f');
     expect(historyService.getAll()).toHaveLength(1);
   });
+
+  it('links checkpoints to chat messages and restores to a message', () => {
+    const link = (messageId: string, messageIndex: number) => ({
+      conversationId: 'conv',
+      messageId,
+      messageIndex,
+    });
+    historyService.createCheckpoint('a', [], 'Before AI edit', 'ai', link('m3', 2));
+    historyService.createCheckpoint('b', [], 'Before AI edit', 'ai', link('m1', 0));
+    historyService.createCheckpoint('c', [], 'Before AI edit', 'ai', link('m1', 0));
+    historyService.createCheckpoint('d', [], 'Typed', 'user');
+
+    expect(historyService.getForConversation('conv').map((c) => c.code)).toEqual(['b', 'c', 'a']);
+    expect(historyService.getForConversation('other')).toEqual([]);
+    expect(historyService.restoreToMessage('m1')?.code).toBe('b');
+    expect(historyService.canRedo()).toBe(true);
+    expect(historyService.restoreToMessage('m2')).toBeNull();
+  });
 });
@@ -1,4 +1,34 @@
+import type { Message, ToolCall } from '../types/aiChat';
+import type { StreamRetry } from '../services/aiStream';
 import type { ExportFormat } from './types';
+import type { WorkspacePreset } from '../stores/layoutStore';
+
+/** Progress of one conversation's AI request, emitted as `ai-stream:{conversationId}` */
+interface AiStreamEvent {
+  conversationId: string;
+  status:
+    | 'streaming'
+    | 'thinking'
+    | 'tool-args-delta'
+    | 'retrying'
+    | 'complete'
+    | 'cancelled'
+    | 'error';
+  /** The conversation's messages so far */
+  messages: Message[];
+  /** With `thinking`: the reasoning text that just streamed in */
+  thinking?: string;
+  /** With `tool-args-delta`: the tool call whose arguments are streaming */
+  toolCall?: ToolCall;
+  /** With `tool-args-delta`: the arguments streamed so far, parsed from the partial JSON */
+  args?: Record<string, unknown>;
+  /** With `tool-args-delta`: the raw argument JSON that just streamed in */
+  argsDelta?: string;
+  /** With `tool-args-delta`: all raw argument JSON streamed so far */
+  argsText?: string;
+  /** With `retrying`: which retry is waiting, and for how long */
+  retry?: StreamRetry;
+}
 
 interface EventMap {
   'menu:file:new': void;
@@ -10,13 +40,35 @@ interface EventMap {
   'menu:file:open_folder': void;
   'menu:file:open_project': void;
   'menu:file:save_all': void;
+  'menu:file:open_recent': { path: string; kind: 'file' | 'folder' };
+  'menu:render': void;
+  'menu:render:toggle_auto_render': void;
+  'menu:ai:toggle_panel': void;
+  'menu:ai:new_conversation': void;
+  'menu:view:layout': WorkspacePreset;
+  'menu:design:lithophane': void;
   'render-requested': { source?: 'ai' };
   'history:restore': { code: string };
+  'editor:insert-snippet': { code: string };
+  /** A document's buffer grew past the backend's large-file limit */
+  'editor:large-file': { documentId: string; bytes: number };
   'code-updated': {
     code: string;
     source: 'customizer' | 'editor' | 'ai' | 'history' | 'file-open';
   };
   'settings:changed': void;
+  [event: `ai-stream:${string}`]: AiStreamEvent;
+  /** Older messages were summarised to keep requests within the model's context window */
+  'conversation:compacted': {
+    conversationId: string;
+    /** Model messages replaced by the summary */
+    summarizedMessages: number;
+    /** Estimated tokens of the conversation before and after */
+    tokensBefore: number;
+    tokensAfter: number;
+  };
+  /** A model titled the conversation after its first exchange */
+  'conversation:titled': { conversationId: string; title: string };
 }
 
 type EventCallback<T> = T extends void ? () => void : (payload: T) => void;
@@ -78,4 +130,4 @@ class EventBus {
 }
 
 export const eventBus = new EventBus();
-export type { EventMap };
+export type { AiStreamEvent, EventMap };
@@ -4,6 +4,9 @@ export interface Diagnostic {
   severity: 'error' | 'warning' | 'info';
   line?: number;
   col?: number;
+  /** Project-relative file the message refers to, when it names one. */
+  file?: string;
+  category?: 'unknown-variable' | 'unknown-module' | 'unknown-function' | 'assertion-failed';
   message: string;
 }
 
@@ -16,6 +19,21 @@ export interface EditorCheckpoint {
   diagnostics: Diagnostic[];
   description: string;
   change_type: ChangeType;
+  /** AI conversation whose request made the checkpoint */
+  conversation_id?: string;
+  /** User message of that request; the checkpoint holds the code from before its edits */
+  message_id?: string;
+  /** Position of the message in the conversation */
+  message_index?: number;
+  /** Project file, other than the document, whose earlier content the checkpoint holds */
+  file_path?: string;
+}
+
+/** The chat message a checkpoint is made for */
+export interface ConversationLink {
+  conversationId: string;
+  messageId: string;
+  messageIndex: number;
 }
 
 export interface CheckpointDiff {
@@ -36,7 +54,8 @@ class HistoryService {
     code: string,
     diagnostics: Diagnostic[],
     description: string,
-    changeType: ChangeType
+    changeType: ChangeType,
+    conversation?: ConversationLink
   ): string {
     const checkpoint: EditorCheckpoint = {
       id: createRandomId(),
@@ -45,6 +64,11 @@ class HistoryService {
       diagnostics,
       description,
       change_type: changeType,
+      ...(conversation && {
+        conversation_id: conversation.conversationId,
+        message_id: conversation.messageId,
+        message_index: conversation.messageIndex,
+      }),
     };
 
     // If not at latest, truncate forward history
@@ -115,6 +139,19 @@ class HistoryService {
     return this.checkpoints.find((c) => c.id === id) ?? null;
   }
 
+  /** Checkpoints made for messages of a conversation, in chat order */
+  getForConversation(conversationId: string): EditorCheckpoint[] {
+    return this.checkpoints
+      .filter((c) => c.conversation_id === conversationId)
+      .sort((a, b) => (a.message_index ?? 0) - (b.message_index ?? 0));
+  }
+
+  /** Restore the code from before a chat message's edits: its first checkpoint */
+  restoreToMessage(messageId: string): EditorCheckpoint | null {
+    const checkpoint = this.checkpoints.find((c) => c.message_id === messageId);
+    return checkpoint ? this.restoreTo(checkpoint.id) : null;
+  }
+
   restoreTo(id: string): EditorCheckpoint | null {
     const index = this.checkpoints.findIndex((c) => c.id === id);
     if (index === -1) return null;
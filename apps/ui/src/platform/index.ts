@@ -7,9 +7,15 @@ export type {
   ExportFormat,
 } from './types';
 export { eventBus } from './eventBus';
-export type { EventMap } from './eventBus';
+export type { AiStreamEvent, EventMap } from './eventBus';
 export { historyService } from './historyService';
-export type { EditorCheckpoint, CheckpointDiff, Diagnostic, ChangeType } from './historyService';
+export type {
+  EditorCheckpoint,
+  CheckpointDiff,
+  ConversationLink,
+  Diagnostic,
+  ChangeType,
+} from './historyService';
 
 import type {
   ConfirmDialogOptions,
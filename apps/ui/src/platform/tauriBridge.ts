@@ -6,12 +6,30 @@ import type {
   ConfirmDialogOptions,
 } from './types';
 import { eventBus } from './eventBus';
+import type { WorkspacePreset } from '../stores/layoutStore';
 import {
   OPENSCAD_PROJECT_FILE_EXTENSIONS,
   hasAllowedExtension,
   isOpenScadProjectFilePath,
 } from '../../../../packages/shared/src/openscadProjectFiles';
 
+/**
+ * Text files go through the backend so their encoding, BOM and line endings
+ * are kept: the editor gets LF-terminated text, and a write puts the file
+ * back in the format it had (UTF-8 for new files). Non-UTF-8 files without
+ * a BOM are read as Latin-1.
+ */
+async function readText(path: string): Promise<string> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  const { content } = await invoke<{ content: string }>('read_text_file', { path });
+  return content;
+}
+
+async function writeText(path: string, content: string): Promise<void> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('write_text_file', { path, content });
+}
+
 const capabilities: PlatformCapabilities = {
   multiFile: true,
   hasNativeMenu: true,
@@ -24,7 +42,6 @@ export class TauriBridge implements PlatformBridge {
 
   async fileOpen(filters?: FileFilter[]): Promise<FileOpenResult | null> {
     const { open } = await import('@tauri-apps/plugin-dialog');
-    const { readTextFile } = await import('@tauri-apps/plugin-fs');
 
     const selected = await open({
       filters,
@@ -34,15 +51,14 @@ export class TauriBridge implements PlatformBridge {
     if (!selected) return null;
 
     const filePath = typeof selected === 'string' ? selected : (selected as { path: string }).path;
-    const content = await readTextFile(filePath);
+    const content = await readText(filePath);
     const name = filePath.split('/').pop() || filePath;
 
     return { path: filePath, name, content };
   }
 
   async fileRead(path: string): Promise<FileOpenResult | null> {
-    const { readTextFile } = await import('@tauri-apps/plugin-fs');
-    const content = await readTextFile(path);
+    const content = await readText(path);
     const name = path.split('/').pop() || path;
     return { path, name, content };
   }
@@ -54,8 +70,7 @@ export class TauriBridge implements PlatformBridge {
     defaultFilename?: string
   ): Promise<string | null> {
     if (path) {
-      const { writeTextFile } = await import('@tauri-apps/plugin-fs');
-      await writeTextFile(path, content);
+      await writeText(path, content);
       return path;
     }
 
@@ -68,12 +83,11 @@ export class TauriBridge implements PlatformBridge {
     defaultFilename?: string
   ): Promise<string | null> {
     const { save } = await import('@tauri-apps/plugin-dialog');
-    const { writeTextFile } = await import('@tauri-apps/plugin-fs');
 
     const savePath = await save({ filters, defaultPath: defaultFilename });
     if (!savePath) return null;
 
-    await writeTextFile(savePath, content);
+    await writeText(savePath, content);
     return savePath;
   }
 
@@ -122,8 +136,7 @@ export class TauriBridge implements PlatformBridge {
 
   async readTextFile(absolutePath: string): Promise<string | null> {
     try {
-      const { readTextFile } = await import('@tauri-apps/plugin-fs');
-      return await readTextFile(absolutePath);
+      return await readText(absolutePath);
     } catch {
       return null;
     }
@@ -134,7 +147,7 @@ export class TauriBridge implements PlatformBridge {
     extensions: string[] = [...OPENSCAD_PROJECT_FILE_EXTENSIONS],
     recursive: boolean = true
   ): Promise<Record<string, string>> {
-    const { readDir, readTextFile } = await import('@tauri-apps/plugin-fs');
+    const { readDir } = await import('@tauri-apps/plugin-fs');
     const files: Record<string, string> = {};
 
     const walk = async (currentDir: string, prefix: string) => {
@@ -158,7 +171,7 @@ export class TauriBridge implements PlatformBridge {
           }
         } else if (hasAllowedExtension(entry.name, extensions)) {
           try {
-            files[relativePath] = await readTextFile(entryPath);
+            files[relativePath] = await readText(entryPath);
           } catch (err) {
             console.warn(`[readDirectoryFiles] Failed to read file ${entryPath}:`, err);
           }
@@ -222,8 +235,7 @@ export class TauriBridge implements PlatformBridge {
   }
 
   async writeTextFile(absolutePath: string, content: string): Promise<void> {
-    const { writeTextFile } = await import('@tauri-apps/plugin-fs');
-    await writeTextFile(absolutePath, content);
+    await writeText(absolutePath, content);
   }
 
   async deleteFile(absolutePath: string): Promise<void> {
@@ -281,7 +293,6 @@ export class TauriBridge implements PlatformBridge {
     onChange: (relativePath: string, content: string | null) => void
   ): Promise<() => void> {
     const { watch } = await import('@tauri-apps/plugin-fs');
-    const { readTextFile } = await import('@tauri-apps/plugin-fs');
 
     let debounceTimer: ReturnType<typeof setTimeout> | null = null;
     const pendingPaths = new Set<string>();
@@ -330,7 +341,7 @@ export class TauriBridge implements PlatformBridge {
             pendingPaths.clear();
             for (const relPath of paths) {
               try {
-                const content = await readTextFile(`${dirPath}/${relPath}`);
+                const content = await readText(`${dirPath}/${relPath}`);
                 onChange(relPath, content);
               } catch {
                 // File may have been deleted between event and read
@@ -420,8 +431,27 @@ export class TauriBridge implements PlatformBridge {
     await listen('menu:file:save', () => eventBus.emit('menu:file:save'));
     await listen('menu:file:save_as', () => eventBus.emit('menu:file:save_as'));
     await listen('menu:file:save_all', () => eventBus.emit('menu:file:save_all'));
+    await listen<{ path: string; kind: 'file' | 'folder' }>('menu:file:open_recent', (event) => {
+      eventBus.emit('menu:file:open_recent', event.payload);
+    });
     await listen<string>('menu:file:export', (event) => {
       eventBus.emit('menu:file:export', event.payload as import('./types').ExportFormat);
     });
+    await listen('menu:render', () => eventBus.emit('menu:render'));
+    await listen('menu:render:toggle_auto_render', () =>
+      eventBus.emit('menu:render:toggle_auto_render')
+    );
+    await listen('menu:ai:toggle_panel', () => eventBus.emit('menu:ai:toggle_panel'));
+    await listen('menu:ai:new_conversation', () => eventBus.emit('menu:ai:new_conversation'));
+    await listen('menu:design:lithophane', () => eventBus.emit('menu:design:lithophane'));
+    await listen<WorkspacePreset>('menu:view:layout', (event) => {
+      eventBus.emit('menu:view:layout', event.payload);
+    });
+    await listen<string>('editor:insert-snippet', (event) => {
+      eventBus.emit('editor:insert-snippet', { code: event.payload });
+    });
+    await listen<{ documentId: string; bytes: number }>('editor:large-file', (event) => {
+      eventBus.emit('editor:large-file', event.payload);
+    });
   }
 }
@@ -3,7 +3,18 @@
  * Enables the app to run as both a Tauri desktop app and a pure web app.
  */
 
-export type ExportFormat = 'stl' | 'obj' | 'amf' | '3mf' | 'png' | 'svg' | 'dxf';
+export type ExportFormat =
+  | 'stl'
+  | 'obj'
+  | 'amf'
+  | '3mf'
+  | 'off'
+  | 'wrl'
+  | 'pov'
+  | 'csg'
+  | 'png'
+  | 'svg'
+  | 'dxf';
 
 export interface FileOpenResult {
   /** File path on disk (null for web where no real path exists) */
@@ -0,0 +1,47 @@
+import { jest } from '@jest/globals';
+
+const invoke = jest.fn<(command: string, args?: Record<string, unknown>) => Promise<unknown>>();
+
+jest.unstable_mockModule('@tauri-apps/api/core', () => ({
+  invoke,
+}));
+
+describe('withAiRequestHeaders', () => {
+  let withAiRequestHeaders: typeof import('../aiRequestHeaders').withAiRequestHeaders;
+  const fetchMock = jest.fn<typeof fetch>();
+
+  beforeAll(async () => {
+    ({ withAiRequestHeaders } = await import('../aiRequestHeaders'));
+  });
+
+  beforeEach(() => {
+    invoke.mockReset();
+    fetchMock.mockReset();
+    fetchMock.mockResolvedValue(new Response('{}'));
+    Object.assign(globalThis, { window: { __TAURI_INTERNALS__: {} }, fetch: fetchMock });
+  });
+
+  afterEach(() => {
+    Reflect.deleteProperty(globalThis, 'window');
+  });
+
+  it('adds the configured headers to each request', async () => {
+    invoke.mockResolvedValue({ 'OpenAI-Organization': 'org-123' });
+
+    await withAiRequestHeaders('openai')!('https://api.openai.com/v1/responses', {
+      method: 'POST',
+      headers: { Authorization: 'Bearer key' },
+    });
+
+    expect(invoke).toHaveBeenCalledWith('get_ai_request_headers', { provider: 'openai' });
+    const headers = new Headers(fetchMock.mock.calls[0][1]?.headers);
+    expect(headers.get('OpenAI-Organization')).toBe('org-123');
+    expect(headers.get('Authorization')).toBe('Bearer key');
+  });
+
+  it('leaves requests alone outside the desktop app', () => {
+    Reflect.deleteProperty(globalThis, 'window');
+
+    expect(withAiRequestHeaders('anthropic')).toBeUndefined();
+  });
+});
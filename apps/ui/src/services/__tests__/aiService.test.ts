@@ -41,9 +41,11 @@ import type { AiToolCallbacks } from '../aiService';
 
 let buildTools: typeof import('../aiService').buildTools;
 let createModel: typeof import('../aiService').createModel;
+let getReasoningRequestOptions: typeof import('../aiService').getReasoningRequestOptions;
+let cleanConversationTitle: typeof import('../aiService').cleanConversationTitle;
 
 type ExecutableTool = {
-  execute: (input: unknown) => Promise<unknown>;
+  execute: (input: unknown, options?: unknown) => Promise<unknown>;
 };
 
 function createCallbacks(overrides: Partial<AiToolCallbacks> = {}): AiToolCallbacks {
@@ -82,6 +84,7 @@ function createCallbacks(overrides: Partial<AiToolCallbacks> = {}): AiToolCallba
     }),
     createProjectFile: () => true,
     editProjectFile: () => null,
+    writeProjectFile: () => null,
     requestRender: () => {},
     setRenderTarget: () => true,
     getMeasurementUnit: () => 'mm',
@@ -119,6 +122,49 @@ describe('createModel', () => {
   });
 });
 
+describe('getReasoningRequestOptions', () => {
+  beforeAll(async () => {
+    ({ getReasoningRequestOptions } = await import('../aiService'));
+  });
+
+  it('gives Claude a thinking budget within its output token limit', () => {
+    expect(
+      getReasoningRequestOptions(
+        'bedrock',
+        'us.anthropic.claude-opus-4-1-20250805-v1:0',
+        'high',
+        32000
+      )
+    ).toEqual({
+      maxOutputTokens: 32000 - 16384,
+      providerOptions: { anthropic: { thinking: { type: 'enabled', budgetTokens: 16384 } } },
+    });
+  });
+
+  it('sends a reasoning effort to OpenAI models and nothing to other models', () => {
+    expect(getReasoningRequestOptions('openai', 'gpt-5', 'low', 32000)).toEqual({
+      maxOutputTokens: 32000,
+      providerOptions: { openai: { reasoningEffort: 'low' } },
+    });
+    expect(getReasoningRequestOptions('openai', 'gpt-4o', 'high', 16384)).toEqual({
+      maxOutputTokens: 16384,
+    });
+  });
+});
+
+describe('cleanConversationTitle', () => {
+  beforeAll(async () => {
+    ({ cleanConversationTitle } = await import('../aiService'));
+  });
+
+  it('keeps the first line without labels, quotes or a trailing period', () => {
+    expect(cleanConversationTitle('"Parametric Gear Box."')).toBe('Parametric Gear Box');
+    expect(cleanConversationTitle('**Title:** Hex Bolt\nA bolt with a nut')).toBe('Hex Bolt');
+    expect(cleanConversationTitle('  \n')).toBeNull();
+    expect(cleanConversationTitle('x'.repeat(80))).toBe(`${'x'.repeat(57)}...`);
+  });
+});
+
 describe('buildTools', () => {
   beforeAll(async () => {
     ({ buildTools } = await import('../aiService'));
@@ -326,6 +372,23 @@ describe('buildTools', () => {
       expect(result.__checkpointId).toBeTruthy();
     });
 
+    it('links the checkpoint to the message whose request made the edit', async () => {
+      const { historyService } = await import('../../platform');
+      const tools = buildTools(createCallbacks()) as Record<string, ExecutableTool>;
+      const conversationLink = { conversationId: 'conv', messageId: 'msg-1', messageIndex: 2 };
+
+      const result = (await tools.apply_edit.execute(
+        { old_string: 'cube(10)', new_string: 'cube(20)' },
+        { toolCallId: 'call-1', messages: [], experimental_context: conversationLink }
+      )) as { __checkpointId: string };
+
+      expect(historyService.getById(result.__checkpointId)).toMatchObject({
+        conversation_id: 'conv',
+        message_id: 'msg-1',
+        message_index: 2,
+      });
+    });
+
     it('reports error when old_string not found in render target', async () => {
       const tools = buildTools(
         createCallbacks({
@@ -340,6 +403,213 @@ describe('buildTools', () => {
 
       expect(result).toContain('❌ Failed to apply edit');
     });
+
+    it('attaches a screenshot of the updated preview when edits are verified', async () => {
+      const tools = buildTools(
+        createCallbacks({
+          waitForPreviewUpdate: async () => true,
+          captureCurrentView: async () => 'data:image/png;base64,BBB=',
+        })
+      ) as Record<string, ExecutableTool>;
+
+      const result = await tools.apply_edit.execute({
+        file_path: 'lib/utils.scad',
+        old_string: 'cube(5)',
+        new_string: 'cube(10)',
+      });
+
+      expect(result).toEqual({
+        status: 'success',
+        message: 'Edit applied to lib/utils.scad.',
+        image_data_url: 'data:image/png;base64,BBB=',
+      });
+    });
+
+    it('reports when the preview does not update after a verified edit', async () => {
+      const tools = buildTools(
+        createCallbacks({ waitForPreviewUpdate: async () => false })
+      ) as Record<string, ExecutableTool>;
+
+      const result = await tools.apply_edit.execute({
+        file_path: 'lib/utils.scad',
+        old_string: 'cube(5)',
+        new_string: 'cube(10)',
+      });
+
+      expect(result).toMatchObject({
+        status: 'success',
+        preview_error: expect.stringContaining('did not update'),
+      });
+    });
+  });
+
+  describe('apply_edits', () => {
+    it('applies the replacements in order as one write under one checkpoint', async () => {
+      const { historyService } = await import('../../platform');
+      const writeProjectFile = jest.fn(() => null);
+      const tools = buildTools(createCallbacks({ writeProjectFile })) as Record<
+        string,
+        ExecutableTool
+      >;
+
+      const result = (await tools.apply_edits.execute({
+        edits: [
+          { old_string: 'cube(10);', new_string: 'cube(20);' },
+          { old_string: 'cube(20);', new_string: 'cube(20, center = true);' },
+        ],
+      })) as { status: 'success'; message: string; __checkpointId?: string };
+
+      expect(writeProjectFile).toHaveBeenCalledTimes(1);
+      expect(writeProjectFile).toHaveBeenCalledWith(
+        'main.scad',
+        'use <lib/utils.scad>\ncube(20, center = true);'
+      );
+      expect(result).toMatchObject({ status: 'success', message: 'Applied 2 replacements.' });
+      expect(historyService.getById(result.__checkpointId!)?.code).toBe(
+        'use <lib/utils.scad>\ncube(10);'
+      );
+    });
+
+    it('changes nothing when one of the replacements does not match', async () => {
+      const writeProjectFile = jest.fn(() => null);
+      const tools = buildTools(createCallbacks({ writeProjectFile })) as Record<
+        string,
+        ExecutableTool
+      >;
+
+      const result = await tools.apply_edits.execute({
+        file_path: 'lib/utils.scad',
+        edits: [
+          { old_string: 'cube(5)', new_string: 'cube(6)' },
+          { old_string: 'sphere(5)', new_string: 'sphere(6)' },
+        ],
+      });
+
+      expect(result).toContain('Replacement 2: old_string not found in the file');
+      expect(writeProjectFile).not.toHaveBeenCalled();
+    });
+
+    it('edits other files without a checkpoint', async () => {
+      const writeProjectFile = jest.fn(() => null);
+      const tools = buildTools(createCallbacks({ writeProjectFile })) as Record<
+        string,
+        ExecutableTool
+      >;
+
+      const result = await tools.apply_edits.execute({
+        file_path: 'lib/utils.scad',
+        edits: [{ old_string: 'cube(5)', new_string: 'cube(6)' }],
+      });
+
+      expect(writeProjectFile).toHaveBeenCalledWith(
+        'lib/utils.scad',
+        'module helper() { cube(6); }'
+      );
+      expect(result).toEqual({
+        status: 'success',
+        message: 'Applied 1 replacement to lib/utils.scad.',
+      });
+    });
+  });
+
+  describe('edit review', () => {
+    it('hands an edit over for review instead of writing it', async () => {
+      const writeProjectFile = jest.fn(() => null);
+      const proposeEdit = jest.fn(async (_path: string, _old: string, _new: string) => undefined);
+      const tools = buildTools(createCallbacks({ writeProjectFile, proposeEdit })) as Record<
+        string,
+        ExecutableTool
+      >;
+
+      const result = await tools.apply_edit.execute({
+        old_string: 'cube(10);',
+        new_string: 'cube(20);',
+      });
+
+      expect(result).toContain('waiting for the user to review it');
+      expect(writeProjectFile).not.toHaveBeenCalled();
+      expect(proposeEdit).toHaveBeenCalledWith(
+        'main.scad',
+        'use <lib/utils.scad>\ncube(10);',
+        'use <lib/utils.scad>\ncube(20);'
+      );
+    });
+
+    it('reports an edit that could not be held for review', async () => {
+      const writeProjectFile = jest.fn(() => null);
+      const proposeEdit = jest.fn(async () => {
+        throw 'No project is open to edit lib/utils.scad';
+      });
+      const tools = buildTools(createCallbacks({ writeProjectFile, proposeEdit })) as Record<
+        string,
+        ExecutableTool
+      >;
+
+      const result = await tools.edit_lines.execute({
+        file_path: 'lib/utils.scad',
+        start_line: 1,
+        end_line: 1,
+        new_text: 'module helper() { cube(6); }',
+      });
+
+      expect(result).toBe(
+        '❌ Failed to hold the edit to lib/utils.scad for review: No project is open to edit lib/utils.scad\n\nNothing was changed.'
+      );
+      expect(writeProjectFile).not.toHaveBeenCalled();
+    });
+  });
+
+  describe('get_current_code', () => {
+    it('numbers the lines of the render target when asked', async () => {
+      const tools = buildTools(createCallbacks()) as Record<string, ExecutableTool>;
+
+      await expect(tools.get_current_code.execute({ numbered: true })).resolves.toBe(
+        '1 | use <lib/utils.scad>\n2 | cube(10);\n'
+      );
+      await expect(tools.get_current_code.execute({ file_path: 'lib/utils.scad' })).resolves.toBe(
+        'module helper() { cube(5); }'
+      );
+    });
+  });
+
+  describe('edit_lines', () => {
+    it('replaces a line range of the render target under a checkpoint', async () => {
+      const { historyService } = await import('../../platform');
+      const writeProjectFile = jest.fn(() => null);
+      const tools = buildTools(createCallbacks({ writeProjectFile })) as Record<
+        string,
+        ExecutableTool
+      >;
+
+      const result = (await tools.edit_lines.execute({
+        start_line: 2,
+        end_line: 2,
+        new_text: 'helper();',
+      })) as { status: 'success'; message: string; __checkpointId?: string };
+
+      expect(writeProjectFile).toHaveBeenCalledWith('main.scad', 'use <lib/utils.scad>\nhelper();');
+      expect(result).toMatchObject({ status: 'success', message: 'Replaced lines 2-2.' });
+      expect(historyService.getById(result.__checkpointId!)?.code).toBe(
+        'use <lib/utils.scad>\ncube(10);'
+      );
+    });
+
+    it('rejects a range outside the file', async () => {
+      const writeProjectFile = jest.fn(() => null);
+      const tools = buildTools(createCallbacks({ writeProjectFile })) as Record<
+        string,
+        ExecutableTool
+      >;
+
+      const result = await tools.edit_lines.execute({
+        start_line: 4,
+        end_line: 4,
+        new_text: 'sphere(5);',
+      });
+
+      expect(result).toContain('Invalid line range 4-4: the file has 2 lines');
+      expect(writeProjectFile).not.toHaveBeenCalled();
+    });
   });
 
   describe('get_diagnostics', () => {
@@ -417,6 +687,63 @@ describe('buildTools', () => {
     });
   });
 
+  describe('write_file', () => {
+    it('replaces the render target under a checkpoint', async () => {
+      const { historyService } = await import('../../platform');
+      const writeProjectFile = jest.fn(() => null);
+      const tools = buildTools(createCallbacks({ writeProjectFile })) as Record<
+        string,
+        ExecutableTool
+      >;
+
+      const result = await tools.write_file.execute({
+        file_path: 'main.scad',
+        content: 'sphere(5);',
+      });
+
+      expect(result).toMatchObject({ status: 'success', message: 'Wrote main.scad.' });
+      expect(writeProjectFile).toHaveBeenCalledWith('main.scad', 'sphere(5);');
+      const checkpoints = historyService.getAll();
+      expect(checkpoints[checkpoints.length - 1]).toMatchObject({
+        code: 'use <lib/utils.scad>\ncube(10);',
+        description: 'Before AI edit',
+      });
+    });
+
+    it('replaces other files and creates missing ones', async () => {
+      const writeProjectFile = jest.fn(() => null);
+      const createProjectFile = jest.fn(() => true);
+      const requestRender = jest.fn();
+      const tools = buildTools(
+        createCallbacks({ writeProjectFile, createProjectFile, requestRender })
+      ) as Record<string, ExecutableTool>;
+
+      await expect(
+        tools.write_file.execute({ file_path: './lib/utils.scad', content: 'module helper() {}' })
+      ).resolves.toMatchObject({ message: 'Wrote lib/utils.scad.' });
+      expect(writeProjectFile).toHaveBeenCalledWith('lib/utils.scad', 'module helper() {}');
+
+      await expect(
+        tools.write_file.execute({ file_path: 'parts/hinge.scad', content: 'cube(2);' })
+      ).resolves.toMatchObject({ message: expect.stringContaining('Created parts/hinge.scad') });
+      expect(createProjectFile).toHaveBeenCalledWith('parts/hinge.scad', 'cube(2);');
+      expect(requestRender).toHaveBeenCalledTimes(2);
+    });
+
+    it('refuses paths outside the project', async () => {
+      const writeProjectFile = jest.fn(() => null);
+      const tools = buildTools(createCallbacks({ writeProjectFile })) as Record<
+        string,
+        ExecutableTool
+      >;
+
+      await expect(
+        tools.write_file.execute({ file_path: '../outside.scad', content: 'cube(1);' })
+      ).resolves.toBe('❌ ../outside.scad is outside the project.');
+      expect(writeProjectFile).not.toHaveBeenCalled();
+    });
+  });
+
   describe('set_render_target', () => {
     it('changes the render target', async () => {
       const setRenderTarget = jest.fn(() => true);
@@ -458,6 +785,64 @@ describe('buildTools', () => {
     });
   });
 
+  describe('tool permissions', () => {
+    it('skips tool calls the user does not allow', async () => {
+      const editProjectFile = jest.fn(() => null);
+      const authorizeTool = jest.fn(async (toolName: string) =>
+        toolName === 'apply_edit' ? 'The user declined the `apply_edit` call.' : null
+      );
+      const tools = buildTools(createCallbacks({ editProjectFile, authorizeTool })) as Record<
+        string,
+        ExecutableTool
+      >;
+
+      const denied = (await tools.apply_edit.execute({
+        file_path: 'lib/utils.scad',
+        old_string: 'cube(5)',
+        new_string: 'cube(6)',
+      })) as string;
+      const allowed = (await tools.read_file.execute({ path: 'main.scad' })) as string;
+
+      expect(denied).toBe('❌ The user declined the `apply_edit` call.');
+      expect(editProjectFile).not.toHaveBeenCalled();
+      expect(allowed).toContain('cube(10)');
+      expect(authorizeTool).toHaveBeenCalledWith('read_file', { path: 'main.scad' });
+    });
+  });
+
+  describe('tool timeouts', () => {
+    afterEach(() => {
+      jest.useRealTimers();
+    });
+
+    it('answers with an error when a tool runs past its timeout', async () => {
+      jest.useFakeTimers();
+      const tools = buildTools(
+        createCallbacks({
+          getRenderValidationInputs: () => new Promise(() => {}),
+          toolTimeouts: { get_diagnostics: 5 },
+        })
+      ) as Record<string, ExecutableTool>;
+
+      const pending = tools.get_diagnostics.execute({}) as Promise<string>;
+      await jest.advanceTimersByTimeAsync(5000);
+
+      await expect(pending).resolves.toContain('❌ `get_diagnostics` timed out after 5s');
+    });
+
+    it('uses the defaults shared with the MCP server when there is no override', async () => {
+      jest.useFakeTimers();
+      const tools = buildTools(
+        createCallbacks({ getRenderValidationInputs: () => new Promise(() => {}) })
+      ) as Record<string, ExecutableTool>;
+
+      const pending = tools.get_diagnostics.execute({}) as Promise<string>;
+      await jest.advanceTimersByTimeAsync(150_000);
+
+      await expect(pending).resolves.toContain('❌ `get_diagnostics` timed out after 150s');
+    });
+  });
+
   it('captures off-angle screenshots through the generic 3D preview callback', async () => {
     const tools = buildTools(
       createCallbacks({
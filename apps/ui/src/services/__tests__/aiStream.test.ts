@@ -0,0 +1,55 @@
+import { jest } from '@jest/globals';
+import { APICallError } from 'ai';
+import { getRetryDelayMs, isTransientStreamError, withStreamRetries } from '../aiStream';
+
+function apiError(statusCode: number, responseHeaders?: Record<string, string>) {
+  return new APICallError({
+    message: `HTTP ${statusCode}`,
+    url: 'https://api.anthropic.com/v1/messages',
+    requestBodyValues: {},
+    statusCode,
+    responseHeaders,
+  });
+}
+
+describe('aiStream', () => {
+  it('retries rate limits and server errors but not request errors', () => {
+    expect(isTransientStreamError(apiError(429))).toBe(true);
+    expect(isTransientStreamError(apiError(529))).toBe(true);
+    expect(isTransientStreamError(apiError(400))).toBe(false);
+    expect(isTransientStreamError(new Error('Invalid API key'))).toBe(false);
+  });
+
+  it('waits as long as the provider asks, or backs off exponentially', () => {
+    expect(getRetryDelayMs(apiError(429, { 'retry-after': '7' }), 1)).toBe(7000);
+    expect(getRetryDelayMs(apiError(429, { 'retry-after-ms': '250' }), 1)).toBe(250);
+    expect(getRetryDelayMs(apiError(429, { 'retry-after': '3600' }), 1)).toBe(60000);
+    expect(getRetryDelayMs(apiError(503), 1)).toBe(1000);
+    expect(getRetryDelayMs(apiError(503), 3)).toBe(4000);
+  });
+
+  it('reports each retry and returns the first successful call', async () => {
+    const call = jest
+      .fn<() => Promise<string>>()
+      .mockRejectedValueOnce(apiError(429, { 'retry-after-ms': '1' }))
+      .mockRejectedValueOnce(apiError(529, { 'retry-after-ms': '1' }))
+      .mockResolvedValueOnce('stream');
+    const onRetry = jest.fn();
+
+    await expect(withStreamRetries(call, { onRetry })).resolves.toBe('stream');
+
+    expect(call).toHaveBeenCalledTimes(3);
+    expect(onRetry.mock.calls).toEqual([
+      [{ attempt: 1, maxAttempts: 4, delayMs: 1, statusCode: 429 }],
+      [{ attempt: 2, maxAttempts: 4, delayMs: 1, statusCode: 529 }],
+    ]);
+  });
+
+  it('gives up on errors that retrying will not fix', async () => {
+    const error = apiError(401);
+    const call = jest.fn<() => Promise<string>>().mockRejectedValue(error);
+
+    await expect(withStreamRetries(call)).rejects.toBe(error);
+    expect(call).toHaveBeenCalledTimes(1);
+  });
+});
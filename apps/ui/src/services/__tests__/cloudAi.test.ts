@@ -0,0 +1,68 @@
+import { jest } from '@jest/globals';
+
+type ChannelMessage = { event: string; data?: unknown };
+
+class MockChannel {
+  onmessage: (message: ChannelMessage) => void = () => {};
+}
+
+const invoke = jest.fn<(command: string, args?: Record<string, unknown>) => Promise<unknown>>();
+
+jest.unstable_mockModule('@tauri-apps/api/core', () => ({
+  Channel: MockChannel,
+  invoke,
+}));
+
+describe('createCloudFetch', () => {
+  let createCloudFetch: typeof import('../cloudAi').createCloudFetch;
+
+  beforeAll(async () => {
+    ({ createCloudFetch } = await import('../cloudAi'));
+  });
+
+  beforeEach(() => {
+    invoke.mockReset();
+  });
+
+  it('streams the backend response as a fetch Response', async () => {
+    invoke.mockImplementation(async (command, args) => {
+      if (command !== 'cloud_ai_request') return undefined;
+      const channel = args?.onEvent as MockChannel;
+      channel.onmessage({ event: 'response', data: { status: 200 } });
+      channel.onmessage({ event: 'chunk', data: 'event: message_start\n' });
+      channel.onmessage({ event: 'chunk', data: 'data: {"type":"message_start"}\n\n' });
+      channel.onmessage({ event: 'end' });
+      return undefined;
+    });
+
+    const cloudFetch = createCloudFetch('bedrock');
+    const response = await cloudFetch('https://api.anthropic.com/v1/messages', {
+      method: 'POST',
+      headers: { 'anthropic-beta': 'a-2025, b-2025', 'x-api-key': 'cloud' },
+      body: JSON.stringify({ model: 'us.anthropic.claude-sonnet-4-5', stream: true }),
+    });
+
+    expect(response.status).toBe(200);
+    expect(response.headers.get('content-type')).toBe('text/event-stream');
+    expect(await response.text()).toBe('event: message_start\ndata: {"type":"message_start"}\n\n');
+    expect(invoke).toHaveBeenCalledWith(
+      'cloud_ai_request',
+      expect.objectContaining({
+        provider: 'bedrock',
+        body: { model: 'us.anthropic.claude-sonnet-4-5', stream: true },
+        betas: ['a-2025', 'b-2025'],
+      })
+    );
+  });
+
+  it('rejects when the backend cannot send the request', async () => {
+    invoke.mockRejectedValue('No AWS credentials in the environment or ~/.aws/credentials');
+
+    await expect(
+      createCloudFetch('vertex')('https://api.anthropic.com/v1/messages', {
+        method: 'POST',
+        body: JSON.stringify({ model: 'claude-sonnet-4-5@20250929' }),
+      })
+    ).rejects.toThrow('No AWS credentials');
+  });
+});
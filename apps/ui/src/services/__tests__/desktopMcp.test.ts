@@ -18,6 +18,7 @@ const mockReadTextFile = jest.fn(async (absolutePath: string) => {
 const mockGetRenderTargetContent = jest.fn();
 const mockListProjectFiles = jest.fn(() => ['main.scad']);
 const mockExportModel = jest.fn();
+const mockWriteFile = jest.fn(async () => undefined);
 const mockResolveWorkingDirDepsDetailed = jest.fn(async () => ({
   files: {},
   missingPaths: [],
@@ -62,6 +63,12 @@ jest.unstable_mockModule('@tauri-apps/api/core', () => ({
 
 jest.unstable_mockModule('@tauri-apps/api/path', () => ({
   join: async (...parts: string[]) => parts.join('/'),
+  dirname: async (path: string) => path.slice(0, path.lastIndexOf('/')),
+}));
+
+jest.unstable_mockModule('@tauri-apps/plugin-fs', () => ({
+  mkdir: jest.fn(async () => undefined),
+  writeFile: (...args: unknown[]) => mockWriteFile(...args),
 }));
 
 jest.unstable_mockModule(renderServiceModule, () => ({
@@ -169,6 +176,8 @@ type ToolResponse = {
     { type: 'text'; text: string } | { type: 'image'; data: string; mimeType: string }
   >;
   isError?: boolean;
+  data?: Record<string, unknown>;
+  diagnostics?: Array<{ severity: string; message: string }>;
 };
 
 function getText(response: ToolResponse): string {
@@ -262,6 +271,7 @@ describe('desktopMcp', () => {
 
   beforeEach(() => {
     jest.clearAllMocks();
+    mockInvoke.mockReset();
     setProjectState();
     nextRequestId = 1;
     lastStartedRequestId = 0;
@@ -351,6 +361,15 @@ describe('desktopMcp', () => {
 
     expect(response.isError).not.toBe(true);
     expect(getText(response)).toContain('[Warning]');
+    expect(response.diagnostics).toEqual([
+      { severity: 'warning', message: 'Object may be non-manifold' },
+    ]);
+    expect(response.data).toEqual({
+      render_target: 'main.scad',
+      render_error: null,
+      error_count: 0,
+      warning_count: 1,
+    });
   });
 
   it('uses the newly settled preview for screenshots immediately after trigger_render', async () => {
@@ -583,6 +602,47 @@ describe('desktopMcp', () => {
     expect(text).toContain('requires an explicit `view` argument');
   });
 
+  it('captures several camera views in one capture_views call', async () => {
+    const response = (await executeToolRequestForTests({
+      requestId: 'req-views',
+      toolName: 'capture_views',
+      arguments: {
+        views: ['front', { azimuth: 90, elevation: 10 }, { direction: [0, 0, 1] }],
+      },
+    })) as ToolResponse;
+
+    expect(response.isError).not.toBe(true);
+    expect(mockCaptureOffscreen).toHaveBeenCalledTimes(3);
+    expect(mockCaptureOffscreen).toHaveBeenNthCalledWith(
+      1,
+      'blob:preview',
+      expect.objectContaining({ view: 'front' })
+    );
+    expect(mockCaptureOffscreen).toHaveBeenNthCalledWith(
+      2,
+      'blob:preview',
+      expect.objectContaining({ azimuth: 90, elevation: 10 })
+    );
+    expect(mockCaptureOffscreen).toHaveBeenNthCalledWith(
+      3,
+      'blob:preview',
+      expect.objectContaining({ direction: [0, 0, 1] })
+    );
+    expect(response.content.filter((item) => item.type === 'image')).toHaveLength(3);
+  });
+
+  it('rejects invalid capture_views requests before capturing', async () => {
+    const response = (await executeToolRequestForTests({
+      requestId: 'req-bad-views',
+      toolName: 'capture_views',
+      arguments: { views: ['front', 'sideways'] },
+    })) as ToolResponse;
+
+    expect(response.isError).toBe(true);
+    expect(getText(response)).toContain('View 2: Unsupported view: sideways');
+    expect(mockCaptureOffscreen).not.toHaveBeenCalled();
+  });
+
   it('adds render-target guidance when explicit-view screenshots only have a 2D artifact', async () => {
     setProjectState({ renderTargetPath: 'layout/top_plate.scad' });
     getRenderArtifactState().publishSettledArtifact({
@@ -658,4 +718,73 @@ describe('desktopMcp', () => {
     expect(text).toContain('Parser error at line 9');
     expect(text).toContain('`get_project_context`');
   });
+  it('applies a parameter set from a project parameter file to export_file', async () => {
+    mockExportModel.mockResolvedValue(new Uint8Array([1, 2, 3]));
+
+    const response = (await executeToolRequestForTests({
+      requestId: 'req-8',
+      toolName: 'export_file',
+      arguments: {
+        format: 'stl',
+        file_path: '/tmp/main.stl',
+        parameter_file: 'presets/sizes.json',
+        parameter_set: 'large',
+      },
+    })) as ToolResponse;
+
+    expect(response.isError).toBeFalsy();
+    expect(getText(response)).toContain('Applied parameter set "large" from presets/sizes.json.');
+    expect(mockExportModel).toHaveBeenCalledWith(
+      'cube(10);',
+      'stl',
+      expect.objectContaining({ parameterFile: 'presets/sizes.json', parameterSet: 'large' })
+    );
+    expect(mockWriteFile).toHaveBeenCalledWith('/tmp/main.stl', new Uint8Array([1, 2, 3]));
+    expect(response.data).toEqual({
+      format: 'stl',
+      path: '/tmp/main.stl',
+      bytes: 3,
+      parameter_file: 'presets/sizes.json',
+      parameter_set: 'large',
+      triangles: null,
+    });
+  });
+
+  it('rejects a parameter set without its parameter file', async () => {
+    const response = (await executeToolRequestForTests({
+      requestId: 'req-9',
+      toolName: 'export_file',
+      arguments: { format: 'stl', file_path: '/tmp/main.stl', parameter_set: 'large' },
+    })) as ToolResponse;
+
+    expect(response.isError).toBe(true);
+    expect(getText(response)).toContain('must be given together');
+    expect(mockExportModel).not.toHaveBeenCalled();
+  });
+  it('decimates an STL export to target_triangles', async () => {
+    mockExportModel.mockResolvedValue(new Uint8Array([1, 2, 3]));
+    mockInvoke.mockImplementation(async (command: unknown) =>
+      command === 'decimate_mesh_file'
+        ? {
+            before: { triangle_count: 5000 },
+            after: { triangle_count: 1000 },
+            outputPath: '/tmp/main.stl',
+          }
+        : undefined
+    );
+
+    const response = (await executeToolRequestForTests({
+      requestId: 'req-10',
+      toolName: 'export_file',
+      arguments: { format: 'stl', file_path: '/tmp/main.stl', target_triangles: 1000 },
+    })) as ToolResponse;
+
+    expect(response.isError).toBeFalsy();
+    expect(getText(response)).toContain('Decimated from 5000 to 1000 triangles.');
+    expect(response.data).toMatchObject({ triangles: 1000 });
+    expect(mockInvoke).toHaveBeenCalledWith('decimate_mesh_file', {
+      path: '/tmp/main.stl',
+      options: { targetTriangles: 1000 },
+    });
+  });
 });
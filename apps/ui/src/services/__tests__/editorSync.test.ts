@@ -0,0 +1,106 @@
+/** @jest-environment jsdom */
+
+import { jest } from '@jest/globals';
+
+let nextDocument = 0;
+const invoke = jest.fn(async (command: string, _args?: Record<string, unknown>) => {
+  switch (command) {
+    case 'list_documents':
+      return [{ id: 'stale', title: 'Untitled', path: null, isActive: true }];
+    case 'open_document':
+      nextDocument += 1;
+      return { document: { id: `doc-${nextDocument}` }, code: (_args as { code: string }).code };
+    default:
+      return null;
+  }
+});
+
+jest.unstable_mockModule('@tauri-apps/api/core', () => ({ invoke }));
+jest.unstable_mockModule('@tauri-apps/api/window', () => ({
+  getCurrentWindow: () => ({ label: 'main' }),
+}));
+
+const { startEditorSync, documentIdForTab, sendEditorEdits, whenEditorSynced } = await import(
+  '../editorSync'
+);
+const { workspaceStore, resetWorkspaceStore } = await import('../../stores/workspaceStore');
+const { getProjectStore } = await import('../../stores/projectStore');
+
+function calls(command: string) {
+  return invoke.mock.calls.filter(([name]) => name === command).map(([, args]) => args);
+}
+
+describe('editorSync', () => {
+  let stop: () => void = () => {};
+
+  beforeEach(() => {
+    (window as unknown as Record<string, unknown>).__TAURI_INTERNALS__ = {};
+    invoke.mockClear();
+    nextDocument = 0;
+    resetWorkspaceStore();
+    getProjectStore()
+      .getState()
+      .openProject(null, { 'main.scad': 'cube(1);', 'lid.scad': 'cube(2);' }, 'main.scad');
+  });
+
+  afterEach(() => {
+    stop();
+    delete (window as unknown as Record<string, unknown>).__TAURI_INTERNALS__;
+  });
+
+  it('opens a document per tab and closes documents left from before', async () => {
+    stop = await startEditorSync();
+    await whenEditorSynced();
+
+    const [tab] = workspaceStore.getState().tabs;
+    expect(calls('open_document')).toEqual([
+      { code: 'cube(1);', path: null, title: 'main.scad', activateDocument: true },
+    ]);
+    expect(documentIdForTab(tab.id)).toBe('doc-1');
+    expect(calls('close_document')).toEqual([{ documentId: 'stale' }]);
+  });
+
+  it('follows tab switches, edits and closes', async () => {
+    stop = await startEditorSync();
+    const [main] = workspaceStore.getState().tabs;
+    const lid = workspaceStore
+      .getState()
+      .createTab({ filePath: '/work/lid.scad', name: 'lid.scad', projectPath: 'lid.scad' });
+    await whenEditorSynced();
+    expect(calls('switch_document')).toContainEqual({ documentId: 'doc-2' });
+
+    getProjectStore().getState().updateFileContent('main.scad', 'cube(3);');
+    workspaceStore.getState().setActiveTab(main.id);
+    workspaceStore.getState().closeTabLocal(lid);
+    await whenEditorSynced();
+
+    expect(calls('update_editor_state')).toEqual([{ code: 'cube(3);', documentId: 'doc-1' }]);
+    expect(calls('switch_document')).toContainEqual({ documentId: 'doc-1' });
+    expect(calls('close_document')).toContainEqual({ documentId: 'doc-2' });
+    expect(documentIdForTab(lid)).toBeUndefined();
+  });
+
+  it('sends typing as edits and falls back to the whole text when they fail', async () => {
+    stop = await startEditorSync();
+    const [tab] = workspaceStore.getState().tabs;
+
+    sendEditorEdits(tab.id, [{ rangeOffset: 5, rangeLength: 1, text: '4' }], 'cube(4);');
+    getProjectStore().getState().updateFileContent('main.scad', 'cube(4);');
+    await whenEditorSynced();
+    expect(calls('apply_editor_edits')).toEqual([
+      {
+        edits: [{ rangeOffset: 5, rangeLength: 1, text: '4' }],
+        expectedLength: 8,
+        documentId: 'doc-1',
+      },
+    ]);
+    expect(calls('update_editor_state')).toEqual([]);
+
+    invoke.mockImplementationOnce(async () => {
+      throw new Error('Editor state out of sync');
+    });
+    sendEditorEdits(tab.id, [{ rangeOffset: 5, rangeLength: 1, text: '5' }], 'cube(5);');
+    await whenEditorSynced();
+    expect(calls('update_editor_state')).toEqual([{ code: 'cube(5);', documentId: 'doc-1' }]);
+  });
+});
@@ -0,0 +1,49 @@
+import { jest } from '@jest/globals';
+
+const invoke = jest.fn();
+
+jest.unstable_mockModule('@tauri-apps/api/core', () => ({ invoke }));
+
+const { settleFileChange } = await import('../fileChanges');
+
+const change = { root: '/projects/box', path: 'lid.scad', removed: false, conflict: false };
+
+describe('fileChanges', () => {
+  beforeEach(() => {
+    invoke.mockClear();
+  });
+
+  it('reloads clean files without asking', async () => {
+    const confirm = jest.fn(async () => true);
+
+    await expect(settleFileChange(change, confirm)).resolves.toBe(true);
+
+    expect(confirm).not.toHaveBeenCalled();
+    expect(invoke).toHaveBeenCalledWith('resolve_file_change', {
+      root: '/projects/box',
+      path: 'lid.scad',
+      reload: true,
+    });
+  });
+
+  it('keeps unsaved edits when the user declines the reload', async () => {
+    const confirm = jest.fn(async () => false);
+
+    await expect(settleFileChange({ ...change, conflict: true }, confirm)).resolves.toBe(false);
+
+    expect(confirm).toHaveBeenCalledTimes(1);
+    expect(invoke).toHaveBeenCalledWith('resolve_file_change', {
+      root: '/projects/box',
+      path: 'lid.scad',
+      reload: false,
+    });
+  });
+
+  it('leaves removed files to the project tree', async () => {
+    await expect(settleFileChange({ ...change, removed: true }, async () => true)).resolves.toBe(
+      false
+    );
+
+    expect(invoke).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,8 @@
+import { lithophaneFileName } from '../lithophane';
+
+describe('lithophane', () => {
+  it('names the generated document after the photo', () => {
+    expect(lithophaneFileName('/Users/me/Pictures/cat.png')).toBe('cat-lithophane.scad');
+    expect(lithophaneFileName('C:\\photos\\family.photo.png')).toBe('family.photo-lithophane.scad');
+  });
+});
@@ -24,6 +24,14 @@ beforeAll(() => {
   });
 });
 
+async function waitForCall(command: string): Promise<void> {
+  for (let attempt = 0; attempt < 50; attempt += 1) {
+    if (invoke.mock.calls.some(([called]) => called === command)) return;
+    await new Promise((resolve) => setTimeout(resolve, 0));
+  }
+  throw new Error(`${command} was never invoked`);
+}
+
 describe('NativeRenderService', () => {
   beforeEach(() => {
     jest.resetModules();
@@ -78,7 +86,44 @@ describe('NativeRenderService', () => {
       diagnostics: [],
     });
 
-    expect(invoke.mock.calls.filter(([command]) => command === 'render_native')).toHaveLength(2);
+    const nativeCalls = invoke.mock.calls.filter(([command]) => command === 'render_native');
+    expect(nativeCalls).toHaveLength(2);
+    expect(nativeCalls[0][1]).toEqual(expect.objectContaining({ checkFinalBranch: true }));
+  });
+
+  it("doesn't cache stale results served while a document re-renders", async () => {
+    let renderCount = 0;
+    invoke.mockImplementation(async (command: string) => {
+      if (command === 'render_init') return 'OpenSCAD 2026.03.16';
+      if (command === 'render_native') {
+        renderCount += 1;
+        return {
+          output: [renderCount],
+          stderr: '',
+          exit_code: 0,
+          duration_ms: 1,
+          stale: renderCount === 1,
+        };
+      }
+      throw new Error(`Unexpected command: ${command}`);
+    });
+
+    const { NativeRenderService } = await import('../nativeRenderService');
+    const service = new NativeRenderService();
+    const options = { view: '3d', documentId: 'doc-1' } as const;
+
+    await expect(service.render('cube(10);', options)).resolves.toEqual(
+      expect.objectContaining({ output: new Uint8Array([1]) })
+    );
+    await expect(service.render('cube(10);', options)).resolves.toEqual(
+      expect.objectContaining({ output: new Uint8Array([2]) })
+    );
+
+    const nativeCalls = invoke.mock.calls.filter(([command]) => command === 'render_native');
+    expect(nativeCalls).toHaveLength(2);
+    expect(nativeCalls[0][1]).toEqual(
+      expect.objectContaining({ documentId: 'doc-1', staleWhileRevalidate: true })
+    );
   });
 
   it('invalidates cached renders when the render target path changes', async () => {
@@ -170,15 +215,13 @@ describe('NativeRenderService', () => {
     );
   });
 
-  it('retries syntax checks in 2D mode when the 3D pass only reports a top-level mismatch', async () => {
+  it('checks syntax with a single evaluation that builds no geometry', async () => {
     invoke.mockImplementation(async (command: string) => {
       if (command === 'render_init') return 'OpenSCAD 2026.03.16';
       if (command === 'render_native') {
-        const renderCalls = invoke.mock.calls.filter(([name]) => name === 'render_native').length;
         return {
           output: [],
-          stderr:
-            renderCalls === 1 ? 'Current top level object is not a 3D object.' : 'WARNING: 2D note',
+          stderr: 'WARNING: 2D note',
           exit_code: 0,
           duration_ms: 1,
         };
@@ -201,16 +244,67 @@ describe('NativeRenderService', () => {
     });
 
     const nativeCalls = invoke.mock.calls.filter(([command]) => command === 'render_native');
-    expect(nativeCalls).toHaveLength(2);
+    expect(nativeCalls).toHaveLength(1);
     expect(nativeCalls[0][1]).toEqual(
       expect.objectContaining({
-        args: ['/input.scad', '-o', '/output.stl', '--backend=manifold'],
-      })
-    );
-    expect(nativeCalls[1][1]).toEqual(
-      expect.objectContaining({
-        args: ['/input.scad', '-o', '/output.svg', '--backend=manifold'],
+        args: ['/input.scad', '-o', '/output.echo'],
+        checkFinalBranch: false,
       })
     );
   });
+
+  it('cancels only the renders it started, by job id', async () => {
+    let finishRender: (() => void) | undefined;
+    invoke.mockImplementation(async (command: string) => {
+      if (command === 'render_init') return 'OpenSCAD 2026.03.16';
+      if (command === 'render_native') {
+        await new Promise<void>((resolve) => {
+          finishRender = resolve;
+        });
+        return { output: [], stderr: 'Render cancelled', exit_code: -1, duration_ms: 1 };
+      }
+      if (command === 'cancel_render') return undefined;
+      throw new Error(`Unexpected command: ${command}`);
+    });
+
+    const { NativeRenderService } = await import('../nativeRenderService');
+    const service = new NativeRenderService();
+
+    const render = service.render('cube(10);', { view: '3d' });
+    await waitForCall('render_native');
+    const { jobId } = invoke.mock.calls.find(([command]) => command === 'render_native')![1] as {
+      jobId: string;
+    };
+
+    service.cancel();
+    expect(invoke).toHaveBeenCalledWith('cancel_render', { jobId });
+    expect(invoke).not.toHaveBeenCalledWith('render_cancel');
+
+    finishRender!();
+    await render;
+    invoke.mockClear();
+    service.cancel();
+    expect(invoke).not.toHaveBeenCalled();
+  });
+
+  it('falls back to the WebAssembly renderer when no OpenSCAD binary is found', async () => {
+    invoke.mockImplementation(async (command: string) => {
+      if (command === 'render_init') throw 'OpenSCAD binary not found.';
+      throw new Error(`Unexpected command: ${command}`);
+    });
+    const warn = jest.spyOn(console, 'warn').mockImplementation(() => {});
+    Object.defineProperty(window, '__TAURI_INTERNALS__', { configurable: true, value: {} });
+
+    try {
+      const { WasmRenderService, ensureRenderService, isWasmFallback } = await import(
+        '../renderService'
+      );
+      await expect(ensureRenderService()).resolves.toBeInstanceOf(WasmRenderService);
+      expect(isWasmFallback()).toBe(true);
+      expect(invoke).toHaveBeenCalledWith('render_init');
+    } finally {
+      delete (window as { __TAURI_INTERNALS__?: unknown }).__TAURI_INTERNALS__;
+      warn.mockRestore();
+    }
+  });
 });
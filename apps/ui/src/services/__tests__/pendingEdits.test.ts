@@ -0,0 +1,33 @@
+import { uniqueReplacement } from '../pendingEdits';
+
+function applyReplacement(code: string, newCode: string) {
+  const replacement = uniqueReplacement(code, newCode);
+  if (!replacement) return null;
+  expect(code.split(replacement.oldString)).toHaveLength(2);
+  return code.replace(replacement.oldString, replacement.newString);
+}
+
+describe('uniqueReplacement', () => {
+  it('replaces just the changed lines', () => {
+    expect(uniqueReplacement('cube(1);\nsphere(2);\n', 'cube(1);\nsphere(3);\n')).toEqual({
+      oldString: 'sphere(2);\n',
+      newString: 'sphere(3);\n',
+    });
+    expect(uniqueReplacement('a\nb\n', 'a\nx\nb\n')).toEqual({
+      oldString: 'b\n',
+      newString: 'x\nb\n',
+    });
+  });
+
+  it('widens the replacement until it is unique', () => {
+    const code = 'cube(1);\ncube(1);\nsphere(1);\n';
+    const next = 'cube(1);\ncube(2);\nsphere(1);\n';
+    expect(applyReplacement(code, next)).toBe(next);
+    expect(applyReplacement('\nx\n\nx\n', '\nx\n\ny\n')).toBe('\nx\n\ny\n');
+    expect(applyReplacement('x\nx\nx', 'x\nx')).toBe('x\nx');
+  });
+
+  it('has no replacement for an empty file', () => {
+    expect(uniqueReplacement('', 'cube(1);')).toBeNull();
+  });
+});
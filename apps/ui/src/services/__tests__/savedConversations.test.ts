@@ -0,0 +1,74 @@
+import { jest } from '@jest/globals';
+import type { Conversation } from '../../types/aiChat';
+
+const invoke = jest.fn<(command: string, args?: unknown) => Promise<unknown>>();
+
+jest.unstable_mockModule('@tauri-apps/api/core', () => ({ invoke }));
+
+const { conversationExportFormat, exportConversation, importConversation } = await import(
+  '../savedConversations'
+);
+
+const conversation: Conversation = {
+  id: 'conv-1',
+  title: 'Rounded box',
+  timestamp: 1,
+  messages: [
+    { id: 'm1', type: 'user', timestamp: 1, parts: [{ type: 'text', text: 'Round it' }] },
+    {
+      id: 'm2',
+      type: 'tool-call',
+      timestamp: 2,
+      toolCallId: 't1',
+      toolName: 'apply_edit',
+      state: 'error',
+      errorText: 'old_string not found',
+    },
+  ],
+};
+
+describe('savedConversations', () => {
+  beforeEach(() => {
+    invoke.mockReset();
+  });
+
+  it('exports conversations in their stored form', async () => {
+    invoke.mockResolvedValue(undefined);
+
+    await exportConversation(conversation, {}, 'markdown', '/tmp/box.md');
+
+    const [command, args] = invoke.mock.calls[0] as [string, Record<string, unknown>];
+    expect(command).toBe('export_conversation');
+    expect(args).toMatchObject({ format: 'markdown', path: '/tmp/box.md', attachments: undefined });
+    expect(args.conversation).toMatchObject({
+      messages: [
+        { id: 'm1', role: 'user', content: [{ type: 'text', text: 'Round it' }] },
+        {
+          id: 'm2',
+          role: 'assistant',
+          content: [
+            { type: 'tool-call', toolCallId: 't1', toolName: 'apply_edit', state: 'error' },
+            { type: 'tool-result', toolCallId: 't1', errorText: 'old_string not found' },
+          ],
+        },
+      ],
+    });
+  });
+
+  it('imports exports back into chat messages', async () => {
+    invoke.mockResolvedValue(undefined);
+    await exportConversation(conversation, {}, 'json', '/tmp/box.json');
+    const exported = invoke.mock.calls[0][1] as { conversation: unknown };
+    invoke.mockResolvedValue({ conversation: exported.conversation, attachments: {} });
+
+    const imported = await importConversation('/tmp/box.json');
+
+    expect(invoke).toHaveBeenLastCalledWith('import_conversation', { path: '/tmp/box.json' });
+    expect(imported.conversation).toEqual(conversation);
+  });
+
+  it('picks the export format from the file extension', () => {
+    expect(conversationExportFormat('/tmp/box.MD')).toBe('markdown');
+    expect(conversationExportFormat('/tmp/box.json')).toBe('json');
+  });
+});
@@ -0,0 +1,43 @@
+/**
+ * Custom AI instructions (desktop). Global instructions and the open
+ * project's `.openscad-studio/instructions.md` are merged into the system
+ * prompt by the backend.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface AiInstructions {
+  global: string;
+  /** Null when no project is open */
+  project: string | null;
+  projectPath: string | null;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+export async function getAiInstructions(): Promise<AiInstructions> {
+  return invoke<AiInstructions>('get_ai_instructions');
+}
+
+export async function setGlobalAiInstructions(content: string): Promise<void> {
+  await invoke('set_global_ai_instructions', { content });
+}
+
+export async function setProjectAiInstructions(content: string): Promise<void> {
+  await invoke('set_project_ai_instructions', { content });
+}
+
+/**
+ * `basePrompt` with the user's instructions appended. Falls back to
+ * `basePrompt` on the web or when the instruction files cannot be read.
+ */
+export async function getEffectiveSystemPrompt(basePrompt: string): Promise<string> {
+  if (!isDesktopTauri()) return basePrompt;
+  try {
+    return await invoke<string>('get_effective_system_prompt', { basePrompt });
+  } catch (error) {
+    console.warn('[aiInstructions] Using the built-in system prompt:', error);
+    return basePrompt;
+  }
+}
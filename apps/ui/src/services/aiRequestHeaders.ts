@@ -0,0 +1,29 @@
+/**
+ * Extra headers for model requests (desktop). The backend's AI provider
+ * settings hold the OpenAI organization and project and any additional
+ * Anthropic headers; `get_ai_request_headers` turns them into headers.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/**
+ * A `fetch` that adds the headers configured for `provider` in the AI
+ * provider settings: the OpenAI organization and project, or the extra
+ * Anthropic headers. Only the desktop app has these settings.
+ */
+export function withAiRequestHeaders(provider: 'openai' | 'anthropic'): typeof fetch | undefined {
+  if (!isDesktopTauri()) return undefined;
+  return async (input, init) => {
+    const configured = await invoke<Record<string, string>>('get_ai_request_headers', {
+      provider,
+    });
+    const headers = new Headers(init?.headers);
+    for (const [name, value] of Object.entries(configured)) {
+      headers.set(name, value);
+    }
+    return fetch(input, { ...init, headers });
+  };
+}
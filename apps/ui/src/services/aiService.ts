@@ -1,12 +1,30 @@
-import { tool } from 'ai';
+import { generateText, tool, type LanguageModel, type ToolSet } from 'ai';
 import { createAnthropic } from '@ai-sdk/anthropic';
 import { createOpenAI } from '@ai-sdk/openai';
 import { z } from 'zod';
-import { eventBus, historyService } from '../platform';
+import { eventBus, type ConversationLink } from '../platform';
+import { createCheckpoint, createFileCheckpoint } from './documentHistory';
+import { withAiRequestHeaders } from './aiRequestHeaders';
+import { createCloudFetch } from './cloudAi';
 import { getRenderService, type RenderOptions } from './renderService';
 import type { PreviewSceneStyle } from './previewSceneConfig';
-import type { AiProvider } from '../stores/apiKeyStore';
+import { GEMINI_BASE_URL, OPENROUTER_BASE_URL, type AiProvider } from '../stores/apiKeyStore';
 import type { MeasurementUnit } from '../stores/settingsStore';
+import type { ReasoningEffort } from '../types/aiChat';
+import {
+  getMaxOutputTokensForModelId,
+  supportsReasoning,
+  THINKING_BUDGET_TOKENS,
+} from '../utils/aiModels';
+import {
+  applyReplacements,
+  numberedLines,
+  replaceLines,
+  replaceUnique,
+  type TextEditResult,
+} from '../utils/textEdits';
+import { normalizeProjectRelativePath } from '../utils/projectFilePaths';
+import { defaultToolTimeoutSecs } from './toolTimeouts';
 import {
   buildProjectContextSummary,
   capturePreviewScreenshot,
@@ -34,12 +52,32 @@ export interface AiToolCallbacks {
   createProjectFile: (path: string, content: string) => boolean;
   /** Edit a file by exact string replacement. Returns null on success, error string on failure. */
   editProjectFile: (path: string, oldString: string, newString: string) => string | null;
+  /** Replace an existing file's content. Returns null on success, error string on failure. */
+  writeProjectFile: (path: string, content: string) => string | null;
   /** Request a render via the renderRequestStore */
   requestRender: (trigger: string, opts?: { immediate?: boolean; code?: string }) => void;
   /** Change the render target */
   setRenderTarget: (path: string) => boolean;
   getMeasurementUnit: () => MeasurementUnit;
   setMeasurementUnit: (unit: MeasurementUnit) => void;
+  /** Check a tool call against the user's tool permissions. Returns null when it may run, or the reason it may not. */
+  authorizeTool?: (toolName: string, input: unknown) => Promise<string | null>;
+  /** Describe the meshes and drawings in the project that `import()` can load (desktop only) */
+  listReferenceGeometry?: () => Promise<string>;
+  /** Search the OpenSCAD reference and library documentation (desktop only) */
+  searchDocs?: (query: string, source?: string) => Promise<string>;
+  /** Find snippets in the bundled OpenSCAD idiom library, code included (desktop only) */
+  searchSnippets?: (query: string) => Promise<string>;
+  /** When set, `apply_edit` waits for the preview to show the edit (false on timeout) and attaches a screenshot of it. */
+  waitForPreviewUpdate?: () => Promise<boolean>;
+  /** Per-tool timeout overrides in seconds */
+  toolTimeouts?: Record<string, number>;
+  /**
+   * When set, edits are held for the user to review instead of being
+   * written: called with the file and its text before and after the edit.
+   * Rejects when the edit can't be held.
+   */
+  proposeEdit?: (filePath: string, oldCode: string, newCode: string) => Promise<unknown>;
 }
 
 export const SYSTEM_PROMPT = `## OpenSCAD AI Assistant
@@ -53,12 +91,15 @@ You are an expert OpenSCAD assistant helping users design and modify 3D models.
 - **See the design**: Use \`get_preview_screenshot\` to see the rendered output
 - **Check for errors**: Use \`get_diagnostics\` to check compilation errors and warnings
 - **Make changes**: Use \`apply_edit\` to modify code with exact string replacement (specify \`file_path\` to edit a specific file, or omit to edit the render target)
+- **Make related changes together**: Use \`apply_edits\` to apply several replacements to one file as a single edit; nothing changes unless all of them match
+- **Edit by line number**: Use \`get_current_code\` with \`numbered: true\` to see line numbers, then \`edit_lines\` to replace a line range, which helps when the same code appears several times
 - **Create files**: Use \`create_file\` to add new files to the project
+- **Rewrite files**: Use \`write_file\` to create a file or replace its whole content when most of it changes
 - **Switch render target**: Use \`set_render_target\` to change which file is compiled and previewed
 - **Update preview**: Use \`trigger_render\` to manually refresh the preview
 
 ### Critical Rules for Editing:
-1. **ALWAYS use exact string replacement**: Never output full file replacements. Use \`apply_edit\` with exact substrings.
+1. **ALWAYS use exact string replacement**: Don't output full file replacements for targeted changes. Use \`apply_edit\` with exact substrings, and \`write_file\` only when rewriting most of a file.
 2. **Provide exact substrings**: The \`old_string\` must match exactly (including whitespace and indentation) and must be unique in the file.
 3. **Keep changes focused**: Each edit should change one logical unit. Break unrelated changes into separate steps.
 4. **Always validate when done**: After finishing all edits, always call \`get_diagnostics\` to confirm the code compiles cleanly. Fix any errors before declaring success.
@@ -78,11 +119,19 @@ You are an expert OpenSCAD assistant helping users design and modify 3D models.
 - Use \`apply_edit\` with \`file_path\` to edit any file in the project by its relative path.
 - Use \`create_file\` to split code into modules (e.g., shared libraries, separate parts).
 - Use \`set_render_target\` to switch which file is being previewed (e.g., to check a different entry point).
+- Before writing \`import()\` calls for meshes or drawings (STL, 3MF, SVG, DXF), call \`list_reference_geometry\` and use the statements it returns; they already correct for the file's units.
+- Before using a library module or function (BOSL2, MCAD or another installed library) you are not certain about, call \`search_docs\` to check its name and parameters instead of guessing.
+- For common idioms (chamfers, rounded edges, threads, honeycomb fills, screw holes, nut traps, arrays), call \`search_snippets\` and adapt the returned modules instead of writing them from scratch.
 
 ### Interpreting Annotated Screenshots:
 - If an attached viewer screenshot includes drawn circles, boxes, ovals, arrows, or freehand marks, treat that markup as intentional user annotation highlighting the area to focus on.
 - Do not describe annotation marks as part of the OpenSCAD geometry or as rendering artifacts unless the user explicitly asks about the annotation itself.
 
+### Modeling from Reference Images:
+- When asked to model what an attached sketch, photo, or drawing shows, identify the main body and its features (holes, slots, fillets, bends) before writing code.
+- Use dimensions written on the image. Infer the rest from its proportions, expose them as top-level parameters, and tell the user which sizes you assumed.
+- After building the model, call \`get_preview_screenshot\` and compare the result against the reference image before declaring success.
+
 ### OpenSCAD Quick Reference:
 
 **3D Primitives:**
@@ -140,6 +189,53 @@ You are an expert OpenSCAD assistant helping users design and modify 3D models.
 - Prefer realistic 3D-printing-safe defaults, ranges, and steps.
 `;
 
+const TITLE_PROMPT =
+  'Write a title of at most six words for a chat about an OpenSCAD model, based on its first exchange. Reply with the title only, without quotes or punctuation at the end.';
+
+/** Longest text of each side of the exchange sent to the title model */
+const TITLE_EXCHANGE_CHARS = 2000;
+
+/** First line of a title model's reply, without quotes, labels or a trailing period */
+export function cleanConversationTitle(text: string): string | null {
+  const title = (text.trim().split('\n')[0] ?? '')
+    .replace(/^[\s"'`*#]*(?:title:[\s"'`*]*)?/i, '')
+    .replace(/[\s"'`*.]+$/, '');
+  if (!title) return null;
+  return title.length > 60 ? `${title.slice(0, 57)}...` : title;
+}
+
+/**
+ * Ask a small model for a short title summarising the first exchange of a
+ * conversation, or null when its reply has no usable title.
+ */
+export async function generateConversationTitle(
+  model: LanguageModel,
+  request: string,
+  reply: string | null
+): Promise<string | null> {
+  const exchange = [`User: ${request.slice(0, TITLE_EXCHANGE_CHARS)}`];
+  if (reply) {
+    exchange.push(`Assistant: ${reply.slice(0, TITLE_EXCHANGE_CHARS)}`);
+  }
+  const { text } = await generateText({
+    model,
+    system: TITLE_PROMPT,
+    prompt: exchange.join('\n\n'),
+    maxOutputTokens: 32,
+  });
+  return cleanConversationTitle(text);
+}
+
+/**
+ * The chat message whose request is running, passed to tools as the
+ * stream's `experimental_context` so checkpoints can be linked to it
+ */
+function getConversationLink(context: unknown): ConversationLink | undefined {
+  return typeof context === 'object' && context !== null && 'messageId' in context
+    ? (context as ConversationLink)
+    : undefined;
+}
+
 export interface CreateModelOptions {
   baseUrl?: string;
 }
@@ -154,9 +250,15 @@ export function createModel(
     const anthropic = createAnthropic({
       apiKey,
       headers: { 'anthropic-dangerous-direct-browser-access': 'true' },
+      fetch: withAiRequestHeaders('anthropic'),
     });
     return anthropic(modelId);
   }
+  if (provider === 'bedrock' || provider === 'vertex') {
+    // The backend signs and sends these requests with the platform's credentials
+    const anthropic = createAnthropic({ apiKey: 'cloud', fetch: createCloudFetch(provider) });
+    return anthropic(modelId);
+  }
   if (provider === 'openai-compatible') {
     const openai = createOpenAI({
       apiKey: apiKey || 'local',
@@ -165,18 +267,235 @@ export function createModel(
     });
     return openai.chat(modelId);
   }
-  const openai = createOpenAI({ apiKey });
+  if (provider === 'azure-openai') {
+    // Azure's v1 API takes the key as `api-key` and the deployment name as the model
+    const azure = createOpenAI({
+      apiKey,
+      baseURL: `${options.baseUrl}/openai/v1`,
+      name: 'azure-openai',
+      fetch: (input, init) => {
+        const headers = new Headers(init?.headers);
+        headers.delete('Authorization');
+        headers.set('api-key', apiKey);
+        return fetch(input, { ...init, headers });
+      },
+    });
+    return azure.chat(modelId);
+  }
+  if (provider === 'gemini') {
+    const gemini = createOpenAI({ apiKey, baseURL: GEMINI_BASE_URL, name: 'gemini' });
+    return gemini.chat(modelId);
+  }
+  if (provider === 'openrouter') {
+    const openrouter = createOpenAI({
+      apiKey,
+      baseURL: OPENROUTER_BASE_URL,
+      name: 'openrouter',
+      headers: { 'X-Title': 'OpenSCAD Studio' },
+    });
+    return openrouter.chat(modelId);
+  }
+  const openai = createOpenAI({ apiKey, fetch: withAiRequestHeaders('openai') });
   return openai(modelId);
 }
 
+const SUMMARY_PROMPT =
+  "Summarize this earlier part of a chat between a user and an assistant that edits OpenSCAD models with tools. Keep the user's goals and requirements, decisions made, dimensions and parameter values, files touched, and anything left unresolved. Skip pleasantries and tool mechanics. Reply with the summary only.";
+
+/**
+ * Ask a small model to summarise the older part of a conversation, given as
+ * a plain-text transcript, so it can stand in for those messages.
+ */
+export async function summarizeConversation(
+  model: LanguageModel,
+  transcript: string
+): Promise<string> {
+  const { text } = await generateText({
+    model,
+    system: SUMMARY_PROMPT,
+    prompt: transcript,
+    maxOutputTokens: 1024,
+  });
+  const summary = text.trim();
+  if (!summary) throw new Error('The summary model returned no text');
+  return summary;
+}
+
+/**
+ * Request options for a reasoning effort: an extended thinking budget for
+ * Claude, or `reasoningEffort` for OpenAI-style providers. Claude's thinking
+ * budget is added to the output tokens, so those shrink to stay within the
+ * model's limit.
+ */
+export function getReasoningRequestOptions(
+  provider: AiProvider,
+  modelId: string,
+  effort: ReasoningEffort,
+  maxOutputTokens: number | undefined
+) {
+  if (effort === 'off' || !supportsReasoning(modelId)) {
+    return { maxOutputTokens };
+  }
+  if (provider === 'anthropic' || provider === 'bedrock' || provider === 'vertex') {
+    const budgetTokens = THINKING_BUDGET_TOKENS[effort];
+    const limit = getMaxOutputTokensForModelId(modelId);
+    return {
+      maxOutputTokens:
+        maxOutputTokens !== undefined && limit !== undefined
+          ? Math.min(maxOutputTokens, limit - budgetTokens)
+          : maxOutputTokens,
+      providerOptions: { anthropic: { thinking: { type: 'enabled', budgetTokens } } },
+    };
+  }
+  return { maxOutputTokens, providerOptions: { openai: { reasoningEffort: effort } } };
+}
+
+/** Tools that change project files and report a checkpoint to restore */
+export const EDIT_TOOL_NAMES: ReadonlySet<string> = new Set([
+  'apply_edit',
+  'apply_edits',
+  'edit_lines',
+  'write_file',
+]);
+
 export function buildTools(callbacks: AiToolCallbacks) {
   const applyEditResultSchema = z.object({
     status: z.enum(['success']),
     message: z.string(),
     __checkpointId: z.string().optional(),
+    image_data_url: z.string().optional(),
+    preview_error: z.string().optional(),
   });
 
-  return {
+  /** Screenshot the re-rendered preview so the model can check its own edit */
+  const attachEditPreview = async <T extends object>(result: T) => {
+    if (!callbacks.waitForPreviewUpdate) return result;
+    if (!(await callbacks.waitForPreviewUpdate())) {
+      return {
+        ...result,
+        preview_error:
+          'The preview did not update after the edit. Call get_diagnostics to check whether the code still renders.',
+      };
+    }
+    const screenshot = await capturePreviewScreenshot({
+      captureCurrentView: callbacks.captureCurrentView,
+      get3dPreviewUrl: callbacks.get3dPreviewUrl,
+      getPreviewSceneStyle: callbacks.getPreviewSceneStyle,
+      getUseModelColors: callbacks.getUseModelColors,
+    });
+    return screenshot.image_data_url
+      ? { ...result, image_data_url: screenshot.image_data_url }
+      : { ...result, preview_error: screenshot.error };
+  };
+
+  /**
+   * Write `code` over a file, taking a checkpoint of `previousCode` first
+   * when it is the render target, and re-render
+   */
+  const writeFileEdit = async (
+    targetPath: string,
+    previousCode: string,
+    code: string,
+    context: unknown
+  ): Promise<{ checkpointId?: string } | { error: string }> => {
+    const isRenderTarget = targetPath === callbacks.getRenderTargetPath();
+    const checkpointId = isRenderTarget
+      ? await createCheckpoint(
+          previousCode,
+          'Before AI edit',
+          'ai',
+          getConversationLink(context),
+          targetPath
+        )
+      : undefined;
+    const error = callbacks.writeProjectFile(targetPath, code);
+    if (error) {
+      return { error };
+    }
+
+    if (isRenderTarget) {
+      eventBus.emit('code-updated', { code, source: 'ai' });
+    }
+    callbacks.requestRender('ai_edit', { immediate: true });
+    return { checkpointId };
+  };
+
+  /**
+   * Replace a whole file (the render target when `filePath` is omitted) with
+   * the result of `edit`, under one checkpoint when it is the render target.
+   * Nothing is written when `edit` fails. In review mode the edit is handed
+   * to `proposeEdit` instead, which applies it if the user accepts.
+   */
+  const applyFileEdit = async (
+    filePath: string | undefined,
+    context: unknown,
+    edit: (code: string) => TextEditResult,
+    message: string
+  ) => {
+    const targetPath = filePath ?? callbacks.getRenderTargetPath();
+    if (!targetPath) {
+      return '❌ No render target set.';
+    }
+    const currentCode = callbacks.readProjectFile(targetPath);
+    if (currentCode === null) {
+      return `❌ File not found: ${targetPath}`;
+    }
+    const result = edit(currentCode);
+    if ('error' in result) {
+      return `❌ Failed to apply edit to ${targetPath}: ${result.error}\n\nNothing was changed. Please check the exact text and try again.`;
+    }
+
+    if (callbacks.proposeEdit) {
+      try {
+        await callbacks.proposeEdit(targetPath, currentCode, result.code);
+      } catch (error) {
+        const reason = error instanceof Error ? error.message : String(error);
+        return `❌ Failed to hold the edit to ${targetPath} for review: ${reason}\n\nNothing was changed.`;
+      }
+      return `⏸ The edit to ${targetPath} is waiting for the user to review it. It is applied only if they accept it, so do not make it again; continue as if it will be applied.`;
+    }
+
+    const written = await writeFileEdit(targetPath, currentCode, result.code, context);
+    if ('error' in written) {
+      return `❌ Failed to apply edit to ${targetPath}: ${written.error}`;
+    }
+    return attachEditPreview({
+      status: 'success' as const,
+      message,
+      ...(written.checkpointId ? { __checkpointId: written.checkpointId } : {}),
+    });
+  };
+
+  /** Model-facing form of an edit result: its message, plus any preview screenshot */
+  const editResultToModelOutput = ({ output }: { output: unknown }) => {
+    const parsed = applyEditResultSchema.safeParse(output);
+    if (parsed.success) {
+      const { message, image_data_url, preview_error } = parsed.data;
+      if (image_data_url) {
+        return {
+          type: 'content' as const,
+          value: [
+            {
+              type: 'text' as const,
+              text: `${message} Screenshot of the updated preview attached; check that it shows the intended change.`,
+            },
+            {
+              type: 'image-data' as const,
+              data: image_data_url.replace(/^data:image\/\w+;base64,/, ''),
+              mediaType: 'image/png',
+            },
+          ],
+        };
+      }
+      return {
+        type: 'text' as const,
+        value: preview_error ? `${message}\n\n${preview_error}` : message,
+      };
+    }
+    return { type: 'text' as const, value: String(output) };
+  };
+
+  const tools = {
     get_project_context: tool({
       description:
         'Get an overview of the project: the render target path, its source code, and a top-level file/folder listing. Call this first to understand what you are working with.',
@@ -303,9 +622,20 @@ export function buildTools(callbacks: AiToolCallbacks) {
         old_string: z.string().describe('The exact text to find (must be unique in the file)'),
         new_string: z.string().describe('The replacement text'),
       }),
-      execute: async ({ file_path, old_string, new_string }) => {
+      execute: async ({ file_path, old_string, new_string }, options) => {
         const renderTarget = callbacks.getRenderTargetPath();
 
+        if (callbacks.proposeEdit) {
+          return applyFileEdit(
+            file_path,
+            options?.experimental_context,
+            (code) => replaceUnique(code, old_string, new_string),
+            file_path && file_path !== renderTarget
+              ? `Edit applied to ${file_path}.`
+              : 'Edit applied successfully.'
+          );
+        }
+
         // If targeting a specific non-render-target file, use editProjectFile
         if (file_path && file_path !== renderTarget) {
           const error = callbacks.editProjectFile(file_path, old_string, new_string);
@@ -313,10 +643,10 @@ export function buildTools(callbacks: AiToolCallbacks) {
             return `❌ Failed to apply edit to ${file_path}: ${error}`;
           }
           callbacks.requestRender('ai_edit', { immediate: true });
-          return {
+          return attachEditPreview({
             status: 'success' as const,
             message: `Edit applied to ${file_path}.`,
-          };
+          });
         }
 
         // Edit the render target (with checkpoints)
@@ -327,11 +657,12 @@ export function buildTools(callbacks: AiToolCallbacks) {
         const currentCode = callbacks.readProjectFile(targetPath) ?? '';
 
         // Create checkpoint before edit
-        const checkpointId = historyService.createCheckpoint(
+        const checkpointId = await createCheckpoint(
           currentCode,
-          [],
           'Before AI edit',
-          'ai'
+          'ai',
+          getConversationLink(options?.experimental_context),
+          targetPath
         );
 
         // Apply the edit via projectStore
@@ -345,21 +676,105 @@ export function buildTools(callbacks: AiToolCallbacks) {
         eventBus.emit('code-updated', { code: newCode, source: 'ai' });
         callbacks.requestRender('ai_edit', { immediate: true });
 
-        return {
+        return attachEditPreview({
           status: 'success' as const,
           message: 'Edit applied successfully.',
           __checkpointId: checkpointId,
-        };
+        });
       },
-      toModelOutput({ output }) {
-        const parsed = applyEditResultSchema.safeParse(output);
-        if (parsed.success) {
-          return { type: 'text' as const, value: parsed.data.message };
+      toModelOutput: editResultToModelOutput,
+    }),
+
+    apply_edits: tool({
+      description:
+        'Apply several exact string replacements to one OpenSCAD file as a single edit. They run in order, each on the result of the previous one, and if any old_string does not appear exactly once nothing is changed. Omit file_path to edit the render target.',
+      inputSchema: z.object({
+        file_path: z
+          .string()
+          .optional()
+          .describe(
+            'Relative path of the file to edit (e.g. "lib/utils.scad"). Omit to edit the render target.'
+          ),
+        edits: z
+          .array(
+            z.object({
+              old_string: z
+                .string()
+                .describe('The exact text to find (must be unique in the file at that point)'),
+              new_string: z.string().describe('The replacement text'),
+            })
+          )
+          .min(1)
+          .describe('Replacements to apply, in order'),
+      }),
+      execute: async ({ file_path, edits }, options) => {
+        const count = edits.length === 1 ? '1 replacement' : `${edits.length} replacements`;
+        return applyFileEdit(
+          file_path,
+          options?.experimental_context,
+          (code) => applyReplacements(code, edits),
+          `Applied ${count}${file_path ? ` to ${file_path}` : ''}.`
+        );
+      },
+      toModelOutput: editResultToModelOutput,
+    }),
+
+    get_current_code: tool({
+      description:
+        'Get the current code of a file (the render target when file_path is omitted). Set numbered to prefix each line with its line number for edit_lines.',
+      inputSchema: z.object({
+        file_path: z
+          .string()
+          .optional()
+          .describe('Relative path of the file to read. Omit for the render target.'),
+        numbered: z
+          .boolean()
+          .optional()
+          .default(false)
+          .describe('Prefix each line with its 1-based line number'),
+      }),
+      execute: async ({ file_path, numbered }) => {
+        const targetPath = file_path ?? callbacks.getRenderTargetPath();
+        if (!targetPath) {
+          return '❌ No render target set.';
         }
-        return { type: 'text' as const, value: String(output) };
+        const code = callbacks.readProjectFile(targetPath);
+        if (code === null) {
+          return `❌ File not found: ${targetPath}`;
+        }
+        return numbered ? numberedLines(code) : code;
       },
     }),
 
+    edit_lines: tool({
+      description:
+        'Replace a range of lines in an OpenSCAD file, using the line numbers from get_current_code with numbered set. Set end_line to start_line - 1 to insert before start_line without removing anything. Omit file_path to edit the render target.',
+      inputSchema: z.object({
+        file_path: z
+          .string()
+          .optional()
+          .describe(
+            'Relative path of the file to edit (e.g. "lib/utils.scad"). Omit to edit the render target.'
+          ),
+        start_line: z.number().int().min(1).describe('First line to replace (1-based)'),
+        end_line: z.number().int().min(0).describe('Last line to replace, inclusive'),
+        new_text: z.string().describe('Text to put in place of those lines'),
+      }),
+      execute: async ({ file_path, start_line, end_line, new_text }, options) => {
+        const change =
+          end_line < start_line
+            ? `Inserted text before line ${start_line}`
+            : `Replaced lines ${start_line}-${end_line}`;
+        return applyFileEdit(
+          file_path,
+          options?.experimental_context,
+          (code) => replaceLines(code, start_line, end_line, new_text),
+          `${change}${file_path ? ` in ${file_path}` : ''}.`
+        );
+      },
+      toModelOutput: editResultToModelOutput,
+    }),
+
     get_diagnostics: tool({
       description: 'Get current OpenSCAD compilation errors and warnings',
       inputSchema: z.object({}),
@@ -390,6 +805,50 @@ export function buildTools(callbacks: AiToolCallbacks) {
       },
     }),
 
+    list_reference_geometry: tool({
+      description:
+        'List the meshes and drawings (STL, OFF, 3MF, SVG, DXF) in the project with their size, units and the import() statement that loads each at the right scale. Check this before writing import() calls.',
+      inputSchema: z.object({}),
+      execute: async () => {
+        if (!callbacks.listReferenceGeometry) {
+          return 'Reference geometry can only be listed in the desktop app.';
+        }
+        return callbacks.listReferenceGeometry();
+      },
+    }),
+
+    search_docs: tool({
+      description:
+        'Search the OpenSCAD language reference, the BOSL2 and MCAD cheat sheets and the documented modules and functions of installed libraries. Use it to check library APIs (names, parameters, includes) before writing code that calls them.',
+      inputSchema: z.object({
+        query: z.string().describe('What to look up, e.g. "cuboid rounding" or "threaded_rod"'),
+        source: z
+          .string()
+          .optional()
+          .describe('Only search "OpenSCAD" or one library, e.g. "BOSL2"'),
+      }),
+      execute: async ({ query, source }) => {
+        if (!callbacks.searchDocs) {
+          return 'Documentation search is only available in the desktop app.';
+        }
+        return callbacks.searchDocs(query, source);
+      },
+    }),
+
+    search_snippets: tool({
+      description:
+        'Search the bundled library of proven OpenSCAD idioms (chamfers, rounded cubes, threads, honeycomb fill, screw holes, nut traps, arrays) and return the best matches with their code.',
+      inputSchema: z.object({
+        query: z.string().describe('What to build, e.g. "chamfered edges" or "M3 nut trap"'),
+      }),
+      execute: async ({ query }) => {
+        if (!callbacks.searchSnippets) {
+          return 'The snippet library is only available in the desktop app.';
+        }
+        return callbacks.searchSnippets(query);
+      },
+    }),
+
     trigger_render: tool({
       description: 'Manually trigger a preview render',
       inputSchema: z.object({}),
@@ -420,6 +879,69 @@ export function buildTools(callbacks: AiToolCallbacks) {
       },
     }),
 
+    write_file: tool({
+      description:
+        'Create a project file or replace its whole content. Prefer apply_edit for targeted changes. The previous content is kept as a checkpoint.',
+      inputSchema: z.object({
+        file_path: z
+          .string()
+          .describe('Relative path of the file to write (e.g. "lib/utils.scad" or "main.scad")'),
+        content: z.string().describe('The complete new content of the file'),
+      }),
+      execute: async ({ file_path, content }, options) => {
+        const targetPath = normalizeProjectRelativePath(file_path);
+        if (!targetPath) {
+          return `❌ ${file_path} is outside the project.`;
+        }
+        const context = options?.experimental_context;
+        const previous = callbacks.readProjectFile(targetPath);
+        // The render target is checkpointed in the editor's history, and
+        // reviewed writes are checkpointed when accepted
+        if (
+          previous !== null &&
+          (targetPath === callbacks.getRenderTargetPath() || callbacks.proposeEdit)
+        ) {
+          return applyFileEdit(
+            targetPath,
+            context,
+            () => ({ code: content }),
+            `Wrote ${targetPath}.`
+          );
+        }
+
+        try {
+          await createFileCheckpoint(
+            targetPath,
+            previous ?? '',
+            `Before AI write to ${targetPath}`,
+            getConversationLink(context)
+          );
+        } catch (error) {
+          const reason = error instanceof Error ? error.message : String(error);
+          return `❌ Failed to checkpoint ${targetPath}: ${reason}\n\nNothing was written.`;
+        }
+        if (previous === null) {
+          if (!callbacks.createProjectFile(targetPath, content)) {
+            return `❌ Failed to create ${targetPath}: the path is invalid.`;
+          }
+        } else {
+          const error = callbacks.writeProjectFile(targetPath, content);
+          if (error) {
+            return `❌ Failed to write ${targetPath}: ${error}`;
+          }
+        }
+        callbacks.requestRender('ai_edit', { immediate: true });
+        return attachEditPreview({
+          status: 'success' as const,
+          message:
+            previous === null
+              ? `Created ${targetPath}. It can now be pulled in with \`include\`/\`use\`.`
+              : `Wrote ${targetPath}.`,
+        });
+      },
+      toModelOutput: editResultToModelOutput,
+    }),
+
     set_render_target: tool({
       description:
         'Change which file is compiled and previewed. The render target is the entry point file that OpenSCAD compiles.',
@@ -456,4 +978,59 @@ export function buildTools(callbacks: AiToolCallbacks) {
       },
     }),
   };
+
+  return withToolPermissions(
+    withToolTimeouts(tools, callbacks.toolTimeouts),
+    callbacks.authorizeTool
+  );
+}
+
+/**
+ * Answer a tool call with an error once it runs past its timeout, so a hung
+ * render can't stall the turn. The tool itself may keep running.
+ */
+function withToolTimeouts<T extends ToolSet>(
+  tools: T,
+  overrides: Record<string, number> = {}
+): T {
+  for (const [name, definition] of Object.entries(tools)) {
+    const execute = definition.execute;
+    if (!execute) continue;
+    const timeoutSecs = overrides[name] ?? defaultToolTimeoutSecs(name);
+    definition.execute = async (input, options) => {
+      let timeoutId: ReturnType<typeof setTimeout> | undefined;
+      const timedOut = new Promise<string>((resolve) => {
+        timeoutId = setTimeout(
+          () =>
+            resolve(
+              `❌ \`${name}\` timed out after ${timeoutSecs}s. The operation may still be running; check \`get_diagnostics\` before retrying.`
+            ),
+          timeoutSecs * 1000
+        );
+      });
+      try {
+        return await Promise.race([execute(input, options), timedOut]);
+      } finally {
+        clearTimeout(timeoutId);
+      }
+    };
+  }
+  return tools;
+}
+
+/** Run each tool only after `authorizeTool` allows the call */
+function withToolPermissions<T extends ToolSet>(
+  tools: T,
+  authorizeTool: AiToolCallbacks['authorizeTool']
+): T {
+  if (!authorizeTool) return tools;
+  for (const [name, definition] of Object.entries(tools)) {
+    const execute = definition.execute;
+    if (!execute) continue;
+    definition.execute = async (input, options) => {
+      const denial = await authorizeTool(name, input);
+      return denial ? `❌ ${denial}` : execute(input, options);
+    };
+  }
+  return tools;
 }
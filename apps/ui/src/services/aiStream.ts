@@ -1,4 +1,4 @@
-import { streamText } from 'ai';
+import { APICallError, streamText, wrapLanguageModel } from 'ai';
 
 type StreamTextArgs = Parameters<typeof streamText>[0];
 type StreamTextResult = ReturnType<typeof streamText>;
@@ -10,10 +10,124 @@ declare global {
     | undefined;
 }
 
-export async function startAiStream(options: StreamTextArgs): Promise<StreamTextResult> {
+/** Tries per model call, counting the first */
+export const MAX_STREAM_ATTEMPTS = 4;
+const BASE_RETRY_DELAY_MS = 1000;
+/** Longest wait, even when the provider asks for more */
+const MAX_RETRY_DELAY_MS = 60000;
+
+export interface StreamRetry {
+  /** 1 for the first retry */
+  attempt: number;
+  maxAttempts: number;
+  delayMs: number;
+  statusCode?: number;
+}
+
+export interface StartAiStreamOptions {
+  /** Called before waiting to retry a rate-limited or failed model call */
+  onRetry?: (retry: StreamRetry) => void;
+}
+
+/** Rate limits (429), overload (529) and other server errors are worth retrying */
+export function isTransientStreamError(error: unknown): boolean {
+  if (!APICallError.isInstance(error) || error.statusCode === undefined) return false;
+  return error.statusCode === 408 || error.statusCode === 429 || error.statusCode >= 500;
+}
+
+/** Delay a provider asked for in `retry-after-ms` or `retry-after` (seconds or a date) */
+function requestedDelayMs(headers: Record<string, string> | undefined): number | null {
+  const milliseconds = headers?.['retry-after-ms'];
+  if (milliseconds && Number.isFinite(Number(milliseconds))) return Number(milliseconds);
+  const retryAfter = headers?.['retry-after'];
+  if (!retryAfter) return null;
+  const seconds = Number(retryAfter);
+  if (Number.isFinite(seconds)) return seconds * 1000;
+  const date = Date.parse(retryAfter);
+  return Number.isNaN(date) ? null : date - Date.now();
+}
+
+/** The provider's requested delay when it sent one, otherwise exponential backoff */
+export function getRetryDelayMs(error: unknown, attempt: number): number {
+  const requested = requestedDelayMs(
+    APICallError.isInstance(error) ? error.responseHeaders : undefined
+  );
+  const delayMs = requested ?? BASE_RETRY_DELAY_MS * 2 ** (attempt - 1);
+  return Math.min(Math.max(delayMs, 0), MAX_RETRY_DELAY_MS);
+}
+
+function wait(delayMs: number, signal?: AbortSignal): Promise<void> {
+  return new Promise((resolve, reject) => {
+    if (signal?.aborted) {
+      reject(signal.reason);
+      return;
+    }
+    const timer = setTimeout(() => {
+      signal?.removeEventListener('abort', onAbort);
+      resolve();
+    }, delayMs);
+    const onAbort = () => {
+      clearTimeout(timer);
+      reject(signal?.reason);
+    };
+    signal?.addEventListener('abort', onAbort, { once: true });
+  });
+}
+
+/** Run `call`, retrying transient failures until it succeeds or runs out of attempts */
+export async function withStreamRetries<T>(
+  call: () => PromiseLike<T>,
+  options: StartAiStreamOptions & { abortSignal?: AbortSignal } = {}
+): Promise<T> {
+  for (let attempt = 1; ; attempt++) {
+    try {
+      return await call();
+    } catch (error) {
+      if (
+        attempt >= MAX_STREAM_ATTEMPTS ||
+        options.abortSignal?.aborted ||
+        !isTransientStreamError(error)
+      ) {
+        throw error;
+      }
+      const delayMs = getRetryDelayMs(error, attempt);
+      options.onRetry?.({
+        attempt,
+        maxAttempts: MAX_STREAM_ATTEMPTS,
+        delayMs,
+        statusCode: APICallError.isInstance(error) ? error.statusCode : undefined,
+      });
+      await wait(delayMs, options.abortSignal);
+    }
+  }
+}
+
+/**
+ * Stream a response. Each model call of the tool loop is retried on rate
+ * limits and server errors, in place of the SDK's own silent retries, so the
+ * UI can show that the request is waiting.
+ */
+export async function startAiStream(
+  options: StreamTextArgs,
+  { onRetry }: StartAiStreamOptions = {}
+): Promise<StreamTextResult> {
   const mock = globalThis.__OPENSCAD_STUDIO_AI_STREAM_MOCK__;
   if (mock) {
     return await mock(options);
   }
-  return streamText(options);
+  const { model, abortSignal } = options;
+  if (typeof model === 'string' || model.specificationVersion !== 'v3') {
+    return streamText(options);
+  }
+  return streamText({
+    ...options,
+    maxRetries: 0,
+    model: wrapLanguageModel({
+      model,
+      middleware: {
+        specificationVersion: 'v3',
+        wrapStream: ({ doStream }) => withStreamRetries(doStream, { onRetry, abortSignal }),
+      },
+    }),
+  });
 }
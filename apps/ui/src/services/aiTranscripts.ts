@@ -0,0 +1,73 @@
+/**
+ * Interrupted AI requests (desktop). While a request runs, its transcript
+ * is saved after every completed tool call; a transcript still on disk at
+ * startup belongs to a request the app quit in the middle of, and can be
+ * resumed from its last completed tool call.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import type { ModelMessage } from 'ai';
+import type { AttachmentStore, Message } from '../types/aiChat';
+import type { AgentTranscriptState } from '../utils/aiTranscript';
+
+export interface InterruptedQuery {
+  conversationId: string;
+  title: string;
+  provider: string;
+  modelId: string;
+  completedToolCalls: number;
+  updatedAt: number;
+}
+
+export interface ResumedQuery {
+  conversationId: string;
+  title: string;
+  provider: string;
+  modelId: string;
+  messages: Message[];
+  attachments: AttachmentStore;
+  apiMessages: ModelMessage[];
+}
+
+export interface SavedTranscript {
+  conversationId: string;
+  title: string;
+  provider: string;
+  modelId: string;
+  messages: Message[];
+  /** Image attachments referenced by `messages`, including their data */
+  attachments: AttachmentStore;
+  transcript: AgentTranscriptState;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+export async function saveAiTranscript({ transcript, ...saved }: SavedTranscript): Promise<void> {
+  if (!isDesktopTauri()) return;
+  await invoke('save_ai_transcript', {
+    transcript: {
+      ...saved,
+      apiMessages: transcript.apiMessages,
+      pendingToolCalls: transcript.pendingToolCalls,
+      pendingToolResults: transcript.pendingToolResults,
+      updatedAt: Date.now(),
+    },
+  }).catch((error) => console.warn('[aiTranscripts] Failed to save transcript:', error));
+}
+
+export async function discardAiTranscript(conversationId: string): Promise<void> {
+  if (!isDesktopTauri()) return;
+  await invoke('discard_ai_transcript', { conversationId }).catch((error) =>
+    console.warn('[aiTranscripts] Failed to discard transcript:', error)
+  );
+}
+
+export async function listInterruptedAiQueries(): Promise<InterruptedQuery[]> {
+  if (!isDesktopTauri()) return [];
+  return invoke<InterruptedQuery[]>('list_interrupted_ai_queries').catch(() => []);
+}
+
+export async function resumeAiQuery(conversationId: string): Promise<ResumedQuery> {
+  return invoke<ResumedQuery>('resume_ai_query', { conversationId });
+}
@@ -0,0 +1,117 @@
+/**
+ * Claude on Amazon Bedrock and Google Vertex AI (desktop). The backend finds
+ * cloud credentials, signs and sends Messages API requests, and streams the
+ * response back in the Anthropic API's own format, so the Anthropic provider
+ * can run on top of `createCloudFetch`.
+ */
+import { Channel, invoke } from '@tauri-apps/api/core';
+import { createRandomId } from '../utils/randomId';
+
+export type CloudAiProvider = 'bedrock' | 'vertex';
+
+export interface CloudProviderStatus {
+  provider: CloudAiProvider;
+  /** Why requests can't be sent yet; null when credentials were found */
+  error: string | null;
+}
+
+/** Routing settings shared with the backend's `ai_providers` settings */
+export interface AiProviderSettings {
+  openaiOrganization: string | null;
+  openaiProject: string | null;
+  anthropicHeaders: Record<string, string>;
+  bedrockRegion: string | null;
+  awsProfile: string | null;
+  vertexProject: string | null;
+  vertexRegion: string | null;
+}
+
+type CloudAiEvent =
+  | { event: 'response'; data: { status: number } }
+  | { event: 'chunk'; data: string }
+  | { event: 'end' };
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+export function isCloudAiProvider(provider: string): provider is CloudAiProvider {
+  return provider === 'bedrock' || provider === 'vertex';
+}
+
+export async function getCloudProviderStatuses(): Promise<CloudProviderStatus[]> {
+  if (!isDesktopTauri()) return [];
+  return invoke<CloudProviderStatus[]>('get_cloud_ai_providers');
+}
+
+export async function getAiProviderSettings(): Promise<AiProviderSettings> {
+  return invoke<AiProviderSettings>('get_ai_provider_settings');
+}
+
+export async function setAiProviderSettings(
+  settings: AiProviderSettings
+): Promise<AiProviderSettings> {
+  return invoke<AiProviderSettings>('set_ai_provider_settings', { settings });
+}
+
+/**
+ * A `fetch` for the Anthropic provider that sends each request through the
+ * backend to `provider`. The request URL and auth headers are ignored.
+ */
+export function createCloudFetch(provider: CloudAiProvider): typeof fetch {
+  return (_input, init) =>
+    new Promise<Response>((resolve, reject) => {
+      const requestId = createRandomId();
+      const body = JSON.parse(typeof init?.body === 'string' ? init.body : '{}');
+      const betas = (new Headers(init?.headers).get('anthropic-beta') ?? '')
+        .split(',')
+        .map((beta) => beta.trim())
+        .filter(Boolean);
+      const encoder = new TextEncoder();
+      let controller: ReadableStreamDefaultController<Uint8Array> | null = null;
+      let finished = false;
+
+      const finish = (error?: unknown) => {
+        if (finished) return;
+        finished = true;
+        if (error === undefined) {
+          controller?.close();
+        } else {
+          controller?.error(error);
+          reject(error);
+        }
+      };
+      const cancel = () => {
+        if (finished) return;
+        void invoke('cancel_cloud_ai_request', { requestId });
+        finish(new DOMException('The request was aborted', 'AbortError'));
+      };
+
+      const stream = new ReadableStream<Uint8Array>({
+        start(streamController) {
+          controller = streamController;
+        },
+        cancel,
+      });
+
+      const channel = new Channel<CloudAiEvent>();
+      channel.onmessage = (message) => {
+        if (finished) return;
+        if (message.event === 'response') {
+          const { status } = message.data;
+          const contentType =
+            body.stream && status < 400 ? 'text/event-stream' : 'application/json';
+          resolve(new Response(stream, { status, headers: { 'content-type': contentType } }));
+        } else if (message.event === 'chunk') {
+          controller?.enqueue(encoder.encode(message.data));
+        } else {
+          finish();
+        }
+      };
+
+      init?.signal?.addEventListener('abort', cancel);
+      invoke('cloud_ai_request', { requestId, provider, body, betas, onEvent: channel }).catch(
+        (error: unknown) => finish(error instanceof Error ? error : new Error(String(error)))
+      );
+    });
+}
@@ -1,7 +1,8 @@
 import { invoke } from '@tauri-apps/api/core';
 import { getRenderService, type Diagnostic, type ExportFormat } from './renderService';
-import { captureOffscreen, type PresetView } from './offscreenRenderer';
+import { captureOffscreen, type CaptureOptions, type PresetView } from './offscreenRenderer';
 import { buildProjectContextSummary } from './studioTooling';
+import { answerToolApprovalRequest, type ToolApprovalRequest } from './toolPermissions';
 import {
   getAuxiliaryFilesForRender,
   getProjectState,
@@ -119,6 +120,11 @@ type McpContent = McpTextContent | McpImageContent;
 interface McpToolResponse {
   content: McpContent[];
   isError?: boolean;
+  /** Machine-readable result (snake_case keys), sent to clients as structured content */
+  data?: Record<string, unknown>;
+  diagnostics?: Diagnostic[];
+  /** Checkpoint made before the tool changed code; the tools answered here don't */
+  checkpointId?: string;
 }
 
 type RenderStateClassification =
@@ -168,6 +174,13 @@ const MCP_SCREENSHOT_VIEWS = new Set<McpScreenshotView>([
   'isometric',
 ]);
 
+const MAX_CAPTURE_VIEWS = 8;
+
+interface CaptureViewRequest {
+  label: string;
+  options: Pick<CaptureOptions, 'view' | 'direction' | 'azimuth' | 'elevation'>;
+}
+
 const renderWaiters = new Map<
   number,
   {
@@ -179,6 +192,19 @@ const renderWaiters = new Map<
 >();
 let nextRenderWaiterId = 1;
 let bridgeUnlistenPromise: Promise<() => void> | null = null;
+/** Tool requests still running, by request id */
+const runningToolRequests = new Map<string, string>();
+/** Requests the backend gave up on; their responses are dropped */
+const cancelledToolRequests = new Set<string>();
+
+/** Tools that wait on a render, which is cancelled with them */
+const RENDER_BACKED_TOOLS = new Set([
+  'get_diagnostics',
+  'trigger_render',
+  'get_preview_screenshot',
+  'capture_views',
+  'export_file',
+]);
 
 function textResponse(text: string, isError = false): McpToolResponse {
   return {
@@ -187,6 +213,23 @@ function textResponse(text: string, isError = false): McpToolResponse {
   };
 }
 
+/** Structured result of a render for `get_diagnostics` and `trigger_render` */
+function renderResult(
+  response: McpToolResponse,
+  artifact: Pick<RenderArtifact, 'diagnostics' | 'error'>
+): McpToolResponse {
+  return {
+    ...response,
+    data: {
+      render_target: getCurrentRenderTargetPath(),
+      render_error: artifact.error || null,
+      error_count: artifact.diagnostics.filter((d) => d.severity === 'error').length,
+      warning_count: artifact.diagnostics.filter((d) => d.severity === 'warning').length,
+    },
+    diagnostics: artifact.diagnostics,
+  };
+}
+
 function debugLog(message: string, payload?: Record<string, unknown>) {
   const isDev = (import.meta as ImportMeta & { env?: { DEV?: boolean } }).env?.DEV ?? false;
   if (isDev) {
@@ -639,14 +682,17 @@ async function handleDiagnostics(): Promise<McpToolResponse> {
 
   const artifact = await runRenderAndWait('manual', refresh.source);
   const snapshotNote = buildSnapshotUsageNote(refresh.summary);
-  return textResponse(
-    [
-      `Diagnostics for render target: ${getCurrentRenderTargetLabel()}`,
-      snapshotNote,
-      formatDiagnostics(artifact.diagnostics, artifact.error),
-    ]
-      .filter(Boolean)
-      .join('\n\n')
+  return renderResult(
+    textResponse(
+      [
+        `Diagnostics for render target: ${getCurrentRenderTargetLabel()}`,
+        snapshotNote,
+        formatDiagnostics(artifact.diagnostics, artifact.error),
+      ]
+        .filter(Boolean)
+        .join('\n\n')
+    ),
+    artifact
   );
 }
 
@@ -668,26 +714,65 @@ async function handleTriggerRender(): Promise<McpToolResponse> {
   const snapshotNote = buildSnapshotUsageNote(refresh.summary);
 
   if (hasFailure) {
-    return textResponse(
-      [
-        `❌ Render failed for ${targetLabel}.`,
-        diagnosticsText,
-        snapshotNote,
-        buildRenderRecoveryGuidance({ includeTriggerRender: true }),
-      ]
-        .filter(Boolean)
-        .join('\n\n'),
-      true
+    return renderResult(
+      textResponse(
+        [
+          `❌ Render failed for ${targetLabel}.`,
+          diagnosticsText,
+          snapshotNote,
+          buildRenderRecoveryGuidance({ includeTriggerRender: true }),
+        ]
+          .filter(Boolean)
+          .join('\n\n'),
+        true
+      ),
+      artifact
     );
   }
 
-  return textResponse(
-    [`✅ Render completed for ${targetLabel}.`, diagnosticsText, snapshotNote]
-      .filter(Boolean)
-      .join('\n\n')
+  return renderResult(
+    textResponse(
+      [`✅ Render completed for ${targetLabel}.`, diagnosticsText, snapshotNote]
+        .filter(Boolean)
+        .join('\n\n')
+    ),
+    artifact
   );
 }
 
+function getScreenshotArtifact(
+  requestedView: string
+): { ok: true; artifact: RenderArtifact } | { ok: false; response: McpToolResponse } {
+  const artifact = getCurrentRenderArtifact();
+  if (!artifact) {
+    return {
+      ok: false,
+      response: textResponse(
+        buildPreviewTroubleshootingMessage(
+          'No settled render artifact is available for the current render target.',
+          { requestedView }
+        ),
+        true
+      ),
+    };
+  }
+
+  if (artifact.previewKind !== 'mesh' || !artifact.previewSrc) {
+    return {
+      ok: false,
+      response: textResponse(
+        buildPreviewTroubleshootingMessage(
+          'A 3D preview is required for MCP screenshots with explicit views.',
+          { requestedView }
+        ),
+        true
+      ),
+    };
+  }
+
+  return { ok: true, artifact };
+}
+
 async function handlePreviewScreenshot(
   argumentsValue: Record<string, unknown>
 ): Promise<McpToolResponse> {
@@ -706,26 +791,11 @@ async function handlePreviewScreenshot(
   }
   const requestedView = viewValue as McpScreenshotView;
 
-  const artifact = getCurrentRenderArtifact();
-  if (!artifact) {
-    return textResponse(
-      buildPreviewTroubleshootingMessage(
-        'No settled render artifact is available for the current render target.',
-        { requestedView }
-      ),
-      true
-    );
-  }
-
-  if (artifact.previewKind !== 'mesh' || !artifact.previewSrc) {
-    return textResponse(
-      buildPreviewTroubleshootingMessage(
-        'A 3D preview is required for MCP screenshots with explicit views.',
-        { requestedView }
-      ),
-      true
-    );
+  const resolved = getScreenshotArtifact(requestedView);
+  if (!resolved.ok) {
+    return resolved.response;
   }
+  const { artifact } = resolved;
 
   debugLog('[desktopMcp] Capturing screenshot from render artifact', {
     requestedView,
@@ -771,6 +841,108 @@ async function handlePreviewScreenshot(
   };
 }
 
+function parseCaptureView(value: unknown): CaptureViewRequest | string {
+  if (typeof value === 'string') {
+    return MCP_SCREENSHOT_VIEWS.has(value as McpScreenshotView)
+      ? { label: value, options: { view: value as McpScreenshotView } }
+      : `Unsupported view: ${value}. Use front, back, top, bottom, left, right, or isometric.`;
+  }
+  if (!value || typeof value !== 'object') {
+    return 'Each view must be a view name or an object with `view`, `azimuth`/`elevation`, or `direction`.';
+  }
+
+  const entry = value as Record<string, unknown>;
+  if (Array.isArray(entry.direction)) {
+    const direction = entry.direction;
+    if (
+      direction.length !== 3 ||
+      !direction.every((component) => typeof component === 'number') ||
+      direction.every((component) => component === 0)
+    ) {
+      return '`direction` must be a non-zero [x, y, z] vector.';
+    }
+    return {
+      label: `direction [${direction.join(', ')}]`,
+      options: { direction: direction as [number, number, number] },
+    };
+  }
+
+  const azimuth = typeof entry.azimuth === 'number' ? entry.azimuth : undefined;
+  const elevation = typeof entry.elevation === 'number' ? entry.elevation : undefined;
+  if (azimuth !== undefined || elevation !== undefined) {
+    return {
+      label: `azimuth ${azimuth ?? 45}°, elevation ${elevation ?? 30}°`,
+      options: { azimuth, elevation },
+    };
+  }
+  if (typeof entry.view === 'string') {
+    return parseCaptureView(entry.view);
+  }
+  return 'Each view object needs `view`, `azimuth`/`elevation`, or `direction`.';
+}
+
+async function handleCaptureViews(
+  argumentsValue: Record<string, unknown>
+): Promise<McpToolResponse> {
+  const rawViews = Array.isArray(argumentsValue.views) ? argumentsValue.views : [];
+  if (rawViews.length === 0) {
+    return textResponse('`capture_views` requires a non-empty `views` list.', true);
+  }
+  if (rawViews.length > MAX_CAPTURE_VIEWS) {
+    return textResponse(
+      `\`capture_views\` accepts at most ${MAX_CAPTURE_VIEWS} views per call.`,
+      true
+    );
+  }
+
+  const views: CaptureViewRequest[] = [];
+  for (const [index, rawView] of rawViews.entries()) {
+    const parsed = parseCaptureView(rawView);
+    if (typeof parsed === 'string') {
+      return textResponse(`View ${index + 1}: ${parsed}`, true);
+    }
+    views.push(parsed);
+  }
+
+  const requestedViews = views.map((view) => view.label).join(', ');
+  const resolved = getScreenshotArtifact(requestedViews);
+  if (!resolved.ok) {
+    return resolved.response;
+  }
+  const { artifact } = resolved;
+
+  const content: McpContent[] = [];
+  for (const view of views) {
+    let dataUrl = '';
+    try {
+      dataUrl = await captureOffscreen(artifact.previewSrc, {
+        ...view.options,
+        sceneStyle: artifact.sceneStyle,
+        useModelColors: artifact.useModelColors,
+      });
+    } catch (error) {
+      return textResponse(
+        buildPreviewTroubleshootingMessage(
+          `Failed to capture the ${view.label} view: ${error instanceof Error ? error.message : String(error)}`,
+          { requestedView: view.label }
+        ),
+        true
+      );
+    }
+    content.push(
+      { type: 'text', text: `View: ${view.label}` },
+      {
+        type: 'image',
+        data: dataUrl.replace(/^data:image\/png;base64,/, ''),
+        mimeType: 'image/png',
+      }
+    );
+  }
+  content.push({ type: 'text', text: `Captured ${views.length} views.` });
+
+  return { content };
+}
+
 function isAbsolutePath(path: string): boolean {
   return path.startsWith('/') || /^[A-Za-z]:[\\/]/.test(path);
 }
@@ -821,6 +993,13 @@ async function loadLibraryExportContext(): Promise<LibraryContext> {
   return context;
 }
 
+/** Triangle counts before and after `decimate_mesh_file` (other stats omitted) */
+interface DecimationReport {
+  before: { triangle_count: number };
+  after: { triangle_count: number };
+  outputPath: string;
+}
+
 async function handleExportFile(argumentsValue: Record<string, unknown>): Promise<McpToolResponse> {
   const format =
     typeof argumentsValue.format === 'string' ? (argumentsValue.format as ExportFormat) : null;
@@ -837,6 +1016,33 @@ async function handleExportFile(argumentsValue: Record<string, unknown>): Promis
   if (!filePath) {
     return textResponse('`export_file` requires a `file_path` argument.', true);
   }
+  const parameterFile =
+    typeof argumentsValue.parameter_file === 'string'
+      ? normalizeProjectRelativePath(argumentsValue.parameter_file)
+      : null;
+  const parameterSet =
+    typeof argumentsValue.parameter_set === 'string' ? argumentsValue.parameter_set : undefined;
+  if (argumentsValue.parameter_file != null && !parameterFile?.endsWith('.json')) {
+    return textResponse(
+      '`parameter_file` must be a workspace-relative customizer parameter file (.json).',
+      true
+    );
+  }
+  const targetTriangles = argumentsValue.target_triangles;
+  if (targetTriangles != null) {
+    if (format !== 'stl') {
+      return textResponse('`target_triangles` is only supported for STL exports.', true);
+    }
+    if (!Number.isInteger(targetTriangles) || (targetTriangles as number) < 1) {
+      return textResponse('`target_triangles` must be a positive whole number.', true);
+    }
+  }
+  if (Boolean(parameterFile) !== Boolean(parameterSet)) {
+    return textResponse(
+      '`parameter_file` and `parameter_set` must be given together to apply a parameter set.',
+      true
+    );
+  }
 
   const libraryContext = await loadLibraryExportContext();
   const refresh = await refreshMcpRenderSnapshot(libraryContext, 'export_file');
@@ -907,6 +1113,8 @@ async function handleExportFile(argumentsValue: Record<string, unknown>): Promis
     libraryPaths: libraryPaths.length > 0 ? libraryPaths : undefined,
     inputPath: renderTargetPath,
     workingDir: workingDir || undefined,
+    parameterFile: parameterFile ?? undefined,
+    parameterSet,
   });
 
   const { dirname } = await import('@tauri-apps/api/path');
@@ -915,12 +1123,36 @@ async function handleExportFile(argumentsValue: Record<string, unknown>): Promis
   await mkdir(parentDir, { recursive: true });
   await writeFile(resolvedPath, exportBytes);
 
+  let decimation: DecimationReport | null = null;
+  if (typeof targetTriangles === 'number') {
+    decimation = await invoke<DecimationReport>('decimate_mesh_file', {
+      path: resolvedPath,
+      options: { targetTriangles },
+    });
+  }
+
   const snapshotNote = buildSnapshotUsageNote(refresh.summary);
-  return textResponse(
-    [`✅ Exported ${format.toUpperCase()} to ${resolvedPath}`, snapshotNote]
-      .filter(Boolean)
-      .join('\n\n')
-  );
+  return {
+    ...textResponse(
+      [
+        `✅ Exported ${format.toUpperCase()} to ${resolvedPath}`,
+        parameterFile && `Applied parameter set "${parameterSet}" from ${parameterFile}.`,
+        decimation &&
+          `Decimated from ${decimation.before.triangle_count} to ${decimation.after.triangle_count} triangles.`,
+        snapshotNote,
+      ]
+        .filter(Boolean)
+        .join('\n\n')
+    ),
+    data: {
+      format,
+      path: resolvedPath,
+      bytes: exportBytes.byteLength,
+      parameter_file: parameterFile,
+      parameter_set: parameterSet ?? null,
+      triangles: decimation?.after.triangle_count ?? null,
+    },
+  };
 }
 
 async function executeToolRequest(payload: McpToolRequestPayload): Promise<McpToolResponse> {
@@ -937,6 +1169,8 @@ async function executeToolRequest(payload: McpToolRequestPayload): Promise<McpTo
       return handleTriggerRender();
     case 'get_preview_screenshot':
       return handlePreviewScreenshot(args);
+    case 'capture_views':
+      return handleCaptureViews(args);
     case 'export_file':
       return handleExportFile(args);
     default:
@@ -966,14 +1200,18 @@ export async function initializeDesktopMcpBridge(
         'mcp:tool-request',
         async (event) => {
           const payload = event.payload;
-          const response = await executeToolRequest(payload).catch((error: unknown) =>
-            textResponse(
-              error instanceof Error
-                ? error.message
-                : `Unexpected MCP tool error: ${String(error)}`,
-              true
+          runningToolRequests.set(payload.requestId, payload.toolName);
+          const response = await executeToolRequest(payload)
+            .catch((error: unknown) =>
+              textResponse(
+                error instanceof Error
+                  ? error.message
+                  : `Unexpected MCP tool error: ${String(error)}`,
+                true
+              )
             )
-          );
+            .finally(() => runningToolRequests.delete(payload.requestId));
+          if (cancelledToolRequests.delete(payload.requestId)) return;
 
           try {
             await submitToolResponse(payload.requestId, response);
@@ -983,6 +1221,28 @@ export async function initializeDesktopMcpBridge(
         }
       );
 
+      // The backend timed the request out and already answered the client
+      const unlistenToolCancelled = await currentWindow.listen<string>(
+        'mcp:tool-cancelled',
+        (event) => {
+          const toolName = runningToolRequests.get(event.payload);
+          if (!toolName) return;
+          cancelledToolRequests.add(event.payload);
+          if (RENDER_BACKED_TOOLS.has(toolName)) {
+            getRenderService().cancel();
+          }
+        }
+      );
+
+      const unlistenToolApproval = await currentWindow.listen<ToolApprovalRequest>(
+        'tool-approval-request',
+        (event) => {
+          void answerToolApprovalRequest(event.payload).catch((error) => {
+            console.error('[desktopMcp] Failed to answer tool approval request:', error);
+          });
+        }
+      );
+
       unlistenOpenRequest = await currentWindow.listen<DesktopWindowOpenRequestPayload>(
         'desktop:open-request',
         async (event) => {
@@ -1014,6 +1274,8 @@ export async function initializeDesktopMcpBridge(
 
       return () => {
         unlistenToolRequest();
+        unlistenToolCancelled();
+        unlistenToolApproval();
         unlistenOpenRequest?.();
         unlistenFocus?.();
       };
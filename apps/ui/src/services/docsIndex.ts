@@ -0,0 +1,69 @@
+/**
+ * Documentation search (desktop). Searches the bundled OpenSCAD reference,
+ * the BOSL2 and MCAD cheat sheets and the doc comments of installed
+ * libraries; the library index is built on first use and refreshed when
+ * libraries change.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface DocsSearchResult {
+  /** "OpenSCAD" or a library name */
+  source: string;
+  title: string;
+  /** Library file the entry was read from */
+  file?: string;
+  content: string;
+  score: number;
+}
+
+export interface IndexedLibrary {
+  name: string;
+  fingerprint: string;
+  files: number;
+  entries: number;
+}
+
+export interface DocsIndexStatus {
+  path: string;
+  builtAt: number | null;
+  libraries: IndexedLibrary[];
+  entries: number;
+  stale: boolean;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/** Best matches first; `source` limits results to "OpenSCAD" or one library */
+export async function searchDocs(
+  query: string,
+  options: { source?: string; limit?: number } = {}
+): Promise<DocsSearchResult[]> {
+  if (!isDesktopTauri()) return [];
+  return invoke<DocsSearchResult[]>('search_docs', {
+    query,
+    source: options.source,
+    limit: options.limit,
+  });
+}
+
+export async function getDocsIndexStatus(): Promise<DocsIndexStatus> {
+  return invoke<DocsIndexStatus>('get_docs_index_status');
+}
+
+/** Rescan the installed libraries */
+export async function buildDocsIndex(): Promise<DocsIndexStatus> {
+  return invoke<DocsIndexStatus>('build_docs_index');
+}
+
+/** Search results formatted for the AI */
+export function describeDocs(query: string, results: DocsSearchResult[]): string {
+  if (results.length === 0) return `No documentation matched "${query}".`;
+  return results
+    .map((result) => {
+      const origin = result.file ? `${result.source}, ${result.file}` : result.source;
+      return `## ${result.title} (${origin})\n${result.content}`;
+    })
+    .join('\n\n');
+}
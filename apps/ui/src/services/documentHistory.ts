@@ -0,0 +1,174 @@
+/**
+ * Checkpoint history of the editor tabs. Where the tabs are mirrored into
+ * backend documents (the desktop main window), each document keeps its own
+ * history in the backend and calls pass the active tab's `documentId`.
+ * Elsewhere the in-memory `historyService` holds a single history.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import {
+  historyService,
+  type ChangeType,
+  type CheckpointDiff,
+  type ConversationLink,
+  type EditorCheckpoint,
+} from '../platform/historyService';
+import {
+  documentIdForProjectPath,
+  documentIdForTab,
+  isEditorSyncActive,
+  whenEditorSynced,
+} from './editorSync';
+import { workspaceStore } from '../stores/workspaceStore';
+import { getProjectStore } from '../stores/projectStore';
+
+/** Document of the tab showing `projectPath`, or of the active tab */
+async function documentId(projectPath?: string): Promise<string | null> {
+  // The tab's pending edits must reach its document before its history moves
+  await whenEditorSynced();
+  const id = projectPath
+    ? documentIdForProjectPath(projectPath)
+    : documentIdForTab(workspaceStore.getState().activeTabId);
+  return id ?? null;
+}
+
+/** Run a history command, or `null` when there is nothing to step to */
+async function step(
+  command: string,
+  args: Record<string, unknown>
+): Promise<EditorCheckpoint | null> {
+  try {
+    return await invoke<EditorCheckpoint>(command, args);
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Checkpoint `code` in the history of the tab showing `projectPath` (the
+ * active tab when omitted)
+ */
+export async function createCheckpoint(
+  code: string,
+  description: string,
+  changeType: ChangeType,
+  conversation?: ConversationLink,
+  projectPath?: string
+): Promise<string> {
+  if (!isEditorSyncActive()) {
+    return historyService.createCheckpoint(code, [], description, changeType, conversation);
+  }
+  return invoke<string>('create_checkpoint', {
+    code,
+    description,
+    changeType,
+    conversation: conversation ?? null,
+    documentId: await documentId(projectPath),
+  });
+}
+
+/**
+ * Checkpoint the content of a project file other than the tab's, so
+ * restoring it writes that file back and leaves the editor alone. Only a
+ * project folder on disk can be written back, so elsewhere this makes no
+ * checkpoint and returns `null`.
+ */
+export async function createFileCheckpoint(
+  projectPath: string,
+  code: string,
+  description: string,
+  conversation?: ConversationLink
+): Promise<string | null> {
+  if (!isEditorSyncActive() || !getProjectStore().getState().projectRoot) return null;
+  return invoke<string>('create_checkpoint', {
+    code,
+    description,
+    changeType: 'ai',
+    conversation: conversation ?? null,
+    documentId: await documentId(),
+    filePath: projectPath,
+  });
+}
+
+export async function undo(): Promise<EditorCheckpoint | null> {
+  if (!isEditorSyncActive()) return historyService.undo();
+  return step('undo', { documentId: await documentId() });
+}
+
+export async function redo(): Promise<EditorCheckpoint | null> {
+  if (!isEditorSyncActive()) return historyService.redo();
+  return step('redo', { documentId: await documentId() });
+}
+
+export async function restoreTo(checkpointId: string): Promise<EditorCheckpoint | null> {
+  if (!isEditorSyncActive()) return historyService.restoreTo(checkpointId);
+  return step('restore_to_checkpoint', { checkpointId, documentId: await documentId() });
+}
+
+/** Checkpoints made for a conversation's messages, in chat order */
+export async function getForConversation(conversationId: string): Promise<EditorCheckpoint[]> {
+  if (!isEditorSyncActive()) return historyService.getForConversation(conversationId);
+  return invoke<EditorCheckpoint[]>('get_checkpoints_for_conversation', {
+    conversationId,
+    documentId: await documentId(),
+  });
+}
+
+/** Put the code back to how it was when a chat message was sent */
+export async function restoreToMessage(messageId: string): Promise<EditorCheckpoint | null> {
+  if (!isEditorSyncActive()) return historyService.restoreToMessage(messageId);
+  return step('restore_conversation_state', { messageId, documentId: await documentId() });
+}
+
+export async function getHistoryState(): Promise<{
+  canUndo: boolean;
+  canRedo: boolean;
+  history: EditorCheckpoint[];
+}> {
+  if (!isEditorSyncActive()) {
+    return {
+      canUndo: historyService.canUndo(),
+      canRedo: historyService.canRedo(),
+      history: historyService.getAll(),
+    };
+  }
+  const id = await documentId();
+  const [canUndo, canRedo, history] = await Promise.all([
+    invoke<boolean>('can_undo', { documentId: id }),
+    invoke<boolean>('can_redo', { documentId: id }),
+    invoke<EditorCheckpoint[]>('get_history', { documentId: id }),
+  ]);
+  return { canUndo, canRedo, history };
+}
+
+export async function getDiff(fromId: string, toId: string): Promise<CheckpointDiff | null> {
+  if (!isEditorSyncActive()) return historyService.getDiff(fromId, toId);
+  try {
+    return await invoke<CheckpointDiff>('get_checkpoint_diff', {
+      fromId,
+      toId,
+      documentId: await documentId(),
+    });
+  } catch {
+    return null;
+  }
+}
+
+/** How the backend saves and restores checkpoint history (desktop) */
+export interface HistorySettings {
+  /** Save checkpoints per project folder so they survive restarts */
+  persist: boolean;
+  /** Drop saved checkpoints older than this many days; 0 keeps them all */
+  maxAgeDays: number;
+  /** Upper bound on the size of each project's saved history */
+  maxBytes: number;
+  /** Also write restored code to the document's file, keeping a `.bak` copy */
+  writeToDisk: boolean;
+}
+
+export async function getHistorySettings(): Promise<HistorySettings> {
+  return invoke<HistorySettings>('get_history_settings');
+}
+
+export async function setHistorySettings(settings: HistorySettings): Promise<void> {
+  await invoke('set_history_settings', { settings });
+}
@@ -0,0 +1,215 @@
+/**
+ * Editor tabs ↔ backend documents (desktop). Every workspace tab is mirrored
+ * as an open document in the backend, and the active tab is its active
+ * document, so backend commands (renders, checkpoints, AI edits) see the
+ * code of the tab they act on. Commands that target a tab pass its
+ * `documentId`; `undefined` means the active document.
+ *
+ * The backend keeps one set of documents for the whole app, so only the
+ * main window mirrors its tabs; other windows leave `documentId` unset.
+ * Calls are sent one at a time, in the order the tabs changed. Typing is
+ * sent as the edits Monaco reports rather than the whole buffer.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import { workspaceStore } from '../stores/workspaceStore';
+import { getProjectStore } from '../stores/projectStore';
+import type { TabId, WorkspaceTab } from '../stores/workspaceTypes';
+
+interface DocumentInfo {
+  id: string;
+  title: string;
+  path: string | null;
+  isActive: boolean;
+}
+
+interface DocumentContents {
+  document: DocumentInfo;
+  code: string;
+}
+
+interface SyncedTab {
+  /** Empty until `open_document` returns */
+  documentId: string;
+  filePath: string | null;
+  /** Code last sent to the backend */
+  code: string;
+}
+
+const syncedTabs = new Map<TabId, SyncedTab>();
+let syncedActiveTabId: TabId | null = null;
+let running = false;
+let queue: Promise<unknown> = Promise.resolve();
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+function enqueue(task: () => Promise<unknown>): void {
+  queue = queue.then(task).catch((error) => {
+    console.error('[editorSync]', error);
+  });
+}
+
+function tabCode(tab: WorkspaceTab): string {
+  return getProjectStore().getState().files[tab.projectPath]?.content ?? '';
+}
+
+/** Whether this window mirrors its tabs into backend documents */
+export function isEditorSyncActive(): boolean {
+  return running;
+}
+
+/** Backend document of a tab, or `undefined` while it isn't mirrored */
+export function documentIdForTab(tabId: TabId | null | undefined): string | undefined {
+  return (tabId && syncedTabs.get(tabId)?.documentId) || undefined;
+}
+
+/** Backend document of the tab showing a project file, if one does */
+export function documentIdForProjectPath(projectPath: string): string | undefined {
+  const tab = workspaceStore.getState().tabs.find((tab) => tab.projectPath === projectPath);
+  return documentIdForTab(tab?.id);
+}
+
+/** Project file shown by the tab mirrored as a backend document */
+export function projectPathForDocument(documentId: string): string | undefined {
+  const entry = [...syncedTabs].find(([, synced]) => synced.documentId === documentId);
+  return workspaceStore.getState().tabs.find((tab) => tab.id === entry?.[0])?.projectPath;
+}
+
+/** A replaced range of a tab's text, in UTF-16 units like Monaco's content changes */
+export interface EditorEdit {
+  rangeOffset: number;
+  rangeLength: number;
+  text: string;
+}
+
+/**
+ * Send edits made in a tab's editor, leaving `code` as its text. If the
+ * backend's copy has drifted and the edits don't apply, the whole text is
+ * sent instead.
+ */
+export function sendEditorEdits(tabId: TabId, edits: readonly EditorEdit[], code: string): void {
+  const synced = syncedTabs.get(tabId);
+  if (!running || !synced) return;
+  synced.code = code;
+  const payload = edits.map(({ rangeOffset, rangeLength, text }) => ({
+    rangeOffset,
+    rangeLength,
+    text,
+  }));
+  enqueue(async () => {
+    try {
+      await invoke('apply_editor_edits', {
+        edits: payload,
+        expectedLength: code.length,
+        documentId: synced.documentId,
+      });
+    } catch {
+      await invoke('update_editor_state', { code, documentId: synced.documentId });
+    }
+  });
+}
+
+/** Resolves once every tab change so far has reached the backend */
+export async function whenEditorSynced(): Promise<void> {
+  await queue;
+}
+
+function openTab(tab: WorkspaceTab, activate: boolean): void {
+  const synced: SyncedTab = { documentId: '', filePath: tab.filePath, code: tabCode(tab) };
+  syncedTabs.set(tab.id, synced);
+  enqueue(async () => {
+    const contents = await invoke<DocumentContents>('open_document', {
+      code: synced.code,
+      path: tab.filePath,
+      title: tab.name,
+      activateDocument: activate,
+    });
+    synced.documentId = contents.document.id;
+    // A path that was already open comes back as that document; its code is the tab's now
+    if (contents.code !== synced.code) {
+      await invoke('update_editor_state', { code: synced.code, documentId: synced.documentId });
+    }
+  });
+}
+
+function closeTab(tabId: TabId, synced: SyncedTab): void {
+  syncedTabs.delete(tabId);
+  enqueue(async () => {
+    const shared = [...syncedTabs.values()].some((tab) => tab.documentId === synced.documentId);
+    if (synced.documentId && !shared) {
+      await invoke('close_document', { documentId: synced.documentId });
+    }
+  });
+}
+
+/** Send whatever changed in the workspace since the last call */
+function reconcile(): void {
+  const { tabs, activeTabId } = workspaceStore.getState();
+
+  for (const [tabId, synced] of syncedTabs) {
+    if (!tabs.some((tab) => tab.id === tabId)) closeTab(tabId, synced);
+  }
+
+  let opened = false;
+  for (const tab of tabs) {
+    const synced = syncedTabs.get(tab.id);
+    if (!synced) {
+      openTab(tab, tab.id === activeTabId);
+      opened = true;
+      continue;
+    }
+    if (synced.filePath !== tab.filePath) {
+      synced.filePath = tab.filePath;
+      enqueue(() =>
+        invoke('set_document_path', { documentId: synced.documentId, path: tab.filePath })
+      );
+    }
+    const code = tabCode(tab);
+    if (synced.code !== code) {
+      synced.code = code;
+      enqueue(() => invoke('update_editor_state', { code, documentId: synced.documentId }));
+    }
+  }
+
+  // Opening a path that is already open focuses it, so re-assert the active tab after opens
+  if (activeTabId && (opened || activeTabId !== syncedActiveTabId)) {
+    syncedActiveTabId = activeTabId;
+    const synced = syncedTabs.get(activeTabId);
+    if (synced) {
+      enqueue(() => invoke('switch_document', { documentId: synced.documentId }));
+    }
+  }
+}
+
+/**
+ * Start mirroring the workspace tabs into backend documents. Documents left
+ * over from before (e.g. a reloaded window) are closed once the tabs are open.
+ */
+export async function startEditorSync(): Promise<() => void> {
+  if (!isDesktopTauri()) return () => {};
+  const { getCurrentWindow } = await import('@tauri-apps/api/window');
+  if (getCurrentWindow().label !== 'main') return () => {};
+
+  const stale = await invoke<DocumentInfo[]>('list_documents');
+  running = true;
+  reconcile();
+  enqueue(async () => {
+    const open = new Set([...syncedTabs.values()].map((tab) => tab.documentId));
+    for (const document of stale) {
+      if (!open.has(document.id)) {
+        await invoke('close_document', { documentId: document.id });
+      }
+    }
+  });
+
+  const unsubscribeWorkspace = workspaceStore.subscribe(reconcile);
+  const unsubscribeProject = getProjectStore().subscribe(reconcile);
+  return () => {
+    unsubscribeWorkspace();
+    unsubscribeProject();
+    running = false;
+    syncedTabs.clear();
+    syncedActiveTabId = null;
+  };
+}
@@ -4,11 +4,17 @@ import { getProjectState } from '../stores/projectStore';
 import type { ProjectStoreState } from '../stores/projectTypes';
 import type { LibrarySettings } from '../stores/settingsStore';
 import { buildProjectRenderInputs, loadConfiguredLibraryAssets } from './projectRenderInputs';
-import { getRenderService, type ExportFormat, type IRenderService } from './renderService';
+import {
+  getRenderService,
+  type ExportFormat,
+  type ImageExportOptions,
+  type IRenderService,
+} from './renderService';
 
 interface ExportModelWithContextOptions {
   format: ExportFormat;
   library: LibrarySettings;
+  imageOptions?: ImageExportOptions;
   source?: string | null;
   state?: ProjectStoreState;
   workingDir?: string | null;
@@ -39,5 +45,6 @@ export async function exportModelWithContext(
   return renderService.exportModel(renderInputs.code, options.format, {
     backend: 'manifold',
     ...renderInputs.renderOptions,
+    imageOptions: options.imageOptions,
   });
 }
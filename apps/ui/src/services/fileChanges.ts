@@ -0,0 +1,126 @@
+/**
+ * External file changes (desktop). The backend watches the open project
+ * folder and reports edits made outside the app as `file:changed`. Clean
+ * files are reloaded; for files with unsaved edits the user chooses between
+ * the disk version and their own, and the backend is told which one won.
+ *
+ * Files the backend writes itself, for an MCP client or when restoring a
+ * file checkpoint, are reported separately and never conflict.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface FileChanged {
+  root: string;
+  /** Root-relative, `/`-separated path */
+  path: string;
+  removed: boolean;
+  /** The file has unsaved edits */
+  conflict: boolean;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/**
+ * Open the project folder in the backend, which starts watching it for
+ * `file:changed`. `entryFile` is the root-relative file shown in the editor.
+ */
+export async function openProject(root: string, entryFile?: string): Promise<void> {
+  if (!isDesktopTauri()) return;
+  await invoke('open_project', { root, entryFile });
+}
+
+/** Close the project folder in the backend, which watches it until no window has it open */
+export async function closeProject(root: string): Promise<void> {
+  if (!isDesktopTauri()) return;
+  await invoke('close_project', { root });
+}
+
+export async function onFileChanged(handler: (change: FileChanged) => void): Promise<() => void> {
+  if (!isDesktopTauri()) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<FileChanged>('file:changed', (event) => handler(event.payload));
+}
+
+/** A file an MCP client wrote into a window's workspace */
+export interface WorkspaceFileWritten {
+  windowId: string;
+  /** Workspace-relative, `/`-separated path */
+  filePath: string;
+  created: boolean;
+}
+
+/** Subscribe to files MCP clients write into this window's workspace */
+export async function onWorkspaceFileWritten(
+  handler: (written: WorkspaceFileWritten) => void
+): Promise<() => void> {
+  if (!isDesktopTauri()) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  const { getCurrentWindow } = await import('@tauri-apps/api/window');
+  const label = getCurrentWindow().label;
+  return listen<WorkspaceFileWritten>('mcp:file-written', (event) => {
+    if (event.payload.windowId === label) handler(event.payload);
+  });
+}
+
+/** A project file put back by restoring one of its checkpoints */
+export interface FileRestored {
+  /** Absolute path */
+  path: string;
+  code: string;
+  /** The file didn't exist at the checkpoint, so it was removed */
+  removed: boolean;
+}
+
+/** Subscribe to project files put back from their checkpoints */
+export async function onFileRestored(
+  handler: (restored: FileRestored) => void
+): Promise<() => void> {
+  if (!isDesktopTauri()) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<FileRestored>('history:file-restored', (event) => handler(event.payload));
+}
+
+/** An undo or restore the backend couldn't write to the document's file */
+export interface RestoreWriteFailed {
+  /** Absolute path */
+  path: string;
+  error: string;
+}
+
+/** Subscribe to restores whose file on disk no longer matches the editor */
+export async function onRestoreWriteFailed(
+  handler: (failed: RestoreWriteFailed) => void
+): Promise<() => void> {
+  if (!isDesktopTauri()) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<RestoreWriteFailed>('history:write-failed', (event) => handler(event.payload));
+}
+
+/** Reload the disk version or keep the editor's; returns the text when it is the active file */
+export async function resolveFileChange(
+  root: string,
+  path: string,
+  reload: boolean
+): Promise<string | null> {
+  return invoke<string | null>('resolve_file_change', { root, path, reload });
+}
+
+/**
+ * Reload a changed file, asking first when it has unsaved edits. Returns
+ * whether the disk version replaced the buffer.
+ */
+export async function settleFileChange(
+  change: FileChanged,
+  confirm: (message: string) => Promise<boolean>
+): Promise<boolean> {
+  if (change.removed) return false;
+  const reload =
+    !change.conflict ||
+    (await confirm(
+      `${change.path} was changed outside OpenSCAD Studio. Reload it and discard your unsaved edits?`
+    ));
+  await resolveFileChange(change.root, change.path, reload);
+  return reload;
+}
@@ -0,0 +1,35 @@
+/**
+ * API key storage (desktop). The backend keeps AI provider keys either in a
+ * plaintext secrets file or in the OS keychain; switching backends moves the
+ * existing keys across.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type KeyStorageBackend = 'file' | 'keychain';
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+export async function getKeyStorageBackend(): Promise<KeyStorageBackend> {
+  if (!isDesktopTauri()) return 'file';
+  return invoke<KeyStorageBackend>('get_key_storage_backend');
+}
+
+/** Switch backends; returns how many keys were moved */
+export async function setKeyStorageBackend(backend: KeyStorageBackend): Promise<number> {
+  return invoke<number>('set_key_storage_backend', { backend });
+}
+
+/** Every stored key, keyed by provider */
+export async function loadApiKeys(): Promise<Record<string, string>> {
+  return invoke<Record<string, string>>('get_api_keys');
+}
+
+export async function saveApiKey(provider: string, key: string): Promise<void> {
+  await invoke('set_api_key', { provider, key });
+}
+
+export async function deleteApiKey(provider: string): Promise<void> {
+  await invoke('delete_api_key', { provider });
+}
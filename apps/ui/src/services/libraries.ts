@@ -0,0 +1,58 @@
+/**
+ * OpenSCAD libraries in the user's library folder (desktop). Known
+ * libraries (BOSL2, MCAD, dotSCAD) can be installed from GitHub and pinned
+ * to a release; folders put there by hand are listed but left alone unless
+ * the user confirms replacing one.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface InstalledLibrary {
+  name: string;
+  path: string;
+  /** Known only for libraries installed by the app */
+  version: string | null;
+  /** Installed by the app, so it can be updated in place */
+  managed: boolean;
+  pinnedVersion: string | null;
+  usage: string | null;
+}
+
+export interface AvailableLibrary {
+  name: string;
+  repo: string;
+  description: string;
+  usage: string;
+  installed: boolean;
+}
+
+export interface LibraryOverview {
+  /** OpenSCAD's user library folder, where libraries are installed */
+  libraryDir: string;
+  installed: InstalledLibrary[];
+  available: AvailableLibrary[];
+}
+
+export async function listLibraries(): Promise<LibraryOverview> {
+  return invoke<LibraryOverview>('list_libraries');
+}
+
+/**
+ * Install or update a known library: `version` (a release tag) when given,
+ * else its pinned version, else the latest release. A folder of the same
+ * name the app didn't install is only replaced with `replaceUnmanaged`.
+ */
+export async function installLibrary(
+  name: string,
+  options: { version?: string; replaceUnmanaged?: boolean } = {}
+): Promise<InstalledLibrary> {
+  return invoke<InstalledLibrary>('install_library', {
+    name,
+    version: options.version ?? null,
+    replaceUnmanaged: options.replaceUnmanaged ?? false,
+  });
+}
+
+/** Pin a library to a release tag, or unpin it with `null` */
+export async function pinLibrary(name: string, version: string | null): Promise<void> {
+  await invoke('pin_library', { name, version });
+}
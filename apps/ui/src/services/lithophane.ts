@@ -0,0 +1,46 @@
+/**
+ * Lithophane generator (desktop). The backend turns a PNG photo into a
+ * self-contained OpenSCAD file, which the app opens as a new document.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface LithophaneOptions {
+  /** Width of the image area in mm */
+  width?: number;
+  minThickness?: number;
+  maxThickness?: number;
+  maxResolution?: number;
+  /** Frame border width in mm; 0 disables the frame */
+  frameWidth?: number;
+}
+
+export interface Lithophane {
+  code: string;
+  columns: number;
+  rows: number;
+  width: number;
+  depth: number;
+}
+
+const IMAGE_FILTERS = [{ name: 'PNG Image', extensions: ['png'] }];
+
+/** Ask for the photo to convert. Returns null if the user cancelled. */
+export async function pickLithophaneImage(): Promise<string | null> {
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const path = await open({ filters: IMAGE_FILTERS, multiple: false });
+  return typeof path === 'string' ? path : null;
+}
+
+export async function generateLithophane(
+  imagePath: string,
+  options?: LithophaneOptions
+): Promise<Lithophane> {
+  return invoke<Lithophane>('generate_lithophane', { imagePath, options });
+}
+
+/** `photo.png` → `photo-lithophane.scad` */
+export function lithophaneFileName(imagePath: string): string {
+  const name = imagePath.split(/[\\/]/).pop() ?? 'image';
+  const stem = name.replace(/\.[^.]+$/, '') || 'image';
+  return `${stem}-lithophane.scad`;
+}
@@ -0,0 +1,26 @@
+/**
+ * Native menu state (desktop). The backend enables menu items and checks
+ * the active layout from the context the focused window last reported.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import type { WorkspacePreset } from '../stores/layoutStore';
+
+export interface MenuContext {
+  hasDocument: boolean;
+  isDirty: boolean;
+  hasDirtyFiles: boolean;
+  /** Kind of the last successful render, if any */
+  geometry: '2d' | '3d' | null;
+  layout: WorkspacePreset | null;
+  /** Checks Pause Auto-Render in the Design menu and the tray when false */
+  autoRender: boolean;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+export async function updateMenuState(context: MenuContext): Promise<void> {
+  if (!isDesktopTauri()) return;
+  await invoke('update_menu_state', { context });
+}
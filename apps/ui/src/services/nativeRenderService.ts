@@ -12,14 +12,15 @@ import {
   type RenderOptions,
   type RenderResult,
   type ExportFormat,
+  type ImageExportOptions,
   type SyntaxCheckResult,
   type Diagnostic,
   RenderCache,
   generateRenderCacheKey,
-  hasOnlyTopLevelDimensionMismatchErrors,
   parseOpenScadStderr,
 } from './renderService';
 import { createExportValidationError } from './exportErrors';
+import { createRandomId } from '../utils/randomId';
 
 // ============================================================================
 // Tauri IPC types (must match Rust structs)
@@ -30,6 +31,22 @@ interface RenderNativeResult {
   stderr: string;
   exit_code: number;
   duration_ms: number;
+  /** Served from an older version of the code while a fresh render runs */
+  stale?: boolean;
+  /** The render job that produced the result; absent for cache hits */
+  job_id?: string;
+}
+
+/** What the OpenSCAD binary supports, probed once per binary */
+export interface OpenScadCapabilities {
+  /** `backend`: `--backend=manifold`; `feature`: `--enable=manifold`; else CGAL only */
+  manifold: 'backend' | 'feature' | 'unsupported';
+  /** `--backend=` is understood at all */
+  backendOption: boolean;
+  /** Probed experimental features (e.g. `lazy-union`) and whether they're accepted */
+  features: Record<string, boolean>;
+  /** Probed export formats by extension (`obj`, `pov`) and whether they can be written */
+  exportFormats: Record<string, boolean>;
 }
 
 // ============================================================================
@@ -41,6 +58,8 @@ export class NativeRenderService implements IRenderService {
   private initPromise: Promise<void> | null = null;
   private disposed = false;
   private version: string | null = null;
+  /** Jobs started by this service that haven't returned yet */
+  private runningJobIds = new Set<string>();
 
   /**
    * Initialize: discover the OpenSCAD binary and verify it works.
@@ -73,6 +92,15 @@ export class NativeRenderService implements IRenderService {
     return this.version;
   }
 
+  /**
+   * Get what the OpenSCAD binary supports. Backend arguments are adapted to
+   * it on the native side, so renders don't need to check this first.
+   */
+  async getCapabilities(): Promise<OpenScadCapabilities> {
+    await this.init();
+    return invoke<OpenScadCapabilities>('get_openscad_capabilities');
+  }
+
   /**
    * Check if a render result is already cached.
    */
@@ -100,6 +128,7 @@ export class NativeRenderService implements IRenderService {
       inputPath,
       workingDir,
       libraryPaths,
+      documentId,
     } = options;
 
     // Check cache
@@ -124,17 +153,23 @@ export class NativeRenderService implements IRenderService {
     if (backend === 'manifold') args.push('--backend=manifold');
     else if (backend === 'cgal') args.push('--backend=cgal');
 
+    // Previews render with `$preview=true`; the final branch is checked in the background.
+    // A document's previous result is shown while the new code renders (`render:revalidated`).
     const result = await this.invokeRender(
       code,
       args,
       auxiliaryFiles,
       inputPath,
       workingDir,
-      libraryPaths
+      libraryPaths,
+      { checkFinalBranch: true, documentId, staleWhileRevalidate: true }
     );
     const diagnostics = parseOpenScadStderr(result.stderr);
 
     const output = new Uint8Array(result.output);
+    if (result.stale) {
+      return { output, kind, diagnostics };
+    }
 
     // Cache the result
     this.cache.set(cacheKey, {
@@ -160,6 +195,9 @@ export class NativeRenderService implements IRenderService {
       workingDir?: string;
       libraryFiles?: Record<string, string>;
       libraryPaths?: string[];
+      imageOptions?: ImageExportOptions;
+      parameterFile?: string;
+      parameterSet?: string;
     } = {}
   ): Promise<Uint8Array> {
     const { backend = 'manifold' } = options;
@@ -176,6 +214,11 @@ export class NativeRenderService implements IRenderService {
       args.push('--export-format=binstl');
     }
 
+    // The backend resolves the file against the project, preferring unsaved content
+    if (options.parameterFile && options.parameterSet) {
+      args.push('-p', options.parameterFile, '-P', options.parameterSet);
+    }
+
     const allFiles =
       options.libraryFiles || options.auxiliaryFiles
         ? { ...(options.libraryFiles || {}), ...(options.auxiliaryFiles || {}) }
@@ -187,7 +230,11 @@ export class NativeRenderService implements IRenderService {
       allFiles,
       options.inputPath,
       options.workingDir,
-      options.libraryPaths
+      options.libraryPaths,
+      {
+        applyExportPreset: true,
+        imageOptions: format === 'png' ? options.imageOptions : undefined,
+      }
     );
     const output = new Uint8Array(result.output);
 
@@ -204,46 +251,43 @@ export class NativeRenderService implements IRenderService {
   }
 
   /**
-   * Check syntax by rendering (same as WASM approach).
+   * Check the code by evaluating it without building geometry (echo export),
+   * which reports the same errors and warnings as a render in a fraction of
+   * the time and doesn't depend on whether the model is 2D or 3D.
    */
   async checkSyntax(code: string, options: RenderOptions = {}): Promise<SyntaxCheckResult> {
     await this.init();
 
-    const preferredView = options.view ?? '3d';
     const allFiles =
       options.libraryFiles || options.auxiliaryFiles
         ? { ...(options.libraryFiles || {}), ...(options.auxiliaryFiles || {}) }
         : undefined;
 
-    const runSyntaxCheck = async (view: '2d' | '3d') => {
-      const outputPath = view === '3d' ? '/output.stl' : '/output.svg';
-      const args = ['/input.scad', '-o', outputPath, '--backend=manifold'];
-      const result = await this.invokeRender(
-        code,
-        args,
-        allFiles,
-        options.inputPath,
-        options.workingDir,
-        options.libraryPaths
-      );
-      return parseOpenScadStderr(result.stderr);
-    };
-
-    const diagnostics = await runSyntaxCheck(preferredView);
-    if (!hasOnlyTopLevelDimensionMismatchErrors(diagnostics)) {
-      return { diagnostics };
-    }
-
-    const fallbackView = preferredView === '3d' ? '2d' : '3d';
-    return { diagnostics: await runSyntaxCheck(fallbackView) };
+    const result = await this.invokeRender(
+      code,
+      ['/input.scad', '-o', '/output.echo'],
+      allFiles,
+      options.inputPath,
+      options.workingDir,
+      options.libraryPaths
+    );
+    return { diagnostics: parseOpenScadStderr(result.stderr) };
   }
 
   /**
-   * Cancel running render.
+   * Cancel the renders this service started. Renders started elsewhere
+   * (e.g. batch exports or MCP previews) keep running.
    */
   cancel(): void {
-    invoke('render_cancel').catch(() => {
-      // Best-effort cancellation
+    for (const jobId of this.runningJobIds) {
+      this.cancelRender(jobId);
+    }
+  }
+
+  /** Cancel one render job by id */
+  cancelRender(jobId: string): void {
+    invoke('cancel_render', { jobId }).catch(() => {
+      // Best-effort cancellation; the job may have just finished
     });
   }
 
@@ -266,22 +310,46 @@ export class NativeRenderService implements IRenderService {
     auxiliaryFiles?: Record<string, string>,
     inputPath?: string,
     workingDir?: string,
-    libraryPaths?: string[]
+    libraryPaths?: string[],
+    {
+      applyExportPreset = false,
+      imageOptions,
+      checkFinalBranch = false,
+      documentId,
+      staleWhileRevalidate = false,
+    }: {
+      applyExportPreset?: boolean;
+      imageOptions?: ImageExportOptions;
+      checkFinalBranch?: boolean;
+      documentId?: string;
+      staleWhileRevalidate?: boolean;
+    } = {}
   ): Promise<RenderNativeResult> {
     if (this.disposed) {
       throw new Error('NativeRenderService has been disposed');
     }
 
-    const result = await invoke<RenderNativeResult>('render_native', {
-      code,
-      args,
-      auxiliaryFiles:
-        auxiliaryFiles && Object.keys(auxiliaryFiles).length > 0 ? auxiliaryFiles : null,
-      inputPath: inputPath ?? null,
-      workingDir: workingDir ?? null,
-      libraryPaths: libraryPaths && libraryPaths.length > 0 ? libraryPaths : null,
-    });
-
-    return result;
+    // The id is chosen here so the render can be cancelled while it runs
+    const jobId = createRandomId();
+    this.runningJobIds.add(jobId);
+    try {
+      return await invoke<RenderNativeResult>('render_native', {
+        code,
+        args,
+        auxiliaryFiles:
+          auxiliaryFiles && Object.keys(auxiliaryFiles).length > 0 ? auxiliaryFiles : null,
+        inputPath: inputPath ?? null,
+        workingDir: workingDir ?? null,
+        libraryPaths: libraryPaths && libraryPaths.length > 0 ? libraryPaths : null,
+        applyExportPreset,
+        imageOptions: imageOptions ?? null,
+        checkFinalBranch,
+        documentId: documentId ?? null,
+        staleWhileRevalidate,
+        jobId,
+      });
+    } finally {
+      this.runningJobIds.delete(jobId);
+    }
   }
 }
@@ -14,6 +14,8 @@ export type PresetView = 'front' | 'back' | 'top' | 'bottom' | 'left' | 'right'
 
 export interface CaptureOptions {
   view?: PresetView | 'current';
+  /** Explicit camera direction from the model center; overrides view and angles */
+  direction?: [number, number, number];
   azimuth?: number;
   elevation?: number;
   width?: number;
@@ -116,7 +118,9 @@ export async function captureOffscreen(
   scene.add(axesOverlay);
 
   let direction: [number, number, number];
-  if (options.azimuth !== undefined || options.elevation !== undefined) {
+  if (options.direction && options.direction.some((component) => component !== 0)) {
+    direction = options.direction;
+  } else if (options.azimuth !== undefined || options.elevation !== undefined) {
     direction = azimuthElevationToDirection(options.azimuth ?? 45, options.elevation ?? 30);
   } else {
     const preset = (options.view as PresetView) || 'isometric';
@@ -0,0 +1,65 @@
+/**
+ * OpenSCAD builds (desktop). Renders use the bundled binary, one found on
+ * PATH, or a stable/nightly build downloaded into the app data folder;
+ * which one can be switched from settings.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type OpenScadChannel = 'stable' | 'nightly';
+
+export interface OpenScadBuild {
+  /** `bundled`, `system`, or a downloaded build's install ID */
+  id: string;
+  source: 'bundled' | 'system' | 'downloaded';
+  channel: OpenScadChannel | null;
+  version: string;
+  path: string;
+  /** Renders currently use this build */
+  active: boolean;
+}
+
+export interface OpenScadBuilds {
+  builds: OpenScadBuild[];
+  /** Builds can be downloaded for this platform */
+  canDownload: boolean;
+}
+
+export interface OpenScadInstallProgress {
+  channel: OpenScadChannel;
+  receivedBytes: number;
+  totalBytes: number | null;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+export async function listOpenScadVersions(): Promise<OpenScadBuilds> {
+  if (!isDesktopTauri()) return { builds: [], canDownload: false };
+  return invoke<OpenScadBuilds>('list_openscad_versions');
+}
+
+export async function installOpenScad(
+  channel: OpenScadChannel,
+  onProgress?: (progress: OpenScadInstallProgress) => void
+): Promise<OpenScadBuild> {
+  const { listen } = await import('@tauri-apps/api/event');
+  const unlisten = await listen<OpenScadInstallProgress>('openscad:install-progress', (event) => {
+    if (event.payload.channel === channel) onProgress?.(event.payload);
+  });
+  try {
+    return await invoke<OpenScadBuild>('install_openscad', { channel });
+  } finally {
+    unlisten();
+  }
+}
+
+/** Switch renders to the build with `id`, or back to automatic discovery
+ * with `null`. Resolves to the version now in use. */
+export async function setActiveOpenScad(id: string | null): Promise<string> {
+  return invoke<string>('set_active_openscad', { id });
+}
+
+export async function removeOpenScadVersion(id: string): Promise<void> {
+  await invoke('remove_openscad_version', { id });
+}
@@ -0,0 +1,150 @@
+/**
+ * AI edits held for review (desktop main window). The backend validates a
+ * proposed edit with a dry-run `apply_edit`, keeps it with its diff until
+ * the user accepts or rejects it, and reports the list as
+ * `editor:pending-edits`. Accepting re-applies the edit to the document as
+ * it is then, under a checkpoint.
+ *
+ * Edits to a file shown in a tab go to that tab's document; other files go
+ * through the backend's project folder, so they need one on disk.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import {
+  documentIdForProjectPath,
+  isEditorSyncActive,
+  projectPathForDocument,
+  whenEditorSynced,
+} from './editorSync';
+
+/** An edit waiting for the user to accept or reject it */
+export interface PendingEdit {
+  id: string;
+  /** Document (tab) the edit is for */
+  documentId: string | null;
+  /** Project-relative file, or `null` for the document's buffer */
+  filePath: string | null;
+  oldString: string;
+  newString: string;
+  /** Line diff of the file, each line prefixed with ` `, `-` or `+` */
+  diff: string;
+  addedLines: number;
+  removedLines: number;
+  createdAt: number;
+}
+
+interface AppliedEdit {
+  documentId: string | null;
+  filePath: string | null;
+  code: string;
+  checkpointId: string | null;
+}
+
+/** Whether edits can be held for review in this window */
+export function isEditReviewAvailable(): boolean {
+  return isEditorSyncActive();
+}
+
+/** Project file a pending edit changes */
+export function pendingEditFile(edit: PendingEdit): string {
+  return (
+    edit.filePath ?? (edit.documentId && projectPathForDocument(edit.documentId)) ?? 'the editor'
+  );
+}
+
+/**
+ * The single replacement that turns `oldCode` into `newCode`: the changed
+ * lines, widened a line at a time until they appear only once in `oldCode`
+ */
+export function uniqueReplacement(
+  oldCode: string,
+  newCode: string
+): { oldString: string; newString: string } | null {
+  const shorter = Math.min(oldCode.length, newCode.length);
+  let prefix = 0;
+  while (prefix < shorter && oldCode[prefix] === newCode[prefix]) prefix++;
+  let suffix = 0;
+  while (
+    suffix < shorter - prefix &&
+    oldCode[oldCode.length - 1 - suffix] === newCode[newCode.length - 1 - suffix]
+  ) {
+    suffix++;
+  }
+
+  const lineEnd = (from: number) => {
+    const newline = oldCode.indexOf('\n', from);
+    return newline === -1 ? oldCode.length : newline + 1;
+  };
+  const lineStart = (before: number) => (before <= 0 ? 0 : oldCode.lastIndexOf('\n', before - 1) + 1);
+  let start = lineStart(prefix);
+  let end = lineEnd(oldCode.length - suffix);
+  for (;;) {
+    const oldString = oldCode.slice(start, end);
+    const unique =
+      oldString !== '' && oldCode.indexOf(oldString, oldCode.indexOf(oldString) + 1) === -1;
+    if (unique) {
+      return {
+        oldString,
+        newString: newCode.slice(start, newCode.length - (oldCode.length - end)),
+      };
+    }
+    if (start === 0 && end === oldCode.length) return null;
+    start = lineStart(start - 1);
+    end = lineEnd(end);
+  }
+}
+
+/**
+ * Hold the edit turning `oldCode` (the file's current text) into `newCode`
+ * for review. Rejects when the backend can't reach the file.
+ */
+export async function proposeEdit(
+  projectPath: string,
+  oldCode: string,
+  newCode: string
+): Promise<PendingEdit> {
+  const replacement = uniqueReplacement(oldCode, newCode);
+  if (!replacement) {
+    throw new Error('An empty file can only be edited directly');
+  }
+  // The document must hold `oldCode` before the edit is checked against it
+  await whenEditorSynced();
+  const documentId = documentIdForProjectPath(projectPath);
+  // A dry run always comes back as `{ status: 'pending', ...edit }`
+  return invoke<PendingEdit>('apply_edit', {
+    filePath: documentId ? null : projectPath,
+    oldString: replacement.oldString,
+    newString: replacement.newString,
+    dryRun: true,
+    documentId: documentId ?? null,
+  });
+}
+
+/**
+ * Apply a pending edit. Resolves to the project file it changed and that
+ * file's new text; rejects when the edit no longer applies, which drops it.
+ */
+export async function acceptPendingEdit(
+  id: string
+): Promise<{ projectPath: string | null; code: string }> {
+  await whenEditorSynced();
+  const applied = await invoke<AppliedEdit>('accept_pending_edit', { id });
+  const projectPath =
+    applied.filePath ?? (applied.documentId && projectPathForDocument(applied.documentId)) ?? null;
+  return { projectPath, code: applied.code };
+}
+
+export async function rejectPendingEdit(id: string): Promise<void> {
+  await invoke('reject_pending_edit', { id });
+}
+
+/** Subscribe to the list of pending edits, sent whenever it changes */
+export async function onPendingEdits(
+  handler: (edits: PendingEdit[]) => void
+): Promise<() => void> {
+  if (typeof window === 'undefined' || !('__TAURI_INTERNALS__' in window)) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<PendingEdit[]>('editor:pending-edits', (event) => {
+    // Only the window mirroring its tabs reviews edits
+    if (isEditReviewAvailable()) handler(event.payload);
+  });
+}
@@ -0,0 +1,98 @@
+/**
+ * Networked printers (desktop). Exported STL or sliced G-code files are
+ * uploaded to OctoPrint or Klipper (Moonraker) by the native side, which
+ * keeps the connections and API keys in the settings store.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import { notifyPromise } from '../utils/notifications';
+
+export type PrinterKind = 'octoprint' | 'moonraker';
+
+export interface PrinterSummary {
+  id: string;
+  name: string;
+  kind: PrinterKind;
+  url: string;
+  hasApiKey: boolean;
+}
+
+export interface PrinterInput {
+  /** Existing printer to update; omit to add one */
+  id?: string;
+  name: string;
+  kind: PrinterKind;
+  url: string;
+  /** Omit to keep the stored key, `''` to remove it */
+  apiKey?: string;
+}
+
+export interface PrinterUploadProgress {
+  printerId: string;
+  fileName: string;
+  stage: 'uploading' | 'done' | 'failed';
+  sentBytes: number;
+  totalBytes: number;
+  message: string | null;
+}
+
+export interface PrinterUpload {
+  printer: string;
+  fileName: string;
+  startedPrint: boolean;
+  durationMs: number;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+export async function listPrinters(): Promise<PrinterSummary[]> {
+  if (!isDesktopTauri()) return [];
+  return invoke<PrinterSummary[]>('list_printers');
+}
+
+export async function savePrinter(printer: PrinterInput): Promise<PrinterSummary> {
+  return invoke<PrinterSummary>('save_printer', { printer });
+}
+
+export async function removePrinter(id: string): Promise<void> {
+  await invoke('remove_printer', { id });
+}
+
+/** Resolves to the server's name and version, e.g. "OctoPrint 1.10.2" */
+export async function testPrinterConnection(id: string): Promise<string> {
+  return invoke<string>('test_printer_connection', { id });
+}
+
+/** Upload an exported file to a printer, with toasts for progress and errors */
+export async function uploadToPrinter(
+  printer: PrinterSummary,
+  filePath: string,
+  options: { startPrint?: boolean; onProgress?: (progress: PrinterUploadProgress) => void } = {}
+): Promise<PrinterUpload> {
+  const { listen } = await import('@tauri-apps/api/event');
+  const unlisten = await listen<PrinterUploadProgress>('printer:upload-progress', (event) => {
+    if (event.payload.printerId === printer.id) options.onProgress?.(event.payload);
+  });
+  try {
+    return await notifyPromise(
+      invoke<PrinterUpload>('upload_to_printer', {
+        printerId: printer.id,
+        filePath,
+        startPrint: options.startPrint ?? false,
+      }),
+      {
+        loading: `Uploading to ${printer.name}…`,
+        success: (upload) =>
+          upload.startedPrint
+            ? `Printing ${upload.fileName} on ${upload.printer}`
+            : `Uploaded ${upload.fileName} to ${upload.printer}`,
+        error: (error) => error.message,
+        toastId: `printer-upload-${printer.id}`,
+        logLabel: '[printing]',
+      }
+    );
+  } finally {
+    unlisten();
+  }
+}
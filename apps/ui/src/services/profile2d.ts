@@ -0,0 +1,44 @@
+/**
+ * 2D profiles (desktop). The native side renders a 2D design to SVG, or
+ * reads a DXF, and returns a drawing annotated with its overall dimensions
+ * plus the bounding box, area and cut length in millimetres.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ProfileMeasurements {
+  boundingBoxMin: [number, number];
+  boundingBoxMax: [number, number];
+  width: number;
+  height: number;
+  /** Filled area in mm², holes excluded */
+  area: number;
+  /** Total outline length, i.e. the laser cut length */
+  perimeter: number;
+  outlineCount: number;
+  holeCount: number;
+}
+
+export interface ProfileDrawing {
+  svg: string;
+  measurements: ProfileMeasurements;
+  warnings: string[];
+}
+
+/** Render a 2D design (defaults to the current editor code) with dimension annotations */
+export async function render2dWithDimensions(
+  code?: string,
+  workingDir?: string | null
+): Promise<ProfileDrawing> {
+  return invoke<ProfileDrawing>('render_2d_with_dimensions', {
+    code,
+    workingDir: workingDir ?? undefined,
+  });
+}
+
+/** SVG preview of a DXF file as a laser cutter would cut it */
+export async function previewDxf(
+  source: { path: string } | { content: string },
+  dimensioned = false
+): Promise<ProfileDrawing> {
+  return invoke<ProfileDrawing>('preview_dxf', { ...source, dimensioned });
+}
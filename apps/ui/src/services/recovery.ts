@@ -0,0 +1,81 @@
+/**
+ * Autosave and crash recovery (desktop). While a window is open its unsaved
+ * buffers are snapshotted every 30 seconds; if the app doesn't shut down
+ * cleanly, the next launch lists those snapshots so they can be restored.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import { getProjectStore } from '../stores/projectStore';
+
+export interface AutosaveBuffer {
+  /** File on disk, or null for an untitled buffer */
+  path: string | null;
+  name: string;
+  content: string;
+}
+
+export interface RecoveredFile {
+  id: string;
+  path: string | null;
+  name: string;
+  savedAt: number;
+  size: number;
+}
+
+export interface RecoveredContent extends RecoveredFile {
+  content: string;
+}
+
+const AUTOSAVE_INTERVAL_MS = 30_000;
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/** Replace this window's snapshots with `buffers` */
+export async function autosaveBuffers(buffers: AutosaveBuffer[]): Promise<void> {
+  if (!isDesktopTauri()) return;
+  await invoke('autosave_buffers', { buffers });
+}
+
+/** Unsaved files left behind by a crash, newest first */
+export async function listRecoveredFiles(): Promise<RecoveredFile[]> {
+  if (!isDesktopTauri()) return [];
+  return invoke<RecoveredFile[]>('list_recovered_files');
+}
+
+/** A recovered file's contents; it is removed from recovery */
+export async function restoreRecoveredFile(id: string): Promise<RecoveredContent> {
+  return invoke<RecoveredContent>('restore_recovered_file', { id });
+}
+
+export async function discardRecoveredFile(id: string): Promise<void> {
+  await invoke('discard_recovered_file', { id });
+}
+
+/** Unsaved files of the open project */
+export function dirtyBuffers(): AutosaveBuffer[] {
+  const { projectRoot, files } = getProjectStore().getState();
+  return Object.entries(files)
+    .filter(([, file]) => file.isDirty)
+    .map(([relativePath, file]) => ({
+      path: projectRoot && !file.isVirtual ? `${projectRoot}/${relativePath}` : null,
+      name: relativePath,
+      content: file.content,
+    }));
+}
+
+/** Snapshot unsaved buffers whenever they changed since the last tick; returns a stop function */
+export function startAutosave(): () => void {
+  if (!isDesktopTauri()) return () => {};
+  let lastSnapshot = '[]';
+  const timer = setInterval(() => {
+    const buffers = dirtyBuffers();
+    const snapshot = JSON.stringify(buffers);
+    if (snapshot === lastSnapshot) return;
+    lastSnapshot = snapshot;
+    autosaveBuffers(buffers).catch((error) => {
+      console.warn('[recovery] Autosave failed:', error);
+    });
+  }, AUTOSAVE_INTERVAL_MS);
+  return () => clearInterval(timer);
+}
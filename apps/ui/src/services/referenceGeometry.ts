@@ -0,0 +1,64 @@
+/**
+ * Reference geometry (desktop). Meshes and drawings a model loads with
+ * `import()` are copied into the project and checked on the native side,
+ * which also works out the `import()` statement that loads each at the
+ * right scale.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ReferenceGeometry {
+  /** Path as written in `import()` */
+  path: string;
+  format: 'stl' | 'off' | '3mf' | 'svg' | 'dxf';
+  encoding?: 'ascii' | 'binary';
+  faceCount?: number;
+  /** Bounding box extents in millimetres once imported as suggested */
+  size?: [number, number, number];
+  units?: string;
+  scale?: number;
+  warnings: string[];
+  importStatement: string;
+}
+
+export interface ReferenceGeometryList {
+  files: ReferenceGeometry[];
+  unreadable: { path: string; reason: string }[];
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/** Copy a mesh or drawing into the project after checking OpenSCAD can import it */
+export async function importReferenceGeometry(sourcePath: string): Promise<ReferenceGeometry> {
+  return invoke<ReferenceGeometry>('import_reference_geometry', { sourcePath });
+}
+
+export async function listReferenceGeometry(): Promise<ReferenceGeometryList> {
+  if (!isDesktopTauri()) return { files: [], unreadable: [] };
+  return invoke<ReferenceGeometryList>('list_reference_geometry');
+}
+
+/** Summary for the AI: one line per file with the statement to use */
+export function describeReferenceGeometry({ files, unreadable }: ReferenceGeometryList): string {
+  if (files.length === 0 && unreadable.length === 0) {
+    return 'The project has no STL, OFF, 3MF, SVG or DXF files to import.';
+  }
+  const lines = ['Reference geometry in the project (use these import() statements as is):'];
+  for (const file of files) {
+    const details = [
+      file.encoding && `${file.encoding} STL`,
+      file.faceCount !== undefined && `${file.faceCount} faces`,
+      file.size && `${file.size.map((value) => value.toFixed(2)).join(' × ')} mm`,
+      file.units && `units: ${file.units}`,
+    ].filter(Boolean);
+    lines.push(
+      `- ${file.importStatement}${details.length > 0 ? ` — ${details.join(', ')}` : ''}`,
+      ...file.warnings.map((warning) => `  Warning: ${warning}`)
+    );
+  }
+  for (const { path, reason } of unreadable) {
+    lines.push(`- ${path} cannot be imported: ${reason}`);
+  }
+  return lines.join('\n');
+}
@@ -0,0 +1,51 @@
+/**
+ * Events from native renders (desktop). Renders report follow-up results
+ * in the background after their command has returned.
+ */
+
+/** The `$preview=false` branch of a successful preview behaves differently */
+export interface PreviewDivergence {
+  previewExitCode: number;
+  finalExitCode: number;
+  finalStderr: string;
+  warnings: string[];
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/** Subscribe to warnings that an exported model will differ from its preview */
+export async function onPreviewDivergence(
+  handler: (divergence: PreviewDivergence) => void
+): Promise<() => void> {
+  if (!isDesktopTauri()) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<PreviewDivergence>('render:preview-divergence', (event) => handler(event.payload));
+}
+
+/**
+ * Subscribe to renders of untrusted project folders that safe mode
+ * isolated. The payload is the project folder.
+ */
+export async function onUntrustedProject(handler: (folder: string) => void): Promise<() => void> {
+  if (!isDesktopTauri()) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<string | null>('render:untrusted-project', (event) => {
+    if (event.payload) handler(event.payload);
+  });
+}
+
+/**
+ * Subscribe to background renders that replaced a stale result. The payload
+ * is the backend document, whose fresh result is now in the render cache.
+ */
+export async function onRenderRevalidated(
+  handler: (documentId: string) => void
+): Promise<() => void> {
+  if (!isDesktopTauri()) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<{ documentId: string }>('render:revalidated', (event) =>
+    handler(event.payload.documentId)
+  );
+}
@@ -0,0 +1,46 @@
+/**
+ * Auto-render scheduling (desktop). Edits ask the backend when to render:
+ * it waits out the debounce interval, lets newer edits supersede waiting
+ * ones, and can hold renders while another runs or skip code that doesn't
+ * parse.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface RenderSchedulerSettings {
+  /** Quiet time after the last edit before an automatic render starts */
+  debounceMs: number;
+  /** Hold automatic renders until no other render is running */
+  onlyWhenIdle: boolean;
+  /** Skip automatic renders of code that doesn't parse */
+  onlyWhenClean: boolean;
+}
+
+export type RenderSkipReason = 'superseded' | 'syntax-errors';
+
+export interface ScheduledRender {
+  generation: number;
+  /** Why the request was dropped; `null` means render now */
+  skipped: RenderSkipReason | null;
+}
+
+export function isDesktopRenderScheduler(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/**
+ * Resolves once the render should start, or with the reason it was dropped.
+ * Requests with the same `documentId` supersede each other.
+ */
+export async function scheduleRender(code: string, documentId?: string): Promise<ScheduledRender> {
+  return invoke<ScheduledRender>('schedule_render', { documentId: documentId ?? null, code });
+}
+
+export async function getRenderSchedulerSettings(): Promise<RenderSchedulerSettings> {
+  return invoke<RenderSchedulerSettings>('get_render_scheduler_settings');
+}
+
+export async function setRenderSchedulerSettings(
+  settings: RenderSchedulerSettings
+): Promise<void> {
+  await invoke('set_render_scheduler_settings', { settings });
+}
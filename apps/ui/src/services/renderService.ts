@@ -6,6 +6,7 @@ import type {
 } from './openscad-worker';
 import { createExportValidationError, isImplicitOpenScadError } from './exportErrors';
 import { notifyError } from '../utils/notifications';
+import type { NativeRenderService } from './nativeRenderService';
 
 // ============================================================================
 // Public types
@@ -15,16 +16,49 @@ export interface Diagnostic {
   severity: 'error' | 'warning' | 'info';
   line?: number;
   col?: number;
+  /** Project-relative file the message refers to, when it names one. */
+  file?: string;
+  category?: 'unknown-variable' | 'unknown-module' | 'unknown-function' | 'assertion-failed';
   message: string;
 }
 
-export type ExportFormat = 'stl' | 'obj' | 'amf' | '3mf' | 'png' | 'svg' | 'dxf';
+export type ExportFormat =
+  | 'stl'
+  | 'obj'
+  | 'amf'
+  | '3mf'
+  | 'off'
+  | 'wrl'
+  | 'pov'
+  | 'csg'
+  | 'png'
+  | 'svg'
+  | 'dxf';
 
 export interface ExportOptions extends Pick<
   RenderOptions,
   'auxiliaryFiles' | 'inputPath' | 'workingDir' | 'libraryFiles' | 'libraryPaths'
 > {
   backend?: 'manifold' | 'cgal' | 'auto';
+  /** PNG size, colors and view (native exports only). */
+  imageOptions?: ImageExportOptions;
+  /** Project-relative customizer parameter file (.json) to apply (native exports only). */
+  parameterFile?: string;
+  /** Parameter set inside `parameterFile` to apply. */
+  parameterSet?: string;
+}
+
+export interface ImageExportOptions {
+  width?: number;
+  height?: number;
+  /** OpenSCAD color scheme name, e.g. "Tomorrow Night". */
+  colorscheme?: string;
+  projection?: 'orthographic' | 'perspective';
+  /** Full geometry render instead of the fast preview. */
+  fullRender?: boolean;
+  /** Viewport overlays: axes, crosshairs, edges, scales, wireframe. */
+  view?: string[];
+  transparent?: boolean;
 }
 
 export interface RenderOptions {
@@ -44,6 +78,9 @@ export interface RenderOptions {
   /** Absolute paths to library directories for native OpenSCAD -L flag resolution.
    *  WASM renderer ignores this. */
   libraryPaths?: string[];
+  /** Backend document (tab) the render is for, so it uses that tab's render cache
+   *  and paths (desktop only). */
+  documentId?: string;
 }
 
 export interface RenderResult {
@@ -575,6 +612,9 @@ if (isTauri()) {
   }) as Promise<typeof import('./nativeRenderService')>;
 }
 
+// Set when the desktop app found no OpenSCAD binary and renders in WebAssembly
+let wasmFallback = false;
+
 /**
  * Get the singleton render service instance.
  * Returns NativeRenderService on desktop (Tauri), WasmRenderService on web.
@@ -588,16 +628,26 @@ export function getRenderService(): IRenderService {
 
 /**
  * Ensure the correct render service is loaded.
- * On Tauri, waits for NativeRenderService to load and swaps it in.
+ * On Tauri, waits for NativeRenderService to load and swaps it in, unless
+ * no OpenSCAD binary can be found, in which case WasmRenderService stays so
+ * previews work before OpenSCAD is installed.
  * On web, resolves immediately.
  * Call this once at startup before the first render.
  */
 export async function ensureRenderService(): Promise<IRenderService> {
-  if (nativeServicePromise) {
+  if (nativeServicePromise && !wasmFallback) {
     const mod = await nativeServicePromise;
     if (mod && (!globalInstance || globalInstance instanceof WasmRenderService)) {
-      if (globalInstance) globalInstance.dispose();
-      globalInstance = new mod.NativeRenderService();
+      const native = new mod.NativeRenderService();
+      try {
+        await native.init();
+        if (globalInstance) globalInstance.dispose();
+        globalInstance = native;
+      } catch (err) {
+        native.dispose();
+        wasmFallback = true;
+        console.warn('[ensureRenderService] Falling back to WebAssembly OpenSCAD:', err);
+      }
     }
   }
   if (!globalInstance) {
@@ -606,6 +656,27 @@ export async function ensureRenderService(): Promise<IRenderService> {
   return globalInstance;
 }
 
+/**
+ * Whether the desktop app is rendering with WebAssembly because no OpenSCAD
+ * binary was found. Stays set until restart.
+ */
+export function isWasmFallback(): boolean {
+  return wasmFallback;
+}
+
+/**
+ * Export formats the OpenSCAD binary can't write, such as POV-Ray with
+ * releases before 2024. Empty on the web, where the bundled build has them all.
+ */
+export async function getUnsupportedExportFormats(): Promise<ExportFormat[]> {
+  const service = getRenderService();
+  if (!('getCapabilities' in service)) return [];
+  const { exportFormats } = await (service as NativeRenderService).getCapabilities();
+  return Object.entries(exportFormats)
+    .filter(([, supported]) => !supported)
+    .map(([format]) => format as ExportFormat);
+}
+
 /**
  * Replace the global render service instance (for testing).
  */
@@ -0,0 +1,32 @@
+/**
+ * Safe mode (desktop). When it is on, projects the user hasn't trusted
+ * render in an isolated workspace with a short timeout, and code reading
+ * files outside the project is refused. Trusting a folder lifts that.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface SafeModeSettings {
+  enabled: boolean;
+  /** Canonical paths of trusted project folders */
+  trustedProjects: string[];
+}
+
+export async function getSafeModeSettings(): Promise<SafeModeSettings> {
+  return invoke<SafeModeSettings>('get_safe_mode_settings');
+}
+
+export async function setSafeModeEnabled(enabled: boolean): Promise<SafeModeSettings> {
+  return invoke<SafeModeSettings>('set_safe_mode_enabled', { enabled });
+}
+
+export async function isProjectTrusted(path: string): Promise<boolean> {
+  return invoke<boolean>('is_project_trusted', { path });
+}
+
+export async function trustProject(path: string): Promise<SafeModeSettings> {
+  return invoke<SafeModeSettings>('trust_project', { path });
+}
+
+export async function untrustProject(path: string): Promise<SafeModeSettings> {
+  return invoke<SafeModeSettings>('untrust_project', { path });
+}
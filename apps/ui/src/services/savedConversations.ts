@@ -0,0 +1,118 @@
+/**
+ * Saved AI conversations (desktop). A saved conversation is written to the
+ * app data folder with the image attachments it references, and its text,
+ * tool call arguments and results included, is added to a search index that
+ * is updated on every save. Conversations can also be exported to a file,
+ * as JSON to import again or as a Markdown transcript. Saves and JSON
+ * exports hold the conversation in its stored, content-block form.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import type { AttachmentStore, Conversation } from '../types/aiChat';
+import {
+  fromStoredConversation,
+  toStoredConversation,
+  type StoredConversation,
+} from '../utils/storedConversation';
+
+export interface ConversationSearchHit {
+  conversationId: string;
+  title: string;
+  timestamp: number;
+  /** The message the snippet is from, unless only the title matched */
+  messageId: string | null;
+  snippet: string;
+  score: number;
+}
+
+export interface SavedConversation {
+  conversation: Conversation;
+  attachments: AttachmentStore;
+}
+
+export type ConversationExportFormat = 'json' | 'markdown';
+
+interface StoredSavedConversation {
+  conversation: StoredConversation;
+  attachments: AttachmentStore;
+}
+
+function fromStored({ conversation, attachments }: StoredSavedConversation): SavedConversation {
+  return { conversation: fromStoredConversation(conversation), attachments };
+}
+
+const JSON_FILTER = { name: 'Conversation', extensions: ['json'] };
+const MARKDOWN_FILTER = { name: 'Markdown Transcript', extensions: ['md'] };
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/** Save `conversation`, replacing its previous save */
+export async function saveConversation(
+  conversation: Conversation,
+  attachments: AttachmentStore
+): Promise<void> {
+  if (!isDesktopTauri()) return;
+  await invoke('save_conversation', {
+    conversation: toStoredConversation(conversation),
+    attachments,
+  });
+}
+
+/** Best matches first */
+export async function searchConversations(
+  query: string,
+  options: { limit?: number } = {}
+): Promise<ConversationSearchHit[]> {
+  if (!isDesktopTauri()) return [];
+  return invoke<ConversationSearchHit[]>('search_conversations', {
+    query,
+    limit: options.limit,
+  });
+}
+
+export async function openSavedConversation(conversationId: string): Promise<SavedConversation> {
+  return fromStored(
+    await invoke<StoredSavedConversation>('open_saved_conversation', { conversationId })
+  );
+}
+
+/** Write `conversation` to `path`; JSON exports include its image attachments */
+export async function exportConversation(
+  conversation: Conversation,
+  attachments: AttachmentStore,
+  format: ConversationExportFormat,
+  path: string
+): Promise<void> {
+  await invoke('export_conversation', {
+    conversation: toStoredConversation(conversation),
+    attachments: format === 'json' ? attachments : undefined,
+    format,
+    path,
+  });
+}
+
+/** Read a JSON export, migrating exports from older versions */
+export async function importConversation(path: string): Promise<SavedConversation> {
+  return fromStored(await invoke<StoredSavedConversation>('import_conversation', { path }));
+}
+
+/** Markdown for `.md` paths, otherwise JSON */
+export function conversationExportFormat(path: string): ConversationExportFormat {
+  return path.toLowerCase().endsWith('.md') ? 'markdown' : 'json';
+}
+
+/** Ask where to export a conversation; null when cancelled */
+export async function pickConversationExportPath(): Promise<string | null> {
+  if (!isDesktopTauri()) return null;
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  return save({ filters: [JSON_FILTER, MARKDOWN_FILTER], defaultPath: 'conversation.json' });
+}
+
+/** Ask for a JSON export to import; null when cancelled */
+export async function pickConversationImportPath(): Promise<string | null> {
+  if (!isDesktopTauri()) return null;
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const path = await open({ filters: [JSON_FILTER], multiple: false });
+  return typeof path === 'string' ? path : null;
+}
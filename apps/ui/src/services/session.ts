@@ -0,0 +1,178 @@
+/**
+ * Recent projects and session restore (desktop). The backend keeps both in
+ * the store plugin; this module tracks the live session (tabs, cursors,
+ * viewer camera, chat draft) and saves it shortly after each change so the
+ * next launch can reopen it.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type RecentKind = 'file' | 'folder';
+
+export interface RecentProject {
+  path: string;
+  name: string;
+  kind: RecentKind;
+  lastOpened: number;
+}
+
+export interface CursorPosition {
+  line: number;
+  column: number;
+}
+
+export interface SessionFile {
+  path: string;
+  cursor?: CursorPosition | null;
+}
+
+export interface SessionCamera {
+  position: [number, number, number];
+  target: [number, number, number];
+}
+
+export interface SessionSnapshot {
+  workingDir: string | null;
+  /** Open tabs backed by files on disk, in tab order */
+  openFiles: SessionFile[];
+  activeFile: string | null;
+  camera: SessionCamera | null;
+  chatDraft: string;
+}
+
+export interface SessionTab {
+  id: string;
+  filePath: string | null;
+}
+
+const SAVE_DELAY_MS = 1000;
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+export async function getRecentProjects(): Promise<RecentProject[]> {
+  if (!isDesktopTauri()) return [];
+  return invoke<RecentProject[]>('get_recent_projects');
+}
+
+/** Record an opened file or folder; also refreshes File → Open Recent */
+export async function addRecentProject(path: string, kind: RecentKind): Promise<void> {
+  if (!isDesktopTauri()) return;
+  await invoke('add_recent_project', { path, kind });
+}
+
+export async function clearRecents(): Promise<void> {
+  if (!isDesktopTauri()) return;
+  await invoke('clear_recents');
+}
+
+/** The previous session, handed out once per launch */
+export async function restoreSession(): Promise<SessionSnapshot | null> {
+  if (!isDesktopTauri()) return null;
+  return invoke<SessionSnapshot | null>('restore_session');
+}
+
+const live = {
+  workingDir: null as string | null,
+  tabs: [] as SessionTab[],
+  activeTabId: null as string | null,
+  cursors: new Map<string, CursorPosition>(),
+  camera: null as SessionCamera | null,
+  chatDraft: '',
+};
+let restoredCamera: SessionCamera | null = null;
+let restoredChatDraft: string | null = null;
+let saveTimer: ReturnType<typeof setTimeout> | null = null;
+
+function currentSnapshot(): SessionSnapshot {
+  const openTabs = live.tabs.filter(
+    (tab): tab is SessionTab & { filePath: string } => tab.filePath !== null
+  );
+  return {
+    workingDir: live.workingDir,
+    openFiles: openTabs.map((tab) => ({
+      path: tab.filePath,
+      cursor: live.cursors.get(tab.id) ?? null,
+    })),
+    activeFile: openTabs.find((tab) => tab.id === live.activeTabId)?.filePath ?? null,
+    camera: live.camera,
+    chatDraft: live.chatDraft,
+  };
+}
+
+function scheduleSave() {
+  if (!isDesktopTauri()) return;
+  if (saveTimer) clearTimeout(saveTimer);
+  saveTimer = setTimeout(() => {
+    saveTimer = null;
+    invoke('save_session', { snapshot: currentSnapshot() }).catch((error) => {
+      console.warn('[session] Failed to save session:', error);
+    });
+  }, SAVE_DELAY_MS);
+}
+
+export function trackSessionWorkspace(
+  workingDir: string | null,
+  tabs: SessionTab[],
+  activeTabId: string | null
+) {
+  live.workingDir = workingDir;
+  live.tabs = tabs;
+  live.activeTabId = activeTabId;
+  for (const id of live.cursors.keys()) {
+    if (!tabs.some((tab) => tab.id === id)) live.cursors.delete(id);
+  }
+  scheduleSave();
+}
+
+export function trackSessionCursor(tabId: string, cursor: CursorPosition) {
+  live.cursors.set(tabId, cursor);
+  scheduleSave();
+}
+
+export function trackSessionCamera(camera: SessionCamera) {
+  live.camera = camera;
+  scheduleSave();
+}
+
+export function trackSessionChatDraft(text: string) {
+  if (text === live.chatDraft) return;
+  live.chatDraft = text;
+  scheduleSave();
+}
+
+/** Cursor last recorded for a tab, used to place it when the tab first opens */
+export function sessionCursor(tabId: string): CursorPosition | undefined {
+  return live.cursors.get(tabId);
+}
+
+/**
+ * Stage a restored session's cursors, camera and chat draft. `tabIdsByPath`
+ * maps each reopened file to its new tab.
+ */
+export function stageRestoredSession(
+  snapshot: SessionSnapshot,
+  tabIdsByPath: Map<string, string>
+) {
+  for (const file of snapshot.openFiles) {
+    const tabId = tabIdsByPath.get(file.path);
+    if (tabId && file.cursor) live.cursors.set(tabId, file.cursor);
+  }
+  live.camera = snapshot.camera;
+  restoredCamera = snapshot.camera;
+  live.chatDraft = snapshot.chatDraft;
+  restoredChatDraft = snapshot.chatDraft || null;
+}
+
+/** The restored viewer camera, applied to the first framed model only */
+export function takeRestoredCamera(): SessionCamera | null {
+  const camera = restoredCamera;
+  restoredCamera = null;
+  return camera;
+}
+
+export function takeRestoredChatDraft(): string | null {
+  const draft = restoredChatDraft;
+  restoredChatDraft = null;
+  return draft;
+}
@@ -0,0 +1,89 @@
+/**
+ * Settings export/import and named profiles (desktop). The backend bundles
+ * its own settings with the frontend preferences collected here (editor,
+ * viewer, libraries, AI model); API keys are only exported on request and
+ * never saved in profiles.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import {
+  getApiKey,
+  getAzureOpenAiConfig,
+  getOpenAiCompatibleConfig,
+  getStoredModelSelection,
+  loadDesktopApiKeys,
+  setStoredModelSelection,
+  storeAzureOpenAiConfig,
+  storeOpenAiCompatibleConfig,
+  type AiModelSelection,
+} from '../stores/apiKeyStore';
+import { loadSettings, saveSettings, type Settings } from '../stores/settingsStore';
+
+export interface SettingsProfile {
+  name: string;
+  savedAt: number;
+}
+
+interface UiSettingsBundle {
+  settings: Settings;
+  aiModel: AiModelSelection;
+  openaiCompatible: { baseUrl: string; modelId: string };
+  azureOpenai?: { endpoint: string; deployments: string[] };
+}
+
+function collectUiSettings(): UiSettingsBundle {
+  const { baseUrl, modelId } = getOpenAiCompatibleConfig();
+  const { endpoint, deployments } = getAzureOpenAiConfig();
+  return {
+    settings: loadSettings(),
+    aiModel: getStoredModelSelection(),
+    openaiCompatible: { baseUrl, modelId },
+    azureOpenai: { endpoint, deployments },
+  };
+}
+
+/** Apply imported frontend preferences; returns the resulting settings */
+async function applyUiSettings(ui: Partial<UiSettingsBundle> | null): Promise<Settings> {
+  // Imports may carry API keys, which the backend has just stored
+  await loadDesktopApiKeys();
+  if (ui?.settings) saveSettings(ui.settings);
+  if (ui?.openaiCompatible) {
+    storeOpenAiCompatibleConfig({
+      ...ui.openaiCompatible,
+      apiKey: getApiKey('openai-compatible'),
+    });
+  }
+  if (ui?.azureOpenai) {
+    storeAzureOpenAiConfig({ ...ui.azureOpenai, apiKey: getApiKey('azure-openai') });
+  }
+  if (ui?.aiModel) setStoredModelSelection(ui.aiModel);
+  return loadSettings();
+}
+
+export async function exportSettings(path: string, includeSecrets = false): Promise<void> {
+  await invoke('export_settings', { path, ui: collectUiSettings(), includeSecrets });
+}
+
+export async function importSettings(path: string): Promise<Settings> {
+  return applyUiSettings(
+    await invoke<Partial<UiSettingsBundle> | null>('import_settings', { path })
+  );
+}
+
+export async function listSettingsProfiles(): Promise<SettingsProfile[]> {
+  return invoke<SettingsProfile[]>('list_settings_profiles');
+}
+
+/** Save the current settings under `name`, replacing a profile of that name */
+export async function saveSettingsProfile(name: string): Promise<SettingsProfile[]> {
+  return invoke<SettingsProfile[]>('save_settings_profile', { name, ui: collectUiSettings() });
+}
+
+export async function applySettingsProfile(name: string): Promise<Settings> {
+  return applyUiSettings(
+    await invoke<Partial<UiSettingsBundle> | null>('apply_settings_profile', { name })
+  );
+}
+
+export async function deleteSettingsProfile(name: string): Promise<SettingsProfile[]> {
+  return invoke<SettingsProfile[]>('delete_settings_profile', { name });
+}
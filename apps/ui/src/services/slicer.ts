@@ -0,0 +1,58 @@
+/**
+ * Slicer hand-off (desktop). The native side exports the design to a
+ * temporary STL or 3MF and opens it in PrusaSlicer, UltiMaker Cura, Bambu
+ * Studio or a configured slicer executable.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type SlicerModelFormat = 'stl' | '3mf';
+
+export interface Slicer {
+  /** `prusaslicer`, `cura`, `bambustudio` or `custom` */
+  id: string;
+  name: string;
+  path: string;
+  format: SlicerModelFormat;
+}
+
+export interface SlicerSettings {
+  slicer?: string | null;
+  executable?: string | null;
+  format?: SlicerModelFormat | null;
+}
+
+export interface SlicerStatus {
+  settings: SlicerSettings;
+  detected: Slicer[];
+  /** Slicer models are sent to, if any */
+  active: Slicer | null;
+}
+
+export interface SlicerHandoff {
+  slicer: Slicer;
+  modelPath: string;
+}
+
+export async function getSlicerSettings(): Promise<SlicerStatus> {
+  return invoke<SlicerStatus>('get_slicer_settings');
+}
+
+/** Pick a slicer, override its executable or the export format; `null` resets a field */
+export async function setSlicer(settings: SlicerSettings): Promise<SlicerStatus> {
+  return invoke<SlicerStatus>('set_slicer', {
+    slicer: settings.slicer ?? null,
+    executable: settings.executable ?? null,
+    format: settings.format ?? null,
+  });
+}
+
+/** Export the design (defaults to the current editor code) and open it in the slicer */
+export async function sendToSlicer(
+  code?: string,
+  workingDir?: string | null
+): Promise<SlicerHandoff> {
+  return invoke<SlicerHandoff>('send_to_slicer', {
+    code,
+    workingDir: workingDir ?? undefined,
+  });
+}
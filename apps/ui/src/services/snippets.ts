@@ -0,0 +1,57 @@
+/**
+ * OpenSCAD snippet library (desktop). Curated idioms bundled with the app;
+ * inserting one checkpoints the current code and places the snippet at the
+ * editor cursor.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface Snippet {
+  id: string;
+  title: string;
+  tags: string[];
+  description: string;
+  code: string;
+}
+
+export interface SnippetSearchResult extends Snippet {
+  score: number;
+}
+
+export interface InsertedSnippet {
+  snippet: Snippet;
+  checkpointId: string;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/** Best matches first; an empty query lists every snippet */
+export async function searchSnippets(
+  query: string,
+  limit?: number
+): Promise<SnippetSearchResult[]> {
+  if (!isDesktopTauri()) return [];
+  return invoke<SnippetSearchResult[]>('search_snippets', { query, limit });
+}
+
+/** Insert a snippet at the cursor; resolves to the checkpoint taken before the insert */
+export async function insertSnippet(id: string): Promise<InsertedSnippet> {
+  return invoke<InsertedSnippet>('insert_snippet', { id });
+}
+
+/** Snippets formatted for the AI, code included */
+export function describeSnippets(query: string, results: SnippetSearchResult[]): string {
+  if (results.length === 0) return `No snippets matched "${query}".`;
+  return results
+    .map((snippet) =>
+      [
+        `## ${snippet.title} (${snippet.id})`,
+        snippet.description,
+        '```openscad',
+        snippet.code.trimEnd(),
+        '```',
+      ].join('\n')
+    )
+    .join('\n\n');
+}
@@ -0,0 +1,17 @@
+/**
+ * Linting as the user types (desktop). The backend parses the render target,
+ * with the editor's unsaved text in place of the active file, without
+ * evaluating it. Each check supersedes the one before it, which leaves the
+ * render queue and fails.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import type { Diagnostic } from '../platform/historyService';
+
+export function isSyntaxCheckAvailable(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/** Syntax errors and warnings with `code` as the active file's text */
+export async function checkSyntax(code: string): Promise<Diagnostic[]> {
+  return invoke<Diagnostic[]>('check_syntax', { code });
+}
@@ -0,0 +1,76 @@
+/**
+ * Project templates (desktop). Built-in parametric starters and templates
+ * saved from the user's projects; creating a project writes the chosen
+ * parameter values into the template's main file and opens it in a new
+ * window.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type TemplateParameterWidget =
+  | { type: 'input' }
+  | { type: 'checkbox' }
+  | { type: 'slider'; min: number; max: number; step: number | null }
+  | { type: 'dropdown'; options: { value: string; label: string }[] }
+  | { type: 'text'; max_length: number | null };
+
+export interface TemplateParameter {
+  name: string;
+  group: string;
+  description: string | null;
+  kind: 'number' | 'boolean' | 'string' | 'vector' | 'expression';
+  /** OpenSCAD literal */
+  defaultValue: string;
+  widget: TemplateParameterWidget;
+  line: number;
+}
+
+export interface ProjectTemplate {
+  id: string;
+  name: string;
+  description: string;
+  builtin: boolean;
+  mainFile: string;
+  files: string[];
+  parameters: TemplateParameter[];
+}
+
+export interface TemplateProject {
+  projectDir: string;
+  mainFile: string;
+  files: string[];
+  windowId: string;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+export async function listTemplates(): Promise<ProjectTemplate[]> {
+  if (!isDesktopTauri()) return [];
+  return invoke<ProjectTemplate[]>('list_templates');
+}
+
+/** Create a project in `dest` (a new or empty folder); `params` are OpenSCAD literals */
+export async function createProjectFromTemplate(
+  templateId: string,
+  dest: string,
+  params: Record<string, string> = {}
+): Promise<TemplateProject> {
+  return invoke<TemplateProject>('create_project_from_template', {
+    name: templateId,
+    params,
+    dest,
+  });
+}
+
+/** Save the open project folder as a user template */
+export async function saveProjectAsTemplate(
+  name: string,
+  description?: string
+): Promise<ProjectTemplate> {
+  return invoke<ProjectTemplate>('save_project_as_template', { name, description });
+}
+
+export async function deleteTemplate(id: string): Promise<void> {
+  await invoke('delete_template', { id });
+}
@@ -0,0 +1,55 @@
+/**
+ * AI tool permissions (desktop). The backend checks each AI tool call
+ * against the user's per-tool policy and read-only mode; calls that need
+ * approval raise a `tool-approval-request` that is answered here.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import { getPlatform } from '../platform';
+
+export interface ToolApprovalRequest {
+  requestId: string;
+  toolName: string;
+  arguments: unknown;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/**
+ * Check an in-app AI tool call before it runs. Resolves to `null` when it
+ * may run, or to the reason it may not (denied or declined by the user).
+ */
+export async function authorizeToolCall(toolName: string, input: unknown): Promise<string | null> {
+  if (!isDesktopTauri()) return null;
+  try {
+    await invoke('authorize_tool_call', { toolName, arguments: input ?? {} });
+    return null;
+  } catch (error) {
+    return error instanceof Error ? error.message : String(error);
+  }
+}
+
+function describeArguments(args: unknown): string {
+  if (!args || typeof args !== 'object') return '';
+  const entries = Object.entries(args as Record<string, unknown>).filter(
+    ([, value]) => value !== undefined && value !== null
+  );
+  return entries
+    .map(([key, value]) => {
+      const text = typeof value === 'string' ? value : JSON.stringify(value);
+      return `${key}: ${text.length > 200 ? `${text.slice(0, 200)}…` : text}`;
+    })
+    .join('\n');
+}
+
+export async function answerToolApprovalRequest(request: ToolApprovalRequest): Promise<void> {
+  const details = describeArguments(request.arguments);
+  const approved = await getPlatform()
+    .confirm(
+      `The AI wants to run \`${request.toolName}\`.${details ? `\n\n${details}` : ''}`,
+      { title: 'Allow AI tool call?', kind: 'warning', okLabel: 'Allow', cancelLabel: 'Deny' }
+    )
+    .catch(() => false);
+  await invoke('approve_tool_call', { requestId: request.requestId, approved });
+}
@@ -0,0 +1,54 @@
+/**
+ * AI tool timeouts. Defaults live in `src-tauri/tool-timeouts.json`, shared
+ * with the backend's MCP server; on desktop the user can override them per
+ * tool, for MCP clients and the in-app agent alike.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import { eventBus } from '../platform/eventBus';
+import toolTimeoutDefaults from '../../src-tauri/tool-timeouts.json';
+
+export interface ToolTimeout {
+  toolName: string;
+  timeoutSecs: number;
+  defaultSecs: number;
+  isCustom: boolean;
+}
+
+function isDesktopTauri(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/** Default timeout of a tool in seconds */
+export function defaultToolTimeoutSecs(toolName: string): number {
+  const tools: Record<string, number> = toolTimeoutDefaults.tools;
+  return tools[toolName] ?? toolTimeoutDefaults.defaultSecs;
+}
+
+export async function getToolTimeouts(): Promise<ToolTimeout[]> {
+  return invoke<ToolTimeout[]>('get_tool_timeouts');
+}
+
+/** Override a tool's timeout in seconds, or restore its default with `null` */
+export async function setToolTimeout(toolName: string, timeoutSecs: number | null): Promise<void> {
+  await invoke('set_tool_timeout', { toolName, timeoutSecs });
+  eventBus.emit('settings:changed');
+}
+
+/**
+ * The user's timeout for each tool that has one, in seconds. Empty on the
+ * web or when the settings cannot be read, leaving the defaults.
+ */
+export async function loadToolTimeoutOverrides(): Promise<Record<string, number>> {
+  if (!isDesktopTauri()) return {};
+  try {
+    const timeouts = await getToolTimeouts();
+    return Object.fromEntries(
+      timeouts
+        .filter((timeout) => timeout.isCustom)
+        .map((timeout) => [timeout.toolName, timeout.timeoutSecs])
+    );
+  } catch (error) {
+    console.warn('[toolTimeouts] Using the default tool timeouts:', error);
+    return {};
+  }
+}
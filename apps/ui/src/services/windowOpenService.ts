@@ -6,6 +6,7 @@ import { DEFAULT_TAB_NAME, createWorkspaceTab } from '../stores/workspaceFactori
 import { getWorkspaceState, workspaceStore } from '../stores/workspaceStore';
 import type { WorkspaceStoreState } from '../stores/workspaceTypes';
 import { addRecentFile, addRecentFolder } from '../utils/recentFiles';
+import { addRecentProject, stageRestoredSession, type SessionSnapshot } from './session';
 import { findEmptyFolders, loadWorkspaceFolder } from '../utils/workspaceFolder';
 
 type FileOpenPlatform = Pick<
@@ -165,6 +166,9 @@ export async function openWorkspaceFolderInWindow(
 
   if (options.trackRecent ?? true) {
     addRecentFolder(dirPath);
+    void addRecentProject(dirPath, 'folder').catch((error) => {
+      console.warn('[windowOpenService] Failed to record recent folder:', error);
+    });
   }
   if (options.requestRender ?? true) {
     requestRender('file_open', { immediate: true });
@@ -222,6 +226,9 @@ export async function openFileInWindow(
 
   if ((options.trackRecent ?? true) && result.path) {
     addRecentFile(result.path);
+    void addRecentProject(result.path, 'file').catch((error) => {
+      console.warn('[windowOpenService] Failed to record recent file:', error);
+    });
   }
   if (options.requestRender ?? true) {
     requestRender('file_open', { immediate: true });
@@ -235,3 +242,42 @@ export async function openFileInWindow(
     reusedExistingTab: false,
   };
 }
+
+/**
+ * After a restored session's folder (or first file) has been opened, reopen
+ * the other files it had open inside that project, in their saved order, and
+ * stage cursors, camera and chat draft. Files outside the project are skipped.
+ */
+export function reopenSessionTabs(snapshot: SessionSnapshot): Map<string, string> {
+  const { projectRoot, files } = getProjectStore().getState();
+  const workspace = workspaceStore.getState();
+  const tabIdsByPath = new Map<string, string>();
+  for (const tab of getWorkspaceState().tabs) {
+    if (tab.filePath) tabIdsByPath.set(tab.filePath, tab.id);
+  }
+
+  for (const file of snapshot.openFiles) {
+    if (tabIdsByPath.has(file.path) || !projectRoot) continue;
+    if (!file.path.startsWith(`${projectRoot}/`)) continue;
+    const projectPath = file.path.slice(projectRoot.length + 1);
+    if (!(projectPath in files)) continue;
+    const tabId = workspace.createTab({
+      filePath: file.path,
+      name: projectPath,
+      projectPath,
+      activate: false,
+    });
+    tabIdsByPath.set(file.path, tabId);
+  }
+
+  const savedOrder = snapshot.openFiles.flatMap((file) => tabIdsByPath.get(file.path) ?? []);
+  const others = getWorkspaceState()
+    .tabs.map((tab) => tab.id)
+    .filter((id) => !savedOrder.includes(id));
+  workspace.reorderTabs([...savedOrder, ...others]);
+  const activeTabId = snapshot.activeFile ? tabIdsByPath.get(snapshot.activeFile) : undefined;
+  if (activeTabId) workspace.setActiveTab(activeTabId);
+
+  stageRestoredSession(snapshot, tabIdsByPath);
+  return tabIdsByPath;
+}
@@ -0,0 +1,74 @@
+/** @jest-environment jsdom */
+
+import { jest } from '@jest/globals';
+
+const invoke = jest.fn<(command: string, args?: Record<string, unknown>) => Promise<unknown>>();
+
+describe('apiKeyStore on desktop', () => {
+  beforeEach(() => {
+    jest.resetModules();
+    invoke.mockReset();
+    localStorage.clear();
+    jest.unstable_mockModule('@tauri-apps/api/core', () => ({
+      invoke,
+    }));
+  });
+
+  it('moves localStorage keys to the backend and reads them from there', async () => {
+    const backend = new Map<string, string>([['openai', 'sk-backend']]);
+    invoke.mockImplementation(async (command, args) => {
+      if (command === 'set_api_key') backend.set(args?.provider as string, args?.key as string);
+      if (command === 'delete_api_key') backend.delete(args?.provider as string);
+      if (command === 'get_api_keys') return Object.fromEntries(backend);
+      return undefined;
+    });
+
+    const store = await import('../apiKeyStore');
+    store.storeApiKey('anthropic', 'sk-ant-local');
+    expect(localStorage.getItem('openscad_studio_anthropic_api_key')).toMatch(/^obf1:/);
+
+    await store.loadDesktopApiKeys();
+    expect(localStorage.getItem('openscad_studio_anthropic_api_key')).toBeNull();
+    expect(backend.get('anthropic')).toBe('sk-ant-local');
+    expect(store.getApiKey('anthropic')).toBe('sk-ant-local');
+    expect(store.getApiKey('openai')).toBe('sk-backend');
+
+    store.clearApiKey('openai');
+    expect(store.getApiKey('openai')).toBeNull();
+    expect(invoke).toHaveBeenCalledWith('delete_api_key', { provider: 'openai' });
+    expect(localStorage.getItem('openscad_studio_openai_api_key')).toBeNull();
+  });
+
+  it('keeps serving localStorage keys until the backend keys have loaded', async () => {
+    const backend = new Map<string, string>();
+    let finishLoading: (() => void) | undefined;
+    invoke.mockImplementation(async (command, args) => {
+      if (command === 'set_api_key') backend.set(args?.provider as string, args?.key as string);
+      if (command === 'delete_api_key') backend.delete(args?.provider as string);
+      if (command === 'get_api_keys') {
+        const snapshot = Object.fromEntries(backend);
+        await new Promise<void>((resolve) => {
+          finishLoading = resolve;
+        });
+        return snapshot;
+      }
+      return undefined;
+    });
+
+    const store = await import('../apiKeyStore');
+    store.storeApiKey('anthropic', 'sk-ant-local');
+
+    const loading = store.loadDesktopApiKeys();
+    await new Promise((resolve) => setTimeout(resolve, 0));
+    expect(store.getApiKey('anthropic')).toBe('sk-ant-local');
+    // Stored after the migration started, so it isn't in the loaded snapshot
+    store.storeApiKey('openai', 'sk-openai-late');
+
+    finishLoading?.();
+    await loading;
+    expect(store.getApiKey('anthropic')).toBe('sk-ant-local');
+    expect(store.getApiKey('openai')).toBe('sk-openai-late');
+    expect(backend.get('openai')).toBe('sk-openai-late');
+    expect(localStorage.getItem('openscad_studio_openai_api_key')).toBeNull();
+  });
+});
@@ -1,5 +1,15 @@
 import { useSyncExternalStore } from 'react';
-import { DEFAULT_MODEL_IDS, getPreferredDefaultModel } from '../utils/aiModels';
+import {
+  DEFAULT_MODEL_IDS,
+  PROVIDER_ORDER_WITH_CUSTOM,
+  getPreferredDefaultModel,
+} from '../utils/aiModels';
+import { deleteApiKey, loadApiKeys, saveApiKey } from '../services/keyStorage';
+import {
+  getCloudProviderStatuses,
+  isCloudAiProvider,
+  type CloudProviderStatus,
+} from '../services/cloudAi';
 
 // ============================================================================
 // Constants
@@ -8,6 +18,11 @@ import { DEFAULT_MODEL_IDS, getPreferredDefaultModel } from '../utils/aiModels';
 const STORAGE_KEYS = {
   anthropic: 'openscad_studio_anthropic_api_key',
   openai: 'openscad_studio_openai_api_key',
+  azureOpenAiApiKey: 'openscad_studio_azure_openai_api_key',
+  azureOpenAiEndpoint: 'openscad_studio_azure_openai_endpoint',
+  azureOpenAiDeployments: 'openscad_studio_azure_openai_deployments',
+  gemini: 'openscad_studio_gemini_api_key',
+  openrouter: 'openscad_studio_openrouter_api_key',
   openaiCompatibleApiKey: 'openscad_studio_openai_compatible_api_key',
   openaiCompatibleBaseUrl: 'openscad_studio_openai_compatible_base_url',
   openaiCompatibleModel: 'openscad_studio_openai_compatible_model',
@@ -15,7 +30,15 @@ const STORAGE_KEYS = {
   modelSelection: 'openscad_studio_ai_model_selection',
 } as const;
 
-export type AiProvider = 'anthropic' | 'openai' | 'openai-compatible';
+export type AiProvider =
+  | 'anthropic'
+  | 'bedrock'
+  | 'vertex'
+  | 'openai'
+  | 'azure-openai'
+  | 'gemini'
+  | 'openrouter'
+  | 'openai-compatible';
 
 export interface AiModelSelection {
   provider: AiProvider;
@@ -28,21 +51,38 @@ export interface OpenAiCompatibleConfig {
   apiKey: string | null;
 }
 
+export interface AzureOpenAiConfig {
+  /** Resource endpoint, e.g. https://my-resource.openai.azure.com */
+  endpoint: string;
+  /** Deployment names, which Azure uses in place of model ids */
+  deployments: string[];
+  apiKey: string | null;
+}
+
 interface ApiKeySnapshot {
   availableProviders: AiProvider[];
   hasAnyKey: boolean;
 }
 
 export const DEFAULT_OPENAI_COMPATIBLE_BASE_URL = 'http://127.0.0.1:11434/v1';
+export const OPENROUTER_BASE_URL = 'https://openrouter.ai/api/v1';
+export const GEMINI_API_URL = 'https://generativelanguage.googleapis.com/v1beta';
+/** Gemini's OpenAI-compatible chat endpoint */
+export const GEMINI_BASE_URL = `${GEMINI_API_URL}/openai`;
 
-const API_KEY_STORAGE_KEYS: Record<AiProvider, string> = {
+/** Bedrock and Vertex use cloud credentials found by the backend, not API keys */
+const API_KEY_STORAGE_KEYS: Partial<Record<AiProvider, string>> = {
   anthropic: STORAGE_KEYS.anthropic,
   openai: STORAGE_KEYS.openai,
+  'azure-openai': STORAGE_KEYS.azureOpenAiApiKey,
+  gemini: STORAGE_KEYS.gemini,
+  openrouter: STORAGE_KEYS.openrouter,
   'openai-compatible': STORAGE_KEYS.openaiCompatibleApiKey,
 };
 
 // ============================================================================
-// API Key Storage (localStorage-based, obfuscated)
+// API Key Storage (localStorage-based and obfuscated on the web; the backend's
+// secrets file or OS keychain on desktop)
 // ============================================================================
 
 const OBF_PREFIX = 'obf1:';
@@ -60,25 +100,91 @@ function deobfuscate(stored: string): string | null {
   }
 }
 
+/** Keys held by the desktop backend; null on the web and until loaded */
+let desktopKeys: Map<AiProvider, string> | null = null;
+
+function writeApiKey(provider: AiProvider, key: string | null): void {
+  const storageKey = API_KEY_STORAGE_KEYS[provider];
+  if (!storageKey) return;
+  if (!desktopKeys) {
+    if (key) {
+      localStorage.setItem(storageKey, obfuscate(key));
+    } else {
+      localStorage.removeItem(storageKey);
+    }
+    return;
+  }
+
+  if (key) {
+    desktopKeys.set(provider, key);
+  } else {
+    desktopKeys.delete(provider);
+  }
+  const write = key ? saveApiKey(provider, key) : deleteApiKey(provider);
+  write.catch((error) => {
+    console.error(`[apiKeyStore] Failed to update the ${provider} API key:`, error);
+  });
+}
+
+/**
+ * Switch to the desktop backend's keys. Keys still in localStorage are moved
+ * to the backend first, and keep being served from localStorage until the
+ * backend's keys have loaded.
+ */
+export async function loadDesktopApiKeys(): Promise<void> {
+  const storageKeys = Object.entries(API_KEY_STORAGE_KEYS) as [AiProvider, string][];
+  const migrated = new Set<AiProvider>();
+  for (const [provider] of storageKeys) {
+    const key = readLocalApiKey(provider);
+    if (key === null) continue;
+    await saveApiKey(provider, key);
+    migrated.add(provider);
+  }
+
+  const keys = await loadApiKeys();
+  desktopKeys = new Map(
+    Object.entries(keys).filter((entry): entry is [AiProvider, string] => isAiProvider(entry[0]))
+  );
+
+  // Carry over keys stored or cleared while loading, then drop the local copies
+  for (const [provider, storageKey] of storageKeys) {
+    const key = readLocalApiKey(provider);
+    if (key !== null) {
+      if (desktopKeys.get(provider) !== key) writeApiKey(provider, key);
+      localStorage.removeItem(storageKey);
+    } else if (migrated.has(provider)) {
+      writeApiKey(provider, null);
+    }
+  }
+  notify();
+}
+
 export function storeApiKey(provider: AiProvider, key: string): void {
-  localStorage.setItem(API_KEY_STORAGE_KEYS[provider], obfuscate(key));
+  writeApiKey(provider, key);
   notify();
 }
 
 export function clearApiKey(provider: AiProvider): void {
-  localStorage.removeItem(API_KEY_STORAGE_KEYS[provider]);
+  writeApiKey(provider, null);
   notify();
 }
 
 export function getApiKey(provider: AiProvider): string | null {
-  const stored = localStorage.getItem(API_KEY_STORAGE_KEYS[provider]);
+  if (desktopKeys) return desktopKeys.get(provider) ?? null;
+  return readLocalApiKey(provider);
+}
+
+function readLocalApiKey(provider: AiProvider): string | null {
+  const storageKey = API_KEY_STORAGE_KEYS[provider];
+  if (!storageKey) return null;
+  const stored = localStorage.getItem(storageKey);
   if (stored === null) return null;
 
   const decoded = deobfuscate(stored);
   if (decoded !== null) return decoded;
 
   // Legacy plaintext value — re-encode so storage is clean going forward
-  localStorage.setItem(API_KEY_STORAGE_KEYS[provider], obfuscate(stored));
+  localStorage.setItem(storageKey, obfuscate(stored));
   return stored;
 }
 
@@ -123,11 +229,7 @@ export function storeOpenAiCompatibleConfig(config: OpenAiCompatibleConfig): voi
     localStorage.removeItem(STORAGE_KEYS.openaiCompatibleModel);
   }
 
-  if (config.apiKey?.trim()) {
-    localStorage.setItem(STORAGE_KEYS.openaiCompatibleApiKey, obfuscate(config.apiKey.trim()));
-  } else {
-    localStorage.removeItem(STORAGE_KEYS.openaiCompatibleApiKey);
-  }
+  writeApiKey('openai-compatible', config.apiKey?.trim() || null);
 
   notify();
 }
@@ -135,7 +237,7 @@ export function storeOpenAiCompatibleConfig(config: OpenAiCompatibleConfig): voi
 export function clearOpenAiCompatibleConfig(): void {
   localStorage.removeItem(STORAGE_KEYS.openaiCompatibleBaseUrl);
   localStorage.removeItem(STORAGE_KEYS.openaiCompatibleModel);
-  localStorage.removeItem(STORAGE_KEYS.openaiCompatibleApiKey);
+  writeApiKey('openai-compatible', null);
   clearStoredModelSelectionForProvider('openai-compatible');
   notify();
 }
@@ -147,19 +249,93 @@ export function hasOpenAiCompatibleConfig(): boolean {
   return storedBaseUrl.length > 0;
 }
 
+/** Resource endpoint without a trailing slash or `/openai/...` API path */
+export function normalizeAzureOpenAiEndpoint(endpoint: string): string {
+  return endpoint
+    .trim()
+    .replace(/\/+$/, '')
+    .replace(/\/openai(\/v1)?$/, '');
+}
+
+export function getAzureOpenAiConfig(): AzureOpenAiConfig {
+  return {
+    endpoint: normalizeAzureOpenAiEndpoint(
+      localStorage.getItem(STORAGE_KEYS.azureOpenAiEndpoint) ?? ''
+    ),
+    deployments: parseDeployments(localStorage.getItem(STORAGE_KEYS.azureOpenAiDeployments) ?? ''),
+    apiKey: getApiKey('azure-openai'),
+  };
+}
+
+/** Split a comma- or newline-separated list of deployment names */
+export function parseDeployments(value: string): string[] {
+  const names = value
+    .split(/[,\n]/)
+    .map((name) => name.trim())
+    .filter(Boolean);
+  return [...new Set(names)];
+}
+
+export function storeAzureOpenAiConfig(config: AzureOpenAiConfig): void {
+  const endpoint = normalizeAzureOpenAiEndpoint(config.endpoint);
+  if (endpoint) {
+    localStorage.setItem(STORAGE_KEYS.azureOpenAiEndpoint, endpoint);
+  } else {
+    localStorage.removeItem(STORAGE_KEYS.azureOpenAiEndpoint);
+  }
+
+  const deployments = parseDeployments(config.deployments.join(','));
+  if (deployments.length > 0) {
+    localStorage.setItem(STORAGE_KEYS.azureOpenAiDeployments, deployments.join(','));
+  } else {
+    localStorage.removeItem(STORAGE_KEYS.azureOpenAiDeployments);
+  }
+
+  writeApiKey('azure-openai', config.apiKey?.trim() || null);
+
+  notify();
+}
+
+export function clearAzureOpenAiConfig(): void {
+  localStorage.removeItem(STORAGE_KEYS.azureOpenAiEndpoint);
+  localStorage.removeItem(STORAGE_KEYS.azureOpenAiDeployments);
+  writeApiKey('azure-openai', null);
+  clearStoredModelSelectionForProvider('azure-openai');
+  notify();
+}
+
+export function hasAzureOpenAiConfig(): boolean {
+  const config = getAzureOpenAiConfig();
+  return Boolean(config.endpoint && config.apiKey);
+}
+
+/** Bedrock and Vertex status from the desktop backend; empty on the web */
+let cloudProviderStatuses: CloudProviderStatus[] = [];
+
+/** Check which cloud platforms have credentials, e.g. after changing their settings */
+export async function loadCloudProviders(): Promise<CloudProviderStatus[]> {
+  cloudProviderStatuses = await getCloudProviderStatuses();
+  notify();
+  return cloudProviderStatuses;
+}
+
 export function isProviderConfigured(provider: AiProvider): boolean {
+  if (isCloudAiProvider(provider)) {
+    return cloudProviderStatuses.some(
+      (status) => status.provider === provider && status.error === null
+    );
+  }
   if (provider === 'openai-compatible') {
     return hasOpenAiCompatibleConfig();
   }
+  if (provider === 'azure-openai') {
+    return hasAzureOpenAiConfig();
+  }
   return hasApiKeyForProvider(provider);
 }
 
 export function getAvailableProviders(): AiProvider[] {
-  const providers: AiProvider[] = [];
-  if (isProviderConfigured('anthropic')) providers.push('anthropic');
-  if (isProviderConfigured('openai')) providers.push('openai');
-  if (isProviderConfigured('openai-compatible')) providers.push('openai-compatible');
-  return providers;
+  return PROVIDER_ORDER_WITH_CUSTOM.filter(isProviderConfigured);
 }
 
 // ============================================================================
@@ -181,9 +357,24 @@ export function getPreferredDefaultModelSelection(providers: readonly string[]):
   if (providers.includes('anthropic')) {
     return { provider: 'anthropic', modelId: getPreferredDefaultModel(['anthropic']) };
   }
+  for (const provider of ['bedrock', 'vertex'] as const) {
+    if (providers.includes(provider)) {
+      return { provider, modelId: DEFAULT_MODEL_IDS[provider] };
+    }
+  }
   if (providers.includes('openai')) {
     return { provider: 'openai', modelId: getPreferredDefaultModel(['openai']) };
   }
+  if (providers.includes('azure-openai')) {
+    const [deployment] = getAzureOpenAiConfig().deployments;
+    if (deployment) return { provider: 'azure-openai', modelId: deployment };
+  }
+  if (providers.includes('gemini')) {
+    return { provider: 'gemini', modelId: DEFAULT_MODEL_IDS.gemini };
+  }
+  if (providers.includes('openrouter')) {
+    return { provider: 'openrouter', modelId: DEFAULT_MODEL_IDS.openrouter };
+  }
   if (providers.includes('openai-compatible')) {
     const config = getOpenAiCompatibleConfig();
     return {
@@ -194,8 +385,8 @@ export function getPreferredDefaultModelSelection(providers: readonly string[]):
   return { provider: 'anthropic', modelId: getPreferredDefaultModel(['anthropic']) };
 }
 
-function isAiProvider(value: unknown): value is AiProvider {
-  return value === 'anthropic' || value === 'openai' || value === 'openai-compatible';
+export function isAiProvider(value: unknown): value is AiProvider {
+  return PROVIDER_ORDER_WITH_CUSTOM.includes(value as AiProvider);
 }
 
 function parseStoredModelSelection(raw: string | null): AiModelSelection | null {
@@ -226,6 +417,9 @@ function getProviderFromKnownModelPrefix(modelId: string): AiProvider | null {
   ) {
     return 'openai';
   }
+  if (modelId.startsWith('gemini')) {
+    return 'gemini';
+  }
   return null;
 }
 
@@ -325,6 +325,16 @@ export function applyWorkspacePreset(preset: WorkspacePreset): void {
   saveLayout();
 }
 
+/** Close the panel if it is open, otherwise open it */
+export function togglePanel(panelId: string, component: string, title: string): void {
+  const existing = dockviewApi?.getPanel(panelId);
+  if (existing) {
+    existing.api.close();
+    return;
+  }
+  openPanel(panelId, component, title);
+}
+
 export function openPanel(panelId: string, component: string, title: string): void {
   if (!dockviewApi) return;
 
@@ -1,6 +1,9 @@
 import type { ModelSelectionSurface } from '../analytics/runtime';
 
 export type VisionSupport = 'yes' | 'no' | 'unknown';
+export type ToolSupport = 'yes' | 'no' | 'unknown';
+/** How much the model reasons before answering; `off` sends no reasoning options */
+export type ReasoningEffort = 'off' | 'low' | 'medium' | 'high';
 export type AssistantMessageState = 'complete' | 'cancelled' | 'error';
 export type ToolCallState = 'pending' | 'completed' | 'error' | 'denied';
 
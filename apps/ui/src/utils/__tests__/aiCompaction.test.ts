@@ -0,0 +1,83 @@
+import type { ModelMessage } from 'ai';
+import {
+  applyCompactionSummary,
+  estimateTokens,
+  getCompactionBudget,
+  splitForCompaction,
+  transcriptForSummary,
+} from '../aiCompaction';
+
+const conversation: ModelMessage[] = [
+  { role: 'user', content: 'Make a box' },
+  {
+    role: 'assistant',
+    content: [
+      { type: 'text', text: 'Writing it.' },
+      { type: 'tool-call', toolCallId: 'call-1', toolName: 'apply_edit', input: { code: 'x' } },
+    ],
+  },
+  {
+    role: 'tool',
+    content: [
+      {
+        type: 'tool-result',
+        toolCallId: 'call-1',
+        toolName: 'apply_edit',
+        output: { type: 'text', value: 'y'.repeat(5000) },
+      },
+    ],
+  },
+  { role: 'user', content: 'Add a lid' },
+  { role: 'assistant', content: 'Added.' },
+  { role: 'user', content: 'Make it taller' },
+];
+
+describe('aiCompaction', () => {
+  it('leaves conversations that fit alone', () => {
+    expect(splitForCompaction(conversation, estimateTokens(conversation))).toBeNull();
+  });
+
+  it('keeps the last requests and their tool loops together', () => {
+    const split = splitForCompaction(conversation, 100);
+
+    expect(split?.older).toEqual(conversation.slice(0, 3));
+    expect(split?.recent).toEqual(conversation.slice(3));
+  });
+
+  it('summarizes all but the last request of short conversations', () => {
+    const long: ModelMessage[] = [
+      { role: 'user', content: 'x'.repeat(4000) },
+      { role: 'assistant', content: 'Done.' },
+    ];
+
+    expect(splitForCompaction(long, 10)).toBeNull();
+    expect(splitForCompaction([...long, { role: 'user', content: 'Again' }], 10)).toEqual({
+      older: long,
+      recent: [{ role: 'user', content: 'Again' }],
+    });
+  });
+
+  it('clips tool traffic in the transcript for the summary model', () => {
+    const transcript = transcriptForSummary(conversation.slice(0, 3));
+
+    expect(transcript).toContain('user: Make a box');
+    expect(transcript).toContain('[called apply_edit {"code":"x"}]');
+    expect(transcript).toContain(`[apply_edit returned ${'y'.repeat(1500)}…]`);
+    expect(transcript).not.toContain('y'.repeat(1501));
+  });
+
+  it('prepends the summary to the first kept request', () => {
+    expect(applyCompactionSummary('A box was made.', conversation.slice(3))[0]).toEqual({
+      role: 'user',
+      content: [
+        { type: 'text', text: expect.stringContaining('A box was made.') },
+        { type: 'text', text: 'Add a lid' },
+      ],
+    });
+  });
+
+  it('leaves room for the reply in the budget', () => {
+    expect(getCompactionBudget(200000, 32000)).toBe(118000);
+    expect(getCompactionBudget(32000)).toBe(24000);
+  });
+});
@@ -236,6 +236,7 @@ describe('aiMessages', () => {
   it('reports known vision support for configured model families', () => {
     expect(getVisionSupportForModelId('claude-sonnet-4-5')).toBe('yes');
     expect(getVisionSupportForModelId('gpt-4o')).toBe('yes');
+    expect(getVisionSupportForModelId('us.anthropic.claude-sonnet-4-5-20250929-v1:0')).toBe('yes');
     expect(getVisionSupportForModelId('text-only-model')).toBe('no');
     expect(getVisionSupportForModelId('mystery-model')).toBe('unknown');
   });
@@ -0,0 +1,107 @@
+import type { TextStreamPart, ToolSet } from 'ai';
+import { createAgentTranscriptState, reduceTranscriptChunk } from '../aiTranscript';
+
+type StreamChunk = TextStreamPart<ToolSet>;
+
+const finishStep = {
+  type: 'finish-step',
+  response: {} as never,
+  usage: {} as never,
+  finishReason: 'tool-calls',
+  rawFinishReason: 'tool-calls',
+  providerMetadata: undefined,
+} as StreamChunk;
+
+function applyChunks(chunks: StreamChunk[]) {
+  let state = createAgentTranscriptState([{ role: 'user', content: 'Make a box' }]);
+  const persisted: StreamChunk['type'][] = [];
+
+  for (const chunk of chunks) {
+    const next = reduceTranscriptChunk(state, chunk);
+    state = next.state;
+    if (next.shouldPersist) persisted.push(chunk.type);
+  }
+
+  return { state, persisted };
+}
+
+describe('aiTranscript', () => {
+  it('records finished steps as model messages', () => {
+    const { state, persisted } = applyChunks([
+      { type: 'text-delta', id: 'text-1', text: 'Checking the code.' },
+      {
+        type: 'tool-call',
+        toolCallId: 'tool-1',
+        toolName: 'get_current_code',
+        input: {},
+      } as StreamChunk,
+      {
+        type: 'tool-result',
+        toolCallId: 'tool-1',
+        toolName: 'get_current_code',
+        input: {},
+        output: 'cube(10);',
+      } as StreamChunk,
+      finishStep,
+    ]);
+
+    expect(persisted).toEqual(['tool-result', 'finish-step']);
+    expect(state.pendingToolCalls).toEqual([]);
+    expect(state.apiMessages).toEqual([
+      { role: 'user', content: 'Make a box' },
+      {
+        role: 'assistant',
+        content: [
+          { type: 'text', text: 'Checking the code.' },
+          { type: 'tool-call', toolCallId: 'tool-1', toolName: 'get_current_code', input: {} },
+        ],
+      },
+      {
+        role: 'tool',
+        content: [
+          {
+            type: 'tool-result',
+            toolCallId: 'tool-1',
+            toolName: 'get_current_code',
+            output: { type: 'text', value: 'cube(10);' },
+          },
+        ],
+      },
+    ]);
+  });
+
+  it('keeps the tool calls of an unfinished step pending', () => {
+    const { state } = applyChunks([
+      {
+        type: 'tool-call',
+        toolCallId: 'tool-1',
+        toolName: 'apply_edit',
+        input: { old_string: 'a', new_string: 'b' },
+      } as StreamChunk,
+      {
+        type: 'tool-error',
+        toolCallId: 'tool-1',
+        toolName: 'apply_edit',
+        input: {},
+        error: new Error('No match'),
+      } as StreamChunk,
+      {
+        type: 'tool-call',
+        toolCallId: 'tool-2',
+        toolName: 'get_preview_screenshot',
+        input: {},
+      } as StreamChunk,
+    ]);
+
+    expect(state.apiMessages).toHaveLength(1);
+    expect(state.pendingToolCalls.map((call) => call.toolCallId)).toEqual(['tool-1', 'tool-2']);
+    expect(state.pendingToolResults).toEqual([
+      {
+        type: 'tool-result',
+        toolCallId: 'tool-1',
+        toolName: 'apply_edit',
+        output: { type: 'error-text', value: 'No match' },
+      },
+    ]);
+  });
+});
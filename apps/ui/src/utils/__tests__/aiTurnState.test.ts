@@ -3,6 +3,7 @@ import {
   attachmentIsReferencedByMessages,
   createActiveTurnState,
   deriveCurrentToolCalls,
+  deriveStreamingReasoning,
   deriveStreamingResponse,
   finalizeActiveTurn,
   finalizeConversationTurn,
@@ -327,6 +328,37 @@ describe('aiTurnState', () => {
     ]);
   });
 
+  it('exposes partial tool arguments while the tool input streams', () => {
+    const { state } = applyChunks([
+      { type: 'tool-input-start', id: 'tool-1', toolName: 'apply_edit' },
+      { type: 'tool-input-delta', id: 'tool-1', delta: '{"old_string": "cube(1);", ' },
+      { type: 'tool-input-delta', id: 'tool-1', delta: '"new_string": "cube([1, ' },
+    ]);
+
+    expect(deriveCurrentToolCalls(state)).toEqual([
+      expect.objectContaining({
+        name: 'apply_edit',
+        state: 'pending',
+        args: { old_string: 'cube(1);', new_string: 'cube([1, ' },
+      }),
+    ]);
+  });
+
+  it('keeps the latest reasoning block out of the persisted messages', () => {
+    const { state, warnings } = applyChunks([
+      { type: 'reasoning-start', id: 'reasoning-1' },
+      { type: 'reasoning-delta', id: 'reasoning-1', text: 'Check the wall ' },
+      { type: 'reasoning-delta', id: 'reasoning-1', text: 'thickness' },
+      { type: 'reasoning-end', id: 'reasoning-1' },
+      { type: 'reasoning-start', id: 'reasoning-2' },
+      { type: 'reasoning-delta', id: 'reasoning-2', text: 'Now the lid' },
+    ] as StreamChunk[]);
+
+    expect(warnings).toEqual([]);
+    expect(deriveStreamingReasoning(state)).toBe('Now the lid');
+    expect(state.persistedMessages).toEqual([]);
+  });
+
   it('ignores duplicate or out-of-order text chunks without corrupting state', () => {
     const { state, warnings } = applyChunks([
       { type: 'text-delta', id: 'missing', text: 'ignored' },
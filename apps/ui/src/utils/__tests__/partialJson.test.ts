@@ -0,0 +1,25 @@
+import { parsePartialJsonObject } from '../partialJson';
+
+describe('parsePartialJsonObject', () => {
+  it('reads every prefix of a streamed tool input', () => {
+    const input = JSON.stringify({
+      file_path: 'lib/box.scad',
+      old_string: 'cube(1);',
+      new_string: 'cube([1, 2]);\n// "wall" \\ done',
+      flags: [1, true, null],
+    });
+
+    for (let length = 1; length <= input.length; length++) {
+      expect(parsePartialJsonObject(input.slice(0, length))).toBeDefined();
+    }
+    expect(parsePartialJsonObject(input)).toEqual(JSON.parse(input));
+  });
+
+  it('keeps partial string values and drops dangling keys and literals', () => {
+    expect(parsePartialJsonObject('{"new_string": "cube(1')).toEqual({ new_string: 'cube(1' });
+    expect(parsePartialJsonObject('{"a": "x\\')).toEqual({ a: 'x' });
+    expect(parsePartialJsonObject('{"a": 1, "old_str')).toEqual({ a: 1 });
+    expect(parsePartialJsonObject('{"a": tr')).toEqual({});
+    expect(parsePartialJsonObject('[1, 2')).toBeUndefined();
+  });
+});
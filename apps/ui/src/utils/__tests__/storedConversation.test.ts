@@ -0,0 +1,96 @@
+import type { Conversation } from '../../types/aiChat';
+import { fromStoredConversation, toStoredConversation } from '../storedConversation';
+
+const conversation: Conversation = {
+  id: 'conv-1',
+  title: 'Rounded box',
+  timestamp: 1_700_000_000_000,
+  messages: [
+    {
+      id: 'm1',
+      type: 'user',
+      timestamp: 1,
+      checkpointId: 'cp-1',
+      parts: [
+        { type: 'text', text: 'Round the corners' },
+        {
+          type: 'image',
+          attachmentId: 'a1',
+          filename: 'sketch.png',
+          mimeType: 'image/png',
+          width: 640,
+          height: 480,
+        },
+      ],
+    },
+    {
+      id: 'm2',
+      type: 'tool-call',
+      timestamp: 2,
+      toolCallId: 't1',
+      toolName: 'apply_edit',
+      args: { old_string: 'cube(10);', new_string: 'cube(8);' },
+      state: 'completed',
+      result: { status: 'success', __checkpointId: 'cp-2' },
+    },
+    {
+      id: 'm3',
+      type: 'tool-call',
+      timestamp: 3,
+      toolCallId: 't2',
+      toolName: 'render',
+      state: 'pending',
+    },
+    {
+      id: 'm4',
+      type: 'assistant',
+      timestamp: 4,
+      turnId: 'turn-1',
+      content: 'Done.',
+      state: 'cancelled',
+    },
+  ],
+};
+
+describe('storedConversation', () => {
+  it('stores tool calls and their results as typed blocks', () => {
+    const stored = toStoredConversation(conversation);
+
+    expect(stored.messages.map(({ role }) => role)).toEqual([
+      'user',
+      'assistant',
+      'assistant',
+      'assistant',
+    ]);
+    expect(stored.messages[0]).toMatchObject({ checkpointId: 'cp-1' });
+    expect(stored.messages[1].content).toEqual([
+      {
+        type: 'tool-call',
+        toolCallId: 't1',
+        toolName: 'apply_edit',
+        args: { old_string: 'cube(10);', new_string: 'cube(8);' },
+        state: 'completed',
+      },
+      {
+        type: 'tool-result',
+        toolCallId: 't1',
+        result: { status: 'success', __checkpointId: 'cp-2' },
+        errorText: undefined,
+      },
+    ]);
+    expect(stored.messages[2].content).toHaveLength(1);
+    expect(stored.messages[3]).toMatchObject({
+      turnId: 'turn-1',
+      state: 'cancelled',
+      content: [{ type: 'text', text: 'Done.' }],
+    });
+  });
+
+  it('restores the chat messages it stored', () => {
+    const restored = fromStoredConversation(
+      JSON.parse(JSON.stringify(toStoredConversation(conversation)))
+    );
+
+    expect(restored).toEqual(conversation);
+  });
+});
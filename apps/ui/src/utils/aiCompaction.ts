@@ -0,0 +1,129 @@
+import type { ModelMessage } from 'ai';
+
+/** Rough cost of an attached image, which is not sent as text */
+const IMAGE_TOKENS = 1600;
+/** Longest tool input or output kept in the text handed to the summary model */
+const SUMMARY_PART_CHARS = 1500;
+/** User requests kept verbatim, with everything that followed them */
+export const KEEP_RECENT_REQUESTS = 2;
+/** Share of the context window a request may fill before older messages are summarised */
+const CONTEXT_FILL_RATIO = 0.75;
+
+type Part = Exclude<ModelMessage['content'], string>[number];
+
+function isImagePart(part: Part): boolean {
+  return part.type === 'image' || (part.type === 'file' && part.mediaType.startsWith('image/'));
+}
+
+/** About four characters per token, which is close enough to decide when to compact */
+export function estimateTokens(messages: ModelMessage[]): number {
+  let chars = 0;
+  let images = 0;
+  for (const message of messages) {
+    if (typeof message.content === 'string') {
+      chars += message.content.length;
+      continue;
+    }
+    for (const part of message.content) {
+      if (isImagePart(part)) {
+        images += 1;
+      } else {
+        chars += JSON.stringify(part).length;
+      }
+    }
+  }
+  return Math.ceil(chars / 4) + images * IMAGE_TOKENS;
+}
+
+/** Conversation tokens a request may send, leaving room for the system prompt, tools and reply */
+export function getCompactionBudget(contextWindow: number, maxOutputTokens = 0): number {
+  return Math.floor(contextWindow * CONTEXT_FILL_RATIO) - maxOutputTokens;
+}
+
+export interface CompactionSplit {
+  /** Messages to replace with a summary */
+  older: ModelMessage[];
+  /** The last requests and their tool loops, kept as they are */
+  recent: ModelMessage[];
+}
+
+/**
+ * Split a conversation that no longer fits `budget` tokens before its last
+ * `KEEP_RECENT_REQUESTS` user messages, so no tool call is separated from its
+ * result. Null when it fits or there is nothing older to summarise.
+ */
+export function splitForCompaction(
+  messages: ModelMessage[],
+  budget: number
+): CompactionSplit | null {
+  if (estimateTokens(messages) <= budget) return null;
+
+  let kept = 0;
+  for (let index = messages.length - 1; index > 0; index--) {
+    if (messages[index].role !== 'user') continue;
+    kept += 1;
+    if (kept === KEEP_RECENT_REQUESTS) {
+      return { older: messages.slice(0, index), recent: messages.slice(index) };
+    }
+  }
+  // Fewer requests than we keep: summarise all but the last one
+  const lastUser = messages.map((message) => message.role).lastIndexOf('user');
+  return lastUser > 0
+    ? { older: messages.slice(0, lastUser), recent: messages.slice(lastUser) }
+    : null;
+}
+
+function clip(text: string): string {
+  return text.length > SUMMARY_PART_CHARS ? `${text.slice(0, SUMMARY_PART_CHARS)}…` : text;
+}
+
+function describePart(part: Part): string {
+  if (isImagePart(part)) return '[image]';
+  switch (part.type) {
+    case 'text':
+      return part.text;
+    case 'reasoning':
+      return '';
+    case 'tool-call':
+      return `[called ${part.toolName} ${clip(JSON.stringify(part.input) ?? '')}]`;
+    case 'tool-result': {
+      const { output } = part;
+      const value =
+        'value' in output && typeof output.value === 'string'
+          ? output.value
+          : JSON.stringify('value' in output ? output.value : output);
+      return `[${part.toolName} returned ${clip(value ?? '')}]`;
+    }
+    default:
+      return '';
+  }
+}
+
+/** Plain-text transcript of `messages` for the summary model, with tool traffic clipped */
+export function transcriptForSummary(messages: ModelMessage[]): string {
+  return messages
+    .map((message) => {
+      const text =
+        typeof message.content === 'string'
+          ? message.content
+          : (message.content as Part[]).map(describePart).filter(Boolean).join('\n');
+      return text ? `${message.role}: ${text}` : '';
+    })
+    .filter(Boolean)
+    .join('\n\n');
+}
+
+const SUMMARY_HEADING =
+  'Summary of the earlier conversation, which was compacted to fit the context window:';
+
+/** `recent`, with the summary of what came before prepended to its first request */
+export function applyCompactionSummary(summary: string, recent: ModelMessage[]): ModelMessage[] {
+  const [first, ...rest] = recent;
+  if (first?.role !== 'user') return recent;
+  const note = { type: 'text' as const, text: `${SUMMARY_HEADING}\n\n${summary}` };
+  const content =
+    typeof first.content === 'string'
+      ? [note, { type: 'text' as const, text: first.content }]
+      : [note, ...first.content];
+  return [{ ...first, content }, ...rest];
+}
@@ -7,16 +7,24 @@ import type {
   UserMessagePart,
   VisionSupport,
 } from '../types/aiChat';
+import { getBaseModelId } from './aiModels';
 
 export function toolResultToOutput(result: unknown) {
   if (typeof result === 'object' && result !== null && 'image_data_url' in result) {
-    const dataUrl = (result as { image_data_url: string }).image_data_url;
+    const { image_data_url: dataUrl, message } = result as {
+      image_data_url: string;
+      message?: unknown;
+    };
     const base64 = dataUrl.replace(/^data:image\/\w+;base64,/, '');
     return {
       type: 'content' as const,
       value: [
         { type: 'image-data' as const, data: base64, mediaType: 'image/png' as const },
-        { type: 'text' as const, text: 'Screenshot captured successfully.' },
+        {
+          type: 'text' as const,
+          // apply_edit results carry a screenshot of the preview after the edit
+          text: typeof message === 'string' ? message : 'Screenshot captured successfully.',
+        },
       ],
     };
   }
@@ -137,7 +145,7 @@ function userMessagePartsToModelContent(message: UserMessage, attachments: Attac
 }
 
 export function getVisionSupportForModelId(modelId: string): VisionSupport {
-  const normalized = modelId.toLowerCase();
+  const normalized = getBaseModelId(modelId);
 
   if (normalized.startsWith('claude')) {
     return 'yes';
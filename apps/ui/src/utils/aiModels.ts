@@ -1,4 +1,14 @@
-export type SupportedModelProvider = 'anthropic' | 'openai' | 'openai-compatible';
+import type { ReasoningEffort, ToolSupport } from '../types/aiChat';
+
+export type SupportedModelProvider =
+  | 'anthropic'
+  | 'bedrock'
+  | 'vertex'
+  | 'openai'
+  | 'azure-openai'
+  | 'gemini'
+  | 'openrouter'
+  | 'openai-compatible';
 
 export interface KnownModelDefinition {
   id: string;
@@ -8,10 +18,44 @@ export interface KnownModelDefinition {
 
 export const DEFAULT_MODEL_IDS: Record<SupportedModelProvider, string> = {
   anthropic: 'claude-sonnet-4-5',
+  bedrock: 'us.anthropic.claude-sonnet-4-5-20250929-v1:0',
+  vertex: 'claude-sonnet-4-5@20250929',
   openai: 'gpt-5.4',
+  'azure-openai': '',
+  gemini: 'gemini-2.5-pro',
+  openrouter: 'anthropic/claude-sonnet-4.5',
   'openai-compatible': 'gemma4:12b',
 };
 
+/**
+ * Small, fast models for background requests such as titling conversations
+ * and summarising older messages. Azure deployments and OpenAI-compatible
+ * servers have no known small model, so they use the conversation's own.
+ */
+export const SMALL_MODEL_IDS: Partial<Record<SupportedModelProvider, string>> = {
+  anthropic: 'claude-haiku-3-5',
+  bedrock: 'us.anthropic.claude-3-5-haiku-20241022-v1:0',
+  vertex: 'claude-3-5-haiku@20241022',
+  openai: 'gpt-4o-mini',
+  gemini: 'gemini-2.5-flash-lite',
+  openrouter: 'openai/gpt-4o-mini',
+};
+
+export function getSmallModelId(provider: SupportedModelProvider, modelId: string): string {
+  return SMALL_MODEL_IDS[provider] ?? modelId;
+}
+
+export const AI_PROVIDER_LABELS: Record<SupportedModelProvider, string> = {
+  anthropic: 'Anthropic',
+  bedrock: 'Amazon Bedrock',
+  vertex: 'Google Vertex AI',
+  openai: 'OpenAI',
+  'azure-openai': 'Azure OpenAI',
+  gemini: 'Google Gemini',
+  openrouter: 'OpenRouter',
+  'openai-compatible': 'OpenAI-compatible',
+};
+
 export const KNOWN_DISPLAY_NAMES: Record<string, string> = {
   'claude-sonnet-4-5': 'Claude Sonnet 4.5 (Latest)',
   'claude-opus-4': 'Claude Opus 4 (Latest)',
@@ -20,6 +64,12 @@ export const KNOWN_DISPLAY_NAMES: Record<string, string> = {
   'claude-opus-4-1-20250805': 'Claude Opus 4.1 (Aug 2025)',
   'claude-3-5-sonnet-20241022': 'Claude 3.5 Sonnet (Oct 2024)',
   'claude-3-5-haiku-20241022': 'Claude 3.5 Haiku (Oct 2024)',
+  'us.anthropic.claude-sonnet-4-5-20250929-v1:0': 'Claude Sonnet 4.5',
+  'us.anthropic.claude-opus-4-1-20250805-v1:0': 'Claude Opus 4.1',
+  'us.anthropic.claude-3-5-haiku-20241022-v1:0': 'Claude 3.5 Haiku',
+  'claude-sonnet-4-5@20250929': 'Claude Sonnet 4.5',
+  'claude-opus-4-1@20250805': 'Claude Opus 4.1',
+  'claude-3-5-haiku@20241022': 'Claude 3.5 Haiku',
   'gpt-5.4': 'GPT-5.4',
   'gpt-5': 'GPT-5',
   'gpt-4o': 'GPT-4o',
@@ -28,6 +78,9 @@ export const KNOWN_DISPLAY_NAMES: Record<string, string> = {
   'o1-mini': 'o1 Mini',
   'o3-mini': 'o3 Mini',
   'gpt-4-turbo': 'GPT-4 Turbo',
+  'gemini-2.5-pro': 'Gemini 2.5 Pro',
+  'gemini-2.5-flash': 'Gemini 2.5 Flash',
+  'gemini-2.5-flash-lite': 'Gemini 2.5 Flash-Lite',
 };
 
 export const DEFAULT_MODEL_CATALOG: KnownModelDefinition[] = [
@@ -46,6 +99,14 @@ export const DEFAULT_MODEL_CATALOG: KnownModelDefinition[] = [
     display_name: KNOWN_DISPLAY_NAMES['claude-haiku-3-5'],
     provider: 'anthropic',
   },
+  ...[
+    DEFAULT_MODEL_IDS.bedrock,
+    'us.anthropic.claude-opus-4-1-20250805-v1:0',
+    'us.anthropic.claude-3-5-haiku-20241022-v1:0',
+  ].map((id) => ({ id, display_name: KNOWN_DISPLAY_NAMES[id], provider: 'bedrock' as const })),
+  ...[DEFAULT_MODEL_IDS.vertex, 'claude-opus-4-1@20250805', 'claude-3-5-haiku@20241022'].map(
+    (id) => ({ id, display_name: KNOWN_DISPLAY_NAMES[id], provider: 'vertex' as const })
+  ),
   {
     id: DEFAULT_MODEL_IDS.openai,
     display_name: KNOWN_DISPLAY_NAMES[DEFAULT_MODEL_IDS.openai],
@@ -66,12 +127,27 @@ export const DEFAULT_MODEL_CATALOG: KnownModelDefinition[] = [
     display_name: KNOWN_DISPLAY_NAMES['gpt-4o'],
     provider: 'openai',
   },
+  {
+    id: DEFAULT_MODEL_IDS.gemini,
+    display_name: KNOWN_DISPLAY_NAMES[DEFAULT_MODEL_IDS.gemini],
+    provider: 'gemini',
+  },
+  {
+    id: 'gemini-2.5-flash',
+    display_name: KNOWN_DISPLAY_NAMES['gemini-2.5-flash'],
+    provider: 'gemini',
+  },
 ];
 
 const PROVIDER_ORDER: SupportedModelProvider[] = ['anthropic', 'openai'];
-const PROVIDER_ORDER_WITH_CUSTOM: SupportedModelProvider[] = [
+export const PROVIDER_ORDER_WITH_CUSTOM: SupportedModelProvider[] = [
   'anthropic',
+  'bedrock',
+  'vertex',
   'openai',
+  'azure-openai',
+  'gemini',
+  'openrouter',
   'openai-compatible',
 ];
 
@@ -87,6 +163,12 @@ export function getPreferredDefaultModel(providers: readonly string[]): string {
   if (normalizedProviders.includes('openai')) {
     return DEFAULT_MODEL_IDS.openai;
   }
+  if (providers.includes('gemini')) {
+    return DEFAULT_MODEL_IDS.gemini;
+  }
+  if (providers.includes('openrouter')) {
+    return DEFAULT_MODEL_IDS.openrouter;
+  }
   if (providers.includes('openai-compatible')) {
     return DEFAULT_MODEL_IDS['openai-compatible'];
   }
@@ -121,6 +203,8 @@ function getModelFreshnessRank(modelId: string): number {
   if (normalized.startsWith('claude-3-5-sonnet')) return 800;
   if (normalized.startsWith('claude-3-5-haiku')) return 790;
   if (normalized.startsWith('gpt-4')) return 700;
+  if (normalized.startsWith('gemini-2.5-pro')) return 690;
+  if (normalized.startsWith('gemini-2.5-flash')) return 680;
 
   return 0;
 }
@@ -151,3 +235,120 @@ export function compareModelsByFreshness(
 
   return a.display_name.localeCompare(b.display_name);
 }
+
+/** Most output tokens the agent asks for, even from models that allow more */
+export const MAX_AGENT_OUTPUT_TOKENS = 32000;
+
+/** More specific prefixes first, so `o1-mini` wins over `o1` */
+const OUTPUT_TOKEN_LIMITS: [prefix: string, limit: number][] = [
+  ['claude-opus-4', 32000],
+  ['claude-sonnet-4', 64000],
+  ['claude-haiku-4', 64000],
+  ['claude-3-7-sonnet', 64000],
+  ['claude-3-5-sonnet', 8192],
+  ['claude-3-5-haiku', 8192],
+  ['claude-haiku-3-5', 8192],
+  ['claude-3', 4096],
+  ['gpt-5', 128000],
+  ['gpt-4.1', 32768],
+  ['gpt-4o', 16384],
+  ['gpt-4-turbo', 4096],
+  ['o1-mini', 65536],
+  ['o1', 100000],
+  ['o3', 100000],
+  ['o4-mini', 100000],
+  ['gemini-2.5', 65536],
+  ['gemini-2.0', 8192],
+];
+
+/** Context window assumed for unknown models; local servers often run small ones */
+export const DEFAULT_CONTEXT_WINDOW = 32000;
+
+/** More specific prefixes first, as for output limits */
+const CONTEXT_WINDOW_LIMITS: [prefix: string, limit: number][] = [
+  ['claude', 200000],
+  ['gpt-5', 400000],
+  ['gpt-4.1', 1047576],
+  ['gpt-4o', 128000],
+  ['gpt-4-turbo', 128000],
+  ['o1-mini', 128000],
+  ['o1', 200000],
+  ['o3', 200000],
+  ['o4-mini', 200000],
+  ['gemini', 1048576],
+];
+
+/**
+ * The model name behind a provider-specific id: strips the Bedrock region and
+ * vendor prefixes (`us.anthropic.`), OpenRouter's vendor (`anthropic/`) and
+ * Bedrock/Vertex version suffixes (`-v1:0`, `@20250929`).
+ */
+export function getBaseModelId(modelId: string): string {
+  return modelId
+    .toLowerCase()
+    .replace(/^[^/]+\//, '')
+    .replace(/^(?:[a-z]+\.)?anthropic\./, '')
+    .replace(/-v\d+:\d+$/, '')
+    .replace(/@(\d{8})$/, '-$1');
+}
+
+/** Whether the model can call tools, which the agent needs to read and edit code */
+export function getToolSupportForModelId(modelId: string): ToolSupport {
+  const normalized = getBaseModelId(modelId);
+
+  if (
+    normalized.startsWith('o1-mini') ||
+    normalized.startsWith('o1-preview') ||
+    normalized.startsWith('gpt-3.5-turbo-instruct') ||
+    normalized.includes('embed') ||
+    normalized.includes('search') ||
+    normalized.includes('completion')
+  ) {
+    return 'no';
+  }
+
+  if (
+    normalized.startsWith('claude') ||
+    normalized.startsWith('gpt-4') ||
+    normalized.startsWith('gpt-5') ||
+    normalized.startsWith('gemini-2') ||
+    /^o\d/.test(normalized)
+  ) {
+    return 'yes';
+  }
+
+  return 'unknown';
+}
+
+/** The model's output token limit, or undefined for unknown models */
+export function getMaxOutputTokensForModelId(modelId: string): number | undefined {
+  const normalized = getBaseModelId(modelId);
+  return OUTPUT_TOKEN_LIMITS.find(([prefix]) => normalized.startsWith(prefix))?.[1];
+}
+
+/** Tokens the model reads per request, or `DEFAULT_CONTEXT_WINDOW` for unknown models */
+export function getContextWindowForModelId(modelId: string): number {
+  const normalized = getBaseModelId(modelId);
+  return (
+    CONTEXT_WINDOW_LIMITS.find(([prefix]) => normalized.startsWith(prefix))?.[1] ??
+    DEFAULT_CONTEXT_WINDOW
+  );
+}
+
+/** Whether the model accepts extended thinking (Claude) or a reasoning effort (OpenAI) */
+export function supportsReasoning(modelId: string): boolean {
+  const normalized = getBaseModelId(modelId);
+  return (
+    normalized.startsWith('claude-3-7') ||
+    /^claude-(?:opus|sonnet|haiku)-4/.test(normalized) ||
+    normalized.startsWith('gpt-5') ||
+    /^o\d/.test(normalized)
+  );
+}
+
+/** Extended thinking budget for each reasoning effort */
+export const THINKING_BUDGET_TOKENS: Record<Exclude<ReasoningEffort, 'off'>, number> = {
+  low: 2048,
+  medium: 8192,
+  high: 16384,
+};
@@ -0,0 +1,123 @@
+import type { ModelMessage, TextStreamPart, ToolSet } from 'ai';
+
+type StreamChunk = TextStreamPart<ToolSet>;
+
+export interface TranscriptToolCallPart {
+  type: 'tool-call';
+  toolCallId: string;
+  toolName: string;
+  input: unknown;
+}
+
+export interface TranscriptToolResultPart {
+  type: 'tool-result';
+  toolCallId: string;
+  toolName: string;
+  output:
+    | { type: 'text'; value: string }
+    | { type: 'json'; value: unknown }
+    | { type: 'error-text'; value: string };
+}
+
+/**
+ * Model-side record of a request, kept alongside the chat messages so an
+ * interrupted tool loop can be continued after a restart.
+ */
+export interface AgentTranscriptState {
+  /** Model messages up to the last finished step */
+  apiMessages: ModelMessage[];
+  /** Text the model streamed in the step in progress */
+  stepText: string;
+  pendingToolCalls: TranscriptToolCallPart[];
+  pendingToolResults: TranscriptToolResultPart[];
+}
+
+export interface TranscriptChunkResult {
+  state: AgentTranscriptState;
+  /** A tool call or step just completed, so the transcript should be saved */
+  shouldPersist: boolean;
+}
+
+export function createAgentTranscriptState(apiMessages: ModelMessage[]): AgentTranscriptState {
+  return { apiMessages, stepText: '', pendingToolCalls: [], pendingToolResults: [] };
+}
+
+function toToolOutput(output: unknown): TranscriptToolResultPart['output'] {
+  return typeof output === 'string'
+    ? { type: 'text', value: output }
+    : { type: 'json', value: output ?? null };
+}
+
+function errorText(error: unknown): string {
+  return error instanceof Error ? error.message : String(error);
+}
+
+function finishStep(state: AgentTranscriptState): AgentTranscriptState {
+  const content = [
+    ...(state.stepText ? [{ type: 'text' as const, text: state.stepText }] : []),
+    ...state.pendingToolCalls,
+  ];
+  if (content.length === 0) return state;
+
+  const stepMessages = [
+    { role: 'assistant', content },
+    ...(state.pendingToolResults.length > 0
+      ? [{ role: 'tool', content: state.pendingToolResults }]
+      : []),
+  ] as ModelMessage[];
+  return createAgentTranscriptState([...state.apiMessages, ...stepMessages]);
+}
+
+export function reduceTranscriptChunk(
+  state: AgentTranscriptState,
+  chunk: StreamChunk
+): TranscriptChunkResult {
+  switch (chunk.type) {
+    case 'text-delta':
+      return { state: { ...state, stepText: state.stepText + chunk.text }, shouldPersist: false };
+
+    case 'tool-call':
+      return {
+        state: {
+          ...state,
+          pendingToolCalls: [
+            ...state.pendingToolCalls,
+            {
+              type: 'tool-call',
+              toolCallId: chunk.toolCallId,
+              toolName: chunk.toolName,
+              input: chunk.input,
+            },
+          ],
+        },
+        shouldPersist: false,
+      };
+
+    case 'tool-result':
+    case 'tool-error':
+      return {
+        state: {
+          ...state,
+          pendingToolResults: [
+            ...state.pendingToolResults,
+            {
+              type: 'tool-result',
+              toolCallId: chunk.toolCallId,
+              toolName: chunk.toolName,
+              output:
+                chunk.type === 'tool-result'
+                  ? toToolOutput(chunk.output)
+                  : { type: 'error-text', value: errorText(chunk.error) },
+            },
+          ],
+        },
+        shouldPersist: true,
+      };
+
+    case 'finish-step':
+      return { state: finishStep(state), shouldPersist: true };
+
+    default:
+      return { state, shouldPersist: false };
+  }
+}
@@ -7,6 +7,7 @@ import type {
   ToolCall,
   ToolCallMessage,
 } from '../types/aiChat';
+import { parsePartialJsonObject } from './partialJson';
 import { createRandomId } from './randomId';
 
 type StreamChunk = TextStreamPart<ToolSet>;
@@ -33,6 +34,8 @@ export interface ActiveTurnState {
   completedToolCalls: ToolCallMessage[];
   persistedAssistantSegments: AssistantMessage[];
   persistedMessages: Array<AssistantMessage | ToolCallMessage>;
+  /** The latest reasoning block; shown while streaming, never persisted */
+  reasoningText: string;
   status: ActiveTurnStatus;
 }
 
@@ -238,6 +241,7 @@ export function createActiveTurnState(turnId: string, userMessageId: string): Ac
     completedToolCalls: [],
     persistedAssistantSegments: [],
     persistedMessages: [],
+    reasoningText: '',
     status: 'streaming',
   };
 }
@@ -369,6 +373,8 @@ export function reduceActiveTurnChunk(state: ActiveTurnState, chunk: StreamChunk
         };
       }
 
+      // Partial arguments let the UI show an edit while the model is still writing it
+      const inputText = existingToolCall.inputText + chunk.delta;
       return {
         state: {
           ...state,
@@ -376,7 +382,8 @@ export function reduceActiveTurnChunk(state: ActiveTurnState, chunk: StreamChunk
             ...state.pendingToolCallsById,
             [chunk.id]: {
               ...existingToolCall,
-              inputText: existingToolCall.inputText + chunk.delta,
+              args: parsePartialJsonObject(inputText) ?? existingToolCall.args,
+              inputText,
             },
           },
         },
@@ -541,7 +548,11 @@ export function reduceActiveTurnChunk(state: ActiveTurnState, chunk: StreamChunk
     }
 
     case 'reasoning-start':
+      return { state: { ...state, reasoningText: '' }, warnings: [] };
+
     case 'reasoning-delta':
+      return { state: { ...state, reasoningText: state.reasoningText + chunk.text }, warnings: [] };
+
     case 'reasoning-end':
     case 'source':
     case 'file':
@@ -612,6 +623,10 @@ export function deriveStreamingResponse(state: ActiveTurnState): string | null {
   return text.trim() ? text : null;
 }
 
+export function deriveStreamingReasoning(state: ActiveTurnState): string | null {
+  return state.reasoningText.trim() ? state.reasoningText : null;
+}
+
 export function deriveCurrentToolCalls(state: ActiveTurnState): ToolCall[] {
   return state.pendingToolCallOrder
     .map((toolCallId) => state.pendingToolCallsById[toolCallId])
@@ -127,6 +127,16 @@ export function notifySuccess(
   });
 }
 
+export function notifyWarning(
+  message: string,
+  options?: { toastId?: string; description?: string }
+) {
+  toast.warning(message, {
+    id: options?.toastId,
+    description: options?.description,
+  });
+}
+
 export async function notifyPromise<T>(
   promise: Promise<T>,
   options: NotifyOperationOptions<T>
@@ -0,0 +1,55 @@
+/**
+ * Best-effort parsing of a JSON object that is still being streamed, such as
+ * tool call input. Open strings and containers are closed, and a trailing
+ * partial literal or dangling key is dropped, so `{"new_string": "cube(1` reads
+ * as `{ new_string: 'cube(1' }`.
+ */
+export function parsePartialJsonObject(text: string): Record<string, unknown> | undefined {
+  const closers: string[] = [];
+  let inString = false;
+  let escaped = false;
+
+  for (const char of text) {
+    if (inString) {
+      if (escaped) {
+        escaped = false;
+      } else if (char === '\\') {
+        escaped = true;
+      } else if (char === '"') {
+        inString = false;
+      }
+    } else if (char === '"') {
+      inString = true;
+    } else if (char === '{') {
+      closers.push('}');
+    } else if (char === '[') {
+      closers.push(']');
+    } else if (char === '}' || char === ']') {
+      closers.pop();
+    }
+  }
+
+  let base = text;
+  if (inString) {
+    // An escape sequence cut off mid-way can't be closed, so drop it
+    base = escaped ? base.slice(0, -1) : base.replace(/\\u[0-9a-fA-F]{0,3}$/, '');
+    base += '"';
+  }
+
+  const withoutLiteral = base.replace(/[-+.\w]+$/, '').replace(/[\s,]+$/, '');
+  const withoutKey = withoutLiteral.replace(/,?\s*"(?:[^"\\]|\\.)*"\s*:?\s*$/, '');
+  const suffix = closers.reverse().join('');
+
+  for (const candidate of [base, withoutLiteral, withoutKey]) {
+    try {
+      const value: unknown = JSON.parse(candidate + suffix);
+      if (typeof value === 'object' && value !== null && !Array.isArray(value)) {
+        return value as Record<string, unknown>;
+      }
+      return undefined;
+    } catch {
+      // Try the next, shorter candidate
+    }
+  }
+  return undefined;
+}
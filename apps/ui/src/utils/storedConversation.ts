@@ -0,0 +1,141 @@
+/**
+ * Conversations as saves and exports store them (`conversation.rs`): each
+ * message has a role and typed content blocks, with tool results stored as
+ * blocks of their own. The chat keeps one message per prompt, reply or tool
+ * call; these convert between the two without losing tool activity, images
+ * or checkpoints.
+ */
+import type {
+  AssistantMessageState,
+  Conversation,
+  Message,
+  ToolCallState,
+  UserImagePart,
+  UserTextPart,
+} from '../types/aiChat';
+
+export interface StoredToolCallBlock {
+  type: 'tool-call';
+  toolCallId: string;
+  toolName: string;
+  args?: Record<string, unknown>;
+  state: ToolCallState;
+}
+
+export interface StoredToolResultBlock {
+  type: 'tool-result';
+  toolCallId: string;
+  result?: unknown;
+  errorText?: string;
+}
+
+export type StoredContentBlock =
+  | UserTextPart
+  | UserImagePart
+  | StoredToolCallBlock
+  | StoredToolResultBlock;
+
+export interface StoredMessage {
+  id: string;
+  role: 'user' | 'assistant';
+  timestamp: number;
+  content: StoredContentBlock[];
+  turnId?: string;
+  state?: AssistantMessageState;
+  checkpointId?: string;
+}
+
+export interface StoredConversation {
+  id: string;
+  title: string;
+  timestamp: number;
+  messages: StoredMessage[];
+}
+
+function toStoredMessage(message: Message): StoredMessage {
+  const { id, timestamp } = message;
+  switch (message.type) {
+    case 'user':
+      return {
+        id,
+        role: 'user',
+        timestamp,
+        content: message.parts,
+        ...(message.checkpointId ? { checkpointId: message.checkpointId } : {}),
+      };
+    case 'assistant':
+      return {
+        id,
+        role: 'assistant',
+        timestamp,
+        content: [{ type: 'text', text: message.content }],
+        turnId: message.turnId,
+        state: message.state,
+      };
+    case 'tool-call': {
+      const { toolCallId, toolName, args, state, result, errorText } = message;
+      const content: StoredContentBlock[] = [
+        { type: 'tool-call', toolCallId, toolName, args, state },
+      ];
+      if (result !== undefined || errorText !== undefined) {
+        content.push({ type: 'tool-result', toolCallId, result, errorText });
+      }
+      return { id, role: 'assistant', timestamp, content };
+    }
+  }
+}
+
+function fromStoredMessage(message: StoredMessage): Message {
+  const { id, timestamp, content } = message;
+  if (message.role === 'user') {
+    return {
+      id,
+      type: 'user',
+      timestamp,
+      parts: content.filter(
+        (block): block is UserTextPart | UserImagePart =>
+          block.type === 'text' || block.type === 'image'
+      ),
+      ...(message.checkpointId ? { checkpointId: message.checkpointId } : {}),
+    };
+  }
+
+  const call = content.find((block): block is StoredToolCallBlock => block.type === 'tool-call');
+  if (call) {
+    const outcome = content.find(
+      (block): block is StoredToolResultBlock =>
+        block.type === 'tool-result' && block.toolCallId === call.toolCallId
+    );
+    return {
+      id,
+      type: 'tool-call',
+      timestamp,
+      toolCallId: call.toolCallId,
+      toolName: call.toolName,
+      args: call.args,
+      state: call.state,
+      result: outcome?.result,
+      errorText: outcome?.errorText,
+    };
+  }
+
+  return {
+    id,
+    type: 'assistant',
+    timestamp,
+    turnId: message.turnId ?? id,
+    content: content
+      .filter((block): block is UserTextPart => block.type === 'text')
+      .map((block) => block.text)
+      .join('\n\n'),
+    state: message.state ?? 'complete',
+  };
+}
+
+export function toStoredConversation(conversation: Conversation): StoredConversation {
+  return { ...conversation, messages: conversation.messages.map(toStoredMessage) };
+}
+
+export function fromStoredConversation(conversation: StoredConversation): Conversation {
+  return { ...conversation, messages: conversation.messages.map(fromStoredMessage) };
+}
@@ -0,0 +1,77 @@
+/**
+ * Text edits for the AI edit tools, matching the backend's `pending_edits`
+ * helpers: exact-string replacements that must match exactly once, applied
+ * one after another as a single edit, and line-range replacements against a
+ * numbered listing of the file.
+ */
+
+export interface Replacement {
+  old_string: string;
+  new_string: string;
+}
+
+export type TextEditResult = { code: string } | { error: string };
+
+/** Replace the single occurrence of `oldString` in `code` */
+export function replaceUnique(code: string, oldString: string, newString: string): TextEditResult {
+  if (!oldString) return { error: 'old_string must not be empty' };
+  const occurrences = code.split(oldString).length - 1;
+  if (occurrences === 0) return { error: 'old_string not found in the file' };
+  if (occurrences > 1) {
+    return { error: `old_string found ${occurrences} times — it must be unique` };
+  }
+  const index = code.indexOf(oldString);
+  return { code: code.slice(0, index) + newString + code.slice(index + oldString.length) };
+}
+
+/**
+ * Apply `replacements` in order, each against the result of the previous
+ * one; fails without a partial result if any of them doesn't match once
+ */
+export function applyReplacements(code: string, replacements: Replacement[]): TextEditResult {
+  if (replacements.length === 0) return { error: 'No replacements given' };
+  let current = code;
+  for (const [index, replacement] of replacements.entries()) {
+    const result = replaceUnique(current, replacement.old_string, replacement.new_string);
+    if ('error' in result) return { error: `Replacement ${index + 1}: ${result.error}` };
+    current = result.code;
+  }
+  return { code: current };
+}
+
+/**
+ * Replace lines `start..=end` (1-based) with `text`. An `end` of `start - 1`
+ * inserts `text` before line `start` without removing any. `text` always
+ * ends up on lines of its own, and the file keeps its final newline, or lack
+ * of one.
+ */
+export function replaceLines(
+  code: string,
+  start: number,
+  end: number,
+  text: string
+): TextEditResult {
+  const lines = code.match(/[^\n]*\n|[^\n]+$/g) ?? [];
+  if (start < 1 || start > lines.length + 1 || end + 1 < start || end > lines.length) {
+    return { error: `Invalid line range ${start}-${end}: the file has ${lines.length} lines` };
+  }
+  const newline = code.includes('\r\n') ? '\r\n' : '\n';
+  let result = lines.slice(0, start - 1).join('');
+  if (text) {
+    // Appending after a last line that has no newline yet
+    if (result && !result.endsWith('\n')) result += newline;
+    result += text;
+    if (!text.endsWith('\n') && (end < lines.length || code.endsWith('\n'))) result += newline;
+  }
+  return { code: result + lines.slice(end).join('') };
+}
+
+/** `code` with a right-aligned line number before each line */
+export function numberedLines(code: string): string {
+  const lines = code.split('\n');
+  if (lines[lines.length - 1] === '') lines.pop();
+  const width = String(lines.length).length;
+  return lines
+    .map((line, index) => `${String(index + 1).padStart(width)} | ${line.replace(/\r$/, '')}\n`)
+    .join('');
+}